@@ -0,0 +1,8 @@
+export type CaptchaProvider = 'turnstile' | 'hcaptcha' | 'recaptcha';
+
+export interface CaptchaTemplate {
+    provider: CaptchaProvider;
+    site_key: string;
+    login: boolean;
+    registration: boolean;
+}
@@ -0,0 +1,5 @@
+export interface LoginHintTemplate {
+    email: string;
+    provider_id?: string;
+    auto_redirect: boolean;
+}
@@ -2,6 +2,10 @@ import type { CodeChallengeMethod } from '$api/types/authorize.ts';
 
 export type AuthProviderType = 'auto' | 'custom' | 'github' | 'google' | 'oidc';
 
+export type AuthProviderClaimPrecedence = 'upstream' | 'first_login' | 'local';
+
+export type AuthProviderUidNormalization = 'exact' | 'trim' | 'trim_lowercase';
+
 export interface ProviderRequest {
     /// Validation: PATTERN_CLIENT_NAME
     name: string;
@@ -22,6 +26,11 @@ export interface ProviderRequest {
     client_secret_post: boolean;
     auto_onboarding: boolean;
     auto_link: boolean;
+    auto_redirect?: boolean;
+    /// Validation: PATTERN_EMAIL_DOMAIN
+    email_domains?: string[];
+    claim_precedence?: AuthProviderClaimPrecedence;
+    uid_normalization?: AuthProviderUidNormalization;
 
     /// Validation: PATTERN_URI
     client_id: string;
@@ -54,6 +63,15 @@ export interface ProviderCallbackRequest {
     iss_atproto?: string;
 }
 
+export interface ProviderDiscoveryRequest {
+    /// Validation: `email`
+    email: string;
+}
+
+export interface ProviderDiscoveryResponse {
+    provider_id?: string;
+}
+
 export interface ProviderLoginRequest {
     // values for the downstream client
     /// Validation: `email`
@@ -112,6 +130,10 @@ export interface ProviderResponse {
     client_secret_post: boolean;
     auto_onboarding: boolean;
     auto_link: boolean;
+    auto_redirect: boolean;
+    email_domains: string[];
+    claim_precedence: AuthProviderClaimPrecedence;
+    uid_normalization: AuthProviderUidNormalization;
 }
 
 export interface ProviderLinkedUserResponse {
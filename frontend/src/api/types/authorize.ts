@@ -21,6 +21,8 @@ export interface LoginRequest {
     code_challenge?: string;
     /// Validation: `plain|S256`
     code_challenge_method?: CodeChallengeMethod;
+    /// Validation: max 4096
+    captcha?: string;
 }
 
 export interface LoginRefreshRequest {
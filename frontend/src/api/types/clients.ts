@@ -8,6 +8,8 @@ export type AuthFlow =
     | 'refresh_token'
     | 'urn:ietf:params:oauth:grant-type:device_code';
 export type CodeChallengeMethod = 'plain' | 'S256';
+export type SubjectType = 'public' | 'pairwise';
+export type UriMatchMode = 'prefix' | 'strict';
 
 export interface NewClientRequest {
     /// Validation: PATTERN_CLIENT_ID
@@ -19,6 +21,7 @@ export interface NewClientRequest {
     redirect_uris: string[];
     /// Validation: PATTERN_URI
     post_logout_redirect_uris?: string[];
+    uri_match_mode?: UriMatchMode;
 }
 
 export interface ScimClientRequestResponse {
@@ -66,6 +69,19 @@ export interface UpdateClientRequest {
     backchannel_logout_uri?: string;
     /// Validation: PATTERN_GROUP
     restrict_group_prefix?: string;
+    introspect: boolean;
+    /// Validation: `{ [PATTERN_CLAIM_NAME]: PATTERN_CLAIM_NAME }`
+    claim_mapping?: { [key: string]: string };
+    /// Validation: PATTERN_CLAIM_NAME
+    claim_namespace?: string;
+    /// Validation: `10 <= refresh_token_max_lifetime <= 31536000`
+    refresh_token_max_lifetime?: number;
+    require_nonce: boolean;
+    first_party: boolean;
+    subject_type: SubjectType;
+    uri_match_mode?: UriMatchMode;
+    /// Validation: PATTERN_URI
+    sector_identifier_uri?: string;
     scim?: ScimClientRequestResponse;
 }
 
@@ -74,6 +90,13 @@ export interface ClientSecretRequest {
     cache_current_hours?: number;
 }
 
+export interface ClientSecretSetRequest {
+    /// Validation: Value between 1 and 24
+    cache_current_hours?: number;
+    /// Validation: max length 256
+    secret: string;
+}
+
 export interface ClientResponse {
     id: string;
     name?: string;
@@ -95,6 +118,15 @@ export interface ClientResponse {
     contacts?: string[];
     backchannel_logout_uri?: string;
     restrict_group_prefix?: string;
+    introspect: boolean;
+    claim_mapping?: { [key: string]: string };
+    claim_namespace?: string;
+    refresh_token_max_lifetime?: number;
+    require_nonce: boolean;
+    first_party: boolean;
+    subject_type: SubjectType;
+    uri_match_mode: UriMatchMode;
+    sector_identifier_uri?: string;
     scim?: ScimClientRequestResponse;
 }
 
@@ -21,7 +21,23 @@ export type EventType =
     | 'UserLoginRevoke'
     | 'LoginNewLocation'
     | 'SuspiciousApiScan'
-    | 'TokenIssued';
+    | 'TokenIssued'
+    | 'UserEmailChangeRequest'
+    | 'IntrospectionDenied'
+    | 'RegistrationRateLimit'
+    | 'TokensRevoked'
+    | 'TokensInvalidated'
+    | 'UserDataExported'
+    | 'UserFederationRemapped'
+    | 'UserOnboarded'
+    | 'CacheFlushed'
+    | 'RegistrationPending'
+    | 'RegistrationApproved'
+    | 'RegistrationRejected'
+    | 'AccountRecovered'
+    | 'RecordsSwept'
+    | 'UserDisabled'
+    | 'UserEnabled';
 
 export interface EventsRequest {
     /// Unix timestamp in seconds
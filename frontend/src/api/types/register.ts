@@ -14,4 +14,6 @@ export interface NewUserRegistrationRequest {
     pow: string;
     /// Validation: PATTERN_URI
     redirect_uri?: string;
+    /// Validation: max 4096
+    captcha?: string;
 }
@@ -5,6 +5,8 @@ export interface ScopeRequest {
     attr_include_access?: string[];
     /// Validation: PATTERN_ATTR
     attr_include_id?: string[];
+    /// Validation: PATTERN_ROLE_SCOPE
+    required_roles?: string[];
 }
 
 export interface ScopeResponse {
@@ -12,4 +14,5 @@ export interface ScopeResponse {
     name: string;
     attr_include_access?: string[];
     attr_include_id?: string[];
+    required_roles?: string[];
 }
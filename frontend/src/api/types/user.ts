@@ -101,6 +101,17 @@ export type UserAccountTypeResponse =
     | 'federated_passkey'
     | 'federated_password';
 
+export interface PendingRegistrationResponse {
+    id: string;
+    email: string;
+    given_name?: string;
+    family_name?: string;
+    language: string;
+    federated: boolean;
+    // Unix timestamp in seconds
+    pending_since: number;
+}
+
 export interface UserResponseSimple {
     id: string;
     email: string;
@@ -134,6 +145,8 @@ export interface UserResponse {
     auth_provider_id?: string;
     federation_uid?: string;
     picture_id?: string;
+    disabled_at?: number;
+    pending_since?: number;
 }
 
 export interface UserValuesResponse {
@@ -14,6 +14,7 @@ export interface UserAttrConfigRequest {
     /// Currently ignored - will be implemented in a future version
     typ?: UserAttrConfigTyp;
     user_editable?: boolean;
+    internal?: boolean;
 }
 
 export interface UserAttrConfigValueResponse {
@@ -23,6 +24,7 @@ export interface UserAttrConfigValueResponse {
     /// Currently ignored - will be implemented in a future version
     typ?: UserAttrConfigTyp;
     user_editable?: boolean;
+    internal?: boolean;
 }
 
 export interface UserAttrConfigResponse {
@@ -20,6 +20,9 @@ export let I18nAdminDe: I18nAdmin = {
         delete1: 'Soll dieses Attribut wirklich gelöscht werden?',
         defaultValue: 'Standard Wert',
         desc: 'Beschreibung',
+        internal: 'Intern',
+        internalDesc:
+            'Interne Attribute werden niemals in Tokens, Userinfo oder SCIM Syncs ausgegeben, selbst wenn sie einem Scope zugeordnet sind.',
         makeEditable: 'Editierbar machen',
         makeEditableP1: 'Dieses Attribut kann durch Benutzer editierbar gemacht werden.',
         makeEditableP2: `<b>ACHTUNG:</b> Diese Änderung kann niemals rückgängig gemacht werden! Jegliche Angaben durch
@@ -52,6 +55,8 @@ export let I18nAdminDe: I18nAdmin = {
                 dass z.B. die freien Eingaben wiederum die Variablen referenzieren können, z.B. mit
                 <code>hsla(var(--action) / .7)</code>.`,
         },
+        claimMapping: 'Claim Mapping',
+        claimNamespace: 'Claim Namespace',
         confidential: 'Vertraulich',
         confidentialNoSecret: 'Dies is kein vertraulicher Client und hat somit kein Secret.',
         config: 'Client Konfiguration',
@@ -59,6 +64,9 @@ export let I18nAdminDe: I18nAdmin = {
         descAuthCode: `Die Gültigkeit der Auth Codes kann angepasst werden um zusätzliche Sicherheit
             zu gewinnen. Auth Codes können nur einmalig verwendet werden und sind normalerweise für 60
             Sekunden gültig. Je kürzer, desto besser, so lange der Client den Code schnell genug nutzen kann.`,
+        descClaimMapping: `Ältere Anwendungen erwarten Claims unter Umständen mit anderen Namen. Jedes Mapping hat
+            das Format <code>intern=ausgabe</code>, z.B. <code>roles=https://example.com/roles</code>. Wenn ein
+            Namespace gesetzt ist, werden alle gemappten Claims in einem Objekt mit diesem Namen verschachtelt.`,
         descClientUri: `Informationen über URI und Kontakte dieses Clients zur Anzeige
             auf der Login Seite.`,
         descName: `Der Client Name kann geändert werden ohne Einfluss auf die Konfiguration.
@@ -78,6 +86,13 @@ export let I18nAdminDe: I18nAdmin = {
             Challenge aktiviert haben.`,
         forceMfa: 'MFA Erzwingen',
         groupLoginPrefix: 'Login Gruppen Prefix',
+        introspect: 'Token Introspection erlauben',
+        requireNonce: 'Nonce erzwingen',
+        firstParty: 'First-Party Client (vertrauenswürdig)',
+        descSectorIdentifier:
+            'Clients mit demselben Host in ihrer Sector Identifier URI erhalten dieselbe `sub`. Ohne ist jeder Client ein eigener Sektor.',
+        pairwiseSub: 'Pairwise Subject (`sub` pro Sektor)',
+        strictUriMatch: 'Strikter URI Abgleich (`*` nur für Pfade oder eine Subdomain)',
         name: 'Client Name',
         scim: {
             baseUri: `Die SCIM base URI muss jene sein, von der Sub-Routen wie 
@@ -114,6 +129,11 @@ export let I18nAdminDe: I18nAdmin = {
                 angegeben werden.`,
             rotateDesc2:
                 'Achtung: Das derzeitige Secret sollte nicht im Cache behalten werden, wenn es ein Leak gab!',
+            custom: 'Eigenes Secret Setzen',
+            customDesc: `Anstatt ein neues Secret zu generieren, kann auch ein eigenes gesetzt werden. Es muss die
+                konfigurierte Mindestlänge und Entropie erfüllen und wird danach nie wieder angezeigt.`,
+            hashed:
+                'Das Secret wird gehasht gespeichert und kann nur direkt nach dem Generieren einmalig angezeigt werden.',
         },
         tokenLifetime: {
             p1: `Die Token Lifetime wird auf Access und ID Tokens angewandt und wird in Sekunden angegeben.`,
@@ -121,6 +141,7 @@ export let I18nAdminDe: I18nAdmin = {
                 sein. RSA Algorithmen existieren lediglich aus Kompatibilitätsgründen.`,
             p3: `Der Algorithmus für Refresh Tokens kann nicht geändert werden, da diese nur von Rauthy
                 genutzt werden sollten.`,
+            p4: `Refresh Tokens werden mit jedem Refresh verlängert. Die optionale maximale Lifetime in Sekunden ist eine absolute Grenze ab dem ursprünglichen Login, nach der sich der User erneut anmelden muss.`,
         },
     },
     common: {
@@ -364,6 +385,14 @@ export let I18nAdminDe: I18nAdmin = {
             autoLinkDesc2: `ACHTUNG: Diese Option kann sehr gefährlich sein und zur Account-Übernahme führen, wenn der
                 Provider keine vollständige E-Mail Überprüfung durchführt und es möglich macht eine fremde Adresse
                 für einen Benutzer einzutragen! Darf in einem solchen Fall NIEMALS verwendet werden!`,
+            autoRedirect: 'Auto-Redirect',
+            autoRedirectDesc: `Wenn ein login_hint oder die eingegebene E-Mail zu einer der E-Mail Domains passt,
+                leitet die Login-Seite direkt zu diesem Provider weiter. Bestehende Benutzer dieser Domains werden
+                beim ersten Login automatisch verknüpft.`,
+            claimPrecedence: 'Claim Vorrang',
+            claimPrecedenceDesc: `Legt fest, ob E-Mail, Namen und Benutzerwerte des Providers die lokalen
+                Werte bei jedem Login (upstream), nur beim ersten Login über diesen Provider (first_login)
+                oder für bestehende Benutzer nie (local) überschreiben.`,
             clientName: 'Client Name',
             custRootCa: 'Eigenes Root CA PEM',
             descAuthMethod: `Die Authentication Method, welche für den <code>/token</code> Endpunkt genutzt werden soll.
@@ -376,6 +405,9 @@ export let I18nAdminDe: I18nAdmin = {
                 Es muss mindestens ein Secret gegeben, oder PKCE aktiviert sein.`,
             descScope: `Der scope der beim Redirect zum Login genutzt werden soll. Werte müssen durch Leerzeichen
                 getrennt angegeben werden`,
+            emailDomains: 'E-Mail Domains',
+            emailDomainsDesc: `E-Mail Domains, die zu diesem Provider gehören. Ein führendes *. trifft auf
+                alle Subdomains zu. Passt ein login_hint, wird dieser Provider vorausgewählt.`,
             errNoAuthMethod: 'Ein client secret existiert, jedoch ist keine auth Methode aktiv',
             errConfidential:
                 'Es muss mindestens entweder ein client secret existieren oder PKCE aktiv sein.',
@@ -397,6 +429,10 @@ export let I18nAdminDe: I18nAdmin = {
                 beim Login verwandt wurde, so kann Rauthy diesen Werten extrahieren und entsprechend weitergeben.`,
             mapUser: `Es kann beim Login automatisch ein Nutzer mit der Rauthy Admin Rolle verlinkt werden, in
                 Abhängigkeit von einem existierenden Upstream ID Claim.`,
+            uidNormalization: 'User ID Normalisierung',
+            uidNormalizationDesc: `Wird auf die User ID des Providers angewendet, bevor sie verknüpft oder gesucht wird.
+                trim_lowercase nur nutzen, wenn der Provider seine User IDs nicht nach Groß- und
+                Kleinschreibung unterscheidet, da sonst verschiedene Benutzer dasselbe Konto erhalten könnten.`,
             valueAdminClaim: 'Admin Claim Wert',
             valueMfaClaim: 'MFA Claim Wert',
         },
@@ -422,6 +458,8 @@ export let I18nAdminDe: I18nAdmin = {
         mapping2: `Jedes existierende Attribut hat einen eigenständigen Wert pro User. Diese Attribute können
             auf einen Scope gemapped werden und werden in diesem Fall im Access bzw. ID Token enthalten sein.`,
         name: 'Scope Name',
+        requiredRoles: 'Sind Rollen ausgewählt, können nur Benutzer mit mindestens einer davon diesen Scope erhalten.',
+        requiredRolesLabel: 'Benötigte Rollen',
     },
     sessions: {
         invalidateAll: 'Alle Sessions Invalidieren',
@@ -460,6 +498,10 @@ export let I18nAdminDe: I18nAdmin = {
             rauthyAdmin: 'rauthy_admin Rolle kann nicht entfernt werden',
         },
         attributes: 'Attribute',
+        deactivate: 'Deaktivieren',
+        deactivateUser: `Den Benutzer stattdessen deaktivieren. Alle Sessions und Tokens werden sofort widerrufen
+            und der Benutzer wird nach der konfigurierten Aufbewahrungsfrist endgültig gelöscht.`,
+        deactivated: 'Deaktiviert und zur Löschung vorgemerkt seit',
         deleteUser: 'Soll dieser Benutzer wirklich gelöscht werden?',
         descAttr: `Setze individuelle Benutzer Attribute. Alle Key / Value Paare 
             werden als String / JSON Wert gehandhabt.`,
@@ -483,6 +525,7 @@ export let I18nAdminDe: I18nAdmin = {
         pwdSendEmailBtn: 'Reset E-Mail Senden',
         pwdSendEmailDesc:
             'Es kann eine neue Reset E-Mail gesendet werden, sollte der Benutzer keine erhalten haben.',
+        reactivate: 'Reaktivieren',
         savePassword: 'Passwort Speichern',
         selfServiceDesc:
             'Es kann entweder ein neues Passwort gesetzt, oder eine Reset E-Mail versendet werden.',
@@ -19,6 +19,9 @@ export let I18nAdminEn: I18nAdmin = {
         delete1: 'Are you sure you want to delete this attribute?',
         defaultValue: 'Default Value',
         desc: 'Description',
+        internal: 'Internal',
+        internalDesc:
+            'Internal attributes will never be included in any tokens, userinfo or SCIM syncs, even if they are mapped to a scope.',
         makeEditable: 'Make Editable',
         makeEditableP1: 'You can convert this attribute and make it editable by users themselves.',
         makeEditableP2: `<b>CAUTION:</b> This can never be changed back! All inputs from a user directly are always
@@ -46,6 +49,8 @@ export let I18nAdminEn: I18nAdmin = {
             descVariables: `Each following label is at the same time the name of the CSS variable. This means,
                 that you can reference these in the free inputs, e.g. with <code>hsla(var(--action) / .7)</code>.`,
         },
+        claimMapping: 'Claim Mapping',
+        claimNamespace: 'Claim Namespace',
         confidential: 'Confidential',
         confidentialNoSecret: 'This is a non-confidential client and therefore has not secret.',
         config: 'Client Configuration',
@@ -53,6 +58,9 @@ export let I18nAdminEn: I18nAdmin = {
         descAuthCode: `The validity for auth codes can be adjusted for increased security. Auth codes
             can be used only once and are valid for 60 seconds by default. The shorter the validity, the
             better, as long as the client can perform the login procedure fast enough.`,
+        descClaimMapping: `Legacy applications may expect claims with different names. Each mapping has the format
+            <code>internal=output</code>, e.g. <code>roles=https://example.com/roles</code>. If a namespace is
+            set, all mapped claims will be nested inside an object with this name.`,
         descClientUri: `Information about this clients URI and contacts to be shown on
             the login page.`,
         descName: `The client name can be changed without any impact on the client configuration.
@@ -72,6 +80,13 @@ export let I18nAdminEn: I18nAdmin = {
             challenge activated.`,
         forceMfa: 'Force MFA',
         groupLoginPrefix: 'Login Group Prefix',
+        introspect: 'Allow Token Introspection',
+        requireNonce: 'Require Nonce',
+        firstParty: 'First-Party Client (trusted)',
+        descSectorIdentifier:
+            'Clients with the same host in their Sector Identifier URI get the same `sub`. Without one, each client is its own sector.',
+        pairwiseSub: 'Pairwise Subject (`sub` per sector)',
+        strictUriMatch: 'Strict URI Matching (`*` only for paths or one subdomain)',
         name: 'Client Name',
         scim: {
             baseUri: `The SCIM base URI is the one from which the sub routes like 
@@ -106,12 +121,18 @@ export let I18nAdminEn: I18nAdmin = {
             rotateDesc1: `To make graceful updates and secret rotations possible, you have the ability to keep the
                 current secret in an in-memory cache for some time. You can enter a value between 1 and 24 hours.`,
             rotateDesc2: 'Caution: You should not cache the current secret if you had a leak!',
+            custom: 'Set Custom Secret',
+            customDesc: `Instead of generating a new secret, you can set your own one. It must match the
+                configured minimum length and entropy and will never be shown again.`,
+            hashed:
+                'The secret is stored hashed and can only be shown once right after it has been generated.',
         },
         tokenLifetime: {
             p1: `The token lifetime applies to Access and ID tokens and is given in seconds.`,
             p2: `If the client supports EdDSA / ed25519 algorithms, it should always be the preferred
                 choice. RSA algorithms exist for compatibility only.`,
             p3: `The algorithm for refresh tokens cannot be changed, since these are used by Rauthy only.`,
+            p4: `Refresh tokens are extended with each refresh. The optional max lifetime in seconds is an absolute cap, counted from the original login, after which the user must authenticate again.`,
         },
     },
     common: {
@@ -348,6 +369,14 @@ export let I18nAdminEn: I18nAdmin = {
             autoLinkDesc2: `CAUTION: This option can be very dangerous and lead to account takeover if the provider
                 does not fully validate E-Mail addresses for users and therefore makes it possible to add a foreign
                 address for a user! MUST NEVER be used in such a case!`,
+            autoRedirect: 'Auto-Redirect',
+            autoRedirectDesc: `If a login_hint or the entered E-Mail matches one of the E-Mail domains, the
+                login page will redirect to this provider immediately. Existing users of these domains will be
+                linked automatically with their first login.`,
+            claimPrecedence: 'Claim Precedence',
+            claimPrecedenceDesc: `Controls if the E-Mail, names and user values from the provider overwrite
+                the local ones with each login (upstream), only with the first login via this provider
+                (first_login), or never for existing users (local).`,
             clientName: 'Client Name',
             custRootCa: 'Custom Root CA PEM',
             descAuthMethod: `The authentication method to use on the <code>/token</code> endpoint.<br>
@@ -359,6 +388,9 @@ export let I18nAdminEn: I18nAdmin = {
                 At least a client secret or PKCE is required.`,
             descScope: `The scope the client should use when redirecting to the login.
                 Provide the values separated by space.`,
+            emailDomains: 'E-Mail Domains',
+            emailDomainsDesc: `E-Mail domains that belong to this provider. A leading *. matches all
+                subdomains. If a login_hint matches, this provider will be pre-selected.`,
             errNoAuthMethod: 'You have given a client secret, but no client auth method is active',
             errConfidential: 'Must at least be a confidential client or use PKCE',
             jsonPath: {
@@ -378,6 +410,10 @@ export let I18nAdminEn: I18nAdmin = {
             mapMfa: `If your provider issues a claim indicating that the user has used at least 2FA during
                 login, you can specify the mfa claim path.`,
             mapUser: `You can map a user to be a Rauthy admin depending on an upstream ID claim.`,
+            uidNormalization: 'User ID Normalization',
+            uidNormalizationDesc: `Applied to the upstream user ID before it is linked or looked up. Only use
+                trim_lowercase, if the provider treats its user IDs case-insensitive, otherwise different
+                upstream users could end up with the same account.`,
             valueAdminClaim: 'Admin Claim Value',
             valueMfaClaim: 'MFA Claim Value',
         },
@@ -403,6 +439,8 @@ export let I18nAdminEn: I18nAdmin = {
         mapping2: `All additional attributes, that were configured, can have a custom value for each user.
             When they are mapped to a scope, they can be included in the Access and / or ID Tokens.`,
         name: 'Scope Name',
+        requiredRoles: 'If any roles are selected, only users with at least one of them can obtain this scope.',
+        requiredRolesLabel: 'Required Roles',
     },
     search: {
         orderBy: 'Order by ...',
@@ -441,6 +479,10 @@ export let I18nAdminEn: I18nAdmin = {
             rauthyAdmin: 'rauthy_admin rule cannot be removed',
         },
         attributes: 'Attributes',
+        deactivate: 'Deactivate',
+        deactivateUser: `Deactivate this user instead. All sessions and tokens will be revoked immediately and
+            the user will be deleted permanently after the configured retention period.`,
+        deactivated: 'Deactivated and scheduled for deletion since',
         deleteUser: 'Are you sure you want to delete this user?',
         descAttr: `Set custom user attributes. All key / value pairs will be handles as String / JSON Value.`,
         forceLogout: `Are you sure you want to invalidate all existing sessions and delete all refresh tokens
@@ -461,6 +503,7 @@ export let I18nAdminEn: I18nAdmin = {
         pwdNoInit: 'The user has not performed the initial password reset yet.',
         pwdSendEmailBtn: 'Send Reset E-Mail',
         pwdSendEmailDesc: 'You may send out a new reset E-Mail, if the user has not received one.',
+        reactivate: 'Reactivate',
         savePassword: 'Save Password',
         selfServiceDesc: 'You can either set a new password, or send out a reset E-Mail.',
         sendResetEmail: 'Send Reset E-Mail',
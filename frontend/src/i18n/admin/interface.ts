@@ -22,6 +22,8 @@ export interface I18nAdmin {
         delete1: string;
         defaultValue: string;
         desc: string;
+        internal: string;
+        internalDesc: string;
         makeEditable: string;
         makeEditableP1: string;
         // inserted as html
@@ -46,11 +48,14 @@ export interface I18nAdmin {
             descFullCss: string;
             descVariables: string;
         };
+        claimMapping: string;
+        claimNamespace: string;
         confidential: string;
         confidentialNoSecret: string;
         config: string;
         delete1: string;
         descAuthCode: string;
+        descClaimMapping: string;
         descClientUri: string;
         descGroupPrefix: string;
         descName: string;
@@ -62,6 +67,12 @@ export interface I18nAdmin {
         errConfidentialPKCE: string;
         forceMfa: string;
         groupLoginPrefix: string;
+        introspect: string;
+        requireNonce: string;
+        firstParty: string;
+        descSectorIdentifier: string;
+        pairwiseSub: string;
+        strictUriMatch: string;
         name: string;
         scim: {
             // inserted as html
@@ -92,11 +103,15 @@ export interface I18nAdmin {
             generate: string;
             rotateDesc1: string;
             rotateDesc2: string;
+            custom: string;
+            customDesc: string;
+            hashed: string;
         };
         tokenLifetime: {
             p1: string;
             p2: string;
             p3: string;
+            p4: string;
         };
     };
     common: {
@@ -293,6 +308,10 @@ export interface I18nAdmin {
             autoLink: string;
             autoLinkDesc1: string;
             autoLinkDesc2: string;
+            autoRedirect: string;
+            autoRedirectDesc: string;
+            claimPrecedence: string;
+            claimPrecedenceDesc: string;
             clientName: string;
             custRootCa: string;
             // inserted as html
@@ -301,6 +320,8 @@ export interface I18nAdmin {
             descClientName: string;
             descClientSecret: string;
             descScope: string;
+            emailDomains: string;
+            emailDomainsDesc: string;
             errNoAuthMethod: string;
             errConfidential: string;
             jsonPath: {
@@ -322,6 +343,8 @@ export interface I18nAdmin {
             rootPemCert: string;
             mapMfa: string;
             mapUser: string;
+            uidNormalization: string;
+            uidNormalizationDesc: string;
             valueAdminClaim: string;
             valueMfaClaim: string;
         };
@@ -346,6 +369,8 @@ export interface I18nAdmin {
         mapping1: string;
         mapping2: string;
         name: string;
+        requiredRoles: string;
+        requiredRolesLabel: string;
     };
     search: {
         orderBy: string;
@@ -382,6 +407,9 @@ export interface I18nAdmin {
             rauthyAdmin: string;
         };
         attributes: string;
+        deactivate: string;
+        deactivateUser: string;
+        deactivated: string;
         deleteUser: string;
         descAttr: string;
         forceLogout: string;
@@ -398,6 +426,7 @@ export interface I18nAdmin {
         pwdNoInit: string;
         pwdSendEmailBtn: string;
         pwdSendEmailDesc: string;
+        reactivate: string;
         savePassword: string;
         selfServiceDesc: string;
         sendResetEmail: string;
@@ -18,6 +18,8 @@ export let I18nAdminKo: I18nAdmin = {
         delete1: '이 속성을 삭제하시겠습니까?',
         defaultValue: 'Default Value',
         desc: '설명',
+        internal: '내부',
+        internalDesc: '내부 속성은 스코프에 매핑되어 있더라도 토큰, userinfo 또는 SCIM 동기화에 절대 포함되지 않습니다.',
         makeEditable: 'Make Editable',
         makeEditableP1: 'You can convert this attribute and make it editable by users themselves.',
         makeEditableP2: `<b>CAUTION:</b> This can never be changed back! All inputs from a user directly are always
@@ -45,6 +47,8 @@ export let I18nAdminKo: I18nAdmin = {
             descVariables: `색상의 각 레이블들은 동시에 CSS 변수의 이름입니다. 즉, 입력 칸에서 이를 참조할 수
                 있습니다. (예: <code>hsla(var(--action) / .7)</code>)`,
         },
+        claimMapping: '클레임 매핑',
+        claimNamespace: '클레임 네임스페이스',
         confidential: '기밀',
         confidentialNoSecret: '이 클라이언트는 기밀이 아닌 클라이언트이므로 Secret이 없습니다.',
         config: '클라이언트 설정',
@@ -52,6 +56,9 @@ export let I18nAdminKo: I18nAdmin = {
         descAuthCode: `보안을 강화하기 위해 인증 코드의 유효 기간을 조정할 수 있습니다. 인증 코드는
             한 번만 사용할 수 있으며 기본적으로 60초 동안 유효합니다. 클라이언트가 로그인 절차를
             충분히 빠르게 수행할 수 있다면 유효 기간이 짧을수록 좋습니다.`,
+        descClaimMapping: `레거시 애플리케이션은 다른 이름의 클레임을 기대할 수 있습니다. 각 매핑은
+            <code>internal=output</code> 형식입니다, 예: <code>roles=https://example.com/roles</code>. 네임스페이스가
+            설정되면 매핑된 모든 클레임이 이 이름의 객체 안에 중첩됩니다.`,
         descClientUri: `로그인 페이지에 표시할 클라이언트 URI 및 연락처에 대한 정보입니다.`,
         descName: `클라이언트 이름은 클라이언트 구성에 영향을 주지 않고 변경할 수 있으며,
             로그인 페이지에서만 표시됩니다.`,
@@ -69,6 +76,13 @@ export let I18nAdminKo: I18nAdmin = {
         errConfidentialPKCE: `클라이언트는 기밀 또는 PKCE 챌린지 중 하나 이상 활성화되어야 합니다.`,
         forceMfa: '강제 MFA',
         groupLoginPrefix: 'Login Group Prefix',
+        introspect: '토큰 인트로스펙션 허용',
+        requireNonce: 'Nonce 필수',
+        firstParty: '퍼스트 파티 클라이언트 (신뢰됨)',
+        descSectorIdentifier:
+            'Sector Identifier URI의 호스트가 같은 클라이언트는 동일한 `sub`를 받습니다. 없으면 각 클라이언트가 자체 섹터가 됩니다.',
+        pairwiseSub: 'Pairwise Subject (섹터별 `sub`)',
+        strictUriMatch: '엄격한 URI 매칭 (`*`는 경로 또는 하나의 서브도메인에만)',
         name: '클라이언트 이름',
         scim: {
             baseUri: `The SCIM base URI is the one from which the sub routes like 
@@ -102,12 +116,17 @@ export let I18nAdminKo: I18nAdmin = {
             rotateDesc1: `To make graceful updates and secret rotations possible, you have the ability to keep the
                 current secret in an in-memory cache for some time. You can enter a value between 1 and 24 hours.`,
             rotateDesc2: 'Caution: You should not cache the current secret if you had a leak!',
+            custom: '사용자 지정 시크릿 설정',
+            customDesc: `새 시크릿을 생성하는 대신 직접 시크릿을 설정할 수 있습니다. 설정된 최소 길이와 엔트로피를
+                충족해야 하며, 이후 다시 표시되지 않습니다.`,
+            hashed: '시크릿은 해시되어 저장되며 생성 직후에 한 번만 표시됩니다.',
         },
         tokenLifetime: {
             p1: `토큰 수명은 액세스 토큰과 ID 토큰에 적용되며, 초 단위입니다.`,
             p2: `클라이언트가 EdDSA 또는 ed25519 알고리즘을 지원하면, 항상 선호되는 옵션이어야 합니다.
                 RSA 알고리즘은 호환성을 위해서만 존재합니다.`,
             p3: `Refresh 토큰의 알고리즘은 Rauthy에서만 사용되므로, 변경할 수 없습니다.`,
+            p4: `Refresh 토큰은 갱신할 때마다 연장됩니다. 선택적 최대 수명(초)은 최초 로그인부터 계산되는 절대 상한이며, 이후에는 다시 인증해야 합니다.`,
         },
     },
     common: {
@@ -336,6 +355,14 @@ export let I18nAdminKo: I18nAdmin = {
             autoLinkDesc2: `CAUTION: This option can be very dangerous and lead to account takeover if the provider
                 does not fully validate E-Mail addresses for users and therefore makes it possible to add a foreign
                 address for a user! MUST NEVER be used in such a case!`,
+            autoRedirect: 'Auto-Redirect',
+            autoRedirectDesc: `If a login_hint or the entered E-Mail matches one of the E-Mail domains, the
+                login page will redirect to this provider immediately. Existing users of these domains will be
+                linked automatically with their first login.`,
+            claimPrecedence: 'Claim Precedence',
+            claimPrecedenceDesc: `Controls if the E-Mail, names and user values from the provider overwrite
+                the local ones with each login (upstream), only with the first login via this provider
+                (first_login), or never for existing users (local).`,
             clientName: '클라이언트 이름',
             custRootCa: '사용자 지정 Root CA PEM 사용',
             descAuthMethod: `<code>/token</code> 엔드포인트에서 사용할 인증 방법입니다.<br>
@@ -347,6 +374,9 @@ export let I18nAdminKo: I18nAdmin = {
             descClientSecret: `인증 공급자가 제공한 클라이언트 Secret입니다. 최소한 클라이언트
                 Secret 또는 PKCE가 필요합니다.`,
             descScope: `로그인 리디렉션에 사용할 범위입니다. 공백으로 구분하여 입력합니다.`,
+            emailDomains: 'E-Mail Domains',
+            emailDomainsDesc: `E-Mail domains that belong to this provider. A leading *. matches all
+                subdomains. If a login_hint matches, this provider will be pre-selected.`,
             errNoAuthMethod: `클라이언트 Secret이 입력되어 있지만, 클라이언트 인증 방법이 활성화되어
                 있지 않습니다.`,
             errConfidential: '최소한 기밀 클라이언트이거나 PKCE를 사용해야 합니다.',
@@ -368,6 +398,10 @@ export let I18nAdminKo: I18nAdmin = {
             mapMfa: `공급자에서 사용자가 로그인하는 동안 2FA 이상을 사용했음을 나타내는 Claim을 발행하는 경우,
                 MFA Claim 경로를 지정할 수 있습니다.`,
             mapUser: `업스트림 ID Claim 에 따라 사용자를 Rauthy 관리자로 매핑할 수 있습니다.`,
+            uidNormalization: 'User ID Normalization',
+            uidNormalizationDesc: `Applied to the upstream user ID before it is linked or looked up. Only use
+                trim_lowercase, if the provider treats its user IDs case-insensitive, otherwise different
+                upstream users could end up with the same account.`,
             valueAdminClaim: '관리자 Claim 값',
             valueMfaClaim: 'MFA Claim 값',
         },
@@ -393,6 +427,8 @@ export let I18nAdminKo: I18nAdmin = {
         mapping2: `구성된 모든 추가 속성은 각 사용자에 대해 사용자 지정 값을 가질 수 있습니다.
             이러한 속성이 범위에 매핑되면 액세스 및 ID 토큰에 포함될 수 있습니다.`,
         name: '범위 이름',
+        requiredRoles: '역할을 선택하면, 해당 역할 중 하나 이상을 가진 사용자만 이 범위를 받을 수 있습니다.',
+        requiredRolesLabel: '필수 역할',
     },
     search: {
         orderBy: '정렬 기준',
@@ -431,6 +467,9 @@ export let I18nAdminKo: I18nAdmin = {
             rauthyAdmin: 'rauthy_admin rule cannot be removed',
         },
         attributes: '속성',
+        deactivate: '비활성화',
+        deactivateUser: '대신 이 사용자를 비활성화합니다. 모든 세션과 토큰이 즉시 취소되며 설정된 보존 기간이 지나면 사용자가 영구적으로 삭제됩니다.',
+        deactivated: '비활성화되어 삭제 예정, 시작 시점',
         deleteUser: '이 사용자를 삭제하시겠습니까?',
         descAttr: `사용자 지정 속성을 설정합니다. 모든 키/값 쌍은 문자열/JSON 값으로 처리됩니다.`,
         forceLogout: `기존 세션을 모두 삭제하고, 이 사용자의 모든 Refresh 토큰을 삭제하시겠습니까?`,
@@ -449,6 +488,7 @@ export let I18nAdminKo: I18nAdmin = {
         pwdNoInit: '사용자가 아직 초기 비밀번호 재설정을 수행하지 않았습니다.',
         pwdSendEmailBtn: '재설정 이메일 보내기',
         pwdSendEmailDesc: '새 비밀번호를 설정하거나, 재설정 이메일을 보낼 수 있습니다.',
+        reactivate: '다시 활성화',
         savePassword: '비밀번호 저장',
         selfServiceDesc: '새 비밀번호를 설정하거나, 재설정 이메일을 보낼 수 있습니다.',
         sendResetEmail: '재설정 이메일 보내기',
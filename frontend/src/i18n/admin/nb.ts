@@ -19,6 +19,9 @@ export let I18nAdminNb: I18nAdmin = {
         delete1: 'Skal dette attributtet slettes?',
         defaultValue: 'Standardverdi',
         desc: 'Beskrivelse',
+        internal: 'Intern',
+        internalDesc:
+            'Interne attributter blir aldri inkludert i tokens, userinfo eller SCIM-synkronisering, selv om de er koblet til et scope.',
         makeEditable: 'Gjør redigerbart',
         makeEditableP1: 'Dette attributtet kan gjøres redigerbart av brukere.',
         makeEditableP2: `<b>ADVARSEL:</b> Denne endringen kan aldri angres! Alle opplysninger gitt direkte av en
@@ -49,6 +52,8 @@ export let I18nAdminNb: I18nAdmin = {
                 for eksempel fritekst kan referere til variablene, like som med
                 <code>hsla(var(--action) / .7)</code>.`,
         },
+        claimMapping: 'Claim-mapping',
+        claimNamespace: 'Claim-navnerom',
         confidential: 'Følsomt',
         confidentialNoSecret: 'Dette er ikke en følsom klient, og har derfor ingen hemmelighet.',
         config: 'Klient konfigurasjon',
@@ -56,6 +61,9 @@ export let I18nAdminNb: I18nAdmin = {
         descAuthCode: `Gyldigheten til Auth kodene kan justeres for å oppnå ekstra sikkerhet.
             Auth kodene kan kun brukes en gang og er normalt gyldige i 60 sekunder. Jo kortere, jo bedre
             så lenge klienten kan bruke koden raskt nok.`,
+        descClaimMapping: `Eldre applikasjoner kan forvente claims med andre navn. Hver mapping har formatet
+            <code>intern=utdata</code>, f.eks. <code>roles=https://example.com/roles</code>. Hvis et navnerom er
+            satt, vil alle mappede claims bli nestet inne i et objekt med dette navnet.`,
         descClientUri: `Informasjon om URI og kontakter for denne klienten, som vises
             på innloggingssiden.`,
         descName: `Klientnavnet kan endres uten å påvirke konfigurasjonen.
@@ -75,6 +83,13 @@ export let I18nAdminNb: I18nAdmin = {
             Challenge aktivert.`,
         forceMfa: 'Tving MFA',
         groupLoginPrefix: 'Gruppepåloggingsprefiks',
+        introspect: 'Tillat token-introspeksjon',
+        requireNonce: 'Krev nonce',
+        firstParty: 'Førstepartsklient (klarert)',
+        descSectorIdentifier:
+            'Klienter med samme vert i Sector Identifier URI får samme `sub`. Uten den er hver klient sin egen sektor.',
+        pairwiseSub: 'Pairwise Subject (`sub` per sektor)',
+        strictUriMatch: 'Streng URI-matching (`*` kun for stier eller ett subdomene)',
         name: 'Klientnavn',
         scim: {
             baseUri: `SCIM Base URI'en må være den som underordnede ruter som
@@ -110,12 +125,18 @@ export let I18nAdminNb: I18nAdmin = {
                 beholdes i in-memory cachen i en viss tid. En verdi mellom 1 og 24 timer kan angis.`,
             rotateDesc2:
                 'ADVARSEL: Den nåværende hemmeligheten bør ikke beholdes i cachen hvis det har vært et lekkasje!',
+            custom: 'Sett Egen Hemmelighet',
+            customDesc: `I stedet for å generere en ny hemmelighet kan du sette din egen. Den må oppfylle den
+                konfigurerte minimumslengden og entropien og vil aldri vises igjen.`,
+            hashed:
+                'Hemmeligheten lagres hashet og kan bare vises én gang rett etter at den er generert.',
         },
         tokenLifetime: {
             p1: 'Tokenets levetid brukes for Access og ID Tokens og angis i sekunder.',
             p2: `Hvis klienten støtter EdDSA / ed25519 algoritmer, bør dette være det foretrukne valget.
                 RSA algoritmer eksisterer kun av kompatibilitetsgrunner.`,
             p3: `Algoritmen for Refresh Tokens kan ikke endres, da disse kun skal brukes av Rauthy.`,
+            p4: `Refresh Tokens forlenges ved hver fornyelse. Den valgfrie maksimale levetiden i sekunder er en absolutt grense fra den opprinnelige innloggingen, hvoretter brukeren må autentisere seg på nytt.`,
         },
     },
     common: {
@@ -330,6 +351,14 @@ export let I18nAdminNb: I18nAdmin = {
             autoLink: 'Auto-link bruker',
             autoLinkDesc1: `Hvis auto-link bruker er aktivert, vil en eventuell eksisterende, ikke-koblet bruker automatisk kobles til denne leverandøren ved innlogging.`,
             autoLinkDesc2: `ADVARSEL: Dette kan være svært farlig og føre til kontoovertakelse hvis leverandøren ikke utfører fullstendig e-postverifisering og lar en fremmed adresse bli registrert for en bruker! MÅ ALDRI brukes i slike tilfeller!`,
+            autoRedirect: 'Auto-Redirect',
+            autoRedirectDesc: `If a login_hint or the entered E-Mail matches one of the E-Mail domains, the
+                login page will redirect to this provider immediately. Existing users of these domains will be
+                linked automatically with their first login.`,
+            claimPrecedence: 'Claim Precedence',
+            claimPrecedenceDesc: `Controls if the E-Mail, names and user values from the provider overwrite
+                the local ones with each login (upstream), only with the first login via this provider
+                (first_login), or never for existing users (local).`,
             clientName: 'Klientnavn',
             custRootCa: 'Egen Root CA PEM',
             descAuthMethod: `Autentiseringsmetoden som skal brukes på <code>/token</code>-endepunktet.<br>De fleste leverandører bør fungere med <code>basic</code>, noen kun med <code>post</code>. I sjeldne tilfeller må begge aktiveres, selv om det kan føre til feil med andre leverandører.`,
@@ -337,6 +366,9 @@ export let I18nAdminNb: I18nAdmin = {
             descClientName: 'Klientnavn som skal vises på Rauthy-innloggingssiden.',
             descClientSecret: `Klienthemmelighet gitt av leverandøren. Minst én hemmelighet eller PKCE må være aktivert.`,
             descScope: `Omfanget klienten skal bruke ved omdirigering til innlogging. Verdier skilles med mellomrom.`,
+            emailDomains: 'E-Mail Domains',
+            emailDomainsDesc: `E-Mail domains that belong to this provider. A leading *. matches all
+                subdomains. If a login_hint matches, this provider will be pre-selected.`,
             errNoAuthMethod:
                 'Du har oppgitt en klienthemmelighet, men ingen autentiseringsmetode er aktiv',
             errConfidential: 'Må være enten en følsom klient eller bruke PKCE',
@@ -354,6 +386,10 @@ export let I18nAdminNb: I18nAdmin = {
             rootPemCert: 'Root PEM-sertifikat',
             mapMfa: `Hvis leverandøren gir en claim som indikerer at brukeren har brukt minst 2FA ved innlogging, kan du oppgi stien til MFA-claimen her.`,
             mapUser: `Du kan mappe en bruker til å være Rauthy-admin basert på en upstream ID-claim.`,
+            uidNormalization: 'User ID Normalization',
+            uidNormalizationDesc: `Applied to the upstream user ID before it is linked or looked up. Only use
+                trim_lowercase, if the provider treats its user IDs case-insensitive, otherwise different
+                upstream users could end up with the same account.`,
             valueAdminClaim: 'Verdi for admin-claim',
             valueMfaClaim: 'Verdi for MFA-claim',
         },
@@ -377,6 +413,8 @@ export let I18nAdminNb: I18nAdmin = {
         mapping1: 'Bruker-attributter kan mappes til egne scopes.',
         mapping2: `Hvert eksisterende attributt har en egen verdi per bruker. Disse attributtene kan mappes til en scope og vil da inkluderes i Access- eller ID-tokenet.`,
         name: 'Scope-navn',
+        requiredRoles: 'Hvis roller er valgt, kan bare brukere med minst én av dem få denne scopen.',
+        requiredRolesLabel: 'Påkrevde roller',
     },
     sessions: {
         invalidateAll: 'Invalidér alle økter',
@@ -415,6 +453,10 @@ export let I18nAdminNb: I18nAdmin = {
             rauthyAdmin: 'rauthy_admin-rollen kan ikke fjernes',
         },
         attributes: 'Attributter',
+        deactivate: 'Deaktiver',
+        deactivateUser: `Deaktiver denne brukeren i stedet. Alle sesjoner og tokens blir trukket tilbake umiddelbart,
+            og brukeren slettes permanent etter den konfigurerte oppbevaringsperioden.`,
+        deactivated: 'Deaktivert og planlagt for sletting siden',
         deleteUser: 'Skal denne brukeren slettes?',
         descAttr: `Sett individuelle bruker-attributter. Alle nøkkel/verdi-par håndteres som String/JSON-verdi.`,
         forceLogout: `Skal alle økter for denne brukeren invalidiseres og alle refresh tokens slettes?`,
@@ -432,6 +474,7 @@ export let I18nAdminNb: I18nAdmin = {
         pwdSendEmailBtn: 'Send e-post for tilbakestilling',
         pwdSendEmailDesc:
             'Du kan sende en ny e-post for tilbakestilling hvis brukeren ikke har mottatt en.',
+        reactivate: 'Reaktiver',
         savePassword: 'Lagre passord',
         selfServiceDesc:
             'Du kan enten sette et nytt passord eller sende en e-post for tilbakestilling.',
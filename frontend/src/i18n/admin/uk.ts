@@ -20,6 +20,9 @@ export let I18nAdminUk: I18nAdmin = {
         delete1: 'Дійсно видалити цей атрибут?',
         defaultValue: 'Значення за замовчуванням',
         desc: 'Опис',
+        internal: 'Внутрішній',
+        internalDesc:
+            "Внутрішні атрибути ніколи не включаються до токенів, userinfo чи синхронізації SCIM, навіть якщо вони прив'язані до scope.",
         makeEditable: 'Дозволити редагування',
         makeEditableP1:
             'Ви можете змінити цей атрибут, дозволивши користувачам редагувати його самостійно.',
@@ -48,6 +51,8 @@ export let I18nAdminUk: I18nAdmin = {
             descVariables: `Кожна наступна мітка є водночас назвою CSS-змінної. Це означає,
                 що ви можете посилатися на них у довільних полях, наприклад: <code>hsla(var(--action) / .7)</code>.`,
         },
+        claimMapping: 'Зіставлення claims',
+        claimNamespace: 'Простір імен claims',
         confidential: 'Конфіденційний',
         confidentialNoSecret: 'Цей клієнт не є конфіденційним, тому не має секрету.',
         config: 'Конфігурація клієнта',
@@ -55,6 +60,9 @@ export let I18nAdminUk: I18nAdmin = {
         descAuthCode: `Термін дії кодів авторизації можна налаштувати для підвищення безпеки. Коди авторизації
             можна використати лише один раз, і за замовчуванням вони дійсні 60 секунд. Чим коротший термін дії,
             тим краще, за умови, що клієнт може виконати процедуру входу достатньо швидко.`,
+        descClaimMapping: `Застарілі застосунки можуть очікувати claims з іншими іменами. Кожне зіставлення має
+            формат <code>internal=output</code>, напр. <code>roles=https://example.com/roles</code>. Якщо задано
+            простір імен, усі зіставлені claims будуть вкладені в об'єкт з цим іменем.`,
         descClientUri: `Інформація про URI цього клієнта та контакти для відображення
             на сторінці входу.`,
         descName: `Назву клієнта можна змінити без будь-якого впливу на його конфігурацію.
@@ -74,6 +82,13 @@ export let I18nAdminUk: I18nAdmin = {
             метод PKCE.`,
         forceMfa: 'Вимагати MFA',
         groupLoginPrefix: 'Префікс групи для входу',
+        introspect: 'Дозволити інтроспекцію токенів',
+        requireNonce: 'Вимагати nonce',
+        firstParty: 'Власний клієнт (довірений)',
+        descSectorIdentifier:
+            'Клієнти з однаковим хостом у Sector Identifier URI отримують однаковий `sub`. Без нього кожен клієнт є окремим сектором.',
+        pairwiseSub: 'Pairwise Subject (`sub` для кожного сектора)',
+        strictUriMatch: 'Сувора перевірка URI (`*` лише для шляхів або одного піддомену)',
         name: 'Назва клієнта',
         scim: {
             baseUri: `Базовий URI для SCIM — це той, з якого можна коректно вивести під-маршрути,
@@ -108,12 +123,18 @@ export let I18nAdminUk: I18nAdmin = {
             rotateDesc1: `Щоб забезпечити плавне оновлення та ротацію секретів, ви маєте можливість зберігати
                 поточний секрет у кеші в пам'яті на деякий час. Ви можете ввести значення від 1 до 24 годин.`,
             rotateDesc2: 'Увага: Не кешуйте поточний секрет, якщо стався його витік!',
+            custom: 'Встановити власний секрет',
+            customDesc: `Замість генерації нового секрету ви можете встановити власний. Він має відповідати
+                налаштованим мінімальній довжині та ентропії і більше ніколи не буде показаний.`,
+            hashed:
+                'Секрет зберігається у хешованому вигляді і може бути показаний лише один раз одразу після генерації.',
         },
         tokenLifetime: {
             p1: `Час життя токена застосовується до токенів доступу (Access) та ID-токенів і вказується в секундах.`,
             p2: `Якщо клієнт підтримує алгоритми EdDSA / ed25519, їм завжди слід віддавати перевагу.
                 Алгоритми RSA існують лише для сумісності.`,
             p3: `Алгоритм для refresh-токенів не може бути змінений, оскільки вони використовуються лише Rauthy.`,
+            p4: `Refresh-токени продовжуються з кожним оновленням. Необов'язковий максимальний час життя в секундах є абсолютною межею від початкового входу, після якої користувач має автентифікуватися знову.`,
         },
     },
     common: {
@@ -357,6 +378,14 @@ export let I18nAdminUk: I18nAdmin = {
             autoLinkDesc2: `УВАГА: Ця опція може бути дуже небезпечною і призвести до захоплення акаунта, якщо
                 провайдер не перевіряє повністю адреси E-Mail для користувачів і, таким чином, дає
                 можливість додати чужу адресу для користувача! НІКОЛИ не використовуйте в такому випадку!`,
+            autoRedirect: 'Auto-Redirect',
+            autoRedirectDesc: `If a login_hint or the entered E-Mail matches one of the E-Mail domains, the
+                login page will redirect to this provider immediately. Existing users of these domains will be
+                linked automatically with their first login.`,
+            claimPrecedence: 'Claim Precedence',
+            claimPrecedenceDesc: `Controls if the E-Mail, names and user values from the provider overwrite
+                the local ones with each login (upstream), only with the first login via this provider
+                (first_login), or never for existing users (local).`,
             clientName: 'Назва клієнта',
             custRootCa: 'Власний кореневий CA (PEM)',
             descAuthMethod: `Метод автентифікації для ендпоінту <code>/token</code>.<br>
@@ -368,6 +397,9 @@ export let I18nAdminUk: I18nAdmin = {
                 Потрібен щонайменше секрет клієнта або PKCE.`,
             descScope: `Скоуп, який клієнт повинен використовувати при перенаправленні на сторінку входу.
                 Вкажіть значення через пробіл.`,
+            emailDomains: 'E-Mail Domains',
+            emailDomainsDesc: `E-Mail domains that belong to this provider. A leading *. matches all
+                subdomains. If a login_hint matches, this provider will be pre-selected.`,
             errNoAuthMethod:
                 'Ви вказали секрет клієнта, але не активували жодного методу автентифікації клієнта',
             errConfidential: 'Клієнт повинен бути конфіденційним або використовувати PKCE',
@@ -388,6 +420,10 @@ export let I18nAdminUk: I18nAdmin = {
             mapMfa: `Якщо ваш провайдер видає claim, що вказує на те, що користувач використовував принаймні
                 2FA під час входу, ви можете вказати шлях до mfa claim.`,
             mapUser: `Ви можете призначити користувача адміном Rauthy на основі клейму (claim) з ID-токена зовнішнього провайдера.`,
+            uidNormalization: 'User ID Normalization',
+            uidNormalizationDesc: `Applied to the upstream user ID before it is linked or looked up. Only use
+                trim_lowercase, if the provider treats its user IDs case-insensitive, otherwise different
+                upstream users could end up with the same account.`,
             valueAdminClaim: 'Значення Admin Claim',
             valueMfaClaim: 'Значення MFA Claim',
         },
@@ -414,6 +450,8 @@ export let I18nAdminUk: I18nAdmin = {
             користувача. Коли вони прив'язані до скоупа, їх можна включити до токенів доступу
             (Access) та/або ID-токенів.`,
         name: 'Назва скоупа',
+        requiredRoles: 'Якщо вибрано ролі, цей скоуп зможуть отримати лише користувачі, які мають хоча б одну з них.',
+        requiredRolesLabel: 'Необхідні ролі',
     },
     search: {
         orderBy: 'Сортувати за...',
@@ -450,6 +488,10 @@ export let I18nAdminUk: I18nAdmin = {
             rauthyAdmin: 'правило rauthy_admin не можна видалити',
         },
         attributes: 'Атрибути',
+        deactivate: 'Деактивувати',
+        deactivateUser: `Натомість деактивувати цього користувача. Усі сесії та токени буде негайно відкликано,
+            а користувача буде остаточно видалено після налаштованого періоду зберігання.`,
+        deactivated: 'Деактивовано та заплановано до видалення з',
         deleteUser: 'Дійсно видалити цього користувача?',
         descAttr: `Встановіть власні атрибути користувача. Усі пари ключ-значення обробляються як String / JSON Value.`,
         forceLogout: `Дійсно завершити всі існуючі сесії та видалити всі
@@ -472,6 +514,7 @@ export let I18nAdminUk: I18nAdmin = {
         pwdSendEmailBtn: 'Надіслати лист для відновлення',
         pwdSendEmailDesc:
             'Ви можете надіслати нового листа для відновлення, якщо користувач його не отримав.',
+        reactivate: 'Активувати знову',
         savePassword: 'Зберегти пароль',
         selfServiceDesc:
             'Ви можете встановити новий пароль або надіслати листа для його відновлення.',
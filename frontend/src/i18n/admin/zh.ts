@@ -19,6 +19,8 @@ export let I18nAdminZh: I18nAdmin = {
         delete1: '您确定要删除此属性吗？',
         defaultValue: '默认值',
         desc: '描述',
+        internal: '内部',
+        internalDesc: '内部属性永远不会包含在任何令牌、userinfo 或 SCIM 同步中，即使它们已映射到作用域。',
         makeEditable: '设为可编辑',
         makeEditableP1: '您可以转换此属性并允许用户自己进行编辑。',
         makeEditableP2: `<b>注意：</b>此操作无法撤消！所有来自用户的直接输入都是不受信任的数据，
@@ -46,6 +48,8 @@ export let I18nAdminZh: I18nAdmin = {
             descVariables: `每个标签同时也是CSS变量名。这意味着，
                 您可以在自由输入框中引用它们，例如<code>hsla(var(--action) / .7)</code>。`,
         },
+        claimMapping: '声明映射',
+        claimNamespace: '声明命名空间',
         confidential: '机密',
         confidentialNoSecret: '这是一个非机密客户端，因此没有密钥。',
         config: '客户端配置',
@@ -53,6 +57,9 @@ export let I18nAdminZh: I18nAdmin = {
         descAuthCode: `可以调整认证码的有效性以提高安全性。认证码
             只能使用一次，默认有效时间为60秒。只要客户端能够足够快地执行登录过程，
             有效性越短越好。`,
+        descClaimMapping: `旧版应用可能需要不同名称的声明。每个映射的格式为
+            <code>internal=output</code>，例如 <code>roles=https://example.com/roles</code>。如果设置了命名空间，
+            所有映射的声明都将嵌套在具有该名称的对象中。`,
         descClientUri: `关于此客户端的URI信息和联系方式将显示在
             登录页面上。`,
         descName: `客户端名称可以更改而不会影响客户端配置。
@@ -71,6 +78,13 @@ export let I18nAdminZh: I18nAdmin = {
             挑战。`,
         forceMfa: '强制MFA',
         groupLoginPrefix: '登录组前缀',
+        introspect: '允许令牌内省',
+        requireNonce: '要求 Nonce',
+        firstParty: '第一方客户端（受信任）',
+        descSectorIdentifier:
+            'Sector Identifier URI 主机相同的客户端将获得相同的 `sub`。未设置时，每个客户端都是独立的扇区。',
+        pairwiseSub: '成对主体标识（每个扇区的 `sub`）',
+        strictUriMatch: '严格 URI 匹配（`*` 仅用于路径或一级子域名）',
         name: '客户端名称',
         scim: {
             baseUri: `SCIM基础URI是从中可以正确派生子路由的URI，
@@ -103,12 +117,17 @@ export let I18nAdminZh: I18nAdmin = {
             rotateDesc1: `为了实现平滑更新和密钥轮换，您可以选择在内存中缓存当前密钥一段时间。
                 您可以输入1到24小时之间的值。`,
             rotateDesc2: '注意：如果发生泄露，您不应缓存当前密钥！',
+            custom: '设置自定义密钥',
+            customDesc: `除了生成新密钥外，您也可以设置自己的密钥。它必须满足配置的最小长度和熵要求，
+                并且之后不会再显示。`,
+            hashed: '密钥以哈希形式存储，只能在生成后立即显示一次。',
         },
         tokenLifetime: {
             p1: `令牌生命周期适用于访问令牌和ID令牌，以秒为单位。`,
             p2: `如果客户端支持EdDSA / ed25519算法，这应该是首选。
                 RSA算法仅出于兼容性考虑而存在。`,
             p3: `刷新令牌的算法无法更改，因为这些令牌仅供Rauthy使用。`,
+            p4: `刷新令牌会在每次刷新时延长。可选的最大生命周期（秒）是从初次登录开始计算的绝对上限，之后用户必须重新认证。`,
         },
     },
     common: {
@@ -332,6 +351,14 @@ export let I18nAdminZh: I18nAdmin = {
             autoLinkDesc2: `注意：如果提供商不对用户完全验证邮箱地址，
                 从而使用户可能添加外来地址，则此选项非常危险并可能导致帐户接管！
                 在这种情况下绝不能使用！`,
+            autoRedirect: 'Auto-Redirect',
+            autoRedirectDesc: `If a login_hint or the entered E-Mail matches one of the E-Mail domains, the
+                login page will redirect to this provider immediately. Existing users of these domains will be
+                linked automatically with their first login.`,
+            claimPrecedence: 'Claim Precedence',
+            claimPrecedenceDesc: `Controls if the E-Mail, names and user values from the provider overwrite
+                the local ones with each login (upstream), only with the first login via this provider
+                (first_login), or never for existing users (local).`,
             clientName: '客户端名称',
             custRootCa: '自定义根CA PEM',
             descAuthMethod: `在<code>/token</code>端点使用的身份验证方法。<br>
@@ -343,6 +370,9 @@ export let I18nAdminZh: I18nAdmin = {
                 至少需要客户端密钥或PKCE。`,
             descScope: `客户端在重定向到登录时应使用的作用域。
                 提供以空格分隔的值。`,
+            emailDomains: 'E-Mail Domains',
+            emailDomainsDesc: `E-Mail domains that belong to this provider. A leading *. matches all
+                subdomains. If a login_hint matches, this provider will be pre-selected.`,
             errNoAuthMethod: '您提供了客户端密钥，但没有激活客户端身份验证方法',
             errConfidential: '必须至少是机密客户端或使用PKCE',
             jsonPath: {
@@ -362,6 +392,10 @@ export let I18nAdminZh: I18nAdmin = {
             mapMfa: `如果您的提供商在登录期间发出表明用户至少使用了2FA的声明，
                 您可以指定MFA声明路径。`,
             mapUser: `您可以根据上游ID声明将用户映射为Rauthy管理员。`,
+            uidNormalization: 'User ID Normalization',
+            uidNormalizationDesc: `Applied to the upstream user ID before it is linked or looked up. Only use
+                trim_lowercase, if the provider treats its user IDs case-insensitive, otherwise different
+                upstream users could end up with the same account.`,
             valueAdminClaim: '管理员声明值',
             valueMfaClaim: 'MFA声明值',
         },
@@ -387,6 +421,8 @@ export let I18nAdminZh: I18nAdmin = {
         mapping2: `所有已配置的附加属性都可以为每个用户提供自定义值。
             当它们映射到作用域时，可以包含在访问令牌和/或ID令牌中。`,
         name: '作用域名称',
+        requiredRoles: '如果选择了角色，只有至少拥有其中一个角色的用户才能获得此作用域。',
+        requiredRolesLabel: '所需角色',
     },
     search: {
         orderBy: '排序方式...',
@@ -423,6 +459,9 @@ export let I18nAdminZh: I18nAdmin = {
             rauthyAdmin: '无法移除rauthy_admin规则',
         },
         attributes: '属性',
+        deactivate: '停用',
+        deactivateUser: '改为停用此用户。所有会话和令牌将立即被撤销，用户将在配置的保留期后被永久删除。',
+        deactivated: '已停用并计划删除，开始于',
         deleteUser: '您确定要删除此用户吗？',
         descAttr: `设置自定义用户属性。所有键/值对将作为字符串/JSON值处理。`,
         forceLogout: `您确定要使此用户的所有现有会话无效并删除所有刷新令牌吗？`,
@@ -440,6 +479,7 @@ export let I18nAdminZh: I18nAdmin = {
         pwdNoInit: '用户尚未执行初始密码重置。',
         pwdSendEmailBtn: '发送重置邮件',
         pwdSendEmailDesc: '如果用户未收到邮件，您可以发送新的重置邮件。',
+        reactivate: '重新启用',
         savePassword: '保存密码',
         selfServiceDesc: '您可以设置新密码或发送重置邮件。',
         sendResetEmail: '发送重置邮件',
@@ -182,6 +182,7 @@ export const I18nDe: I18n = {
         needsUserUpdate: `Es fehlen Informationen zum Account. Diese benötigen ein Update, bevor
             mit dem Login fortgefahren werden kann.`,
         orLoginWith: 'oder einloggen mit',
+        orRecoverWith: 'oder Account wiederherstellen mit',
         password: 'Password',
         passwordExpired:
             'Passwort ist abgelaufen. Eine E-Mail mit einem Reset-Link wurde verschickt.',
@@ -215,6 +216,8 @@ export const I18nDe: I18n = {
         textChanged: 'Ihre E-Mail Adresse wurde erfolgreich geändert von',
         textLogin: 'Sie können sich jetzt mit der neuen Adresse einloggen.',
         to: 'zu',
+        titleAborted: 'E-Mail Wechsel abgebrochen',
+        textAborted: 'Der angeforderte Wechsel Ihrer E-Mail Adresse wurde abgebrochen. Ihre aktuelle Adresse bleibt aktiv.',
     },
     error: {
         // errorText: "Die angeforderte Seite konnte nicht gefunden werden.",
@@ -230,6 +233,7 @@ export const I18nDe: I18n = {
         logout: 'Logout',
         confirmMsg: 'Sind Sie sicher, dass Sie sich ausloggen und die Session beenden möchten?',
         cancel: 'Abbrechen',
+        loggedOut: 'Sie wurden erfolgreich ausgeloggt.',
     },
     mfa: {
         p1: `Wenn Sie mehrere Systeme parallel nutzen möchten, wie z.B. Windows und Android, 
@@ -176,6 +176,7 @@ export const I18nEn: I18n = {
         needsUserUpdate: `Some information for this account is missing. You need to update these 
             values before you can proceed with the login.`,
         orLoginWith: 'or login with',
+        orRecoverWith: 'or recover your account with',
         password: 'Password',
         passwordExpired: 'Password has expired. An E-Mail with a reset link was sent.',
         passwordForgotten: 'Password forgotten?',
@@ -208,6 +209,8 @@ export const I18nEn: I18n = {
         textChanged: 'Your E-Mail address has been changed from',
         textLogin: 'You can now log in using your new address.',
         to: 'to',
+        titleAborted: 'E-Mail Change aborted',
+        textAborted: 'The requested change of your E-Mail address has been aborted. Your current address stays active.',
     },
     error: {
         // errorText: "The requested data could not be found",
@@ -223,6 +226,7 @@ export const I18nEn: I18n = {
         logout: 'Logout',
         confirmMsg: 'Do you really want to logout and end your session?',
         cancel: 'Cancel',
+        loggedOut: 'You have been logged out successfully.',
     },
     mfa: {
         p1: `If you plan on using your MFA key with multiple systems like Windows and Android, 
@@ -142,6 +142,7 @@ export interface I18n {
         navigateToAccount: string;
         needsUserUpdate: string;
         orLoginWith: string;
+        orRecoverWith: string;
         password: string;
         passwordExpired: string;
         passwordForgotten: string;
@@ -174,6 +175,8 @@ export interface I18n {
         textChanged: string;
         textLogin: string;
         to: string;
+        titleAborted: string;
+        textAborted: string;
     };
     error: {
         details: string;
@@ -187,6 +190,7 @@ export interface I18n {
         logout: string;
         confirmMsg: string;
         cancel: string;
+        loggedOut: string;
     };
     mfa: {
         p1: string;
@@ -171,6 +171,7 @@ export const I18nKo: I18n = {
         needsUserUpdate: `Some information for this account is missing. You need to update these 
             values before you can proceed with the login.`,
         orLoginWith: '또는 다음으로 로그인',
+        orRecoverWith: '또는 다음으로 계정 복구',
         password: '비밀번호',
         passwordExpired: 'Password has expired. An E-Mail with a reset link was sent.',
         passwordForgotten: '비밀번호를 잊으셨나요?',
@@ -203,6 +204,8 @@ export const I18nKo: I18n = {
         textChanged: '이메일 주소가 다음으로부터 변경되었습니다',
         textLogin: '이제 새로운 주소로 로그인할 수 있습니다.',
         to: '에서',
+        titleAborted: '이메일 변경이 취소되었습니다.',
+        textAborted: '요청된 이메일 주소 변경이 취소되었습니다. 현재 주소는 그대로 유지됩니다.',
     },
     error: {
         // errorText: "요청 정보를 찾을 수 없습니다.",
@@ -218,6 +221,7 @@ export const I18nKo: I18n = {
         logout: '로그아웃',
         confirmMsg: '로그아웃하고 세션을 종료하겠습니까?',
         cancel: '취소',
+        loggedOut: '성공적으로 로그아웃되었습니다.',
     },
     mfa: {
         p1: '윈도우와 안드로이드 등 다양한 시스템에서 MFA를 사용하려면, 안드로이드에서 키를 등록하여야 합니다.',
@@ -175,6 +175,7 @@ export const I18nNb: I18n = {
         needsUserUpdate: `Some information for this account is missing. You need to update these 
             values before you can proceed with the login.`,
         orLoginWith: 'eller logg inn med',
+        orRecoverWith: 'eller gjenopprett kontoen med',
         password: 'Passord',
         passwordExpired: 'Password has expired. An E-Mail with a reset link was sent.',
         passwordForgotten: 'Glemt passord?',
@@ -207,6 +208,8 @@ export const I18nNb: I18n = {
         textChanged: 'Din e-postadresse ble vellykket endret fra',
         textLogin: 'Du kan nå logge inn med den nye adressen.',
         to: 'til',
+        titleAborted: 'E-postbytte avbrutt',
+        textAborted: 'Den forespurte endringen av e-postadressen din ble avbrutt. Din nåværende adresse forblir aktiv.',
     },
     error: {
         details: 'Vis detaljer',
@@ -220,6 +223,7 @@ export const I18nNb: I18n = {
         logout: 'Logg ut',
         confirmMsg: 'Er du sikker på at du vil logge ut og avslutte økten?',
         cancel: 'Avbryt',
+        loggedOut: 'Du er nå logget ut.',
     },
     mfa: {
         p1: `Hvis du ønsker å bruke flere systemer parallelt, som f.eks. Windows og Android, bør du 
@@ -182,6 +182,7 @@ export const I18nUk: I18n = {
         needsUserUpdate: `Some information for this account is missing. You need to update these 
             values before you can proceed with the login.`,
         orLoginWith: 'або увійдіть за допомогою',
+        orRecoverWith: 'або відновіть обліковий запис за допомогою',
         password: 'Пароль',
         passwordExpired: 'Password has expired. An E-Mail with a reset link was sent.',
         passwordForgotten: 'Забули пароль?',
@@ -214,6 +215,8 @@ export const I18nUk: I18n = {
         textChanged: 'Вашу адресу E-Mail було змінено з',
         textLogin: 'Тепер ви можете увійти, використовуючи нову адресу.',
         to: 'на',
+        titleAborted: 'Зміну E-Mail скасовано',
+        textAborted: 'Запит на зміну вашої адреси E-Mail скасовано. Ваша поточна адреса залишається активною.',
     },
     error: {
         // errorText: "Запитані дані не знайдено",
@@ -229,6 +232,7 @@ export const I18nUk: I18n = {
         logout: 'Вийти',
         confirmMsg: 'Ви дійсно хочете вийти і завершити сесію?',
         cancel: 'Скасувати',
+        loggedOut: 'Ви успішно вийшли.',
     },
     mfa: {
         p1: `Якщо ви плануєте використовувати свій MFA-ключ з різними системами, як-от Windows та
@@ -164,6 +164,7 @@ export const I18nZh: I18n = {
         needsUserUpdate: `Some information for this account is missing. You need to update these 
             values before you can proceed with the login.`,
         orLoginWith: '或使用其他方式登录：',
+        orRecoverWith: '或使用以下方式恢复账户：',
         password: '密码',
         passwordExpired: 'Password has expired. An E-Mail with a reset link was sent.',
         passwordForgotten: '忘记密码？',
@@ -195,6 +196,8 @@ export const I18nZh: I18n = {
         textChanged: '您的电子邮件地址已从',
         textLogin: '您现在可以使用您的新地址进行登录。',
         to: '更新为',
+        titleAborted: '电子邮件更改已取消',
+        textAborted: '您请求的电子邮件地址更改已被取消。您当前的地址保持不变。',
     },
     error: {
         // errorText: "找不到请求的资源",
@@ -210,6 +213,7 @@ export const I18nZh: I18n = {
         logout: '退出登录',
         confirmMsg: '您确定要退出登录并结束会话吗？',
         cancel: '取消',
+        loggedOut: '您已成功退出登录。',
     },
     mfa: {
         p1: `如果您计划在多个系统上使用您的MFA密钥，例如Windows和Android，您应该在Android上进行注册。`,
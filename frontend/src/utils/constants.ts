@@ -17,7 +17,10 @@ export const POST_LOGOUT_REDIRECT_URI = '/auth/v1/';
 export const EVENT_LEVELS = ['info', 'notice', 'warning', 'critical'];
 export const EVENT_TYPES = [
     '-',
+    'AccountRecovered',
+    'CacheFlushed',
     'ForcedLogout',
+    'IntrospectionDenied',
     'InvalidLogins',
     'IpBlacklisted',
     'IpBlacklistRemoved',
@@ -30,11 +33,24 @@ export const EVENT_TYPES = [
     'RauthyStarted',
     'RauthyHealthy',
     'RauthyUnhealthy',
+    'RecordsSwept',
+    'RegistrationApproved',
+    'RegistrationPending',
+    'RegistrationRateLimit',
+    'RegistrationRejected',
     'SecretsMigrated',
     'SuspiciousApiScan',
     'TokenIssued',
+    'TokensInvalidated',
+    'TokensRevoked',
+    'UserDataExported',
+    'UserDisabled',
     'UserEmailChange',
+    'UserEmailChangeRequest',
+    'UserEnabled',
+    'UserFederationRemapped',
     'UserLoginRevoke',
+    'UserOnboarded',
     'UserPasswordReset',
     'Test',
 ];
@@ -44,10 +60,12 @@ export const EVENT_TYPES = [
 export const TPL_ADMIN_BUTTON_HIDE = 'tpl_admin_btn_hide';
 export const TPL_ATPROTO_ID = 'tpl_atproto_id';
 export const TPL_AUTH_PROVIDERS = 'tpl_auth_providers';
+export const TPL_CAPTCHA = 'tpl_captcha';
 export const TPL_CLIENT_NAME = 'tpl_client_name';
 export const TPL_CLIENT_URL = 'tpl_client_url';
 export const TPL_CLIENT_LOGO_UPDATED = 'tpl_client_logo_updated';
 export const TPL_CSRF_TOKEN = 'tpl_csrf_token';
+export const TPL_DISPLAY = 'tpl_display';
 export const TPL_EMAIL_OLD = 'tpl_email_old';
 export const TPL_EMAIL_NEW = 'tpl_email_new';
 // export const TPL_ERROR_DETAILS = 'tpl_error_details';
@@ -55,7 +73,9 @@ export const TPL_ERROR_TEXT = 'tpl_error_text';
 export const TPL_DEVICE_USER_CODE_LENGTH = 'tpl_device_user_code_length';
 export const TPL_IS_REG_OPEN = 'tpl_is_reg_open';
 export const TPL_LOGIN_ACTION = 'tpl_login_action';
+export const TPL_LOGIN_HINT = 'tpl_login_hint';
 export const TPL_PASSWORD_RESET = 'tpl_password_reset';
+export const TPL_PROVIDER_RECOVERY = 'tpl_provider_recovery';
 export const TPL_STATUS_CODE = 'tpl_status_code';
 export const TPL_RESTRICTED_EMAIL_DOMAIN = 'tpl_restricted_email_domain';
 export const TPL_USER_VALUES_CONFIG = 'tpl_user_values_config';
@@ -9,8 +9,9 @@ import { PKCE_VERIFIER_UPSTREAM } from '$utils/constants';
  * The payload does not need to contain `pkce_challenge` and `pow`, as they will be
  * generated inside this function. Will return an error string, if anything fails,
  * and do a redirect otherwise.
+ * With `recovery` set, an account recovery via an already linked provider is started instead.
  */
-export async function execProviderLogin(payload: ProviderLoginRequest) {
+export async function execProviderLogin(payload: ProviderLoginRequest, recovery = false) {
     let pkce = await generatePKCE();
     if (!pkce) {
         return;
@@ -25,7 +26,8 @@ export async function execProviderLogin(payload: ProviderLoginRequest) {
     }
     payload.pow = pow;
 
-    let res = await fetchPost<string>('/auth/v1/providers/login', payload);
+    let url = recovery ? '/auth/v1/providers/recovery' : '/auth/v1/providers/login';
+    let res = await fetchPost<string>(url, payload);
 
     if (res.text) {
         saveProviderToken(res.text);
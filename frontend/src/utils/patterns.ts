@@ -6,6 +6,9 @@ export const PATTERN_ATTR = '^[a-zA-Z0-9\\-_\\/]{2,32}$';
 export const PATTERN_ATTR_DESC = '^[a-zA-Z0-9\\-_\\/\\s]{0,128}$';
 export const PATTERN_API_KEY = '^[a-zA-Z0-9_\\/\\-]{2,24}$';
 export const PATTERN_CITY = '^[a-zA-Z0-9À-ÿ\\-]{0,48}$';
+export const PATTERN_CLAIM_NAME = '^[a-zA-Z0-9.:\\/_\\-~#]{1,128}$';
+export const PATTERN_CLAIM_MAPPING =
+    '^[a-zA-Z0-9.:\\/_\\-~#]{1,128}=[a-zA-Z0-9.:\\/_\\-~#]{1,128}$';
 export const PATTERN_CLIENT_ID = "^[a-zA-Z0-9,.:\\/_\\-&?=~#!$'\\(\\)*+%]{2,256}$";
 export const PATTERN_CLIENT_NAME =
     '^[a-zA-Z0-9À-ɏ\\-\\s\\u3041-\\u3096\\u30A0-\\u30FF\\u3400-\\u4DB5\\u4E00-\\u9FCB\\uF900-\\uFA6A\\u2E80-\\u2FD5\\uFF66-\\uFF9F\\uFFA1-\\uFFDC\\u31F0-\\u31FF]{2,128}$';
@@ -14,6 +17,8 @@ export const PATTERN_CLIENT_NAME =
 export const PATTERN_CONTACT = '^[a-zA-Z0-9\\+.@\\/:-]{0,48}$';
 export const PATTERN_CSS_VALUE_LOOSE = '^[a-z0-9\\-,.#\\(\\)%\\/\\s]+$';
 // export const PATTERN_FLOW = '^(authorization_code|client_credentials|password|refresh_token)$';
+export const PATTERN_EMAIL_DOMAIN =
+    '^(\\*\\.)?[a-z0-9]([a-z0-9\\-]*[a-z0-9])?(\\.[a-z0-9]([a-z0-9\\-]*[a-z0-9])?)*$';
 export const PATTERN_GROUP = '^[a-zA-Z0-9\\-_\\/,:*\\s]{2,64}$';
 export const PATTERN_ROLE_SCOPE = '^[a-zA-Z0-9\\-_\\/,:*.]{2,64}$';
 // export const PATTERN_IPV4 = '^(?:25[0-5]|2[0-4]\\d|1\\d\\d|[1-9]\\d|\\d)(?:\\.(?:25[0-5]|2[0-4]\\d|1\\d\\d|[1-9]\\d|\\d)){3}$';
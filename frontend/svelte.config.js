@@ -30,9 +30,25 @@ const config = {
         csp: {
             directives: {
                 'default-src': ['none'],
-                'connect-src': ['self'],
-                'script-src': ['self', 'wasm-unsafe-eval'],
-                'style-src': ['self', 'unsafe-inline'],
+                'connect-src': ['self', 'https://*.hcaptcha.com'],
+                // the external hosts are only used if a `[captcha].provider` is configured
+                'script-src': [
+                    'self',
+                    'wasm-unsafe-eval',
+                    'https://challenges.cloudflare.com',
+                    'https://*.hcaptcha.com',
+                    'https://hcaptcha.com',
+                    'https://www.google.com/recaptcha/',
+                    'https://www.gstatic.com/recaptcha/',
+                ],
+                'frame-src': [
+                    'https://challenges.cloudflare.com',
+                    'https://*.hcaptcha.com',
+                    'https://hcaptcha.com',
+                    'https://www.google.com/recaptcha/',
+                    'https://recaptcha.google.com/recaptcha/',
+                ],
+                'style-src': ['self', 'unsafe-inline', 'https://*.hcaptcha.com'],
                 'img-src': ['self'],
             },
         },
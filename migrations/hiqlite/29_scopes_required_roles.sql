@@ -0,0 +1,2 @@
+ALTER TABLE scopes
+    ADD required_roles TEXT;
@@ -0,0 +1,6 @@
+ALTER TABLE clients
+    ADD introspect INTEGER DEFAULT 0 NOT NULL;
+
+-- existing clients keep being able to use the introspection endpoint
+UPDATE clients
+SET introspect = 1;
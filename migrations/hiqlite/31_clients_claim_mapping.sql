@@ -0,0 +1,4 @@
+ALTER TABLE clients
+    ADD claim_mapping TEXT;
+ALTER TABLE clients
+    ADD claim_namespace TEXT;
@@ -0,0 +1,17 @@
+CREATE TABLE user_emails
+(
+    email      TEXT    NOT NULL,
+    user_id    TEXT    NOT NULL
+        CONSTRAINT user_emails_users_id_fk
+            REFERENCES users
+            ON UPDATE CASCADE ON DELETE CASCADE,
+    verified   INTEGER NOT NULL,
+    created_at INTEGER NOT NULL,
+    CONSTRAINT user_emails_pk
+        PRIMARY KEY (user_id, email)
+) STRICT;
+
+-- pending addresses must not block other users, only verified ones are unique
+CREATE UNIQUE INDEX user_emails_email_verified_uindex
+    ON user_emails (email)
+    WHERE verified = TRUE;
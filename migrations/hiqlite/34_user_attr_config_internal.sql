@@ -0,0 +1,2 @@
+ALTER TABLE user_attr_config
+    ADD internal INTEGER DEFAULT false NOT NULL;
@@ -0,0 +1,5 @@
+ALTER TABLE auth_providers
+    ADD email_domains TEXT;
+
+ALTER TABLE auth_providers
+    ADD auto_redirect INTEGER DEFAULT 0 NOT NULL;
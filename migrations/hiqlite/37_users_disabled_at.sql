@@ -0,0 +1,5 @@
+ALTER TABLE users
+    ADD disabled_at INTEGER;
+
+CREATE INDEX users_disabled_at_index
+    ON users (disabled_at);
@@ -0,0 +1,2 @@
+ALTER TABLE clients
+    ADD refresh_token_max_lifetime INTEGER;
@@ -0,0 +1,2 @@
+ALTER TABLE auth_providers
+    ADD claim_precedence TEXT DEFAULT 'upstream' NOT NULL;
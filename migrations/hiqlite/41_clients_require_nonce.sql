@@ -0,0 +1,2 @@
+ALTER TABLE clients
+    ADD require_nonce INTEGER DEFAULT 0 NOT NULL;
@@ -0,0 +1,2 @@
+ALTER TABLE clients
+    ADD first_party INTEGER DEFAULT 0 NOT NULL;
@@ -0,0 +1,2 @@
+ALTER TABLE auth_providers
+    ADD uid_normalization TEXT DEFAULT 'exact' NOT NULL;
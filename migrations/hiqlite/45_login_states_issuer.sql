@@ -0,0 +1,5 @@
+ALTER TABLE user_login_states
+    ADD issuer TEXT;
+
+ALTER TABLE failed_backchannel_logouts
+    ADD issuer TEXT;
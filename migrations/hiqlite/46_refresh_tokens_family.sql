@@ -0,0 +1,8 @@
+ALTER TABLE refresh_tokens
+    ADD family_id TEXT DEFAULT '' NOT NULL;
+
+UPDATE refresh_tokens
+SET family_id = id;
+
+CREATE INDEX refresh_tokens_family_id_index
+    ON refresh_tokens (family_id);
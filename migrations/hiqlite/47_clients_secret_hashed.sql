@@ -0,0 +1,2 @@
+ALTER TABLE clients
+    ADD secret_hashed INTEGER DEFAULT 0 NOT NULL;
@@ -0,0 +1,6 @@
+ALTER TABLE clients
+    ADD introspect BOOL DEFAULT false NOT NULL;
+
+-- existing clients keep being able to use the introspection endpoint
+UPDATE clients
+SET introspect = true;
@@ -0,0 +1,4 @@
+ALTER TABLE clients
+    ADD claim_mapping VARCHAR;
+ALTER TABLE clients
+    ADD claim_namespace VARCHAR;
@@ -0,0 +1,5 @@
+ALTER TABLE issued_tokens
+    ADD client_id VARCHAR;
+
+CREATE INDEX issued_tokens_client_id_index
+    ON issued_tokens (client_id);
@@ -0,0 +1,2 @@
+ALTER TABLE user_attr_config
+    ADD internal BOOL DEFAULT false NOT NULL;
@@ -0,0 +1,14 @@
+CREATE TABLE user_ldap_links
+(
+    user_id        VARCHAR NOT NULL
+        CONSTRAINT user_ldap_links_users_id_fk
+            REFERENCES users
+            ON UPDATE CASCADE ON DELETE CASCADE,
+    provider_id    VARCHAR NOT NULL,
+    federation_uid VARCHAR NOT NULL,
+    CONSTRAINT user_ldap_links_pk
+        PRIMARY KEY (user_id, provider_id)
+);
+
+CREATE UNIQUE INDEX user_ldap_links_provider_id_federation_uid_uindex
+    ON user_ldap_links (provider_id, federation_uid);
@@ -0,0 +1,5 @@
+ALTER TABLE auth_providers
+    ADD email_domains VARCHAR;
+
+ALTER TABLE auth_providers
+    ADD auto_redirect BOOL DEFAULT false NOT NULL;
@@ -0,0 +1,5 @@
+ALTER TABLE users
+    ADD pending_since BIGINT;
+
+CREATE INDEX users_pending_since_index
+    ON users (pending_since);
@@ -0,0 +1,2 @@
+ALTER TABLE clients
+    ADD require_nonce BOOL DEFAULT false NOT NULL;
@@ -0,0 +1,2 @@
+ALTER TABLE clients
+    ADD first_party BOOL DEFAULT false NOT NULL;
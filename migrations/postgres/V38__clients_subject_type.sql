@@ -0,0 +1,4 @@
+ALTER TABLE clients
+    ADD subject_type VARCHAR DEFAULT 'public' NOT NULL;
+ALTER TABLE clients
+    ADD sector_identifier_uri VARCHAR;
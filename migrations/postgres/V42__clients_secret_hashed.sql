@@ -0,0 +1,2 @@
+ALTER TABLE clients
+    ADD secret_hashed BOOL DEFAULT false NOT NULL;
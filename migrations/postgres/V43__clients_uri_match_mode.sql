@@ -0,0 +1,3 @@
+-- All existing clients keep the legacy prefix matching for their `*` rules.
+ALTER TABLE clients
+    ADD uri_match_mode VARCHAR DEFAULT 'prefix' NOT NULL;
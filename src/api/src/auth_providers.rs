@@ -4,23 +4,30 @@ use actix_web::web::{Json, Query};
 use actix_web::{HttpRequest, HttpResponse, delete, get, post, put, web};
 use actix_web_lab::__reexports::futures_util::StreamExt;
 use rauthy_api_types::auth_providers::{
-    ProviderCallbackRequest, ProviderLinkedUserResponse, ProviderLoginRequest,
-    ProviderLookupRequest, ProviderRequest,
+    ProviderCallbackRequest, ProviderDiscoveryRequest, ProviderLinkedUserResponse,
+    ProviderLoginRequest, ProviderLookupRequest, ProviderRequest,
 };
-use rauthy_api_types::auth_providers::{ProviderLookupResponse, ProviderResponse};
-use rauthy_api_types::generic::LogoParams;
+use rauthy_api_types::auth_providers::{
+    ProviderDiscoveryResponse, ProviderFederationResponse, ProviderFederationUpdateRequest,
+    ProviderLookupResponse, ProviderResponse,
+};
+use rauthy_api_types::generic::{CursorParams, LogoParams};
 use rauthy_api_types::users::{UserResponse, WebauthnLoginResponse};
 use rauthy_common::constants::{HEADER_JSON, PROVIDER_ATPROTO};
+use rauthy_common::utils::real_ip_from_req;
 use rauthy_data::entity::api_keys::{AccessGroup, AccessRights};
 use rauthy_data::entity::auth_providers::{
     AuthProvider, AuthProviderLinkCookie, AuthProviderTemplate,
 };
+use rauthy_data::entity::continuation_token::Cursor;
 use rauthy_data::entity::logos::{Logo, LogoType};
 use rauthy_data::entity::pow::PowEntity;
 use rauthy_data::entity::theme::ThemeCssFull;
 use rauthy_data::entity::user_federation::UserFederation;
 use rauthy_data::entity::users::User;
+use rauthy_data::events::event::Event;
 use rauthy_data::html::HtmlCached;
+use rauthy_data::rauthy_config::RauthyConfig;
 use rauthy_error::{ErrorResponse, ErrorResponseType};
 use spow::pow::Pow;
 use tracing::debug;
@@ -128,6 +135,40 @@ pub async fn post_provider_lookup(
     Ok(HttpResponse::Ok().json(resp))
 }
 
+/// Home Realm Discovery for the given E-Mail
+///
+/// Returns the upstream auth provider the user should be redirected to, if the E-Mail
+/// domain belongs to an enabled provider with `auto_redirect`. If `provider_id` is empty,
+/// the local login should be used.
+///
+/// **Permissions**
+/// - `session-init`
+/// - `session-auth`
+#[utoipa::path(
+    post,
+    path = "/providers/discover",
+    tag = "providers",
+    request_body = ProviderDiscoveryRequest,
+    responses(
+        (status = 200, description = "OK", body = ProviderDiscoveryResponse),
+        (status = 400, description = "BadRequest", body = ErrorResponse),
+    ),
+)]
+#[post("/providers/discover")]
+pub async fn post_provider_discover(
+    Json(payload): Json<ProviderDiscoveryRequest>,
+    principal: ReqPrincipal,
+) -> Result<HttpResponse, ErrorResponse> {
+    principal.validate_session_auth_or_init()?;
+    payload.validate()?;
+
+    let provider = AuthProvider::find_home_realm(&payload.email.to_lowercase()).await?;
+
+    Ok(HttpResponse::Ok().json(ProviderDiscoveryResponse {
+        provider_id: provider.map(|p| p.id),
+    }))
+}
+
 /// Start the login flow for an upstream auth provider
 ///
 /// **Permissions**
@@ -156,7 +197,56 @@ pub async fn post_provider_login(
     PowEntity::check_prevent_reuse(challenge.to_string()).await?;
 
     let (cookie, xsrf_token, location) =
-        rauthy_service::oidc::auth_providers::login_start::login_start(payload).await?;
+        rauthy_service::oidc::auth_providers::login_start::login_start(payload, false).await?;
+
+    Ok(HttpResponse::Accepted()
+        .insert_header((LOCATION, location))
+        .cookie(cookie)
+        .body(xsrf_token))
+}
+
+/// Start an account recovery via an already linked upstream auth provider
+///
+/// Works exactly like `/providers/login`, but the callback will only accept users, which are
+/// already linked to this provider. New links or users will never be created. On success, all
+/// passkeys and sessions for the user will be deleted, and the callback redirects to the
+/// password reset page, where new credentials can be set up.
+///
+/// Only available with `mfa.provider_recovery` enabled.
+///
+/// **Permissions**
+/// - `session-init`
+#[utoipa::path(
+    post,
+    path = "/providers/recovery",
+    tag = "providers",
+    request_body = ProviderLoginRequest,
+    responses(
+        (status = 202, description = "Accepted"),
+        (status = 400, description = "BadRequest", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse),
+        (status = 404, description = "NotFound", body = ErrorResponse),
+    ),
+)]
+#[post("/providers/recovery")]
+pub async fn post_provider_recovery(
+    Json(payload): Json<ProviderLoginRequest>,
+    principal: ReqPrincipal,
+) -> Result<HttpResponse, ErrorResponse> {
+    if !RauthyConfig::get().vars.mfa.provider_recovery {
+        return Err(ErrorResponse::new(
+            ErrorResponseType::Forbidden,
+            "Account recovery via upstream providers is disabled",
+        ));
+    }
+    principal.validate_session_auth_or_init()?;
+    payload.validate()?;
+
+    let challenge = Pow::validate(&payload.pow)?;
+    PowEntity::check_prevent_reuse(challenge.to_string()).await?;
+
+    let (cookie, xsrf_token, location) =
+        rauthy_service::oidc::auth_providers::login_start::login_start(payload, true).await?;
 
     Ok(HttpResponse::Accepted()
         .insert_header((LOCATION, location))
@@ -276,6 +366,128 @@ pub async fn get_providers_minimal() -> Result<HttpResponse, ErrorResponse> {
     Ok(HttpResponse::Ok().insert_header(HEADER_JSON).body(tpl))
 }
 
+/// GET all user federations with upstream auth providers
+///
+/// The result is paginated with keyset pagination ordered by `(provider_id, user_id)`.
+/// The `x-next-cursor` header contains the opaque cursor for the next page and will be
+/// missing on the last one.
+///
+/// **Permissions**
+/// - `rauthy_admin`
+#[utoipa::path(
+    get,
+    path = "/providers/federations",
+    tag = "providers",
+    params(CursorParams),
+    responses(
+        (status = 206, description = "PartialContent", body = [ProviderFederationResponse]),
+        (status = 400, description = "BadRequest", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse),
+    ),
+)]
+#[get("/providers/federations")]
+pub async fn get_provider_federations(
+    principal: ReqPrincipal,
+    Query(params): Query<CursorParams>,
+) -> Result<HttpResponse, ErrorResponse> {
+    principal.validate_api_key_or_admin_session(AccessGroup::Providers, AccessRights::Read)?;
+    params.validate()?;
+
+    let limit = params.limit.unwrap_or(100) as i64;
+    let cursor = match &params.cursor {
+        Some(c) => Some(Cursor::decode(c)?),
+        None => None,
+    };
+
+    let (federations, next) = UserFederation::find_after(cursor, limit).await?;
+    let res = federations
+        .into_iter()
+        .map(|f| ProviderFederationResponse {
+            user_id: f.user_id,
+            provider_id: f.provider_id,
+            federation_uid: f.federation_uid,
+        })
+        .collect::<Vec<_>>();
+
+    let mut resp = HttpResponse::PartialContent();
+    if let Some(next) = next {
+        resp.insert_header(next.into_header_pair()?);
+    }
+    Ok(resp.json(res))
+}
+
+/// PUT re-map a user's federation link to a new upstream user id
+///
+/// This is needed, when an upstream provider migrated its subject identifiers and the
+/// existing link became stale, which would prevent the user from logging in. There is no
+/// generic admin API to re-resolve the id from an upstream provider, so the new
+/// `federation_uid` must be given explicitly. Each re-map creates a
+/// `UserFederationRemapped` event for audit.
+///
+/// **Permissions**
+/// - `rauthy_admin`
+#[utoipa::path(
+    put,
+    path = "/providers/{id}/federations/{user_id}",
+    tag = "providers",
+    request_body = ProviderFederationUpdateRequest,
+    responses(
+        (status = 200, description = "OK", body = ProviderFederationResponse),
+        (status = 400, description = "BadRequest", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse),
+        (status = 404, description = "NotFound", body = ErrorResponse),
+        (status = 406, description = "NotAccepted", body = ErrorResponse),
+    ),
+)]
+#[put("/providers/{id}/federations/{user_id}")]
+pub async fn put_provider_federation(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    principal: ReqPrincipal,
+    Json(payload): Json<ProviderFederationUpdateRequest>,
+) -> Result<HttpResponse, ErrorResponse> {
+    principal.validate_api_key_or_admin_session(AccessGroup::Providers, AccessRights::Update)?;
+    payload.validate()?;
+
+    let (provider_id, user_id) = path.into_inner();
+    let provider = AuthProvider::find(&provider_id).await?;
+    let user = User::find(user_id).await?;
+    let federation = UserFederation::find_by_user_provider(&user.id, &provider_id).await?;
+    let federation_uid = provider
+        .uid_normalization
+        .apply(&payload.federation_uid)
+        .into_owned();
+
+    if federation.federation_uid != federation_uid {
+        UserFederation::update_uid(
+            &user.id,
+            &provider_id,
+            &federation_uid,
+            provider.uid_normalization,
+        )
+        .await?;
+        User::invalidate_cache(&user.id, &user.email).await?;
+
+        Event::user_federation_remapped(
+            &user.email,
+            &provider_id,
+            &federation.federation_uid,
+            &federation_uid,
+            real_ip_from_req(&req)?,
+        )
+        .send()
+        .await?;
+    }
+
+    Ok(HttpResponse::Ok().json(ProviderFederationResponse {
+        user_id: user.id,
+        provider_id,
+        federation_uid,
+    }))
+}
+
 /// PUT update an upstream auth provider
 ///
 /// **Permissions**
@@ -544,7 +756,7 @@ pub async fn post_provider_link(
 
     // directly redirect to the provider login page
     let (login_cookie, xsrf_token, location) =
-        rauthy_service::oidc::auth_providers::login_start::login_start(payload).await?;
+        rauthy_service::oidc::auth_providers::login_start::login_start(payload, false).await?;
 
     Ok(HttpResponse::Accepted()
         .insert_header((LOCATION, location))
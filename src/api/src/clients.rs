@@ -7,22 +7,25 @@ use actix_web::web::{Json, Query};
 use actix_web::{HttpRequest, HttpResponse, delete, get, post, put, web};
 use actix_web_lab::__reexports::futures_util::StreamExt;
 use rauthy_api_types::clients::{
-    ClientResponse, ClientSecretRequest, ClientSecretResponse, DynamicClientRequest,
-    DynamicClientResponse, NewClientRequest, UpdateClientRequest,
+    ClientResponse, ClientSecretRequest, ClientSecretResponse, ClientSecretSetRequest,
+    DynamicClientRequest, DynamicClientResponse, NewClientRequest, TokenPreviewRequest,
+    TokenPreviewResponse, UpdateClientRequest,
 };
 use rauthy_api_types::forward_auth::{ForwardAuthCallbackParams, ForwardAuthParams};
-use rauthy_api_types::generic::LogoParams;
+use rauthy_api_types::generic::{CursorParams, LogoParams, TokensRevokedResponse};
 use rauthy_common::utils::real_ip_from_req;
 use rauthy_data::entity::api_keys::{AccessGroup, AccessRights};
 use rauthy_data::entity::clients::Client;
 use rauthy_data::entity::clients_dyn::ClientDyn;
 use rauthy_data::entity::clients_scim::ClientScim;
+use rauthy_data::entity::continuation_token::Cursor;
 use rauthy_data::entity::failed_backchannel_logout::FailedBackchannelLogout;
 use rauthy_data::entity::logos::{Logo, LogoType};
 use rauthy_data::entity::user_login_states::UserLoginState;
 use rauthy_data::rauthy_config::RauthyConfig;
 use rauthy_error::{ErrorResponse, ErrorResponseType};
 use rauthy_service::oidc::{helpers, logout};
+use rauthy_service::token_set::TokenIssuer;
 use rauthy_service::{client, forward_auth};
 use tracing::{debug, error, warn};
 use validator::Validate;
@@ -32,14 +35,20 @@ use validator::Validate;
 /// This endpoint will NOT include any `SCIM` configurations. These will only be included on direct
 /// `GET /clients/{id}` requests.
 ///
+/// If `limit` or `cursor` are given, the clients will be paginated ordered by their `id` and the
+/// response will be an HTTP 206. The `x-next-cursor` header contains the opaque cursor for the
+/// next page and will be missing on the last one.
+///
 /// **Permissions**
 /// - rauthy_admin
 #[utoipa::path(
     get,
     path = "/clients",
     tag = "clients",
+    params(CursorParams),
     responses(
         (status = 200, description = "Ok", body = [ClientResponse]),
+        (status = 206, description = "PartialContent", body = [ClientResponse]),
         (status = 400, description = "BadRequest"),
         (status = 401, description = "Unauthorized"),
         (status = 403, description = "Forbidden"),
@@ -48,8 +57,32 @@ use validator::Validate;
 )]
 #[tracing::instrument(skip_all)]
 #[get("/clients")]
-pub async fn get_clients(principal: ReqPrincipal) -> Result<HttpResponse, ErrorResponse> {
+pub async fn get_clients(
+    principal: ReqPrincipal,
+    Query(params): Query<CursorParams>,
+) -> Result<HttpResponse, ErrorResponse> {
     principal.validate_api_key_or_admin_session(AccessGroup::Clients, AccessRights::Read)?;
+    params.validate()?;
+
+    if params.limit.is_some() || params.cursor.is_some() {
+        let limit = params.limit.unwrap_or(100) as i64;
+        let cursor = match &params.cursor {
+            Some(c) => Some(Cursor::decode(c)?),
+            None => None,
+        };
+
+        let (clients, next) = Client::find_after(cursor, limit).await?;
+        let res = clients
+            .into_iter()
+            .map(|c| c.into_response(None))
+            .collect::<Vec<_>>();
+
+        let mut resp = HttpResponse::PartialContent();
+        if let Some(next) = next {
+            resp.insert_header(next.into_header_pair()?);
+        }
+        return Ok(resp.json(res));
+    }
 
     let clients = Client::find_all().await?;
 
@@ -460,7 +493,7 @@ pub async fn delete_client_logo(
 /// Generates a new client secret
 ///
 /// Generates a new secret for the given client id and sets the client to `confidential` too, if it was
-/// not the case yet.
+/// not the case yet. The secret is only saved hashed and therefore returned this one time only.
 ///
 /// **Permissions**
 /// - rauthy_admin
@@ -495,6 +528,123 @@ pub async fn put_generate_client_secret(
         .map(|r| HttpResponse::Ok().json(r))
 }
 
+/// Sets a custom client secret
+///
+/// Sets the given secret for the client id and sets the client to `confidential` too, if it was
+/// not the case yet. The secret must match the `access.client_secret_min_*` policy. It is only
+/// saved hashed and will never be returned.
+///
+/// **Permissions**
+/// - rauthy_admin
+#[utoipa::path(
+    put,
+    path = "/clients/{id}/secret/custom",
+    tag = "clients",
+    request_body = ClientSecretSetRequest,
+    responses(
+        (status = 200, description = "Ok", body = ClientSecretResponse),
+        (status = 400, description = "BadRequest", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse),
+        (status = 404, description = "NotFound", body = ErrorResponse),
+    ),
+)]
+#[put("/clients/{id}/secret/custom")]
+pub async fn put_custom_client_secret(
+    id: web::Path<String>,
+    principal: ReqPrincipal,
+    Json(payload): Json<ClientSecretSetRequest>,
+) -> Result<HttpResponse, ErrorResponse> {
+    principal.validate_api_key_or_admin_session(AccessGroup::Secrets, AccessRights::Update)?;
+    payload.validate()?;
+
+    client::set_custom_secret(id.into_inner(), payload.cache_current_hours, payload.secret)
+        .await
+        .map(|r| HttpResponse::Ok().json(r))
+}
+
+/// Revokes all tokens for a client
+///
+/// All currently valid access tokens will be revoked, and a new "not valid before" timestamp
+/// will be set for this client, which makes sure that refresh tokens issued before it will be
+/// rejected as well. Token introspection and the userinfo endpoint will reject revoked tokens
+/// immediately.
+///
+/// **Important:** Resource servers that only validate JWTs locally without using introspection
+/// cannot know about this revocation and will accept access tokens until they expire.
+///
+/// **Permissions**
+/// - rauthy_admin
+#[utoipa::path(
+    post,
+    path = "/clients/{id}/tokens/revoke",
+    tag = "clients",
+    responses(
+        (status = 200, description = "Ok", body = TokensRevokedResponse),
+        (status = 400, description = "BadRequest", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse),
+        (status = 404, description = "NotFound", body = ErrorResponse),
+    ),
+)]
+#[post("/clients/{id}/tokens/revoke")]
+pub async fn post_client_tokens_revoke(
+    id: web::Path<String>,
+    principal: ReqPrincipal,
+) -> Result<HttpResponse, ErrorResponse> {
+    principal.validate_api_key_or_admin_session(AccessGroup::Clients, AccessRights::Update)?;
+
+    client::revoke_tokens(id.into_inner())
+        .await
+        .map(|r| HttpResponse::Ok().json(r))
+}
+
+/// Returns the decoded claims of the tokens a user would receive from this client
+///
+/// This is a testing endpoint, which only exists with `dev.token_preview` set, which in turn
+/// refuses to start without `dev.danger_unsafe_test_mode`. The tokens are built and signed like
+/// during a real login, but nothing is persisted and only the claims are returned.
+///
+/// **Permissions**
+/// - rauthy_admin
+#[utoipa::path(
+    post,
+    path = "/clients/{id}/token_preview",
+    tag = "clients",
+    request_body = TokenPreviewRequest,
+    responses(
+        (status = 200, description = "Ok", body = TokenPreviewResponse),
+        (status = 400, description = "BadRequest", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse),
+        (status = 404, description = "NotFound", body = ErrorResponse),
+    ),
+)]
+#[post("/clients/{id}/token_preview")]
+pub async fn post_client_token_preview(
+    id: web::Path<String>,
+    principal: ReqPrincipal,
+    payload: Json<TokenPreviewRequest>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ErrorResponse> {
+    if !RauthyConfig::get().vars.dev.token_preview {
+        return Err(ErrorResponse::new(
+            ErrorResponseType::NotFound,
+            "Token preview is disabled",
+        ));
+    }
+    principal.validate_api_key_or_admin_session(AccessGroup::Clients, AccessRights::Read)?;
+    payload.validate()?;
+
+    client::token_preview(
+        id.into_inner(),
+        payload.into_inner(),
+        TokenIssuer::from_req(&req),
+    )
+    .await
+    .map(|r| HttpResponse::Ok().json(r))
+}
+
 /// Deletes an OIDC client
 ///
 /// **Permissions**
@@ -6,7 +6,7 @@ use chrono::Utc;
 use rauthy_api_types::events::{EventResponse, EventsListenParams, EventsRequest};
 use rauthy_common::utils::real_ip_from_req;
 use rauthy_data::entity::api_keys::{AccessGroup, AccessRights};
-use rauthy_data::events::event::Event;
+use rauthy_data::events::event::{Event, LoginFailureReason};
 use rauthy_data::events::listener::EventRouterMsg;
 use rauthy_data::rauthy_config::RauthyConfig;
 use rauthy_error::{ErrorResponse, ErrorResponseType};
@@ -132,11 +132,16 @@ pub async fn post_event_test(
         Event::backchannel_logout_failed("dummy_client", "dummy_user", 3)
             .send()
             .await?;
-        Event::invalid_login(1, ip.to_string()).send().await?;
+        Event::invalid_login(1, ip.to_string(), LoginFailureReason::WrongPassword)
+            .send()
+            .await?;
         Event::brute_force(ip.to_string()).send().await?;
         Event::force_logout("dummy@example.com".to_string())
             .send()
             .await?;
+        Event::introspection_denied("dummy_client", ip)
+            .send()
+            .await?;
         Event::ip_blacklisted(Utc::now(), ip.to_string())
             .send()
             .await?;
@@ -162,10 +167,37 @@ pub async fn post_event_test(
         Event::rauthy_healthy().send().await?;
         Event::rauthy_unhealthy_cache().send().await?;
         Event::rauthy_unhealthy_db().send().await?;
+        Event::registration_rate_limit(ip, Utc::now().timestamp())
+            .send()
+            .await?;
         Event::scim_task_failed("dummy_client", &ScimAction::GroupsSync, 3)
             .send()
             .await?;
         Event::secrets_migrated(ip).send().await?;
+        Event::tokens_revoked("client dummy_client".to_string(), 13)
+            .send()
+            .await?;
+        Event::tokens_invalidated(ip, Utc::now().timestamp())
+            .send()
+            .await?;
+        Event::user_data_exported("admin@localhost", ip)
+            .send()
+            .await?;
+        Event::user_federation_remapped("admin@localhost", "provider123", "old123", "new123", ip)
+            .send()
+            .await?;
+        Event::user_onboarded("admin@localhost", "password", Some(ip))
+            .send()
+            .await?;
+        Event::cache_flushed("app", "admin@localhost", ip)
+            .send()
+            .await?;
+        Event::user_disabled("user@localhost", "admin@localhost", ip)
+            .send()
+            .await?;
+        Event::user_enabled("user@localhost", "admin@localhost", ip)
+            .send()
+            .await?;
         Event::suspicious_request(
             "/.git/config",
             "123.123.123.123".parse().unwrap(),
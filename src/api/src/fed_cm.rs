@@ -18,7 +18,9 @@ use rauthy_data::entity::sessions::Session;
 use rauthy_data::entity::users::User;
 use rauthy_data::rauthy_config::RauthyConfig;
 use rauthy_error::{ErrorResponse, ErrorResponseType};
-use rauthy_service::token_set::{AuthCodeFlow, AuthTime, DeviceCodeFlow, TokenNonce, TokenSet};
+use rauthy_service::token_set::{
+    AuthCodeFlow, AuthTime, DeviceCodeFlow, TokenIssuer, TokenNonce, TokenSet,
+};
 use tracing::{debug, error, warn};
 use validator::Validate;
 
@@ -266,6 +268,8 @@ pub async fn post_fed_cm_token(
         ));
     }
 
+    client.validate_nonce(payload.nonce.as_deref())?;
+
     // TODO what about confidential clients? Should we maybe return an auth_code?
     //  impl a new `FedCM` flow for client's and reject if not true?
 
@@ -296,6 +300,8 @@ pub async fn post_fed_cm_token(
         None,
         AuthCodeFlow::No,
         DeviceCodeFlow::No,
+        TokenIssuer::from_req(&req),
+        None,
     )
     .await?;
 
@@ -2,13 +2,14 @@ use crate::ReqPrincipal;
 use actix_web::http::header;
 use actix_web::http::header::{CACHE_CONTROL, CONTENT_TYPE, HeaderValue};
 use actix_web::web::{Json, Query};
-use actix_web::{HttpRequest, HttpResponse, Responder, get, post, put, web};
+use actix_web::{HttpRequest, HttpResponse, Responder, delete, get, post, put, web};
 use chrono::Utc;
 use cryptr::EncKeys;
 use rauthy_api_types::generic::{
-    AppVersionResponse, Argon2ParamsResponse, EncKeyMigrateRequest, EncKeysResponse,
-    HealthResponse, I18nConfigResponse, LoginTimeResponse, PasswordHashTimesRequest,
-    PasswordPolicyRequest, PasswordPolicyResponse, SearchParams, SearchParamsType,
+    AppVersionResponse, Argon2ParamsResponse, CacheNamespaceResponse, EncKeyMigrateRequest,
+    EncKeysResponse, HealthResponse, I18nConfigResponse, LoginTimeResponse,
+    PasswordHashTimesRequest, PasswordPolicyRequest, PasswordPolicyResponse, SearchParams,
+    SearchParamsType,
 };
 use rauthy_common::compression::compress_br;
 use rauthy_common::constants::{
@@ -36,6 +37,7 @@ use std::fmt::Write;
 use std::ops::Sub;
 use std::str::FromStr;
 use std::sync::LazyLock;
+use strum::IntoEnumIterator;
 use tracing::{error, info, warn};
 use validator::Validate;
 
@@ -112,6 +114,134 @@ pub async fn get_auth_check_admin(principal: ReqPrincipal) -> Result<HttpRespons
     Ok(HttpResponse::Ok().finish())
 }
 
+/// Returns all cache namespaces
+///
+/// Lists all cache namespaces with their amount of entries on this node and the lifetime of new
+/// entries. This is a diagnostics tool and not needed for any normal flows.
+///
+/// **Permissions**
+/// - rauthy_admin
+#[utoipa::path(
+    get,
+    path = "/cache",
+    tag = "generic",
+    responses(
+        (status = 200, description = "Ok", body = [CacheNamespaceResponse]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse),
+    ),
+)]
+#[get("/cache")]
+pub async fn get_cache(principal: ReqPrincipal) -> Result<HttpResponse, ErrorResponse> {
+    principal.validate_api_key_or_admin_session(AccessGroup::Generic, AccessRights::Read)?;
+
+    let mut res = Vec::new();
+    for cache in Cache::iter() {
+        // we only need the keys -> `()` skips the value deserialization
+        let entries = DB::hql().get_snapshot::<_, ()>(cache).await?.len();
+        res.push(CacheNamespaceResponse {
+            name: cache.as_ref().to_string(),
+            entries,
+            ttl: cache.ttl(),
+            flushable: cache.is_flushable(),
+        });
+    }
+
+    Ok(HttpResponse::Ok().json(res))
+}
+
+/// Flushes all cache namespaces
+///
+/// Flushes all cache namespaces, that only contain re-fetchable data, on all nodes. Namespaces
+/// that hold short-lived state like auth codes or rate limits are never touched.
+///
+/// **Permissions**
+/// - rauthy_admin
+#[utoipa::path(
+    delete,
+    path = "/cache",
+    tag = "generic",
+    responses(
+        (status = 200, description = "Ok"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse),
+    ),
+)]
+#[delete("/cache")]
+pub async fn delete_cache(
+    principal: ReqPrincipal,
+    req: HttpRequest,
+) -> Result<HttpResponse, ErrorResponse> {
+    principal.validate_api_key_or_admin_session(AccessGroup::Generic, AccessRights::Delete)?;
+
+    for cache in Cache::iter().filter(Cache::is_flushable) {
+        DB::hql().clear_cache(cache).await?;
+    }
+
+    let actor = principal.actor().await?;
+    warn!("All flushable caches have been flushed by {actor}");
+    Event::cache_flushed("all", &actor, real_ip_from_req(&req)?)
+        .send()
+        .await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Flushes a single cache namespace
+///
+/// Flushes the given cache namespace on all nodes. Only namespaces that contain re-fetchable
+/// data can be flushed.
+///
+/// **Permissions**
+/// - rauthy_admin
+#[utoipa::path(
+    delete,
+    path = "/cache/{namespace}",
+    tag = "generic",
+    responses(
+        (status = 200, description = "Ok"),
+        (status = 400, description = "BadRequest", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse),
+        (status = 404, description = "NotFound", body = ErrorResponse),
+    ),
+)]
+#[delete("/cache/{namespace}")]
+pub async fn delete_cache_namespace(
+    path: web::Path<String>,
+    principal: ReqPrincipal,
+    req: HttpRequest,
+) -> Result<HttpResponse, ErrorResponse> {
+    principal.validate_api_key_or_admin_session(AccessGroup::Generic, AccessRights::Delete)?;
+
+    let namespace = path.into_inner();
+    let cache = Cache::from_str(&namespace).map_err(|_| {
+        ErrorResponse::new(
+            ErrorResponseType::NotFound,
+            format!("Cache namespace '{namespace}' does not exist"),
+        )
+    })?;
+    if !cache.is_flushable() {
+        return Err(ErrorResponse::new(
+            ErrorResponseType::BadRequest,
+            format!(
+                "Cache namespace '{namespace}' holds state which cannot be re-fetched and must \
+                not be flushed"
+            ),
+        ));
+    }
+
+    DB::hql().clear_cache(cache).await?;
+
+    let actor = principal.actor().await?;
+    warn!("Cache '{namespace}' has been flushed by {actor}");
+    Event::cache_flushed(&namespace, &actor, real_ip_from_req(&req)?)
+        .send()
+        .await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
 /// Returns the existing encryption key ID's
 ///
 /// Only the Key ID's are returned and never the key itself.
@@ -5,6 +5,7 @@
 use actix_web::http::StatusCode;
 use actix_web::http::header::{
     ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_METHODS, HeaderMap, HeaderValue,
+    LOCATION,
 };
 use actix_web::{HttpRequest, HttpResponse, HttpResponseBuilder, web};
 use rauthy_api_types::tos::ToSAwaitLoginResponse;
@@ -196,6 +197,11 @@ pub async fn map_auth_step(
         AuthStep::ProviderLink => Ok(HttpResponse::NoContent()
             .insert_header(fed_cm_header)
             .finish()),
+
+        // The user is not logged in after a recovery, but needs to set up new credentials first.
+        AuthStep::ProviderRecovery(location) => Ok(HttpResponse::Accepted()
+            .insert_header((LOCATION, location))
+            .finish()),
     }
 }
 
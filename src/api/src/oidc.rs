@@ -6,13 +6,17 @@ use actix_web::http::header::{
 };
 use actix_web::http::{StatusCode, header};
 use actix_web::web::{Form, Json, Query};
-use actix_web::{HttpRequest, HttpResponse, HttpResponseBuilder, ResponseError, get, post, web};
+use actix_web::{
+    HttpMessage, HttpRequest, HttpResponse, HttpResponseBuilder, ResponseError, get, post, web,
+};
 use chrono::Utc;
+use futures::StreamExt;
 use rauthy_api_types::oidc::{
     AuthRequest, CertsParams, DeviceAcceptedRequest, DeviceCodeResponse, DeviceGrantRequest,
     DeviceVerifyRequest, DeviceVerifyResponse, JWKSCerts, JWKSPublicKeyCerts, LoginRefreshRequest,
-    LoginRequest, LogoutRequest, OAuth2ErrorResponse, OAuth2ErrorTypeResponse, SessionInfoResponse,
-    TokenInfo, TokenRequest, TokenRevocationRequest, TokenValidationRequest,
+    LoginRequest, LogoutRequest, OAuth2ErrorResponse, OAuth2ErrorTypeResponse,
+    RedirectUriValidationRequest, RedirectUriValidationResponse, SessionInfoResponse, TokenInfo,
+    TokenRequest, TokenRevocationRequest, TokenValidationRequest,
 };
 use rauthy_api_types::sessions::SessionState;
 use rauthy_api_types::users::{Userinfo, WebauthnLoginResponse};
@@ -21,8 +25,9 @@ use rauthy_common::constants::{
     APPLICATION_JSON, COOKIE_MFA, GRANT_TYPE_DEVICE_CODE, HEADER_HTML, HEADER_RETRY_NOT_BEFORE,
     PROVIDER_ATPROTO,
 };
-use rauthy_common::utils::real_ip_from_req;
+use rauthy_common::utils::{etag_from_body, etag_matches, real_ip_from_req};
 use rauthy_data::api_cookie::ApiCookie;
+use rauthy_data::captcha::Captcha;
 use rauthy_data::entity::api_keys::{AccessGroup, AccessRights};
 use rauthy_data::entity::auth_providers::{
     AuthProvider, AuthProviderTemplate, NewFederatedUserCreated,
@@ -31,7 +36,9 @@ use rauthy_data::entity::browser_id::{BrowserId, BrowserIdSetNew};
 use rauthy_data::entity::clients::Client;
 use rauthy_data::entity::devices::DeviceAuthCode;
 use rauthy_data::entity::fed_cm::FedCMLoginStatus;
-use rauthy_data::entity::ip_rate_limit::DeviceIpRateLimit;
+use rauthy_data::entity::ip_rate_limit::{
+    DeviceIpRateLimit, RedirectUriIpRateLimit, TokenRateLimit,
+};
 use rauthy_data::entity::jwk::{JWKS, JWKSPublicKey, JwkKeyPair, JwkKeyPairType};
 use rauthy_data::entity::logos::{Logo, LogoType};
 use rauthy_data::entity::pow::PowEntity;
@@ -40,21 +47,25 @@ use rauthy_data::entity::theme::ThemeCssFull;
 use rauthy_data::entity::users::User;
 use rauthy_data::entity::webauthn::WebauthnCookie;
 use rauthy_data::entity::well_known::WellKnown;
+use rauthy_data::events::event::LoginFailureReason;
+use rauthy_data::html::HtmlCached;
 use rauthy_data::html::templates::{
-    AuthorizeHtml, CallbackHtml, Error1Html, ErrorHtml, FrontendAction, HtmlTemplate,
+    AuthorizeHtml, CallbackHtml, Error1Html, ErrorHtml, FrontendAction, HtmlTemplate, TplCaptcha,
+    TplLoginHint,
 };
+use rauthy_data::ip_reputation::{IpReputation, IpReputationDecision};
 use rauthy_data::language::Language;
 use rauthy_data::rauthy_config::RauthyConfig;
 use rauthy_error::{ErrorResponse, ErrorResponseType};
 use rauthy_service::oidc::{authorize, logout, token_info, token_revocation, userinfo, validation};
-use rauthy_service::token_set::TokenSet;
+use rauthy_service::token_set::{TokenIssuer, TokenSet};
 use rauthy_service::{login_delay, oidc};
 use spow::pow::Pow;
 use std::borrow::Cow;
 use std::ops::Add;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::{error, info, warn};
-use validator::Validate;
+use validator::{Validate, ValidateEmail};
 
 /// OIDC Authorization HTML
 ///
@@ -81,12 +92,13 @@ pub async fn get_authorize(
     params.validate()?;
 
     let principal = principal.into_inner();
-    let lang = Language::try_from(&req).unwrap_or_default();
+    let lang = Language::from_req_ui_locales(&req, params.ui_locales.as_deref());
 
     let (client, origin_header) = match validation::validate_auth_req_param(
         &req,
         &params.client_id,
         &params.redirect_uri,
+        &params.response_type,
         &params.code_challenge,
         &params.code_challenge_method,
     )
@@ -107,6 +119,17 @@ pub async fn get_authorize(
     };
     let theme_ts = ThemeCssFull::find_theme_ts(client.id.clone()).await?;
 
+    if params.state.is_none() && RauthyConfig::get().vars.access.auth_state_required {
+        let status = StatusCode::BAD_REQUEST;
+        let body = Error1Html::build(&lang, theme_ts, status, "'state' is missing");
+        return Ok(ErrorHtml::response(body, status));
+    }
+    if let Err(err) = client.validate_nonce(params.nonce.as_deref()) {
+        let status = err.status_code();
+        let body = Error1Html::build(&lang, theme_ts, status, err.message);
+        return Ok(ErrorHtml::response(body, status));
+    }
+
     // check prompt and max_age to possibly force a new session
     let mut force_new_session = if params
         .prompt
@@ -159,7 +182,7 @@ pub async fn get_authorize(
     let auth_providers_json = AuthProviderTemplate::get_all_json_template().await?;
     let logo_updated = Logo::find_updated(&client.id, &LogoType::Client).await?;
 
-    let mut templates = Vec::with_capacity(8);
+    let mut templates = Vec::with_capacity(10);
     templates.push(HtmlTemplate::AuthProviders(auth_providers_json));
     templates.push(HtmlTemplate::ClientName(client.name.unwrap_or_default()));
     templates.push(HtmlTemplate::ClientUrl(
@@ -169,14 +192,32 @@ pub async fn get_authorize(
     templates.push(HtmlTemplate::IsRegOpen(
         RauthyConfig::get().vars.user_registration.enable,
     ));
+    templates.push(HtmlTemplate::ProviderRecovery(
+        RauthyConfig::get().vars.mfa.provider_recovery,
+    ));
     if RauthyConfig::get().vars.atproto.enable {
         let provider_atproto = AuthProvider::find_by_iss(PROVIDER_ATPROTO.to_string()).await?;
         templates.push(HtmlTemplate::AtprotoId(provider_atproto.id));
     }
+    // `display` is only a hint as well -> `page` is the default and anything unknown is ignored
+    if let Some(tpl) = TplCaptcha::from_config() {
+        templates.push(HtmlTemplate::Captcha(tpl));
+    }
+    match params.display.as_deref() {
+        Some("popup") => templates.push(HtmlTemplate::Display("popup")),
+        Some("touch") => templates.push(HtmlTemplate::Display("touch")),
+        Some("wap") => templates.push(HtmlTemplate::Display("wap")),
+        _ => {}
+    }
 
     // if the user is still authenticated and everything is valid -> immediate refresh
     if !force_new_session && principal.validate_session_auth().is_ok() {
         let csrf = principal.get_session_csrf_token()?;
+        if let Some(state) = &params.state
+            && let Some(session) = &principal.session
+        {
+            session.bind_auth_state(state).await?;
+        }
 
         templates.push(HtmlTemplate::CsrfToken(csrf.to_string()));
         templates.push(HtmlTemplate::LoginAction(FrontendAction::Refresh));
@@ -207,10 +248,26 @@ pub async fn get_authorize(
             let body = Error1Html::build(&lang, theme_ts, status, err.message);
             return Ok(ErrorHtml::response(body, status));
         }
+        if let Some(state) = &params.state {
+            session.bind_auth_state(state).await?;
+        }
 
         templates.push(HtmlTemplate::CsrfToken(session.csrf_token.clone()));
         templates.push(HtmlTemplate::LoginAction(action));
 
+        // The `login_hint` is optional and only a hint -> simply ignore invalid values.
+        if let Some(hint) = params.login_hint
+            && hint.validate_email()
+        {
+            let email = hint.to_lowercase();
+            let provider = AuthProvider::find_by_email_domain(&email).await?;
+            templates.push(HtmlTemplate::LoginHint(TplLoginHint {
+                email,
+                auto_redirect: provider.as_ref().is_some_and(|p| p.auto_redirect),
+                provider_id: provider.map(|p| p.id),
+            }));
+        }
+
         let body = AuthorizeHtml::build(&lang, &client.id, theme_ts, &templates);
         build_authorize_resp(
             accept_encoding,
@@ -289,6 +346,7 @@ fn build_authorize_resp(
         (status = 202, description = "Correct credentials and no MFA Login required, adds Location header"),
         (status = 400, description = "Missing / bad input data", body = ErrorResponse),
         (status = 401, description = "Bad input or CSRF Token error", body = ErrorResponse),
+        (status = 403, description = "Login blocked because of the IP reputation", body = ErrorResponse),
     ),
 )]
 #[post("/oidc/authorize")]
@@ -319,9 +377,28 @@ pub async fn post_authorize_handle(
     let challenge = Pow::validate(&payload.pow)?;
     PowEntity::check_prevent_reuse(challenge.to_string()).await?;
 
+    let ip = real_ip_from_req(&req)?;
+    let reputation = IpReputation::check(ip).await?;
+    if reputation == IpReputationDecision::Block {
+        return Err(ErrorResponse::new(
+            ErrorResponseType::Forbidden,
+            "Login not allowed from this IP",
+        ));
+    }
+    Captcha::verify_login(
+        payload.captcha.as_deref(),
+        ip,
+        reputation == IpReputationDecision::StepUp,
+    )
+    .await?;
+
     let mut has_password_been_hashed = false;
     let mut add_login_delay = true;
     let mut user_needs_mfa = false;
+    let mut failure_reason = LoginFailureReason::Other;
+    let email = payload.email.clone();
+    let redirect_uri = payload.redirect_uri.clone();
+    let state = payload.state.clone();
 
     let res = match authorize::post_authorize(
         &req,
@@ -346,6 +423,12 @@ pub async fn post_authorize_handle(
                 return Err(err);
             }
 
+            // The credentials were correct, but the client or user is not allowed to get all
+            // requested scopes with `strict_scopes` -> regular OAuth error for the client.
+            if let ErrorResponseType::InvalidScope = err.error {
+                return Ok(invalid_scope_redirect(&redirect_uri, state.as_deref()));
+            }
+
             if let ErrorResponseType::PasswordRefresh = err.error {
                 info!("User password has expired");
                 return Err(err);
@@ -358,6 +441,7 @@ pub async fn post_authorize_handle(
                 return Err(err);
             }
 
+            failure_reason = LoginFailureReason::from_login_err(&err);
             let err = Err(ErrorResponse::new(
                 ErrorResponseType::Unauthorized,
                 "Invalid user credentials",
@@ -369,8 +453,11 @@ pub async fn post_authorize_handle(
         }
     };
 
-    let ip = real_ip_from_req(&req)?;
-    login_delay::handle_login_delay(ip, start, res, has_password_been_hashed).await
+    let res =
+        login_delay::handle_login_delay(ip, start, res, has_password_been_hashed, failure_reason)
+            .await;
+    login_delay::notify_lockout(&req, ip, email, &res);
+    res
 }
 
 /// Immediate login refresh with valid session
@@ -404,14 +491,32 @@ pub async fn post_authorize_refresh(
         &req,
         &payload.client_id,
         &payload.redirect_uri,
+        // a refresh can only ever happen for an already started `authorization_code` flow
+        "code",
         &payload.code_challenge,
         &payload.code_challenge_method,
     )
     .await?;
 
-    let auth_step =
-        authorize::post_authorize_refresh(session, client, header_origin, payload).await?;
-    map_auth_step(auth_step, &req, NewFederatedUserCreated::No).await
+    let redirect_uri = payload.redirect_uri.clone();
+    let state = payload.state.clone();
+    match authorize::post_authorize_refresh(session, client, header_origin, payload).await {
+        Ok(auth_step) => map_auth_step(auth_step, &req, NewFederatedUserCreated::No).await,
+        Err(err) if err.error == ErrorResponseType::InvalidScope => {
+            Ok(invalid_scope_redirect(&redirect_uri, state.as_deref()))
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// The login UI follows the `Location` from a `202` just like for a successful login.
+fn invalid_scope_redirect(redirect_uri: &str, state: Option<&str>) -> HttpResponse {
+    HttpResponse::Accepted()
+        .insert_header((
+            header::LOCATION,
+            authorize::build_error_location(redirect_uri, state, "invalid_scope"),
+        ))
+        .finish()
 }
 
 #[get("/oidc/callback")]
@@ -433,23 +538,54 @@ pub async fn get_callback_html(
     get,
     path = "/oidc/certs",
     tag = "oidc",
-    responses((status = 200, description = "Ok", body = JWKSCerts)),
+    responses(
+        (status = 200, description = "Ok", body = JWKSCerts),
+        (status = 304, description = "NotModified, if the `If-None-Match` header matches"),
+    ),
 )]
 #[get("/oidc/certs")]
-pub async fn get_certs(params: Query<CertsParams>) -> Result<HttpResponse, ErrorResponse> {
+pub async fn get_certs(
+    req: HttpRequest,
+    params: Query<CertsParams>,
+) -> Result<HttpResponse, ErrorResponse> {
     let mut jwks = JWKS::find_pk().await?;
 
     if params.skip_okp == Some(true) {
         jwks.keys.retain(|k| k.kty != JwkKeyPairType::OKP);
     }
 
-    let res = JWKSCerts::from(jwks);
-    Ok(HttpResponse::Ok()
-        .insert_header((
-            header::ACCESS_CONTROL_ALLOW_ORIGIN,
-            HeaderValue::from_static("*"),
-        ))
-        .json(res))
+    // The ETag is built from the final body, so it changes immediately with any key rotation.
+    let body = serde_json::to_vec(&JWKSCerts::from(jwks))?;
+    let etag = etag_from_body(&body);
+    let cache_control = format!(
+        "public, max-age={}, must-revalidate",
+        RauthyConfig::get().vars.lifetimes.jwks_max_age
+    );
+
+    let not_modified = req
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| etag_matches(v, &etag));
+    let mut resp = if not_modified {
+        HttpResponse::NotModified()
+    } else {
+        HttpResponse::Ok()
+    };
+    resp.insert_header((
+        header::ACCESS_CONTROL_ALLOW_ORIGIN,
+        HeaderValue::from_static("*"),
+    ))
+    .insert_header((header::ETAG, etag))
+    .insert_header((header::CACHE_CONTROL, cache_control));
+
+    if not_modified {
+        Ok(resp.finish())
+    } else {
+        Ok(resp
+            .insert_header((CONTENT_TYPE, APPLICATION_JSON))
+            .body(body))
+    }
 }
 
 /// Single JWK by kid
@@ -712,9 +848,12 @@ pub async fn get_logout(
                 }),
         )
     } else {
-        Ok(HttpResponse::build(StatusCode::from_u16(302).unwrap())
-            .insert_header(("location", "/auth/v1/"))
-            .finish())
+        // The default page after a logout without a valid `post_logout_redirect_uri`.
+        // There is no session anymore, so there is nothing to confirm.
+        let theme_ts = ThemeCssFull::find_theme_ts_rauthy().await?;
+        HtmlCached::Logout(String::default())
+            .handle(req, theme_ts, false)
+            .await
     }
 }
 
@@ -743,6 +882,54 @@ pub async fn post_logout(
     logout::post_logout_handle(req, payload, session).await
 }
 
+/// Check a `redirect_uri`
+///
+/// Checks if the given `redirect_uri` would be accepted for the client without starting a flow,
+/// which helps with debugging `redirect_uri` mismatches during integration. Only returns if the
+/// URI is valid and how it matched, but never any of the registered URIs.
+///
+/// This endpoint is rate-limited by `access.redirect_uri_validation_rate_limit` per minute and IP.
+#[utoipa::path(
+    get,
+    path = "/oidc/redirect_uri",
+    tag = "oidc",
+    params(RedirectUriValidationRequest),
+    responses(
+        (status = 200, description = "Ok", body = RedirectUriValidationResponse),
+        (status = 400, description = "BadRequest", body = ErrorResponse),
+        (status = 404, description = "NotFound", body = ErrorResponse),
+        (status = 429, description = "TooManyRequests", body = ErrorResponse),
+    ),
+)]
+#[get("/oidc/redirect_uri")]
+pub async fn get_redirect_uri_validation(
+    req: HttpRequest,
+    Query(params): Query<RedirectUriValidationRequest>,
+) -> Result<HttpResponse, ErrorResponse> {
+    if RauthyConfig::get()
+        .vars
+        .access
+        .redirect_uri_validation_rate_limit
+        == 0
+    {
+        return Err(ErrorResponse::new(
+            ErrorResponseType::NotFound,
+            "The redirect_uri validation endpoint is disabled",
+        ));
+    }
+    params.validate()?;
+
+    RedirectUriIpRateLimit::check_insert(real_ip_from_req(&req)?).await?;
+
+    let client = Client::find_maybe_ephemeral(params.client_id).await?;
+    let mode = client.match_redirect_uri(&params.redirect_uri);
+
+    Ok(HttpResponse::Ok().json(RedirectUriValidationResponse {
+        valid: mode.is_some(),
+        mode,
+    }))
+}
+
 /// Rotate JWKs
 ///
 /// Rotates all currently exiting JWKs (Json Web Keys) for signing new tokens. This is a manual
@@ -935,7 +1122,11 @@ pub async fn get_session_xsrf(principal: ReqPrincipal) -> Result<HttpResponse, E
 /// The accepted options and values depend on the clients config.<br>
 /// Username enumeration will be prevented and no matter if the request was successful or not, it will
 /// always take about the same amount of time, except for the first 5-7 (successful) request after
-/// a fresh restart of the application.
+/// a fresh restart of the application.<br>
+/// A `grant_type` which is not enabled for the client will be rejected with an `unauthorized_client`
+/// error, which names the allowed grants.<br>
+/// Requests may be rate limited per IP and `client_id` with the `token_rate_limit_*` config, and
+/// bodies above `token_max_body_bytes` will be rejected before being read.
 #[utoipa::path(
     post,
     path = "/oidc/token",
@@ -946,31 +1137,61 @@ pub async fn get_session_xsrf(principal: ReqPrincipal) -> Result<HttpResponse, E
         (status = 400, description = "BadRequest", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
         (status = 404, description = "NotFound", body = ErrorResponse),
+        (status = 413, description = "PayloadTooLarge", body = ErrorResponse),
+        (status = 429, description = "TooManyRequests", body = ErrorResponse),
     ),
 )]
 #[post("/oidc/token")]
-#[tracing::instrument(level = "debug", skip_all, fields(grant_type = payload.grant_type))]
+#[tracing::instrument(level = "debug", skip_all, fields(grant_type))]
 pub async fn post_token(
     req: HttpRequest,
     browser_id: BrowserId,
-    Form(payload): Form<TokenRequest>,
+    body: web::Payload,
 ) -> Result<HttpResponse, ErrorResponse> {
+    let payload = token_request_from_body(&req, body).await?;
+    tracing::Span::current().record("grant_type", &payload.grant_type);
     payload.validate()?;
 
     let ip = real_ip_from_req(&req)?;
+    let client_id = payload
+        .try_get_client_id_secret(&req)
+        .ok()
+        .map(|(id, _)| id)
+        .filter(|id| !id.is_empty());
+    TokenRateLimit::check_insert_ip(ip)?;
+    if let Some(client_id) = &client_id {
+        TokenRateLimit::check_client(ip, client_id)?;
+    }
 
     if payload.grant_type == GRANT_TYPE_DEVICE_CODE {
         // the `urn:ietf:params:oauth:grant-type:device_code` needs
         // a fully customized handling here with customized error response
         // to meet the oauth rfc
-        return Ok(oidc::grant_type_device_code(ip, payload).await);
+        let resp = oidc::grant_type_device_code(ip, payload, TokenIssuer::from_req(&req)).await;
+        if resp.status().is_success()
+            && let Some(client_id) = &client_id
+        {
+            TokenRateLimit::insert_client(client_id);
+        }
+        return Ok(resp);
     }
 
     let start = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
     let has_password_been_hashed = payload.grant_type == "password";
+    let username = if has_password_been_hashed {
+        payload.username.clone()
+    } else {
+        None
+    };
 
-    let res = match oidc::get_token_set(payload, browser_id, req).await {
+    let mut failure_reason = LoginFailureReason::Other;
+    let res = match oidc::get_token_set(payload, browser_id, req.clone()).await {
         Ok((token_set, headers)) => {
+            // the client has been authenticated successfully at this point
+            if let Some(client_id) = &client_id {
+                TokenRateLimit::insert_client(client_id);
+            }
+
             let mut builder = HttpResponseBuilder::new(StatusCode::OK);
             for h in headers {
                 builder.insert_header(h);
@@ -980,15 +1201,77 @@ pub async fn post_token(
         }
         Err(err) => {
             error!("{}", err.message);
+            // these do not depend on any user credentials and must be RFC 6749 error responses
+            let oauth_error = match err.error {
+                ErrorResponseType::InvalidGrant => Some(OAuth2ErrorTypeResponse::InvalidGrant),
+                ErrorResponseType::InvalidScope => Some(OAuth2ErrorTypeResponse::InvalidScope),
+                ErrorResponseType::UnauthorizedClient => {
+                    Some(OAuth2ErrorTypeResponse::UnauthorizedClient)
+                }
+                _ => None,
+            };
+            if let Some(error) = oauth_error {
+                return Ok(HttpResponse::BadRequest().json(OAuth2ErrorResponse {
+                    error,
+                    error_description: Some(err.message),
+                }));
+            }
             if !has_password_been_hashed {
                 return Err(err);
             }
+            failure_reason = LoginFailureReason::from_login_err(&err);
             // TODO return always the same error here as well, just like during authorize?
             Err(err)
         }
     };
 
-    login_delay::handle_login_delay(ip, start, res, has_password_been_hashed).await
+    let res =
+        login_delay::handle_login_delay(ip, start, res, has_password_been_hashed, failure_reason)
+            .await;
+    if let Some(username) = username {
+        login_delay::notify_lockout(&req, ip, username, &res);
+    }
+    res
+}
+
+/// Reads the `TokenRequest` form from the body, while enforcing the `token_max_body_bytes`.
+/// A request with a bigger `Content-Length` will be rejected without reading the body at all.
+async fn token_request_from_body(
+    req: &HttpRequest,
+    mut body: web::Payload,
+) -> Result<TokenRequest, ErrorResponse> {
+    let limit = RauthyConfig::get().vars.access.token_max_body_bytes as usize;
+    let too_large = || {
+        TokenRateLimit::count_body_too_large();
+        ErrorResponse::new(
+            ErrorResponseType::PayloadTooLarge,
+            format!("Max body size {limit} bytes"),
+        )
+    };
+
+    if let Some(len) = req.headers().get(header::CONTENT_LENGTH)
+        && len.to_str()?.parse::<usize>().unwrap_or(usize::MAX) > limit
+    {
+        return Err(too_large());
+    }
+    if req.content_type() != "application/x-www-form-urlencoded" {
+        return Err(ErrorResponse::new(
+            ErrorResponseType::BadRequest,
+            "Content-Type must be application/x-www-form-urlencoded",
+        ));
+    }
+
+    // the `Content-Length` may be missing with a chunked body
+    let mut bytes = web::BytesMut::new();
+    while let Some(chunk) = body.next().await {
+        let chunk = chunk?;
+        if bytes.len() + chunk.len() > limit {
+            return Err(too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    Ok(serde_urlencoded::from_bytes::<TokenRequest>(&bytes)?)
 }
 
 #[utoipa::path(
@@ -1033,6 +1316,9 @@ pub async fn post_token_revoke(
 /// 1. `Basic` auth with `client_id:client_secret`
 /// 2. `Bearer` JWT token
 ///
+/// The authenticated client must have the `introspect` flag enabled, which marks it as a trusted
+/// resource server. Any other client will receive a `403` with `access_denied`.
+///
 /// If your client application can't provide any, you can disable authorization for this endpoint
 /// by setting `DANGER_DISABLE_INTROSPECT_AUTH=true` in the Rauthy config.
 /// Only do this, if you know what you are doing and have other ways to prevent public access to
@@ -1045,6 +1331,7 @@ pub async fn post_token_revoke(
     responses(
         (status = 200, description = "Ok", body = TokenInfo),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse),
         (status = 404, description = "NotFound", body = ErrorResponse),
     ),
 )]
@@ -1215,8 +1502,8 @@ pub async fn get_forward_auth(req: HttpRequest) -> Result<HttpResponse, ErrorRes
     ),
 )]
 #[get("/.well-known/openid-configuration")]
-pub async fn get_well_known() -> Result<HttpResponse, ErrorResponse> {
-    let wk = WellKnown::json().await?;
+pub async fn get_well_known(req: HttpRequest) -> Result<HttpResponse, ErrorResponse> {
+    let wk = WellKnown::json(RauthyConfig::get().issuer_from_req(&req)).await?;
     Ok(HttpResponse::Ok()
         .insert_header((CONTENT_TYPE, APPLICATION_JSON))
         .insert_header((
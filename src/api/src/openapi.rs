@@ -38,10 +38,14 @@ use utoipa::{OpenApi, openapi};
         auth_providers::post_provider,
         auth_providers::post_provider_lookup,
         auth_providers::post_provider_login,
+        auth_providers::post_provider_recovery,
+        auth_providers::post_provider_discover,
         auth_providers::post_provider_callback,
         auth_providers::post_provider_link,
         auth_providers::delete_provider_link,
         auth_providers::get_providers_minimal,
+        auth_providers::get_provider_federations,
+        auth_providers::put_provider_federation,
         auth_providers::put_provider,
         auth_providers::delete_provider,
         auth_providers::get_provider_delete_safe,
@@ -68,6 +72,9 @@ use utoipa::{OpenApi, openapi};
         clients::post_clients_dyn,
         clients::put_clients,
         clients::put_generate_client_secret,
+        clients::put_custom_client_secret,
+        clients::post_client_tokens_revoke,
+        clients::post_client_token_preview,
         clients::delete_client,
         clients::get_forward_auth_oidc,
         clients::get_forward_auth_callback,
@@ -89,6 +96,9 @@ use utoipa::{OpenApi, openapi};
 
         generic::get_auth_check,
         generic::get_auth_check_admin,
+        generic::get_cache,
+        generic::delete_cache,
+        generic::delete_cache_namespace,
         generic::get_enc_keys,
         generic::post_migrate_enc_key,
         generic::get_login_time,
@@ -145,6 +155,7 @@ use utoipa::{OpenApi, openapi};
         oidc::post_device_auth,
         oidc::get_logout,
         oidc::post_logout,
+        oidc::get_redirect_uri_validation,
         oidc::rotate_jwk,
         oidc::post_session,
         oidc::get_session_info,
@@ -168,6 +179,8 @@ use utoipa::{OpenApi, openapi};
 
         sessions::get_sessions,
         sessions::delete_sessions,
+        sessions::get_tokens_not_before,
+        sessions::post_tokens_not_before,
         sessions::delete_sessions_for_user,
 
         themes::get_theme,
@@ -189,6 +202,7 @@ use utoipa::{OpenApi, openapi};
         users::delete_cust_attr,
         users::get_users_register,
         users::post_users_register,
+        users::get_users_pending,
         users::get_user_by_id,
         users::get_user_attr,
         users::put_user_attr,
@@ -199,6 +213,12 @@ use utoipa::{OpenApi, openapi};
         users::get_user_devices,
         users::put_user_device_name,
         users::delete_user_device,
+        users::get_user_emails,
+        users::post_user_email,
+        users::get_user_email_add_confirm,
+        users::put_user_email_primary,
+        users::delete_user_email,
+        users::post_user_tokens_revoke,
         users::get_user_webid,
         users::get_user_webid_data,
         users::put_user_webid_data,
@@ -216,10 +236,15 @@ use utoipa::{OpenApi, openapi};
         users::put_user_self,
         users::get_user_self_delete_config,
         users::delete_user_self,
+        users::post_user_self_export,
         users::post_user_self_convert_passkey,
         users::get_user_values_config,
         users::put_user_self_preferred_username,
         users::delete_user_by_id,
+        users::post_user_disable,
+        users::post_user_enable,
+        users::post_user_approve,
+        users::post_user_reject,
     ),
     components(
         schemas(
@@ -244,6 +269,7 @@ use utoipa::{OpenApi, openapi};
             AccessRights,
             AddressClaim,
             ApiKeyAccess,
+            AuthProviderClaimPrecedence,
             AuthProviderType,
             AuthProviderTemplate,
             BackupListing,
@@ -255,6 +281,8 @@ use utoipa::{OpenApi, openapi};
             JwkKeyPairAlg,
             JwkKeyPairType,
             Language,
+            SubjectType,
+            UriMatchMode,
 
             rauthy_jwt::claims::JwtTokenType,
 
@@ -310,6 +338,7 @@ use utoipa::{OpenApi, openapi};
             PamUserCreateRequest,
             PamUserUpdateRequest,
             RoleRequest,
+            CursorParams,
             PaginationParams,
             PasswordHashTimesRequest,
             PasswordPolicyRequest,
@@ -319,7 +348,9 @@ use utoipa::{OpenApi, openapi};
             ProviderRequest,
             ProviderLoginRequest,
             ProviderLookupRequest,
+            ProviderDiscoveryRequest,
             ProviderCallbackRequest,
+            RedirectUriValidationRequest,
             RequestResetRequest,
             ScopeRequest,
             SessionState,
@@ -328,6 +359,7 @@ use utoipa::{OpenApi, openapi};
             TokenValidationRequest,
             UpdateClientRequest,
             ClientSecretRequest,
+            ClientSecretSetRequest,
             UpdateUserRequest,
             UpdateUserSelfRequest,
             UserValuesRequest,
@@ -352,6 +384,8 @@ use utoipa::{OpenApi, openapi};
             DeviceCodeResponse,
             DynamicClientResponse,
             ClientSecretResponse,
+            TokenPreviewRequest,
+            TokenPreviewResponse,
             EncKeysResponse,
             HealthResponse,
             I18nConfigResponse,
@@ -361,6 +395,9 @@ use utoipa::{OpenApi, openapi};
             OAuth2ErrorResponse,
             OAuth2ErrorTypeResponse,
             PasswordPolicyResponse,
+            TokensRevokedResponse,
+            TokensNotBeforeResponse,
+            CacheNamespaceResponse,
             MfaModTokenResponse,
             PamGetentResponse,
             PamGroupResponse,
@@ -377,8 +414,13 @@ use utoipa::{OpenApi, openapi};
             PamUserDetailsResponse,
             PreferredUsernameRequest,
             ProviderResponse,
+            ProviderFederationResponse,
+            ProviderFederationUpdateRequest,
             ProviderLinkedUserResponse,
             ProviderLookupResponse,
+            ProviderDiscoveryResponse,
+            RedirectUriMatch,
+            RedirectUriValidationResponse,
             ScopeResponse,
             SessionResponse,
             SessionInfoResponse,
@@ -396,12 +438,21 @@ use utoipa::{OpenApi, openapi};
             UserAttrConfigValueResponse,
             UserAttrValueResponse,
             UserAttrValuesResponse,
+            UserDataExport,
+            UserDataExportClientLogin,
+            UserDataExportFederation,
+            UserDataExportLoginLocation,
+            UserDataExportRequest,
+            UserDataExportSession,
+            UserEmailRequest,
+            UserEmailResponse,
             UserEditableAttrResponse,
             UserEditableAttrsResponse,
             Userinfo,
             UserValuesResponse,
             UserAccountTypeResponse,
             UserResponse,
+            PendingRegistrationResponse,
             WebauthnAuthStartResponse,
             WebauthnLoginFinishResponse,
             WebauthnLoginResponse,
@@ -1,14 +1,16 @@
 use crate::ReqPrincipal;
 use actix_web::web::Query;
-use actix_web::{HttpResponse, delete, get, web};
-use rauthy_api_types::generic::PaginationParams;
+use actix_web::{HttpRequest, HttpResponse, delete, get, post, web};
+use rauthy_api_types::generic::{PaginationParams, TokensNotBeforeResponse};
 use rauthy_api_types::sessions::SessionResponse;
+use rauthy_common::utils::real_ip_from_req;
 use rauthy_data::entity;
 use rauthy_data::entity::api_keys::{AccessGroup, AccessRights};
 use rauthy_data::entity::continuation_token::ContinuationToken;
 use rauthy_data::entity::issued_tokens::IssuedToken;
 use rauthy_data::entity::refresh_tokens::RefreshToken;
 use rauthy_data::entity::sessions::Session;
+use rauthy_data::entity::token_nbf::GlobalTokenNbf;
 use rauthy_data::entity::users::User;
 use rauthy_data::events::event::Event;
 use rauthy_data::rauthy_config::RauthyConfig;
@@ -16,7 +18,7 @@ use rauthy_error::ErrorResponse;
 use rauthy_service::oidc::logout;
 use std::cmp::max;
 use tokio::task;
-use tracing::error;
+use tracing::{error, warn};
 use validator::Validate;
 
 /// Returns all existing sessions
@@ -71,7 +73,7 @@ pub async fn get_sessions(
                 // .insert_header(("x-user-count", user_count))
                 .insert_header(("x-page-count", x_page_count))
                 .insert_header(("x-page-size", page_size as u32))
-                .insert_header(token.into_header_pair())
+                .insert_header(token.into_header_pair()?)
                 .json(users))
         } else {
             Ok(HttpResponse::PartialContent()
@@ -142,6 +144,60 @@ pub async fn delete_sessions(principal: ReqPrincipal) -> Result<HttpResponse, Er
     Ok(HttpResponse::Ok().finish())
 }
 
+/// Returns the current global "not valid before" timestamp for all tokens
+///
+/// Resource servers that validate tokens locally should reject any token with an `iat` before
+/// this value.
+#[utoipa::path(
+    get,
+    path = "/tokens/not_before",
+    tag = "sessions",
+    responses(
+        (status = 200, description = "Ok", body = TokensNotBeforeResponse),
+    ),
+)]
+#[get("/tokens/not_before")]
+pub async fn get_tokens_not_before() -> Result<HttpResponse, ErrorResponse> {
+    let not_before = GlobalTokenNbf::find().await?;
+    Ok(HttpResponse::Ok().json(TokensNotBeforeResponse { not_before }))
+}
+
+/// Sets the global "not valid before" timestamp for all tokens to now
+///
+/// **CAUTION:** This will invalidate every single access, id and refresh token that has been
+/// issued before the current second. Every client will have to fetch new tokens. Existing sessions are not
+/// touched, which makes it possible to re-issue tokens without another login. Use
+/// `DELETE /sessions` in addition, if a full re-authentication is needed.
+///
+/// **Permissions**
+/// - rauthy_admin
+#[utoipa::path(
+    post,
+    path = "/tokens/not_before",
+    tag = "sessions",
+    responses(
+        (status = 200, description = "Ok", body = TokensNotBeforeResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse),
+    ),
+)]
+#[post("/tokens/not_before")]
+pub async fn post_tokens_not_before(
+    req: HttpRequest,
+    principal: ReqPrincipal,
+) -> Result<HttpResponse, ErrorResponse> {
+    principal.validate_api_key_or_admin_session(AccessGroup::Sessions, AccessRights::Delete)?;
+
+    let not_before = GlobalTokenNbf::bump().await?;
+
+    warn!("All tokens issued before {not_before} have been invalidated");
+    Event::tokens_invalidated(real_ip_from_req(&req)?, not_before)
+        .send()
+        .await?;
+
+    Ok(HttpResponse::Ok().json(TokensNotBeforeResponse { not_before }))
+}
+
 /// Invalidates all existing sessions for the given `user_id`.
 ///
 ///**Important:** Since JWT Tokens are stateless, it cannot invalidate already existing tokens.
@@ -1,12 +1,15 @@
 use crate::{ReqPrincipal, content_len_limit};
 use actix_web::http::StatusCode;
-use actix_web::http::header::{ACCEPT, HeaderName, HeaderValue, LOCATION};
-use actix_web::mime::TEXT_HTML;
+use actix_web::http::header::{ACCEPT, CONTENT_DISPOSITION, HeaderName, HeaderValue, LOCATION};
+use actix_web::mime::{APPLICATION_JSON, TEXT_HTML};
 use actix_web::web::{Json, Query};
 use actix_web::{HttpRequest, HttpResponse, ResponseError, delete, get, patch, post, put, web};
+use bytes::Bytes;
 use chrono::Utc;
+use futures::{SinkExt, TryStreamExt};
 use rauthy_api_types::PatchOp;
-use rauthy_api_types::generic::{PaginationParams, PasswordPolicyResponse};
+use rauthy_api_types::events::EventResponse;
+use rauthy_api_types::generic::{PaginationParams, PasswordPolicyResponse, TokensRevokedResponse};
 use rauthy_api_types::oidc::PasswordResetResponse;
 use rauthy_api_types::users::*;
 use rauthy_common::constants::{
@@ -15,6 +18,7 @@ use rauthy_common::constants::{
 };
 use rauthy_common::utils::real_ip_from_req;
 use rauthy_data::api_cookie::ApiCookie;
+use rauthy_data::captcha::Captcha;
 use rauthy_data::entity::api_keys::{AccessGroup, AccessRights};
 use rauthy_data::entity::browser_id::BrowserId;
 use rauthy_data::entity::clients::Client;
@@ -22,17 +26,21 @@ use rauthy_data::entity::clients_scim::ClientScim;
 use rauthy_data::entity::continuation_token::ContinuationToken;
 use rauthy_data::entity::devices::DeviceEntity;
 use rauthy_data::entity::groups::Group;
+use rauthy_data::entity::ip_rate_limit::RegistrationIpRateLimit;
+use rauthy_data::entity::issued_tokens::IssuedToken;
 use rauthy_data::entity::login_locations::LoginLocation;
 use rauthy_data::entity::mfa_mod_token::MfaModToken;
 use rauthy_data::entity::password::PasswordPolicy;
 use rauthy_data::entity::pictures::{PICTURE_STORAGE_TYPE, PictureStorage, UserPicture};
 use rauthy_data::entity::pow::PowEntity;
 use rauthy_data::entity::refresh_tokens::RefreshToken;
+use rauthy_data::entity::refresh_tokens_devices::RefreshTokenDevice;
 use rauthy_data::entity::sessions::Session;
 use rauthy_data::entity::theme::ThemeCssFull;
 use rauthy_data::entity::tos::ToS;
 use rauthy_data::entity::tos_user_accept::ToSUserAccept;
 use rauthy_data::entity::user_attr::{UserAttrConfigEntity, UserAttrValueEntity};
+use rauthy_data::entity::user_emails::UserEmail;
 use rauthy_data::entity::user_revoke::UserRevoke;
 use rauthy_data::entity::users::User;
 use rauthy_data::entity::users_values::UserValues;
@@ -111,7 +119,7 @@ pub async fn get_users(
                 .insert_header(("x-user-count", user_count))
                 .insert_header(("x-page-count", x_page_count))
                 .insert_header(("x-page-size", page_size as u32))
-                .insert_header(token.into_header_pair())
+                .insert_header(token.into_header_pair()?)
                 .json(users))
         } else {
             Ok(HttpResponse::PartialContent()
@@ -151,6 +159,12 @@ pub async fn post_users(
 ) -> Result<HttpResponse, ErrorResponse> {
     principal.validate_api_key_or_admin_session(AccessGroup::Users, AccessRights::Create)?;
     payload.validate()?;
+
+    let reg = &RauthyConfig::get().vars.user_registration;
+    if !reg.domain_restriction_admin_bypass {
+        reg.validate_email_domain(&payload.email)?;
+    }
+
     // We are not using the UserValuesValidator on purpose here.
     // When an admin registers a new user, the user details view will be shown immediately anyway,
     // and an admin may have good reason to not set some values, like e.g. the preferred username.
@@ -416,28 +430,16 @@ pub async fn post_users_register_handle(
     .validate()?;
 
     let reg = &RauthyConfig::get().vars.user_registration;
+    reg.validate_email_domain(&payload.email)?;
 
-    if let Some(restriction) = &reg.domain_restriction {
-        if !payload.email.ends_with(restriction) {
-            return Err(ErrorResponse::new(
-                ErrorResponseType::BadRequest,
-                format!("Domain for the open registration are restricted to '@{restriction}'"),
-            ));
-        }
-    } else if !reg.domain_blacklist.is_empty() {
-        for blacklisted in &reg.domain_blacklist {
-            if payload.email.ends_with(blacklisted) {
-                return Err(ErrorResponse::new(
-                    ErrorResponseType::BadRequest,
-                    "Domain is blacklisted",
-                ));
-            }
-        }
-    }
+    // The rate limiter only does an in-memory cache lookup and therefore comes before the PoW.
+    let ip = real_ip_from_req(&req)?;
+    RegistrationIpRateLimit::check(ip).await?;
 
     // Note: Always keep the PoW validation BEFORE any other expensive checks with DB access.
     let challenge = Pow::validate(&payload.pow)?;
     PowEntity::check_prevent_reuse(challenge.to_string()).await?;
+    Captcha::verify_registration(payload.captcha.as_deref(), ip).await?;
 
     if let Some(redirect_uri) = &payload.redirect_uri
         && !reg.allow_open_redirect
@@ -459,22 +461,29 @@ pub async fn post_users_register_handle(
 
     let lang = Language::try_from(&req).unwrap_or_default();
     let user = User::create_from_reg(payload, lang).await?;
-
-    RauthyConfig::get()
-        .tx_events
-        .send_async(Event::new_user(
-            user.email.clone(),
-            real_ip_from_req(&req)?.to_string(),
-        ))
-        .await
-        .unwrap();
+    RegistrationIpRateLimit::insert(ip).await?;
 
     if let Some(tos) = ToS::find_latest().await? {
-        let ip = real_ip_from_req(&req)?;
         let loc = get_location(&req, ip)?;
         ToSUserAccept::create(user.id.clone(), tos.ts, ip, loc).await?;
     }
 
+    if user.pending_since.is_some() {
+        // The SCIM sync and the `NewUserRegistered` event happen with the approval.
+        Event::registration_pending(&user.email, "registration", Some(ip))
+            .send()
+            .await?;
+        return Ok(HttpResponse::NoContent()
+            .insert_header(HEADER_ALLOW_ALL_ORIGINS)
+            .finish());
+    }
+
+    RauthyConfig::get()
+        .tx_events
+        .send_async(Event::new_user(user.email.clone(), ip.to_string()))
+        .await
+        .unwrap();
+
     task::spawn(async move {
         let email = user.email.clone();
         if let Err(err) = ClientScim::create_update_user(user).await {
@@ -490,6 +499,41 @@ pub async fn post_users_register_handle(
         .finish())
 }
 
+/// Returns all users with a registration, that awaits the approval of an admin
+///
+/// **Permissions**
+/// - rauthy_admin
+#[utoipa::path(
+    get,
+    path = "/users/pending",
+    tag = "users",
+    responses(
+        (status = 200, description = "Ok", body = [PendingRegistrationResponse]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse),
+    ),
+)]
+#[get("/users/pending")]
+pub async fn get_users_pending(principal: ReqPrincipal) -> Result<HttpResponse, ErrorResponse> {
+    principal.validate_api_key_or_admin_session(AccessGroup::Users, AccessRights::Read)?;
+
+    let res = User::find_pending()
+        .await?
+        .into_iter()
+        .map(|user| PendingRegistrationResponse {
+            id: user.id,
+            email: user.email,
+            given_name: (!user.given_name.is_empty()).then_some(user.given_name),
+            family_name: user.family_name,
+            language: user.language.to_string(),
+            federated: user.federation_count > 0,
+            pending_since: user.pending_since.unwrap_or_default(),
+        })
+        .collect::<Vec<_>>();
+
+    Ok(HttpResponse::Ok().json(res))
+}
+
 /// Returns a single user by its *id*
 #[utoipa::path(
     get,
@@ -860,12 +904,15 @@ async fn validate_user_picture_access(
             let claims: JwtCommonClaims = serde_json::from_slice(&buf)?;
             debug!("token claims are ok: {:?}", claims);
             if (claims.typ == JwtTokenType::Bearer || claims.typ == JwtTokenType::Id)
-                && claims.sub == Some(user_id)
+                && claims.sub.is_some()
                 && claims.scope.unwrap_or_default().contains("profile")
             {
                 let client = Client::find(claims.azp.to_string()).await?;
-                let cors = client.get_validated_origin_header(req)?;
-                return Ok(cors);
+                // the `sub` may be a pairwise one for this client
+                if claims.sub == Some(client.subject(user_id).as_ref()) {
+                    let cors = client.get_validated_origin_header(req)?;
+                    return Ok(cors);
+                }
             }
         }
     }
@@ -1013,6 +1060,59 @@ pub async fn delete_user_device(
     Ok(HttpResponse::Ok().finish())
 }
 
+/// Revokes all tokens for a user
+///
+/// Deletes all refresh tokens, including the ones for devices, and revokes all currently valid
+/// access tokens for this user. Existing sessions will not be touched, use
+/// `DELETE /sessions/{user_id}` to force a logout.
+///
+/// **Important:** Resource servers that only validate JWTs locally without using introspection
+/// cannot know about this revocation and will accept access tokens until they expire.
+///
+/// **Permissions**
+/// - rauthy_admin
+#[utoipa::path(
+    post,
+    path = "/users/{id}/tokens/revoke",
+    tag = "users",
+    responses(
+        (status = 200, description = "Ok", body = TokensRevokedResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse),
+        (status = 404, description = "NotFound", body = ErrorResponse),
+    ),
+)]
+#[post("/users/{id}/tokens/revoke")]
+pub async fn post_user_tokens_revoke(
+    path: web::Path<String>,
+    principal: ReqPrincipal,
+) -> Result<HttpResponse, ErrorResponse> {
+    principal.validate_api_key_or_admin_session(AccessGroup::Sessions, AccessRights::Delete)?;
+
+    let user = User::find(path.into_inner()).await?;
+
+    let refresh_tokens = RefreshToken::invalidate_for_user(&user.id).await?
+        + RefreshTokenDevice::invalidate_all_for_user(&user.id).await?;
+    let access_tokens = IssuedToken::revoke_for_user(&user.id, true).await?;
+
+    warn!(
+        "All tokens for user '{}' have been revoked: {access_tokens} access, {refresh_tokens} \
+        refresh tokens",
+        user.email
+    );
+    Event::tokens_revoked(
+        format!("user {}", user.email),
+        access_tokens + refresh_tokens,
+    )
+    .send()
+    .await?;
+
+    Ok(HttpResponse::Ok().json(TokensRevokedResponse {
+        access_tokens,
+        refresh_tokens,
+    }))
+}
+
 /// Endpoint for resetting passwords
 ///
 /// The `id` is the user id and `reset_id` is a random 64 character long string sent via E-Mail for a
@@ -1050,6 +1150,224 @@ pub async fn get_user_email_confirm(
     }
 }
 
+/// Aborts a pending E-Mail change
+///
+/// The `id` is the user id and `abort_id` is a random 64 character long string sent via E-Mail to
+/// the old address for a pre-authenticated request.
+#[utoipa::path(
+    get,
+    path = "/users/{id}/email_change_abort/{abort_id}",
+    tag = "users",
+    responses(
+        (status = 200, description = "Ok"),
+        (status = 400, description = "BadRequest", body = ErrorResponse),
+        (status = 404, description = "NotFound", body = ErrorResponse),
+    ),
+)]
+#[get("/users/{id}/email_change_abort/{abort_id}")]
+pub async fn get_user_email_change_abort(
+    path: web::Path<(String, String)>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let lang = Language::try_from(&req).unwrap_or_default();
+    let (user_id, abort_id) = path.into_inner();
+    match User::abort_email_change(req, user_id, abort_id).await {
+        Ok(html) => HttpResponse::Ok().insert_header(HEADER_HTML).body(html),
+        Err(err) => {
+            let status = err.status_code();
+            let body = Error3Html::build(
+                &lang,
+                ThemeCssFull::find_theme_ts_rauthy()
+                    .await
+                    .unwrap_or_else(|_| Utc::now().timestamp()),
+                status,
+                err.message,
+            );
+            ErrorHtml::response(body, status)
+        }
+    }
+}
+
+/// GET all E-Mail addresses for this user
+///
+/// The primary address is always the first one in the list.
+#[utoipa::path(
+    get,
+    path = "/users/{id}/emails",
+    tag = "users",
+    responses(
+        (status = 200, description = "Ok", body = [UserEmailResponse]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse),
+    ),
+)]
+#[get("/users/{id}/emails")]
+pub async fn get_user_emails(
+    path: web::Path<String>,
+    principal: ReqPrincipal,
+) -> Result<HttpResponse, ErrorResponse> {
+    let user_id = path.into_inner();
+    principal.validate_user_or_admin(&user_id)?;
+
+    let user = User::find(user_id).await?;
+    let mut resp = vec![UserEmailResponse {
+        email: user.email,
+        verified: user.email_verified,
+        primary: true,
+        created_at: None,
+    }];
+    for email in UserEmail::find_for_user(&user.id).await? {
+        resp.push(UserEmailResponse {
+            email: email.email,
+            verified: email.verified,
+            primary: false,
+            created_at: Some(email.created_at),
+        });
+    }
+
+    Ok(HttpResponse::Ok().json(resp))
+}
+
+/// Add an additional E-Mail address
+///
+/// A confirmation link will be sent to the new address. It can only be used for logins, password
+/// resets and as a new primary address after it has been confirmed.
+#[utoipa::path(
+    post,
+    path = "/users/{id}/emails",
+    tag = "users",
+    request_body = UserEmailRequest,
+    responses(
+        (status = 200, description = "Ok", body = UserEmailResponse),
+        (status = 400, description = "BadRequest", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse),
+        (status = 406, description = "NotAccepted", body = ErrorResponse),
+    ),
+)]
+#[post("/users/{id}/emails")]
+pub async fn post_user_email(
+    path: web::Path<String>,
+    principal: ReqPrincipal,
+    Json(payload): Json<UserEmailRequest>,
+) -> Result<HttpResponse, ErrorResponse> {
+    payload.validate()?;
+    let user_id = path.into_inner();
+    principal.validate_user_or_admin(&user_id)?;
+
+    let user = User::find(user_id).await?;
+    let email = user.add_email(payload.email).await?;
+
+    Ok(HttpResponse::Ok().json(UserEmailResponse {
+        email: email.email,
+        verified: email.verified,
+        primary: false,
+        created_at: Some(email.created_at),
+    }))
+}
+
+/// Confirms an additional E-Mail address
+///
+/// The `id` is the user id and `confirm_id` is a random 64 character long string sent via E-Mail
+/// to the new address for a pre-authenticated request.
+#[utoipa::path(
+    get,
+    path = "/users/{id}/emails/confirm/{confirm_id}",
+    tag = "users",
+    responses(
+        (status = 302, description = "Found"),
+        (status = 400, description = "BadRequest", body = ErrorResponse),
+        (status = 404, description = "NotFound", body = ErrorResponse),
+    ),
+)]
+#[get("/users/{id}/emails/confirm/{confirm_id}")]
+pub async fn get_user_email_add_confirm(
+    path: web::Path<(String, String)>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let lang = Language::try_from(&req).unwrap_or_default();
+    let (user_id, confirm_id) = path.into_inner();
+    match User::confirm_email_add(&req, user_id, confirm_id).await {
+        Ok(_) => HttpResponse::build(StatusCode::FOUND)
+            .insert_header((LOCATION, "/auth/v1/account"))
+            .finish(),
+        Err(err) => {
+            let status = err.status_code();
+            let body = Error3Html::build(
+                &lang,
+                ThemeCssFull::find_theme_ts_rauthy()
+                    .await
+                    .unwrap_or_else(|_| Utc::now().timestamp()),
+                status,
+                err.message,
+            );
+            ErrorHtml::response(body, status)
+        }
+    }
+}
+
+/// Set a verified, additional E-Mail address as the new primary one
+///
+/// The old primary address will be kept as an additional one.
+#[utoipa::path(
+    put,
+    path = "/users/{id}/emails/primary",
+    tag = "users",
+    request_body = UserEmailRequest,
+    responses(
+        (status = 200, description = "Ok"),
+        (status = 400, description = "BadRequest", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse),
+        (status = 404, description = "NotFound", body = ErrorResponse),
+    ),
+)]
+#[put("/users/{id}/emails/primary")]
+pub async fn put_user_email_primary(
+    path: web::Path<String>,
+    principal: ReqPrincipal,
+    req: HttpRequest,
+    Json(payload): Json<UserEmailRequest>,
+) -> Result<HttpResponse, ErrorResponse> {
+    payload.validate()?;
+    let user_id = path.into_inner();
+    principal.validate_user_or_admin(&user_id)?;
+
+    let mut user = User::find(user_id).await?;
+    user.set_primary_email(&req, payload.email).await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Remove an additional E-Mail address
+///
+/// The primary address can never be removed. Set another address as primary first.
+#[utoipa::path(
+    delete,
+    path = "/users/{id}/emails/{email}",
+    tag = "users",
+    responses(
+        (status = 200, description = "Ok"),
+        (status = 400, description = "BadRequest", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse),
+        (status = 404, description = "NotFound", body = ErrorResponse),
+    ),
+)]
+#[delete("/users/{id}/emails/{email}")]
+pub async fn delete_user_email(
+    path: web::Path<(String, String)>,
+    principal: ReqPrincipal,
+) -> Result<HttpResponse, ErrorResponse> {
+    let (user_id, email) = path.into_inner();
+    principal.validate_user_or_admin(&user_id)?;
+
+    let user = User::find(user_id).await?;
+    user.remove_email(email).await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
 /// Endpoint for resetting passwords
 ///
 /// The `id` is the user id and `reset_id` is a random 64 character long string sent via E-Mail for a
@@ -1947,6 +2265,138 @@ pub async fn get_user_self_delete_config(
     }
 }
 
+const EXPORT_EVENTS_BATCH: i64 = 500;
+/// Closes the `events` array of an export, that could not be finished.
+const EXPORT_INCOMPLETE: &[u8] = br#"],"incomplete":true}"#;
+
+/// Export all personal data for the currently logged-in user
+///
+/// The export is a single JSON document, which contains the profile, linked federations, sessions,
+/// passkeys, devices, accepted ToS and all events referencing this user. Secrets like password
+/// hashes, session IDs or passkey credentials are never part of it.
+///
+/// Because the event history can be large, the response is streamed and the `events` are always
+/// the last key in the document. If an error occurs while streaming, the document will end with
+/// `"incomplete": true` after the `events`.
+///
+/// You need a valid `MfaModToken` to request the export.
+///
+/// **Permissions**
+/// - authenticated user
+#[utoipa::path(
+    post,
+    path = "/users/{id}/self/export",
+    tag = "users",
+    request_body = UserDataExportRequest,
+    responses(
+        (status = 200, description = "Ok", body = UserDataExport),
+        (status = 400, description = "BadRequest", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse),
+    ),
+)]
+#[post("/users/{id}/self/export")]
+pub async fn post_user_self_export(
+    path: web::Path<String>,
+    principal: ReqPrincipal,
+    req: HttpRequest,
+    Json(payload): Json<UserDataExportRequest>,
+) -> Result<HttpResponse, ErrorResponse> {
+    principal.validate_session_auth()?;
+    payload.validate()?;
+
+    let id = path.into_inner();
+    principal.is_user(&id)?;
+
+    let ip = real_ip_from_req(&req)?;
+    let token = MfaModToken::find(&payload.mfa_mod_token_id).await?;
+    token.validate(&id, ip)?;
+
+    let user = User::find(id).await?;
+    let user_id = user.id.clone();
+    let email = user.email.clone();
+    let export = user.into_data_export().await?;
+
+    // The first batch is fetched before the response starts, so that any DB issue can still
+    // be returned as a proper error instead of a broken document.
+    let first = Event::find_for_user(
+        &user_id,
+        &email,
+        (Utc::now().timestamp_millis() + 1, ""),
+        EXPORT_EVENTS_BATCH,
+    )
+    .await?;
+
+    // `events` are streamed as the last key -> strip the closing bracket of the object
+    let mut head = serde_json::to_vec(&export)?;
+    head.pop();
+    head.extend_from_slice(br#","events":["#);
+
+    info!("User data export for user {user_id}");
+    Event::user_data_exported(&email, ip).send().await?;
+
+    let (mut tx, rx) = futures::channel::mpsc::channel(1);
+
+    task::spawn(async move {
+        if tx
+            .send(Ok::<Bytes, String>(Bytes::from(head)))
+            .await
+            .is_err()
+        {
+            return;
+        }
+
+        let mut batch = Ok(first);
+        let mut is_first = true;
+        loop {
+            let (events, next) = match batch {
+                Ok(res) => res,
+                Err(err) => {
+                    error!(?err, "Error fetching events for user data export");
+                    // Close the document with an explicit marker, so that an incomplete export
+                    // can never be mistaken for a complete one.
+                    let _ = tx.send(Ok(Bytes::from_static(EXPORT_INCOMPLETE))).await;
+                    return;
+                }
+            };
+
+            let mut buf = Vec::with_capacity(events.len() * 128);
+            for event in events {
+                if !is_first {
+                    buf.push(b',');
+                }
+                is_first = false;
+                if let Err(err) = serde_json::to_writer(&mut buf, &EventResponse::from(event)) {
+                    error!(?err, "Error serializing event for user data export");
+                    let _ = tx.send(Ok(Bytes::from_static(EXPORT_INCOMPLETE))).await;
+                    return;
+                }
+            }
+            if !buf.is_empty() && tx.send(Ok(Bytes::from(buf))).await.is_err() {
+                return;
+            }
+
+            match next {
+                Some((ts, id)) => {
+                    batch = Event::find_for_user(&user_id, &email, (ts, &id), EXPORT_EVENTS_BATCH)
+                        .await;
+                }
+                None => break,
+            }
+        }
+
+        let _ = tx.send(Ok(Bytes::from_static(b"]}"))).await;
+    });
+
+    Ok(HttpResponse::Ok()
+        .content_type(APPLICATION_JSON)
+        .insert_header((
+            CONTENT_DISPOSITION,
+            "attachment; filename=\"rauthy_data_export.json\"",
+        ))
+        .streaming(rx.into_stream()))
+}
+
 /// DELETE for a user by self-service, if enabled
 ///
 /// This endpoint always deletes this very user the session is valid for.
@@ -2181,3 +2631,187 @@ pub async fn delete_user_by_id(
     let user = User::find(id.into_inner()).await?;
     handle_user_delete(user).await
 }
+
+/// Soft-deletes a user
+///
+/// The user will be disabled and all sessions and tokens will be revoked. The user itself and
+/// its upstream federation links are kept for `user_delete.disabled_retention_days`, before
+/// they will be deleted permanently.
+///
+/// **Permissions**
+/// - rauthy_admin
+#[utoipa::path(
+    post,
+    path = "/users/{id}/disable",
+    tag = "users",
+    responses(
+        (status = 200, description = "Ok", body = UserResponse),
+        (status = 400, description = "BadRequest", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse),
+        (status = 404, description = "NotFound", body = ErrorResponse),
+    ),
+)]
+#[post("/users/{id}/disable")]
+pub async fn post_user_disable(
+    id: web::Path<String>,
+    principal: ReqPrincipal,
+    req: HttpRequest,
+) -> Result<HttpResponse, ErrorResponse> {
+    principal.validate_api_key_or_admin_session(AccessGroup::Users, AccessRights::Update)?;
+
+    let id = id.into_inner();
+    if principal.user_id().ok() == Some(id.as_str()) {
+        return Err(ErrorResponse::new(
+            ErrorResponseType::BadRequest,
+            "You cannot disable your own account",
+        ));
+    }
+
+    let mut user = User::find(id).await?;
+    // sessions and refresh tokens are invalidated with the save of a disabled user already
+    user.disable().await?;
+    RefreshTokenDevice::invalidate_all_for_user(&user.id).await?;
+    IssuedToken::revoke_for_user(&user.id, true).await?;
+    logout::execute_backchannel_logout(None, Some(user.id.clone())).await?;
+
+    Event::user_disabled(
+        &user.email,
+        &principal.actor().await?,
+        real_ip_from_req(&req)?,
+    )
+    .send()
+    .await?;
+
+    let resp = user.clone().into_response(None).await?;
+    if let Err(err) = ClientScim::create_update_user(user).await {
+        error!("{err}");
+    }
+
+    Ok(HttpResponse::Ok().json(resp))
+}
+
+/// Re-enables a soft-deleted user
+///
+/// **Permissions**
+/// - rauthy_admin
+#[utoipa::path(
+    post,
+    path = "/users/{id}/enable",
+    tag = "users",
+    responses(
+        (status = 200, description = "Ok", body = UserResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse),
+        (status = 404, description = "NotFound", body = ErrorResponse),
+    ),
+)]
+#[post("/users/{id}/enable")]
+pub async fn post_user_enable(
+    id: web::Path<String>,
+    principal: ReqPrincipal,
+    req: HttpRequest,
+) -> Result<HttpResponse, ErrorResponse> {
+    principal.validate_api_key_or_admin_session(AccessGroup::Users, AccessRights::Update)?;
+
+    let mut user = User::find(id.into_inner()).await?;
+    user.enable().await?;
+
+    Event::user_enabled(
+        &user.email,
+        &principal.actor().await?,
+        real_ip_from_req(&req)?,
+    )
+    .send()
+    .await?;
+
+    let resp = user.clone().into_response(None).await?;
+    if let Err(err) = ClientScim::create_update_user(user).await {
+        error!("{err}");
+    }
+
+    Ok(HttpResponse::Ok().json(resp))
+}
+
+/// Approves a pending registration
+///
+/// Local accounts will receive the welcome E-Mail to set their password, federated ones are
+/// notified that they can log in now.
+///
+/// **Permissions**
+/// - rauthy_admin
+#[utoipa::path(
+    post,
+    path = "/users/{id}/approve",
+    tag = "users",
+    responses(
+        (status = 200, description = "Ok", body = UserResponse),
+        (status = 400, description = "BadRequest", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse),
+        (status = 404, description = "NotFound", body = ErrorResponse),
+    ),
+)]
+#[post("/users/{id}/approve")]
+pub async fn post_user_approve(
+    id: web::Path<String>,
+    principal: ReqPrincipal,
+    req: HttpRequest,
+) -> Result<HttpResponse, ErrorResponse> {
+    principal.validate_api_key_or_admin_session(AccessGroup::Users, AccessRights::Update)?;
+
+    let mut user = User::find(id.into_inner()).await?;
+    user.approve_registration().await?;
+
+    let ip = real_ip_from_req(&req)?;
+    Event::registration_approved(&user.email, &principal.actor().await?, ip)
+        .send()
+        .await?;
+    Event::new_user(user.email.clone(), ip.to_string())
+        .send()
+        .await?;
+
+    let resp = user.clone().into_response(None).await?;
+    if let Err(err) = ClientScim::create_update_user(user).await {
+        error!("{err}");
+    }
+
+    Ok(HttpResponse::Ok().json(resp))
+}
+
+/// Rejects a pending registration
+///
+/// The user will be deleted and the applicant will be notified via E-Mail.
+///
+/// **Permissions**
+/// - rauthy_admin
+#[utoipa::path(
+    post,
+    path = "/users/{id}/reject",
+    tag = "users",
+    responses(
+        (status = 204, description = "NoContent"),
+        (status = 400, description = "BadRequest", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse),
+        (status = 404, description = "NotFound", body = ErrorResponse),
+    ),
+)]
+#[post("/users/{id}/reject")]
+pub async fn post_user_reject(
+    id: web::Path<String>,
+    principal: ReqPrincipal,
+    req: HttpRequest,
+) -> Result<HttpResponse, ErrorResponse> {
+    principal.validate_api_key_or_admin_session(AccessGroup::Users, AccessRights::Delete)?;
+
+    let user = User::find(id.into_inner()).await?;
+    let email = user.email.clone();
+    user.reject_registration().await?;
+
+    Event::registration_rejected(&email, &principal.actor().await?, real_ip_from_req(&req)?)
+        .send()
+        .await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -1,4 +1,4 @@
-use crate::cust_validation::validate_vec_scopes;
+use crate::cust_validation::{validate_vec_email_domain, validate_vec_scopes};
 use rauthy_common::regex::{
     RE_ALNUM, RE_ATPROTO_HANDLE, RE_CLIENT_ID, RE_CLIENT_NAME, RE_CODE_CHALLENGE, RE_SCOPE_SPACE,
     RE_URI,
@@ -16,6 +16,32 @@ pub enum AuthProviderType {
     OIDC,
 }
 
+/// Controls if upstream claims overwrite the local user attributes during a login.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthProviderClaimPrecedence {
+    /// Upstream claims overwrite local values with each login
+    #[default]
+    Upstream,
+    /// Upstream claims are only applied with the first login, which creates the link
+    FirstLogin,
+    /// Local values always win for existing users
+    Local,
+}
+
+/// Normalization for the upstream user id, before it is linked to or looked up for a local user.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthProviderUidNormalization {
+    /// The upstream user id is used as-is
+    #[default]
+    Exact,
+    /// Leading and trailing whitespace is removed
+    Trim,
+    /// Whitespace is removed and ASCII characters are lowercased
+    TrimLowercase,
+}
+
 #[derive(Deserialize, Validate, ToSchema)]
 pub struct ProviderRequest {
     /// Validation: `[a-zA-Z0-9À-ÿ-\s]{2,128}]`
@@ -45,6 +71,23 @@ pub struct ProviderRequest {
     pub client_secret_post: bool,
     pub auto_onboarding: bool,
     pub auto_link: bool,
+    /// Automatically redirect to this provider on the login page, when the `login_hint`
+    /// matches one of the `email_domains`.
+    #[serde(default)]
+    pub auto_redirect: bool,
+    /// E-Mail domains which belong to this provider. A leading `*.` matches all subdomains.
+    ///
+    /// Validation: `Vec<^(\*\.)?[a-z0-9]([a-z0-9-]*[a-z0-9])?(\.[a-z0-9]([a-z0-9-]*[a-z0-9])?)*$>`
+    #[validate(custom(function = "validate_vec_email_domain"))]
+    #[serde(default)]
+    pub email_domains: Vec<String>,
+    /// Defaults to `upstream`, if not given
+    #[serde(default)]
+    pub claim_precedence: AuthProviderClaimPrecedence,
+    /// Defaults to `exact`, if not given. Only enable a normalization, if the upstream
+    /// provider treats its user ids case-insensitive.
+    #[serde(default)]
+    pub uid_normalization: AuthProviderUidNormalization,
 
     // This validation is pretty loose, but if we make it too strict,
     // we will most probably get into compatibility issues.
@@ -93,6 +136,14 @@ pub struct ProviderCallbackRequest {
     pub iss_atproto: Option<String>,
 }
 
+#[derive(Deserialize, Validate, ToSchema)]
+#[cfg_attr(debug_assertions, derive(Serialize))]
+pub struct ProviderDiscoveryRequest {
+    /// Validation: `email`
+    #[validate(email)]
+    pub email: String,
+}
+
 #[derive(Deserialize, Validate, ToSchema)]
 #[cfg_attr(debug_assertions, derive(Serialize))]
 pub struct ProviderLoginRequest {
@@ -158,6 +209,13 @@ pub struct ProviderLookupRequest {
     pub metadata_url: Option<String>,
 }
 
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(debug_assertions, derive(Deserialize))]
+pub struct ProviderDiscoveryResponse {
+    /// The provider the user should be redirected to, if any
+    pub provider_id: Option<String>,
+}
+
 #[derive(Serialize, ToSchema)]
 pub struct ProviderResponse {
     pub id: String,
@@ -185,6 +243,26 @@ pub struct ProviderResponse {
     pub client_secret_post: bool,
     pub auto_onboarding: bool,
     pub auto_link: bool,
+    pub auto_redirect: bool,
+    pub email_domains: Vec<String>,
+    pub claim_precedence: AuthProviderClaimPrecedence,
+    pub uid_normalization: AuthProviderUidNormalization,
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct ProviderFederationUpdateRequest {
+    /// The new, upstream user id for this link
+    ///
+    /// Validation: length 1 - 256
+    #[validate(length(min = 1, max = 256))]
+    pub federation_uid: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ProviderFederationResponse {
+    pub user_id: String,
+    pub provider_id: String,
+    pub federation_uid: String,
 }
 
 #[derive(Serialize, Deserialize, ToSchema)]
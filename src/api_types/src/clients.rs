@@ -4,6 +4,7 @@ use rauthy_common::regex::{
     RE_CLIENT_ID, RE_CLIENT_NAME, RE_GROUPS, RE_SCOPE_SPACE, RE_TOKEN_ENDPOINT_AUTH_METHOD, RE_URI,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use utoipa::ToSchema;
 use validator::Validate;
 
@@ -17,6 +18,9 @@ pub struct DynamicClientRequest {
     /// Validation: `Vec<^(authorization_code|client_credentials|urn:ietf:params:oauth:grant-type:device_code|password|refresh_token)$>`
     #[validate(custom(function = "validate_vec_grant_types"))]
     pub grant_types: Vec<String>,
+    /// Rauthy only supports `code`. If given, it must be used together with the
+    /// `authorization_code` grant type.
+    pub response_types: Option<Vec<String>>,
     /// Validation: `[a-zA-Z0-9À-ÿ-\\s]{2,128}`
     #[validate(regex(path = "*RE_CLIENT_NAME", code = "[a-zA-Z0-9À-ɏ-\\s]{2,128}"))]
     pub client_name: Option<String>,
@@ -37,7 +41,6 @@ pub struct DynamicClientRequest {
     /// Validation: `^(RS256|RS384|RS512|EdDSA)$`
     pub token_endpoint_auth_signing_alg: Option<JwkKeyPairAlg>,
     // Rauthy will only accept the following defaults
-    // `response_type=code`
     // `subject_type=public`
     // `require_auth_time=true` (always added by Rauthy anyway)
     //
@@ -137,6 +140,9 @@ pub struct NewClientRequest {
     /// Validation: `Vec<^[a-zA-Z0-9,.:/_\\-&?=~#!$'()*+%]+$>`
     #[validate(custom(function = "validate_vec_uri"))]
     pub post_logout_redirect_uris: Option<Vec<String>>,
+    /// How `*` rules inside the `redirect_uris` and `post_logout_redirect_uris` are matched.
+    /// Defaults to `strict` for new clients.
+    pub uri_match_mode: Option<UriMatchMode>,
 }
 
 #[derive(Deserialize, Validate, ToSchema)]
@@ -196,6 +202,51 @@ pub struct UpdateClientRequest {
     /// Validation: `^[a-zA-Z0-9-_/,:*\\s]{2,64}$`
     #[validate(regex(path = "*RE_GROUPS", code = "^[a-zA-Z0-9-_/,:*\\s]{2,64}$"))]
     pub restrict_group_prefix: Option<String>,
+    /// Allows this client to use the token introspection endpoint as a resource server.
+    #[serde(default)]
+    pub introspect: bool,
+    /// Renames internal claims in issued tokens, e.g. `roles` -> `https://example.com/roles`.
+    ///
+    /// Validation: `HashMap<^[a-zA-Z0-9.:/_\-~#]{1,128}$, ^[a-zA-Z0-9.:/_\-~#]{1,128}$>`,
+    /// reserved JWT claims like `iss` or `sub` are not allowed
+    #[validate(custom(function = "validate_claim_mapping"))]
+    pub claim_mapping: Option<HashMap<String, String>>,
+    /// If set, all mapped claims will be nested inside an object with this name.
+    ///
+    /// Validation: `^[a-zA-Z0-9.:/_\-~#]{1,128}$`, reserved JWT claims are not allowed
+    #[validate(custom(function = "validate_claim_namespace"))]
+    pub claim_namespace: Option<String>,
+    /// Absolute maximum lifetime in seconds for refresh tokens, counted from the original
+    /// authentication. Each refresh still extends the token by the `refresh_token_lifetime`,
+    /// but never beyond this cap. Without a value, refresh tokens can be extended indefinitely.
+    ///
+    /// Validation: `10 <= refresh_token_max_lifetime <= 31536000`
+    #[validate(range(min = 10, max = 31536000))]
+    pub refresh_token_max_lifetime: Option<i32>,
+    /// Rejects authorization requests without a `nonce`, which will then always be echoed in
+    /// the `id_token`.
+    #[serde(default)]
+    pub require_nonce: bool,
+    /// Marks a trusted first-party application. Can only be set by an admin and will be reset
+    /// when a dynamic client updates itself. First-party clients bypass `access.strict_scopes`:
+    /// scopes which are not allowed or not entitled are silently dropped instead of being
+    /// rejected with an `invalid_scope` error.
+    #[serde(default)]
+    pub first_party: bool,
+    /// `pairwise` issues a different, but stable `sub` for the same user in each sector.
+    /// The current type is kept, if not given.
+    pub subject_type: Option<SubjectType>,
+    /// How `*` rules inside the `redirect_uris` and `post_logout_redirect_uris` are matched.
+    /// The current mode is kept, if not given.
+    pub uri_match_mode: Option<UriMatchMode>,
+    /// Only used with `pairwise` subjects. All clients with the same host in their
+    /// `sector_identifier_uri` share a sector and therefore get the same `sub` for a user.
+    /// Without it, each client is its own sector. The URI must return a JSON array, which
+    /// contains all `redirect_uris` of the client.
+    ///
+    /// Validation: `[a-zA-Z0-9,.:/_-&?=~#!$'()*+%@]+$`
+    #[validate(regex(path = "*RE_URI", code = "[a-zA-Z0-9,.:/_-&?=~#!$'()*+%@]+$"))]
+    pub sector_identifier_uri: Option<String>,
     #[validate(nested)]
     pub scim: Option<ScimClientRequestResponse>,
 }
@@ -208,6 +259,33 @@ pub struct ClientSecretRequest {
     pub cache_current_hours: Option<u8>,
 }
 
+#[derive(Validate, Deserialize, ToSchema)]
+#[cfg_attr(debug_assertions, derive(Serialize))]
+pub struct ClientSecretSetRequest {
+    /// Validation: Value between 1 and 24
+    #[validate(range(min = 1, max = 24))]
+    pub cache_current_hours: Option<u8>,
+    /// The new secret. It must match the `access.client_secret_min_*` policy.
+    ///
+    /// Validation: max length 256
+    #[validate(length(max = 256))]
+    pub secret: String,
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+#[cfg_attr(debug_assertions, derive(Serialize))]
+pub struct TokenPreviewRequest {
+    /// The E-Mail of the user the tokens should be built for
+    #[validate(email)]
+    pub email: String,
+    /// The requested scopes, just like during the authorization. The client's default scopes
+    /// will be used, if not given.
+    ///
+    /// Validation: `Vec<^[a-z0-9-_/,:*]{2,64}$>`
+    #[validate(custom(function = "validate_vec_scopes"))]
+    pub scopes: Option<Vec<String>>,
+}
+
 #[derive(Serialize, Deserialize, ToSchema, Validate)]
 #[cfg_attr(debug_assertions, derive(Debug))]
 pub struct ScimClientRequestResponse {
@@ -254,10 +332,32 @@ pub struct ClientResponse {
     pub backchannel_logout_uri: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub restrict_group_prefix: Option<String>,
+    pub introspect: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claim_mapping: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claim_namespace: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token_max_lifetime: Option<i32>,
+    pub require_nonce: bool,
+    pub first_party: bool,
+    pub subject_type: SubjectType,
+    pub uri_match_mode: UriMatchMode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sector_identifier_uri: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub scim: Option<ScimClientRequestResponse>,
 }
 
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(debug_assertions, derive(Deserialize))]
+pub struct TokenPreviewResponse {
+    /// The decoded claims of the access token
+    pub access_token: serde_json::Value,
+    /// The decoded claims of the ID token
+    pub id_token: serde_json::Value,
+}
+
 #[derive(Serialize, ToSchema)]
 #[cfg_attr(debug_assertions, derive(Deserialize))]
 pub struct ClientSecretResponse {
@@ -303,3 +403,62 @@ pub struct DynamicClientResponse {
     pub token_endpoint_auth_method: String,
     pub token_endpoint_auth_signing_alg: String,
 }
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SubjectType {
+    #[default]
+    Public,
+    Pairwise,
+}
+
+impl SubjectType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Public => "public",
+            Self::Pairwise => "pairwise",
+        }
+    }
+}
+
+impl From<String> for SubjectType {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "pairwise" => Self::Pairwise,
+            _ => Self::Public,
+        }
+    }
+}
+
+/// How `*` rules inside the `redirect_uris` and `post_logout_redirect_uris` of a client are
+/// matched.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum UriMatchMode {
+    /// Legacy mode: a rule ending with `*` matches any URI starting with everything in front
+    /// of it, no matter the origin.
+    Prefix,
+    /// A `*` at the very end matches any path on the exact same origin, `*.` as the first label
+    /// of the host matches exactly one subdomain level, and loopback URIs accept any port.
+    #[default]
+    Strict,
+}
+
+impl UriMatchMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Prefix => "prefix",
+            Self::Strict => "strict",
+        }
+    }
+}
+
+impl From<String> for UriMatchMode {
+    /// Defaults to `Self::Prefix` for unknown values, which is the legacy behavior.
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "strict" => Self::Strict,
+            _ => Self::Prefix,
+        }
+    }
+}
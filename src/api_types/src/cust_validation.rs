@@ -1,7 +1,9 @@
+use rauthy_common::constants::RESERVED_CLAIMS;
 use rauthy_common::regex::{
-    RE_ATTR, RE_CODE_CHALLENGE_METHOD, RE_CONTACT, RE_GRANT_TYPES, RE_GROUPS, RE_LINUX_HOSTNAME,
-    RE_ORIGIN, RE_ROLES_SCOPES, RE_URI,
+    RE_ATTR, RE_CLAIM_NAME, RE_CODE_CHALLENGE_METHOD, RE_CONTACT, RE_EMAIL_DOMAIN_PATTERN,
+    RE_GRANT_TYPES, RE_GROUPS, RE_LINUX_HOSTNAME, RE_ORIGIN, RE_ROLES_SCOPES, RE_URI,
 };
+use std::collections::{HashMap, HashSet};
 use validator::ValidationError;
 
 #[inline]
@@ -24,6 +26,36 @@ pub fn validate_vec_attr(value: &[String]) -> Result<(), ValidationError> {
     Ok(())
 }
 
+pub fn validate_claim_mapping(value: &HashMap<String, String>) -> Result<(), ValidationError> {
+    let mut targets = HashSet::with_capacity(value.len());
+    for (from, to) in value {
+        if !RE_CLAIM_NAME.is_match(from) || !RE_CLAIM_NAME.is_match(to) {
+            return Err(ValidationError::new("^[a-zA-Z0-9.:/_\\-~#]{1,128}$"));
+        }
+        if RESERVED_CLAIMS.contains(&from.as_str()) || RESERVED_CLAIMS.contains(&to.as_str()) {
+            return Err(ValidationError::new(
+                "reserved JWT claims cannot be used in a claim mapping",
+            ));
+        }
+        if !targets.insert(to.as_str()) {
+            return Err(ValidationError::new("mapped claim names must be unique"));
+        }
+    }
+    Ok(())
+}
+
+pub fn validate_claim_namespace(value: &str) -> Result<(), ValidationError> {
+    if !RE_CLAIM_NAME.is_match(value) {
+        return Err(ValidationError::new("^[a-zA-Z0-9.:/_\\-~#]{1,128}$"));
+    }
+    if RESERVED_CLAIMS.contains(&value) {
+        return Err(ValidationError::new(
+            "reserved JWT claims cannot be used as claim namespace",
+        ));
+    }
+    Ok(())
+}
+
 #[inline]
 pub fn validate_vec_challenge(value: &[String]) -> Result<(), ValidationError> {
     let mut err = None;
@@ -58,6 +90,21 @@ pub fn validate_vec_contact(value: &[String]) -> Result<(), ValidationError> {
     Ok(())
 }
 
+#[inline]
+pub fn validate_vec_email_domain(value: &[String]) -> Result<(), ValidationError> {
+    let mut err = None;
+    value.iter().for_each(|v| {
+        if !RE_EMAIL_DOMAIN_PATTERN.is_match(v) {
+            err =
+                Some("^(\\*\\.)?[a-z0-9]([a-z0-9-]*[a-z0-9])?(\\.[a-z0-9]([a-z0-9-]*[a-z0-9])?)*$");
+        }
+    });
+    if let Some(e) = err {
+        return Err(ValidationError::new(e));
+    }
+    Ok(())
+}
+
 #[inline]
 pub fn validate_vec_grant_types(value: &[String]) -> Result<(), ValidationError> {
     let mut err = None;
@@ -35,6 +35,22 @@ pub enum EventType {
     SuspiciousApiScan,
     LoginNewLocation,
     TokenIssued,
+    UserEmailChangeRequest,
+    IntrospectionDenied,
+    RegistrationRateLimit,
+    TokensRevoked,
+    TokensInvalidated,
+    UserDataExported,
+    UserFederationRemapped,
+    UserOnboarded,
+    CacheFlushed,
+    RegistrationPending,
+    RegistrationApproved,
+    RegistrationRejected,
+    AccountRecovered,
+    RecordsSwept,
+    UserDisabled,
+    UserEnabled,
 }
 
 #[derive(Deserialize, Validate, ToSchema, IntoParams)]
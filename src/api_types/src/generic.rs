@@ -1,9 +1,21 @@
 use crate::sessions::SessionState;
-use rauthy_common::regex::{RE_ALNUM, RE_SEARCH};
+use rauthy_common::regex::{RE_ALNUM, RE_SEARCH, RE_TOKEN_68};
 use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 use validator::Validate;
 
+#[derive(Deserialize, Validate, ToSchema, IntoParams)]
+pub struct CursorParams {
+    /// Validation: `1 <= limit <= 1000`, defaults to `100`
+    #[validate(range(min = 1, max = 1000))]
+    pub limit: Option<u16>,
+    /// Opaque value from the `x-next-cursor` header of the previous page.
+    ///
+    /// Validation: `^[a-zA-Z0-9-._~+/]+=*$`
+    #[validate(regex(path = "*RE_TOKEN_68", code = "^[a-zA-Z0-9-._~+/]+=*$"))]
+    pub cursor: Option<String>,
+}
+
 #[derive(Deserialize, Validate, ToSchema, IntoParams)]
 pub struct EncKeyMigrateRequest {
     /// Validation: `[a-zA-Z0-9]`
@@ -54,8 +66,10 @@ pub struct PaginationParams {
     pub page_size: Option<u16>,
     pub offset: Option<u16>,
     pub backwards: Option<bool>,
-    /// Validation: `[a-zA-Z0-9]`
-    #[validate(regex(path = "*RE_ALNUM", code = "[a-zA-Z0-9]"))]
+    /// Opaque value from the `x-continuation-token` header of the previous page.
+    ///
+    /// Validation: `^[a-zA-Z0-9-._~+/]+=*$`
+    #[validate(regex(path = "*RE_TOKEN_68", code = "^[a-zA-Z0-9-._~+/]+=*$"))]
     pub continuation_token: Option<String>,
     /// Ignored for anything else than `/sessions`. Defaults to `SessionState::Auth`.
     pub session_state: Option<SessionState>,
@@ -192,3 +206,34 @@ pub struct PasswordPolicyResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub not_recently_used: Option<i32>,
 }
+
+#[derive(Debug, Serialize, ToSchema)]
+#[cfg_attr(debug_assertions, derive(Deserialize))]
+pub struct TokensRevokedResponse {
+    /// The amount of revoked access tokens
+    pub access_tokens: usize,
+    /// The amount of revoked refresh tokens, including device refresh tokens
+    pub refresh_tokens: usize,
+}
+
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(debug_assertions, derive(Deserialize))]
+pub struct CacheNamespaceResponse {
+    /// The name to be used for `DELETE /cache/{namespace}`
+    pub name: String,
+    /// The amount of entries on this node. Expired entries may be included, as long as they
+    /// have not been cleaned up.
+    pub entries: usize,
+    /// The lifetime in seconds for new entries, if it is fixed.
+    pub ttl: Option<i64>,
+    /// Only pure caches can be flushed, where a miss just triggers a re-fetch. All others hold
+    /// short-lived state like auth codes or rate limits.
+    pub flushable: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TokensNotBeforeResponse {
+    /// Unix timestamp in seconds. Any token with an `iat` before this value must be
+    /// rejected. `0` means that no global `nbf` is in place.
+    pub not_before: i64,
+}
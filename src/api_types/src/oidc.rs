@@ -53,6 +53,9 @@ pub struct AuthRequest {
     pub state: Option<String>,
     /// Validation: `[a-zA-Z0-9,.:/_-&?=~#!$'()*+%@]+$`
     #[validate(regex(path = "*RE_URI", code = "[a-zA-Z0-9,.:/_-&?=~#!$'()*+%@]+$"))]
+    pub nonce: Option<String>,
+    /// Validation: `[a-zA-Z0-9,.:/_-&?=~#!$'()*+%@]+$`
+    #[validate(regex(path = "*RE_URI", code = "[a-zA-Z0-9,.:/_-&?=~#!$'()*+%@]+$"))]
     pub code_challenge: Option<String>,
     /// Validation: `plain|S256`
     #[validate(regex(path = "*RE_CODE_CHALLENGE_METHOD", code = "plain|S256"))]
@@ -62,6 +65,23 @@ pub struct AuthRequest {
     /// Validation: `[a-zA-Z0-9]`
     #[validate(regex(path = "*RE_ALNUM", code = "[a-zA-Z0-9]"))]
     pub prompt: Option<String>,
+    /// Will only be used, if it is a valid E-Mail, and ignored otherwise.
+    ///
+    /// Validation: max length 256
+    #[validate(length(max = 256))]
+    pub login_hint: Option<String>,
+    /// How the login page should be displayed: `page`, `popup`, `touch` or `wap`.
+    /// Unsupported values are ignored.
+    ///
+    /// Validation: max length 16
+    #[validate(length(max = 16))]
+    pub display: Option<String>,
+    /// Space-separated list of BCP47 language tags, ordered by preference. The first supported
+    /// one will be used for the login page. Unsupported values are ignored.
+    ///
+    /// Validation: max length 256
+    #[validate(length(max = 256))]
+    pub ui_locales: Option<String>,
 }
 
 #[inline]
@@ -128,6 +148,11 @@ pub struct LoginRequest {
     /// Validation: `plain|S256`
     #[validate(regex(path = "*RE_CODE_CHALLENGE_METHOD", code = "plain|S256"))]
     pub code_challenge_method: Option<String>,
+    /// The token from the CAPTCHA widget, only required if CAPTCHAs are enabled.
+    ///
+    /// Validation: max length 4096
+    #[validate(length(max = 4096))]
+    pub captcha: Option<String>,
 }
 
 #[derive(Deserialize, Validate, ToSchema)]
@@ -181,6 +206,20 @@ pub struct LogoutRequest {
     pub logout_token: Option<String>,
 }
 
+#[derive(Deserialize, Validate, ToSchema, IntoParams)]
+#[cfg_attr(debug_assertions, derive(Serialize))]
+pub struct RedirectUriValidationRequest {
+    /// Validation: `^[a-zA-Z0-9,.:/_\-&?=~#!$'()*+%]{2,128}$`
+    #[validate(regex(
+        path = "*RE_CLIENT_ID",
+        code = "^[a-zA-Z0-9,.:/_\\-&?=~#!$'()*+%]{2,256}$"
+    ))]
+    pub client_id: String,
+    /// Validation: `[a-zA-Z0-9,.:/_-&?=~#!$'()*+%@]+$`
+    #[validate(regex(path = "*RE_URI", code = "[a-zA-Z0-9,.:/_-&?=~#!$'()*+%@]+$"))]
+    pub redirect_uri: String,
+}
+
 #[derive(Deserialize, Validate, ToSchema)]
 #[cfg_attr(debug_assertions, derive(Serialize))]
 pub struct DeviceGrantRequest {
@@ -378,6 +417,25 @@ pub struct JWKSCerts {
     pub keys: Vec<JWKSPublicKeyCerts>,
 }
 
+/// The way a `redirect_uri` matched one of the registered URIs of a client.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RedirectUriMatch {
+    Exact,
+    /// Prefix match for a registered URI ending with `*`, or a `*.` subdomain match
+    Wildcard,
+    /// Any port for a registered loopback URI (RFC 8252)
+    Loopback,
+}
+
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(debug_assertions, derive(Deserialize))]
+pub struct RedirectUriValidationResponse {
+    pub valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<RedirectUriMatch>,
+}
+
 #[derive(Serialize, ToSchema)]
 pub struct PasswordResetResponse {
     pub csrf_token: String,
@@ -1,4 +1,4 @@
-use crate::cust_validation::validate_vec_attr;
+use crate::cust_validation::{validate_vec_attr, validate_vec_roles};
 use rauthy_common::regex::RE_ROLES_SCOPES;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
@@ -16,6 +16,11 @@ pub struct ScopeRequest {
     /// Validation: `^[a-zA-Z0-9-_/]{2,128}$`
     #[validate(custom(function = "validate_vec_attr"))]
     pub attr_include_id: Option<Vec<String>>,
+    /// If set, only users with at least one of these roles can obtain this scope.
+    ///
+    /// Validation: `^[a-zA-Z0-9-_/,:*.]{2,64}$`
+    #[validate(custom(function = "validate_vec_roles"))]
+    pub required_roles: Option<Vec<String>>,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -27,4 +32,6 @@ pub struct ScopeResponse {
     pub attr_include_access: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub attr_include_id: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required_roles: Option<Vec<String>>,
 }
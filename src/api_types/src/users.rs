@@ -99,6 +99,11 @@ pub struct NewUserRegistrationRequest {
     /// Validation: `[a-zA-Z0-9,.:/_\-&?=~#!$'()*+%]+`
     #[validate(regex(path = "*RE_URI", code = "[a-zA-Z0-9,.:/_\\-&?=~#!$'()*+%]+"))]
     pub redirect_uri: Option<String>,
+    /// The token from the CAPTCHA widget, only required if CAPTCHAs are enabled.
+    ///
+    /// Validation: max length 4096
+    #[validate(length(max = 4096))]
+    pub captcha: Option<String>,
 }
 
 #[derive(Deserialize, Validate, ToSchema)]
@@ -203,6 +208,23 @@ pub struct UpdateUserSelfRequest {
     pub user_values: Option<UserValuesRequest>,
 }
 
+#[derive(Deserialize, Validate, ToSchema)]
+#[cfg_attr(debug_assertions, derive(Serialize))]
+pub struct UserDataExportRequest {
+    /// The export requires a fresh re-authentication via `/users/{id}/mfa_token` or a
+    /// Webauthn `MfaPurpose::MfaModToken` flow.
+    #[validate(length(min = 32, max = 32))]
+    pub mfa_mod_token_id: String,
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+#[cfg_attr(debug_assertions, derive(Serialize))]
+pub struct UserEmailRequest {
+    /// Validation: `email`
+    #[validate(email)]
+    pub email: String,
+}
+
 #[derive(Debug, Default, PartialEq, Deserialize, Validate, ToSchema)]
 #[cfg_attr(debug_assertions, derive(Serialize))]
 pub struct UserValuesRequest {
@@ -272,6 +294,9 @@ pub struct UserAttrConfigRequest {
     /// Currently ignored - will be implemented in a future version
     pub typ: Option<UserAttrConfigTyp>,
     pub user_editable: Option<bool>,
+    /// Internal attributes will never be included in any tokens or userinfo, even if they are
+    /// mapped to a scope.
+    pub internal: Option<bool>,
 }
 
 #[derive(Deserialize, Validate, ToSchema)]
@@ -412,6 +437,7 @@ pub struct UserAttrConfigValueResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub typ: Option<UserAttrConfigTyp>,
     pub user_editable: bool,
+    pub internal: bool,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -433,6 +459,77 @@ pub struct UserAttrValuesResponse {
     pub values: Vec<UserAttrValueResponse>,
 }
 
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(debug_assertions, derive(Deserialize))]
+pub struct UserDataExportClientLogin {
+    pub client_id: String,
+    /// Unix timestamp in seconds
+    pub last_login: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(debug_assertions, derive(Deserialize))]
+pub struct UserDataExportFederation {
+    pub provider_id: String,
+    pub federation_uid: String,
+}
+
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(debug_assertions, derive(Deserialize))]
+pub struct UserDataExportLoginLocation {
+    pub ip: String,
+    /// Unix timestamp in seconds
+    pub last_seen: i64,
+    pub user_agent: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(debug_assertions, derive(Deserialize))]
+pub struct UserDataExportSession {
+    pub is_mfa: bool,
+    /// Unix timestamp in seconds
+    pub exp: i64,
+    /// Unix timestamp in seconds
+    pub last_seen: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_ip: Option<String>,
+}
+
+/// The full data export for a single user. Secrets like password hashes, session IDs or
+/// passkey credentials are never part of it.
+///
+/// The `events` are appended as the very last key when the export is streamed and are not
+/// part of this struct, because they can grow large.
+#[derive(Serialize, ToSchema)]
+pub struct UserDataExport {
+    /// Unix timestamp in seconds
+    pub exported_at: i64,
+    pub user: UserResponse,
+    pub emails: Vec<UserEmailResponse>,
+    pub attributes: Vec<UserAttrValueResponse>,
+    pub federations: Vec<UserDataExportFederation>,
+    pub sessions: Vec<UserDataExportSession>,
+    pub passkeys: Vec<PasskeyResponse>,
+    pub devices: Vec<DeviceResponse>,
+    pub login_locations: Vec<UserDataExportLoginLocation>,
+    pub client_logins: Vec<UserDataExportClientLogin>,
+    pub tos_accepts: Vec<crate::tos::ToSUserAcceptResponse>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(debug_assertions, derive(Deserialize))]
+pub struct UserEmailResponse {
+    pub email: String,
+    pub verified: bool,
+    /// The primary address is used for all notifications and the `email` claim.
+    pub primary: bool,
+    /// Unix timestamp in seconds, `None` for the primary address
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<i64>,
+}
+
 #[derive(Serialize, ToSchema)]
 #[cfg_attr(debug_assertions, derive(Deserialize))]
 pub struct UserEditableAttrResponse {
@@ -556,6 +653,25 @@ pub struct UserResponse {
     pub federation_uid: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub picture_id: Option<String>,
+    /// Unix timestamp in seconds, if the user has been soft-deleted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disabled_at: Option<i64>,
+    /// Unix timestamp in seconds, if the registration awaits the approval of an admin
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pending_since: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PendingRegistrationResponse {
+    pub id: String,
+    pub email: String,
+    pub given_name: Option<String>,
+    pub family_name: Option<String>,
+    pub language: String,
+    /// `true` if the user has been created via an upstream auth provider
+    pub federated: bool,
+    /// Unix timestamp in seconds
+    pub pending_since: i64,
 }
 
 #[derive(Serialize, Deserialize, ToSchema)]
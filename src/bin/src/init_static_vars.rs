@@ -7,9 +7,7 @@ use rauthy_common::{DB_TYPE, DbType, HTTP_CLIENT};
 use rauthy_data::rauthy_config::RauthyConfig;
 use rauthy_handlers::generic::{I18N_CONFIG, TIMEZONES_BR};
 use regex::Regex;
-use reqwest::tls;
 use std::time::Duration;
-use tracing::{debug, warn};
 
 /// The only job of this function is to trigger the `LazyLock` init for some values that will be
 /// used all the time anyway. When this is triggered at the very start of the application, the
@@ -74,54 +72,19 @@ pub fn trigger() {
         .unwrap();
 
     let http_client = {
-        let tls_version = match vars.http_client.min_tls.as_ref() {
-            "1.3" => tls::Version::TLS_1_3,
-            "1.2" => tls::Version::TLS_1_2,
-            "1.1" => {
-                warn!(
-                    r#"
-    You are allowing TLS 1.1 for the global HTTP client.
-    Only do this, if you know what you are doing!
-    "#
-                );
-                tls::Version::TLS_1_1
-            }
-            "1.0" => {
-                warn!(
-                    r#"
-    You are allowing TLS 1.0 for the global HTTP client.
-    Only do this, if you know what you are doing!
-    "#
-                );
-                tls::Version::TLS_1_0
-            }
-            _ => panic!("Invalid value for HTTP_MIN_TLS, allowed: '1.3', '1.2', '1.1', '1.0'"),
-        };
+        let danger_insecure = vars.http_client.danger_insecure || vars.dev.dev_mode;
+        let tls_config = rauthy_data::http_client::tls_config(&vars.http_client, danger_insecure)
+            .unwrap_or_else(|err| panic!("{}", err.message));
 
-        let mut builder = reqwest::Client::builder()
+        reqwest::Client::builder()
             .connect_timeout(Duration::from_secs(vars.http_client.connect_timeout as u64))
             .timeout(Duration::from_secs(vars.http_client.request_timeout as u64))
             .pool_idle_timeout(Duration::from_secs(vars.http_client.idle_timeout as u64))
-            .min_tls_version(tls_version)
             .user_agent(format!("Rauthy Client v{RAUTHY_VERSION}"))
             .https_only(!vars.http_client.danger_unencrypted || !vars.dev.dev_mode)
-            .danger_accept_invalid_certs(vars.http_client.danger_insecure || vars.dev.dev_mode)
-            .use_rustls_tls();
-
-        if let Some(bundle) = vars.http_client.root_ca_bundle.as_ref() {
-            let certs = reqwest::Certificate::from_pem_bundle(bundle.trim().as_bytes())
-                .expect("Cannot parse given HTTP_CUST_ROOT_CA_BUNDLE");
-            debug!(
-                "Adding {} custom Root CA certificates to HTTP Client",
-                certs.len()
-            );
-
-            for cert in certs {
-                builder = builder.add_root_certificate(cert);
-            }
-        }
-
-        builder.build().unwrap()
+            .use_preconfigured_tls(tls_config)
+            .build()
+            .unwrap()
     };
     HTTP_CLIENT.set(http_client).unwrap();
 
@@ -1,6 +1,9 @@
+use rauthy_common::redact::{Redact, redact};
 use rauthy_data::rauthy_config::RauthyConfig;
 use std::env;
-use tracing::Level;
+use std::io::{self, Write};
+use tracing::{Level, Metadata};
+use tracing_subscriber::fmt::MakeWriter;
 
 // Sets up the logging / tracing depending on the env var `LOG_LEVEL`
 pub fn setup_logging() -> tracing::Level {
@@ -30,11 +33,20 @@ pub fn setup_logging() -> tracing::Level {
         }
     }
 
+    let redact_rules = Redact::from_fields(&config.redact_fields)
+        .expect("`[logging].redact_fields` has been validated already");
+    let writer = RedactMakeWriter {
+        enabled: redact_rules.is_enabled(),
+        level: parse_level(&config.redact_level),
+    };
+    redact_rules.init();
+
     if is_log_fmt_json() {
         let subscriber = tracing_subscriber::FmtSubscriber::builder()
             .json()
             .with_max_level(log_level)
             .with_env_filter(filter)
+            .with_writer(writer)
             .finish();
         tracing::subscriber::set_global_default(subscriber)
             .expect("setting default subscriber failed");
@@ -42,6 +54,7 @@ pub fn setup_logging() -> tracing::Level {
         let subscriber = tracing_subscriber::FmtSubscriber::builder()
             .with_max_level(log_level)
             .with_env_filter(filter)
+            .with_writer(writer)
             .finish();
         tracing::subscriber::set_global_default(subscriber)
             .expect("setting default subscriber failed");
@@ -50,6 +63,52 @@ pub fn setup_logging() -> tracing::Level {
     log_level
 }
 
+/// Masks the configured `[logging].redact_fields` in each formatted log line before it is
+/// written to stdout. Because this works on the final output, it catches values from event
+/// messages, `ErrorResponse`s and span attributes all the same.
+struct RedactMakeWriter {
+    enabled: bool,
+    /// Events with this level or a more verbose one will be redacted.
+    level: Level,
+}
+
+impl<'a> MakeWriter<'a> for RedactMakeWriter {
+    type Writer = RedactWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactWriter {
+            redact: self.enabled,
+        }
+    }
+
+    fn make_writer_for(&'a self, meta: &Metadata<'_>) -> Self::Writer {
+        RedactWriter {
+            redact: self.enabled && *meta.level() >= self.level,
+        }
+    }
+}
+
+struct RedactWriter {
+    redact: bool,
+}
+
+impl Write for RedactWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // The fmt layer writes each event as a whole, so we never split a value in two.
+        if self.redact {
+            let line = String::from_utf8_lossy(buf);
+            io::stdout().write_all(redact(&line).as_bytes())?;
+        } else {
+            io::stdout().write_all(buf)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stdout().flush()
+    }
+}
+
 pub fn is_log_fmt_json() -> bool {
     RauthyConfig::get().vars.logging.log_fmt.as_ref() == "json"
 }
@@ -6,10 +6,13 @@ use rauthy_common::password_hasher;
 use rauthy_data::database::{Cache, DB};
 use rauthy_data::email::mailer;
 use rauthy_data::entity::atproto;
+use rauthy_data::entity::auth_providers::AuthProvider;
+use rauthy_data::entity::pairwise_secret::PairwiseSecret;
 use rauthy_data::entity::pictures::UserPicture;
 use rauthy_data::events::health_watch::watch_health;
 use rauthy_data::events::listener::EventListener;
 use rauthy_data::events::notifier::EventNotifier;
+use rauthy_data::migration::fsck;
 use rauthy_data::rauthy_config::RauthyConfig;
 use rauthy_handlers::openapi::ApiDoc;
 use rauthy_handlers::swagger_ui::{OPENAPI_CONFIG, OPENAPI_JSON};
@@ -37,8 +40,12 @@ mod version_migration;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().collect();
+    // `rauthy fsck [--fix]` only checks the database for orphaned rows and exits afterward
+    let fsck_mode =
+        (args.len() > 1 && args[1] == "fsck").then(|| args.iter().any(|a| a == "--fix"));
+
     let (config_file, test_mode) = {
-        let args: Vec<String> = env::args().collect();
         if args.len() > 1 && args[1] == "test" {
             ("config-test.toml", true)
         } else {
@@ -114,6 +121,20 @@ async fn main() -> Result<(), Box<dyn Error>> {
     debug!("Applying database migrations");
     DB::migrate().await.expect("Database migration error");
 
+    if let Some(fix) = fsck_mode {
+        let res = fsck::fsck(fix).await;
+        DB::hql().shutdown().await?;
+        match res {
+            // exit with an error code to make unfixed orphaned rows visible in scripts
+            Ok(count) if count > 0 && !fix => std::process::exit(1),
+            Ok(_) => return Ok(()),
+            Err(err) => {
+                error!("Error during database check: {err}");
+                std::process::exit(1);
+            }
+        }
+    }
+
     debug!("Starting Events handler");
     EventNotifier::init_notifiers(tx_email).await.unwrap();
     tokio::spawn(EventListener::listen(
@@ -135,11 +156,13 @@ async fn main() -> Result<(), Box<dyn Error>> {
     }
 
     UserPicture::test_config().await.unwrap();
+    PairwiseSecret::init().await.unwrap();
 
     // We need to clear some caches
     DB::hql().clear_cache(Cache::Html).await?;
     // whole App cache to make sure config changes are always updated
     DB::hql().clear_cache(Cache::App).await?;
+    AuthProvider::check_email_domain_conflicts().await.unwrap();
 
     #[cfg(debug_assertions)]
     {
@@ -6,6 +6,7 @@ use prometheus::Registry;
 use rauthy_common::is_hiqlite;
 use rauthy_common::utils::UseDummyAddress;
 use rauthy_data::ListenScheme;
+use rauthy_data::entity::ip_rate_limit::TokenRateLimit;
 use rauthy_data::rauthy_config::RauthyConfig;
 use rauthy_handlers::{
     api_keys, atproto, auth_providers, backup, blacklist, clients, cors_preflight, dev_only, email,
@@ -35,6 +36,8 @@ pub async fn server_with_metrics() -> std::io::Result<()> {
     let listen_addr = RauthyConfig::get().vars.server.listen_address.to_string();
 
     let shared_registry = Registry::new();
+    TokenRateLimit::register_metrics(&shared_registry)
+        .expect("Cannot register the token rate limit metrics");
     let metrics = PrometheusMetricsBuilder::new("api")
         .registry(shared_registry.clone())
         .endpoint("/metrics")
@@ -270,8 +273,12 @@ fn api_services() -> actix_web::Scope {
                 .service(atproto::get_atproto_client_metadata)
                 .service(auth_providers::post_providers)
                 .service(auth_providers::get_providers_minimal)
+                .service(auth_providers::get_provider_federations)
+                .service(auth_providers::put_provider_federation)
                 .service(auth_providers::post_provider)
                 .service(auth_providers::post_provider_login)
+                .service(auth_providers::post_provider_recovery)
+                .service(auth_providers::post_provider_discover)
                 .service(auth_providers::get_provider_delete_safe)
                 .service(auth_providers::post_provider_lookup)
                 .service(auth_providers::get_provider_callback_html)
@@ -350,6 +357,7 @@ fn api_services() -> actix_web::Scope {
                 .service(oidc::get_cert_by_kid)
                 .service(oidc::get_logout)
                 .service(oidc::post_logout)
+                .service(oidc::get_redirect_uri_validation)
                 .service(oidc::rotate_jwk)
                 .service(oidc::post_session)
                 .service(oidc::get_session_info)
@@ -363,6 +371,9 @@ fn api_services() -> actix_web::Scope {
                 .service(clients::post_clients)
                 .service(clients::put_clients)
                 .service(clients::put_generate_client_secret)
+                .service(clients::put_custom_client_secret)
+                .service(clients::post_client_tokens_revoke)
+                .service(clients::post_client_token_preview)
                 .service(clients::delete_client)
                 .service(clients::post_clients_dyn)
                 .service(clients::get_clients_dyn)
@@ -410,6 +421,7 @@ fn api_services() -> actix_web::Scope {
                 .service(users::get_users)
                 .service(users::get_users_register)
                 .service(users::post_users_register)
+                .service(users::get_users_pending)
                 .service(html::get_user_password_reset_fixed)
                 .service(users::get_user_values_config)
                 .service(users::get_cust_attr)
@@ -428,14 +440,23 @@ fn api_services() -> actix_web::Scope {
                 .service(users::get_user_devices)
                 .service(users::put_user_device_name)
                 .service(users::delete_user_device)
+                .service(users::post_user_tokens_revoke)
                 .service(users::get_user_webid_data)
                 .service(users::put_user_webid_data)
                 .service(users::get_user_email_confirm)
+                .service(users::get_user_email_change_abort)
+                .service(users::get_user_emails)
+                .service(users::post_user_email)
+                .service(users::get_user_email_add_confirm)
+                .service(users::put_user_email_primary)
+                .service(users::delete_user_email)
                 .service(users::post_user_self_convert_passkey)
                 .service(users::put_user_self_preferred_username)
                 .service(generic::post_password_hash_times)
                 .service(sessions::get_sessions)
                 .service(sessions::delete_sessions)
+                .service(sessions::get_tokens_not_before)
+                .service(sessions::post_tokens_not_before)
                 .service(sessions::delete_session_by_id)
                 .service(sessions::delete_sessions_for_user)
                 .service(tos::get_tos)
@@ -453,7 +474,12 @@ fn api_services() -> actix_web::Scope {
                 .service(users::put_user_self)
                 .service(users::get_user_self_delete_config)
                 .service(users::delete_user_self)
+                .service(users::post_user_self_export)
                 .service(users::delete_user_by_id)
+                .service(users::post_user_disable)
+                .service(users::post_user_enable)
+                .service(users::post_user_approve)
+                .service(users::post_user_reject)
                 .service(users::post_user_password_request_reset)
                 .service(users::get_user_revoke)
                 .service(users::get_user_webauthn_passkeys)
@@ -484,6 +510,9 @@ fn api_services() -> actix_web::Scope {
                 .service(oidc::get_userinfo)
                 .service(oidc::post_userinfo)
                 .service(oidc::get_forward_auth)
+                .service(generic::get_cache)
+                .service(generic::delete_cache)
+                .service(generic::delete_cache_namespace)
                 .service(generic::get_enc_keys)
                 .service(generic::post_migrate_enc_key)
                 .service(generic::ping)
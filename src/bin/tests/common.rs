@@ -1,14 +1,20 @@
 #![allow(dead_code)]
-use rauthy_api_types::oidc::{LoginRequest, SessionInfoResponse, TokenRequest};
+use rauthy_api_types::auth_providers::{ProviderCallbackRequest, ProviderLoginRequest};
+use rauthy_api_types::clients::UpdateClientRequest;
+use rauthy_api_types::oidc::{JwkKeyPairAlg, LoginRequest, SessionInfoResponse, TokenRequest};
+use rauthy_api_types::users::UserResponseSimple;
 use rauthy_common::constants::CSRF_HEADER;
 use rauthy_common::sha256;
-use rauthy_common::utils::base64_url_encode;
+use rauthy_common::utils::{base64_url_encode, base64_url_no_pad_encode};
 use rauthy_service::token_set::TokenSet;
-use reqwest::header::{HeaderMap, HeaderValue, SET_COOKIE};
+use reqwest::header::{COOKIE, HeaderMap, HeaderValue, LOCATION, SET_COOKIE};
 use reqwest::{Response, header};
+use serde_json::{Value, json};
 use spow::pow::Pow;
 use std::error::Error;
-use std::sync::OnceLock;
+use std::sync::{Arc, Mutex, OnceLock};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
 
 #[macro_export]
 macro_rules! aw {
@@ -124,6 +130,7 @@ pub async fn session_headers() -> (HeaderMap, TokenSet) {
         nonce: Some("MySuperNonce".to_string()),
         code_challenge: Some(challenge_s256),
         code_challenge_method: Some("S256".to_string()),
+        captcha: None,
     };
 
     let res = client
@@ -244,3 +251,248 @@ pub async fn get_solved_pow() -> String {
     let pow = res.text().await.unwrap();
     Pow::work(&pow).unwrap()
 }
+
+/// A minimal HTTP server on a random local port, which answers every request with the current
+/// `body` as JSON. Returns its base URL.
+pub async fn mock_json_server(body: Arc<Mutex<Value>>) -> String {
+    mock_server(move || body.lock().unwrap().to_string()).await
+}
+
+/// A minimal upstream provider, which answers every request with a token set, that contains an
+/// `id_token` with the currently set `claims`. Rauthy does not validate the signature of an
+/// upstream `id_token`, so there is no need for real keys.
+pub async fn mock_upstream(claims: Arc<Mutex<Value>>) -> String {
+    mock_server(move || {
+        let id_token = format!(
+            "{}.{}.",
+            base64_url_no_pad_encode(br#"{"alg":"none"}"#),
+            base64_url_no_pad_encode(claims.lock().unwrap().to_string().as_bytes()),
+        );
+        json!({
+            "access_token": "mock",
+            "token_type": "Bearer",
+            "id_token": id_token,
+        })
+        .to_string()
+    })
+    .await
+}
+
+async fn mock_server<F>(json_body: F) -> String
+where
+    F: Fn() -> String + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut stream, _)) = listener.accept().await else {
+                continue;
+            };
+
+            // The request content does not matter, but it must be read fully before the
+            // connection is closed.
+            let mut req = Vec::with_capacity(1024);
+            let mut buf = [0u8; 1024];
+            while let Ok(n) = stream.read(&mut buf).await {
+                if n == 0 {
+                    break;
+                }
+                req.extend_from_slice(&buf[..n]);
+
+                let req_str = String::from_utf8_lossy(&req);
+                if let Some((head, body)) = req_str.split_once("\r\n\r\n") {
+                    let content_length = head
+                        .lines()
+                        .find_map(|l| {
+                            let (k, v) = l.split_once(':')?;
+                            k.eq_ignore_ascii_case("content-length")
+                                .then(|| v.trim().parse::<usize>().ok())?
+                        })
+                        .unwrap_or(0);
+                    if body.len() >= content_length {
+                        break;
+                    }
+                }
+            }
+
+            let body = json_body();
+            let resp = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            let _ = stream.write_all(resp.as_bytes()).await;
+            let _ = stream.shutdown().await;
+        }
+    });
+
+    format!("http://{addr}")
+}
+
+/// The payload for a custom provider in front of the `upstream_url`. The `settings` overwrite
+/// the defaults.
+pub fn provider_payload(name: &str, upstream_url: &str, settings: Value) -> Value {
+    let mut payload = json!({
+        "name": name,
+        "typ": "custom",
+        "enabled": true,
+        "issuer": upstream_url,
+        "authorization_endpoint": format!("{upstream_url}/authorize"),
+        "token_endpoint": format!("{upstream_url}/token"),
+        "userinfo_endpoint": format!("{upstream_url}/userinfo"),
+        "use_pkce": true,
+        "client_secret_basic": false,
+        "client_secret_post": false,
+        "auto_onboarding": true,
+        "auto_link": true,
+        "client_id": "rauthy",
+        "scope": "openid email profile",
+    });
+    if let (Some(payload), Value::Object(settings)) = (payload.as_object_mut(), settings) {
+        payload.extend(settings);
+    }
+    payload
+}
+
+pub async fn create_provider(payload: &Value) -> Result<String, Box<dyn Error>> {
+    let res = reqwest::Client::new()
+        .post(format!("{}/providers/create", get_backend_url()))
+        .headers(get_auth_headers().await?)
+        .json(payload)
+        .send()
+        .await?;
+    let provider = check_status(res, 200).await?.json::<Value>().await?;
+    Ok(provider["id"].as_str().unwrap().to_string())
+}
+
+/// Does a full login via the upstream provider and returns the status of the callback.
+pub async fn provider_login(provider_id: &str) -> Result<u16, Box<dyn Error>> {
+    let res = provider_flow(provider_id, "login").await?;
+    Ok(res.status().as_u16())
+}
+
+/// Does a full account recovery via the upstream provider and returns the callback response.
+pub async fn provider_recovery(provider_id: &str) -> Result<Response, Box<dyn Error>> {
+    provider_flow(provider_id, "recovery").await
+}
+
+/// Starts the flow at `/providers/{start}` and finishes it with the mocked upstream callback.
+async fn provider_flow(provider_id: &str, start: &str) -> Result<Response, Box<dyn Error>> {
+    let backend_url = get_backend_url();
+    let client = reqwest::Client::new();
+
+    let res = client
+        .post(format!("{backend_url}/oidc/session"))
+        .send()
+        .await?;
+    let mut headers = cookie_csrf_headers_from_res_direct(res).await?;
+
+    let challenge_plain = "oDXug9zfYqfz8ejcqMpALRPXfW8QhbKV2AVuScAt8xrLKDAmaRYQ4yRi2uqcH9ys";
+    let challenge_s256 = base64_url_encode(sha256!(challenge_plain.as_bytes()));
+    let payload = ProviderLoginRequest {
+        email: None,
+        client_id: "rauthy".to_string(),
+        redirect_uri: format!("{backend_url}/oidc/callback"),
+        scopes: None,
+        state: None,
+        nonce: None,
+        code_challenge: Some(challenge_s256.clone()),
+        code_challenge_method: Some("S256".to_string()),
+        pow: get_solved_pow().await,
+        provider_id: provider_id.to_string(),
+        pkce_challenge: challenge_s256,
+        handle: None,
+    };
+    let res = client
+        .post(format!("{backend_url}/providers/{start}"))
+        .headers(headers.clone())
+        .json(&payload)
+        .send()
+        .await?;
+    let res = check_status(res, 202).await?;
+
+    let location = res.headers().get(LOCATION).unwrap().to_str()?.to_string();
+    let (_, state) = location.split_once("state=").unwrap();
+    let state = state.split('&').next().unwrap().to_string();
+    let callback_cookie = res
+        .headers()
+        .get_all(SET_COOKIE)
+        .iter()
+        .filter_map(|c| c.to_str().ok()?.split_once(';').map(|(c, _)| c.to_string()))
+        .find(|c| c.contains("UpstreamAuthCallback="))
+        .unwrap();
+    let xsrf_token = res.text().await?;
+
+    let session_cookie = headers.remove(COOKIE).unwrap();
+    headers.insert(
+        COOKIE,
+        HeaderValue::from_str(&format!("{}; {callback_cookie}", session_cookie.to_str()?))?,
+    );
+
+    let payload = ProviderCallbackRequest {
+        state,
+        code: "mock".to_string(),
+        xsrf_token,
+        pkce_verifier: challenge_plain.to_string(),
+        iss_atproto: None,
+    };
+    let res = client
+        .post(format!("{backend_url}/providers/callback"))
+        .headers(headers)
+        .json(&payload)
+        .send()
+        .await?;
+
+    Ok(res)
+}
+
+pub async fn find_user(email: &str) -> Result<Option<UserResponseSimple>, Box<dyn Error>> {
+    let res = reqwest::Client::new()
+        .get(format!("{}/users", get_backend_url()))
+        .headers(get_auth_headers().await?)
+        .send()
+        .await?;
+    let users = check_status(res, 200)
+        .await?
+        .json::<Vec<UserResponseSimple>>()
+        .await?;
+    Ok(users.into_iter().find(|u| u.email == email))
+}
+
+/// A valid update for a public client with the `authorization_code` flow. Tests only set the
+/// values they care about and take the rest via the struct update syntax.
+pub fn update_client_request(id: &str) -> UpdateClientRequest {
+    UpdateClientRequest {
+        id: id.to_string(),
+        name: None,
+        confidential: false,
+        redirect_uris: Vec::default(),
+        post_logout_redirect_uris: None,
+        allowed_origins: None,
+        enabled: true,
+        flows_enabled: vec!["authorization_code".to_string()],
+        access_token_alg: JwkKeyPairAlg::EdDSA,
+        id_token_alg: JwkKeyPairAlg::EdDSA,
+        auth_code_lifetime: 60,
+        access_token_lifetime: 60,
+        scopes: vec!["openid".to_string()],
+        default_scopes: vec!["openid".to_string()],
+        challenges: None,
+        force_mfa: false,
+        client_uri: None,
+        contacts: None,
+        backchannel_logout_uri: None,
+        restrict_group_prefix: None,
+        introspect: false,
+        claim_mapping: None,
+        claim_namespace: None,
+        refresh_token_max_lifetime: None,
+        require_nonce: false,
+        first_party: false,
+        subject_type: None,
+        uri_match_mode: None,
+        sector_identifier_uri: None,
+        scim: None,
+    }
+}
@@ -1,14 +1,15 @@
 use crate::common::{
     CLIENT_ID, CLIENT_SECRET, PASSWORD, USERNAME, check_status, code_state_from_headers,
     cookie_csrf_headers_from_res, get_auth_headers, get_backend_url, get_solved_pow,
-    init_client_bcl_uri,
+    init_client_bcl_uri, update_client_request,
 };
 use actix_web::{App, HttpResponse, HttpServer, http, web};
 use chrono::Utc;
 use ed25519_compact::Noise;
 use josekit::jwk;
 use pretty_assertions::assert_eq;
-use rauthy_api_types::clients::UpdateClientRequest;
+use rauthy_api_types::clients::{ClientSecretResponse, NewClientRequest, UpdateClientRequest};
+use rauthy_api_types::generic::TokensRevokedResponse;
 use rauthy_api_types::oidc::{
     JktClaim, JwkKeyPairAlg, LoginRequest, TokenInfo, TokenRequest, TokenRevocationRequest,
     TokenValidationRequest,
@@ -24,7 +25,7 @@ use rauthy_data::entity::jwk::{JWKS, JWKSPublicKey, JwkKeyPairType};
 use rauthy_error::{ErrorResponse, ErrorResponseType};
 use rauthy_jwt::claims::JwtTokenType;
 use rauthy_service::token_set::TokenSet;
-use reqwest::header::AUTHORIZATION;
+use reqwest::header::{self, AUTHORIZATION};
 use ring::digest;
 use std::error::Error;
 use std::fmt::Write;
@@ -49,9 +50,20 @@ async fn test_certs() -> Result<(), Box<dyn Error>> {
     let url = format!("{}/oidc/certs", backend_url);
     let res = reqwest::get(&url).await?;
     assert_eq!(res.status(), 200);
+    let etag = res.headers().get(header::ETAG).unwrap().clone();
+    assert!(res.headers().get(header::CACHE_CONTROL).is_some());
     let certs = res.json::<JWKS>().await?;
     assert_eq!(certs.keys.len(), 4);
 
+    // an unchanged key set must be answered with a 304
+    let res = reqwest::Client::new()
+        .get(&url)
+        .header(header::IF_NONE_MATCH, etag.clone())
+        .send()
+        .await?;
+    assert_eq!(res.status(), 304);
+    assert_eq!(res.headers().get(header::ETAG), Some(&etag));
+
     // for _ in 1..1000 {
     //     aw!(reqwest::get(&url)).unwrap();
     // }
@@ -67,9 +79,18 @@ async fn test_certs() -> Result<(), Box<dyn Error>> {
     // get all certs and check for the new ones
     let res = reqwest::get(&url).await?;
     assert_eq!(res.status(), 200);
+    assert_ne!(res.headers().get(header::ETAG), Some(&etag));
     let new_certs = res.json::<JWKS>().await?;
     assert_eq!(new_certs.keys.len(), 8);
 
+    // the old ETag must not match anymore after the rotation
+    let res = reqwest::Client::new()
+        .get(&url)
+        .header(header::IF_NONE_MATCH, etag)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
+
     Ok(())
 }
 
@@ -109,6 +130,7 @@ async fn test_authorization_code_flow() -> Result<(), Box<dyn Error>> {
         nonce: Some(nonce.to_owned()),
         code_challenge: Some(challenge_plain.to_owned()),
         code_challenge_method: Some("plain".to_string()),
+        captcha: None,
     };
     let res = reqwest::Client::new()
         .post(&url_auth)
@@ -297,6 +319,15 @@ async fn test_authorization_code_flow() -> Result<(), Box<dyn Error>> {
         contacts: None,
         backchannel_logout_uri: Some(init_client_bcl_uri()),
         restrict_group_prefix: None,
+        introspect: true,
+        claim_mapping: None,
+        claim_namespace: None,
+        refresh_token_max_lifetime: None,
+        require_nonce: false,
+        first_party: false,
+        subject_type: None,
+        uri_match_mode: None,
+        sector_identifier_uri: None,
         scim: None,
     };
     let url_client = format!("{}/clients/{}", backend_url, CLIENT_ID);
@@ -358,6 +389,91 @@ async fn test_authorization_code_flow() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_auth_code_flow_pkce_state() -> Result<(), Box<dyn Error>> {
+    let backend_url = get_backend_url();
+    let client = reqwest::Client::new();
+
+    // the `rauthy` client is public and must always use PKCE
+    let redirect_uri = format!("{}/oidc/callback", backend_url);
+    let query = format!(
+        "client_id=rauthy&redirect_uri={}&response_type=code&state=MyState",
+        redirect_uri
+    );
+    let url_auth = format!("{}/oidc/authorize?{}", backend_url, query);
+    let res = client.get(&url_auth).send().await?;
+    // should be 400 - missing code_challenge
+    check_status(res, 400).await?;
+
+    let verifier = get_rand(64);
+    let challenge_s256 =
+        base64_url_encode(digest::digest(&digest::SHA256, verifier.as_bytes()).as_ref());
+    let url_auth = format!(
+        "{}/oidc/authorize?{}&code_challenge={}&code_challenge_method=S256",
+        backend_url, query, challenge_s256
+    );
+    let mut res = client.get(&url_auth).send().await?;
+    res = check_status(res, 200).await?;
+    let headers = cookie_csrf_headers_from_res(res).await?;
+
+    let mut req_login = LoginRequest {
+        email: USERNAME.to_string(),
+        password: Some(PASSWORD.to_string()),
+        pow: get_solved_pow().await,
+        client_id: "rauthy".to_string(),
+        redirect_uri: redirect_uri.clone(),
+        scopes: None,
+        state: Some("NotMyState".to_string()),
+        nonce: None,
+        code_challenge: Some(challenge_s256.clone()),
+        code_challenge_method: Some("S256".to_string()),
+        captcha: None,
+    };
+    let res = client
+        .post(&url_auth)
+        .headers(headers.clone())
+        .json(&req_login)
+        .send()
+        .await?;
+    // should be 400 - the state was not bound to this session
+    check_status(res, 400).await?;
+
+    req_login.state = Some("MyState".to_string());
+    req_login.pow = get_solved_pow().await;
+    let mut res = client
+        .post(&url_auth)
+        .headers(headers.clone())
+        .json(&req_login)
+        .send()
+        .await?;
+    res = check_status(res, 202).await?;
+    let (code, state) = code_state_from_headers(res)?;
+    assert_eq!(state.as_deref(), Some("MyState"));
+
+    let mut req_token = TokenRequest {
+        grant_type: "authorization_code".to_string(),
+        code: Some(code),
+        redirect_uri: Some(redirect_uri),
+        client_id: Some("rauthy".to_string()),
+        client_secret: None,
+        code_verifier: Some(get_rand(64)),
+        device_code: None,
+        username: None,
+        password: None,
+        refresh_token: None,
+    };
+    let url_token = format!("{}/oidc/token", backend_url);
+    let res = client.post(&url_token).form(&req_token).send().await?;
+    // should be 401 - wrong code verifier
+    check_status(res, 401).await?;
+
+    req_token.code_verifier = Some(verifier);
+    let res = client.post(&url_token).form(&req_token).send().await?;
+    check_status(res, 200).await?;
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_client_credentials_flow() -> Result<(), Box<dyn Error>> {
     let backend_url = get_backend_url();
@@ -436,6 +552,7 @@ async fn test_concurrent_logins() -> Result<(), Box<dyn Error>> {
         nonce: None,
         code_challenge: Some(challenge_plain.to_owned()),
         code_challenge_method: None,
+        captcha: None,
     };
 
     let start = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
@@ -793,6 +910,7 @@ async fn test_auth_code_flow_ephemeral_client() -> Result<(), Box<dyn Error>> {
         nonce: Some(nonce.to_owned()),
         code_challenge: Some(challenge_s256),
         code_challenge_method: Some("S256".to_string()),
+        captcha: None,
     };
     let res = client
         .post(&url_auth)
@@ -1031,6 +1149,54 @@ async fn test_token_introspection() -> Result<(), Box<dyn Error>> {
     let info = serde_json::from_str::<TokenInfo>(&text)?;
     assert!(info.active);
 
+    // a client without the `introspect` flag must not be allowed to introspect
+    let auth_headers = get_auth_headers().await?;
+    let new_client = NewClientRequest {
+        id: "no_introspect".to_string(),
+        secret: None,
+        name: None,
+        confidential: true,
+        redirect_uris: vec!["http://localhost:8080/callback".to_string()],
+        post_logout_redirect_uris: None,
+        uri_match_mode: None,
+    };
+    let res = client
+        .post(format!("{}/clients", backend_url))
+        .headers(auth_headers.clone())
+        .json(&new_client)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
+    let res = client
+        .post(format!("{}/clients/no_introspect/secret", backend_url))
+        .headers(auth_headers.clone())
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
+    let secret = res.json::<ClientSecretResponse>().await?.secret.unwrap();
+
+    let plain = format!("no_introspect:{}", secret);
+    let res = client
+        .post(&url)
+        .header(
+            AUTHORIZATION,
+            format!("Basic {}", base64_encode(plain.as_bytes())),
+        )
+        .form(&payload)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 403);
+    let err = res.json::<ErrorResponse>().await?;
+    assert_eq!(err.error, ErrorResponseType::Forbidden);
+    assert!(err.message.starts_with("access_denied"));
+
+    let res = client
+        .delete(format!("{}/clients/no_introspect", backend_url))
+        .headers(auth_headers)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
+
     // make sure requests with invalid tokens return inactive
     payload.token = base64_encode(b"IAmTotallyInvalid");
     let res = client
@@ -1094,15 +1260,15 @@ async fn test_token_revocation() -> Result<(), Box<dyn Error>> {
         .unwrap();
     assert!(res.status().is_success());
 
+    // a revoked token is just an inactive one at the introspection (RFC 7662 2.2)
     let res = validate_token_request(ts.access_token.clone())
         .await
         .unwrap();
-    assert!(!res.status().is_success());
-    let err = res.json::<ErrorResponse>().await?;
-    assert_eq!(err.error, ErrorResponseType::Unauthorized);
-    assert_eq!(err.message, "token was revoked");
+    assert!(res.status().is_success());
+    let text = res.text().await?;
+    assert!(!serde_json::from_str::<TokenInfo>(&text)?.active);
 
-    // make sure it works for the userinfo in the same way
+    // the userinfo rejects it
     let res = reqwest::Client::new()
         .get(&format!("{}/oidc/userinfo", get_backend_url()))
         .header(AUTHORIZATION, format!("Bearer {}", ts.access_token))
@@ -1135,10 +1301,178 @@ async fn test_token_revocation() -> Result<(), Box<dyn Error>> {
     let res = validate_token_request(ts.access_token.clone())
         .await
         .unwrap();
+    assert!(res.status().is_success());
+    let text = res.text().await?;
+    assert!(!serde_json::from_str::<TokenInfo>(&text)?.active);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_client_tokens_revoke() -> Result<(), Box<dyn Error>> {
+    let backend_url = get_backend_url();
+    let client = reqwest::Client::new();
+    let auth_headers = get_auth_headers().await?;
+
+    let client_id = "revoke_tokens";
+    let new_client = NewClientRequest {
+        id: client_id.to_string(),
+        secret: None,
+        name: None,
+        confidential: true,
+        redirect_uris: vec!["http://localhost:8080/callback".to_string()],
+        post_logout_redirect_uris: None,
+        uri_match_mode: None,
+    };
+    let res = client
+        .post(format!("{}/clients", backend_url))
+        .headers(auth_headers.clone())
+        .json(&new_client)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
+
+    let update_client = UpdateClientRequest {
+        confidential: true,
+        redirect_uris: new_client.redirect_uris.clone(),
+        flows_enabled: vec![
+            "client_credentials".to_string(),
+            "password".to_string(),
+            "refresh_token".to_string(),
+        ],
+        ..update_client_request(client_id)
+    };
+    let res = client
+        .put(format!("{}/clients/{}", backend_url, client_id))
+        .headers(auth_headers.clone())
+        .json(&update_client)
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    let res = client
+        .post(format!("{}/clients/{}/secret", backend_url, client_id))
+        .headers(auth_headers.clone())
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
+    let secret = res.json::<ClientSecretResponse>().await?.secret.unwrap();
+
+    let token_req = TokenRequest {
+        grant_type: "client_credentials".to_string(),
+        code: None,
+        redirect_uri: None,
+        client_id: Some(client_id.to_string()),
+        client_secret: Some(secret),
+        code_verifier: None,
+        device_code: None,
+        username: None,
+        password: None,
+        refresh_token: None,
+    };
+    let url_token = format!("{}/oidc/token", backend_url);
+    let res = client.post(&url_token).form(&token_req).send().await?;
+    assert_eq!(res.status(), 200);
+    let ts = res.json::<TokenSet>().await?;
+
+    // a user login for the same client, which issues a refresh token as well
+    let password_req = TokenRequest {
+        grant_type: "password".to_string(),
+        code: None,
+        redirect_uri: None,
+        client_id: token_req.client_id.clone(),
+        client_secret: token_req.client_secret.clone(),
+        code_verifier: None,
+        device_code: None,
+        username: Some(USERNAME.to_string()),
+        password: Some(PASSWORD.to_string()),
+        refresh_token: None,
+    };
+    let res = client.post(&url_token).form(&password_req).send().await?;
+    assert_eq!(res.status(), 200);
+    let refresh_token = res.json::<TokenSet>().await?.refresh_token.unwrap();
+    let refresh_req = TokenRequest {
+        grant_type: "refresh_token".to_string(),
+        code: None,
+        redirect_uri: None,
+        client_id: token_req.client_id.clone(),
+        client_secret: token_req.client_secret.clone(),
+        code_verifier: None,
+        device_code: None,
+        username: None,
+        password: None,
+        refresh_token: Some(refresh_token),
+    };
+
+    // the init_client is allowed to introspect
+    let url_introspect = format!("{}/oidc/introspect", backend_url);
+    let plain = format!("{}:{}", CLIENT_ID, CLIENT_SECRET);
+    let basic = format!("Basic {}", base64_encode(plain.as_bytes()));
+    let payload = TokenValidationRequest {
+        token: ts.access_token,
+    };
+    let res = client
+        .post(&url_introspect)
+        .header(AUTHORIZATION, &basic)
+        .form(&payload)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
+    let text = res.text().await?;
+    assert!(serde_json::from_str::<TokenInfo>(&text)?.active);
+
+    let res = client
+        .post(format!(
+            "{}/clients/{}/tokens/revoke",
+            backend_url, client_id
+        ))
+        .headers(auth_headers.clone())
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
+    let revoked = res.json::<TokensRevokedResponse>().await?;
+    assert_eq!(revoked.access_tokens, 2);
+    assert_eq!(revoked.refresh_tokens, 1);
+
+    let res = client
+        .post(&url_introspect)
+        .header(AUTHORIZATION, &basic)
+        .form(&payload)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
+    let text = res.text().await?;
+    assert!(!serde_json::from_str::<TokenInfo>(&text)?.active);
+
+    // new tokens must be accepted again
+    time::sleep(Duration::from_millis(1100)).await;
+
+    // The refresh token has been issued in the same second as the revocation, which the
+    // client `nbf` alone would not cover.
+    let res = client.post(&url_token).form(&refresh_req).send().await?;
     assert!(!res.status().is_success());
-    let err = res.json::<ErrorResponse>().await?;
-    assert_eq!(err.error, ErrorResponseType::Unauthorized);
-    assert_eq!(err.message, "token was revoked");
+
+    let res = client.post(&url_token).form(&token_req).send().await?;
+    assert_eq!(res.status(), 200);
+    let payload = TokenValidationRequest {
+        token: res.json::<TokenSet>().await?.access_token,
+    };
+    let res = client
+        .post(&url_introspect)
+        .header(AUTHORIZATION, &basic)
+        .form(&payload)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
+    let text = res.text().await?;
+    assert!(serde_json::from_str::<TokenInfo>(&text)?.active);
+
+    let res = client
+        .delete(format!("{}/clients/{}", backend_url, client_id))
+        .headers(auth_headers)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
 
     Ok(())
 }
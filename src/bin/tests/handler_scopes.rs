@@ -29,6 +29,7 @@ async fn test_scopes() -> Result<(), Box<dyn Error>> {
         scope: "scope123".to_string(),
         attr_include_access: None,
         attr_include_id: None,
+        required_roles: None,
     };
     let res = client
         .post(&url)
@@ -79,6 +80,15 @@ async fn test_scopes() -> Result<(), Box<dyn Error>> {
         contacts: init_client.contacts,
         backchannel_logout_uri: Some(init_client_bcl_uri()),
         restrict_group_prefix: None,
+        introspect: init_client.introspect,
+        claim_mapping: init_client.claim_mapping,
+        claim_namespace: init_client.claim_namespace,
+        refresh_token_max_lifetime: init_client.refresh_token_max_lifetime,
+        require_nonce: init_client.require_nonce,
+        first_party: init_client.first_party,
+        subject_type: Some(init_client.subject_type),
+        uri_match_mode: None,
+        sector_identifier_uri: init_client.sector_identifier_uri,
         scim: None,
     };
     let res = client
@@ -97,6 +107,7 @@ async fn test_scopes() -> Result<(), Box<dyn Error>> {
         scope: "scope456".to_string(),
         attr_include_access: None,
         attr_include_id: None,
+        required_roles: None,
     };
     let url_name = format!("{}/{}", url, scope.id);
     let res = client
@@ -1,11 +1,13 @@
 use crate::common::{
-    get_auth_headers, get_backend_url, get_solved_pow, get_token_set, get_token_set_init_client,
+    USERNAME, get_auth_headers, get_backend_url, get_solved_pow, get_token_set,
+    get_token_set_init_client,
 };
 use pretty_assertions::assert_eq;
 use rauthy_api_types::api_keys::{AccessGroup, AccessRights, ApiKeyAccess, ApiKeyRequest};
 use rauthy_api_types::generic::Language;
 use rauthy_api_types::users::{
-    NewUserRequest, RequestResetRequest, UserResponse, UserResponseSimple, Userinfo,
+    NewUserRequest, PendingRegistrationResponse, RequestResetRequest, UserResponse,
+    UserResponseSimple, Userinfo,
 };
 use rauthy_common::utils::new_store_id;
 use reqwest::StatusCode;
@@ -161,6 +163,54 @@ async fn test_password_reset_always_ok() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_pending_registrations() -> Result<(), Box<dyn Error>> {
+    let auth_headers = get_auth_headers().await?;
+    let client = reqwest::Client::new();
+
+    // the test backend runs without `require_approval`
+    let res = client
+        .get(format!("{}/users/pending", get_backend_url()))
+        .headers(auth_headers.clone())
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
+    let pending = res.json::<Vec<PendingRegistrationResponse>>().await?;
+    assert!(pending.is_empty());
+
+    let res = client
+        .get(format!("{}/users", get_backend_url()))
+        .headers(auth_headers.clone())
+        .send()
+        .await?;
+    let users = res.json::<Vec<UserResponseSimple>>().await?;
+    let admin = users.into_iter().find(|u| u.email == USERNAME).unwrap();
+
+    // active users must never be touched by an approval or rejection
+    for action in ["approve", "reject"] {
+        let res = client
+            .post(format!(
+                "{}/users/{}/{}",
+                get_backend_url(),
+                admin.id,
+                action
+            ))
+            .headers(auth_headers.clone())
+            .send()
+            .await?;
+        assert_eq!(res.status(), 400);
+    }
+
+    let res = client
+        .get(format!("{}/users/{}", get_backend_url(), admin.id))
+        .headers(auth_headers)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_userinfo() -> Result<(), Box<dyn Error>> {
     let url = format!("{}/oidc/userinfo", get_backend_url());
@@ -202,7 +252,7 @@ async fn test_user_picture() -> Result<(), Box<dyn Error>> {
     let users = res.json::<Vec<UserResponseSimple>>().await?;
     let user_id_admin = users
         .iter()
-        .find(|u| u.email == "admin@localhost")
+        .find(|u| u.email == USERNAME)
         .unwrap()
         .id
         .clone();
@@ -8,7 +8,8 @@ use rauthy_api_types::users::{
 };
 use rauthy_common::utils::base64_url_no_pad_decode;
 use rauthy_data::entity::user_attr::UserAttrConfigEntity;
-use rauthy_jwt::claims::JwtAccessClaims;
+use rauthy_jwt::claims::{JwtAccessClaims, JwtIdClaims};
+use reqwest::header::AUTHORIZATION;
 use serde_json::Value;
 use std::error::Error;
 
@@ -38,6 +39,7 @@ async fn test_cust_attrs() -> Result<(), Box<dyn Error>> {
         default_value: None,
         typ: None,
         user_editable: None,
+        internal: None,
     };
     let res = client
         .post(&url_attrs)
@@ -55,6 +57,7 @@ async fn test_cust_attrs() -> Result<(), Box<dyn Error>> {
         scope: "cust_scope".to_string(),
         attr_include_access: None,
         attr_include_id: None,
+        required_roles: None,
     };
     let url_scopes = format!("{}/scopes", backend_url);
     let res = client
@@ -74,6 +77,7 @@ async fn test_cust_attrs() -> Result<(), Box<dyn Error>> {
         scope: "cust_scope".to_string(),
         attr_include_access: Some(vec!["cust1".to_string()]),
         attr_include_id: Some(vec!["cust1".to_string()]),
+        required_roles: None,
     };
     let url_scope = format!("{}/{}", url_scopes, scope.id);
     let res = client
@@ -131,6 +135,15 @@ async fn test_cust_attrs() -> Result<(), Box<dyn Error>> {
         contacts: None,
         backchannel_logout_uri: None,
         restrict_group_prefix: None,
+        introspect: c.introspect,
+        claim_mapping: c.claim_mapping,
+        claim_namespace: c.claim_namespace,
+        refresh_token_max_lifetime: c.refresh_token_max_lifetime,
+        require_nonce: c.require_nonce,
+        first_party: c.first_party,
+        subject_type: Some(c.subject_type),
+        uri_match_mode: None,
+        sector_identifier_uri: c.sector_identifier_uri,
         scim: None,
     };
     let res = client
@@ -186,6 +199,7 @@ async fn test_cust_attrs() -> Result<(), Box<dyn Error>> {
         default_value: None,
         typ: None,
         user_editable: None,
+        internal: None,
     };
     let url_attr_mod = format!("{}/users/attr/{}", backend_url, cust_attr.name);
     let res = client
@@ -248,6 +262,57 @@ async fn test_cust_attrs() -> Result<(), Box<dyn Error>> {
         &Value::String("Some String".to_string())
     );
 
+    // mark the attr as internal - it must never be released anymore, even though it is mapped
+    let cust_attr_internal = UserAttrConfigRequest {
+        name: "cust2".to_string(),
+        desc: Some("some description 2".to_string()),
+        default_value: None,
+        typ: None,
+        user_editable: None,
+        internal: Some(true),
+    };
+    let url_attr_internal = format!("{}/users/attr/cust2", backend_url);
+    let res = client
+        .put(&url_attr_internal)
+        .headers(auth_headers.clone())
+        .json(&cust_attr_internal)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
+    let attr = res.json::<UserAttrConfigEntity>().await?;
+    assert!(attr.internal);
+
+    // the admin API still returns the value
+    let res = client
+        .get(&url_user_attr)
+        .headers(auth_headers.clone())
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
+    let resp = res.json::<UserAttrValuesResponse>().await?;
+    assert_eq!(resp.values.len(), 1);
+
+    // neither the access nor the id token contain the attr
+    let token = get_token_set().await;
+    let bytes = extract_raw_claims(&token.access_token);
+    let claims = serde_json::from_slice::<JwtAccessClaims>(&bytes).unwrap();
+    assert!(claims.custom.is_none());
+    let id_token = token.id_token.as_deref().unwrap();
+    let bytes = extract_raw_claims(id_token);
+    let claims = serde_json::from_slice::<JwtIdClaims>(&bytes).unwrap();
+    assert!(claims.custom.is_none());
+
+    // the same is true for the userinfo
+    let res = client
+        .get(format!("{}/oidc/userinfo", backend_url))
+        .header(AUTHORIZATION, format!("Bearer {}", token.access_token))
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
+    let info = res.json::<Value>().await?;
+    assert!(info.get("cust2").is_none());
+    assert!(!info.to_string().contains("Some String"));
+
     // delete the custom attr and make sure its properly cleaned up everywhere
     // add a new attr
     let url_attr_del = format!("{}/users/attr/cust2", backend_url);
@@ -1,8 +1,8 @@
 use crate::common::{CLIENT_ID, CLIENT_SECRET, get_auth_headers, get_backend_url};
 use pretty_assertions::{assert_eq, assert_ne};
 use rauthy_api_types::clients::{
-    ClientResponse, ClientSecretRequest, ClientSecretResponse, NewClientRequest,
-    UpdateClientRequest,
+    ClientResponse, ClientSecretRequest, ClientSecretResponse, ClientSecretSetRequest,
+    NewClientRequest, UpdateClientRequest,
 };
 use rauthy_api_types::oidc::{JwkKeyPairAlg, TokenRequest};
 use rauthy_common::constants::APPLICATION_JSON;
@@ -50,6 +50,50 @@ async fn test_clients() -> Result<(), Box<dyn Error>> {
     assert_eq!(client.scopes.len(), 2);
     assert_eq!(client.default_scopes.len(), 2);
     assert_eq!(client.challenges.as_ref().unwrap().len(), 1);
+    // the admin UI client is always trusted
+    assert!(client.first_party);
+
+    // cursor pagination ordered by id
+    let res = reqwest::Client::new()
+        .get(format!("{}?limit=1", url))
+        .headers(auth_headers.clone())
+        .send()
+        .await?;
+    assert_eq!(res.status(), 206);
+    let cursor = res
+        .headers()
+        .get("x-next-cursor")
+        .unwrap()
+        .to_str()?
+        .to_string();
+    let clients = res.json::<Vec<ClientResponse>>().await?;
+    assert_eq!(clients.len(), 1);
+    assert_eq!(clients[0].id, "init_client");
+
+    let res = reqwest::Client::new()
+        .get(format!("{}?limit=1&cursor={}", url, cursor))
+        .headers(auth_headers.clone())
+        .send()
+        .await?;
+    assert_eq!(res.status(), 206);
+    let clients = res.json::<Vec<ClientResponse>>().await?;
+    assert_eq!(clients.len(), 1);
+    assert_eq!(clients[0].id, "rauthy");
+
+    // a modified cursor must be rejected
+    let mut tampered = cursor.into_bytes();
+    let last = tampered.len() - 1;
+    tampered[last] = if tampered[last] == b'A' { b'B' } else { b'A' };
+    let res = reqwest::Client::new()
+        .get(format!(
+            "{}?limit=1&cursor={}",
+            url,
+            String::from_utf8(tampered)?
+        ))
+        .headers(auth_headers.clone())
+        .send()
+        .await?;
+    assert_eq!(res.status(), 400);
 
     // add a new client
     let new_client = NewClientRequest {
@@ -59,6 +103,7 @@ async fn test_clients() -> Result<(), Box<dyn Error>> {
         confidential: true,
         redirect_uris: vec!["http://test.client.io/callback".to_string()],
         post_logout_redirect_uris: Some(vec!["http://test.client.io/logout".to_string()]),
+        uri_match_mode: None,
     };
     let res = reqwest::Client::new()
         .post(&url)
@@ -124,6 +169,15 @@ async fn test_clients() -> Result<(), Box<dyn Error>> {
         ]),
         backchannel_logout_uri: None,
         restrict_group_prefix: None,
+        introspect: false,
+        claim_mapping: None,
+        claim_namespace: None,
+        refresh_token_max_lifetime: None,
+        require_nonce: false,
+        first_party: false,
+        subject_type: None,
+        uri_match_mode: None,
+        sector_identifier_uri: None,
         scim: None,
     };
 
@@ -262,7 +316,7 @@ async fn test_client_secret() -> Result<(), Box<dyn Error>> {
     let res = client.post(&url_token).form(&token_req).send().await?;
     assert_eq!(res.status(), 200);
 
-    // make sure we get the same secret back
+    // generated secrets are only shown once
     let res = client
         .post(&url)
         .headers(auth_headers.clone())
@@ -270,8 +324,8 @@ async fn test_client_secret() -> Result<(), Box<dyn Error>> {
         .await?;
     assert!(res.status().is_success());
     let resp = res.json::<ClientSecretResponse>().await?;
-    assert!(resp.secret.is_some());
-    assert_eq!(resp.secret.unwrap(), secret_new);
+    assert!(resp.confidential);
+    assert!(resp.secret.is_none());
 
     // rotate secret gracefully and cache the current one
     let payload = ClientSecretRequest {
@@ -293,7 +347,41 @@ async fn test_client_secret() -> Result<(), Box<dyn Error>> {
     let res = client.post(&url_token).form(&token_req).send().await?;
     assert_eq!(res.status(), 200);
 
-    token_req.client_secret = Some(secret_rotated);
+    token_req.client_secret = Some(secret_rotated.clone());
+    let res = client.post(&url_token).form(&token_req).send().await?;
+    assert_eq!(res.status(), 200);
+
+    // a manually set secret must match the policy
+    let url_custom = format!("{url}/custom");
+    let mut payload = ClientSecretSetRequest {
+        cache_current_hours: None,
+        secret: "secret12".repeat(8),
+    };
+    let res = client
+        .put(&url_custom)
+        .headers(auth_headers.clone())
+        .json(&payload)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 400);
+
+    // the current secret must not have been touched
+    let res = client.post(&url_token).form(&token_req).send().await?;
+    assert_eq!(res.status(), 200);
+
+    // a generated secret always matches the policy -> set the original one again
+    payload.secret = CLIENT_SECRET.to_string();
+    let res = client
+        .put(&url_custom)
+        .headers(auth_headers.clone())
+        .json(&payload)
+        .send()
+        .await?;
+    assert!(res.status().is_success());
+    let resp = res.json::<ClientSecretResponse>().await?;
+    assert!(resp.secret.is_none());
+
+    token_req.client_secret = Some(CLIENT_SECRET.to_string());
     let res = client.post(&url_token).form(&token_req).send().await?;
     assert_eq!(res.status(), 200);
 
@@ -15,6 +15,7 @@ async fn test_dynamic_client() -> Result<(), Box<dyn Error>> {
     let mut payload = DynamicClientRequest {
         redirect_uris: vec!["http://localhost:8080/*".to_string()],
         grant_types: vec!["authorization_code".to_string()],
+        response_types: None,
         client_name: Some("Dyn Test Client 123".to_string()),
         client_uri: None,
         contacts: None,
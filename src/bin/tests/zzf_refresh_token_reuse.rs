@@ -0,0 +1,97 @@
+use crate::common::{CLIENT_ID, CLIENT_SECRET, PASSWORD, USERNAME, check_status, get_backend_url};
+use pretty_assertions::assert_ne;
+use rauthy_api_types::oidc::TokenRequest;
+use rauthy_service::token_set::TokenSet;
+use std::error::Error;
+use std::time::Duration;
+use tokio::time;
+
+mod common;
+
+fn refresh_req(refresh_token: &str) -> TokenRequest {
+    TokenRequest {
+        grant_type: "refresh_token".to_string(),
+        code: None,
+        redirect_uri: None,
+        client_id: Some(CLIENT_ID.to_string()),
+        client_secret: Some(CLIENT_SECRET.to_string()),
+        code_verifier: None,
+        device_code: None,
+        username: None,
+        password: None,
+        refresh_token: Some(refresh_token.to_string()),
+    }
+}
+
+async fn refresh(refresh_token: &str) -> Result<reqwest::Response, Box<dyn Error>> {
+    let url = format!("{}/oidc/token", get_backend_url());
+    let res = reqwest::Client::new()
+        .post(&url)
+        .form(&refresh_req(refresh_token))
+        .send()
+        .await?;
+    Ok(res)
+}
+
+async fn login() -> Result<String, Box<dyn Error>> {
+    let url = format!("{}/oidc/token", get_backend_url());
+    let body = TokenRequest {
+        grant_type: "password".to_string(),
+        code: None,
+        redirect_uri: None,
+        client_id: Some(CLIENT_ID.to_string()),
+        client_secret: Some(CLIENT_SECRET.to_string()),
+        code_verifier: None,
+        device_code: None,
+        username: Some(USERNAME.to_string()),
+        password: Some(PASSWORD.to_string()),
+        refresh_token: None,
+    };
+    let res = reqwest::Client::new().post(&url).form(&body).send().await?;
+    assert_eq!(res.status(), 200);
+    let ts = res.json::<TokenSet>().await?;
+    Ok(ts.refresh_token.unwrap())
+}
+
+#[tokio::test]
+async fn test_refresh_token_concurrent_and_replay() -> Result<(), Box<dyn Error>> {
+    let rt_orig = login().await?;
+    // a second, independent login for the same user
+    let rt_other = login().await?;
+
+    // make sure the `iat` differs, so we get a new refresh token
+    time::sleep(Duration::from_secs(1)).await;
+
+    // the first refresh rotates the token
+    let res = refresh(&rt_orig).await?;
+    assert_eq!(res.status(), 200);
+    let rt_rotated = res.json::<TokenSet>().await?.refresh_token.unwrap();
+    assert_ne!(rt_orig, rt_rotated);
+
+    // a concurrent refresh with the same token inside the grace time must be accepted
+    let res = refresh(&rt_orig).await?;
+    assert_eq!(res.status(), 200);
+    let rt_concurrent = res.json::<TokenSet>().await?.refresh_token.unwrap();
+
+    // the latest rotation is still valid after the grace time of the original one
+    time::sleep(Duration::from_secs(6)).await;
+    let res = refresh(&rt_concurrent).await?;
+    assert_eq!(res.status(), 200);
+    let rt_latest = res.json::<TokenSet>().await?.refresh_token.unwrap();
+
+    // a replay of the original token after the grace time must be rejected ...
+    let res = refresh(&rt_orig).await?;
+    check_status(res, 404).await?;
+
+    // ... and the whole family must have been revoked
+    let res = refresh(&rt_rotated).await?;
+    check_status(res, 404).await?;
+    let res = refresh(&rt_latest).await?;
+    check_status(res, 404).await?;
+
+    // ... while other logins of the same user must not be affected
+    let res = refresh(&rt_other).await?;
+    assert_eq!(res.status(), 200);
+
+    Ok(())
+}
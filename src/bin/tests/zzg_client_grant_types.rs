@@ -0,0 +1,123 @@
+use crate::common::{
+    PASSWORD, USERNAME, check_status, get_auth_headers, get_backend_url, update_client_request,
+};
+use pretty_assertions::assert_eq;
+use rauthy_api_types::clients::NewClientRequest;
+use rauthy_api_types::oidc::TokenRequest;
+use std::error::Error;
+
+mod common;
+
+#[tokio::test]
+async fn test_client_grant_and_response_types() -> Result<(), Box<dyn Error>> {
+    let backend_url = get_backend_url();
+    let client = reqwest::Client::new();
+    let auth_headers = get_auth_headers().await?;
+
+    // a new client only has the `authorization_code` flow enabled by default
+    let client_id = "grant_types";
+    let redirect_uri = "http://localhost:8080/callback";
+    let new_client = NewClientRequest {
+        id: client_id.to_string(),
+        secret: None,
+        name: None,
+        confidential: false,
+        redirect_uris: vec![redirect_uri.to_string()],
+        post_logout_redirect_uris: None,
+        uri_match_mode: None,
+    };
+    let res = client
+        .post(format!("{}/clients", backend_url))
+        .headers(auth_headers.clone())
+        .json(&new_client)
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    // grant_type not registered for the client
+    let body = TokenRequest {
+        grant_type: "password".to_string(),
+        code: None,
+        redirect_uri: None,
+        client_id: Some(client_id.to_string()),
+        client_secret: None,
+        code_verifier: None,
+        device_code: None,
+        username: Some(USERNAME.to_string()),
+        password: Some(PASSWORD.to_string()),
+        refresh_token: None,
+    };
+    let res = client
+        .post(format!("{}/oidc/token", backend_url))
+        .form(&body)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 400);
+    let err = res.json::<serde_json::Value>().await?;
+    assert_eq!(err["error"], "unauthorized_client");
+    assert_eq!(
+        err["error_description"],
+        "'password' flow is not allowed for this client, allowed: authorization_code"
+    );
+
+    // response_type other than `code`
+    let res = client
+        .get(format!(
+            "{}/oidc/authorize?client_id={}&redirect_uri={}&response_type=token",
+            backend_url, client_id, redirect_uri
+        ))
+        .send()
+        .await?;
+    assert_eq!(res.status(), 400);
+
+    // invalid flow combinations must be rejected on save
+    let mut update_client = update_client_request(client_id);
+    let url_client = format!("{}/clients/{}", backend_url, client_id);
+    let res = client
+        .put(&url_client)
+        .headers(auth_headers.clone())
+        .json(&update_client)
+        .send()
+        .await?;
+    check_status(res, 400).await?;
+
+    update_client.redirect_uris = vec![redirect_uri.to_string()];
+    update_client.flows_enabled = vec!["client_credentials".to_string()];
+    let res = client
+        .put(&url_client)
+        .headers(auth_headers.clone())
+        .json(&update_client)
+        .send()
+        .await?;
+    check_status(res, 400).await?;
+
+    update_client.flows_enabled = vec!["refresh_token".to_string()];
+    let res = client
+        .put(&url_client)
+        .headers(auth_headers.clone())
+        .json(&update_client)
+        .send()
+        .await?;
+    check_status(res, 400).await?;
+
+    update_client.flows_enabled = vec![
+        "authorization_code".to_string(),
+        "refresh_token".to_string(),
+    ];
+    let res = client
+        .put(&url_client)
+        .headers(auth_headers.clone())
+        .json(&update_client)
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    let res = client
+        .delete(&url_client)
+        .headers(auth_headers)
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    Ok(())
+}
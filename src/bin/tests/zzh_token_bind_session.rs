@@ -0,0 +1,121 @@
+use crate::common::{
+    CLIENT_ID, CLIENT_SECRET, PASSWORD, USERNAME, check_status, code_state_from_headers,
+    cookie_csrf_headers_from_res_direct, get_auth_headers, get_backend_url, get_solved_pow,
+};
+use pretty_assertions::assert_eq;
+use rauthy_api_types::oidc::{LoginRequest, TokenRequest};
+use rauthy_common::utils::base64_url_no_pad_decode;
+use rauthy_service::token_set::TokenSet;
+use std::error::Error;
+use std::time::Duration;
+use tokio::time;
+
+mod common;
+
+fn refresh_req(refresh_token: &str) -> TokenRequest {
+    TokenRequest {
+        grant_type: "refresh_token".to_string(),
+        code: None,
+        redirect_uri: None,
+        client_id: Some(CLIENT_ID.to_string()),
+        client_secret: Some(CLIENT_SECRET.to_string()),
+        code_verifier: None,
+        device_code: None,
+        username: None,
+        password: None,
+        refresh_token: Some(refresh_token.to_string()),
+    }
+}
+
+#[tokio::test]
+async fn test_refresh_after_session_revocation() -> Result<(), Box<dyn Error>> {
+    let backend_url = get_backend_url();
+    let client = reqwest::Client::new();
+
+    // we need a session in Init state
+    let res = client
+        .post(format!("{}/oidc/session", backend_url))
+        .send()
+        .await?;
+    assert!(res.status().is_success());
+    let headers = cookie_csrf_headers_from_res_direct(res).await?;
+
+    let challenge_plain = "oDXug9zfYqfz8ejcqMpALRPXfW8QhbKV2AVuScAt8xrLKDAmaRYQ4yRi2uqcH9ys";
+    let redirect_uri = "http://localhost:3000/oidc/callback";
+    let req_login = LoginRequest {
+        email: USERNAME.to_string(),
+        password: Some(PASSWORD.to_string()),
+        pow: get_solved_pow().await,
+        client_id: CLIENT_ID.to_string(),
+        redirect_uri: redirect_uri.to_string(),
+        scopes: None,
+        state: None,
+        nonce: None,
+        code_challenge: Some(challenge_plain.to_string()),
+        code_challenge_method: Some("plain".to_string()),
+        captcha: None,
+    };
+    let res = client
+        .post(format!(
+            "{}/oidc/authorize?client_id={}&redirect_uri={}&response_type=code",
+            backend_url, CLIENT_ID, redirect_uri
+        ))
+        .headers(headers)
+        .json(&req_login)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 202);
+    let (code, _state) = code_state_from_headers(res)?;
+
+    let req_token = TokenRequest {
+        grant_type: "authorization_code".to_string(),
+        code: Some(code),
+        redirect_uri: Some(redirect_uri.to_string()),
+        client_id: Some(CLIENT_ID.to_string()),
+        client_secret: Some(CLIENT_SECRET.to_string()),
+        code_verifier: Some(challenge_plain.to_string()),
+        device_code: None,
+        username: None,
+        password: None,
+        refresh_token: None,
+    };
+    let url_token = format!("{}/oidc/token", backend_url);
+    let res = client.post(&url_token).form(&req_token).send().await?;
+    assert_eq!(res.status(), 200);
+    let rt = res.json::<TokenSet>().await?.refresh_token.unwrap();
+
+    // the refresh token must be bound to the session from the login
+    let payload = rt.split('.').nth(1).unwrap();
+    let claims =
+        serde_json::from_slice::<serde_json::Value>(&base64_url_no_pad_decode(payload).unwrap())?;
+    let sid = claims["sid"].as_str().unwrap().to_string();
+
+    // make sure the `iat` differs, so we get a new refresh token, which keeps the binding
+    time::sleep(Duration::from_secs(1)).await;
+    let res = client
+        .post(&url_token)
+        .form(&refresh_req(&rt))
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
+    let rt = res.json::<TokenSet>().await?.refresh_token.unwrap();
+
+    // revoke the session
+    let res = client
+        .delete(format!("{}/sessions/id/{}", backend_url, sid))
+        .headers(get_auth_headers().await?)
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    let res = client
+        .post(&url_token)
+        .form(&refresh_req(&rt))
+        .send()
+        .await?;
+    assert_eq!(res.status(), 400);
+    let err = res.json::<serde_json::Value>().await?;
+    assert_eq!(err["error"], "invalid_grant");
+
+    Ok(())
+}
@@ -0,0 +1,219 @@
+use crate::common::{
+    PASSWORD, USERNAME, check_status, get_auth_headers, get_backend_url, update_client_request,
+};
+use pretty_assertions::assert_eq;
+use rauthy_api_types::clients::{NewClientRequest, UpdateClientRequest, UriMatchMode};
+use rauthy_api_types::oidc::{LogoutRequest, TokenRequest};
+use rauthy_service::token_set::TokenSet;
+use reqwest::header::LOCATION;
+use reqwest::redirect::Policy;
+use std::error::Error;
+
+mod common;
+
+static CLIENT_ID: &str = "post_logout";
+static CLIENT_ID_LEGACY: &str = "post_logout_legacy";
+static REDIRECT_URI: &str = "http://localhost:3000/oidc/callback";
+
+async fn logout(
+    client_id: &str,
+    post_logout_redirect_uri: &str,
+) -> Result<reqwest::Response, Box<dyn Error>> {
+    let backend_url = get_backend_url();
+
+    // we need a fresh `id_token` each time, because each logout revokes the tokens
+    let body = TokenRequest {
+        grant_type: "password".to_string(),
+        code: None,
+        redirect_uri: None,
+        client_id: Some(client_id.to_string()),
+        client_secret: None,
+        code_verifier: None,
+        device_code: None,
+        username: Some(USERNAME.to_string()),
+        password: Some(PASSWORD.to_string()),
+        refresh_token: None,
+    };
+    let res = reqwest::Client::new()
+        .post(format!("{}/oidc/token", backend_url))
+        .form(&body)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
+    let ts = res.json::<TokenSet>().await?;
+
+    let res = reqwest::Client::builder()
+        .redirect(Policy::none())
+        .build()?
+        .get(format!("{}/oidc/logout", backend_url))
+        // makes sure we get a redirect instead of a backchannel response
+        .header("sec-fetch-site", "same-origin")
+        .query(&LogoutRequest {
+            id_token_hint: ts.id_token,
+            post_logout_redirect_uri: Some(post_logout_redirect_uri.to_string()),
+            state: None,
+            logout_token: None,
+        })
+        .send()
+        .await?;
+    Ok(res)
+}
+
+fn update_request(
+    client_id: &str,
+    post_logout_redirect_uris: Vec<String>,
+    uri_match_mode: Option<UriMatchMode>,
+) -> UpdateClientRequest {
+    UpdateClientRequest {
+        redirect_uris: vec![REDIRECT_URI.to_string()],
+        post_logout_redirect_uris: Some(post_logout_redirect_uris),
+        flows_enabled: vec!["authorization_code".to_string(), "password".to_string()],
+        uri_match_mode,
+        ..update_client_request(client_id)
+    }
+}
+
+#[tokio::test]
+async fn test_post_logout_redirect_uri_matching() -> Result<(), Box<dyn Error>> {
+    let backend_url = get_backend_url();
+    let client = reqwest::Client::new();
+    let auth_headers = get_auth_headers().await?;
+
+    // wildcards which could leave the origin must be rejected
+    let mut new_client = NewClientRequest {
+        id: CLIENT_ID.to_string(),
+        secret: None,
+        name: None,
+        confidential: false,
+        redirect_uris: vec![REDIRECT_URI.to_string()],
+        post_logout_redirect_uris: Some(vec!["https://*.com/logout".to_string()]),
+        uri_match_mode: None,
+    };
+    let res = client
+        .post(format!("{}/clients", backend_url))
+        .headers(auth_headers.clone())
+        .json(&new_client)
+        .send()
+        .await?;
+    check_status(res, 400).await?;
+
+    let post_logout_redirect_uris = vec![
+        "https://*.staging.example.com/logout".to_string(),
+        "https://app.example.com/logout/*".to_string(),
+        "http://127.0.0.1/logout".to_string(),
+    ];
+    new_client.post_logout_redirect_uris = Some(post_logout_redirect_uris.clone());
+    let res = client
+        .post(format!("{}/clients", backend_url))
+        .headers(auth_headers.clone())
+        .json(&new_client)
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    let update_client = update_request(CLIENT_ID, post_logout_redirect_uris, None);
+    let url_client = format!("{}/clients/{}", backend_url, CLIENT_ID);
+    let res = client
+        .put(&url_client)
+        .headers(auth_headers.clone())
+        .json(&update_client)
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    for accepted in [
+        "https://pr-12.staging.example.com/logout",
+        "https://app.example.com/logout/done",
+        "http://127.0.0.1:53421/logout",
+    ] {
+        let res = logout(CLIENT_ID, accepted).await?;
+        assert_eq!(res.status(), 302, "{accepted}");
+        assert_eq!(res.headers().get(LOCATION).unwrap(), accepted);
+    }
+
+    for rejected in [
+        "https://evil.net/logout",
+        "https://a.b.staging.example.com/logout",
+        "https://pr-12.staging.example.com.evil.net/logout",
+        "https://app.example.com@evil.net/logout/",
+        "https://app.example.com:8443/logout/done",
+        "http://127.0.0.1:53421/other",
+    ] {
+        let res = logout(CLIENT_ID, rejected).await?;
+        assert_eq!(res.status(), 400, "{rejected}");
+    }
+
+    let res = client
+        .delete(&url_client)
+        .headers(auth_headers)
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_legacy_prefix_uri_rules() -> Result<(), Box<dyn Error>> {
+    let backend_url = get_backend_url();
+    let client = reqwest::Client::new();
+    let auth_headers = get_auth_headers().await?;
+
+    // existing clients are migrated to the `prefix` mode, which accepts the legacy rules
+    let post_logout_redirect_uris = vec!["https://app.example.com*".to_string()];
+    let new_client = NewClientRequest {
+        id: CLIENT_ID_LEGACY.to_string(),
+        secret: None,
+        name: None,
+        confidential: false,
+        redirect_uris: vec![REDIRECT_URI.to_string()],
+        post_logout_redirect_uris: Some(post_logout_redirect_uris.clone()),
+        uri_match_mode: Some(UriMatchMode::Prefix),
+    };
+    let res = client
+        .post(format!("{}/clients", backend_url))
+        .headers(auth_headers.clone())
+        .json(&new_client)
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    // updates without a mode must keep the current one and not fail the validation
+    let url_client = format!("{}/clients/{}", backend_url, CLIENT_ID_LEGACY);
+    let update_client = update_request(CLIENT_ID_LEGACY, post_logout_redirect_uris.clone(), None);
+    let res = client
+        .put(&url_client)
+        .headers(auth_headers.clone())
+        .json(&update_client)
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    let accepted = "https://app.example.com/logout";
+    let res = logout(CLIENT_ID_LEGACY, accepted).await?;
+    assert_eq!(res.status(), 302);
+    assert_eq!(res.headers().get(LOCATION).unwrap(), accepted);
+
+    // the rule cannot be used in `strict` mode
+    let update_client = update_request(
+        CLIENT_ID_LEGACY,
+        post_logout_redirect_uris,
+        Some(UriMatchMode::Strict),
+    );
+    let res = client
+        .put(&url_client)
+        .headers(auth_headers.clone())
+        .json(&update_client)
+        .send()
+        .await?;
+    check_status(res, 400).await?;
+
+    let res = client
+        .delete(&url_client)
+        .headers(auth_headers)
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    Ok(())
+}
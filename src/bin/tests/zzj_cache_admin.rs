@@ -0,0 +1,67 @@
+use crate::common::{check_status, get_auth_headers, get_backend_url};
+use pretty_assertions::assert_eq;
+use rauthy_api_types::generic::CacheNamespaceResponse;
+use std::error::Error;
+
+mod common;
+
+#[tokio::test]
+async fn test_cache_inspect_flush() -> Result<(), Box<dyn Error>> {
+    let backend_url = get_backend_url();
+    let client = reqwest::Client::new();
+    let auth_headers = get_auth_headers().await?;
+
+    let res = client.get(format!("{}/cache", backend_url)).send().await?;
+    check_status(res, 401).await?;
+
+    let res = client
+        .get(format!("{}/cache", backend_url))
+        .headers(auth_headers.clone())
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
+    let namespaces = res.json::<Vec<CacheNamespaceResponse>>().await?;
+    let app = namespaces.iter().find(|ns| ns.name == "app").unwrap();
+    assert!(app.flushable);
+    let auth_code = namespaces.iter().find(|ns| ns.name == "auth_code").unwrap();
+    assert!(!auth_code.flushable);
+
+    let res = client
+        .delete(format!("{}/cache/app", backend_url))
+        .headers(auth_headers.clone())
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    // state which cannot be re-fetched must never be flushed
+    let res = client
+        .delete(format!("{}/cache/auth_code", backend_url))
+        .headers(auth_headers.clone())
+        .send()
+        .await?;
+    check_status(res, 400).await?;
+
+    let res = client
+        .delete(format!("{}/cache/unknown", backend_url))
+        .headers(auth_headers.clone())
+        .send()
+        .await?;
+    check_status(res, 404).await?;
+
+    // the admin session must still be valid after flushing everything else
+    let res = client
+        .delete(format!("{}/cache", backend_url))
+        .headers(auth_headers.clone())
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    let res = client
+        .get(format!("{}/cache", backend_url))
+        .headers(auth_headers)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
+
+    Ok(())
+}
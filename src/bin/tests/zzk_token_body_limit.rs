@@ -0,0 +1,36 @@
+use crate::common::{CLIENT_ID, CLIENT_SECRET, get_backend_url};
+use pretty_assertions::assert_eq;
+use std::error::Error;
+
+mod common;
+
+#[tokio::test]
+async fn test_token_body_limit() -> Result<(), Box<dyn Error>> {
+    let url = format!("{}/oidc/token", get_backend_url());
+    let client = reqwest::Client::new();
+
+    // the default `token_max_body_bytes` is 16384
+    let oversized = format!(
+        "grant_type=client_credentials&client_id={CLIENT_ID}&client_secret={CLIENT_SECRET}&pad={}",
+        "a".repeat(16384)
+    );
+    let res = client
+        .post(&url)
+        .header("content-type", "application/x-www-form-urlencoded")
+        .body(oversized)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 413);
+
+    let res = client
+        .post(&url)
+        .header("content-type", "application/json")
+        .body(format!(
+            r#"{{"grant_type":"client_credentials","client_id":"{CLIENT_ID}"}}"#
+        ))
+        .send()
+        .await?;
+    assert_eq!(res.status(), 400);
+
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use crate::common::{
+    PASSWORD, USERNAME, check_status, get_auth_headers, get_backend_url, update_client_request,
+};
+use pretty_assertions::assert_eq;
+use rauthy_api_types::clients::{NewClientRequest, UpdateClientRequest};
+use rauthy_api_types::oidc::TokenRequest;
+use rauthy_common::utils::base64_url_no_pad_decode;
+use rauthy_service::token_set::TokenSet;
+use std::error::Error;
+use std::time::Duration;
+use tokio::time;
+
+mod common;
+
+static CLIENT_ID: &str = "sliding_refresh";
+
+fn token_req(refresh_token: Option<&str>) -> TokenRequest {
+    let (grant_type, username, password) = if refresh_token.is_some() {
+        ("refresh_token", None, None)
+    } else {
+        (
+            "password",
+            Some(USERNAME.to_string()),
+            Some(PASSWORD.to_string()),
+        )
+    };
+    TokenRequest {
+        grant_type: grant_type.to_string(),
+        code: None,
+        redirect_uri: None,
+        client_id: Some(CLIENT_ID.to_string()),
+        client_secret: None,
+        code_verifier: None,
+        device_code: None,
+        username,
+        password,
+        refresh_token: refresh_token.map(String::from),
+    }
+}
+
+async fn fetch_refresh_token(
+    refresh_token: Option<&str>,
+) -> Result<reqwest::Response, Box<dyn Error>> {
+    let res = reqwest::Client::new()
+        .post(format!("{}/oidc/token", get_backend_url()))
+        .form(&token_req(refresh_token))
+        .send()
+        .await?;
+    Ok(res)
+}
+
+/// Returns the refresh token with its `(exp, auth_time)` claims.
+async fn refresh_token_claims(
+    res: reqwest::Response,
+) -> Result<(String, i64, i64), Box<dyn Error>> {
+    assert_eq!(res.status(), 200);
+    let rt = res.json::<TokenSet>().await?.refresh_token.unwrap();
+    let payload = rt.split('.').nth(1).unwrap();
+    let claims =
+        serde_json::from_slice::<serde_json::Value>(&base64_url_no_pad_decode(payload).unwrap())?;
+    let exp = claims["exp"].as_i64().unwrap();
+    let auth_time = claims["auth_time"].as_i64().unwrap();
+    Ok((rt, exp, auth_time))
+}
+
+#[tokio::test]
+async fn test_refresh_token_sliding_expiration() -> Result<(), Box<dyn Error>> {
+    let backend_url = get_backend_url();
+    let client = reqwest::Client::new();
+    let auth_headers = get_auth_headers().await?;
+
+    let new_client = NewClientRequest {
+        id: CLIENT_ID.to_string(),
+        secret: None,
+        name: None,
+        confidential: false,
+        redirect_uris: vec!["http://localhost:3000/oidc/callback".to_string()],
+        post_logout_redirect_uris: None,
+        uri_match_mode: None,
+    };
+    let res = client
+        .post(format!("{}/clients", backend_url))
+        .headers(auth_headers.clone())
+        .json(&new_client)
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    let mut update_client = UpdateClientRequest {
+        redirect_uris: new_client.redirect_uris,
+        flows_enabled: vec!["password".to_string(), "refresh_token".to_string()],
+        access_token_lifetime: 10,
+        ..update_client_request(CLIENT_ID)
+    };
+    let url_client = format!("{}/clients/{}", backend_url, CLIENT_ID);
+    let res = client
+        .put(&url_client)
+        .headers(auth_headers.clone())
+        .json(&update_client)
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    // without an absolute max, each refresh extends the lifetime
+    let (rt, exp, auth_time) = refresh_token_claims(fetch_refresh_token(None).await?).await?;
+    time::sleep(Duration::from_secs(1)).await;
+    let (_rt, exp_refreshed, auth_time_refreshed) =
+        refresh_token_claims(fetch_refresh_token(Some(&rt)).await?).await?;
+    assert!(exp_refreshed > exp);
+    assert_eq!(auth_time_refreshed, auth_time);
+
+    // with an absolute max, the expiry is capped, counted from the original login
+    update_client.refresh_token_max_lifetime = Some(10);
+    let res = client
+        .put(&url_client)
+        .headers(auth_headers.clone())
+        .json(&update_client)
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    let (rt, exp, auth_time) = refresh_token_claims(fetch_refresh_token(None).await?).await?;
+    assert_eq!(exp, auth_time + 10);
+    time::sleep(Duration::from_secs(1)).await;
+    let (rt, exp_refreshed, _) =
+        refresh_token_claims(fetch_refresh_token(Some(&rt)).await?).await?;
+    assert_eq!(exp_refreshed, auth_time + 10);
+
+    // after the cap, the user must authenticate again
+    time::sleep(Duration::from_secs(10)).await;
+    let res = fetch_refresh_token(Some(&rt)).await?;
+    check_status(res, 401).await?;
+
+    let res = client
+        .delete(&url_client)
+        .headers(auth_headers)
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use crate::common::{CLIENT_ID, USERNAME, check_status, get_auth_headers, get_backend_url};
+use pretty_assertions::assert_eq;
+use rauthy_api_types::clients::{TokenPreviewRequest, TokenPreviewResponse};
+use std::error::Error;
+
+mod common;
+
+#[tokio::test]
+async fn test_token_preview() -> Result<(), Box<dyn Error>> {
+    let backend_url = get_backend_url();
+    let client = reqwest::Client::new();
+    let url = format!("{}/clients/{}/token_preview", backend_url, CLIENT_ID);
+
+    let payload = TokenPreviewRequest {
+        email: USERNAME.to_string(),
+        scopes: Some(vec![
+            "openid".to_string(),
+            "email".to_string(),
+            "profile".to_string(),
+        ]),
+    };
+
+    // admin access is needed
+    let res = client.post(&url).json(&payload).send().await?;
+    check_status(res, 401).await?;
+
+    let res = client
+        .post(&url)
+        .headers(get_auth_headers().await?)
+        .json(&payload)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
+    let preview = res.json::<TokenPreviewResponse>().await?;
+
+    assert_eq!(preview.access_token["azp"], CLIENT_ID);
+    assert_eq!(preview.access_token["typ"], "Bearer");
+    assert!(preview.access_token["sub"].is_string());
+    assert_eq!(preview.id_token["email"], USERNAME);
+    assert_eq!(preview.id_token["typ"], "Id");
+    assert_eq!(preview.id_token["sub"], preview.access_token["sub"]);
+
+    // unknown users must be rejected
+    let res = client
+        .post(&url)
+        .headers(get_auth_headers().await?)
+        .json(&TokenPreviewRequest {
+            email: "unknown@localhost.de".to_string(),
+            scopes: None,
+        })
+        .send()
+        .await?;
+    check_status(res, 404).await?;
+
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use crate::common::{
+    PASSWORD, USERNAME, check_status, code_state_from_headers, cookie_csrf_headers_from_res_direct,
+    get_auth_headers, get_backend_url, get_solved_pow, update_client_request,
+};
+use pretty_assertions::assert_eq;
+use rauthy_api_types::clients::{NewClientRequest, UpdateClientRequest};
+use rauthy_api_types::oidc::{LoginRequest, TokenRequest};
+use rauthy_common::utils::base64_url_no_pad_decode;
+use rauthy_service::token_set::TokenSet;
+use std::error::Error;
+
+mod common;
+
+static CLIENT_ID: &str = "require_nonce";
+static REDIRECT_URI: &str = "http://localhost:3000/oidc/callback";
+static CHALLENGE_PLAIN: &str = "oDXug9zfYqfz8ejcqMpALRPXfW8QhbKV2AVuScAt8xrLKDAmaRYQ4yRi2uqcH9ys";
+
+async fn login(nonce: Option<&str>) -> Result<reqwest::Response, Box<dyn Error>> {
+    let backend_url = get_backend_url();
+    let client = reqwest::Client::new();
+
+    // we need a session in Init state
+    let res = client
+        .post(format!("{}/oidc/session", backend_url))
+        .send()
+        .await?;
+    assert!(res.status().is_success());
+    let headers = cookie_csrf_headers_from_res_direct(res).await?;
+
+    let req_login = LoginRequest {
+        email: USERNAME.to_string(),
+        password: Some(PASSWORD.to_string()),
+        pow: get_solved_pow().await,
+        client_id: CLIENT_ID.to_string(),
+        redirect_uri: REDIRECT_URI.to_string(),
+        scopes: None,
+        state: None,
+        nonce: nonce.map(String::from),
+        code_challenge: Some(CHALLENGE_PLAIN.to_string()),
+        code_challenge_method: Some("plain".to_string()),
+        captcha: None,
+    };
+    let res = client
+        .post(format!(
+            "{}/oidc/authorize?client_id={}&redirect_uri={}&response_type=code",
+            backend_url, CLIENT_ID, REDIRECT_URI
+        ))
+        .headers(headers)
+        .json(&req_login)
+        .send()
+        .await?;
+    Ok(res)
+}
+
+#[tokio::test]
+async fn test_nonce_required_and_echoed() -> Result<(), Box<dyn Error>> {
+    let backend_url = get_backend_url();
+    let client = reqwest::Client::new();
+    let auth_headers = get_auth_headers().await?;
+
+    let new_client = NewClientRequest {
+        id: CLIENT_ID.to_string(),
+        secret: None,
+        name: None,
+        confidential: false,
+        redirect_uris: vec![REDIRECT_URI.to_string()],
+        post_logout_redirect_uris: None,
+        uri_match_mode: None,
+    };
+    let res = client
+        .post(format!("{}/clients", backend_url))
+        .headers(auth_headers.clone())
+        .json(&new_client)
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    let update_client = UpdateClientRequest {
+        redirect_uris: new_client.redirect_uris,
+        challenges: Some(vec!["plain".to_string()]),
+        require_nonce: true,
+        ..update_client_request(CLIENT_ID)
+    };
+    let url_client = format!("{}/clients/{}", backend_url, CLIENT_ID);
+    let res = client
+        .put(&url_client)
+        .headers(auth_headers.clone())
+        .json(&update_client)
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    // the authorization request must be rejected without a `nonce`
+    let res = client
+        .get(format!(
+            "{}/oidc/authorize?client_id={}&redirect_uri={}&response_type=code\
+            &code_challenge={}&code_challenge_method=plain",
+            backend_url, CLIENT_ID, REDIRECT_URI, CHALLENGE_PLAIN
+        ))
+        .send()
+        .await?;
+    assert_eq!(res.status(), 400);
+
+    let res = login(None).await?;
+    check_status(res, 400).await?;
+
+    // with a `nonce`, it must be echoed in the `id_token`
+    let nonce = "Pq3Xx7mYgkWbL2sN0tVuRcZ8";
+    let res = login(Some(nonce)).await?;
+    assert_eq!(res.status(), 202);
+    let (code, _state) = code_state_from_headers(res)?;
+
+    let req_token = TokenRequest {
+        grant_type: "authorization_code".to_string(),
+        code: Some(code),
+        redirect_uri: Some(REDIRECT_URI.to_string()),
+        client_id: Some(CLIENT_ID.to_string()),
+        client_secret: None,
+        code_verifier: Some(CHALLENGE_PLAIN.to_string()),
+        device_code: None,
+        username: None,
+        password: None,
+        refresh_token: None,
+    };
+    let res = client
+        .post(format!("{}/oidc/token", backend_url))
+        .form(&req_token)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
+    let id_token = res.json::<TokenSet>().await?.id_token.unwrap();
+    let payload = id_token.split('.').nth(1).unwrap();
+    let claims =
+        serde_json::from_slice::<serde_json::Value>(&base64_url_no_pad_decode(payload).unwrap())?;
+    assert_eq!(claims["nonce"], nonce);
+
+    let res = client
+        .delete(&url_client)
+        .headers(auth_headers)
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    Ok(())
+}
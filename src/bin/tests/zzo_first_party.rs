@@ -0,0 +1,139 @@
+use crate::common::{
+    PASSWORD, USERNAME, check_status, cookie_csrf_headers_from_res_direct, get_auth_headers,
+    get_backend_url, get_solved_pow, update_client_request,
+};
+use pretty_assertions::assert_eq;
+use rauthy_api_types::clients::{ClientResponse, NewClientRequest, UpdateClientRequest};
+use rauthy_api_types::oidc::LoginRequest;
+use rauthy_common::sha256;
+use rauthy_common::utils::base64_url_encode;
+use reqwest::header::LOCATION;
+use std::error::Error;
+
+mod common;
+
+static CLIENT_ID: &str = "first_party";
+static REDIRECT_URI: &str = "http://localhost:3000/oidc/callback";
+
+/// Logs in with the `email` scope, which is not allowed for the client, and returns the
+/// `Location` of the response.
+async fn login_location() -> Result<String, Box<dyn Error>> {
+    let backend_url = get_backend_url();
+    let client = reqwest::Client::new();
+
+    let res = client
+        .post(format!("{backend_url}/oidc/session"))
+        .send()
+        .await?;
+    let headers = cookie_csrf_headers_from_res_direct(res).await?;
+
+    let challenge_plain = "oDXug9zfYqfz8ejcqMpALRPXfW8QhbKV2AVuScAt8xrLKDAmaRYQ4yRi2uqcH9ys";
+    let req_login = LoginRequest {
+        email: USERNAME.to_string(),
+        password: Some(PASSWORD.to_string()),
+        pow: get_solved_pow().await,
+        client_id: CLIENT_ID.to_string(),
+        redirect_uri: REDIRECT_URI.to_string(),
+        scopes: Some(vec!["openid".to_string(), "email".to_string()]),
+        state: None,
+        nonce: None,
+        code_challenge: Some(base64_url_encode(sha256!(challenge_plain.as_bytes()))),
+        code_challenge_method: Some("S256".to_string()),
+        captcha: None,
+    };
+    let res = client
+        .post(format!("{backend_url}/oidc/authorize"))
+        .headers(headers)
+        .json(&req_login)
+        .send()
+        .await?;
+    let res = check_status(res, 202).await?;
+
+    Ok(res.headers().get(LOCATION).unwrap().to_str()?.to_string())
+}
+
+#[tokio::test]
+async fn test_first_party_clients() -> Result<(), Box<dyn Error>> {
+    let backend_url = get_backend_url();
+    let client = reqwest::Client::new();
+    let auth_headers = get_auth_headers().await?;
+
+    let new_client = NewClientRequest {
+        id: CLIENT_ID.to_string(),
+        secret: None,
+        name: None,
+        confidential: false,
+        redirect_uris: vec![REDIRECT_URI.to_string()],
+        post_logout_redirect_uris: None,
+        uri_match_mode: None,
+    };
+    let res = client
+        .post(format!("{}/clients", backend_url))
+        .headers(auth_headers.clone())
+        .json(&new_client)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
+    // new clients are never trusted by default
+    assert!(!res.json::<ClientResponse>().await?.first_party);
+
+    let update_client = UpdateClientRequest {
+        redirect_uris: new_client.redirect_uris,
+        ..update_client_request(CLIENT_ID)
+    };
+    let url_client = format!("{}/clients/{}", backend_url, CLIENT_ID);
+    let res = client
+        .put(&url_client)
+        .headers(auth_headers.clone())
+        .json(&update_client)
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    // The test config has `strict_scopes = true`, which rejects the not allowed `email` scope
+    // for third-party clients.
+    let location = login_location().await?;
+    assert!(location.contains("error=invalid_scope"), "{location}");
+
+    // only an admin can mark a client as trusted
+    let mut update_client = update_client;
+    update_client.first_party = true;
+    let res = client.put(&url_client).json(&update_client).send().await?;
+    check_status(res, 401).await?;
+
+    let res = client
+        .put(&url_client)
+        .headers(auth_headers.clone())
+        .json(&update_client)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
+    assert!(res.json::<ClientResponse>().await?.first_party);
+
+    // first-party clients bypass `strict_scopes` and get the scope silently dropped
+    let location = login_location().await?;
+    assert!(location.starts_with(REDIRECT_URI), "{location}");
+    assert!(location.contains("code="), "{location}");
+    assert!(!location.contains("error="), "{location}");
+
+    // an update without the flag must drop the trust
+    let mut payload = serde_json::to_value(&update_client)?;
+    payload.as_object_mut().unwrap().remove("first_party");
+    let res = client
+        .put(&url_client)
+        .headers(auth_headers.clone())
+        .json(&payload)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
+    assert!(!res.json::<ClientResponse>().await?.first_party);
+
+    let res = client
+        .delete(&url_client)
+        .headers(auth_headers)
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    Ok(())
+}
@@ -0,0 +1,175 @@
+use crate::common::{
+    PASSWORD, USERNAME, check_status, get_auth_headers, get_backend_url, mock_json_server,
+    update_client_request,
+};
+use pretty_assertions::{assert_eq, assert_ne};
+use rauthy_api_types::clients::{NewClientRequest, SubjectType, UpdateClientRequest};
+use rauthy_api_types::oidc::TokenRequest;
+use rauthy_api_types::users::Userinfo;
+use rauthy_common::utils::base64_url_no_pad_decode;
+use rauthy_service::token_set::TokenSet;
+use serde_json::json;
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+
+mod common;
+
+static REDIRECT_URI: &str = "http://localhost:3000/oidc/callback";
+
+/// Returns the status of the client update.
+async fn upsert_client(
+    client_id: &str,
+    subject_type: Option<SubjectType>,
+    sector_identifier_uri: Option<&str>,
+) -> Result<u16, Box<dyn Error>> {
+    let backend_url = get_backend_url();
+    let client = reqwest::Client::new();
+    let auth_headers = get_auth_headers().await?;
+
+    let new_client = NewClientRequest {
+        id: client_id.to_string(),
+        secret: None,
+        name: None,
+        confidential: false,
+        redirect_uris: vec![REDIRECT_URI.to_string()],
+        post_logout_redirect_uris: None,
+        uri_match_mode: None,
+    };
+    // the client may exist already from a previous call
+    let _ = client
+        .post(format!("{}/clients", backend_url))
+        .headers(auth_headers.clone())
+        .json(&new_client)
+        .send()
+        .await?;
+
+    let update_client = UpdateClientRequest {
+        redirect_uris: new_client.redirect_uris,
+        flows_enabled: vec!["authorization_code".to_string(), "password".to_string()],
+        subject_type,
+        sector_identifier_uri: sector_identifier_uri.map(String::from),
+        ..update_client_request(client_id)
+    };
+    let res = client
+        .put(format!("{}/clients/{}", backend_url, client_id))
+        .headers(auth_headers)
+        .json(&update_client)
+        .send()
+        .await?;
+
+    Ok(res.status().as_u16())
+}
+
+/// Returns the `sub` from the `id_token` and the `sub` from the userinfo endpoint.
+async fn subs(client_id: &str) -> Result<(String, String), Box<dyn Error>> {
+    let backend_url = get_backend_url();
+    let client = reqwest::Client::new();
+
+    let body = TokenRequest {
+        grant_type: "password".to_string(),
+        code: None,
+        redirect_uri: None,
+        client_id: Some(client_id.to_string()),
+        client_secret: None,
+        code_verifier: None,
+        device_code: None,
+        username: Some(USERNAME.to_string()),
+        password: Some(PASSWORD.to_string()),
+        refresh_token: None,
+    };
+    let res = client
+        .post(format!("{}/oidc/token", backend_url))
+        .form(&body)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
+    let ts = res.json::<TokenSet>().await?;
+
+    let payload = ts.id_token.as_ref().unwrap().split('.').nth(1).unwrap();
+    let claims =
+        serde_json::from_slice::<serde_json::Value>(&base64_url_no_pad_decode(payload).unwrap())?;
+    let sub = claims["sub"].as_str().unwrap().to_string();
+
+    let res = client
+        .get(format!("{}/oidc/userinfo", backend_url))
+        .bearer_auth(&ts.access_token)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
+    let userinfo = res.json::<Userinfo>().await?;
+
+    Ok((sub, userinfo.sub))
+}
+
+#[tokio::test]
+async fn test_pairwise_sub() -> Result<(), Box<dyn Error>> {
+    assert_eq!(
+        upsert_client("pairwise_a", Some(SubjectType::Public), None).await?,
+        200
+    );
+    let (sub_public, userinfo_sub) = subs("pairwise_a").await?;
+    assert_eq!(sub_public, userinfo_sub);
+
+    assert_eq!(
+        upsert_client("pairwise_a", Some(SubjectType::Pairwise), None).await?,
+        200
+    );
+    assert_eq!(
+        upsert_client("pairwise_b", Some(SubjectType::Pairwise), None).await?,
+        200
+    );
+
+    // stable per client and matching the userinfo endpoint
+    let (sub_a, userinfo_sub) = subs("pairwise_a").await?;
+    assert_ne!(sub_a, sub_public);
+    assert_eq!(sub_a, userinfo_sub);
+    assert_eq!(subs("pairwise_a").await?.0, sub_a);
+
+    // an update without a `subject_type` must keep the current one
+    assert_eq!(upsert_client("pairwise_a", None, None).await?, 200);
+    assert_eq!(subs("pairwise_a").await?.0, sub_a);
+
+    // distinct across sectors
+    let (sub_b, userinfo_sub) = subs("pairwise_b").await?;
+    assert_ne!(sub_b, sub_a);
+    assert_eq!(sub_b, userinfo_sub);
+
+    // the same sector shares the `sub`
+    let sector_uris = Arc::new(Mutex::new(json!([REDIRECT_URI])));
+    let sector_url = mock_json_server(sector_uris.clone()).await;
+    let sector_a = format!("{sector_url}/a.json");
+    let sector_b = format!("{sector_url}/b.json");
+    assert_eq!(
+        upsert_client("pairwise_a", Some(SubjectType::Pairwise), Some(&sector_a)).await?,
+        200
+    );
+    assert_eq!(
+        upsert_client("pairwise_b", Some(SubjectType::Pairwise), Some(&sector_b)).await?,
+        200
+    );
+    let (sub_a, _) = subs("pairwise_a").await?;
+    let (sub_b, _) = subs("pairwise_b").await?;
+    assert_eq!(sub_a, sub_b);
+
+    // the `sector_identifier_uri` must contain all `redirect_uris`
+    *sector_uris.lock().unwrap() = json!(["http://localhost:3000/other"]);
+    let sector_c = format!("{sector_url}/c.json");
+    assert_eq!(
+        upsert_client("pairwise_b", Some(SubjectType::Pairwise), Some(&sector_c)).await?,
+        400
+    );
+    assert_eq!(subs("pairwise_b").await?.0, sub_b);
+
+    let backend_url = get_backend_url();
+    let auth_headers = get_auth_headers().await?;
+    for client_id in ["pairwise_a", "pairwise_b"] {
+        let res = reqwest::Client::new()
+            .delete(format!("{}/clients/{}", backend_url, client_id))
+            .headers(auth_headers.clone())
+            .send()
+            .await?;
+        check_status(res, 200).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use crate::common::{check_status, get_auth_headers, get_backend_url, update_client_request};
+use pretty_assertions::assert_eq;
+use rauthy_api_types::clients::{ClientSecretResponse, NewClientRequest, UpdateClientRequest};
+use rauthy_api_types::oidc::TokenRequest;
+use reqwest::header::RETRY_AFTER;
+use std::error::Error;
+
+mod common;
+
+static CLIENT_ID: &str = "token_rate_limit";
+
+/// The `token_rate_limit_client` is set to `100` in `config-test.toml`
+#[tokio::test]
+async fn test_token_rate_limit_client() -> Result<(), Box<dyn Error>> {
+    let backend_url = get_backend_url();
+    let client = reqwest::Client::new();
+    let auth_headers = get_auth_headers().await?;
+
+    let new_client = NewClientRequest {
+        id: CLIENT_ID.to_string(),
+        secret: None,
+        name: None,
+        confidential: true,
+        redirect_uris: vec!["http://localhost:3000/oidc/callback".to_string()],
+        post_logout_redirect_uris: None,
+        uri_match_mode: None,
+    };
+    let res = client
+        .post(format!("{}/clients", backend_url))
+        .headers(auth_headers.clone())
+        .json(&new_client)
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    let update_client = UpdateClientRequest {
+        confidential: true,
+        redirect_uris: new_client.redirect_uris,
+        flows_enabled: vec!["client_credentials".to_string()],
+        ..update_client_request(CLIENT_ID)
+    };
+    let res = client
+        .put(format!("{}/clients/{}", backend_url, CLIENT_ID))
+        .headers(auth_headers.clone())
+        .json(&update_client)
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    let res = client
+        .put(format!("{}/clients/{}/secret", backend_url, CLIENT_ID))
+        .headers(auth_headers)
+        .send()
+        .await?;
+    let secret = check_status(res, 200)
+        .await?
+        .json::<ClientSecretResponse>()
+        .await?
+        .secret
+        .unwrap();
+
+    let url_token = format!("{}/oidc/token", backend_url);
+    let mut token_req = TokenRequest {
+        grant_type: "client_credentials".to_string(),
+        code: None,
+        redirect_uri: None,
+        client_id: Some(CLIENT_ID.to_string()),
+        client_secret: Some("IAmSoWrong1337".to_string()),
+        code_verifier: None,
+        device_code: None,
+        username: None,
+        password: None,
+        refresh_token: None,
+    };
+
+    // Requests with an invalid secret must not count for the client. Otherwise, anyone could
+    // lock out a client by only knowing its `client_id`.
+    for _ in 0..110 {
+        let res = client.post(&url_token).form(&token_req).send().await?;
+        assert_eq!(res.status(), 401);
+    }
+
+    token_req.client_secret = Some(secret);
+    // The fixed window may roll over while we are sending requests, which would reset the
+    // counter. We need to see the limit being hit within at most 2 windows.
+    let mut limited = None;
+    for _ in 0..201 {
+        let res = client.post(&url_token).form(&token_req).send().await?;
+        if res.status() == 429 {
+            limited = Some(res);
+            break;
+        }
+        assert_eq!(res.status(), 200);
+    }
+    let res = limited.expect("the client to be rate limited");
+    assert!(res.headers().get(RETRY_AFTER).is_some());
+
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use crate::common::{
+    USERNAME, check_status, create_provider, find_user, get_auth_headers, get_backend_url,
+    mock_upstream, provider_login, provider_payload,
+};
+use pretty_assertions::{assert_eq, assert_ne};
+use rauthy_api_types::generic::Language;
+use rauthy_api_types::users::NewUserRequest;
+use serde_json::{Value, json};
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+
+mod common;
+
+fn claims(sub: &str, email: &str, given_name: &str) -> Value {
+    json!({
+        "sub": sub,
+        "email": email,
+        "email_verified": true,
+        "given_name": given_name,
+    })
+}
+
+#[tokio::test]
+async fn test_claim_precedence_upstream() -> Result<(), Box<dyn Error>> {
+    let claims_upstream = Arc::new(Mutex::new(claims(
+        "precedence-upstream",
+        "precedence_upstream@localhost",
+        "Upstream1",
+    )));
+    let upstream_url = mock_upstream(claims_upstream.clone()).await;
+    let payload = provider_payload(
+        "Precedence Upstream",
+        &upstream_url,
+        json!({ "claim_precedence": "upstream" }),
+    );
+    let provider_id = create_provider(&payload).await?;
+
+    assert_eq!(provider_login(&provider_id).await?, 202);
+    let user = find_user("precedence_upstream@localhost").await?.unwrap();
+    assert_eq!(user.given_name.as_deref(), Some("Upstream1"));
+
+    // upstream changes are applied with each login
+    *claims_upstream.lock().unwrap() = claims(
+        "precedence-upstream",
+        "precedence_upstream2@localhost",
+        "Upstream2",
+    );
+    assert_eq!(provider_login(&provider_id).await?, 202);
+    assert!(find_user("precedence_upstream@localhost").await?.is_none());
+    let user = find_user("precedence_upstream2@localhost").await?.unwrap();
+    assert_eq!(user.given_name.as_deref(), Some("Upstream2"));
+
+    // An upstream E-Mail, which belongs to another account, must not fail the login.
+    // The local one is kept in this case, while all other values are still updated.
+    *claims_upstream.lock().unwrap() = claims("precedence-upstream", USERNAME, "Upstream3");
+    assert_eq!(provider_login(&provider_id).await?, 202);
+    let user_collision = find_user("precedence_upstream2@localhost").await?.unwrap();
+    assert_eq!(user_collision.id, user.id);
+    assert_eq!(user_collision.given_name.as_deref(), Some("Upstream3"));
+    assert_ne!(
+        find_user(USERNAME).await?.unwrap().given_name.as_deref(),
+        Some("Upstream3")
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_claim_precedence_first_login() -> Result<(), Box<dyn Error>> {
+    // an already existing local user, which will be linked via `auto_link`
+    let email = "precedence_first_login@localhost";
+    let new_user = NewUserRequest {
+        given_name: Some("Local".to_string()),
+        family_name: None,
+        email: email.to_string(),
+        language: Language::En,
+        roles: vec![],
+        groups: None,
+        user_expires: None,
+        tz: None,
+    };
+    let res = reqwest::Client::new()
+        .post(format!("{}/users", get_backend_url()))
+        .headers(get_auth_headers().await?)
+        .json(&new_user)
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    let claims_upstream = Arc::new(Mutex::new(claims(
+        "precedence-first-login",
+        email,
+        "Upstream1",
+    )));
+    let upstream_url = mock_upstream(claims_upstream.clone()).await;
+    let payload = provider_payload(
+        "Precedence First Login",
+        &upstream_url,
+        json!({ "claim_precedence": "first_login" }),
+    );
+    let provider_id = create_provider(&payload).await?;
+
+    // the login, which creates the link, applies the upstream claims
+    assert_eq!(provider_login(&provider_id).await?, 202);
+    let user = find_user(email).await?.unwrap();
+    assert_eq!(user.given_name.as_deref(), Some("Upstream1"));
+
+    // all later logins keep the local values
+    *claims_upstream.lock().unwrap() = claims(
+        "precedence-first-login",
+        "precedence_first_login2@localhost",
+        "Upstream2",
+    );
+    assert_eq!(provider_login(&provider_id).await?, 202);
+    let user = find_user(email).await?.unwrap();
+    assert_eq!(user.given_name.as_deref(), Some("Upstream1"));
+    assert!(
+        find_user("precedence_first_login2@localhost")
+            .await?
+            .is_none()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_claim_precedence_local() -> Result<(), Box<dyn Error>> {
+    let email = "precedence_local@localhost";
+    let claims_upstream = Arc::new(Mutex::new(claims("precedence-local", email, "Upstream1")));
+    let upstream_url = mock_upstream(claims_upstream.clone()).await;
+    let payload = provider_payload(
+        "Precedence Local",
+        &upstream_url,
+        json!({ "claim_precedence": "local" }),
+    );
+    let provider_id = create_provider(&payload).await?;
+
+    // new users are always created from the upstream claims
+    assert_eq!(provider_login(&provider_id).await?, 202);
+    let user = find_user(email).await?.unwrap();
+    assert_eq!(user.given_name.as_deref(), Some("Upstream1"));
+
+    *claims_upstream.lock().unwrap() = claims(
+        "precedence-local",
+        "precedence_local2@localhost",
+        "Upstream2",
+    );
+    assert_eq!(provider_login(&provider_id).await?, 202);
+    let user = find_user(email).await?.unwrap();
+    assert_eq!(user.given_name.as_deref(), Some("Upstream1"));
+    assert!(find_user("precedence_local2@localhost").await?.is_none());
+
+    Ok(())
+}
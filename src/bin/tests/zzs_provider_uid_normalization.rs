@@ -0,0 +1,104 @@
+use crate::common::{
+    create_provider, find_user, get_auth_headers, get_backend_url, mock_upstream, provider_login,
+    provider_payload,
+};
+use pretty_assertions::{assert_eq, assert_ne};
+use serde_json::{Value, json};
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+
+mod common;
+
+fn claims(sub: &str, email: &str) -> Value {
+    json!({
+        "sub": sub,
+        "email": email,
+        "email_verified": true,
+    })
+}
+
+async fn update_provider(id: &str, payload: &Value) -> Result<u16, Box<dyn Error>> {
+    let res = reqwest::Client::new()
+        .put(format!("{}/providers/{id}", get_backend_url()))
+        .headers(get_auth_headers().await?)
+        .json(payload)
+        .send()
+        .await?;
+    Ok(res.status().as_u16())
+}
+
+#[tokio::test]
+async fn test_uid_normalization_legacy_link() -> Result<(), Box<dyn Error>> {
+    let email = "uid_normalization@localhost";
+    let claims_upstream = Arc::new(Mutex::new(claims(" ABC ", email)));
+    let upstream_url = mock_upstream(claims_upstream.clone()).await;
+    // without `auto_link`, a login that misses the link can't end up with the same user
+    let mut payload = provider_payload(
+        "UID Normalization",
+        &upstream_url,
+        json!({ "auto_link": false, "uid_normalization": "exact" }),
+    );
+    let provider_id = create_provider(&payload).await?;
+
+    // without any links, the normalization can be changed freely
+    payload["uid_normalization"] = json!("trim");
+    assert_eq!(update_provider(&provider_id, &payload).await?, 200);
+    payload["uid_normalization"] = json!("exact");
+    assert_eq!(update_provider(&provider_id, &payload).await?, 200);
+
+    // the legacy link is stored as-is
+    assert_eq!(provider_login(&provider_id).await?, 202);
+    let user = find_user(email).await?.unwrap();
+
+    payload["uid_normalization"] = json!("trim_lowercase");
+    assert_eq!(update_provider(&provider_id, &payload).await?, 200);
+
+    *claims_upstream.lock().unwrap() = claims("abc", email);
+    assert_eq!(provider_login(&provider_id).await?, 202);
+    assert_eq!(find_user(email).await?.unwrap().id, user.id);
+
+    // the upstream id is normalized for the lookup as well
+    *claims_upstream.lock().unwrap() = claims("  Abc", email);
+    assert_eq!(provider_login(&provider_id).await?, 202);
+    assert_eq!(find_user(email).await?.unwrap().id, user.id);
+
+    // the original upstream id is gone and can't be restored for a less normalizing mode
+    for normalization in ["exact", "trim"] {
+        payload["uid_normalization"] = json!(normalization);
+        assert_eq!(update_provider(&provider_id, &payload).await?, 400);
+    }
+    assert_eq!(provider_login(&provider_id).await?, 202);
+    assert_eq!(find_user(email).await?.unwrap().id, user.id);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_uid_normalization_collision() -> Result<(), Box<dyn Error>> {
+    let email_1 = "uid_normalization_1@localhost";
+    let email_2 = "uid_normalization_2@localhost";
+    let claims_upstream = Arc::new(Mutex::new(claims("XYZ", email_1)));
+    let upstream_url = mock_upstream(claims_upstream.clone()).await;
+    let mut payload = provider_payload(
+        "UID Normalization Collision",
+        &upstream_url,
+        json!({ "uid_normalization": "exact" }),
+    );
+    let provider_id = create_provider(&payload).await?;
+
+    assert_eq!(provider_login(&provider_id).await?, 202);
+    *claims_upstream.lock().unwrap() = claims("xyz", email_2);
+    assert_eq!(provider_login(&provider_id).await?, 202);
+    let user_2 = find_user(email_2).await?.unwrap();
+    assert_ne!(find_user(email_1).await?.unwrap().id, user_2.id);
+
+    // both links would collapse into `xyz`
+    payload["uid_normalization"] = json!("trim_lowercase");
+    assert_eq!(update_provider(&provider_id, &payload).await?, 400);
+
+    // nothing has been changed
+    assert_eq!(provider_login(&provider_id).await?, 202);
+    assert_eq!(find_user(email_2).await?.unwrap().id, user_2.id);
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use crate::common::{
+    CLIENT_ID, CLIENT_SECRET, check_status, get_auth_headers, get_backend_url,
+    get_token_set_init_client,
+};
+use chrono::Utc;
+use pretty_assertions::{assert_eq, assert_ne};
+use rauthy_api_types::generic::TokensNotBeforeResponse;
+use rauthy_api_types::oidc::{TokenInfo, TokenRequest, TokenValidationRequest};
+use std::error::Error;
+use std::time::Duration;
+use tokio::time;
+
+mod common;
+
+async fn introspect(token: &str) -> Result<bool, Box<dyn Error>> {
+    let res = reqwest::Client::new()
+        .post(format!("{}/oidc/introspect", get_backend_url()))
+        .basic_auth(CLIENT_ID, Some(CLIENT_SECRET))
+        .form(&TokenValidationRequest {
+            token: token.to_string(),
+        })
+        .send()
+        .await?;
+    let text = check_status(res, 200).await?.text().await?;
+    Ok(serde_json::from_str::<TokenInfo>(&text)?.active)
+}
+
+async fn refresh(refresh_token: String) -> Result<u16, Box<dyn Error>> {
+    let req = TokenRequest {
+        grant_type: "refresh_token".to_string(),
+        code: None,
+        redirect_uri: None,
+        client_id: Some(CLIENT_ID.to_string()),
+        client_secret: Some(CLIENT_SECRET.to_string()),
+        code_verifier: None,
+        device_code: None,
+        username: None,
+        password: None,
+        refresh_token: Some(refresh_token),
+    };
+    let res = reqwest::Client::new()
+        .post(format!("{}/oidc/token", get_backend_url()))
+        .form(&req)
+        .send()
+        .await?;
+    Ok(res.status().as_u16())
+}
+
+async fn fetch_not_before() -> Result<i64, Box<dyn Error>> {
+    // public for resource servers, which validate tokens on their own
+    let res = reqwest::Client::new()
+        .get(format!("{}/tokens/not_before", get_backend_url()))
+        .send()
+        .await?;
+    let resp = check_status(res, 200)
+        .await?
+        .json::<TokensNotBeforeResponse>()
+        .await?;
+    Ok(resp.not_before)
+}
+
+#[tokio::test]
+async fn test_tokens_not_before() -> Result<(), Box<dyn Error>> {
+    let url = format!("{}/tokens/not_before", get_backend_url());
+    let client = reqwest::Client::new();
+
+    let not_before = fetch_not_before().await?;
+    assert!(not_before < Utc::now().timestamp());
+
+    let ts = get_token_set_init_client().await;
+    assert!(introspect(&ts.access_token).await?);
+
+    let res = client.post(&url).send().await?;
+    check_status(res, 401).await?;
+
+    // make sure the bump happens in a later second than the `iat`
+    time::sleep(Duration::from_millis(1100)).await;
+    let res = client
+        .post(&url)
+        .headers(get_auth_headers().await?)
+        .send()
+        .await?;
+    let bumped = check_status(res, 200)
+        .await?
+        .json::<TokensNotBeforeResponse>()
+        .await?
+        .not_before;
+    assert!(bumped > not_before);
+    assert_eq!(fetch_not_before().await?, bumped);
+
+    assert!(!introspect(&ts.access_token).await?);
+    assert_ne!(refresh(ts.refresh_token.unwrap()).await?, 200);
+
+    // The token endpoint keeps working right away. Tokens issued in the same second as the bump
+    // stay valid.
+    let ts = get_token_set_init_client().await;
+    assert!(introspect(&ts.access_token).await?);
+
+    Ok(())
+}
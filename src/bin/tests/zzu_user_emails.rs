@@ -0,0 +1,289 @@
+use crate::common::{
+    CLIENT_ID, CLIENT_SECRET, USERNAME, check_status, get_auth_headers, get_backend_url,
+    get_solved_pow,
+};
+use pretty_assertions::{assert_eq, assert_ne};
+use rauthy_api_types::generic::Language;
+use rauthy_api_types::oidc::TokenRequest;
+use rauthy_api_types::users::{
+    NewUserRequest, RequestResetRequest, UpdateUserRequest, UserEmailRequest, UserEmailResponse,
+    UserResponse,
+};
+use reqwest::header::ACCEPT;
+use std::error::Error;
+
+mod common;
+
+// seeded with the DEV data
+const USER_ID: &str = "za9UxpH7XVxqrtpEbThoqvn2";
+const INIT_ADMIN_ID: &str = "m4PJ3TnyP32LA8hzY23deme3";
+const EMAIL_PRIMARY: &str = "admin@localhost";
+const EMAIL_ADD: &str = "admin.secondary@localhost";
+const EMAIL_ADD_CONFIRM_ID: &str =
+    "EmAdd8jxH0UfJ7gtIvZq3Nc2ZdPmgXsQ1bLR4kTnWyVoEa6Bp9CrDuS5FlYhKzMx";
+const PWD_RESET_ID: &str = "PwdRst5tGkWn2QxLb7HcVu0ZyJ3mDe8FaRo1NsKi6TpXqMjB4lEvCh9SdUgYwPzA";
+const PASSWORD: &str = "123EvenMoreSafe";
+
+async fn fetch_emails(user_id: &str) -> Result<Vec<UserEmailResponse>, Box<dyn Error>> {
+    let res = reqwest::Client::new()
+        .get(format!("{}/users/{user_id}/emails", get_backend_url()))
+        .headers(get_auth_headers().await?)
+        .send()
+        .await?;
+    Ok(check_status(res, 200)
+        .await?
+        .json::<Vec<UserEmailResponse>>()
+        .await?)
+}
+
+async fn add_email(user_id: &str, email: &str) -> Result<u16, Box<dyn Error>> {
+    let res = reqwest::Client::new()
+        .post(format!("{}/users/{user_id}/emails", get_backend_url()))
+        .headers(get_auth_headers().await?)
+        .json(&UserEmailRequest {
+            email: email.to_string(),
+        })
+        .send()
+        .await?;
+    Ok(res.status().as_u16())
+}
+
+async fn set_primary(user_id: &str, email: &str) -> Result<u16, Box<dyn Error>> {
+    let res = reqwest::Client::new()
+        .put(format!(
+            "{}/users/{user_id}/emails/primary",
+            get_backend_url()
+        ))
+        .headers(get_auth_headers().await?)
+        .json(&UserEmailRequest {
+            email: email.to_string(),
+        })
+        .send()
+        .await?;
+    Ok(res.status().as_u16())
+}
+
+async fn remove_email(user_id: &str, email: &str) -> Result<u16, Box<dyn Error>> {
+    let res = reqwest::Client::new()
+        .delete(format!(
+            "{}/users/{user_id}/emails/{email}",
+            get_backend_url()
+        ))
+        .headers(get_auth_headers().await?)
+        .send()
+        .await?;
+    Ok(res.status().as_u16())
+}
+
+async fn login(username: &str) -> Result<u16, Box<dyn Error>> {
+    let req = TokenRequest {
+        grant_type: "password".to_string(),
+        code: None,
+        redirect_uri: None,
+        client_id: Some(CLIENT_ID.to_string()),
+        client_secret: Some(CLIENT_SECRET.to_string()),
+        code_verifier: None,
+        device_code: None,
+        username: Some(username.to_string()),
+        password: Some(PASSWORD.to_string()),
+        refresh_token: None,
+    };
+    let res = reqwest::Client::new()
+        .post(format!("{}/oidc/token", get_backend_url()))
+        .form(&req)
+        .send()
+        .await?;
+    Ok(res.status().as_u16())
+}
+
+async fn request_reset(email: &str) -> Result<(), Box<dyn Error>> {
+    let res = reqwest::Client::new()
+        .post(format!("{}/users/request_reset", get_backend_url()))
+        .json(&RequestResetRequest {
+            email: email.to_string(),
+            redirect_uri: None,
+            pow: get_solved_pow().await,
+        })
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+    Ok(())
+}
+
+/// Returns `true` if the seeded password reset link is still usable.
+async fn pwd_reset_exists() -> Result<bool, Box<dyn Error>> {
+    let res = reqwest::Client::new()
+        .get(format!(
+            "{}/users/{USER_ID}/reset/{PWD_RESET_ID}",
+            get_backend_url()
+        ))
+        .header(ACCEPT, "application/json")
+        .send()
+        .await?;
+    Ok(res.status().is_success())
+}
+
+#[tokio::test]
+async fn test_user_emails() -> Result<(), Box<dyn Error>> {
+    let auth_headers = get_auth_headers().await?;
+    let client = reqwest::Client::new();
+
+    // give the user a known password to test logins
+    let res = client
+        .get(format!("{}/users/{USER_ID}", get_backend_url()))
+        .headers(auth_headers.clone())
+        .send()
+        .await?;
+    let user = check_status(res, 200).await?.json::<UserResponse>().await?;
+    assert_eq!(user.email, EMAIL_PRIMARY);
+    let upd_req = UpdateUserRequest {
+        email: user.email,
+        given_name: user.given_name,
+        family_name: user.family_name,
+        language: None,
+        password: Some(PASSWORD.to_string()),
+        roles: user.roles,
+        groups: user.groups,
+        enabled: true,
+        email_verified: true,
+        user_expires: None,
+        user_values: None,
+    };
+    let res = client
+        .put(format!("{}/users/{USER_ID}", get_backend_url()))
+        .headers(auth_headers.clone())
+        .json(&upd_req)
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    // the primary address always comes first
+    let emails = fetch_emails(USER_ID).await?;
+    assert_eq!(emails.len(), 2);
+    assert_eq!(emails[0].email, EMAIL_PRIMARY);
+    assert!(emails[0].primary);
+    assert!(emails[0].verified);
+    assert_eq!(emails[1].email, EMAIL_ADD);
+    assert!(!emails[1].primary);
+    assert!(!emails[1].verified);
+
+    // a pending address can't be used for anything yet
+    assert_eq!(login(EMAIL_PRIMARY).await?, 200);
+    assert_ne!(login(EMAIL_ADD).await?, 200);
+    assert_eq!(set_primary(USER_ID, EMAIL_ADD).await?, 400);
+    request_reset(EMAIL_ADD).await?;
+    assert!(pwd_reset_exists().await?);
+
+    // primary addresses are unique, for the user itself as well
+    assert_eq!(add_email(USER_ID, USERNAME).await?, 406);
+    assert_eq!(add_email(USER_ID, EMAIL_PRIMARY).await?, 400);
+    assert_eq!(
+        add_email(USER_ID, &EMAIL_PRIMARY.to_uppercase()).await?,
+        400
+    );
+
+    // pending addresses don't block other users
+    assert_eq!(add_email(INIT_ADMIN_ID, EMAIL_ADD).await?, 200);
+    assert_eq!(remove_email(INIT_ADMIN_ID, EMAIL_ADD).await?, 200);
+    assert_eq!(remove_email(INIT_ADMIN_ID, EMAIL_ADD).await?, 404);
+
+    // confirm the address via the link from the E-Mail
+    let url_confirm = format!(
+        "{}/users/{USER_ID}/emails/confirm/{EMAIL_ADD_CONFIRM_ID}",
+        get_backend_url()
+    );
+    let client_no_redirect = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()?;
+    let res = client_no_redirect.get(&url_confirm).send().await?;
+    check_status(res, 302).await?;
+    // the link can only be used once
+    let res = client_no_redirect.get(&url_confirm).send().await?;
+    assert_ne!(res.status(), 302);
+
+    let emails = fetch_emails(USER_ID).await?;
+    assert_eq!(emails.len(), 2);
+    assert_eq!(emails[1].email, EMAIL_ADD);
+    assert!(emails[1].verified);
+
+    // verified additional addresses are unique against everyone else
+    assert_eq!(add_email(INIT_ADMIN_ID, EMAIL_ADD).await?, 406);
+    let new_user = NewUserRequest {
+        email: EMAIL_ADD.to_string(),
+        family_name: None,
+        given_name: None,
+        language: Language::En,
+        groups: None,
+        roles: vec!["admin".to_string()],
+        user_expires: None,
+        tz: None,
+    };
+    let res = client
+        .post(format!("{}/users", get_backend_url()))
+        .headers(auth_headers.clone())
+        .json(&new_user)
+        .send()
+        .await?;
+    check_status(res, 406).await?;
+
+    let res = client
+        .post(format!("{}/users", get_backend_url()))
+        .headers(auth_headers.clone())
+        .json(&NewUserRequest {
+            email: "user.emails@localhost".to_string(),
+            ..new_user
+        })
+        .send()
+        .await?;
+    let other = check_status(res, 200).await?.json::<UserResponse>().await?;
+    let res = client
+        .put(format!("{}/users/{}", get_backend_url(), other.id))
+        .headers(auth_headers.clone())
+        .json(&UpdateUserRequest {
+            email: EMAIL_ADD.to_string(),
+            given_name: Some("Emails".to_string()),
+            family_name: None,
+            language: None,
+            password: None,
+            roles: other.roles,
+            groups: None,
+            enabled: true,
+            email_verified: true,
+            user_expires: None,
+            user_values: None,
+        })
+        .send()
+        .await?;
+    check_status(res, 406).await?;
+
+    // login and password reset via the additional address
+    assert_eq!(login(EMAIL_ADD).await?, 200);
+    assert_eq!(login(&EMAIL_ADD.to_uppercase()).await?, 200);
+    request_reset(EMAIL_ADD).await?;
+    assert!(!pwd_reset_exists().await?);
+
+    // swap the primary address, the old one is kept as an additional one
+    assert_eq!(set_primary(USER_ID, EMAIL_ADD).await?, 200);
+    let emails = fetch_emails(USER_ID).await?;
+    assert_eq!(emails.len(), 2);
+    assert_eq!(emails[0].email, EMAIL_ADD);
+    assert!(emails[0].primary);
+    assert_eq!(emails[1].email, EMAIL_PRIMARY);
+    assert!(!emails[1].primary);
+    assert!(emails[1].verified);
+    assert_eq!(login(EMAIL_PRIMARY).await?, 200);
+    assert_eq!(login(EMAIL_ADD).await?, 200);
+
+    // the primary address can never be removed
+    assert_eq!(remove_email(USER_ID, EMAIL_ADD).await?, 400);
+
+    // restore the original state
+    assert_eq!(set_primary(USER_ID, EMAIL_PRIMARY).await?, 200);
+    assert_eq!(remove_email(USER_ID, EMAIL_ADD).await?, 200);
+    let emails = fetch_emails(USER_ID).await?;
+    assert_eq!(emails.len(), 1);
+    assert_eq!(emails[0].email, EMAIL_PRIMARY);
+    assert_ne!(login(EMAIL_ADD).await?, 200);
+
+    Ok(())
+}
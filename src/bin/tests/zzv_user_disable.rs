@@ -0,0 +1,192 @@
+use crate::common::{
+    CLIENT_ID, CLIENT_SECRET, check_status, create_provider, find_user, get_auth_headers,
+    get_backend_url, mock_upstream, provider_login, provider_payload,
+};
+use pretty_assertions::{assert_eq, assert_ne};
+use rauthy_api_types::generic::Language;
+use rauthy_api_types::oidc::{TokenInfo, TokenRequest, TokenValidationRequest};
+use rauthy_api_types::users::{NewUserRequest, UpdateUserRequest, UserResponse};
+use rauthy_common::utils::get_rand;
+use reqwest::Response;
+use serde_json::json;
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+
+mod common;
+
+const PASSWORD: &str = "123SoftDeleteSafe";
+
+async fn post_state(user_id: &str, action: &str) -> Result<Response, Box<dyn Error>> {
+    Ok(reqwest::Client::new()
+        .post(format!("{}/users/{user_id}/{action}", get_backend_url()))
+        .headers(get_auth_headers().await?)
+        .send()
+        .await?)
+}
+
+async fn token(req: TokenRequest) -> Result<Response, Box<dyn Error>> {
+    Ok(reqwest::Client::new()
+        .post(format!("{}/oidc/token", get_backend_url()))
+        .form(&req)
+        .send()
+        .await?)
+}
+
+fn password_req(email: &str) -> TokenRequest {
+    TokenRequest {
+        grant_type: "password".to_string(),
+        code: None,
+        redirect_uri: None,
+        client_id: Some(CLIENT_ID.to_string()),
+        client_secret: Some(CLIENT_SECRET.to_string()),
+        code_verifier: None,
+        device_code: None,
+        username: Some(email.to_string()),
+        password: Some(PASSWORD.to_string()),
+        refresh_token: None,
+    }
+}
+
+async fn introspect(token: &str) -> Result<bool, Box<dyn Error>> {
+    let res = reqwest::Client::new()
+        .post(format!("{}/oidc/introspect", get_backend_url()))
+        .basic_auth(CLIENT_ID, Some(CLIENT_SECRET))
+        .form(&TokenValidationRequest {
+            token: token.to_string(),
+        })
+        .send()
+        .await?;
+    let text = check_status(res, 200).await?.text().await?;
+    Ok(serde_json::from_str::<TokenInfo>(&text)?.active)
+}
+
+#[tokio::test]
+async fn test_user_disable_enable() -> Result<(), Box<dyn Error>> {
+    let auth_headers = get_auth_headers().await?;
+    let client = reqwest::Client::new();
+    let email = format!("{}@disable.localhost", get_rand(8).to_lowercase());
+
+    let res = client
+        .post(format!("{}/users", get_backend_url()))
+        .headers(auth_headers.clone())
+        .json(&NewUserRequest {
+            email: email.clone(),
+            family_name: None,
+            given_name: Some("Disable".to_string()),
+            language: Language::En,
+            groups: None,
+            roles: vec!["admin".to_string()],
+            user_expires: None,
+            tz: None,
+        })
+        .send()
+        .await?;
+    let user = check_status(res, 200).await?.json::<UserResponse>().await?;
+    let res = client
+        .put(format!("{}/users/{}", get_backend_url(), user.id))
+        .headers(auth_headers.clone())
+        .json(&UpdateUserRequest {
+            email: email.clone(),
+            given_name: user.given_name,
+            family_name: None,
+            language: None,
+            password: Some(PASSWORD.to_string()),
+            roles: user.roles,
+            groups: None,
+            enabled: true,
+            email_verified: true,
+            user_expires: None,
+            user_values: None,
+        })
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    let res = token(password_req(&email)).await?;
+    let ts = check_status(res, 200)
+        .await?
+        .json::<serde_json::Value>()
+        .await?;
+    let access_token = ts["access_token"].as_str().unwrap().to_string();
+    let refresh_token = ts["refresh_token"].as_str().unwrap().to_string();
+    assert!(introspect(&access_token).await?);
+
+    // an admin can never disable the own account
+    let res = post_state("m4PJ3TnyP32LA8hzY23deme3", "disable").await?;
+    check_status(res, 400).await?;
+
+    let res = post_state(&user.id, "disable").await?;
+    let disabled = check_status(res, 200).await?.json::<UserResponse>().await?;
+    assert!(!disabled.enabled);
+    assert!(disabled.disabled_at.is_some());
+
+    // all existing tokens are revoked and new logins are rejected
+    assert!(!introspect(&access_token).await?);
+    let res = token(TokenRequest {
+        grant_type: "refresh_token".to_string(),
+        username: None,
+        password: None,
+        refresh_token: Some(refresh_token),
+        ..password_req(&email)
+    })
+    .await?;
+    assert_ne!(res.status(), 200);
+    let res = token(password_req(&email)).await?;
+    assert_ne!(res.status(), 200);
+
+    // the user itself is kept until the retention period is over
+    let res = post_state(&user.id, "enable").await?;
+    let enabled = check_status(res, 200).await?.json::<UserResponse>().await?;
+    assert!(enabled.enabled);
+    assert_eq!(enabled.disabled_at, None);
+
+    let res = token(password_req(&email)).await?;
+    check_status(res, 200).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_user_disable_federated() -> Result<(), Box<dyn Error>> {
+    let email = "disable.federated@localhost";
+    let claims = Arc::new(Mutex::new(json!({
+        "sub": "disable-federated",
+        "email": email,
+        "email_verified": true,
+    })));
+    let upstream_url = mock_upstream(claims).await;
+    let payload = provider_payload("Disable Federated", &upstream_url, json!({}));
+    let provider_id = create_provider(&payload).await?;
+
+    assert_eq!(provider_login(&provider_id).await?, 202);
+    let user = find_user(email).await?.unwrap();
+
+    let res = post_state(&user.id, "disable").await?;
+    check_status(res, 200).await?;
+    // the link is kept, but the upstream login must not bypass the soft-delete
+    assert_eq!(provider_login(&provider_id).await?, 401);
+    assert_eq!(find_user(email).await?.unwrap().id, user.id);
+
+    let res = post_state(&user.id, "enable").await?;
+    check_status(res, 200).await?;
+    assert_eq!(provider_login(&provider_id).await?, 202);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_user_disabled_retention() -> Result<(), Box<dyn Error>> {
+    // Seeded with the DEV data, disabled longer ago than `disabled_retention_days`. The cleanup
+    // runs once right after the startup.
+    let res = reqwest::Client::new()
+        .get(format!(
+            "{}/users/DsbldRtntnExprdUsr000001",
+            get_backend_url()
+        ))
+        .headers(get_auth_headers().await?)
+        .send()
+        .await?;
+    check_status(res, 404).await?;
+
+    Ok(())
+}
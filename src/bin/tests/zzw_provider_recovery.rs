@@ -0,0 +1,105 @@
+use crate::common::{
+    check_status, create_provider, find_user, get_auth_headers, get_backend_url, mock_upstream,
+    provider_login, provider_payload, provider_recovery,
+};
+use pretty_assertions::assert_eq;
+use rauthy_api_types::users::UserResponse;
+use reqwest::header::{ACCEPT, LOCATION};
+use serde_json::{Value, json};
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+
+mod common;
+
+fn claims(sub: &str, email: &str) -> Value {
+    json!({
+        "sub": sub,
+        "email": email,
+        "email_verified": true,
+    })
+}
+
+#[tokio::test]
+async fn test_provider_recovery() -> Result<(), Box<dyn Error>> {
+    let email = "provider.recovery@localhost";
+    let claims_upstream = Arc::new(Mutex::new(claims("recovery-linked", email)));
+    let upstream_url = mock_upstream(claims_upstream.clone()).await;
+    let payload = provider_payload("Provider Recovery", &upstream_url, json!({}));
+    let provider_id = create_provider(&payload).await?;
+
+    assert_eq!(provider_login(&provider_id).await?, 202);
+    let user = find_user(email).await?.unwrap();
+
+    // the callback redirects to the password reset page for the linked user
+    let res = provider_recovery(&provider_id).await?;
+    let res = check_status(res, 202).await?;
+    let location = res.headers().get(LOCATION).unwrap().to_str()?.to_string();
+    assert!(
+        location.starts_with(&format!("{}/users/{}/reset/", get_backend_url(), user.id)),
+        "{location}"
+    );
+
+    let res = reqwest::Client::new()
+        .get(&location)
+        .header(ACCEPT, "application/json")
+        .send()
+        .await?;
+    check_status(res, 200).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_provider_recovery_unlinked() -> Result<(), Box<dyn Error>> {
+    let email = "provider.recovery.unlinked@localhost";
+    let claims_upstream = Arc::new(Mutex::new(claims("recovery-unlinked", email)));
+    let upstream_url = mock_upstream(claims_upstream.clone()).await;
+    let payload = provider_payload("Provider Recovery Unlinked", &upstream_url, json!({}));
+    let provider_id = create_provider(&payload).await?;
+
+    // a recovery never onboards new users, even with `auto_onboarding`
+    assert_eq!(provider_recovery(&provider_id).await?.status(), 403);
+    assert!(find_user(email).await?.is_none());
+
+    // a matching E-Mail alone is not enough, even with `auto_link`
+    assert_eq!(provider_login(&provider_id).await?, 202);
+    *claims_upstream.lock().unwrap() = claims("recovery-unlinked-other", email);
+    assert_eq!(provider_recovery(&provider_id).await?.status(), 403);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_provider_recovery_admin() -> Result<(), Box<dyn Error>> {
+    let email = "provider.recovery.admin@localhost";
+    let claims_upstream = Arc::new(Mutex::new(json!({
+        "sub": "recovery-admin",
+        "email": email,
+        "email_verified": true,
+        "role": "admin",
+    })));
+    let upstream_url = mock_upstream(claims_upstream).await;
+    let payload = provider_payload(
+        "Provider Recovery Admin",
+        &upstream_url,
+        json!({
+            "admin_claim_path": "$.role",
+            "admin_claim_value": "admin",
+        }),
+    );
+    let provider_id = create_provider(&payload).await?;
+
+    assert_eq!(provider_login(&provider_id).await?, 202);
+    let user_id = find_user(email).await?.unwrap().id;
+    let res = reqwest::Client::new()
+        .get(format!("{}/users/{user_id}", get_backend_url()))
+        .headers(get_auth_headers().await?)
+        .send()
+        .await?;
+    let user = check_status(res, 200).await?.json::<UserResponse>().await?;
+    assert!(user.roles.iter().any(|r| r == "rauthy_admin"));
+
+    assert_eq!(provider_recovery(&provider_id).await?.status(), 403);
+
+    Ok(())
+}
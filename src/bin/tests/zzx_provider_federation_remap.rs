@@ -0,0 +1,119 @@
+use crate::common::{
+    check_status, create_provider, find_user, get_auth_headers, get_backend_url, mock_upstream,
+    provider_login, provider_payload,
+};
+use pretty_assertions::assert_eq;
+use rauthy_api_types::auth_providers::ProviderFederationResponse;
+use reqwest::Response;
+use serde_json::{Value, json};
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+
+mod common;
+
+fn claims(sub: &str, email: &str) -> Value {
+    json!({
+        "sub": sub,
+        "email": email,
+        "email_verified": true,
+    })
+}
+
+async fn remap(provider_id: &str, user_id: &str, uid: &str) -> Result<Response, Box<dyn Error>> {
+    Ok(reqwest::Client::new()
+        .put(format!(
+            "{}/providers/{provider_id}/federations/{user_id}",
+            get_backend_url()
+        ))
+        .headers(get_auth_headers().await?)
+        .json(&json!({ "federation_uid": uid }))
+        .send()
+        .await?)
+}
+
+#[tokio::test]
+async fn test_provider_federation_remap() -> Result<(), Box<dyn Error>> {
+    let email_1 = "federation.remap_1@localhost";
+    let email_2 = "federation.remap_2@localhost";
+    let claims_upstream = Arc::new(Mutex::new(claims("OLD-ID", email_1)));
+    let upstream_url = mock_upstream(claims_upstream.clone()).await;
+    let payload = provider_payload(
+        "Federation Remap",
+        &upstream_url,
+        json!({ "uid_normalization": "trim_lowercase" }),
+    );
+    let provider_id = create_provider(&payload).await?;
+
+    assert_eq!(provider_login(&provider_id).await?, 202);
+    let user_1 = find_user(email_1).await?.unwrap();
+    *claims_upstream.lock().unwrap() = claims("other-id", email_2);
+    assert_eq!(provider_login(&provider_id).await?, 202);
+    let user_2 = find_user(email_2).await?.unwrap();
+
+    // The upstream provider migrated its ids, which makes the existing link stale. The login
+    // can't fix it by itself, because the user is already linked to this provider.
+    *claims_upstream.lock().unwrap() = claims("new-id", email_1);
+    assert_eq!(provider_login(&provider_id).await?, 406);
+
+    // the new id is stored with the normalization of the provider
+    let res = remap(&provider_id, &user_1.id, " NEW-ID ").await?;
+    let federation = check_status(res, 200)
+        .await?
+        .json::<ProviderFederationResponse>()
+        .await?;
+    assert_eq!(federation.user_id, user_1.id);
+    assert_eq!(federation.provider_id, provider_id);
+    assert_eq!(federation.federation_uid, "new-id");
+
+    assert_eq!(provider_login(&provider_id).await?, 202);
+    assert_eq!(find_user(email_1).await?.unwrap().id, user_1.id);
+
+    // re-mapping to the current id is a no-op
+    let res = remap(&provider_id, &user_1.id, "new-id").await?;
+    check_status(res, 200).await?;
+
+    // the new id must not be linked to another user already, after the normalization
+    let res = remap(&provider_id, &user_1.id, " OTHER-ID").await?;
+    check_status(res, 406).await?;
+    assert_eq!(provider_login(&provider_id).await?, 202);
+    assert_eq!(find_user(email_1).await?.unwrap().id, user_1.id);
+    *claims_upstream.lock().unwrap() = claims("other-id", email_2);
+    assert_eq!(provider_login(&provider_id).await?, 202);
+    assert_eq!(find_user(email_2).await?.unwrap().id, user_2.id);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_provider_federation_remap_invalid() -> Result<(), Box<dyn Error>> {
+    let email = "federation.remap_invalid@localhost";
+    let claims_upstream = Arc::new(Mutex::new(claims("invalid-id", email)));
+    let upstream_url = mock_upstream(claims_upstream).await;
+    let payload = provider_payload("Federation Remap Invalid", &upstream_url, json!({}));
+    let provider_id = create_provider(&payload).await?;
+
+    assert_eq!(provider_login(&provider_id).await?, 202);
+    let user = find_user(email).await?.unwrap();
+
+    let res = reqwest::Client::new()
+        .put(format!(
+            "{}/providers/{provider_id}/federations/{}",
+            get_backend_url(),
+            user.id
+        ))
+        .json(&json!({ "federation_uid": "new-id" }))
+        .send()
+        .await?;
+    check_status(res, 401).await?;
+
+    let res = remap(&provider_id, &user.id, "").await?;
+    check_status(res, 400).await?;
+
+    // the init admin is not linked to this provider
+    let res = remap(&provider_id, "m4PJ3TnyP32LA8hzY23deme3", "new-id").await?;
+    check_status(res, 404).await?;
+    let res = remap("DoesNotExist", &user.id, "new-id").await?;
+    check_status(res, 404).await?;
+
+    Ok(())
+}
@@ -75,6 +75,27 @@ pub static API_KEY_LENGTH: usize = 64;
 pub static DEVICE_KEY_LENGTH: u8 = 64;
 pub static EVENTS_LATEST_LIMIT: u16 = 100;
 pub static GRANT_TYPE_DEVICE_CODE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+/// JWT claims which are needed for token validation and therefore can never be renamed or overwritten
+/// by a client's claim mapping.
+pub static RESERVED_CLAIMS: [&str; 17] = [
+    "iss",
+    "sub",
+    "aud",
+    "exp",
+    "nbf",
+    "iat",
+    "jti",
+    "azp",
+    "typ",
+    "scope",
+    "cnf",
+    "did",
+    "amr",
+    "auth_time",
+    "at_hash",
+    "sid",
+    "nonce",
+];
 pub const UPSTREAM_AUTH_CALLBACK_TIMEOUT_SECS: u16 = 300;
 pub const CACHE_TTL_APP: Option<i64> = Some(43200);
 pub const CACHE_TTL_AUTH_PROVIDER_CALLBACK: Option<i64> =
@@ -84,14 +105,18 @@ pub const CACHE_TTL_USER: Option<i64> = Some(600);
 
 pub static IDX_APP_VERSION: &str = "rauthy_app_version";
 pub static IDX_AUTH_PROVIDER: &str = "auth_provider_";
+pub static IDX_AUTH_STATE: &str = "auth_state_";
 pub static IDX_AUTH_PROVIDER_LOGO: &str = "auth_provider_logo_";
 pub static IDX_AUTH_PROVIDER_TEMPLATE: &str = "provider_json_tpl";
 pub static IDX_CLIENTS: &str = "clients_";
 pub static IDX_CLIENT_LOGO: &str = "client_logo_";
+pub static IDX_CLIENT_TOKENS_NBF: &str = "client_tokens_nbf";
+pub static IDX_GLOBAL_TOKENS_NBF: &str = "global_tokens_nbf";
 pub static IDX_GROUPS: &str = "groups_";
 pub static IDX_JWK_KID: &str = "jwk_kid_";
 pub static IDX_JWK_LATEST: &str = "jwk_latest_";
 pub static IDX_JWKS: &str = "jkws_";
+pub static IDX_LOCKOUT_NOTIFY: &str = "lockout_notify_";
 pub static IDX_LOGIN_TIME: &str = "login_time_";
 pub static IDX_MFA_MOD: &str = "mfa_mod_";
 pub static IDX_PASSWORD_RULES: &str = "password_rules_";
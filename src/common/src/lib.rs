@@ -7,6 +7,7 @@ pub mod constants;
 pub mod logging;
 pub mod markdown;
 pub mod password_hasher;
+pub mod redact;
 pub mod regex;
 pub mod sanitize_html;
 pub mod utils;
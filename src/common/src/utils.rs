@@ -112,6 +112,21 @@ pub fn base64_url_no_pad_decode_buf(b64: &str, buf: &mut Vec<u8>) -> Result<(),
 }
 
 #[inline(always)]
+/// Builds a strong `ETag` from the given response body.
+pub fn etag_from_body(body: &[u8]) -> String {
+    let hash = ring::digest::digest(&ring::digest::SHA256, body);
+    format!("\"{}\"", base64_url_no_pad_encode(&hash.as_ref()[..16]))
+}
+
+/// Checks if an `If-None-Match` header value matches the given `etag`. Uses the weak
+/// comparison, as required by RFC 9110.
+pub fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    if_none_match
+        .split(',')
+        .map(str::trim)
+        .any(|value| value == "*" || value == etag || value.strip_prefix("W/") == Some(etag))
+}
+
 pub fn new_store_id() -> String {
     get_rand(24)
 }
@@ -218,6 +233,46 @@ pub fn build_trusted_proxies(proxies: &[String]) -> Vec<cidr::IpCidr> {
     proxies
 }
 
+/// Checks if the (lowercase) E-Mail `domain` matches the given `pattern`.
+///
+/// A pattern is either an exact domain like `example.com`, or a wildcard like `*.example.com`,
+/// which matches any subdomain, but not the apex domain itself.
+pub fn email_domain_matches(pattern: &str, domain: &str) -> bool {
+    if let Some(suffix) = pattern.strip_prefix("*.") {
+        domain
+            .strip_suffix(suffix)
+            .is_some_and(|sub| sub.len() > 1 && sub.ends_with('.'))
+    } else {
+        pattern == domain
+    }
+}
+
+/// Checks if there is any domain that would be matched by both patterns.
+pub fn email_domain_patterns_overlap(a: &str, b: &str) -> bool {
+    match (a.strip_prefix("*."), b.strip_prefix("*.")) {
+        (None, None) => a == b,
+        (Some(_), None) => email_domain_matches(a, b),
+        (None, Some(_)) => email_domain_matches(b, a),
+        (Some(sa), Some(sb)) => {
+            sa == sb || email_domain_matches(a, sb) || email_domain_matches(b, sa)
+        }
+    }
+}
+
+/// Checks if the (lowercase) E-Mail `domain` is blocked by the given blacklist `pattern`.
+///
+/// Other than for the allowlist, an exact domain like `evil.net` blocks all of its subdomains
+/// as well, like it always did. A wildcard like `*.evil.net` only blocks the subdomains.
+pub fn email_domain_blocked(pattern: &str, domain: &str) -> bool {
+    if pattern.starts_with("*.") {
+        email_domain_matches(pattern, domain)
+    } else {
+        domain
+            .strip_suffix(pattern)
+            .is_some_and(|sub| sub.is_empty() || (sub.len() > 1 && sub.ends_with('.')))
+    }
+}
+
 #[inline(always)]
 fn ip_from_cust_header(headers: &HeaderMap) -> Option<IpAddr> {
     // If a custom override has been set, try this first and use the default as fallback
@@ -283,6 +338,82 @@ mod tests {
         assert_eq!(rnd.len(), 1024);
     }
 
+    #[test]
+    fn test_etag() {
+        let etag = etag_from_body(b"{\"keys\":[]}");
+        assert!(etag.starts_with('"') && etag.ends_with('"'));
+        assert_eq!(etag, etag_from_body(b"{\"keys\":[]}"));
+        assert_ne!(etag, etag_from_body(b"{\"keys\":[{}]}"));
+
+        assert!(etag_matches(&etag, &etag));
+        assert!(etag_matches(&format!("W/{etag}"), &etag));
+        assert!(etag_matches(&format!("\"abc\", {etag}"), &etag));
+        assert!(etag_matches("*", &etag));
+        assert!(!etag_matches("\"abc\"", &etag));
+        assert!(!etag_matches("", &etag));
+    }
+
+    #[test]
+    fn test_email_domain_matches() {
+        assert!(email_domain_matches("example.com", "example.com"));
+        assert!(!email_domain_matches("example.com", "sub.example.com"));
+        assert!(!email_domain_matches("example.com", "evil-example.com"));
+
+        assert!(email_domain_matches("*.example.com", "sub.example.com"));
+        assert!(email_domain_matches("*.example.com", "a.b.example.com"));
+        assert!(!email_domain_matches("*.example.com", "example.com"));
+        assert!(!email_domain_matches("*.example.com", "evilexample.com"));
+        assert!(!email_domain_matches("*.example.com", ".example.com"));
+    }
+
+    #[test]
+    fn test_email_domain_blocked() {
+        assert!(email_domain_blocked("evil.net", "evil.net"));
+        assert!(email_domain_blocked("evil.net", "sub.evil.net"));
+        assert!(email_domain_blocked("evil.net", "a.b.evil.net"));
+        assert!(!email_domain_blocked("evil.net", "notevil.net"));
+        assert!(!email_domain_blocked("evil.net", ".evil.net"));
+
+        assert!(email_domain_blocked("*.evil.net", "sub.evil.net"));
+        assert!(!email_domain_blocked("*.evil.net", "evil.net"));
+    }
+
+    #[test]
+    fn test_email_domain_patterns_overlap() {
+        assert!(email_domain_patterns_overlap("example.com", "example.com"));
+        assert!(!email_domain_patterns_overlap("example.com", "example.org"));
+
+        assert!(email_domain_patterns_overlap(
+            "*.example.com",
+            "sub.example.com"
+        ));
+        assert!(email_domain_patterns_overlap(
+            "sub.example.com",
+            "*.example.com"
+        ));
+        assert!(!email_domain_patterns_overlap(
+            "*.example.com",
+            "example.com"
+        ));
+
+        assert!(email_domain_patterns_overlap(
+            "*.example.com",
+            "*.example.com"
+        ));
+        assert!(email_domain_patterns_overlap(
+            "*.example.com",
+            "*.eu.example.com"
+        ));
+        assert!(email_domain_patterns_overlap(
+            "*.eu.example.com",
+            "*.example.com"
+        ));
+        assert!(!email_domain_patterns_overlap(
+            "*.eu.example.com",
+            "*.us.example.com"
+        ));
+    }
+
     #[test]
     fn test_trusted_proxy_check() {
         let raw = vec![
@@ -9,6 +9,14 @@ use std::borrow::Cow;
 use std::fmt::Display;
 use tracing::warn;
 
+/// The attributes for a cookie, that are not part of its value.
+struct CookieAttrs<'a> {
+    mode: &'a CookieMode,
+    path: &'a str,
+    domain: Option<&'a str>,
+    same_site: SameSite,
+}
+
 pub struct ApiCookie;
 
 impl ApiCookie {
@@ -31,13 +39,53 @@ impl ApiCookie {
         V: Into<Cow<'b, str>> + Display,
     {
         let access = &RauthyConfig::get().vars.access;
-        let path = if access.cookie_set_path { "/auth" } else { "/" };
-        let (name, secure, path) = match access.cookie_mode {
-            CookieMode::Host => (format!("__Host-{name}"), true, "/"),
-            CookieMode::Secure => (format!("__Secure-{name}"), true, path),
+        let attrs = CookieAttrs {
+            mode: &access.cookie_mode,
+            path: if access.cookie_set_path { "/auth" } else { "/" },
+            domain: None,
+            same_site,
+        };
+        Self::build_with_attrs(name, Self::encrypt_value(value), max_age, attrs)
+    }
+
+    /// Builds the session cookie with the `session_cookie_*` attributes from the config.
+    /// Must be used for setting the session cookie and for deleting it, because browsers
+    /// will only overwrite a cookie with a matching `Domain` and `Path`.
+    pub fn build_session<'c, 'b, N, V>(name: N, value: V, max_age: i64) -> Cookie<'c>
+    where
+        N: Into<Cow<'c, str>> + Display,
+        V: Into<Cow<'b, str>> + Display,
+    {
+        let access = &RauthyConfig::get().vars.access;
+        let default_path = if access.cookie_set_path { "/auth" } else { "/" };
+        let attrs = CookieAttrs {
+            mode: &access.cookie_mode,
+            path: access
+                .session_cookie_path
+                .as_deref()
+                .unwrap_or(default_path),
+            domain: access.session_cookie_domain.as_deref(),
+            same_site: access.session_cookie_same_site,
+        };
+        Self::build_with_attrs(name, Self::encrypt_value(value), max_age, attrs)
+    }
+
+    fn build_with_attrs<'c, N>(
+        name: N,
+        value_b64: String,
+        max_age: i64,
+        attrs: CookieAttrs<'_>,
+    ) -> Cookie<'c>
+    where
+        N: Into<Cow<'c, str>> + Display,
+    {
+        let name = Self::prefixed_name(attrs.mode, name);
+        let (secure, path) = match attrs.mode {
+            CookieMode::Host => (true, "/"),
+            CookieMode::Secure => (true, attrs.path),
             CookieMode::DangerInsecure => {
                 warn!("Building INSECURE cookie - you MUST NEVER use this in production");
-                (name.to_string(), false, path)
+                (false, attrs.path)
             }
         };
         let max_age = if max_age < 1 {
@@ -46,30 +94,48 @@ impl ApiCookie {
             cookie::time::Duration::seconds(max_age)
         };
 
+        let mut cookie = Cookie::build(name, value_b64)
+            .secure(secure)
+            .http_only(true)
+            .same_site(attrs.same_site)
+            .max_age(max_age)
+            .path(path.to_string())
+            .finish();
+        // `__Host-` cookies must never have a domain
+        if *attrs.mode != CookieMode::Host
+            && let Some(domain) = attrs.domain
+        {
+            cookie.set_domain(domain.to_string());
+        }
+        cookie
+    }
+
+    #[inline]
+    fn encrypt_value<'b, V>(value: V) -> String
+    where
+        V: Into<Cow<'b, str>> + Display,
+    {
         // we always encrypt any cookie value
         let enc =
             EncValue::encrypt(value.into().as_bytes()).expect("ENC_VALUES not set up correctly");
-        let value_b64 = base64_encode(enc.into_bytes().as_ref());
+        base64_encode(enc.into_bytes().as_ref())
+    }
 
-        Cookie::build(name, value_b64)
-            .secure(secure)
-            .http_only(true)
-            .same_site(same_site)
-            .max_age(max_age)
-            .path(path)
-            .finish()
+    /// Returns the cookie name with the prefix for the given `CookieMode`.
+    #[inline]
+    fn prefixed_name<N: Display>(mode: &CookieMode, name: N) -> String {
+        match mode {
+            CookieMode::Host => format!("__Host-{name}"),
+            CookieMode::Secure => format!("__Secure-{name}"),
+            CookieMode::DangerInsecure => name.to_string(),
+        }
     }
 
     pub fn from_req<'c, N>(req: &HttpRequest, cookie_name: N) -> Option<String>
     where
         N: Into<Cow<'c, str>> + Display,
     {
-        let name = match RauthyConfig::get().vars.access.cookie_mode {
-            CookieMode::Host => format!("__Host-{cookie_name}"),
-            CookieMode::Secure => format!("__Secure-{cookie_name}"),
-            CookieMode::DangerInsecure => cookie_name.to_string(),
-        };
-        // req.cookie(&name)
+        let name = Self::prefixed_name(&RauthyConfig::get().vars.access.cookie_mode, cookie_name);
         Self::cookie_into_value(req.cookie(&name))
     }
 
@@ -77,11 +143,7 @@ impl ApiCookie {
     where
         N: Into<Cow<'c, str>> + Display,
     {
-        let name = match RauthyConfig::get().vars.access.cookie_mode {
-            CookieMode::Host => format!("__Host-{cookie_name}"),
-            CookieMode::Secure => format!("__Secure-{cookie_name}"),
-            CookieMode::DangerInsecure => cookie_name.to_string(),
-        };
+        let name = Self::prefixed_name(&RauthyConfig::get().vars.access.cookie_mode, cookie_name);
         Self::cookie_into_value(req.cookie(&name))
     }
 
@@ -98,3 +160,53 @@ impl ApiCookie {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(attrs: CookieAttrs<'_>) -> String {
+        ApiCookie::build_with_attrs("RauthySession", "value".to_string(), 60, attrs).to_string()
+    }
+
+    #[test]
+    fn test_session_cookie_attrs() {
+        // `__Host-` always ignores `Domain` and forces `Path=/`
+        let cookie = build(CookieAttrs {
+            mode: &CookieMode::Host,
+            path: "/auth",
+            domain: Some("example.com"),
+            same_site: SameSite::Strict,
+        });
+        assert!(cookie.starts_with("__Host-RauthySession=value"));
+        assert!(cookie.contains("Path=/;"));
+        assert!(!cookie.contains("Domain="));
+        assert!(cookie.contains("SameSite=Strict"));
+        assert!(cookie.contains("Secure"));
+        assert!(cookie.contains("HttpOnly"));
+        assert!(cookie.contains("Max-Age=60"));
+
+        let cookie = build(CookieAttrs {
+            mode: &CookieMode::Secure,
+            path: "/auth",
+            domain: Some("example.com"),
+            same_site: SameSite::None,
+        });
+        assert!(cookie.starts_with("__Secure-RauthySession=value"));
+        assert!(cookie.contains("Path=/auth"));
+        assert!(cookie.contains("Domain=example.com"));
+        assert!(cookie.contains("SameSite=None"));
+        assert!(cookie.contains("Secure"));
+
+        let cookie = build(CookieAttrs {
+            mode: &CookieMode::DangerInsecure,
+            path: "/",
+            domain: None,
+            same_site: SameSite::Lax,
+        });
+        assert!(cookie.starts_with("RauthySession=value"));
+        assert!(!cookie.contains("Domain="));
+        assert!(cookie.contains("SameSite=Lax"));
+        assert!(!cookie.contains("Secure"));
+    }
+}
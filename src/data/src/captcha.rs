@@ -0,0 +1,240 @@
+use crate::entity::failed_login_counter::FailedLoginCounter;
+use crate::html::templates::TplCaptcha;
+use crate::rauthy_config::{RauthyConfig, VarsCaptcha};
+use rauthy_common::http_client;
+use rauthy_error::{ErrorResponse, ErrorResponseType};
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use tracing::{debug, error, warn};
+
+/// The supported CAPTCHA providers. All of them share the same `siteverify` API, which makes it
+/// possible to verify tokens for each of them in the exact same way, only the URL differs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CaptchaProvider {
+    Turnstile,
+    HCaptcha,
+    ReCaptcha,
+}
+
+impl From<&str> for CaptchaProvider {
+    fn from(s: &str) -> Self {
+        match s {
+            "turnstile" => Self::Turnstile,
+            "hcaptcha" => Self::HCaptcha,
+            "recaptcha" => Self::ReCaptcha,
+            v => {
+                panic!("Cannot parse {v} as CaptchaProvider");
+            }
+        }
+    }
+}
+
+impl CaptchaProvider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Turnstile => "turnstile",
+            Self::HCaptcha => "hcaptcha",
+            Self::ReCaptcha => "recaptcha",
+        }
+    }
+
+    pub fn verify_url(&self) -> &'static str {
+        match self {
+            Self::Turnstile => "https://challenges.cloudflare.com/turnstile/v0/siteverify",
+            Self::HCaptcha => "https://api.hcaptcha.com/siteverify",
+            Self::ReCaptcha => "https://www.google.com/recaptcha/api/siteverify",
+        }
+    }
+}
+
+impl TplCaptcha {
+    /// Builds the widget config for the UI from the current config, if a provider is set.
+    pub fn from_config() -> Option<Self> {
+        let config = RauthyConfig::get();
+        let rep = &config.vars.ip_reputation;
+        let step_up = (rep.file.is_some() || rep.url.is_some()) && rep.threshold_step_up > 0;
+        Self::new(&config.vars.captcha, step_up)
+    }
+
+    fn new(cfg: &VarsCaptcha, step_up: bool) -> Option<Self> {
+        let provider = cfg.provider.as_ref()?;
+        Some(Self {
+            provider: provider.as_str(),
+            site_key: cfg.site_key.clone(),
+            login: cfg.login || step_up,
+            registration: cfg.registration,
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct SiteVerifyRequest<'a> {
+    secret: &'a str,
+    response: &'a str,
+    remoteip: String,
+}
+
+#[derive(Deserialize)]
+struct SiteVerifyResponse {
+    success: bool,
+    #[serde(default, rename = "error-codes")]
+    error_codes: Vec<String>,
+}
+
+pub struct Captcha;
+
+impl Captcha {
+    /// Verifies the given CAPTCHA token for a login, if CAPTCHAs are enabled for logins and the
+    /// IP has reached the configured `login_threshold` of failed logins.
+    ///
+    /// With `step_up`, which is set for IPs with a bad reputation, the CAPTCHA will always be
+    /// verified. Without a configured provider, the login will be rejected in that case.
+    ///
+    /// This must be called before any credentials are checked.
+    pub async fn verify_login(
+        token: Option<&str>,
+        ip: IpAddr,
+        step_up: bool,
+    ) -> Result<(), ErrorResponse> {
+        let cfg = &RauthyConfig::get().vars.captcha;
+        if step_up {
+            if cfg.provider.is_none() {
+                // should never happen, the config validation requires a provider for step-ups
+                warn!(%ip, "CAPTCHA step-up required without a configured provider");
+                return Err(ErrorResponse::new(
+                    ErrorResponseType::Forbidden,
+                    "Login not allowed from this IP",
+                ));
+            }
+            return Self::verify(token, ip).await;
+        }
+        if cfg.provider.is_none() {
+            return Ok(());
+        }
+        if !cfg.login {
+            return Ok(());
+        }
+
+        if cfg.login_threshold > 0 {
+            let failed_logins = FailedLoginCounter::get(ip.to_string()).await?.unwrap_or(0);
+            if failed_logins < cfg.login_threshold as i64 {
+                return Ok(());
+            }
+        }
+
+        Self::verify(token, ip).await
+    }
+
+    /// Verifies the given CAPTCHA token for an open user registration, if CAPTCHAs are enabled
+    /// for registrations.
+    pub async fn verify_registration(token: Option<&str>, ip: IpAddr) -> Result<(), ErrorResponse> {
+        let cfg = &RauthyConfig::get().vars.captcha;
+        if cfg.provider.is_none() || !cfg.registration {
+            return Ok(());
+        }
+
+        Self::verify(token, ip).await
+    }
+
+    async fn verify(token: Option<&str>, ip: IpAddr) -> Result<(), ErrorResponse> {
+        let cfg = &RauthyConfig::get().vars.captcha;
+        let Some(provider) = &cfg.provider else {
+            return Ok(());
+        };
+
+        let Some(token) = token else {
+            return Err(ErrorResponse::new(
+                ErrorResponseType::BadRequest,
+                "CAPTCHA required",
+            ));
+        };
+
+        let payload = SiteVerifyRequest {
+            secret: &cfg.secret,
+            response: token,
+            remoteip: ip.to_string(),
+        };
+        let res = match http_client()
+            .post(provider.verify_url())
+            .form(&payload)
+            .send()
+            .await
+        {
+            Ok(res) if res.status().is_success() => res.json::<SiteVerifyResponse>().await,
+            Ok(res) => {
+                return Self::provider_unavailable(format!(
+                    "CAPTCHA provider returned status {}",
+                    res.status()
+                ));
+            }
+            Err(err) => Err(err),
+        };
+
+        match res {
+            Ok(SiteVerifyResponse { success: true, .. }) => {
+                debug!(%ip, "CAPTCHA verified successfully");
+                Ok(())
+            }
+            Ok(SiteVerifyResponse { error_codes, .. }) => {
+                warn!(%ip, ?error_codes, "CAPTCHA verification failed");
+                Err(ErrorResponse::new(
+                    ErrorResponseType::BadRequest,
+                    "CAPTCHA verification failed",
+                ))
+            }
+            Err(err) => Self::provider_unavailable(format!(
+                "Error verifying CAPTCHA with {}: {err}",
+                provider.as_str()
+            )),
+        }
+    }
+
+    #[inline]
+    fn provider_unavailable(msg: String) -> Result<(), ErrorResponse> {
+        error!("{msg}");
+
+        if RauthyConfig::get().vars.captcha.fail_open {
+            warn!("CAPTCHA provider unreachable and `fail_open` is set - skipping verification");
+            Ok(())
+        } else {
+            Err(ErrorResponse::new(
+                ErrorResponseType::Connection,
+                "CAPTCHA provider unreachable",
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tpl_captcha() {
+        let mut cfg = VarsCaptcha {
+            provider: None,
+            site_key: "site_key".to_string(),
+            secret: "secret".to_string(),
+            login: false,
+            login_threshold: 3,
+            registration: true,
+            fail_open: false,
+        };
+        assert_eq!(TplCaptcha::new(&cfg, true), None);
+
+        cfg.provider = Some(CaptchaProvider::Turnstile);
+        let tpl = TplCaptcha::new(&cfg, false).unwrap();
+        assert_eq!(tpl.provider, "turnstile");
+        assert_eq!(tpl.site_key, "site_key");
+        assert!(!tpl.login);
+        assert!(tpl.registration);
+
+        // the IP reputation step-up may require a CAPTCHA even with disabled `login`
+        let tpl = TplCaptcha::new(&cfg, true).unwrap();
+        assert!(tpl.login);
+
+        // the secret must never be rendered into the HTML
+        let json = serde_json::to_string(&tpl).unwrap();
+        assert!(!json.contains("secret"));
+    }
+}
@@ -0,0 +1,180 @@
+use crate::rauthy_config::RauthyConfig;
+use chrono::Utc;
+use rauthy_common::constants::RESERVED_CLAIMS;
+use rauthy_common::http_client;
+use rauthy_error::{ErrorResponse, ErrorResponseType};
+use serde::Serialize;
+use std::time::Duration;
+use tracing::{debug, error, warn};
+
+/// Signs the outbound request. The value is `sha256=` followed by the hex encoded
+/// `HMAC-SHA256(secret, "{timestamp}.{body}")`.
+pub static HEADER_SIGNATURE: &str = "x-rauthy-signature";
+/// Unix timestamp in seconds, that is part of the signature to prevent replays.
+pub static HEADER_TIMESTAMP: &str = "x-rauthy-timestamp";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClaimsTokenType {
+    Access,
+    Id,
+}
+
+#[derive(Serialize)]
+struct ClaimsProviderRequest<'a> {
+    user_id: &'a str,
+    client_id: &'a str,
+    scopes: Vec<&'a str>,
+    token_type: ClaimsTokenType,
+}
+
+/// Fetches additional, dynamic claims from an external HTTP endpoint at token build time.
+pub struct ClaimsProvider;
+
+impl ClaimsProvider {
+    /// Returns the claims for the given user and token, that the provider is allowed to set.
+    /// Returns `None` if the provider is disabled, or if it could not be reached and
+    /// `fail_open` is set.
+    pub async fn fetch(
+        user_id: &str,
+        client_id: &str,
+        scope: &str,
+        token_type: ClaimsTokenType,
+    ) -> Result<Option<serde_json::Map<String, serde_json::Value>>, ErrorResponse> {
+        let cfg = &RauthyConfig::get().vars.claims_provider;
+        let Some(url) = &cfg.url else {
+            return Ok(None);
+        };
+
+        let payload = ClaimsProviderRequest {
+            user_id,
+            client_id,
+            scopes: scope.split(' ').filter(|s| !s.is_empty()).collect(),
+            token_type,
+        };
+        let body = serde_json::to_vec(&payload)?;
+        let ts = Utc::now().timestamp().to_string();
+
+        let res = http_client()
+            .post(url)
+            .timeout(Duration::from_millis(cfg.timeout_ms as u64))
+            .header("content-type", "application/json")
+            .header(HEADER_TIMESTAMP, &ts)
+            .header(HEADER_SIGNATURE, Self::signature(&cfg.secret, &ts, &body))
+            .body(body)
+            .send()
+            .await;
+
+        let value = match res {
+            Ok(res) if res.status().is_success() => res.json::<serde_json::Value>().await,
+            Ok(res) => {
+                return Self::provider_unavailable(format!(
+                    "Claims provider returned status {}",
+                    res.status()
+                ));
+            }
+            Err(err) => Err(err),
+        };
+        let obj = match value {
+            Ok(serde_json::Value::Object(obj)) => obj,
+            Ok(_) => {
+                return Self::provider_unavailable(
+                    "Claims provider did not return a JSON object".to_string(),
+                );
+            }
+            Err(err) => {
+                return Self::provider_unavailable(format!(
+                    "Error fetching claims from provider: {err}"
+                ));
+            }
+        };
+
+        Ok(Some(Self::filter_allowed(obj, &cfg.allowed_claims)))
+    }
+
+    /// Removes all claims which are not part of the `allowed_claims` and never lets any
+    /// reserved claim through.
+    fn filter_allowed(
+        obj: serde_json::Map<String, serde_json::Value>,
+        allowed_claims: &[String],
+    ) -> serde_json::Map<String, serde_json::Value> {
+        obj.into_iter()
+            .filter(|(name, _)| {
+                if RESERVED_CLAIMS.contains(&name.as_str())
+                    || !allowed_claims.iter().any(|c| c == name)
+                {
+                    debug!("Ignoring not allowed claim '{name}' from claims provider");
+                    false
+                } else {
+                    true
+                }
+            })
+            .collect()
+    }
+
+    #[inline]
+    fn signature(secret: &str, ts: &str, body: &[u8]) -> String {
+        let mut input = Vec::with_capacity(ts.len() + 1 + body.len());
+        input.extend_from_slice(ts.as_bytes());
+        input.push(b'.');
+        input.extend_from_slice(body);
+        let mac = hmac_sha256::HMAC::mac(input, secret.as_bytes());
+        format!("sha256={}", hex::encode(mac))
+    }
+
+    #[inline]
+    fn provider_unavailable(
+        msg: String,
+    ) -> Result<Option<serde_json::Map<String, serde_json::Value>>, ErrorResponse> {
+        error!("{msg}");
+
+        if RauthyConfig::get().vars.claims_provider.fail_open {
+            warn!("Claims provider unavailable and `fail_open` is set - skipping custom claims");
+            Ok(None)
+        } else {
+            Err(ErrorResponse::new(
+                ErrorResponseType::Connection,
+                "Claims provider unavailable",
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_allowed() {
+        let obj = serde_json::json!({
+            "sub": "overwritten",
+            "iss": "overwritten",
+            "tenant": "acme",
+            "permissions": ["read"],
+            "unknown": true,
+        });
+        let serde_json::Value::Object(obj) = obj else {
+            unreachable!()
+        };
+
+        let allowed = vec![
+            "tenant".to_string(),
+            "permissions".to_string(),
+            "sub".to_string(),
+        ];
+        let filtered = ClaimsProvider::filter_allowed(obj, &allowed);
+        assert_eq!(filtered.len(), 2);
+        assert_eq!(filtered.get("tenant").unwrap(), "acme");
+        assert!(filtered.get("permissions").is_some());
+        assert!(filtered.get("sub").is_none());
+        assert!(filtered.get("iss").is_none());
+        assert!(filtered.get("unknown").is_none());
+    }
+
+    #[test]
+    fn test_signature() {
+        let sig = ClaimsProvider::signature("secret", "1700000000", b"{}");
+        let expected = hmac_sha256::HMAC::mac(b"1700000000.{}", b"secret");
+        assert_eq!(sig, format!("sha256={}", hex::encode(expected)));
+    }
+}
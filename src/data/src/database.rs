@@ -5,6 +5,7 @@ use crate::rauthy_config::RauthyConfig;
 use futures_util::StreamExt;
 use hiqlite::cache_idx::CacheIndex;
 use hiqlite_macros::embed::*;
+use rauthy_common::constants::{CACHE_TTL_APP, CACHE_TTL_SESSION, CACHE_TTL_USER};
 use rauthy_common::{is_hiqlite, is_postgres};
 use rauthy_error::ErrorResponse;
 use std::env;
@@ -34,7 +35,8 @@ struct MigrationsHiqlite;
 /// CAUTION: DO NOT change the order when adding new entries to now have false-positive
 /// during updates for already existing environments. Caches are not indexed via String / Name,
 /// but via u32 internally.
-#[derive(Debug, strum::EnumIter)]
+#[derive(Debug, Clone, Copy, PartialEq, strum::EnumIter, strum::AsRefStr, strum::EnumString)]
+#[strum(serialize_all = "snake_case")]
 pub enum Cache {
     Atproto,
     App,
@@ -64,6 +66,43 @@ impl CacheIndex for Cache {
     }
 }
 
+impl Cache {
+    /// Returns `true` for pure caches, where a miss just triggers a re-fetch from the database
+    /// or an upstream. All others are the single source of truth for short-lived state like
+    /// auth codes, rate limits or WebAuthn ceremonies and must not be flushed at runtime.
+    pub fn is_flushable(&self) -> bool {
+        matches!(
+            self,
+            Self::App
+                | Self::ClientDynamic
+                | Self::ClientEphemeral
+                | Self::JwksRemote
+                | Self::ThemeTs
+                | Self::Html
+                | Self::Session
+                | Self::ToS
+                | Self::User
+        )
+    }
+
+    /// The lifetime in seconds for new entries in this cache, if it is fixed.
+    pub fn ttl(&self) -> Option<i64> {
+        match self {
+            Self::App => CACHE_TTL_APP,
+            Self::ClientDynamic => {
+                Some(RauthyConfig::get().vars.dynamic_clients.rate_limit_sec as i64)
+            }
+            Self::ClientEphemeral => {
+                Some(RauthyConfig::get().vars.ephemeral_clients.cache_lifetime as i64)
+            }
+            Self::JwksRemote => Some(3600),
+            Self::Session => CACHE_TTL_SESSION,
+            Self::User => CACHE_TTL_USER,
+            _ => None,
+        }
+    }
+}
+
 pub struct DB;
 
 impl DB {
@@ -222,6 +261,7 @@ impl DB {
             init_prod::migrate_init_prod().await?;
         }
 
+        let mut migrated = false;
         if let Ok(from) = env::var("MIGRATE_DB_FROM") {
             if config.is_ha_cluster {
                 // TODO does this error make sense or might we be able to do it anyway?
@@ -261,6 +301,7 @@ impl DB {
                         "You provided an unknown database type, please check the MIGRATE_DB_FROM"
                     );
                 };
+                migrated = true;
             }
         } else if config.vars.dev.dev_mode && config.is_primary_node {
             migrate_dev_data().await.expect("Migrating DEV DATA");
@@ -273,6 +314,12 @@ impl DB {
         // update the DbVersion after successful pool creation and migrations
         DbVersion::upsert(db_version).await?;
 
+        if migrated && config.vars.database.migrate_exit {
+            info!("Migration from 'MIGRATE_DB_FROM' finished and 'MIGRATE_EXIT' is set - exiting");
+            Self::hql().shutdown().await?;
+            std::process::exit(0);
+        }
+
         Ok(())
     }
 }
@@ -403,7 +450,7 @@ impl DB {
 /// Be very careful when you use this verifier. It will make any TLS connection work but does NOT
 /// VALIDATE any certificates. Use is discouraged and it should only be done for testing.
 #[derive(Debug)]
-struct NoTlsVerifier {}
+pub(crate) struct NoTlsVerifier {}
 
 impl rustls::client::danger::ServerCertVerifier for NoTlsVerifier {
     fn verify_server_cert(
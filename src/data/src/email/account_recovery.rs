@@ -0,0 +1,113 @@
+use crate::email::i18n::account_recovery::I18nEmailAccountRecovery;
+use crate::email::mailer::EMail;
+use crate::entity::theme::ThemeCssFull;
+use crate::entity::users::User;
+use crate::rauthy_config::RauthyConfig;
+use askama::Template;
+use std::time::Duration;
+use tracing::error;
+
+#[derive(Default, Template)]
+#[template(path = "email/account_recovery.html")]
+pub struct EMailAccountRecoveryHtml<'a> {
+    pub lang: &'a str,
+    pub theme_vars: String,
+    pub email_sub_prefix: &'a str,
+    pub provider: &'a str,
+    pub link_account: &'a str,
+    pub support_email: &'a str,
+    // i18n_email
+    pub header: &'a str,
+    pub text: &'a str,
+    pub if_invalid: &'a str,
+    pub provider_label: &'a str,
+    pub account_link: &'a str,
+    pub contact: &'a str,
+}
+
+#[derive(Default, Template)]
+#[template(path = "email/account_recovery.txt")]
+pub struct EmailAccountRecoveryTxt<'a> {
+    pub email_sub_prefix: &'a str,
+    pub provider: &'a str,
+    pub link_account: &'a str,
+    pub support_email: &'a str,
+    // i18n_email
+    pub header: &'a str,
+    pub text: &'a str,
+    pub if_invalid: &'a str,
+    pub provider_label: &'a str,
+    pub account_link: &'a str,
+    pub contact: &'a str,
+}
+
+/// Notifies the user about an account recovery via the upstream auth provider with the given
+/// name.
+pub async fn send_account_recovered(user: &User, provider: &str) {
+    let config = RauthyConfig::get();
+    let link_account = format!("{}/auth/v1/account", config.pub_url_with_scheme);
+    let support_email = config
+        .vars
+        .email
+        .rauthy_admin_email
+        .as_deref()
+        .unwrap_or_default();
+
+    let i18n = I18nEmailAccountRecovery::build(&user.language);
+    let email_sub_prefix = &config.vars.email.sub_prefix;
+    let text = EmailAccountRecoveryTxt {
+        email_sub_prefix,
+        provider,
+        link_account: &link_account,
+        support_email,
+        header: i18n.header,
+        text: i18n.text,
+        if_invalid: i18n.if_invalid,
+        provider_label: i18n.provider,
+        account_link: i18n.account_link,
+        contact: i18n.contact,
+    };
+
+    let theme_vars = ThemeCssFull::find_theme_variables_email()
+        .await
+        .unwrap_or_default();
+    let html = EMailAccountRecoveryHtml {
+        lang: user.language.as_str(),
+        theme_vars,
+        email_sub_prefix,
+        provider,
+        link_account: &link_account,
+        support_email,
+        header: i18n.header,
+        text: i18n.text,
+        if_invalid: i18n.if_invalid,
+        provider_label: i18n.provider,
+        account_link: i18n.account_link,
+        contact: i18n.contact,
+    };
+
+    let req = EMail {
+        recipient_name: user.email_recipient_name(),
+        address: user.email.to_string(),
+        subject: format!("{email_sub_prefix} - {}", i18n.subject),
+        text: Some(
+            text.render()
+                .expect("Template rendering: EmailAccountRecoveryTxt"),
+        ),
+        html: Some(
+            html.render()
+                .expect("Template rendering: EMailAccountRecoveryHtml"),
+        ),
+    };
+
+    let res = config
+        .tx_email
+        .send_timeout(req, Duration::from_secs(10))
+        .await;
+    if let Err(err) = res {
+        error!(
+            user.email, error = ?err,
+            "sending account recovery notification email",
+        );
+    }
+}
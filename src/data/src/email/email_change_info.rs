@@ -1,5 +1,7 @@
 use crate::email::email_ts_prettify;
 use crate::email::i18n::change_info_new::I18nEmailChangeInfoNew;
+use crate::email::i18n::change_info_old::I18nEmailChangeInfoOld;
+use crate::email::i18n::email_add::I18nEmailAdd;
 use crate::email::mailer::EMail;
 use crate::entity::magic_links::MagicLink;
 use crate::entity::theme::ThemeCssFull;
@@ -38,6 +40,39 @@ pub struct EMailChangeInfoNewTxt<'a> {
     pub expires: &'a str,
 }
 
+#[derive(Default, Template)]
+#[template(path = "email/change_info_old.html")]
+pub struct EMailChangeInfoOldHtml<'a> {
+    pub lang: &'a str,
+    pub theme_vars: String,
+    pub email_sub_prefix: &'a str,
+    pub link: &'a str,
+    pub exp: &'a str,
+    pub new_email: &'a str,
+    // i18n_email
+    pub header: &'a str,
+    pub change_info: &'a str,
+    pub click_link: &'a str,
+    pub validity: &'a str,
+    pub expires: &'a str,
+    pub button_text: &'a str,
+}
+
+#[derive(Default, Template)]
+#[template(path = "email/change_info_old.txt")]
+pub struct EMailChangeInfoOldTxt<'a> {
+    pub email_sub_prefix: &'a str,
+    pub link: &'a str,
+    pub exp: &'a str,
+    pub new_email: &'a str,
+    // i18n_email
+    pub header: &'a str,
+    pub change_info: &'a str,
+    pub click_link: &'a str,
+    pub validity: &'a str,
+    pub expires: &'a str,
+}
+
 pub async fn send_email_change_info_new(
     magic_link: &MagicLink,
     user: &User,
@@ -108,3 +143,152 @@ pub async fn send_email_change_info_new(
         }
     }
 }
+
+/// Sends the confirmation link for an additional address to this new address.
+pub async fn send_email_add_confirm(
+    magic_link: &MagicLink,
+    user: &User,
+    user_tz: Option<&str>,
+    email: &str,
+) {
+    let link = format!(
+        "{}/users/{}/emails/confirm/{}",
+        RauthyConfig::get().issuer,
+        magic_link.user_id,
+        &magic_link.id,
+    );
+    let exp = email_ts_prettify(magic_link.exp, &user.language, user_tz);
+    let theme_vars = ThemeCssFull::find_theme_variables_email()
+        .await
+        .unwrap_or_default();
+
+    let i18n = I18nEmailAdd::build(&user.language);
+    let email_sub_prefix = &RauthyConfig::get().vars.email.sub_prefix;
+    let text = EMailChangeInfoNewTxt {
+        email_sub_prefix,
+        link: &link,
+        exp: &exp,
+        header: i18n.header,
+        click_link: i18n.click_link,
+        validity: i18n.validity,
+        expires: i18n.expires,
+    };
+
+    let html = EMailChangeInfoNewHtml {
+        lang: user.language.as_str(),
+        theme_vars,
+        email_sub_prefix,
+        link: &link,
+        exp: &exp,
+        header: i18n.header,
+        click_link: i18n.click_link,
+        validity: i18n.validity,
+        expires: i18n.expires,
+        button_text: i18n.button_text,
+    };
+
+    let req = EMail {
+        recipient_name: user.email_recipient_name(),
+        address: email.to_string(),
+        subject: format!("{} - {}", email_sub_prefix, i18n.subject),
+        text: Some(
+            text.render()
+                .expect("Template rendering: EMailChangeInfoNewTxt"),
+        ),
+        html: Some(
+            html.render()
+                .expect("Template rendering: EMailChangeInfoNewHtml"),
+        ),
+    };
+
+    let res = RauthyConfig::get()
+        .tx_email
+        .send_timeout(req, Duration::from_secs(10))
+        .await;
+    match res {
+        Ok(_) => {}
+        Err(ref e) => {
+            error!(
+                email, error = ?e,
+                "sending magic link email request",
+            );
+        }
+    }
+}
+
+/// Informs the current address about a requested E-Mail change. The given `magic_link` must be
+/// the one with `MagicLinkUsage::EmailChangeAbort`, which lets the user block the change.
+pub async fn send_email_change_info_old(
+    magic_link: &MagicLink,
+    user: &User,
+    user_tz: Option<&str>,
+    new_email: &str,
+) {
+    let link = format!(
+        "{}/users/{}/email_change_abort/{}",
+        RauthyConfig::get().issuer,
+        magic_link.user_id,
+        &magic_link.id,
+    );
+    let exp = email_ts_prettify(magic_link.exp, &user.language, user_tz);
+    let theme_vars = ThemeCssFull::find_theme_variables_email()
+        .await
+        .unwrap_or_default();
+
+    let i18n = I18nEmailChangeInfoOld::build(&user.language);
+    let email_sub_prefix = &RauthyConfig::get().vars.email.sub_prefix;
+    let text = EMailChangeInfoOldTxt {
+        email_sub_prefix,
+        link: &link,
+        exp: &exp,
+        new_email,
+        header: i18n.header,
+        change_info: i18n.change_info,
+        click_link: i18n.click_link,
+        validity: i18n.validity,
+        expires: i18n.expires,
+    };
+
+    let html = EMailChangeInfoOldHtml {
+        lang: user.language.as_str(),
+        theme_vars,
+        email_sub_prefix,
+        link: &link,
+        exp: &exp,
+        new_email,
+        header: i18n.header,
+        change_info: i18n.change_info,
+        click_link: i18n.click_link,
+        validity: i18n.validity,
+        expires: i18n.expires,
+        button_text: i18n.button_text,
+    };
+
+    let req = EMail {
+        recipient_name: user.email_recipient_name(),
+        address: user.email.clone(),
+        subject: format!("{} - {}", email_sub_prefix, i18n.subject),
+        text: Some(
+            text.render()
+                .expect("Template rendering: EMailChangeInfoOldTxt"),
+        ),
+        html: Some(
+            html.render()
+                .expect("Template rendering: EMailChangeInfoOldHtml"),
+        ),
+    };
+
+    let res = RauthyConfig::get()
+        .tx_email
+        .send_timeout(req, Duration::from_secs(10))
+        .await;
+    match res {
+        Ok(_) => {}
+        Err(ref e) => {
+            error!(
+                email = user.email, error = ?e,
+                "sending email change info to old address",
+            );
+        }
+    }
+}
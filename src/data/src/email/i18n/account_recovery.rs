@@ -0,0 +1,107 @@
+use crate::language::Language;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct I18nEmailAccountRecovery<'a> {
+    pub subject: &'a str,
+    pub header: &'a str,
+    pub text: &'a str,
+    pub if_invalid: &'a str,
+    pub provider: &'a str,
+    pub account_link: &'a str,
+    pub contact: &'a str,
+}
+
+impl I18nEmailAccountRecovery<'_> {
+    pub fn build(lang: &Language) -> Self {
+        match lang {
+            Language::De => Self::build_de(),
+            Language::En => Self::build_en(),
+            Language::Ko => Self::build_ko(),
+            Language::Nb => Self::build_nb(),
+            Language::Uk => Self::build_uk(),
+            Language::ZhHans => Self::build_zh_hans(),
+        }
+    }
+}
+
+impl I18nEmailAccountRecovery<'_> {
+    fn build_de() -> Self {
+        Self {
+            subject: "Account wiederhergestellt",
+            header: "Dein Account wurde wiederhergestellt",
+            text: "Dein Account wurde über einen verknüpften Login Anbieter wiederhergestellt. \
+                Alle Passkeys und aktiven Sessions wurden entfernt.",
+            if_invalid: "Falls du das nicht selbst warst, kontaktiere bitte umgehend einen \
+                Administrator.",
+            provider: "Anbieter",
+            account_link: "Zum Account",
+            contact: "Kontakt",
+        }
+    }
+
+    fn build_en() -> Self {
+        Self {
+            subject: "Account recovered",
+            header: "Your account has been recovered",
+            text: "Your account has been recovered via a linked login provider. All passkeys \
+                and active sessions have been removed.",
+            if_invalid: "If this was not you, please contact an administrator immediately.",
+            provider: "Provider",
+            account_link: "Go to Account",
+            contact: "Contact",
+        }
+    }
+
+    fn build_ko() -> Self {
+        Self {
+            subject: "Account recovered",
+            header: "Your account has been recovered",
+            text: "Your account has been recovered via a linked login provider. All passkeys \
+                and active sessions have been removed.",
+            if_invalid: "If this was not you, please contact an administrator immediately.",
+            provider: "Provider",
+            account_link: "Go to Account",
+            contact: "Contact",
+        }
+    }
+
+    fn build_nb() -> Self {
+        Self {
+            subject: "Konto gjenopprettet",
+            header: "Kontoen din er gjenopprettet",
+            text: "Kontoen din er gjenopprettet via en tilknyttet innloggingsleverandør. Alle \
+                passkeys og aktive økter er fjernet.",
+            if_invalid: "Hvis dette ikke var deg, vennligst kontakt en administrator umiddelbart.",
+            provider: "Leverandør",
+            account_link: "Gå til konto",
+            contact: "Kontakt",
+        }
+    }
+
+    fn build_uk() -> Self {
+        Self {
+            subject: "Акаунт відновлено",
+            header: "Ваш акаунт відновлено",
+            text: "Ваш акаунт було відновлено через пов'язаного провайдера входу. Усі ключі \
+                доступу та активні сесії було видалено.",
+            if_invalid: "Якщо це були не ви, негайно зверніться до адміністратора.",
+            provider: "Провайдер",
+            account_link: "Перейти до акаунта",
+            contact: "Контакт",
+        }
+    }
+
+    fn build_zh_hans() -> Self {
+        Self {
+            subject: "Account recovered",
+            header: "Your account has been recovered",
+            text: "Your account has been recovered via a linked login provider. All passkeys \
+                and active sessions have been removed.",
+            if_invalid: "If this was not you, please contact an administrator immediately.",
+            provider: "Provider",
+            account_link: "Go to Account",
+            contact: "Contact",
+        }
+    }
+}
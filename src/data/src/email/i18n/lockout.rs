@@ -0,0 +1,112 @@
+use crate::language::Language;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct I18nEmailLockout<'a> {
+    pub subject: &'a str,
+    pub header: &'a str,
+    pub time: &'a str,
+    pub unknown_location: &'a str,
+    pub if_invalid: &'a str,
+    pub account_link: &'a str,
+    pub contact: &'a str,
+}
+
+impl I18nEmailLockout<'_> {
+    pub fn build(lang: &Language) -> Self {
+        match lang {
+            Language::De => Self::build_de(),
+            Language::En => Self::build_en(),
+            Language::Ko => Self::build_ko(),
+            Language::Nb => Self::build_nb(),
+            Language::Uk => Self::build_uk(),
+            Language::ZhHans => Self::build_zh_hans(),
+        }
+    }
+}
+
+impl I18nEmailLockout<'_> {
+    fn build_de() -> Self {
+        Self {
+            subject: "Sicherheitswarnung - Login blockiert",
+            header: "Nach zu vielen fehlgeschlagenen Logins für diesen Account wurde der Zugriff \
+                von der folgenden Adresse blockiert",
+            time: "Zeitpunkt",
+            unknown_location: "Unbekannter Ort",
+            if_invalid: "Sollten diese Logins nicht von dir stammen, versucht möglicherweise \
+                jemand dein Passwort zu erraten. Du solltest deine Login-Daten erneuern!",
+            account_link: "Passwort ändern",
+            contact: "Kontakt",
+        }
+    }
+
+    fn build_en() -> Self {
+        Self {
+            subject: "Security Warning - Login blocked",
+            header: "After too many failed logins for this account, access from the following \
+                address has been blocked",
+            time: "Time",
+            unknown_location: "Unknown location",
+            if_invalid: "If these logins were not made by you, someone may be trying to guess \
+                your password. You should update your credentials!",
+            account_link: "Change Password",
+            contact: "Contact",
+        }
+    }
+
+    fn build_ko() -> Self {
+        Self {
+            subject: "Security Warning - Login blocked",
+            header: "After too many failed logins for this account, access from the following \
+                address has been blocked",
+            time: "Time",
+            unknown_location: "Unknown location",
+            if_invalid: "If these logins were not made by you, someone may be trying to guess \
+                your password. You should update your credentials!",
+            account_link: "Change Password",
+            contact: "Contact",
+        }
+    }
+
+    fn build_nb() -> Self {
+        Self {
+            subject: "Sikkerhetsvarsel - Innlogging blokkert",
+            header: "Etter for mange mislykkede innlogginger for denne kontoen har tilgang fra \
+                følgende adresse blitt blokkert",
+            time: "Tidspunkt",
+            unknown_location: "Ukjent sted",
+            if_invalid: "Hvis disse innloggingene ikke ble gjort av deg, kan noen prøve å gjette \
+                passordet ditt. Du bør oppdatere dine påloggingsopplysninger!",
+            account_link: "Endre passord",
+            contact: "Kontakt",
+        }
+    }
+
+    fn build_uk() -> Self {
+        Self {
+            subject: "Попередження безпеки - Вхід заблоковано",
+            header: "Після занадто великої кількості невдалих спроб входу до цього акаунта \
+                доступ з наступної адреси було заблоковано",
+            time: "Час",
+            unknown_location: "Невідоме місце",
+            if_invalid: "Якщо ці спроби входу були не ваші, можливо, хтось намагається вгадати \
+                ваш пароль. Вам слід оновити свої облікові дані!",
+            account_link: "Змінити пароль",
+            contact: "Контакт",
+        }
+    }
+
+    fn build_zh_hans() -> Self {
+        Self {
+            subject: "Security Warning - Login blocked",
+            header: "After too many failed logins for this account, access from the following \
+                address has been blocked",
+            time: "Time",
+            unknown_location: "Unknown location",
+            if_invalid: "If these logins were not made by you, someone may be trying to guess \
+                your password. You should update your credentials!",
+            account_link: "Change Password",
+            contact: "Contact",
+        }
+    }
+}
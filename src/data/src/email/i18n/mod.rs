@@ -1,8 +1,13 @@
+pub mod account_recovery;
 pub mod change_info_new;
 pub mod change_info_old;
 pub mod confirm_change;
 pub mod confirm_change_html;
+pub mod email_add;
+pub mod lockout;
 pub mod login_location;
 pub mod password_new;
+pub mod registration;
 pub mod reset;
 pub mod reset_info;
+pub mod welcome;
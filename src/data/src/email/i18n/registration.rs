@@ -0,0 +1,119 @@
+use crate::language::Language;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct I18nEmailRegistration<'a> {
+    pub subject_approved: &'a str,
+    pub header_approved: &'a str,
+    pub text_approved: &'a str,
+    pub subject_rejected: &'a str,
+    pub header_rejected: &'a str,
+    pub text_rejected: &'a str,
+    pub account_link: &'a str,
+    pub contact: &'a str,
+}
+
+impl I18nEmailRegistration<'_> {
+    pub fn build(lang: &Language) -> Self {
+        match lang {
+            Language::De => Self::build_de(),
+            Language::En => Self::build_en(),
+            Language::Ko => Self::build_ko(),
+            Language::Nb => Self::build_nb(),
+            Language::Uk => Self::build_uk(),
+            Language::ZhHans => Self::build_zh_hans(),
+        }
+    }
+}
+
+impl I18nEmailRegistration<'_> {
+    fn build_de() -> Self {
+        Self {
+            subject_approved: "Registrierung bestätigt",
+            header_approved: "Deine Registrierung wurde bestätigt",
+            text_approved: "Ein Administrator hat deine Registrierung bestätigt. Du kannst dich \
+                ab sofort über deinen bisherigen Login Anbieter anmelden.",
+            subject_rejected: "Registrierung abgelehnt",
+            header_rejected: "Deine Registrierung wurde abgelehnt",
+            text_rejected: "Ein Administrator hat deine Registrierung abgelehnt. Alle Daten zu \
+                dieser Registrierung wurden gelöscht.",
+            account_link: "Zum Account",
+            contact: "Kontakt",
+        }
+    }
+
+    fn build_en() -> Self {
+        Self {
+            subject_approved: "Registration approved",
+            header_approved: "Your registration has been approved",
+            text_approved: "An administrator has approved your registration. You can now log in \
+                with the same provider you have used for the registration.",
+            subject_rejected: "Registration rejected",
+            header_rejected: "Your registration has been rejected",
+            text_rejected: "An administrator has rejected your registration. All data for this \
+                registration has been deleted.",
+            account_link: "Go to Account",
+            contact: "Contact",
+        }
+    }
+
+    fn build_ko() -> Self {
+        Self {
+            subject_approved: "Registration approved",
+            header_approved: "Your registration has been approved",
+            text_approved: "An administrator has approved your registration. You can now log in \
+                with the same provider you have used for the registration.",
+            subject_rejected: "Registration rejected",
+            header_rejected: "Your registration has been rejected",
+            text_rejected: "An administrator has rejected your registration. All data for this \
+                registration has been deleted.",
+            account_link: "Go to Account",
+            contact: "Contact",
+        }
+    }
+
+    fn build_nb() -> Self {
+        Self {
+            subject_approved: "Registrering godkjent",
+            header_approved: "Registreringen din er godkjent",
+            text_approved: "En administrator har godkjent registreringen din. Du kan nå logge \
+                inn med den samme leverandøren som du brukte for registreringen.",
+            subject_rejected: "Registrering avvist",
+            header_rejected: "Registreringen din er avvist",
+            text_rejected: "En administrator har avvist registreringen din. Alle data for denne \
+                registreringen er slettet.",
+            account_link: "Gå til konto",
+            contact: "Kontakt",
+        }
+    }
+
+    fn build_uk() -> Self {
+        Self {
+            subject_approved: "Реєстрацію підтверджено",
+            header_approved: "Вашу реєстрацію підтверджено",
+            text_approved: "Адміністратор підтвердив вашу реєстрацію. Тепер ви можете увійти \
+                через того ж провайдера, якого використовували для реєстрації.",
+            subject_rejected: "Реєстрацію відхилено",
+            header_rejected: "Вашу реєстрацію відхилено",
+            text_rejected: "Адміністратор відхилив вашу реєстрацію. Усі дані цієї реєстрації \
+                було видалено.",
+            account_link: "Перейти до акаунта",
+            contact: "Контакт",
+        }
+    }
+
+    fn build_zh_hans() -> Self {
+        Self {
+            subject_approved: "Registration approved",
+            header_approved: "Your registration has been approved",
+            text_approved: "An administrator has approved your registration. You can now log in \
+                with the same provider you have used for the registration.",
+            subject_rejected: "Registration rejected",
+            header_rejected: "Your registration has been rejected",
+            text_rejected: "An administrator has rejected your registration. All data for this \
+                registration has been deleted.",
+            account_link: "Go to Account",
+            contact: "Contact",
+        }
+    }
+}
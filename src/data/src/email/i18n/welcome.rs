@@ -0,0 +1,65 @@
+use crate::language::Language;
+use crate::rauthy_config::{RauthyConfig, VarsTemplate};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct I18nEmailWelcome<'a> {
+    pub subject: &'a str,
+    pub header: &'a str,
+    pub text: &'a str,
+    pub click_link: &'a str,
+    pub validity: &'a str,
+    pub expires: &'a str,
+    pub button_text: &'a str,
+}
+
+impl From<&'static VarsTemplate> for I18nEmailWelcome<'_> {
+    fn from(tpl: &'static VarsTemplate) -> Self {
+        Self {
+            subject: tpl.subject.as_ref(),
+            header: tpl.header.as_ref(),
+            text: tpl.text.as_deref().unwrap_or_default(),
+            click_link: tpl.click_link.as_ref(),
+            validity: tpl.validity.as_ref(),
+            expires: tpl.expires.as_ref(),
+            button_text: tpl.button.as_ref(),
+        }
+    }
+}
+
+impl I18nEmailWelcome<'_> {
+    pub fn build(lang: &Language) -> Self {
+        let tpls = &RauthyConfig::get().vars.templates;
+        match lang {
+            Language::De => Self::from(&tpls.welcome_de),
+            Language::En => Self::from(&tpls.welcome_en),
+            Language::Ko => Self::from(&tpls.welcome_ko),
+            Language::Nb => Self::from(&tpls.welcome_nb),
+            Language::Uk => Self::from(&tpls.welcome_uk),
+            Language::ZhHans => Self::from(&tpls.welcome_zhhans),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rauthy_config::Vars;
+
+    #[test]
+    fn test_welcome_from_template() {
+        let vars = Box::leak(Box::new(Vars::default()));
+        let i18n = I18nEmailWelcome::from(&vars.templates.welcome_de);
+        assert_eq!(i18n.subject, "Willkommen");
+        assert_eq!(i18n.text, "Dein neuer Account wurde angelegt.");
+        assert_eq!(i18n.button_text, "Account einrichten");
+
+        let vars = Box::leak(Box::new(Vars::default()));
+        vars.templates.welcome_en.text = None;
+        vars.templates.welcome_en.button = "Get started".into();
+        let i18n = I18nEmailWelcome::from(&vars.templates.welcome_en);
+        assert_eq!(i18n.subject, "Welcome");
+        assert_eq!(i18n.text, "");
+        assert_eq!(i18n.button_text, "Get started");
+    }
+}
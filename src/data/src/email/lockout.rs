@@ -0,0 +1,125 @@
+use crate::email::email_ts_prettify;
+use crate::email::i18n::lockout::I18nEmailLockout;
+use crate::email::mailer::EMail;
+use crate::entity::theme::ThemeCssFull;
+use crate::entity::users::User;
+use crate::rauthy_config::RauthyConfig;
+use askama::Template;
+use std::time::Duration;
+use tracing::error;
+
+#[derive(Default, Template)]
+#[template(path = "email/lockout.html")]
+pub struct EMailLockoutHtml<'a> {
+    pub lang: &'a str,
+    pub theme_vars: String,
+    pub email_sub_prefix: &'a str,
+    pub ip: &'a str,
+    pub location: &'a str,
+    pub ts: &'a str,
+    pub link_account: &'a str,
+    pub support_email: &'a str,
+    // i18n_email
+    pub header: &'a str,
+    pub time: &'a str,
+    pub unknown_location: &'a str,
+    pub if_invalid: &'a str,
+    pub account_link: &'a str,
+    pub contact: &'a str,
+}
+
+#[derive(Default, Template)]
+#[template(path = "email/lockout.txt")]
+pub struct EmailLockoutTxt<'a> {
+    pub email_sub_prefix: &'a str,
+    pub ip: &'a str,
+    pub location: &'a str,
+    pub ts: &'a str,
+    pub link_account: &'a str,
+    pub support_email: &'a str,
+    // i18n_email
+    pub header: &'a str,
+    pub time: &'a str,
+    pub unknown_location: &'a str,
+    pub if_invalid: &'a str,
+    pub account_link: &'a str,
+    pub contact: &'a str,
+}
+
+pub async fn send_lockout(
+    user: &User,
+    ip: String,
+    location: Option<String>,
+    ts: i64,
+    user_tz: Option<&str>,
+) {
+    let config = RauthyConfig::get();
+    let link_account = format!("{}/auth/v1/account", config.pub_url_with_scheme);
+    let location = location.as_deref().unwrap_or_default();
+    let ts = email_ts_prettify(ts, &user.language, user_tz);
+    let support_email = config
+        .vars
+        .email
+        .rauthy_admin_email
+        .as_deref()
+        .unwrap_or_default();
+
+    let i18n = I18nEmailLockout::build(&user.language);
+    let email_sub_prefix = &config.vars.email.sub_prefix;
+    let text = EmailLockoutTxt {
+        email_sub_prefix,
+        ip: &ip,
+        location,
+        ts: &ts,
+        link_account: &link_account,
+        support_email,
+        header: i18n.header,
+        time: i18n.time,
+        unknown_location: i18n.unknown_location,
+        if_invalid: i18n.if_invalid,
+        account_link: i18n.account_link,
+        contact: i18n.contact,
+    };
+
+    let theme_vars = ThemeCssFull::find_theme_variables_email()
+        .await
+        .unwrap_or_default();
+    let html = EMailLockoutHtml {
+        lang: user.language.as_str(),
+        theme_vars,
+        email_sub_prefix,
+        ip: &ip,
+        location,
+        ts: &ts,
+        link_account: &link_account,
+        support_email,
+        header: i18n.header,
+        time: i18n.time,
+        unknown_location: i18n.unknown_location,
+        if_invalid: i18n.if_invalid,
+        account_link: i18n.account_link,
+        contact: i18n.contact,
+    };
+
+    let req = EMail {
+        recipient_name: user.email_recipient_name(),
+        address: user.email.to_string(),
+        subject: format!("{email_sub_prefix} - {}", i18n.subject),
+        text: Some(text.render().expect("Template rendering: EmailLockoutTxt")),
+        html: Some(html.render().expect("Template rendering: EMailLockoutHtml")),
+    };
+
+    let res = config
+        .tx_email
+        .send_timeout(req, Duration::from_secs(10))
+        .await;
+    match res {
+        Ok(_) => {}
+        Err(ref e) => {
+            error!(
+                user.email, error = ?e,
+                "sending lockout notification email",
+            );
+        }
+    }
+}
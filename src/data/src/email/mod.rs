@@ -4,17 +4,21 @@ use chrono::DateTime;
 use chrono_tz::Tz;
 use std::str::FromStr;
 
+pub mod account_recovery;
 pub mod custom;
 pub mod email_change_confirm;
 pub mod email_change_info;
 pub mod i18n;
+pub mod lockout;
 pub mod login_location;
 pub mod mailer;
 mod mailer_microsoft_graph;
 pub mod notification;
 pub mod password_reset;
 pub mod password_reset_info;
+pub mod registration;
 pub mod smtp_oauth_token;
+pub mod welcome;
 
 /// Prettifies unix timestamps for E-Mails in a better readable format for end users
 fn email_ts_prettify(ts: i64, lang: &Language, user_tz: Option<&str>) -> String {
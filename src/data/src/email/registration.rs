@@ -0,0 +1,139 @@
+use crate::email::i18n::registration::I18nEmailRegistration;
+use crate::email::mailer::EMail;
+use crate::entity::theme::ThemeCssFull;
+use crate::entity::users::User;
+use crate::rauthy_config::RauthyConfig;
+use askama::Template;
+use std::time::Duration;
+use tracing::error;
+
+#[derive(Default, Template)]
+#[template(path = "email/registration.html")]
+pub struct EMailRegistrationHtml<'a> {
+    pub lang: &'a str,
+    pub theme_vars: String,
+    pub email_sub_prefix: &'a str,
+    pub link_account: &'a str,
+    pub support_email: &'a str,
+    // i18n_email
+    pub header: &'a str,
+    pub text: &'a str,
+    pub account_link: &'a str,
+    pub contact: &'a str,
+}
+
+#[derive(Default, Template)]
+#[template(path = "email/registration.txt")]
+pub struct EmailRegistrationTxt<'a> {
+    pub email_sub_prefix: &'a str,
+    pub link_account: &'a str,
+    pub support_email: &'a str,
+    // i18n_email
+    pub header: &'a str,
+    pub text: &'a str,
+    pub account_link: &'a str,
+    pub contact: &'a str,
+}
+
+/// Notifies a federated user about the approval of the registration. Local accounts receive
+/// the welcome E-Mail with the link to set a password instead.
+pub async fn send_registration_approved(user: User) {
+    let i18n = I18nEmailRegistration::build(&user.language);
+    let link_account = format!(
+        "{}/auth/v1/account",
+        RauthyConfig::get().pub_url_with_scheme
+    );
+    send_registration_result(
+        &user,
+        i18n.subject_approved,
+        i18n.header_approved,
+        i18n.text_approved,
+        &link_account,
+        &i18n,
+    )
+    .await;
+}
+
+/// Notifies the applicant about the rejection of the registration. This must be called before
+/// the user is deleted.
+pub async fn send_registration_rejected(user: User) {
+    let i18n = I18nEmailRegistration::build(&user.language);
+    send_registration_result(
+        &user,
+        i18n.subject_rejected,
+        i18n.header_rejected,
+        i18n.text_rejected,
+        "",
+        &i18n,
+    )
+    .await;
+}
+
+async fn send_registration_result(
+    user: &User,
+    subject: &str,
+    header: &str,
+    text: &str,
+    link_account: &str,
+    i18n: &I18nEmailRegistration<'_>,
+) {
+    let config = RauthyConfig::get();
+    let support_email = config
+        .vars
+        .email
+        .rauthy_admin_email
+        .as_deref()
+        .unwrap_or_default();
+    let email_sub_prefix = &config.vars.email.sub_prefix;
+
+    let text_tpl = EmailRegistrationTxt {
+        email_sub_prefix,
+        link_account,
+        support_email,
+        header,
+        text,
+        account_link: i18n.account_link,
+        contact: i18n.contact,
+    };
+
+    let theme_vars = ThemeCssFull::find_theme_variables_email()
+        .await
+        .unwrap_or_default();
+    let html = EMailRegistrationHtml {
+        lang: user.language.as_str(),
+        theme_vars,
+        email_sub_prefix,
+        link_account,
+        support_email,
+        header,
+        text,
+        account_link: i18n.account_link,
+        contact: i18n.contact,
+    };
+
+    let req = EMail {
+        recipient_name: user.email_recipient_name(),
+        address: user.email.to_string(),
+        subject: format!("{email_sub_prefix} - {subject}"),
+        text: Some(
+            text_tpl
+                .render()
+                .expect("Template rendering: EmailRegistrationTxt"),
+        ),
+        html: Some(
+            html.render()
+                .expect("Template rendering: EMailRegistrationHtml"),
+        ),
+    };
+
+    let res = config
+        .tx_email
+        .send_timeout(req, Duration::from_secs(10))
+        .await;
+    if let Err(err) = res {
+        error!(
+            user.email, error = ?err,
+            "sending registration result email",
+        );
+    }
+}
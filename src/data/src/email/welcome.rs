@@ -0,0 +1,141 @@
+use crate::email::email_ts_prettify;
+use crate::email::i18n::welcome::I18nEmailWelcome;
+use crate::email::mailer::EMail;
+use crate::entity::magic_links::MagicLink;
+use crate::entity::theme::ThemeCssFull;
+use crate::entity::users::User;
+use crate::rauthy_config::RauthyConfig;
+use askama::Template;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::{debug, error};
+
+/// `(batch start, emails sent in the current batch)`
+static WELCOME_BATCH: LazyLock<Mutex<(Instant, u16)>> =
+    LazyLock::new(|| Mutex::new((Instant::now(), 0)));
+
+#[derive(Default, Template)]
+#[template(path = "email/welcome.html")]
+pub struct EMailWelcomeHtml<'a> {
+    pub lang: &'a str,
+    pub theme_vars: String,
+    pub email_sub_prefix: &'a str,
+    pub link: &'a str,
+    pub exp: &'a str,
+    // i18n_email
+    pub header: &'a str,
+    pub text: &'a str,
+    pub click_link: &'a str,
+    pub validity: &'a str,
+    pub expires: &'a str,
+    pub button_text: &'a str,
+}
+
+#[derive(Default, Template)]
+#[template(path = "email/welcome.txt")]
+pub struct EmailWelcomeTxt<'a> {
+    pub email_sub_prefix: &'a str,
+    pub link: &'a str,
+    pub exp: &'a str,
+    // i18n_email
+    pub header: &'a str,
+    pub text: &'a str,
+    pub click_link: &'a str,
+    pub validity: &'a str,
+    pub expires: &'a str,
+}
+
+/// Reserves a slot in the current or the next free batch and returns its start.
+///
+/// The lock is only held for the calculation. Waiting for the slot happens outside of it, so
+/// a full batch never blocks anything else than the sends that are queued up behind it.
+fn reserve_batch_slot() -> Instant {
+    let (batch_size, delay) = {
+        let vars = &RauthyConfig::get().vars.email.jobs;
+        (
+            vars.batch_size.max(1),
+            Duration::from_millis(vars.batch_delay_ms as u64),
+        )
+    };
+
+    let mut lock = WELCOME_BATCH
+        .lock()
+        .expect("WELCOME_BATCH to never be poisoned");
+    let now = Instant::now();
+    if now.saturating_duration_since(lock.0) >= delay {
+        *lock = (now, 0);
+    } else if lock.1 >= batch_size {
+        // The start may already be in the future, if multiple batches are queued up.
+        *lock = (lock.0 + delay, 0);
+    }
+    lock.1 += 1;
+    lock.0
+}
+
+/// Sends the welcome E-Mail with the onboarding link for a user created by an admin.
+///
+/// These are throttled with the `[email.jobs]` batch settings, because an admin may create a lot
+/// of users in a short amount of time, for instance during a bulk import via the API. Each call
+/// waits until its batch has started, so this should be spawned as a task.
+pub async fn send_welcome(magic_link: MagicLink, user: User, user_tz: Option<String>) {
+    let send_at = reserve_batch_slot();
+    if send_at > Instant::now() {
+        debug!("Welcome E-Mail batch is full - waiting for the next one");
+        tokio::time::sleep_until(send_at).await;
+    }
+
+    let link = format!(
+        "{}/users/{}/reset/{}?type={}",
+        RauthyConfig::get().issuer,
+        magic_link.user_id,
+        &magic_link.id,
+        magic_link.usage,
+    );
+    let exp = email_ts_prettify(magic_link.exp, &user.language, user_tz.as_deref());
+    let theme_vars = ThemeCssFull::find_theme_variables_email()
+        .await
+        .unwrap_or_default();
+
+    let i18n = I18nEmailWelcome::build(&user.language);
+    let email_sub_prefix = &RauthyConfig::get().vars.email.sub_prefix;
+    let text = EmailWelcomeTxt {
+        email_sub_prefix,
+        link: &link,
+        exp: &exp,
+        header: i18n.header,
+        text: i18n.text,
+        click_link: i18n.click_link,
+        validity: i18n.validity,
+        expires: i18n.expires,
+    };
+    let html = EMailWelcomeHtml {
+        lang: user.language.as_str(),
+        theme_vars,
+        email_sub_prefix,
+        link: &link,
+        exp: &exp,
+        header: i18n.header,
+        text: i18n.text,
+        click_link: i18n.click_link,
+        validity: i18n.validity,
+        expires: i18n.expires,
+        button_text: i18n.button_text,
+    };
+
+    let req = EMail {
+        recipient_name: user.email_recipient_name(),
+        address: user.email.to_string(),
+        subject: format!("{email_sub_prefix} - {}", i18n.subject),
+        text: Some(text.render().expect("Template rendering: EmailWelcomeTxt")),
+        html: Some(html.render().expect("Template rendering: EMailWelcomeHtml")),
+    };
+
+    let res = RauthyConfig::get()
+        .tx_email
+        .send_timeout(req, Duration::from_secs(10))
+        .await;
+    if let Err(err) = res {
+        error!(user.email, error = ?err, "sending welcome email");
+    }
+}
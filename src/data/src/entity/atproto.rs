@@ -151,6 +151,10 @@ impl Client {
             client_secret_post: false,
             auto_onboarding: false,
             auto_link: false,
+            auto_redirect: false,
+            email_domains: Vec::default(),
+            claim_precedence: Default::default(),
+            uid_normalization: Default::default(),
             client_id: "rauthy".to_owned(),
             client_secret: None,
             scope: String::new(),
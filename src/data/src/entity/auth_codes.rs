@@ -2,11 +2,12 @@ use crate::database::{Cache, DB};
 use crate::rauthy_config::RauthyConfig;
 use chrono::Utc;
 use rauthy_common::utils::get_rand;
-use rauthy_error::ErrorResponse;
+use rauthy_error::{ErrorResponse, ErrorResponseType};
 use serde::{Deserialize, Serialize};
 use std::fmt::Write;
 use std::fmt::{Debug, Formatter};
 use std::ops::Add;
+use tracing::warn;
 use utoipa::ToSchema;
 
 #[derive(Deserialize, Serialize)]
@@ -101,6 +102,29 @@ impl AuthCode {
         }
     }
 
+    /// Remembers the `nonce` for the given client until `ttl` has passed and rejects it, if it
+    /// has been used for another authorization request during this time already.
+    pub async fn register_nonce(
+        client_id: &str,
+        nonce: &str,
+        ttl: i32,
+    ) -> Result<(), ErrorResponse> {
+        let idx = format!("nonce_{client_id}_{nonce}");
+        let used: Option<bool> = DB::hql().get(Cache::AuthCode, idx.clone()).await?;
+        if used.is_some() {
+            warn!("'nonce' replay detected for client {client_id}");
+            return Err(ErrorResponse::new(
+                ErrorResponseType::BadRequest,
+                "'nonce' has been used already",
+            ));
+        }
+
+        DB::hql()
+            .put(Cache::AuthCode, idx, &true, Some(ttl as i64))
+            .await?;
+        Ok(())
+    }
+
     /// CAUTION: DO NOT use this reset in any other case than after accepting updated ToS!
     pub async fn reset_exp(&mut self, auth_code_lifetime: i32) -> Result<(), ErrorResponse> {
         self.exp = Utc::now()
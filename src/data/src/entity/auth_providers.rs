@@ -5,6 +5,7 @@ use crate::entity::user_federation::UserFederation;
 use crate::entity::users::User;
 use crate::entity::users_values::UserValues;
 use crate::entity::{atproto, auth_provider_cust_impls};
+use crate::events::event::Event;
 use crate::language::Language;
 use crate::rauthy_config::RauthyConfig;
 use actix_web::cookie::Cookie;
@@ -25,7 +26,8 @@ use rauthy_common::constants::{
     IDX_AUTH_PROVIDER_TEMPLATE, PROVIDER_ATPROTO, PROVIDER_LINK_COOKIE,
 };
 use rauthy_common::utils::{
-    base64_decode, base64_encode, base64_url_no_pad_decode, deserialize, new_store_id, serialize,
+    base64_decode, base64_encode, base64_url_no_pad_decode, deserialize, email_domain_matches,
+    email_domain_patterns_overlap, new_store_id, serialize,
 };
 use rauthy_common::{http_client, is_hiqlite};
 use rauthy_error::{ErrorResponse, ErrorResponseType};
@@ -34,7 +36,7 @@ use serde_json::{Value, value};
 use serde_json_path::JsonPath;
 use std::borrow::Cow;
 use std::str::FromStr;
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 use utoipa::ToSchema;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, postgres_types::FromSql)]
@@ -107,6 +109,151 @@ impl From<AuthProviderType> for rauthy_api_types::auth_providers::AuthProviderTy
     }
 }
 
+/// Controls if upstream claims overwrite the local user attributes during a login. This affects
+/// the E-Mail, names and user values, but never the admin role or MFA claim mappings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthProviderClaimPrecedence {
+    /// Upstream claims overwrite local values with each login
+    #[default]
+    Upstream,
+    /// Upstream claims are only applied with the first login, which creates the link
+    FirstLogin,
+    /// Local values always win for existing users
+    Local,
+}
+
+impl AuthProviderClaimPrecedence {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Upstream => "upstream",
+            Self::FirstLogin => "first_login",
+            Self::Local => "local",
+        }
+    }
+
+    /// `true` if the upstream claims should overwrite the local values of an existing user.
+    /// `first_login` must be set, if the link to the provider has been created with this login.
+    pub fn overwrites_local(&self, first_login: bool) -> bool {
+        match self {
+            Self::Upstream => true,
+            Self::FirstLogin => first_login,
+            Self::Local => false,
+        }
+    }
+}
+
+impl From<String> for AuthProviderClaimPrecedence {
+    /// Defaults to `Self::Upstream` for unknown values
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "first_login" => Self::FirstLogin,
+            "local" => Self::Local,
+            _ => Self::Upstream,
+        }
+    }
+}
+
+impl From<rauthy_api_types::auth_providers::AuthProviderClaimPrecedence>
+    for AuthProviderClaimPrecedence
+{
+    fn from(value: rauthy_api_types::auth_providers::AuthProviderClaimPrecedence) -> Self {
+        match value {
+            rauthy_api_types::auth_providers::AuthProviderClaimPrecedence::Upstream => {
+                Self::Upstream
+            }
+            rauthy_api_types::auth_providers::AuthProviderClaimPrecedence::FirstLogin => {
+                Self::FirstLogin
+            }
+            rauthy_api_types::auth_providers::AuthProviderClaimPrecedence::Local => Self::Local,
+        }
+    }
+}
+
+impl From<AuthProviderClaimPrecedence>
+    for rauthy_api_types::auth_providers::AuthProviderClaimPrecedence
+{
+    fn from(value: AuthProviderClaimPrecedence) -> Self {
+        match value {
+            AuthProviderClaimPrecedence::Upstream => Self::Upstream,
+            AuthProviderClaimPrecedence::FirstLogin => Self::FirstLogin,
+            AuthProviderClaimPrecedence::Local => Self::Local,
+        }
+    }
+}
+
+/// Normalization for the upstream user id. It is applied before a link is created or looked up.
+/// When it changes, the stored links of the provider are migrated to the new normalization.
+///
+/// The variants are ordered from the least to the most normalizing one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthProviderUidNormalization {
+    /// The upstream user id is used as-is
+    #[default]
+    Exact,
+    /// Leading and trailing whitespace is removed
+    Trim,
+    /// Whitespace is removed and ASCII characters are lowercased
+    TrimLowercase,
+}
+
+impl AuthProviderUidNormalization {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Exact => "exact",
+            Self::Trim => "trim",
+            Self::TrimLowercase => "trim_lowercase",
+        }
+    }
+
+    pub fn apply<'a>(&self, uid: &'a str) -> Cow<'a, str> {
+        let trim = |uid: &'a str| uid.trim_matches([' ', '\t', '\n', '\r']);
+        match self {
+            Self::Exact => Cow::Borrowed(uid),
+            Self::Trim => Cow::Borrowed(trim(uid)),
+            Self::TrimLowercase => Cow::Owned(trim(uid).to_ascii_lowercase()),
+        }
+    }
+}
+
+impl From<String> for AuthProviderUidNormalization {
+    /// Defaults to `Self::Exact` for unknown values
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "trim" => Self::Trim,
+            "trim_lowercase" => Self::TrimLowercase,
+            _ => Self::Exact,
+        }
+    }
+}
+
+impl From<rauthy_api_types::auth_providers::AuthProviderUidNormalization>
+    for AuthProviderUidNormalization
+{
+    fn from(value: rauthy_api_types::auth_providers::AuthProviderUidNormalization) -> Self {
+        match value {
+            rauthy_api_types::auth_providers::AuthProviderUidNormalization::Exact => Self::Exact,
+            rauthy_api_types::auth_providers::AuthProviderUidNormalization::Trim => Self::Trim,
+            rauthy_api_types::auth_providers::AuthProviderUidNormalization::TrimLowercase => {
+                Self::TrimLowercase
+            }
+        }
+    }
+}
+
+impl From<AuthProviderUidNormalization>
+    for rauthy_api_types::auth_providers::AuthProviderUidNormalization
+{
+    fn from(value: AuthProviderUidNormalization) -> Self {
+        match value {
+            AuthProviderUidNormalization::Exact => Self::Exact,
+            AuthProviderUidNormalization::Trim => Self::Trim,
+            AuthProviderUidNormalization::TrimLowercase => Self::TrimLowercase,
+        }
+    }
+}
+
 /// Minimal version of the OpenID metadata. This is used for upstream oauth2 lookup.
 /// Only includes the data we care about when doing a config lookup.
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -179,12 +326,19 @@ pub struct AuthProvider {
     pub client_secret_post: bool,
     pub auto_onboarding: bool,
     pub auto_link: bool,
+    pub auto_redirect: bool,
+    /// Comma separated list of E-Mail domain patterns
+    pub email_domains: Option<String>,
+    pub claim_precedence: AuthProviderClaimPrecedence,
+    pub uid_normalization: AuthProviderUidNormalization,
 }
 
 impl<'r> From<hiqlite::Row<'r>> for AuthProvider {
     fn from(mut row: Row<'r>) -> Self {
         let typ_str: String = row.get("typ");
         let typ = AuthProviderType::from(typ_str);
+        let claim_precedence: String = row.get("claim_precedence");
+        let uid_normalization: String = row.get("uid_normalization");
 
         Self {
             id: row.get("id"),
@@ -208,6 +362,10 @@ impl<'r> From<hiqlite::Row<'r>> for AuthProvider {
             client_secret_post: row.get("client_secret_post"),
             auto_onboarding: row.get("auto_onboarding"),
             auto_link: row.get("auto_link"),
+            auto_redirect: row.get("auto_redirect"),
+            email_domains: row.get("email_domains"),
+            claim_precedence: AuthProviderClaimPrecedence::from(claim_precedence),
+            uid_normalization: AuthProviderUidNormalization::from(uid_normalization),
         }
     }
 }
@@ -216,6 +374,8 @@ impl From<tokio_postgres::Row> for AuthProvider {
     fn from(row: tokio_postgres::Row) -> Self {
         let typ_str: String = row.get("typ");
         let typ = AuthProviderType::from(typ_str);
+        let claim_precedence: String = row.get("claim_precedence");
+        let uid_normalization: String = row.get("uid_normalization");
 
         Self {
             id: row.get("id"),
@@ -239,6 +399,10 @@ impl From<tokio_postgres::Row> for AuthProvider {
             client_secret_post: row.get("client_secret_post"),
             auto_onboarding: row.get("auto_onboarding"),
             auto_link: row.get("auto_link"),
+            auto_redirect: row.get("auto_redirect"),
+            email_domains: row.get("email_domains"),
+            claim_precedence: AuthProviderClaimPrecedence::from(claim_precedence),
+            uid_normalization: AuthProviderUidNormalization::from(uid_normalization),
         }
     }
 }
@@ -246,6 +410,7 @@ impl From<tokio_postgres::Row> for AuthProvider {
 impl AuthProvider {
     pub async fn create(payload: ProviderRequest) -> Result<Self, ErrorResponse> {
         let slf = Self::try_from_id_req(new_store_id(), payload)?;
+        slf.validate_email_domains(&Self::find_all().await?)?;
         let typ = slf.typ.as_str();
 
         let sql = r#"
@@ -253,9 +418,10 @@ INSERT INTO
 auth_providers (id, name, enabled, typ, issuer, authorization_endpoint, token_endpoint,
 userinfo_endpoint, jwks_endpoint, client_id, secret, scope, admin_claim_path, admin_claim_value,
 mfa_claim_path, mfa_claim_value, use_pkce, client_secret_basic, client_secret_post, auto_onboarding,
-auto_link)
+auto_link, auto_redirect, email_domains, claim_precedence, uid_normalization)
 VALUES
-($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21)"#;
+($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21,
+$22, $23, $24, $25)"#;
 
         if is_hiqlite() {
             DB::hql()
@@ -282,7 +448,11 @@ VALUES
                         slf.client_secret_basic,
                         slf.client_secret_post,
                         slf.auto_onboarding,
-                        slf.auto_link
+                        slf.auto_link,
+                        slf.auto_redirect,
+                        &slf.email_domains,
+                        slf.claim_precedence.as_str(),
+                        slf.uid_normalization.as_str()
                     ),
                 )
                 .await?;
@@ -311,6 +481,10 @@ VALUES
                     &slf.client_secret_post,
                     &slf.auto_onboarding,
                     &slf.auto_link,
+                    &slf.auto_redirect,
+                    &slf.email_domains,
+                    &slf.claim_precedence.as_str(),
+                    &slf.uid_normalization.as_str(),
                 ],
             )
             .await?;
@@ -384,6 +558,45 @@ VALUES
         Ok(res)
     }
 
+    /// Returns the first enabled provider with an `email_domains` pattern matching the
+    /// domain of the given E-Mail.
+    pub async fn find_by_email_domain(email: &str) -> Result<Option<Self>, ErrorResponse> {
+        let Some((_, domain)) = email.rsplit_once('@') else {
+            return Ok(None);
+        };
+        let domain = domain.to_lowercase();
+
+        let provider = Self::find_all()
+            .await?
+            .into_iter()
+            .find(|p| p.enabled && p.matches_email_domain(&domain));
+
+        Ok(provider)
+    }
+
+    /// Home Realm Discovery: returns the enabled provider with `auto_redirect`, whose
+    /// `email_domains` match the given E-Mail. The user should be routed to it directly.
+    pub async fn find_home_realm(email: &str) -> Result<Option<Self>, ErrorResponse> {
+        Ok(Self::find_by_email_domain(email)
+            .await?
+            .filter(|p| p.auto_redirect))
+    }
+
+    /// Logs an error for each pair of enabled providers with overlapping `email_domains`.
+    /// New conflicts are rejected on create / update, but they may exist from before.
+    pub async fn check_email_domain_conflicts() -> Result<(), ErrorResponse> {
+        let providers = Self::find_all().await?;
+        for (i, provider) in providers.iter().enumerate() {
+            if let Err(err) = provider.validate_email_domains(&providers[i + 1..]) {
+                error!(
+                    "Invalid `email_domains` for Auth Provider '{}': {}",
+                    provider.name, err.message
+                );
+            }
+        }
+        Ok(())
+    }
+
     pub async fn find_linked_users(
         id: &str,
     ) -> Result<Vec<ProviderLinkedUserResponse>, ErrorResponse> {
@@ -417,7 +630,30 @@ WHERE uf.provider_id = $1"#;
     }
 
     pub async fn update(id: String, payload: ProviderRequest) -> Result<(), ErrorResponse> {
-        Self::try_from_id_req(id, payload)?.save().await
+        let slf = Self::try_from_id_req(id, payload)?;
+        slf.validate_email_domains(&Self::find_all().await?)?;
+
+        let current = Self::find(&slf.id).await?;
+        if current.uid_normalization != slf.uid_normalization {
+            // The original upstream ids are not stored, so existing links can't be migrated
+            // back to a less normalizing mode.
+            if slf.uid_normalization < current.uid_normalization
+                && UserFederation::has_for_provider(&slf.id).await?
+            {
+                return Err(ErrorResponse::new(
+                    ErrorResponseType::BadRequest,
+                    format!(
+                        "The User ID Normalization cannot be changed from '{}' to '{}' while \
+                        users are linked to this provider",
+                        current.uid_normalization.as_str(),
+                        slf.uid_normalization.as_str(),
+                    ),
+                ));
+            }
+            UserFederation::normalize_for_provider(&slf.id, slf.uid_normalization).await?;
+        }
+
+        slf.save().await
     }
 
     pub async fn save(&self) -> Result<(), ErrorResponse> {
@@ -429,8 +665,9 @@ SET name = $1, enabled = $2, issuer = $3, typ = $4, authorization_endpoint = $5,
 token_endpoint = $6, userinfo_endpoint = $7, jwks_endpoint = $8, client_id = $9, secret = $10,
 scope = $11, admin_claim_path = $12, admin_claim_value = $13, mfa_claim_path = $14,
 mfa_claim_value = $15, use_pkce = $16, client_secret_basic = $17, client_secret_post = $18,
-auto_onboarding = $19, auto_link = $20
-WHERE id = $21"#;
+auto_onboarding = $19, auto_link = $20, auto_redirect = $21, email_domains = $22,
+claim_precedence = $23, uid_normalization = $24
+WHERE id = $25"#;
 
         if is_hiqlite() {
             DB::hql()
@@ -457,6 +694,10 @@ WHERE id = $21"#;
                         self.client_secret_post,
                         self.auto_onboarding,
                         self.auto_link,
+                        self.auto_redirect,
+                        self.email_domains.clone(),
+                        self.claim_precedence.as_str(),
+                        self.uid_normalization.as_str(),
                         self.id.clone()
                     ),
                 )
@@ -485,6 +726,10 @@ WHERE id = $21"#;
                     &self.client_secret_post,
                     &self.auto_onboarding,
                     &self.auto_link,
+                    &self.auto_redirect,
+                    &self.email_domains,
+                    &self.claim_precedence.as_str(),
+                    &self.uid_normalization.as_str(),
                     &self.id,
                 ],
             )
@@ -506,6 +751,44 @@ impl AuthProvider {
         format!("{IDX_AUTH_PROVIDER}_{id}")
     }
 
+    /// Checks the given, lowercase E-Mail domain against the configured `email_domains`.
+    pub fn matches_email_domain(&self, domain: &str) -> bool {
+        self.email_domains.as_deref().is_some_and(|domains| {
+            domains
+                .split(',')
+                .any(|pattern| email_domain_matches(pattern, domain))
+        })
+    }
+
+    /// Makes sure that no E-Mail domain could be routed to more than one enabled provider.
+    fn validate_email_domains(&self, others: &[AuthProvider]) -> Result<(), ErrorResponse> {
+        let Some(domains) = self.email_domains.as_deref().filter(|_| self.enabled) else {
+            return Ok(());
+        };
+
+        for other in others.iter().filter(|p| p.enabled && p.id != self.id) {
+            let Some(other_domains) = &other.email_domains else {
+                continue;
+            };
+            for pattern in domains.split(',') {
+                if let Some(conflict) = other_domains
+                    .split(',')
+                    .find(|o| email_domain_patterns_overlap(pattern, o))
+                {
+                    return Err(ErrorResponse::new(
+                        ErrorResponseType::BadRequest,
+                        format!(
+                            "E-Mail domain '{pattern}' conflicts with '{conflict}' from provider '{}'",
+                            other.name
+                        ),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     fn cleanup_scope(scope: &str) -> String {
         scope
             .split(' ')
@@ -518,6 +801,17 @@ impl AuthProvider {
     fn try_from_id_req(id: String, req: ProviderRequest) -> Result<Self, ErrorResponse> {
         let scope = Self::cleanup_scope(&req.scope);
         let secret = Self::secret_encrypted(&req.client_secret)?;
+        let email_domains = if req.email_domains.is_empty() {
+            None
+        } else {
+            Some(
+                req.email_domains
+                    .iter()
+                    .map(|d| d.trim().to_lowercase())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            )
+        };
 
         Ok(Self {
             id,
@@ -544,6 +838,10 @@ impl AuthProvider {
             client_secret_post: req.client_secret_post,
             auto_onboarding: req.auto_onboarding,
             auto_link: req.auto_link,
+            auto_redirect: req.auto_redirect,
+            email_domains,
+            claim_precedence: req.claim_precedence.into(),
+            uid_normalization: req.uid_normalization.into(),
         })
     }
 
@@ -697,6 +995,13 @@ impl TryFrom<AuthProvider> for ProviderResponse {
             client_secret_post: value.client_secret_post,
             auto_onboarding: value.auto_onboarding,
             auto_link: value.auto_link,
+            auto_redirect: value.auto_redirect,
+            email_domains: value
+                .email_domains
+                .map(|d| d.split(',').map(String::from).collect())
+                .unwrap_or_default(),
+            claim_precedence: value.claim_precedence.into(),
+            uid_normalization: value.uid_normalization.into(),
         })
     }
 }
@@ -720,6 +1025,9 @@ pub struct AuthProviderCallback {
 
     // TODO add a nonce upstream as well? -> improvement?
     pub pkce_challenge: String,
+
+    /// Set for an account recovery, which only accepts already existing links
+    pub recovery: bool,
 }
 
 // CRUD
@@ -865,7 +1173,9 @@ impl AuthProviderCallback {
             // to go on and try fetching userinfo using the access token below.
             match AuthProviderIdClaims::try_from(claims_bytes.as_slice()) {
                 Ok(claims) => {
-                    return claims.validate_update_user(provider, link_cookie).await;
+                    return claims
+                        .validate_update_user(provider, link_cookie, self.recovery)
+                        .await;
                 }
                 Err(err) => {
                     debug!("Failed to extract claims from id_token: {err}. Trying access token.");
@@ -895,7 +1205,9 @@ impl AuthProviderCallback {
                     .await?;
             }
 
-            claims.validate_update_user(provider, link_cookie).await
+            claims
+                .validate_update_user(provider, link_cookie, self.recovery)
+                .await
         } else {
             let err = "Neither `access_token` nor `id_token` existed";
             error!("{err}");
@@ -980,7 +1292,9 @@ impl AuthProviderCallback {
             ..Default::default()
         };
 
-        claims.validate_update_user(provider, link_cookie).await
+        claims
+            .validate_update_user(provider, link_cookie, self.recovery)
+            .await
     }
 }
 
@@ -1152,6 +1466,7 @@ impl AuthProviderIdClaims<'_> {
         &self,
         provider: &AuthProvider,
         link_cookie: &Option<AuthProviderLinkCookie>,
+        recovery: bool,
     ) -> Result<(User, ProviderMfaLogin, NewFederatedUserCreated), ErrorResponse> {
         if self.email.is_none() {
             let err = "No `email` in ID token claims. This is a mandatory claim";
@@ -1187,17 +1502,37 @@ impl AuthProviderIdClaims<'_> {
             }
         };
 
+        // set, if the link to this provider is created with this login
+        let mut first_login = false;
         let (user_opt, new_federated_user) = match UserFederation::find_by_federation_id(
             &provider.id,
             &claims_user_id,
+            provider.uid_normalization,
         )
         .await
         {
             Ok(fed) => {
                 debug!("found already existing user by federation lookup: {fed:?}");
                 let user = User::find(fed.user_id).await?;
+                if user.disabled_at.is_some() {
+                    // soft-deleted users keep their links for the retention period, but
+                    // must never be able to log in again
+                    return Err(ErrorResponse::new(
+                        ErrorResponseType::Disabled,
+                        "This account has been deactivated and is scheduled for deletion",
+                    ));
+                }
                 (Some(user), NewFederatedUserCreated::No)
             }
+            Err(_) if recovery => {
+                // A recovery must never create new links or users. Otherwise, anyone with
+                // control over an upstream account with a matching E-Mail could take over
+                // the local account.
+                return Err(ErrorResponse::new(
+                    ErrorResponseType::Forbidden,
+                    "Account recovery is only possible via an already linked provider",
+                ));
+            }
             Err(_) => {
                 debug!("did not find already existing user by federation lookup");
                 if let Ok(user) =
@@ -1233,9 +1568,11 @@ impl AuthProviderIdClaims<'_> {
                         UserFederation::create(
                             user.id.clone(),
                             provider.id.clone(),
-                            claims_user_id.clone(),
+                            &claims_user_id,
+                            provider.uid_normalization,
                         )
                         .await?;
+                        first_login = true;
 
                         (Some(user), NewFederatedUserCreated::No)
                     } else if provider.auto_link || user.federation_count > 0 {
@@ -1245,9 +1582,11 @@ impl AuthProviderIdClaims<'_> {
                         UserFederation::create(
                             user.id.clone(),
                             provider.id.clone(),
-                            claims_user_id.clone(),
+                            &claims_user_id,
+                            provider.uid_normalization,
                         )
                         .await?;
+                        first_login = true;
 
                         (Some(user), NewFederatedUserCreated::No)
                     } else {
@@ -1355,23 +1694,44 @@ impl AuthProviderIdClaims<'_> {
         }
 
         let now = Utc::now().timestamp();
+        // new users will always be created from the upstream claims
+        let apply_claims =
+            user_opt.is_none() || provider.claim_precedence.overwrites_local(first_login);
+
         let user = if let Some(mut user) = user_opt {
             let mut old_email = None;
 
-            // check / update email
-            if Some(user.email.as_str()) != self.email.as_deref() {
-                old_email = Some(user.email);
-                user.email = self.email.as_ref().unwrap().to_string();
-            }
+            if apply_claims {
+                // check / update email
+                let email = self.email.as_deref().unwrap();
+                if user.email != email {
+                    // The upstream E-Mail may belong to another account already. We keep the
+                    // local one in this case instead of failing the whole login.
+                    match User::validate_email_free(email.to_string()).await {
+                        Ok(_) => {
+                            old_email = Some(user.email);
+                            user.email = email.to_string();
+                        }
+                        Err(_) => {
+                            warn!(
+                                user_id = user.id,
+                                provider = provider.name,
+                                "Upstream E-Mail is already taken by another account - keeping \
+                                the local one",
+                            );
+                        }
+                    }
+                }
 
-            // check other existing values and possibly update them
-            let given_name = self.given_name();
-            if user.given_name.as_str() != given_name {
-                user.given_name = given_name.to_string();
-            }
-            let family_name = self.family_name();
-            if user.family_name.as_deref() != family_name {
-                user.family_name = family_name.map(String::from);
+                // check other existing values and possibly update them
+                let given_name = self.given_name();
+                if user.given_name.as_str() != given_name {
+                    user.given_name = given_name.to_string();
+                }
+                let family_name = self.family_name();
+                if user.family_name.as_deref() != family_name {
+                    user.family_name = family_name.map(String::from);
+                }
             }
 
             // should this user be a rauthy admin?
@@ -1430,11 +1790,26 @@ impl AuthProviderIdClaims<'_> {
                     .as_ref()
                     .map(|l| Language::from(l.as_ref()))
                     .unwrap_or_default(),
+                pending_since: RauthyConfig::get()
+                    .vars
+                    .user_registration
+                    .require_approval_federated
+                    .then(|| Utc::now().timestamp()),
                 ..Default::default()
             };
             let user = User::create_federated(new_user).await?;
-            UserFederation::create(user.id.clone(), provider.id.clone(), claims_user_id.clone())
-                .await?;
+            UserFederation::create(
+                user.id.clone(),
+                provider.id.clone(),
+                &claims_user_id,
+                provider.uid_normalization,
+            )
+            .await?;
+            if user.pending_since.is_some() {
+                Event::registration_pending(&user.email, &provider.name, None)
+                    .send()
+                    .await?;
+            }
             user
         };
 
@@ -1490,7 +1865,7 @@ impl AuthProviderIdClaims<'_> {
         } else {
             self.login.as_ref().map(|l| l.to_string())
         };
-        if let Some(username) = preferred_username {
+        if let Some(username) = preferred_username.filter(|_| apply_claims) {
             // Check via local read first, which is a lot cheaper than write through the Raft.
             if UserValues::validate_preferred_username_free(username.clone())
                 .await
@@ -1503,7 +1878,7 @@ impl AuthProviderIdClaims<'_> {
             }
         }
 
-        if found_values {
+        if found_values && apply_claims {
             UserValues::upsert(user.id.clone(), user_values).await?;
         }
 
@@ -1571,4 +1946,112 @@ mod tests {
         let claims_bytes = AuthProviderIdClaims::self_as_bytes_from_token(raw).unwrap();
         assert!(AuthProviderIdClaims::try_from(claims_bytes.as_ref()).is_ok());
     }
+
+    #[test]
+    fn test_claim_precedence() {
+        let upstream = AuthProviderClaimPrecedence::Upstream;
+        assert!(upstream.overwrites_local(true));
+        assert!(upstream.overwrites_local(false));
+
+        let first_login = AuthProviderClaimPrecedence::FirstLogin;
+        assert!(first_login.overwrites_local(true));
+        assert!(!first_login.overwrites_local(false));
+
+        let local = AuthProviderClaimPrecedence::Local;
+        assert!(!local.overwrites_local(true));
+        assert!(!local.overwrites_local(false));
+
+        for p in [upstream, first_login, local] {
+            assert_eq!(AuthProviderClaimPrecedence::from(p.as_str().to_string()), p);
+        }
+        // rows from before the migration must keep the old behavior
+        assert_eq!(
+            AuthProviderClaimPrecedence::from(String::default()),
+            AuthProviderClaimPrecedence::Upstream
+        );
+    }
+
+    #[test]
+    fn test_uid_normalization() {
+        let exact = AuthProviderUidNormalization::Exact;
+        assert_eq!(exact.apply(" ABC "), " ABC ");
+        assert_ne!(exact.apply(" ABC "), exact.apply("abc"));
+
+        let trim = AuthProviderUidNormalization::Trim;
+        assert_eq!(trim.apply("\t ABC \r\n"), "ABC");
+        assert_ne!(trim.apply(" ABC "), trim.apply("abc"));
+
+        let trim_lowercase = AuthProviderUidNormalization::TrimLowercase;
+        assert_eq!(trim_lowercase.apply(" ABC "), "abc");
+        assert_eq!(trim_lowercase.apply(" ABC "), trim_lowercase.apply("abc"));
+        // non-ASCII must stay untouched to match the SQL side
+        assert_eq!(trim_lowercase.apply("ÄBC"), "Äbc");
+
+        for n in [exact, trim, trim_lowercase] {
+            assert_eq!(
+                AuthProviderUidNormalization::from(n.as_str().to_string()),
+                n
+            );
+        }
+        assert_eq!(
+            AuthProviderUidNormalization::from(String::default()),
+            AuthProviderUidNormalization::Exact
+        );
+
+        // decides if the links of a provider can be migrated
+        assert!(exact < trim);
+        assert!(trim < trim_lowercase);
+    }
+
+    #[test]
+    fn test_matches_email_domain() {
+        let mut provider = AuthProvider {
+            id: "test".to_string(),
+            name: "Test".to_string(),
+            enabled: true,
+            typ: AuthProviderType::OIDC,
+            issuer: "https://iam.example.com".to_string(),
+            authorization_endpoint: String::default(),
+            token_endpoint: String::default(),
+            userinfo_endpoint: String::default(),
+            jwks_endpoint: None,
+            client_id: "rauthy".to_string(),
+            secret: None,
+            scope: "openid".to_string(),
+            admin_claim_path: None,
+            admin_claim_value: None,
+            mfa_claim_path: None,
+            mfa_claim_value: None,
+            use_pkce: true,
+            client_secret_basic: false,
+            client_secret_post: false,
+            auto_onboarding: false,
+            auto_link: false,
+            auto_redirect: false,
+            email_domains: None,
+            claim_precedence: AuthProviderClaimPrecedence::Upstream,
+            uid_normalization: AuthProviderUidNormalization::Exact,
+        };
+        assert!(!provider.matches_email_domain("example.com"));
+
+        provider.email_domains = Some("example.com,*.corp.example.com".to_string());
+        assert!(provider.matches_email_domain("example.com"));
+        assert!(provider.matches_email_domain("eu.corp.example.com"));
+        assert!(!provider.matches_email_domain("corp.example.com"));
+        assert!(!provider.matches_email_domain("notexample.com"));
+
+        let mut other = provider.clone();
+        other.id = "other".to_string();
+        other.email_domains = Some("example.org,*.example.com".to_string());
+        assert!(provider.validate_email_domains(&[other.clone()]).is_err());
+        // the provider itself must be skipped during updates
+        assert!(provider.validate_email_domains(&[provider.clone()]).is_ok());
+
+        other.email_domains = Some("example.org,sub.example.org".to_string());
+        assert!(provider.validate_email_domains(&[other.clone()]).is_ok());
+        other.email_domains = Some("eu.corp.example.com".to_string());
+        assert!(provider.validate_email_domains(&[other.clone()]).is_err());
+        other.enabled = false;
+        assert!(provider.validate_email_domains(&[other]).is_ok());
+    }
 }
@@ -2,7 +2,9 @@ use crate::database::{Cache, DB};
 use crate::entity::auth_providers::ProviderMfaLogin;
 use crate::entity::clients_dyn::ClientDyn;
 use crate::entity::clients_scim::ClientScim;
+use crate::entity::continuation_token::Cursor;
 use crate::entity::jwk::JwkKeyPairAlg;
+use crate::entity::pairwise_secret::PairwiseSecret;
 use crate::entity::scopes::Scope;
 use crate::entity::users::User;
 use crate::rauthy_config::RauthyConfig;
@@ -16,17 +18,21 @@ use hiqlite::Params;
 use hiqlite_macros::params;
 use rauthy_api_types::clients::{
     ClientResponse, DynamicClientRequest, DynamicClientResponse, EphemeralClientRequest,
-    NewClientRequest, ScimClientRequestResponse,
+    NewClientRequest, ScimClientRequestResponse, SubjectType, UriMatchMode,
 };
-use rauthy_common::constants::{APPLICATION_JSON, CACHE_TTL_APP, SECRET_LEN_CLIENTS};
-use rauthy_common::utils::{get_rand, real_ip_from_req};
-use rauthy_common::{http_client, is_hiqlite};
+use rauthy_api_types::oidc::RedirectUriMatch;
+use rauthy_common::constants::{
+    APPLICATION_JSON, CACHE_TTL_APP, GRANT_TYPE_DEVICE_CODE, SECRET_LEN_CLIENTS,
+};
+use rauthy_common::utils::{base64_url_no_pad_encode, get_rand, real_ip_from_req};
+use rauthy_common::{http_client, is_hiqlite, sha256};
 use rauthy_error::{ErrorResponse, ErrorResponseType};
 use reqwest::Url;
 use reqwest::header::CONTENT_TYPE;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::cmp::min;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
 use std::fmt::{Debug, Formatter};
 use std::ops::Deref;
@@ -40,8 +46,11 @@ SET name = $1, enabled = $2, confidential = $3, secret = $4, secret_kid = $5, re
     post_logout_redirect_uris = $7, allowed_origins = $8, flows_enabled = $9, access_token_alg = $10,
     id_token_alg = $11, auth_code_lifetime = $12, access_token_lifetime = $13, scopes = $14,
     default_scopes = $15, challenge = $16, force_mfa= $17, client_uri = $18, contacts = $19,
-    backchannel_logout_uri = $20, restrict_group_prefix = $21
-WHERE id = $22"#;
+    backchannel_logout_uri = $20, restrict_group_prefix = $21, introspect = $22,
+    claim_mapping = $23, claim_namespace = $24, refresh_token_max_lifetime = $25,
+    require_nonce = $26, first_party = $27, subject_type = $28, sector_identifier_uri = $29,
+    secret_hashed = $30, uri_match_mode = $31
+WHERE id = $32"#;
 
 /**
 # OIDC Client
@@ -61,6 +70,9 @@ pub struct Client {
     pub confidential: bool,
     pub secret: Option<Vec<u8>>,
     pub secret_kid: Option<String>,
+    /// If `true`, the `secret` only contains the encrypted SHA256 hash of the secret, which means
+    /// it cannot be shown again after it has been generated or set.
+    pub secret_hashed: bool,
     pub redirect_uris: String,
     pub post_logout_redirect_uris: Option<String>,
     pub allowed_origins: Option<String>,
@@ -79,6 +91,15 @@ pub struct Client {
     pub contacts: Option<String>,
     pub backchannel_logout_uri: Option<String>,
     pub restrict_group_prefix: Option<String>,
+    pub introspect: bool,
+    pub claim_mapping: Option<String>,
+    pub claim_namespace: Option<String>,
+    pub refresh_token_max_lifetime: Option<i32>,
+    pub require_nonce: bool,
+    pub first_party: bool,
+    pub subject_type: SubjectType,
+    pub sector_identifier_uri: Option<String>,
+    pub uri_match_mode: UriMatchMode,
 }
 
 impl Debug for Client {
@@ -86,15 +107,18 @@ impl Debug for Client {
         write!(
             f,
             "Client {{ id: {}, name: {:?}, endabled: {}, confidential: {}, secret: <hidden>, \
-        redirect_uris: {}, post_logout_redirect_uris: {:?}, allowed_origins: {:?}, \
+        secret_hashed: {}, redirect_uris: {}, post_logout_redirect_uris: {:?}, allowed_origins: {:?}, \
         flows_enabled: {}, access_token_alg: {}, id_token_alg: {}, auth_code_lifetime: {}, \
         access_token_lifetime: {}, scopes: {}, default_scopes: {}, challenge: {:?}, force_mfa: {}, \
-        client_uri: {:?}, contacts: {:?}, backchannel_logout_uri: {:?}, restrict_group_prefix: {:?} \
-        }}",
+        client_uri: {:?}, contacts: {:?}, backchannel_logout_uri: {:?}, restrict_group_prefix: {:?}, \
+        introspect: {}, claim_mapping: {:?}, claim_namespace: {:?}, \
+        refresh_token_max_lifetime: {:?}, require_nonce: {}, first_party: {}, \
+        subject_type: {}, sector_identifier_uri: {:?}, uri_match_mode: {} }}",
             self.id,
             self.name,
             self.enabled,
             self.confidential,
+            self.secret_hashed,
             self.redirect_uris,
             self.post_logout_redirect_uris,
             self.allowed_origins,
@@ -111,6 +135,15 @@ impl Debug for Client {
             self.contacts,
             self.backchannel_logout_uri,
             self.restrict_group_prefix,
+            self.introspect,
+            self.claim_mapping,
+            self.claim_namespace,
+            self.refresh_token_max_lifetime,
+            self.require_nonce,
+            self.first_party,
+            self.subject_type.as_str(),
+            self.sector_identifier_uri,
+            self.uri_match_mode.as_str(),
         )
     }
 }
@@ -124,6 +157,7 @@ impl From<tokio_postgres::Row> for Client {
             confidential: row.get("confidential"),
             secret: row.get("secret"),
             secret_kid: row.get("secret_kid"),
+            secret_hashed: row.get("secret_hashed"),
             redirect_uris: row.get("redirect_uris"),
             post_logout_redirect_uris: row.get("post_logout_redirect_uris"),
             allowed_origins: row.get("allowed_origins"),
@@ -140,6 +174,15 @@ impl From<tokio_postgres::Row> for Client {
             contacts: row.get("contacts"),
             backchannel_logout_uri: row.get("backchannel_logout_uri"),
             restrict_group_prefix: row.get("restrict_group_prefix"),
+            introspect: row.get("introspect"),
+            claim_mapping: row.get("claim_mapping"),
+            claim_namespace: row.get("claim_namespace"),
+            refresh_token_max_lifetime: row.get("refresh_token_max_lifetime"),
+            require_nonce: row.get("require_nonce"),
+            first_party: row.get("first_party"),
+            subject_type: SubjectType::from(row.get::<_, String>("subject_type")),
+            sector_identifier_uri: row.get("sector_identifier_uri"),
+            uri_match_mode: UriMatchMode::from(row.get::<_, String>("uri_match_mode")),
         }
     }
 }
@@ -162,14 +205,18 @@ impl Client {
         };
         let mut client = Client::try_from(client_req)?;
         client.secret_kid = kid;
+        client.validate_flows_combination()?;
+        client.validate_uri_rules()?;
 
         let sql = r#"
 INSERT INTO clients (id, name, enabled, confidential, secret, secret_kid, redirect_uris,
 post_logout_redirect_uris, allowed_origins, flows_enabled, access_token_alg, id_token_alg,
 auth_code_lifetime, access_token_lifetime, scopes, default_scopes, challenge, force_mfa,
-client_uri, contacts, backchannel_logout_uri, restrict_group_prefix)
+client_uri, contacts, backchannel_logout_uri, restrict_group_prefix, introspect, claim_mapping,
+claim_namespace, refresh_token_max_lifetime, require_nonce,
+first_party, subject_type, sector_identifier_uri, secret_hashed, uri_match_mode)
 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17,
-$18, $19, $20, $21, $22)"#;
+$18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29, $30, $31, $32)"#;
 
         if is_hiqlite() {
             DB::hql()
@@ -197,7 +244,17 @@ $18, $19, $20, $21, $22)"#;
                         &client.client_uri,
                         &client.contacts,
                         &client.backchannel_logout_uri,
-                        &client.restrict_group_prefix
+                        &client.restrict_group_prefix,
+                        client.introspect,
+                        &client.claim_mapping,
+                        &client.claim_namespace,
+                        client.refresh_token_max_lifetime,
+                        client.require_nonce,
+                        client.first_party,
+                        client.subject_type.as_str(),
+                        &client.sector_identifier_uri,
+                        client.secret_hashed,
+                        client.uri_match_mode.as_str()
                     ),
                 )
                 .await?;
@@ -227,6 +284,16 @@ $18, $19, $20, $21, $22)"#;
                     &client.contacts,
                     &client.backchannel_logout_uri,
                     &client.restrict_group_prefix,
+                    &client.introspect,
+                    &client.claim_mapping,
+                    &client.claim_namespace,
+                    &client.refresh_token_max_lifetime,
+                    &client.require_nonce,
+                    &client.first_party,
+                    &client.subject_type.as_str(),
+                    &client.sector_identifier_uri,
+                    &client.secret_hashed,
+                    &client.uri_match_mode.as_str(),
                 ],
             )
             .await?;
@@ -245,6 +312,7 @@ $18, $19, $20, $21, $22)"#;
             .unwrap_or_else(|| "client_secret_basic".to_string());
 
         let client = Self::try_from_dyn_reg(client_req, origin_header)?;
+        client.validate_uri_rules()?;
 
         let created = Utc::now().timestamp();
         let (_secret_plain, registration_token) = Self::generate_new_secret()?;
@@ -253,9 +321,11 @@ $18, $19, $20, $21, $22)"#;
 INSERT INTO clients (id, name, enabled, confidential, secret, secret_kid, redirect_uris,
 post_logout_redirect_uris, allowed_origins, flows_enabled, access_token_alg, id_token_alg,
 auth_code_lifetime, access_token_lifetime, scopes, default_scopes, challenge, force_mfa,
-client_uri, contacts, backchannel_logout_uri, restrict_group_prefix)
+client_uri, contacts, backchannel_logout_uri, restrict_group_prefix, introspect, claim_mapping,
+claim_namespace, refresh_token_max_lifetime, require_nonce,
+first_party, subject_type, sector_identifier_uri, secret_hashed, uri_match_mode)
 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20,
-$21, $22)"#;
+$21, $22, $23, $24, $25, $26, $27, $28, $29, $30, $31, $32)"#;
         let sql_2 = r#"
 INSERT INTO
 clients_dyn (id, created, registration_token, token_endpoint_auth_method)
@@ -288,7 +358,17 @@ VALUES ($1, $2, $3, $4)"#;
                             &client.client_uri,
                             &client.contacts,
                             &client.backchannel_logout_uri,
-                            &client.restrict_group_prefix
+                            &client.restrict_group_prefix,
+                            client.introspect,
+                            &client.claim_mapping,
+                            &client.claim_namespace,
+                            client.refresh_token_max_lifetime,
+                            client.require_nonce,
+                            client.first_party,
+                            client.subject_type.as_str(),
+                            &client.sector_identifier_uri,
+                            client.secret_hashed,
+                            client.uri_match_mode.as_str()
                         ),
                     ),
                     (
@@ -332,6 +412,16 @@ VALUES ($1, $2, $3, $4)"#;
                     &client.contacts,
                     &client.backchannel_logout_uri,
                     &client.restrict_group_prefix,
+                    &client.introspect,
+                    &client.claim_mapping,
+                    &client.claim_namespace,
+                    &client.refresh_token_max_lifetime,
+                    &client.require_nonce,
+                    &client.first_party,
+                    &client.subject_type.as_str(),
+                    &client.sector_identifier_uri,
+                    &client.secret_hashed,
+                    &client.uri_match_mode.as_str(),
                 ],
             )
             .await?;
@@ -425,6 +515,29 @@ VALUES ($1, $2, $3, $4)"#;
         Ok(clients)
     }
 
+    /// Keyset pagination ordered by `id`. Returns a `Cursor` for the next page, if this page
+    /// has been completely filled.
+    pub async fn find_after(
+        cursor: Option<Cursor<String>>,
+        limit: i64,
+    ) -> Result<(Vec<Self>, Option<Cursor<String>>), ErrorResponse> {
+        let after = cursor.map(|c| c.0).unwrap_or_default();
+        let sql = "SELECT * FROM clients WHERE id > $1 ORDER BY id ASC LIMIT $2";
+        let clients: Vec<Self> = if is_hiqlite() {
+            DB::hql().query_as(sql, params!(after, limit)).await?
+        } else {
+            DB::pg_query(sql, &[&after, &limit], limit as usize).await?
+        };
+
+        let next = if clients.len() as i64 == limit {
+            clients.last().map(|c| Cursor(c.id.clone()))
+        } else {
+            None
+        };
+
+        Ok((clients, next))
+    }
+
     /// Finds all clients that match an entry in `ids` and have a configured `backchannel_logout_uri`.
     pub async fn find_all_bcl(ids: &[&str]) -> Result<Vec<Self>, ErrorResponse> {
         // Unfortunately, we cannot build the `IN` value upfront and use prepared statements,
@@ -546,6 +659,16 @@ VALUES ($1, $2, $3, $4)"#;
                 contacts,
                 backchannel_logout_uri,
                 &self.restrict_group_prefix,
+                self.introspect,
+                &self.claim_mapping,
+                &self.claim_namespace,
+                self.refresh_token_max_lifetime,
+                self.require_nonce,
+                self.first_party,
+                self.subject_type.as_str(),
+                &self.sector_identifier_uri,
+                self.secret_hashed,
+                self.uri_match_mode.as_str(),
                 &self.id
             ),
         ));
@@ -591,6 +714,16 @@ VALUES ($1, $2, $3, $4)"#;
                 &contacts,
                 &backchannel_logout_uri,
                 &self.restrict_group_prefix,
+                &self.introspect,
+                &self.claim_mapping,
+                &self.claim_namespace,
+                &self.refresh_token_max_lifetime,
+                &self.require_nonce,
+                &self.first_party,
+                &self.subject_type.as_str(),
+                &self.sector_identifier_uri,
+                &self.secret_hashed,
+                &self.uri_match_mode.as_str(),
                 &self.id,
             ],
         )
@@ -644,6 +777,16 @@ VALUES ($1, $2, $3, $4)"#;
                         contacts,
                         backchannel_logout_uri,
                         &self.restrict_group_prefix,
+                        self.introspect,
+                        self.claim_mapping.clone(),
+                        self.claim_namespace.clone(),
+                        self.refresh_token_max_lifetime,
+                        self.require_nonce,
+                        self.first_party,
+                        self.subject_type.as_str(),
+                        &self.sector_identifier_uri,
+                        self.secret_hashed,
+                        self.uri_match_mode.as_str(),
                         self.id.clone()
                     ),
                 )
@@ -673,6 +816,16 @@ VALUES ($1, $2, $3, $4)"#;
                     &contacts,
                     &backchannel_logout_uri,
                     &self.restrict_group_prefix,
+                    &self.introspect,
+                    &self.claim_mapping,
+                    &self.claim_namespace,
+                    &self.refresh_token_max_lifetime,
+                    &self.require_nonce,
+                    &self.first_party,
+                    &self.subject_type.as_str(),
+                    &self.sector_identifier_uri,
+                    &self.secret_hashed,
+                    &self.uri_match_mode.as_str(),
                     &self.id,
                 ],
             )
@@ -710,6 +863,13 @@ VALUES ($1, $2, $3, $4)"#;
         new_client.scopes = current.scopes;
         new_client.default_scopes = current.default_scopes;
         new_client.allowed_origins = current.allowed_origins;
+        // `sub` values must stay stable for all existing users
+        new_client.subject_type = current.subject_type;
+        new_client.sector_identifier_uri = current.sector_identifier_uri;
+        new_client.uri_match_mode = current.uri_match_mode;
+        new_client.validate_uri_rules()?;
+        // `first_party` is not copied over on purpose: a client could modify its own
+        // `redirect_uris` here, so an admin must trust it again afterward.
 
         client_dyn.token_endpoint_auth_method = token_endpoint_auth_method;
         client_dyn.last_used = Some(Utc::now().timestamp());
@@ -781,15 +941,14 @@ WHERE id = $4"#;
         cache_current_hours: Option<u8>,
     ) -> Result<(), ErrorResponse> {
         if let Some(hours) = cache_current_hours
-            && let Some(plain) = self.get_secret_cleartext()?
+            && let Some(hash) = self.get_secret_hash()?
         {
-            // The original idea was to save secrets hashed. However, since the secrets are used as
-            // a key, the HashMap inside the `hiqlite` cache handler will take care of this for us,
-            // to they could not be read from memory at runtime.
+            // Only the hash is used as the cache key, because hashed secrets cannot be
+            // recovered anymore anyway.
             DB::hql()
                 .put_bytes(
                     Cache::ClientSecret,
-                    plain,
+                    hex::encode(hash),
                     self.id.as_bytes().to_vec(),
                     Some(hours as i64),
                 )
@@ -802,7 +961,8 @@ WHERE id = $4"#;
         client_id: &str,
         secret: &str,
     ) -> Result<(), ErrorResponse> {
-        match DB::hql().get_bytes(Cache::ClientSecret, secret).await? {
+        let key = hex::encode(sha256!(secret.as_bytes()));
+        match DB::hql().get_bytes(Cache::ClientSecret, key).await? {
             None => {
                 debug!("No cached secret found for client {client_id}");
                 Err(ErrorResponse::new(
@@ -878,15 +1038,116 @@ impl Client {
     /// The decryption depends on correctly set up `ENC_KEYS` and `ENC_KEY_ACTIVE` environment
     /// variables and panics, if this is not the case.
     pub fn generate_new_secret() -> Result<(String, Vec<u8>), ErrorResponse> {
-        // CAUTION: DO NOT change the length of the `client_secret` here.
-        // If you need to at some point, make sure to update `Self::validate_secret()` as well,
-        // because it expects exactly 64 chars long secrets.
         let rnd = utils::secure_random_alnum(SECRET_LEN_CLIENTS);
         debug_assert_eq!(rnd.len(), SECRET_LEN_CLIENTS);
         let enc = EncValue::encrypt(rnd.as_bytes())?.into_bytes().to_vec();
         Ok((rnd, enc))
     }
 
+    /// Generates a new random 64 character long client secret and returns the cleartext and
+    /// the encrypted hash of it.
+    pub fn generate_new_secret_hashed() -> Result<(String, Vec<u8>), ErrorResponse> {
+        let rnd = utils::secure_random_alnum(SECRET_LEN_CLIENTS);
+        let hash = Self::hash_secret(&rnd)?;
+        Ok((rnd, hash))
+    }
+
+    /// Returns the encrypted SHA256 hash of the given secret, which is the format for all secrets
+    /// with `secret_hashed == true`.
+    pub fn hash_secret(secret: &str) -> Result<Vec<u8>, ErrorResponse> {
+        Ok(EncValue::encrypt(sha256!(secret.as_bytes()))?
+            .into_bytes()
+            .to_vec())
+    }
+
+    /// Validates a manually set client secret against the `access.client_secret_min_*` policy
+    /// and returns the encrypted hash of it.
+    pub fn hash_custom_secret(secret: &str) -> Result<Vec<u8>, ErrorResponse> {
+        let access = &RauthyConfig::get().vars.access;
+        Self::validate_secret_policy(
+            secret,
+            access.client_secret_min_length,
+            access.client_secret_min_entropy,
+        )?;
+        Self::hash_secret(secret)
+    }
+
+    fn validate_secret_policy(
+        secret: &str,
+        min_length: u16,
+        min_entropy: u16,
+    ) -> Result<(), ErrorResponse> {
+        let len = secret.chars().count();
+        if len < min_length as usize {
+            return Err(ErrorResponse::new(
+                ErrorResponseType::BadRequest,
+                format!("The client secret must be at least {min_length} characters long"),
+            ));
+        }
+
+        let entropy = Self::secret_entropy_bits(secret);
+        if entropy < min_entropy as f64 {
+            return Err(ErrorResponse::new(
+                ErrorResponseType::BadRequest,
+                format!(
+                    "The client secret is too weak: estimated entropy of {entropy:.0} bits is \
+                    below the required {min_entropy} bits - use a random secret or let Rauthy \
+                    generate one"
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Estimates the entropy of a secret from its character frequencies (Shannon entropy per
+    /// character) multiplied by the amount of characters, which are not part of a repetition of
+    /// an earlier sequence. This under-estimates truly random secrets a bit, but it reliably
+    /// catches repeated patterns and secrets with only a few distinct characters.
+    fn secret_entropy_bits(secret: &str) -> f64 {
+        let chars = secret.chars().collect::<Vec<_>>();
+        if chars.is_empty() {
+            return 0.0;
+        }
+
+        let mut counts = HashMap::with_capacity(chars.len());
+        for c in &chars {
+            *counts.entry(*c).or_insert(0u32) += 1;
+        }
+        let len = chars.len() as f64;
+        let per_char = counts
+            .values()
+            .map(|count| {
+                let p = *count as f64 / len;
+                -p * p.log2()
+            })
+            .sum::<f64>();
+
+        // skip all sequences of at least 3 chars that have been there before already
+        let mut counted = 0;
+        let mut i = 0;
+        while i < chars.len() {
+            let longest_repeat = (0..i)
+                .map(|start| {
+                    chars[start..]
+                        .iter()
+                        .zip(&chars[i..])
+                        .take_while(|(a, b)| a == b)
+                        .count()
+                })
+                .max()
+                .unwrap_or(0);
+            if longest_repeat >= 3 {
+                i += longest_repeat;
+            } else {
+                counted += 1;
+                i += 1;
+            }
+        }
+
+        per_char * counted as f64
+    }
+
     #[inline(always)]
     pub fn get_access_token_alg(&self) -> Result<JwkKeyPairAlg, ErrorResponse> {
         JwkKeyPairAlg::from_str(self.access_token_alg.as_str())
@@ -934,9 +1195,23 @@ impl Client {
         }
     }
 
+    /// Returns the `internal=output` claim name pairs from the `claim_mapping`.
+    pub fn get_claim_mapping(&self) -> Option<Vec<(&str, &str)>> {
+        let mapping = self.claim_mapping.as_ref()?;
+        let res = mapping
+            .split(',')
+            .filter_map(|m| m.split_once('='))
+            .collect::<Vec<_>>();
+        if res.is_empty() { None } else { Some(res) }
+    }
+
     /// Decrypts the client secret (if it exists) and then returns it as clear text.
+    /// Hashed secrets cannot be recovered and will always return `None`.
     #[inline]
     pub fn get_secret_cleartext(&self) -> Result<Option<String>, ErrorResponse> {
+        if self.secret_hashed {
+            return Ok(None);
+        }
         if let Some(secret) = self.secret.as_ref() {
             let bytes = EncValue::try_from(secret.clone())?.decrypt()?;
             let cleartext = String::from_utf8_lossy(bytes.as_ref()).to_string();
@@ -946,6 +1221,19 @@ impl Client {
         }
     }
 
+    /// Returns the SHA256 hash of the client secret (if it exists).
+    pub fn get_secret_hash(&self) -> Result<Option<Vec<u8>>, ErrorResponse> {
+        let Some(secret) = self.secret.as_ref() else {
+            return Ok(None);
+        };
+        let dec = EncValue::try_from(secret.clone())?.decrypt()?;
+        if self.secret_hashed {
+            Ok(Some(dec.to_vec()))
+        } else {
+            Ok(Some(sha256!(dec.as_ref()).to_vec()))
+        }
+    }
+
     #[inline]
     pub fn get_default_scopes(&self) -> Vec<String> {
         let mut res = Vec::new();
@@ -1022,6 +1310,40 @@ impl Client {
         Url::from_str(&self.id).is_ok()
     }
 
+    #[inline]
+    pub fn is_pairwise(&self) -> bool {
+        self.subject_type == SubjectType::Pairwise
+    }
+
+    /// Returns the `sub` for the given user, as it should be seen by this client.
+    ///
+    /// For `pairwise` clients, this is an HMAC of the user id and the sector identifier. It is
+    /// stable for all clients inside the same sector, but cannot be correlated across sectors.
+    pub fn subject<'a>(&self, user_id: &'a str) -> Cow<'a, str> {
+        if !self.is_pairwise() {
+            return Cow::Borrowed(user_id);
+        }
+        Cow::Owned(self.pairwise_subject(PairwiseSecret::get(), user_id))
+    }
+
+    /// Without the server secret, nobody can compute the `pairwise` subject for a known user id.
+    fn pairwise_subject(&self, secret: &[u8], user_id: &str) -> String {
+        let input = format!("{}:{user_id}", self.sector_identifier());
+        let mac = hmac_sha256::HMAC::mac(input.as_bytes(), secret);
+        base64_url_no_pad_encode(&mac)
+    }
+
+    /// The host of the `sector_identifier_uri`, or the `client_id` as a fallback, which makes
+    /// each client without a `sector_identifier_uri` its own sector.
+    fn sector_identifier(&self) -> Cow<'_, str> {
+        self.sector_identifier_uri
+            .as_deref()
+            .and_then(|uri| Url::parse(uri).ok())
+            .and_then(|url| url.host_str().map(String::from))
+            .map(Cow::Owned)
+            .unwrap_or(Cow::Borrowed(self.id.as_str()))
+    }
+
     /// Sanitizes the current scopes and deletes everything, which does not exist in the `scopes`
     /// table in the database
     pub async fn sanitize_scopes(scps: Vec<String>) -> Result<String, ErrorResponse> {
@@ -1069,6 +1391,8 @@ impl Client {
         }
 
         let matrix_enabled = RauthyConfig::get().vars.matrix.msc3861_enable;
+        // trusted first-party clients always get not allowed scopes silently dropped
+        let strict = RauthyConfig::get().vars.access.strict_scopes && !self.first_party;
 
         for s in scopes {
             if self.default_scopes.split(',').any(|d| d == s) {
@@ -1081,6 +1405,11 @@ impl Client {
                 .any(|allowed| Scope::matches(allowed, s, matrix_enabled))
             {
                 res.push(s.clone());
+            } else if strict {
+                return Err(ErrorResponse::new(
+                    ErrorResponseType::InvalidScope,
+                    format!("'{s}' is not allowed for this client"),
+                ));
             }
         }
 
@@ -1201,12 +1530,7 @@ impl Client {
 
     #[inline]
     pub fn validate_redirect_uri(&self, redirect_uri: &str) -> Result<(), ErrorResponse> {
-        let has_any = self.get_redirect_uris().iter().any(|uri| {
-            (uri.ends_with('*') && redirect_uri.starts_with(uri.split_once('*').unwrap().0))
-                || uri.as_str().eq(redirect_uri)
-        });
-
-        if has_any {
+        if self.match_redirect_uri(redirect_uri).is_some() {
             Ok(())
         } else {
             debug!(
@@ -1220,22 +1544,21 @@ impl Client {
         }
     }
 
+    /// Returns the way the given `redirect_uri` matches the registered ones, if at all.
+    /// An exact match always takes precedence.
+    pub fn match_redirect_uri(&self, redirect_uri: &str) -> Option<RedirectUriMatch> {
+        match_uris(self.uri_match_mode, &self.get_redirect_uris(), redirect_uri)
+    }
+
     #[inline]
     pub fn validate_post_logout_redirect_uri(
         &self,
         post_logout_redirect_uri: &str,
     ) -> Result<(), ErrorResponse> {
-        let has_any = self
-            .get_post_logout_uris()
-            .unwrap_or_default()
-            .iter()
-            .any(|uri| {
-                (uri.ends_with('*')
-                    && post_logout_redirect_uri.starts_with(uri.split_once('*').unwrap().0))
-                    || uri.as_str().eq(post_logout_redirect_uri)
-            });
-
-        if has_any {
+        if self
+            .match_post_logout_redirect_uri(post_logout_redirect_uri)
+            .is_some()
+        {
             Ok(())
         } else {
             trace!("Invalid `post_logout_redirect_uri`");
@@ -1246,13 +1569,131 @@ impl Client {
         }
     }
 
+    /// Returns the way the given `post_logout_redirect_uri` matches the registered ones, if at
+    /// all. An exact match always takes precedence.
+    pub fn match_post_logout_redirect_uri(
+        &self,
+        post_logout_redirect_uri: &str,
+    ) -> Option<RedirectUriMatch> {
+        match_uris(
+            self.uri_match_mode,
+            &self.get_post_logout_uris()?,
+            post_logout_redirect_uri,
+        )
+    }
+
+    /// Makes sure that wildcards in the `redirect_uris` and `post_logout_redirect_uris` can only
+    /// ever match URIs on a single, well-defined origin or subdomain level. Clients in the legacy
+    /// `prefix` mode are not restricted.
+    pub fn validate_uri_rules(&self) -> Result<(), ErrorResponse> {
+        let uris = self
+            .get_redirect_uris()
+            .into_iter()
+            .chain(self.get_post_logout_uris().unwrap_or_default());
+        for uri in uris {
+            if !is_valid_uri_rule(self.uri_match_mode, &uri) {
+                return Err(ErrorResponse::new(
+                    ErrorResponseType::BadRequest,
+                    format!(
+                        "Invalid redirect URI '{uri}': a wildcard is only allowed at the very end \
+                        of the path or as the first label of the host, like \
+                        'https://*.example.com/logout', unless the client uses the legacy \
+                        `prefix` uri_match_mode"
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Fetches the `sector_identifier_uri`, which must return a JSON array containing all
+    /// `redirect_uris` of this client, as required by OpenID Connect Dynamic Client
+    /// Registration 1.0, Section 5.
+    pub async fn validate_sector_identifier_uri(&self) -> Result<(), ErrorResponse> {
+        let Some(uri) = &self.sector_identifier_uri else {
+            return Ok(());
+        };
+        if Url::parse(uri)
+            .ok()
+            .and_then(|url| url.host_str().map(|_| ()))
+            .is_none()
+        {
+            return Err(ErrorResponse::new(
+                ErrorResponseType::BadRequest,
+                format!("Invalid sector_identifier_uri '{uri}': it must be a URL with a host"),
+            ));
+        }
+
+        let res = http_client()
+            .get(uri)
+            .header(CONTENT_TYPE, APPLICATION_JSON)
+            .send()
+            .await
+            .map_err(|err| {
+                ErrorResponse::new(
+                    ErrorResponseType::BadRequest,
+                    format!("Cannot fetch sector_identifier_uri {uri}: {err:?}"),
+                )
+            })?;
+        if !res.status().is_success() {
+            return Err(ErrorResponse::new(
+                ErrorResponseType::BadRequest,
+                format!(
+                    "Cannot fetch sector_identifier_uri {uri}: status {}",
+                    res.status()
+                ),
+            ));
+        }
+        let sector_uris = res.json::<Vec<String>>().await.map_err(|err| {
+            ErrorResponse::new(
+                ErrorResponseType::BadRequest,
+                format!("sector_identifier_uri {uri} must return a JSON array of URIs: {err:?}"),
+            )
+        })?;
+
+        if let Some(missing) = self
+            .get_redirect_uris()
+            .into_iter()
+            .find(|redirect_uri| !sector_uris.contains(redirect_uri))
+        {
+            return Err(ErrorResponse::new(
+                ErrorResponseType::BadRequest,
+                format!("redirect_uri '{missing}' is missing in the sector_identifier_uri {uri}"),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the allowed PKCE challenge methods. Public clients always require PKCE, which
+    /// means they fall back to `S256`, even if nothing has been configured.
+    #[inline]
+    pub fn challenges_allowed(&self) -> Option<&str> {
+        match &self.challenge {
+            Some(c) => Some(c.as_str()),
+            None if !self.confidential => Some("S256"),
+            None => None,
+        }
+    }
+
+    #[inline]
+    pub fn validate_nonce(&self, nonce: Option<&str>) -> Result<(), ErrorResponse> {
+        if self.require_nonce && nonce.is_none() {
+            trace!("'nonce' is missing");
+            return Err(ErrorResponse::new(
+                ErrorResponseType::BadRequest,
+                "'nonce' is missing",
+            ));
+        }
+        Ok(())
+    }
+
     #[inline]
     pub fn validate_code_challenge(
         &self,
         code_challenge: &Option<String>,
         code_challenge_method: &Option<String>,
     ) -> Result<(), ErrorResponse> {
-        if let Some(methods_allowed) = &self.challenge {
+        if let Some(methods_allowed) = self.challenges_allowed() {
             if code_challenge.is_none() {
                 trace!("'code_challenge' is missing");
                 return Err(ErrorResponse::new(
@@ -1269,7 +1710,7 @@ impl Client {
                 ));
             };
 
-            if methods_allowed.contains(method) {
+            if methods_allowed.split(',').any(|m| m.trim() == method) {
                 Ok(())
             } else {
                 trace!("given code_challenge_method is not allowed");
@@ -1294,18 +1735,16 @@ impl Client {
         &self,
         code_challenge_method: &str,
     ) -> Result<(), ErrorResponse> {
-        if self.challenge.is_none() {
+        let Some(methods_allowed) = self.challenges_allowed() else {
             return Err(ErrorResponse::new(
                 ErrorResponseType::BadRequest,
                 "'code_challenge' not allowed",
             ));
-        }
+        };
         if code_challenge_method.is_empty()
-            || !self
-                .challenge
-                .as_ref()
-                .unwrap()
-                .contains(code_challenge_method)
+            || !methods_allowed
+                .split(',')
+                .any(|m| m.trim() == code_challenge_method)
         {
             return Err(ErrorResponse::new(
                 ErrorResponseType::BadRequest,
@@ -1317,15 +1756,90 @@ impl Client {
 
     #[inline]
     pub fn validate_flow(&self, flow: &str) -> Result<(), ErrorResponse> {
-        if flow.is_empty() || !self.flows_enabled.contains(flow) {
+        if flow.is_empty() || !self.flows_enabled.split(',').any(|f| f.trim() == flow) {
+            return Err(ErrorResponse::new(
+                ErrorResponseType::UnauthorizedClient,
+                format!(
+                    "'{flow}' flow is not allowed for this client, allowed: {}",
+                    Self::fmt_allowed(&self.flows_enabled)
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Rauthy only supports `response_type=code`, which is only valid for clients with
+    /// the `authorization_code` flow enabled.
+    #[inline]
+    pub fn validate_response_type(&self, response_type: &str) -> Result<(), ErrorResponse> {
+        let allowed = if self.validate_flow("authorization_code").is_ok() {
+            "code"
+        } else {
+            ""
+        };
+        if response_type.is_empty() || response_type != allowed {
+            return Err(ErrorResponse::new(
+                ErrorResponseType::UnauthorizedClient,
+                format!(
+                    "'{response_type}' response_type is not allowed for this client, allowed: {}",
+                    Self::fmt_allowed(allowed)
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Validates the combination of the enabled flows before a client is saved, to catch
+    /// configurations which could never work at the token or authorization endpoint.
+    pub fn validate_flows_combination(&self) -> Result<(), ErrorResponse> {
+        let flows = self.get_flows();
+        let has = |flow: &str| flows.iter().any(|f| f == flow);
+
+        if has("authorization_code") && self.redirect_uris.is_empty() {
+            return Err(ErrorResponse::new(
+                ErrorResponseType::BadRequest,
+                "'authorization_code' flow requires the 'code' response_type, which needs at \
+                least one 'redirect_uri'",
+            ));
+        }
+        if has("client_credentials") && !self.confidential {
             return Err(ErrorResponse::new(
                 ErrorResponseType::BadRequest,
-                format!("'{flow}' flow is not allowed for this client"),
+                "'client_credentials' flow is allowed for confidential clients only",
             ));
         }
+        if has("refresh_token")
+            && !has("authorization_code")
+            && !has("password")
+            && !has(GRANT_TYPE_DEVICE_CODE)
+        {
+            return Err(ErrorResponse::new(
+                ErrorResponseType::BadRequest,
+                format!(
+                    "'refresh_token' flow requires one of: authorization_code, password, \
+                    {GRANT_TYPE_DEVICE_CODE}"
+                ),
+            ));
+        }
+
         Ok(())
     }
 
+    #[inline]
+    fn fmt_allowed(csv: &str) -> String {
+        let allowed = csv
+            .split(',')
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+            .collect::<Vec<_>>()
+            .join(", ");
+        if allowed.is_empty() {
+            "none".to_string()
+        } else {
+            allowed
+        }
+    }
+
     #[inline]
     pub async fn validate_secret(
         &self,
@@ -1339,8 +1853,8 @@ impl Client {
                 "Cannot validate 'client_secret' for public client",
             ));
         }
-        if secret.len() != SECRET_LEN_CLIENTS {
-            error!("Invalid / too short secret given as `client_secret`");
+        if secret.is_empty() {
+            error!("Empty `client_secret` given");
             return Err(ErrorResponse::new(
                 ErrorResponseType::Unauthorized,
                 "Invalid 'client_secret'",
@@ -1353,14 +1867,16 @@ impl Client {
                 format!("'{}' has no secret while being confidential", &self.id),
             )
         })?;
-        let cleartext = EncValue::try_from(secret_enc.clone())?.decrypt()?;
+        let dec = EncValue::try_from(secret_enc.clone())?.decrypt()?;
 
-        // make sure this function is updated if the secret length ever changes
-        debug_assert_eq!(cleartext.len(), SECRET_LEN_CLIENTS);
-
-        let a = <&[u8; 64]>::try_from(cleartext.as_ref()).unwrap();
-        let b = <&[u8; 64]>::try_from(secret.as_bytes()).unwrap();
-        if constant_time_eq::constant_time_eq_64(a, b)
+        // Legacy secrets may have been set manually, so they can have any length. This only
+        // leaks the length of the secret, which is fine.
+        let is_valid = if self.secret_hashed {
+            constant_time_eq::constant_time_eq(dec.as_ref(), sha256!(secret.as_bytes()))
+        } else {
+            constant_time_eq::constant_time_eq(dec.as_ref(), secret.as_bytes())
+        };
+        if is_valid
             || Client::validate_cached_secret(&self.id, secret)
                 .await
                 .is_ok()
@@ -1453,6 +1969,12 @@ impl Client {
         let default_scopes = self.get_default_scopes();
         let challenges = self.get_challenges();
         let contacts = self.get_contacts();
+        let claim_mapping = self.get_claim_mapping().map(|mapping| {
+            mapping
+                .into_iter()
+                .map(|(from, to)| (from.to_string(), to.to_string()))
+                .collect()
+        });
 
         let access_token_alg = JwkKeyPairAlg::from_str(&self.access_token_alg)
             .expect("internal JwkKeyPairAlg conversion to always succeed")
@@ -1482,6 +2004,15 @@ impl Client {
             contacts,
             backchannel_logout_uri: self.backchannel_logout_uri,
             restrict_group_prefix: self.restrict_group_prefix,
+            introspect: self.introspect,
+            claim_mapping,
+            claim_namespace: self.claim_namespace,
+            refresh_token_max_lifetime: self.refresh_token_max_lifetime,
+            require_nonce: self.require_nonce,
+            first_party: self.first_party,
+            subject_type: self.subject_type,
+            sector_identifier_uri: self.sector_identifier_uri,
+            uri_match_mode: self.uri_match_mode,
             scim: scim.map(|scim| ScimClientRequestResponse {
                 bearer_token: scim.bearer_token,
                 base_uri: scim.base_uri,
@@ -1507,6 +2038,7 @@ impl From<EphemeralClientRequest> for Client {
             confidential: false,
             secret: None,
             secret_kid: None,
+            secret_hashed: false,
             redirect_uris: value.redirect_uris.join(","),
             post_logout_redirect_uris: value.post_logout_redirect_uris.map(|uris| uris.join(",")),
             allowed_origins: None,
@@ -1533,6 +2065,15 @@ impl From<EphemeralClientRequest> for Client {
             contacts: value.contacts.map(|c| c.join(",")),
             backchannel_logout_uri: None,
             restrict_group_prefix: None,
+            introspect: false,
+            claim_mapping: None,
+            claim_namespace: None,
+            refresh_token_max_lifetime: None,
+            require_nonce: false,
+            first_party: false,
+            subject_type: SubjectType::Public,
+            sector_identifier_uri: None,
+            uri_match_mode: UriMatchMode::Prefix,
         }
     }
 }
@@ -1556,6 +2097,7 @@ impl Default for Client {
             confidential: false,
             secret: None,
             secret_kid: None,
+            secret_hashed: false,
             redirect_uris: String::default(),
             post_logout_redirect_uris: None,
             allowed_origins: None,
@@ -1572,6 +2114,15 @@ impl Default for Client {
             contacts: None,
             backchannel_logout_uri: None,
             restrict_group_prefix: None,
+            introspect: false,
+            claim_mapping: None,
+            claim_namespace: None,
+            refresh_token_max_lifetime: None,
+            require_nonce: false,
+            first_party: false,
+            subject_type: SubjectType::Public,
+            sector_identifier_uri: None,
+            uri_match_mode: UriMatchMode::Strict,
         }
     }
 }
@@ -1611,6 +2162,7 @@ impl TryFrom<NewClientRequest> for Client {
             confidential: client.confidential,
             redirect_uris,
             post_logout_redirect_uris,
+            uri_match_mode: client.uri_match_mode.unwrap_or_default(),
             ..Default::default()
         })
     }
@@ -1621,6 +2173,29 @@ impl Client {
         req: DynamicClientRequest,
         origin_header: Option<String>,
     ) -> Result<Self, ErrorResponse> {
+        // the combination is only checked if `response_types` is given explicitly
+        let response_types = req
+            .response_types
+            .as_deref()
+            .unwrap_or(&[])
+            .iter()
+            .map(|rt| rt.as_str())
+            .collect::<Vec<_>>();
+        if let Some(rt) = response_types.iter().find(|rt| **rt != "code") {
+            return Err(ErrorResponse::new(
+                ErrorResponseType::BadRequest,
+                format!("Unsupported response_type '{rt}', allowed: code"),
+            ));
+        }
+        let has_auth_code = req.grant_types.iter().any(|g| g == "authorization_code");
+        if req.response_types.is_some() && has_auth_code != response_types.contains(&"code") {
+            return Err(ErrorResponse::new(
+                ErrorResponseType::BadRequest,
+                "The 'code' response_type and the 'authorization_code' grant_type must be used \
+                together",
+            ));
+        }
+
         let id = format!("dyn${}", get_rand(16));
 
         let confidential = req.token_endpoint_auth_method.as_deref() != Some("none");
@@ -1675,13 +2250,14 @@ impl Client {
             .default_scopes
             .join(",");
 
-        Ok(Self {
+        let client = Self {
             id,
             name: req.client_name,
             enabled: true,
             confidential,
             secret,
             secret_kid,
+            secret_hashed: false,
             redirect_uris: req.redirect_uris.join(","),
             post_logout_redirect_uris: req.post_logout_redirect_uri.filter(|uri| !uri.is_empty()),
             allowed_origins,
@@ -1703,7 +2279,10 @@ impl Client {
             contacts: req.contacts.map(|c| c.join(",")).filter(|c| !c.is_empty()),
             backchannel_logout_uri: req.backchannel_logout_uri,
             ..Default::default()
-        })
+        };
+        client.validate_flows_combination()?;
+
+        Ok(client)
     }
 
     pub fn into_dynamic_client_response(
@@ -1783,6 +2362,145 @@ fn extract_external_origin<'a>(
     Ok(Some(origin))
 }
 
+/// Splits a URI into `(scheme, host, port, rest)`. Returns `None` for anything that could be
+/// used to confuse the matching, like userinfo or backslashes inside the authority.
+fn split_uri(uri: &str) -> Option<(&str, &str, Option<&str>, &str)> {
+    let (scheme, after) = uri.split_once("://")?;
+    let idx = after.find(['/', '?', '#']).unwrap_or(after.len());
+    let (authority, rest) = after.split_at(idx);
+    if scheme.is_empty() || authority.contains(['@', '\\']) {
+        return None;
+    }
+
+    let (host, port) = if authority.starts_with('[') {
+        let (host, port) = authority.split_at(authority.find(']')? + 1);
+        if port.is_empty() {
+            (host, None)
+        } else {
+            (host, Some(port.strip_prefix(':')?))
+        }
+    } else {
+        match authority.split_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        }
+    };
+    if host.is_empty()
+        || port.is_some_and(|p| p.is_empty() || !p.bytes().all(|b| b.is_ascii_digit()))
+    {
+        return None;
+    }
+
+    Some((scheme, host, port, rest))
+}
+
+/// Returns the best match for the `target` against all registered `rules`. An exact match always
+/// takes precedence.
+fn match_uris(mode: UriMatchMode, rules: &[String], target: &str) -> Option<RedirectUriMatch> {
+    if rules.iter().any(|rule| rule == target) {
+        return Some(RedirectUriMatch::Exact);
+    }
+    rules
+        .iter()
+        .filter_map(|rule| match_uri(mode, rule, target))
+        .min_by_key(|m| *m != RedirectUriMatch::Exact)
+}
+
+/// Matches a `redirect_uri` or `post_logout_redirect_uri` against a single registered `rule`:
+///
+/// - exact match
+/// - `prefix` mode: a rule ending with `*` matches anything starting with the part in front of it
+/// - `strict` mode:
+///   - loopback: `http://127.0.0.1`, `http://[::1]` and `http://localhost` accept any port
+///     (RFC 8252)
+///   - wildcard: a `*` at the very end matches any path on the exact same origin, and `*.` as
+///     the first label of the host matches exactly one subdomain level
+fn match_uri(mode: UriMatchMode, rule: &str, target: &str) -> Option<RedirectUriMatch> {
+    if rule == target {
+        return Some(RedirectUriMatch::Exact);
+    }
+    if mode == UriMatchMode::Prefix {
+        return rule
+            .split_once('*')
+            .filter(|(prefix, _)| rule.ends_with('*') && target.starts_with(prefix))
+            .map(|_| RedirectUriMatch::Wildcard);
+    }
+    if target.contains(['\\', '*']) || target.contains(char::is_whitespace) {
+        return None;
+    }
+
+    let (r_scheme, r_host, r_port, r_rest) = split_uri(rule)?;
+    let (t_scheme, t_host, t_port, t_rest) = split_uri(target)?;
+    if !r_scheme.eq_ignore_ascii_case(t_scheme) {
+        return None;
+    }
+
+    let is_host_wildcard = r_host.starts_with("*.");
+    let host_matches = if let Some(suffix) = r_host.strip_prefix("*.") {
+        let t_host = t_host.to_ascii_lowercase();
+        t_host
+            .strip_suffix(&suffix.to_ascii_lowercase())
+            .and_then(|sub| sub.strip_suffix('.'))
+            .is_some_and(|label| {
+                !label.is_empty()
+                    && label
+                        .bytes()
+                        .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+            })
+    } else {
+        r_host.eq_ignore_ascii_case(t_host)
+    };
+    if !host_matches {
+        return None;
+    }
+
+    let is_path_wildcard = r_rest.ends_with('*');
+    let rest_matches = match r_rest.strip_suffix('*') {
+        Some(prefix) => t_rest.starts_with(prefix),
+        None => r_rest == t_rest,
+    };
+    if !rest_matches {
+        return None;
+    }
+
+    if r_port == t_port {
+        if is_host_wildcard || is_path_wildcard {
+            Some(RedirectUriMatch::Wildcard)
+        } else {
+            Some(RedirectUriMatch::Exact)
+        }
+    } else if !is_host_wildcard
+        && !is_path_wildcard
+        && r_scheme.eq_ignore_ascii_case("http")
+        && matches!(r_host, "127.0.0.1" | "[::1]" | "localhost")
+    {
+        Some(RedirectUriMatch::Loopback)
+    } else {
+        None
+    }
+}
+
+/// In `strict` mode, a `*` is only allowed at the very end, or as the first label of the host
+/// with at least 2 labels following it, which means something like `https://*.com/` is rejected.
+fn is_valid_uri_rule(mode: UriMatchMode, rule: &str) -> bool {
+    if mode == UriMatchMode::Prefix || !rule.contains('*') {
+        return true;
+    }
+
+    let Some((_, host, _, rest)) = split_uri(rule) else {
+        return false;
+    };
+    let host_ok = match host.strip_prefix("*.") {
+        Some(suffix) => {
+            !suffix.contains('*') && suffix.split('.').filter(|l| !l.is_empty()).count() >= 2
+        }
+        None => !host.contains('*'),
+    };
+    let rest_ok = rest.find('*').is_none_or(|i| i == rest.len() - 1);
+
+    host_ok && rest_ok
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1790,6 +2508,32 @@ mod tests {
     use actix_web::test::TestRequest;
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn test_challenges_allowed() {
+        // public clients always fall back to S256
+        let mut client = Client {
+            confidential: false,
+            challenge: None,
+            ..Default::default()
+        };
+        assert_eq!(client.challenges_allowed(), Some("S256"));
+        assert!(client.validate_challenge_method("S256").is_ok());
+        assert!(client.validate_challenge_method("plain").is_err());
+        assert!(client.validate_code_challenge(&None, &None).is_err());
+        assert!(
+            client
+                .validate_code_challenge(&Some("challenge".to_string()), &Some("S256".to_string()))
+                .is_ok()
+        );
+
+        client.confidential = true;
+        assert_eq!(client.challenges_allowed(), None);
+        assert!(client.validate_code_challenge(&None, &None).is_ok());
+
+        client.challenge = Some("S256,plain".to_string());
+        assert!(client.validate_challenge_method("plain").is_ok());
+    }
+
     #[test]
     fn test_client_impl() {
         let mut client = Client {
@@ -1799,6 +2543,7 @@ mod tests {
             confidential: false,
             secret: None,
             secret_kid: None,
+            secret_hashed: false,
             redirect_uris: "".to_string(),
             post_logout_redirect_uris: None,
             allowed_origins: Some(
@@ -1817,6 +2562,15 @@ mod tests {
             contacts: Some("batman@localhost.de,@alfred:matrix.org".to_string()),
             backchannel_logout_uri: None,
             restrict_group_prefix: None,
+            introspect: false,
+            claim_mapping: None,
+            claim_namespace: None,
+            refresh_token_max_lifetime: None,
+            require_nonce: false,
+            first_party: false,
+            subject_type: SubjectType::Public,
+            sector_identifier_uri: None,
+            uri_match_mode: UriMatchMode::Strict,
         };
 
         assert_eq!(client.get_access_token_alg().unwrap(), JwkKeyPairAlg::EdDSA);
@@ -1898,6 +2652,8 @@ mod tests {
         assert_eq!(client.validate_flow("password"), Ok(()));
         assert!(client.validate_flow("blabla").is_err());
         assert!(client.validate_flow("").is_err());
+        // must not match as a substring of `authorization_code`
+        assert!(client.validate_flow("code").is_err());
 
         // contacts
         assert_eq!(
@@ -2064,4 +2820,251 @@ mod tests {
         assert_eq!(&client.scopes, "openid");
         assert_eq!(&client.default_scopes, "openid");
     }
+
+    #[test]
+    fn test_validate_flow_unauthorized_client() {
+        let client = Client {
+            flows_enabled: "authorization_code,refresh_token".to_string(),
+            ..Default::default()
+        };
+
+        let err = client.validate_flow("password").unwrap_err();
+        assert_eq!(err.error, ErrorResponseType::UnauthorizedClient);
+        assert_eq!(
+            err.message,
+            "'password' flow is not allowed for this client, allowed: authorization_code, \
+            refresh_token"
+        );
+
+        assert_eq!(client.validate_response_type("code"), Ok(()));
+        let err = client.validate_response_type("token").unwrap_err();
+        assert_eq!(err.error, ErrorResponseType::UnauthorizedClient);
+        assert_eq!(
+            err.message,
+            "'token' response_type is not allowed for this client, allowed: code"
+        );
+
+        let client = Client {
+            flows_enabled: "client_credentials".to_string(),
+            ..Default::default()
+        };
+        let err = client.validate_response_type("code").unwrap_err();
+        assert_eq!(err.error, ErrorResponseType::UnauthorizedClient);
+        assert_eq!(
+            err.message,
+            "'code' response_type is not allowed for this client, allowed: none"
+        );
+    }
+
+    #[test]
+    fn test_validate_flows_combination() {
+        let mut client = Client {
+            confidential: true,
+            redirect_uris: "http://localhost:3000/oidc/callback".to_string(),
+            flows_enabled: "authorization_code,client_credentials,refresh_token".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(client.validate_flows_combination(), Ok(()));
+
+        // `code` response_type needs a redirect_uri
+        client.redirect_uris = String::default();
+        assert!(client.validate_flows_combination().is_err());
+        client.flows_enabled = "client_credentials".to_string();
+        assert_eq!(client.validate_flows_combination(), Ok(()));
+
+        // client_credentials for confidential clients only
+        client.confidential = false;
+        assert!(client.validate_flows_combination().is_err());
+
+        // refresh_token without any flow issuing one
+        client.confidential = true;
+        client.flows_enabled = "client_credentials,refresh_token".to_string();
+        assert!(client.validate_flows_combination().is_err());
+        client.flows_enabled = format!("refresh_token,{GRANT_TYPE_DEVICE_CODE}");
+        assert_eq!(client.validate_flows_combination(), Ok(()));
+    }
+
+    #[test]
+    fn test_match_redirect_uri() {
+        let client = Client {
+            redirect_uris: "http://localhost:3000/callback,https://app.example.com/*".to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            client.match_redirect_uri("http://localhost:3000/callback"),
+            Some(RedirectUriMatch::Exact)
+        );
+        assert_eq!(
+            client.match_redirect_uri("https://app.example.com/oidc/callback"),
+            Some(RedirectUriMatch::Wildcard)
+        );
+        assert_eq!(
+            client.match_redirect_uri("https://app.example.com/*"),
+            Some(RedirectUriMatch::Exact)
+        );
+        assert_eq!(client.match_redirect_uri("http://localhost:3000/"), None);
+        assert_eq!(client.match_redirect_uri("https://evil.example.com/"), None);
+        assert!(
+            client
+                .validate_redirect_uri("http://localhost:3000/callback/other")
+                .is_err()
+        );
+
+        // legacy rules are only valid in `prefix` mode, which is used for all existing clients
+        let mut client = Client {
+            redirect_uris: "https://app.example.com*".to_string(),
+            post_logout_redirect_uris: Some("*".to_string()),
+            uri_match_mode: UriMatchMode::Prefix,
+            ..Default::default()
+        };
+        assert_eq!(client.validate_uri_rules(), Ok(()));
+        assert_eq!(
+            client.match_redirect_uri("https://app.example.com/callback"),
+            Some(RedirectUriMatch::Wildcard)
+        );
+        assert_eq!(
+            client.match_post_logout_redirect_uri("https://app.example.com/"),
+            Some(RedirectUriMatch::Wildcard)
+        );
+        client.uri_match_mode = UriMatchMode::Strict;
+        assert!(client.validate_uri_rules().is_err());
+        assert_eq!(
+            client.match_redirect_uri("https://app.example.com/callback"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_validate_secret_policy() {
+        // too short
+        assert!(Client::validate_secret_policy("0AhSwTN3FafiAxS5pvcSaPd", 32, 96).is_err());
+        // long enough, but weak
+        assert!(Client::validate_secret_policy(&"a".repeat(64), 32, 96).is_err());
+        assert!(Client::validate_secret_policy(&"secret12".repeat(8), 32, 96).is_err());
+        // hex encoded 128 bit
+        assert!(Client::validate_secret_policy("7f3a9c2e51d84b06af1e93c7d250b8e4", 32, 96).is_ok());
+
+        // generated secrets must always match the default policy
+        for _ in 0..100 {
+            let secret = utils::secure_random_alnum(SECRET_LEN_CLIENTS);
+            assert_eq!(Client::validate_secret_policy(&secret, 32, 96), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_match_uri() {
+        let strict = |rule, target| match_uri(UriMatchMode::Strict, rule, target);
+        let valid = |rule| is_valid_uri_rule(UriMatchMode::Strict, rule);
+
+        let rule = "https://*.staging.example.com/logout";
+        assert!(valid(rule));
+        assert_eq!(
+            strict(rule, "https://pr-12.staging.example.com/logout"),
+            Some(RedirectUriMatch::Wildcard)
+        );
+        // exactly one subdomain level, the same path and no other origin
+        assert!(strict(rule, "https://a.b.staging.example.com/logout").is_none());
+        assert!(strict(rule, "https://staging.example.com/logout").is_none());
+        assert!(strict(rule, "https://pr-12.staging.example.com/other").is_none());
+        assert!(strict(rule, "https://pr-12.staging.example.com.evil.net/logout").is_none());
+        assert!(strict(rule, "http://pr-12.staging.example.com/logout").is_none());
+
+        let rule = "https://app.example.com/logout/*";
+        assert_eq!(
+            strict(rule, "https://app.example.com/logout/done?a=b"),
+            Some(RedirectUriMatch::Wildcard)
+        );
+        assert!(strict(rule, "https://app.example.com/other").is_none());
+        assert!(strict(rule, "https://app.example.com:8443/logout/").is_none());
+
+        // a trailing wildcard must never be able to leave the origin
+        let rule = "https://app.example.com*";
+        assert!(!valid(rule));
+        assert!(strict(rule, "https://app.example.com.evil.net/").is_none());
+        assert!(strict(rule, "https://app.example.com@evil.net/").is_none());
+        let rule = "https://app.example.com/*";
+        assert!(strict(rule, "https://app.example.com@evil.net/").is_none());
+        assert!(strict(rule, "https://app.example.com\\@evil.net/").is_none());
+
+        let rule = "http://127.0.0.1/logout";
+        assert_eq!(
+            strict(rule, "http://127.0.0.1:53421/logout"),
+            Some(RedirectUriMatch::Loopback)
+        );
+        assert!(strict(rule, "http://127.0.0.1:53421/other").is_none());
+        assert!(strict(rule, "http://127.0.0.2:53421/logout").is_none());
+        assert!(strict("https://app.example.com/", "https://app.example.com:8443/").is_none());
+
+        assert!(!valid("https://*.com/"));
+        assert!(!valid("https://*/"));
+        assert!(!valid("https://app.*.example.com/"));
+        assert!(!valid("https://app.example.com/*/logout"));
+        assert!(valid("https://*.example.com/*"));
+
+        // legacy rules keep working in `prefix` mode
+        let prefix = |rule, target| match_uri(UriMatchMode::Prefix, rule, target);
+        for rule in ["https://app.example.com*", "*"] {
+            assert!(is_valid_uri_rule(UriMatchMode::Prefix, rule));
+            assert_eq!(
+                prefix(rule, "https://app.example.com/logout"),
+                Some(RedirectUriMatch::Wildcard)
+            );
+        }
+        assert_eq!(
+            prefix("https://app.example.com/", "https://app.example.com/"),
+            Some(RedirectUriMatch::Exact)
+        );
+        assert!(prefix("https://app.example.com/", "https://app.example.com/a").is_none());
+        assert!(prefix("http://127.0.0.1/logout", "http://127.0.0.1:53421/logout").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_pairwise_subject() {
+        let secret = b"pairwise_test_secret";
+        let user_id = "za9UxpH7XVxqrtpEbThoqvn2";
+        let mut client_a = Client {
+            id: "client_a".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(client_a.subject(user_id), user_id);
+
+        client_a.subject_type = SubjectType::Pairwise;
+        let sub_a = client_a.pairwise_subject(secret, user_id);
+        assert_ne!(sub_a, user_id);
+        // stable for the same client
+        assert_eq!(client_a.pairwise_subject(secret, user_id), sub_a);
+        // different users must never collide
+        assert_ne!(
+            client_a.pairwise_subject(secret, "Bn3pRqRfzE5TJ2ldPEoR4yWu"),
+            sub_a
+        );
+        // cannot be computed without the server secret
+        assert_ne!(client_a.pairwise_subject(b"another_secret", user_id), sub_a);
+
+        // without a `sector_identifier_uri`, each client is its own sector
+        let mut client_b = Client {
+            id: "client_b".to_string(),
+            subject_type: SubjectType::Pairwise,
+            ..Default::default()
+        };
+        assert_ne!(client_b.pairwise_subject(secret, user_id), sub_a);
+
+        // clients with the same sector host share the `sub`
+        client_a.sector_identifier_uri = Some("https://example.com/sector_a.json".to_string());
+        client_b.sector_identifier_uri = Some("https://example.com/sector_b.json".to_string());
+        assert_eq!(
+            client_a.pairwise_subject(secret, user_id),
+            client_b.pairwise_subject(secret, user_id)
+        );
+
+        client_b.sector_identifier_uri = Some("https://other.example.com/sector.json".to_string());
+        assert_ne!(
+            client_a.pairwise_subject(secret, user_id),
+            client_b.pairwise_subject(secret, user_id)
+        );
+
+        client_b.sector_identifier_uri = Some("not a uri".to_string());
+        assert!(client_b.validate_sector_identifier_uri().await.is_err());
+    }
 }
@@ -1,44 +1,68 @@
 use actix_web::http::header::{HeaderName, HeaderValue};
+use cryptr::EncValue;
+use rauthy_common::utils::{
+    base64_url_no_pad_decode, base64_url_no_pad_encode, deserialize, serialize,
+};
 use rauthy_error::{ErrorResponse, ErrorResponseType};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use std::fmt::{Display, Formatter};
+use std::fmt::Debug;
 use std::str::FromStr;
 
+/// Opaque keyset pagination cursor, that contains the sort key `T` of the last row from the
+/// previous page.
+///
+/// The sort key is encrypted before it is handed out, which makes the cursor tamper-evident.
+/// Clients cannot create or modify cursors to probe arbitrary rows, they can only pass back
+/// the exact values they received.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cursor<T>(pub T);
+
+impl<T> Cursor<T>
+where
+    T: Debug + Serialize + DeserializeOwned,
+{
+    pub fn encode(&self) -> Result<String, ErrorResponse> {
+        let bytes = serialize(&self.0)?;
+        let enc = EncValue::encrypt(&bytes)?;
+        Ok(base64_url_no_pad_encode(enc.into_bytes().as_ref()))
+    }
+
+    pub fn decode(value: &str) -> Result<Self, ErrorResponse> {
+        let err = || ErrorResponse::new(ErrorResponseType::BadRequest, "Invalid pagination cursor");
+
+        let bytes = base64_url_no_pad_decode(value).map_err(|_| err())?;
+        let dec = EncValue::try_from_bytes(bytes)
+            .and_then(|enc| enc.decrypt())
+            .map_err(|_| err())?;
+        let key = deserialize::<T>(dec.as_ref()).map_err(|_| err())?;
+
+        Ok(Self(key))
+    }
+
+    pub fn into_header_pair(self) -> Result<(HeaderName, HeaderValue), ErrorResponse> {
+        // base64 values will always be valid
+        let name = HeaderName::from_str("x-next-cursor").unwrap();
+        let value = HeaderValue::from_str(&self.encode()?).unwrap();
+        Ok((name, value))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContinuationToken {
     pub id: String,
     pub ts: i64,
 }
 
-impl Display for ContinuationToken {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}{}", self.ts, self.id)
-    }
-}
-
 impl TryFrom<&str> for ContinuationToken {
     type Error = ErrorResponse;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        if value.len() < 16 {
-            return Err(ErrorResponse::new(
-                ErrorResponseType::BadRequest,
-                "Invalid continuation_token",
-            ));
-        }
-
-        let (ts, id) = value.split_at(10);
-        let ts = i64::from_str(ts).map_err(|err| {
-            ErrorResponse::new(
-                ErrorResponseType::BadRequest,
-                format!("timestamp str cannot be parsed into i64: {err:?}"),
-            )
-        })?;
-
-        Ok(Self {
-            id: id.to_string(),
-            ts,
-        })
+        Cursor::<Self>::decode(value)
+            .map(|cursor| cursor.0)
+            .map_err(|_| {
+                ErrorResponse::new(ErrorResponseType::BadRequest, "Invalid continuation_token")
+            })
     }
 }
 
@@ -47,10 +71,10 @@ impl ContinuationToken {
         Self { id, ts }
     }
 
-    pub fn into_header_pair(self) -> (HeaderName, HeaderValue) {
-        // these header values will always be valid
+    pub fn into_header_pair(self) -> Result<(HeaderName, HeaderValue), ErrorResponse> {
+        // base64 values will always be valid
         let name = HeaderName::from_str("x-continuation-token").unwrap();
-        let value = HeaderValue::from_str(&self.to_string()).unwrap();
-        (name, value)
+        let value = HeaderValue::from_str(&Cursor(self).encode()?).unwrap();
+        Ok((name, value))
     }
 }
@@ -12,6 +12,8 @@ pub struct FailedBackchannelLogout {
     pub sub: String,
     pub sid: String,
     pub retry_count: i32,
+    /// The `iss` the logged out tokens have been issued under. `None` for the default issuer.
+    pub issuer: Option<String>,
 }
 
 impl From<tokio_postgres::Row> for FailedBackchannelLogout {
@@ -21,6 +23,7 @@ impl From<tokio_postgres::Row> for FailedBackchannelLogout {
             sub: row.get("sub"),
             sid: row.get("sid"),
             retry_count: row.get("retry_count"),
+            issuer: row.get("issuer"),
         }
     }
 }
@@ -30,20 +33,23 @@ impl FailedBackchannelLogout {
         client_id: String,
         sub: Option<String>,
         sid: Option<String>,
+        issuer: Option<String>,
     ) -> Result<(), ErrorResponse> {
         let sub = sub.unwrap_or_default();
         let sid = sid.unwrap_or_default();
 
         let sql = r#"
-INSERT INTO failed_backchannel_logouts (client_id, sub, sid, retry_count)
-VALUES ($1, $2, $3, 0)
+INSERT INTO failed_backchannel_logouts (client_id, sub, sid, retry_count, issuer)
+VALUES ($1, $2, $3, 0, $4)
 ON CONFLICT (client_id, sub, sid)
-DO UPDATE SET retry_count = retry_count + 1"#;
+DO UPDATE SET retry_count = retry_count + 1, issuer = excluded.issuer"#;
 
         if is_hiqlite() {
-            DB::hql().execute(sql, params!(client_id, sub, sid)).await?;
+            DB::hql()
+                .execute(sql, params!(client_id, sub, sid, issuer))
+                .await?;
         } else {
-            DB::pg_execute(sql, &[&client_id, &sub, &sid]).await?;
+            DB::pg_execute(sql, &[&client_id, &sub, &sid, &issuer]).await?;
         }
 
         Ok(())
@@ -12,6 +12,19 @@ impl FailedLoginCounter {
         Ok(DB::hql().counter_add(Cache::IpBlacklist, ip, 1).await?)
     }
 
+    /// Returns the duration in seconds for an IP blacklisting, if the amount of failed logins has
+    /// just reached one of the thresholds. With 25 and more, each new failure blacklists again.
+    pub fn blacklist_secs(failed_logins: u32) -> Option<u32> {
+        match failed_logins {
+            t if t >= 25 => Some(86400),
+            20 => Some(3600),
+            15 => Some(900),
+            10 => Some(600),
+            7 => Some(60),
+            _ => None,
+        }
+    }
+
     pub async fn reset(ip: String) -> Result<(), ErrorResponse> {
         DB::hql().counter_del(Cache::IpBlacklist, ip).await?;
         Ok(())
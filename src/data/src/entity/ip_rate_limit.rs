@@ -1,9 +1,46 @@
 use crate::database::{Cache, DB};
+use crate::events::event::Event;
 use crate::rauthy_config::RauthyConfig;
 use chrono::{DateTime, Utc};
-use rauthy_error::ErrorResponse;
+use prometheus::{IntCounter, IntCounterVec, IntGaugeVec, Opts, Registry};
+use rauthy_error::{ErrorResponse, ErrorResponseType};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::IpAddr;
 use std::ops::Add;
+use std::sync::{LazyLock, Mutex};
+use tracing::warn;
+
+static TOKEN_RATE_LIMIT: LazyLock<Mutex<TokenRateLimit>> =
+    LazyLock::new(|| Mutex::new(TokenRateLimit::default()));
+
+static METRIC_TOKEN_LIMITED: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "rauthy_token_rate_limited_total",
+            "Requests on /oidc/token rejected by the rate limiter",
+        ),
+        &["key"],
+    )
+    .unwrap()
+});
+static METRIC_TOKEN_TRACKED: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    IntGaugeVec::new(
+        Opts::new(
+            "rauthy_token_rate_limit_tracked",
+            "Clients and IPs tracked by the /oidc/token rate limiter in the current window",
+        ),
+        &["key"],
+    )
+    .unwrap()
+});
+static METRIC_TOKEN_BODY_TOO_LARGE: LazyLock<IntCounter> = LazyLock::new(|| {
+    IntCounter::new(
+        "rauthy_token_body_too_large_total",
+        "Requests on /oidc/token rejected because of their body size",
+    )
+    .unwrap()
+});
 
 /// Caution: The `exp` on this struct does not define the timeout. It is only used
 /// to return information back to the limited client when it is allowed to poll again.
@@ -34,3 +71,391 @@ impl DeviceIpRateLimit {
         Ok(dt)
     }
 }
+
+/// Rate limiter for the open user registration. It keeps the timestamps of all successful
+/// registrations per IP inside the configured `rate_limit_window`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RegistrationIpRateLimit {
+    pub registrations: Vec<i64>,
+    /// The `retry_at` the last event has been sent for, to only send it once per blocked period
+    #[serde(default)]
+    pub notified_retry_at: Option<i64>,
+}
+
+impl RegistrationIpRateLimit {
+    #[inline]
+    fn cache_idx(ip: &IpAddr) -> String {
+        format!("reg_{ip}")
+    }
+
+    /// Returns an `Err(TooManyRequests)` if the given IP is currently limited, containing the
+    /// timestamp at which the next registration will be allowed again.
+    pub async fn check(ip: IpAddr) -> Result<(), ErrorResponse> {
+        let cfg = &RauthyConfig::get().vars.user_registration;
+        if cfg.rate_limit_max == 0 && cfg.rate_limit_min_interval == 0 {
+            return Ok(());
+        }
+
+        let slf: Option<Self> = DB::hql()
+            .get(Cache::IpRateLimit, Self::cache_idx(&ip))
+            .await?;
+        let Some(mut slf) = slf else {
+            return Ok(());
+        };
+
+        let now = Utc::now().timestamp();
+        if let Some(retry_at) = slf.retry_at(now) {
+            // A blocked client may keep on sending requests, which must not flood the events.
+            if slf.needs_notify(retry_at) {
+                warn!(%ip, "Open user registration rate limit exceeded");
+                Event::registration_rate_limit(ip, retry_at).send().await?;
+                DB::hql()
+                    .put(
+                        Cache::IpRateLimit,
+                        Self::cache_idx(&ip),
+                        &slf,
+                        Some(Self::window()),
+                    )
+                    .await?;
+            }
+
+            return Err(ErrorResponse::new(
+                ErrorResponseType::TooManyRequests(retry_at),
+                format!("Too many registrations, you may try again at: {retry_at}"),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Saves a successful registration for the given IP.
+    pub async fn insert(ip: IpAddr) -> Result<(), ErrorResponse> {
+        let cfg = &RauthyConfig::get().vars.user_registration;
+        if cfg.rate_limit_max == 0 && cfg.rate_limit_min_interval == 0 {
+            return Ok(());
+        }
+
+        let idx = Self::cache_idx(&ip);
+        let slf: Option<Self> = DB::hql().get(Cache::IpRateLimit, idx.clone()).await?;
+        let mut slf = slf.unwrap_or_default();
+
+        let now = Utc::now().timestamp();
+        let window = Self::window();
+        slf.registrations.retain(|ts| *ts > now - window);
+        slf.registrations.push(now);
+
+        DB::hql()
+            .put(Cache::IpRateLimit, idx, &slf, Some(window))
+            .await?;
+
+        Ok(())
+    }
+
+    #[inline]
+    fn window() -> i64 {
+        let cfg = &RauthyConfig::get().vars.user_registration;
+        cfg.rate_limit_window
+            .max(cfg.rate_limit_min_interval as u32) as i64
+    }
+
+    /// Returns `true` only for the first rejected request of a blocked period.
+    fn needs_notify(&mut self, retry_at: i64) -> bool {
+        if self.notified_retry_at == Some(retry_at) {
+            false
+        } else {
+            self.notified_retry_at = Some(retry_at);
+            true
+        }
+    }
+
+    fn retry_at(&self, now: i64) -> Option<i64> {
+        let cfg = &RauthyConfig::get().vars.user_registration;
+        let window = cfg.rate_limit_window as i64;
+        let min_interval = cfg.rate_limit_min_interval as i64;
+
+        let mut recent = self
+            .registrations
+            .iter()
+            .filter(|ts| **ts > now - window)
+            .copied()
+            .collect::<Vec<_>>();
+        recent.sort_unstable();
+
+        if cfg.rate_limit_max > 0 && recent.len() >= cfg.rate_limit_max as usize {
+            // the oldest entry that needs to expire until we are below the limit again
+            let idx = recent.len() - cfg.rate_limit_max as usize;
+            return Some(recent[idx] + window);
+        }
+
+        if min_interval > 0
+            && let Some(last) = self.registrations.iter().max()
+            && *last > now - min_interval
+        {
+            return Some(last + min_interval);
+        }
+
+        None
+    }
+}
+
+/// Rate limiter for `GET /oidc/redirect_uri`, which counts the requests per IP inside a fixed
+/// window of 60 seconds, to prevent the enumeration of registered `redirect_uri`s.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RedirectUriIpRateLimit {
+    pub window_start: i64,
+    pub count: u16,
+}
+
+impl RedirectUriIpRateLimit {
+    #[inline]
+    fn cache_idx(ip: &IpAddr) -> String {
+        format!("redirect_uri_{ip}")
+    }
+
+    /// Counts the request for the given IP and returns an `Err(TooManyRequests)`, if the
+    /// `redirect_uri_validation_rate_limit` has been exceeded.
+    pub async fn check_insert(ip: IpAddr) -> Result<(), ErrorResponse> {
+        let limit = RauthyConfig::get()
+            .vars
+            .access
+            .redirect_uri_validation_rate_limit;
+        let idx = Self::cache_idx(&ip);
+        let now = Utc::now().timestamp();
+
+        let slf: Option<Self> = DB::hql().get(Cache::IpRateLimit, idx.clone()).await?;
+        let mut slf = match slf {
+            Some(slf) if slf.window_start > now - 60 => slf,
+            _ => Self {
+                window_start: now,
+                count: 0,
+            },
+        };
+
+        let retry_at = slf.window_start + 60;
+        if slf.count >= limit {
+            return Err(ErrorResponse::new(
+                ErrorResponseType::TooManyRequests(retry_at),
+                format!("You hit a rate limit. You may try again at: {retry_at}"),
+            ));
+        }
+
+        slf.count += 1;
+        DB::hql()
+            .put(Cache::IpRateLimit, idx, &slf, Some(retry_at - now))
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Rate limiter for `POST /oidc/token`, which counts the requests per IP and per `client_id`
+/// inside a fixed `token_rate_limit_window`.
+///
+/// In contrast to the other limiters, the state is kept in memory, because the token endpoint is
+/// way too hot to do a cache write on each request. This means the limits apply per node.
+#[derive(Debug, Default)]
+pub struct TokenRateLimit {
+    window_start: i64,
+    clients: HashMap<String, u16>,
+    ips: HashMap<IpAddr, u16>,
+}
+
+impl TokenRateLimit {
+    /// Registers the limiter metrics with the given Prometheus registry.
+    pub fn register_metrics(registry: &Registry) -> Result<(), prometheus::Error> {
+        registry.register(Box::new(METRIC_TOKEN_LIMITED.clone()))?;
+        registry.register(Box::new(METRIC_TOKEN_TRACKED.clone()))?;
+        registry.register(Box::new(METRIC_TOKEN_BODY_TOO_LARGE.clone()))?;
+        Ok(())
+    }
+
+    /// Counts the request for the given IP and returns an `Err(TooManyRequests)`, if it has
+    /// exceeded its limit. Every request counts the same, no matter if it succeeds afterward or
+    /// not.
+    pub fn check_insert_ip(ip: IpAddr) -> Result<(), ErrorResponse> {
+        let cfg = &RauthyConfig::get().vars.access;
+        if cfg.token_rate_limit_ip == 0 {
+            return Ok(());
+        }
+
+        let res = TOKEN_RATE_LIMIT.lock().unwrap().hit_ip(
+            Utc::now().timestamp(),
+            cfg.token_rate_limit_window,
+            ip,
+            cfg.token_rate_limit_ip,
+        );
+        Self::map_limited(res, ip, None)
+    }
+
+    /// Returns an `Err(TooManyRequests)`, if the given `client_id` has already exceeded its
+    /// limit. This does not count the request, because the `client_id` is not authenticated
+    /// at this point. Anyone could send it, and it must not be possible to lock out a client
+    /// with invalid requests. The counting happens in [TokenRateLimit::insert_client].
+    pub fn check_client(ip: IpAddr, client_id: &str) -> Result<(), ErrorResponse> {
+        let cfg = &RauthyConfig::get().vars.access;
+        if cfg.token_rate_limit_client == 0 {
+            return Ok(());
+        }
+
+        let res = TOKEN_RATE_LIMIT.lock().unwrap().client_limited(
+            Utc::now().timestamp(),
+            cfg.token_rate_limit_window,
+            client_id,
+            cfg.token_rate_limit_client,
+        );
+        Self::map_limited(res, ip, Some(client_id))
+    }
+
+    /// Counts a request for the given `client_id`. Must only be called after a successful
+    /// token request, when the client has been authenticated.
+    pub fn insert_client(client_id: &str) {
+        let cfg = &RauthyConfig::get().vars.access;
+        if cfg.token_rate_limit_client == 0 {
+            return;
+        }
+
+        TOKEN_RATE_LIMIT.lock().unwrap().count_client(
+            Utc::now().timestamp(),
+            cfg.token_rate_limit_window,
+            client_id,
+        );
+    }
+
+    /// Must be called for each request on `/oidc/token` rejected because of its body size.
+    pub fn count_body_too_large() {
+        METRIC_TOKEN_BODY_TOO_LARGE.inc();
+    }
+
+    fn map_limited(
+        res: Result<(), (&'static str, i64)>,
+        ip: IpAddr,
+        client_id: Option<&str>,
+    ) -> Result<(), ErrorResponse> {
+        if let Err((key, retry_at)) = res {
+            warn!(%ip, ?client_id, "Token endpoint rate limit exceeded for {key}");
+            METRIC_TOKEN_LIMITED.with_label_values(&[key]).inc();
+
+            return Err(ErrorResponse::new(
+                ErrorResponseType::TooManyRequests(retry_at),
+                format!("You hit a rate limit. You may try again at: {retry_at}"),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Resets all counters if `now` is inside a new window and returns the `retry_at` timestamp
+    /// for the current one.
+    fn rotate(&mut self, now: i64, window: u16) -> i64 {
+        let window = window.max(1) as i64;
+        let window_start = now - now % window;
+        if window_start != self.window_start {
+            self.window_start = window_start;
+            self.clients.clear();
+            self.ips.clear();
+        }
+        window_start + window
+    }
+
+    /// Returns the limited key and the `retry_at` timestamp on error.
+    fn hit_ip(
+        &mut self,
+        now: i64,
+        window: u16,
+        ip: IpAddr,
+        limit: u16,
+    ) -> Result<(), (&'static str, i64)> {
+        let retry_at = self.rotate(now, window);
+
+        let count = self.ips.entry(ip).or_default();
+        if *count >= limit {
+            return Err(("ip", retry_at));
+        }
+        *count += 1;
+
+        METRIC_TOKEN_TRACKED
+            .with_label_values(&["ip"])
+            .set(self.ips.len() as i64);
+        Ok(())
+    }
+
+    fn client_limited(
+        &mut self,
+        now: i64,
+        window: u16,
+        client_id: &str,
+        limit: u16,
+    ) -> Result<(), (&'static str, i64)> {
+        let retry_at = self.rotate(now, window);
+
+        if self.clients.get(client_id).copied().unwrap_or_default() >= limit {
+            return Err(("client", retry_at));
+        }
+        Ok(())
+    }
+
+    fn count_client(&mut self, now: i64, window: u16, client_id: &str) {
+        self.rotate(now, window);
+
+        match self.clients.get_mut(client_id) {
+            Some(count) => *count = count.saturating_add(1),
+            None => {
+                self.clients.insert(client_id.to_string(), 1);
+            }
+        }
+
+        METRIC_TOKEN_TRACKED
+            .with_label_values(&["client"])
+            .set(self.clients.len() as i64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_registration_limit_notify_once() {
+        let mut limit = RegistrationIpRateLimit::default();
+        assert!(limit.needs_notify(1_700_000_060));
+        assert!(!limit.needs_notify(1_700_000_060));
+        assert!(!limit.needs_notify(1_700_000_060));
+        // a new blocked period will be notified again
+        assert!(limit.needs_notify(1_700_000_120));
+    }
+
+    #[test]
+    fn test_token_rate_limit() {
+        let mut limit = TokenRateLimit::default();
+        let ip = IpAddr::from_str("192.168.1.1").unwrap();
+        let ip_other = IpAddr::from_str("192.168.1.2").unwrap();
+        let now = 1_700_000_010;
+
+        // per IP
+        for _ in 0..3 {
+            assert!(limit.hit_ip(now, 60, ip, 3).is_ok());
+        }
+        assert_eq!(limit.hit_ip(now, 60, ip, 3), Err(("ip", 1_700_000_040)));
+        assert!(limit.hit_ip(now, 60, ip_other, 3).is_ok());
+
+        // a new window resets all counters
+        assert!(limit.hit_ip(now + 30, 60, ip, 3).is_ok());
+
+        // per client, only counted by the explicit insert after authentication
+        for _ in 0..10 {
+            assert!(limit.client_limited(now + 30, 60, "client", 2).is_ok());
+        }
+        limit.count_client(now + 30, 60, "client");
+        assert!(limit.client_limited(now + 30, 60, "client", 2).is_ok());
+        limit.count_client(now + 30, 60, "client");
+        assert_eq!(
+            limit.client_limited(now + 30, 60, "client", 2),
+            Err(("client", 1_700_000_100))
+        );
+        assert!(limit.client_limited(now + 30, 60, "other", 2).is_ok());
+
+        // the next window allows the client again
+        assert!(limit.client_limited(now + 90, 60, "client", 2).is_ok());
+        assert!(limit.clients.is_empty());
+    }
+}
@@ -1,4 +1,6 @@
 use crate::database::DB;
+use crate::entity::sessions::Session;
+use crate::rauthy_config::RauthyConfig;
 use chrono::Utc;
 use cryptr::utils::secure_random_alnum;
 use hiqlite_macros::params;
@@ -10,6 +12,7 @@ use tracing::debug;
 #[derive(Debug, Clone)]
 pub struct IssuedToken {
     pub jti: String,
+    pub client_id: Option<String>,
     pub user_id: Option<String>,
     pub did: Option<String>,
     pub sid: Option<String>,
@@ -21,6 +24,7 @@ impl From<hiqlite::Row<'_>> for IssuedToken {
     fn from(mut row: hiqlite::Row<'_>) -> Self {
         Self {
             jti: row.get("jti"),
+            client_id: row.get("client_id"),
             user_id: row.get("user_id"),
             did: row.get("did"),
             sid: row.get("sid"),
@@ -34,6 +38,7 @@ impl From<tokio_postgres::Row> for IssuedToken {
     fn from(row: tokio_postgres::Row) -> Self {
         Self {
             jti: row.get("jti"),
+            client_id: row.get("client_id"),
             user_id: row.get("user_id"),
             did: row.get("did"),
             sid: row.get("sid"),
@@ -62,6 +67,7 @@ impl IssuedToken {
     }
 
     pub async fn create(
+        client_id: &str,
         user_id: Option<&str>,
         did: Option<&str>,
         sid: Option<String>,
@@ -72,6 +78,7 @@ impl IssuedToken {
         loop {
             let slf = Self {
                 jti: secure_random_alnum(12),
+                client_id: Some(client_id.to_string()),
                 user_id: user_id.map(String::from),
                 did: did.map(String::from),
                 sid: sid.clone(),
@@ -86,23 +93,47 @@ impl IssuedToken {
         }
     }
 
+    pub async fn find(jti: &str) -> Result<Self, ErrorResponse> {
+        let sql = "SELECT * FROM issued_tokens WHERE jti = $1";
+        let slf = if is_hiqlite() {
+            DB::hql().query_map_one(sql, params!(jti)).await?
+        } else {
+            DB::pg_query_one(sql, &[&jti]).await?
+        };
+        Ok(slf)
+    }
+
     #[inline]
     async fn insert(&self) -> Result<(), ErrorResponse> {
         let sql = r#"
-INSERT INTO issued_tokens (jti, user_id, did, sid, exp)
-VALUES ($1, $2, $3, $4, $5)
+INSERT INTO issued_tokens (jti, client_id, user_id, did, sid, exp)
+VALUES ($1, $2, $3, $4, $5, $6)
 "#;
         if is_hiqlite() {
             DB::hql()
                 .execute(
                     sql,
-                    params!(&self.jti, &self.user_id, &self.did, &self.sid, self.exp),
+                    params!(
+                        &self.jti,
+                        &self.client_id,
+                        &self.user_id,
+                        &self.did,
+                        &self.sid,
+                        self.exp
+                    ),
                 )
                 .await?;
         } else {
             DB::pg_execute(
                 sql,
-                &[&self.jti, &self.user_id, &self.did, &self.sid, &self.exp],
+                &[
+                    &self.jti,
+                    &self.client_id,
+                    &self.user_id,
+                    &self.did,
+                    &self.sid,
+                    &self.exp,
+                ],
             )
             .await?;
         }
@@ -146,23 +177,49 @@ VALUES ($1, $2, $3, $4, $5)
         Ok(())
     }
 
+    /// Revokes all not yet revoked tokens for the given client and returns the amount of
+    /// revoked tokens.
+    pub async fn revoke_for_client(client_id: &str) -> Result<usize, ErrorResponse> {
+        let sql = r#"
+UPDATE issued_tokens SET revoked = $1
+WHERE client_id = $2 AND revoked IS NOT TRUE AND exp > $3"#;
+        let now = Utc::now().timestamp();
+
+        let rows_affected = if is_hiqlite() {
+            DB::hql()
+                .execute(sql, params!(true, client_id, now))
+                .await?
+        } else {
+            DB::pg_execute(sql, &[&true, &client_id, &now]).await?
+        };
+
+        Ok(rows_affected)
+    }
+
+    /// Revokes all not yet revoked tokens for the given user and returns the amount of
+    /// revoked tokens.
     pub async fn revoke_for_user(
         user_id: &str,
         include_device_tokens: bool,
-    ) -> Result<(), ErrorResponse> {
+    ) -> Result<usize, ErrorResponse> {
         let sql = if include_device_tokens {
-            "UPDATE issued_tokens SET revoked = $1 WHERE user_id = $2"
+            r#"
+UPDATE issued_tokens SET revoked = $1
+WHERE user_id = $2 AND revoked IS NOT TRUE AND exp > $3"#
         } else {
-            "UPDATE issued_tokens SET revoked = $1 WHERE user_id = $2 AND did IS NULL"
+            r#"
+UPDATE issued_tokens SET revoked = $1
+WHERE user_id = $2 AND revoked IS NOT TRUE AND exp > $3 AND did IS NULL"#
         };
+        let now = Utc::now().timestamp();
 
-        if is_hiqlite() {
-            DB::hql().execute(sql, params!(true, user_id)).await?;
+        let rows_affected = if is_hiqlite() {
+            DB::hql().execute(sql, params!(true, user_id, now)).await?
         } else {
-            DB::pg_execute(sql, &[&true, &user_id]).await?;
-        }
+            DB::pg_execute(sql, &[&true, &user_id, &now]).await?
+        };
 
-        Ok(())
+        Ok(rows_affected)
     }
 
     pub async fn revoke_for_session(
@@ -184,6 +241,24 @@ VALUES ($1, $2, $3, $4, $5)
         Ok(())
     }
 
+    /// Revokes all not yet revoked tokens bound to a session, which does not exist anymore or
+    /// has expired, and returns the amount of revoked tokens.
+    pub async fn revoke_for_inactive_sessions() -> Result<usize, ErrorResponse> {
+        let sql = r#"
+UPDATE issued_tokens SET revoked = $1
+WHERE sid IS NOT NULL AND revoked IS NOT TRUE AND exp > $2
+AND sid NOT IN (SELECT id FROM sessions WHERE exp >= $2)"#;
+        let now = Utc::now().timestamp();
+
+        let rows_affected = if is_hiqlite() {
+            DB::hql().execute(sql, params!(true, now)).await?
+        } else {
+            DB::pg_execute(sql, &[&true, &now]).await?
+        };
+
+        Ok(rows_affected)
+    }
+
     #[inline]
     pub async fn validate_not_revoked(jti: &str) -> Result<(), ErrorResponse> {
         let sql = "SELECT * FROM issued_tokens WHERE jti = $1";
@@ -195,14 +270,22 @@ VALUES ($1, $2, $3, $4, $5)
         };
 
         if let Some(slf) = opt {
-            if slf.revoked != Some(true) {
-                Ok(())
-            } else {
-                Err(ErrorResponse::new(
+            if slf.revoked == Some(true) {
+                return Err(ErrorResponse::new(
                     ErrorResponseType::Unauthorized,
                     "token was revoked",
-                ))
+                ));
+            }
+            if let Some(sid) = slf.sid
+                && RauthyConfig::get().vars.access.token_bind_session
+                && !Session::is_active(sid).await?
+            {
+                return Err(ErrorResponse::new(
+                    ErrorResponseType::Unauthorized,
+                    "the session this token is bound to is not active anymore",
+                ));
             }
+            Ok(())
         } else {
             Err(ErrorResponse::new(
                 ErrorResponseType::Unauthorized,
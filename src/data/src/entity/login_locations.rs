@@ -120,6 +120,16 @@ VALUES ($1, $2, $3, $4, $5, $6)"#;
         Ok(slf)
     }
 
+    pub async fn find_for_user(user_id: &str) -> Result<Vec<Self>, ErrorResponse> {
+        let sql = "SELECT * FROM login_locations WHERE user_id = $1 ORDER BY last_seen DESC";
+        let res = if is_hiqlite() {
+            DB::hql().query_map(sql, params!(user_id)).await?
+        } else {
+            DB::pg_query(sql, &[&user_id], 4).await?
+        };
+        Ok(res)
+    }
+
     pub async fn find_by_ip(user_id: String, ip: IpAddr) -> Result<Option<Self>, ErrorResponse> {
         let ip = ip.to_string();
         let sql = "SELECT * FROM login_locations WHERE user_id = $1 AND ip = $2";
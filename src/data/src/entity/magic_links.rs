@@ -16,7 +16,11 @@ use tracing::warn;
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum MagicLinkUsage {
+    /// Confirms an additional address for a user
+    EmailAdd(String),
     EmailChange(String),
+    /// Sent to the old address during an E-Mail change to be able to abort it
+    EmailChangeAbort(String),
     PasswordReset(Option<String>),
     NewUser(Option<String>),
 }
@@ -35,7 +39,9 @@ impl TryFrom<&str> for MagicLinkUsage {
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         let (ty, v) = value.split_once('$').unwrap_or((value, ""));
         let slf = match ty {
+            "email_add" => MagicLinkUsage::EmailAdd(v.to_string()),
             "email_change" => MagicLinkUsage::EmailChange(v.to_string()),
+            "email_change_abort" => MagicLinkUsage::EmailChangeAbort(v.to_string()),
             "new_user" => {
                 if !v.is_empty() {
                     MagicLinkUsage::NewUser(Some(v.to_string()))
@@ -67,7 +73,9 @@ impl Display for MagicLinkUsage {
         // For types with a value, `$` was chosen as the separating characters since it is URL safe.
         // It also makes splitting of the value quite easy.
         match self {
+            MagicLinkUsage::EmailAdd(email) => write!(f, "email_add${email}"),
             MagicLinkUsage::EmailChange(email) => write!(f, "email_change${email}"),
+            MagicLinkUsage::EmailChangeAbort(email) => write!(f, "email_change_abort${email}"),
             MagicLinkUsage::NewUser(redirect_uri) => {
                 if let Some(uri) = redirect_uri {
                     write!(f, "new_user${uri}")
@@ -216,8 +224,10 @@ VALUES ($1, $2, $3, $4, $5, $6)"#;
         Ok(res)
     }
 
+    /// Deletes all pending E-Mail change links for the given user, including the ones that have
+    /// been sent to the old address to abort the change.
     pub async fn invalidate_all_email_change(user_id: &str) -> Result<(), ErrorResponse> {
-        let sql = "DELETE FROM magic_links WHERE user_id = $1 AND usage LIKE 'email_change$%'";
+        let sql = "DELETE FROM magic_links WHERE user_id = $1 AND usage LIKE 'email_change%'";
         if is_hiqlite() {
             DB::hql().execute(sql, params!(user_id)).await?;
         } else {
@@ -381,5 +391,15 @@ mod tests {
         let s = ml.to_string();
         let ml_from = MagicLinkUsage::try_from(&s).unwrap();
         assert_eq!(ml, ml_from);
+
+        let ml = MagicLinkUsage::EmailChangeAbort("admin@localhost".to_string());
+        let s = ml.to_string();
+        let ml_from = MagicLinkUsage::try_from(&s).unwrap();
+        assert_eq!(ml, ml_from);
+
+        let ml = MagicLinkUsage::EmailAdd("admin@localhost".to_string());
+        let s = ml.to_string();
+        let ml_from = MagicLinkUsage::try_from(&s).unwrap();
+        assert_eq!(ml, ml_from);
     }
 }
@@ -34,6 +34,7 @@ pub mod login_locations;
 pub mod logos;
 pub mod magic_links;
 pub mod mfa_mod_token;
+pub mod pairwise_secret;
 pub mod pam;
 pub mod password;
 pub mod pictures;
@@ -46,10 +47,13 @@ pub mod scim_types;
 pub mod scopes;
 pub mod sessions;
 pub mod theme;
+pub mod token_nbf;
 pub mod tos;
 pub mod tos_user_accept;
 pub mod user_attr;
+pub mod user_emails;
 pub mod user_federation;
+pub mod user_ldap_links;
 pub mod user_login_states;
 pub mod user_revoke;
 pub mod users;
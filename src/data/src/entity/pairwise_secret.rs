@@ -0,0 +1,90 @@
+use crate::database::DB;
+use crate::entity::config::ConfigEntity;
+use cryptr::EncValue;
+use hiqlite_macros::params;
+use rauthy_common::is_hiqlite;
+use rauthy_common::utils::get_rand;
+use rauthy_error::{ErrorResponse, ErrorResponseType};
+use std::sync::OnceLock;
+use tracing::info;
+
+static SECRET: OnceLock<Vec<u8>> = OnceLock::new();
+
+/// The server-side secret, which keys the HMAC for all `pairwise` subjects. It is generated once
+/// and must never change afterward, because all `pairwise` subjects would change with it.
+pub struct PairwiseSecret;
+
+impl PairwiseSecret {
+    /// Loads the secret from the database, or generates a new one during the very first start.
+    /// Must be called once at startup before any token can be issued.
+    pub async fn init() -> Result<(), ErrorResponse> {
+        let enc = match Self::find_enc().await? {
+            Some(enc) => enc,
+            None => {
+                info!("Generating new secret for pairwise subjects");
+                let enc = EncValue::encrypt(get_rand(64).as_bytes())?
+                    .into_bytes()
+                    .to_vec();
+
+                // If multiple nodes start at the same time, only the first insert wins and
+                // everyone reads it back afterward.
+                let sql = r#"
+INSERT INTO config (id, data)
+VALUES ('pairwise_secret', $1)
+ON CONFLICT (id) DO NOTHING"#;
+                if is_hiqlite() {
+                    DB::hql().execute(sql, params!(enc)).await?;
+                } else {
+                    DB::pg_execute(sql, &[&enc]).await?;
+                }
+
+                Self::find_enc().await?.ok_or_else(|| {
+                    ErrorResponse::new(
+                        ErrorResponseType::Internal,
+                        "Cannot find the pairwise secret after inserting it",
+                    )
+                })?
+            }
+        };
+
+        let secret = EncValue::try_from(enc)?.decrypt()?.to_vec();
+        let _ = SECRET.set(secret);
+        Ok(())
+    }
+
+    #[inline]
+    pub fn get() -> &'static [u8] {
+        SECRET
+            .get()
+            .expect("PairwiseSecret::init() must be called at startup")
+    }
+
+    /// Re-encrypts the secret with the given key. The secret value itself never changes.
+    pub async fn migrate_enc_key(new_kid: &str) -> Result<(), ErrorResponse> {
+        let Some(enc) = Self::find_enc().await? else {
+            return Ok(());
+        };
+        let dec = EncValue::try_from(enc)?.decrypt()?;
+        let enc = EncValue::encrypt_with_key_id(dec.as_ref(), new_kid.to_string())?
+            .into_bytes()
+            .to_vec();
+
+        let sql = "UPDATE config SET data = $1 WHERE id = 'pairwise_secret'";
+        if is_hiqlite() {
+            DB::hql().execute(sql, params!(enc)).await?;
+        } else {
+            DB::pg_execute(sql, &[&enc]).await?;
+        }
+        Ok(())
+    }
+
+    async fn find_enc() -> Result<Option<Vec<u8>>, ErrorResponse> {
+        let sql = "SELECT * FROM config WHERE id = 'pairwise_secret'";
+        let config: Option<ConfigEntity> = if is_hiqlite() {
+            DB::hql().query_as_optional(sql, params!()).await?
+        } else {
+            DB::pg_query_opt(sql, &[]).await?
+        };
+        Ok(config.map(|c| c.data))
+    }
+}
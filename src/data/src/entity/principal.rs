@@ -1,5 +1,6 @@
 use crate::entity::api_keys::{AccessGroup, AccessRights, ApiKey};
 use crate::entity::sessions::{Session, SessionState};
+use crate::entity::users::User;
 use crate::rauthy_config::RauthyConfig;
 use actix_web::{HttpRequest, web};
 use rauthy_common::constants::RAUTHY_ADMIN_ROLE;
@@ -78,6 +79,16 @@ impl Principal {
             })
     }
 
+    /// Returns a human-readable name of the API Key or the user's E-Mail for events and logs.
+    pub async fn actor(&self) -> Result<String, ErrorResponse> {
+        if let Some(api_key) = &self.api_key {
+            Ok(format!("API Key `{}`", api_key.name))
+        } else {
+            let user = User::find(self.user_id()?.to_string()).await?;
+            Ok(user.email)
+        }
+    }
+
     #[inline(always)]
     pub fn validate_api_key(
         &self,
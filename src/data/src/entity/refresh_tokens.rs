@@ -16,6 +16,11 @@ pub struct RefreshToken {
     pub is_mfa: bool,
     pub session_id: Option<String>,
     pub access_token_jti: Option<String>,
+    /// The `id` of the very first refresh token of this login. It is carried over with each
+    /// rotation, so a replay can revoke exactly the affected chain of tokens.
+    pub family_id: String,
+    /// Only `None` for tokens, which have been issued before it was stored.
+    pub client_id: Option<String>,
 }
 
 impl Debug for RefreshToken {
@@ -46,6 +51,8 @@ impl From<tokio_postgres::Row> for RefreshToken {
             is_mfa: row.get("is_mfa"),
             session_id: row.get("session_id"),
             access_token_jti: row.get("access_token_jti"),
+            family_id: row.get("family_id"),
+            client_id: row.get("client_id"),
         }
     }
 }
@@ -65,7 +72,10 @@ impl RefreshToken {
         is_mfa: bool,
         session_id: Option<String>,
         access_token_jti: Option<String>,
+        family_id: Option<String>,
+        client_id: String,
     ) -> Result<Self, ErrorResponse> {
+        let family_id = family_id.unwrap_or_else(|| id.clone());
         let rt = Self {
             id,
             user_id,
@@ -75,6 +85,8 @@ impl RefreshToken {
             is_mfa,
             session_id,
             access_token_jti,
+            family_id,
+            client_id: Some(client_id),
         };
 
         rt.save().await?;
@@ -101,6 +113,22 @@ impl RefreshToken {
         Ok(())
     }
 
+    /// Deletes all refresh tokens bound to a session, which does not exist anymore or has
+    /// expired, and returns the amount of deleted tokens.
+    pub async fn delete_for_inactive_sessions() -> Result<usize, ErrorResponse> {
+        let sql = r#"
+DELETE FROM refresh_tokens
+WHERE session_id IS NOT NULL AND session_id NOT IN (SELECT id FROM sessions WHERE exp >= $1)"#;
+        let now = Utc::now().timestamp();
+
+        let rows_affected = if is_hiqlite() {
+            DB::hql().execute(sql, params!(now)).await?
+        } else {
+            DB::pg_execute(sql, &[&now]).await?
+        };
+        Ok(rows_affected)
+    }
+
     pub async fn find_all() -> Result<Vec<Self>, ErrorResponse> {
         let sql = "SELECT * FROM refresh_tokens";
         let res = if is_hiqlite() {
@@ -140,14 +168,42 @@ impl RefreshToken {
         Ok(())
     }
 
-    pub async fn invalidate_for_user(user_id: &str) -> Result<(), ErrorResponse> {
+    /// Deletes all refresh tokens for the given user and returns the amount of deleted tokens.
+    pub async fn invalidate_for_user(user_id: &str) -> Result<usize, ErrorResponse> {
         let sql = "DELETE FROM refresh_tokens WHERE user_id = $1";
-        if is_hiqlite() {
-            DB::hql().execute(sql, params!(user_id)).await?;
+        let rows_affected = if is_hiqlite() {
+            DB::hql().execute(sql, params!(user_id)).await?
         } else {
-            DB::pg_execute(sql, &[&user_id]).await?;
-        }
-        Ok(())
+            DB::pg_execute(sql, &[&user_id]).await?
+        };
+        Ok(rows_affected)
+    }
+
+    /// Deletes all refresh tokens for the given client and returns the amount of deleted tokens.
+    /// Older tokens without a `client_id` are found via the `issued_tokens` of their access token.
+    pub async fn invalidate_for_client(client_id: &str) -> Result<usize, ErrorResponse> {
+        let sql = r#"
+DELETE FROM refresh_tokens
+WHERE client_id = $1
+    OR (client_id IS NULL
+        AND access_token_jti IN (SELECT jti FROM issued_tokens WHERE client_id = $1))"#;
+        let rows_affected = if is_hiqlite() {
+            DB::hql().execute(sql, params!(client_id)).await?
+        } else {
+            DB::pg_execute(sql, &[&client_id]).await?
+        };
+        Ok(rows_affected)
+    }
+
+    /// Deletes all refresh tokens of the given family and returns the amount of deleted tokens.
+    pub async fn invalidate_family(family_id: &str) -> Result<usize, ErrorResponse> {
+        let sql = "DELETE FROM refresh_tokens WHERE family_id = $1";
+        let rows_affected = if is_hiqlite() {
+            DB::hql().execute(sql, params!(family_id)).await?
+        } else {
+            DB::pg_execute(sql, &[&family_id]).await?
+        };
+        Ok(rows_affected)
     }
 
     pub async fn find(id: &str) -> Result<Self, ErrorResponse> {
@@ -184,8 +240,9 @@ impl RefreshToken {
 
     pub async fn save(&self) -> Result<(), ErrorResponse> {
         let sql = r#"
-INSERT INTO refresh_tokens (id, user_id, nbf, exp, scope, is_mfa, session_id, access_token_jti)
-VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+INSERT INTO refresh_tokens
+(id, user_id, nbf, exp, scope, is_mfa, session_id, access_token_jti, family_id, client_id)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
 ON CONFLICT(id) DO UPDATE
 SET user_id = $2, nbf = $3, exp = $4, scope = $5, session_id = $7, access_token_jti = $8"#;
 
@@ -201,7 +258,9 @@ SET user_id = $2, nbf = $3, exp = $4, scope = $5, session_id = $7, access_token_
                         self.scope.clone(),
                         self.is_mfa,
                         self.session_id.clone(),
-                        self.access_token_jti.clone()
+                        self.access_token_jti.clone(),
+                        self.family_id.clone(),
+                        self.client_id.clone()
                     ),
                 )
                 .await?;
@@ -217,6 +276,8 @@ SET user_id = $2, nbf = $3, exp = $4, scope = $5, session_id = $7, access_token_
                     &self.is_mfa,
                     &self.session_id,
                     &self.access_token_jti,
+                    &self.family_id,
+                    &self.client_id,
                 ],
             )
             .await?;
@@ -171,26 +171,44 @@ impl RefreshTokenDevice {
         Ok(slf)
     }
 
-    pub async fn invalidate_all_for_device(device_id: &str) -> Result<(), ErrorResponse> {
+    /// Deletes all refresh tokens for the given device and returns the amount of deleted tokens.
+    pub async fn invalidate_all_for_device(device_id: &str) -> Result<usize, ErrorResponse> {
         let sql = "DELETE FROM refresh_tokens_devices WHERE device_id = $1";
-        if is_hiqlite() {
-            DB::hql().execute(sql, params!(device_id)).await?;
+        let rows_affected = if is_hiqlite() {
+            DB::hql().execute(sql, params!(device_id)).await?
         } else {
-            DB::pg_execute(sql, &[&device_id]).await?;
-        }
+            DB::pg_execute(sql, &[&device_id]).await?
+        };
 
-        Ok(())
+        Ok(rows_affected)
+    }
+
+    /// Deletes all device refresh tokens for the given client and returns the amount of deleted
+    /// tokens.
+    pub async fn invalidate_all_for_client(client_id: &str) -> Result<usize, ErrorResponse> {
+        let sql = r#"
+DELETE FROM refresh_tokens_devices
+WHERE device_id IN (SELECT id FROM devices WHERE client_id = $1)"#;
+        let rows_affected = if is_hiqlite() {
+            DB::hql().execute(sql, params!(client_id)).await?
+        } else {
+            DB::pg_execute(sql, &[&client_id]).await?
+        };
+
+        Ok(rows_affected)
     }
 
-    pub async fn invalidate_all_for_user(user_id: &str) -> Result<(), ErrorResponse> {
+    /// Deletes all device refresh tokens for the given user and returns the amount of deleted
+    /// tokens.
+    pub async fn invalidate_all_for_user(user_id: &str) -> Result<usize, ErrorResponse> {
         let sql = "DELETE FROM refresh_tokens_devices WHERE user_id = $1";
-        if is_hiqlite() {
-            DB::hql().execute(sql, params!(user_id)).await?;
+        let rows_affected = if is_hiqlite() {
+            DB::hql().execute(sql, params!(user_id)).await?
         } else {
-            DB::pg_execute(sql, &[&user_id]).await?;
-        }
+            DB::pg_execute(sql, &[&user_id]).await?
+        };
 
-        Ok(())
+        Ok(rows_affected)
     }
 
     pub async fn save(&self) -> Result<(), ErrorResponse> {
@@ -1,6 +1,7 @@
 use crate::database::{Cache, DB};
 use crate::entity::clients::Client;
 use crate::entity::user_attr::UserAttrConfigEntity;
+use crate::entity::users::User;
 use crate::entity::well_known::WellKnown;
 use deadpool_postgres::GenericClient;
 use hiqlite::Params;
@@ -23,6 +24,8 @@ pub struct Scope {
     pub attr_include_access: Option<String>,
     // Custom user attributes as CSV to include in the id token
     pub attr_include_id: Option<String>,
+    // Roles as CSV - if set, only users with at least one of them can obtain this scope
+    pub required_roles: Option<String>,
 }
 
 impl From<tokio_postgres::Row> for Scope {
@@ -32,6 +35,7 @@ impl From<tokio_postgres::Row> for Scope {
             name: row.get("name"),
             attr_include_access: row.get("attr_include_access"),
             attr_include_id: row.get("attr_include_id"),
+            required_roles: row.get("required_roles"),
         }
     }
 }
@@ -74,32 +78,35 @@ impl Scope {
             name: scope_req.scope,
             attr_include_access,
             attr_include_id,
+            required_roles: Self::required_roles_csv(scope_req.required_roles),
         };
 
         if is_hiqlite() {
             DB::hql()
                 .execute(
                     r#"
-INSERT INTO scopes (id, name, attr_include_access, attr_include_id)
-VALUES ($1, $2, $3, $4)"#,
+INSERT INTO scopes (id, name, attr_include_access, attr_include_id, required_roles)
+VALUES ($1, $2, $3, $4, $5)"#,
                     params!(
                         &new_scope.id,
                         &new_scope.name,
                         &new_scope.attr_include_access,
-                        &new_scope.attr_include_id
+                        &new_scope.attr_include_id,
+                        &new_scope.required_roles
                     ),
                 )
                 .await?;
         } else {
             DB::pg_execute(
                 r#"
-    INSERT INTO scopes (id, name, attr_include_access, attr_include_id)
-    VALUES ($1, $2, $3, $4)"#,
+    INSERT INTO scopes (id, name, attr_include_access, attr_include_id, required_roles)
+    VALUES ($1, $2, $3, $4, $5)"#,
                 &[
                     &new_scope.id,
                     &new_scope.name,
                     &new_scope.attr_include_access,
                     &new_scope.attr_include_id,
+                    &new_scope.required_roles,
                 ],
             )
             .await?;
@@ -272,6 +279,7 @@ VALUES ($1, $2, $3, $4)"#,
             name: scope_req.scope,
             attr_include_access,
             attr_include_id,
+            required_roles: Self::required_roles_csv(scope_req.required_roles),
         };
 
         if is_hiqlite() {
@@ -286,12 +294,13 @@ VALUES ($1, $2, $3, $4)"#,
             txn.push((
                 r#"
 UPDATE scopes
-SET name = $1, attr_include_access = $2, attr_include_id = $3
-WHERE id = $4"#,
+SET name = $1, attr_include_access = $2, attr_include_id = $3, required_roles = $4
+WHERE id = $5"#,
                 params!(
                     &new_scope.name,
                     &new_scope.attr_include_access,
                     &new_scope.attr_include_id,
+                    &new_scope.required_roles,
                     &new_scope.id
                 ),
             ));
@@ -313,12 +322,13 @@ WHERE id = $4"#,
                 &txn,
                 r#"
 UPDATE scopes
-SET name = $1, attr_include_access = $2, attr_include_id = $3
-WHERE id = $4"#,
+SET name = $1, attr_include_access = $2, attr_include_id = $3, required_roles = $4
+WHERE id = $5"#,
                 &[
                     &new_scope.name,
                     &new_scope.attr_include_access,
                     &new_scope.attr_include_id,
+                    &new_scope.required_roles,
                     &new_scope.id,
                 ],
             )
@@ -394,6 +404,52 @@ WHERE id = $4"#,
 }
 
 impl Scope {
+    #[inline]
+    fn required_roles_csv(roles: Option<Vec<String>>) -> Option<String> {
+        let roles = roles?
+            .into_iter()
+            .filter(|r| !r.is_empty())
+            .collect::<Vec<_>>();
+        if roles.is_empty() {
+            None
+        } else {
+            Some(roles.join(","))
+        }
+    }
+
+    /// Returns `true` if the user is allowed to obtain this scope. This is always the case, unless
+    /// the scope has `required_roles` and the user has none of them.
+    #[inline]
+    pub fn is_user_entitled(&self, user: &User) -> bool {
+        match &self.required_roles {
+            None => true,
+            Some(required) => {
+                let required = required.split(',').collect::<Vec<_>>();
+                user.roles_iter().any(|r| required.contains(&r))
+            }
+        }
+    }
+
+    /// Removes all scopes the user is not entitled to from the given list and returns them in a
+    /// separate `Vec`. Only scopes with `required_roles` can ever be removed.
+    pub async fn filter_user_entitled<S: AsRef<str>>(
+        scopes: Vec<S>,
+        user: &User,
+    ) -> Result<(Vec<S>, Vec<S>), ErrorResponse> {
+        let all = Self::find_all().await?;
+        if all.iter().all(|s| s.required_roles.is_none()) {
+            return Ok((scopes, Vec::new()));
+        }
+
+        let (allowed, denied) = scopes.into_iter().partition(|requested| {
+            all.iter()
+                .find(|s| s.name == requested.as_ref())
+                .map(|s| s.is_user_entitled(user))
+                .unwrap_or(true)
+        });
+        Ok((allowed, denied))
+    }
+
     pub fn clean_up_attrs(
         req_attrs: Option<Vec<String>>,
         existing_attrs: &HashSet<String>,
@@ -468,6 +524,9 @@ impl From<Scope> for ScopeResponse {
             name: value.name,
             attr_include_access,
             attr_include_id,
+            required_roles: value
+                .required_roles
+                .map(|roles| roles.split(',').map(String::from).collect()),
         }
     }
 }
@@ -10,7 +10,7 @@ use chrono::Utc;
 use hiqlite_macros::params;
 use rauthy_api_types::generic::SearchParamsIdx;
 use rauthy_common::constants::{
-    CACHE_TTL_SESSION, COOKIE_SESSION, COOKIE_SESSION_FED_CM, CSRF_HEADER,
+    CACHE_TTL_SESSION, COOKIE_SESSION, COOKIE_SESSION_FED_CM, CSRF_HEADER, IDX_AUTH_STATE,
 };
 use rauthy_common::is_hiqlite;
 use rauthy_common::utils::get_rand;
@@ -226,6 +226,17 @@ impl Session {
         Ok(sessions)
     }
 
+    // not cached -> only used for the user data export
+    pub async fn find_for_user(user_id: &str) -> Result<Vec<Self>, ErrorResponse> {
+        let sql = "SELECT * FROM sessions WHERE user_id = $1 ORDER BY exp DESC";
+        let sessions = if is_hiqlite() {
+            DB::hql().query_map(sql, params!(user_id)).await?
+        } else {
+            DB::pg_query(sql, &[&user_id], 2).await?
+        };
+        Ok(sessions)
+    }
+
     pub async fn find_paginated(
         continuation_token: Option<ContinuationToken>,
         page_size: i64,
@@ -575,7 +586,7 @@ impl Session {
 
     pub fn client_cookie(&self) -> cookie::Cookie<'_> {
         let max_age = self.exp - Utc::now().timestamp();
-        ApiCookie::build(COOKIE_SESSION, Cow::from(&self.id), max_age)
+        ApiCookie::build_session(COOKIE_SESSION, Cow::from(&self.id), max_age)
     }
 
     pub fn client_cookie_fed_cm(&self) -> cookie::Cookie<'_> {
@@ -587,6 +598,59 @@ impl Session {
         )
     }
 
+    #[inline]
+    fn auth_state_idx(&self, state: &str) -> String {
+        let hash = hmac_sha256::Hash::hash(state.as_bytes());
+        format!("{IDX_AUTH_STATE}{}_{}", self.id, hex::encode(hash))
+    }
+
+    /// Binds the `state` from an authorization request to this session. A session can have
+    /// multiple bound states at once, for instance with logins in multiple tabs.
+    pub async fn bind_auth_state(&self, state: &str) -> Result<(), ErrorResponse> {
+        DB::hql()
+            .put(
+                Cache::Session,
+                self.auth_state_idx(state),
+                &true,
+                CACHE_TTL_SESSION,
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Makes sure that the `state` for a login has been bound to this session during the
+    /// authorization request before, and if it is required.
+    pub async fn validate_auth_state(&self, state: Option<&str>) -> Result<(), ErrorResponse> {
+        let access = &RauthyConfig::get().vars.access;
+        let Some(state) = state else {
+            if access.auth_state_required {
+                return Err(ErrorResponse::new(
+                    ErrorResponseType::BadRequest,
+                    "'state' is missing",
+                ));
+            }
+            return Ok(());
+        };
+
+        if !access.auth_state_binding {
+            return Ok(());
+        }
+        let bound: Option<bool> = DB::hql()
+            .get(Cache::Session, self.auth_state_idx(state))
+            .await?;
+        if bound.is_none() {
+            warn!(
+                "'state' does not match any authorization request for session {}",
+                self.id
+            );
+            return Err(ErrorResponse::new(
+                ErrorResponseType::BadRequest,
+                "'state' does not match the authorization request",
+            ));
+        }
+        Ok(())
+    }
+
     pub fn extract_from_req(
         session_req: web::ReqData<Option<Session>>,
     ) -> Result<Session, ErrorResponse> {
@@ -635,6 +699,16 @@ impl Session {
         Ok(())
     }
 
+    /// Checks if a session, which tokens have been bound to with `token_bind_session`, is still
+    /// active. It must exist and must neither be expired nor logged out.
+    pub async fn is_active(id: String) -> Result<bool, ErrorResponse> {
+        match Self::find(id).await {
+            Ok(slf) => Ok(slf.exp >= Utc::now().timestamp()),
+            Err(err) if err.error == ErrorResponseType::NotFound => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
     #[inline(always)]
     pub fn state(&self) -> Result<SessionState, ErrorResponse> {
         SessionState::from_str(self.state.as_str())
@@ -688,6 +762,7 @@ impl Session {
                     "/auth/v1/oidc/callback",
                     "/auth/v1/oidc/token",
                     "/auth/v1/providers/login",
+                    "/auth/v1/providers/recovery",
                     "/auth/v1/providers/callback",
                     "/auth/v1/dev/providers_callback",
                     "/auth/v1/tos/accept",
@@ -700,6 +775,7 @@ impl Session {
                     "/auth/v1/oidc/callback",
                     "/auth/v1/oidc/token",
                     "/auth/v1/providers/login",
+                    "/auth/v1/providers/recovery",
                     "/auth/v1/providers/callback",
                     "/auth/v1/tos/accept",
                     "/auth/v1/tos/deny",
@@ -0,0 +1,179 @@
+use crate::database::{Cache, DB};
+use crate::rauthy_config::RauthyConfig;
+use chrono::Utc;
+use hiqlite_macros::params;
+use rauthy_common::constants::{CACHE_TTL_APP, IDX_CLIENT_TOKENS_NBF, IDX_GLOBAL_TOKENS_NBF};
+use rauthy_common::is_hiqlite;
+use rauthy_common::utils::{deserialize, serialize};
+use rauthy_error::{ErrorResponse, ErrorResponseType};
+use std::collections::HashMap;
+
+/// Per-client "not valid before" timestamps. Any token issued for a client with an `iat`
+/// before its timestamp must be treated as revoked.
+///
+/// Tokens issued in the same second stay valid, otherwise a client would not be able to get new
+/// tokens until the next second after a revocation. Access tokens issued just before the
+/// revocation are still covered by the stateful revocation of the `issued_tokens`.
+///
+/// Access tokens can be revoked via the `issued_tokens` as well, but refresh tokens are not
+/// linked to a client in the database. This makes it possible to revoke everything for a
+/// client at once, even stateless tokens.
+pub struct ClientTokenNbf;
+
+impl ClientTokenNbf {
+    async fn find_all() -> Result<HashMap<String, i64>, ErrorResponse> {
+        let client = DB::hql();
+        if let Some(map) = client.get(Cache::App, IDX_CLIENT_TOKENS_NBF).await? {
+            return Ok(map);
+        }
+
+        let sql = "SELECT data FROM config WHERE id = 'client_tokens_nbf'";
+        let data: Option<Vec<u8>> = if is_hiqlite() {
+            DB::hql()
+                .query_raw(sql, params!())
+                .await?
+                .pop()
+                .map(|mut row| row.get("data"))
+        } else {
+            DB::pg_query_rows(sql, &[], 1)
+                .await?
+                .pop()
+                .map(|row| row.get("data"))
+        };
+        let map = match data {
+            Some(bytes) => deserialize::<HashMap<String, i64>>(&bytes)?,
+            None => HashMap::default(),
+        };
+
+        client
+            .put(Cache::App, IDX_CLIENT_TOKENS_NBF, &map, CACHE_TTL_APP)
+            .await?;
+
+        Ok(map)
+    }
+
+    pub async fn find(client_id: &str) -> Result<Option<i64>, ErrorResponse> {
+        Ok(Self::find_all().await?.get(client_id).copied())
+    }
+
+    /// Sets the `nbf` for all tokens of the given client.
+    pub async fn upsert(client_id: String, nbf: i64) -> Result<(), ErrorResponse> {
+        let mut map = Self::find_all().await?;
+        map.insert(client_id, nbf);
+        let data = serialize(&map)?;
+
+        let sql = r#"
+INSERT INTO config (id, data) VALUES ('client_tokens_nbf', $1)
+ON CONFLICT(id) DO UPDATE SET data = $1"#;
+        if is_hiqlite() {
+            DB::hql().execute(sql, params!(data)).await?;
+        } else {
+            DB::pg_execute(sql, &[&data]).await?;
+        }
+
+        DB::hql()
+            .put(Cache::App, IDX_CLIENT_TOKENS_NBF, &map, CACHE_TTL_APP)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns an error if a token for the given client with the given `iat` has been revoked.
+    #[inline]
+    pub async fn validate(client_id: &str, iat: i64) -> Result<(), ErrorResponse> {
+        if let Some(nbf) = Self::find(client_id).await?
+            && iat < nbf
+        {
+            return Err(ErrorResponse::new(
+                ErrorResponseType::Unauthorized,
+                "token was revoked",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The global "not valid before" timestamp for all tokens. Any token with an `iat` before this
+/// value must be treated as revoked.
+///
+/// Just like for `ClientTokenNbf`, tokens issued in the same second stay valid. Otherwise, the
+/// token endpoints would reject freshly issued tokens until the next second after a bump.
+///
+/// The effective value is the later one of `access.tokens_not_before` from the config and the
+/// value that has been set at runtime via `bump()`.
+pub struct GlobalTokenNbf;
+
+impl GlobalTokenNbf {
+    pub async fn find() -> Result<i64, ErrorResponse> {
+        let from_config = RauthyConfig::get().vars.access.tokens_not_before;
+
+        let client = DB::hql();
+        let cached: Option<i64> = client.get(Cache::App, IDX_GLOBAL_TOKENS_NBF).await?;
+        if let Some(nbf) = cached {
+            return Ok(nbf.max(from_config));
+        }
+
+        let sql = "SELECT data FROM config WHERE id = 'global_tokens_nbf'";
+        let data: Option<Vec<u8>> = if is_hiqlite() {
+            DB::hql()
+                .query_raw(sql, params!())
+                .await?
+                .pop()
+                .map(|mut row| row.get("data"))
+        } else {
+            DB::pg_query_rows(sql, &[], 1)
+                .await?
+                .pop()
+                .map(|row| row.get("data"))
+        };
+        let nbf = match data {
+            Some(bytes) => deserialize::<i64>(&bytes)?,
+            None => 0,
+        };
+
+        client
+            .put(Cache::App, IDX_GLOBAL_TOKENS_NBF, &nbf, CACHE_TTL_APP)
+            .await?;
+
+        Ok(nbf.max(from_config))
+    }
+
+    /// Sets the global `nbf` to now, which invalidates every single token issued so far.
+    /// Returns the new effective value.
+    pub async fn bump() -> Result<i64, ErrorResponse> {
+        let nbf = Utc::now().timestamp();
+        let data = serialize(&nbf)?;
+
+        let sql = r#"
+INSERT INTO config (id, data) VALUES ('global_tokens_nbf', $1)
+ON CONFLICT(id) DO UPDATE SET data = $1"#;
+        if is_hiqlite() {
+            DB::hql().execute(sql, params!(data)).await?;
+        } else {
+            DB::pg_execute(sql, &[&data]).await?;
+        }
+
+        DB::hql()
+            .put(Cache::App, IDX_GLOBAL_TOKENS_NBF, &nbf, CACHE_TTL_APP)
+            .await?;
+
+        Ok(nbf.max(RauthyConfig::get().vars.access.tokens_not_before))
+    }
+}
+
+/// Combined validation of the global and per-client "not valid before" timestamps.
+pub struct TokenNbf;
+
+impl TokenNbf {
+    /// Returns an error if a token for the given client with the given `iat` has been revoked,
+    /// either globally or for this client only.
+    pub async fn validate(client_id: &str, iat: i64) -> Result<(), ErrorResponse> {
+        if iat < GlobalTokenNbf::find().await? {
+            return Err(ErrorResponse::new(
+                ErrorResponseType::Unauthorized,
+                "token was revoked",
+            ));
+        }
+        ClientTokenNbf::validate(client_id, iat).await
+    }
+}
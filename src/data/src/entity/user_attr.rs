@@ -24,6 +24,9 @@ pub struct UserAttrConfigEntity {
     pub default_value: Option<Vec<u8>>,
     pub typ: Option<UserAttrConfigTyp>,
     pub user_editable: bool,
+    /// Internal attributes are a hard backstop and will never leave Rauthy via tokens, userinfo
+    /// or SCIM, no matter which scope mappings exist.
+    pub internal: bool,
 }
 
 impl From<hiqlite::Row<'_>> for UserAttrConfigEntity {
@@ -40,6 +43,7 @@ impl From<hiqlite::Row<'_>> for UserAttrConfigEntity {
             default_value: row.get("default_value"),
             typ,
             user_editable: row.get("user_editable"),
+            internal: row.get("internal"),
         }
     }
 }
@@ -58,6 +62,7 @@ impl From<tokio_postgres::Row> for UserAttrConfigEntity {
             default_value: row.get("default_value"),
             typ,
             user_editable: row.get("user_editable"),
+            internal: row.get("internal"),
         }
     }
 }
@@ -84,6 +89,7 @@ impl UserAttrConfigEntity {
 
         let typ = new_attr.typ.as_ref().map(|t| t.as_str());
         let user_editable = new_attr.user_editable.unwrap_or(false);
+        let internal = new_attr.internal.unwrap_or(false);
         let default_value = if let Some(bytes) = &new_attr.default_value {
             Some(serde_json::to_vec(bytes)?)
         } else {
@@ -94,28 +100,30 @@ impl UserAttrConfigEntity {
             DB::hql()
                 .execute(
                     r#"
-INSERT INTO user_attr_config (name, desc, default_value, typ, user_editable)
-VALUES ($1, $2, $3, $4, $5)"#,
+INSERT INTO user_attr_config (name, desc, default_value, typ, user_editable, internal)
+VALUES ($1, $2, $3, $4, $5, $6)"#,
                     params!(
                         &new_attr.name,
                         &new_attr.desc,
                         &default_value,
                         typ,
-                        user_editable
+                        user_editable,
+                        internal
                     ),
                 )
                 .await?;
         } else {
             DB::pg_execute(
                 r#"
-INSERT INTO user_attr_config (name, "desc", default_value, typ, user_editable)
-VALUES ($1, $2, $3, $4, $5)"#,
+INSERT INTO user_attr_config (name, "desc", default_value, typ, user_editable, internal)
+VALUES ($1, $2, $3, $4, $5, $6)"#,
                 &[
                     &new_attr.name,
                     &new_attr.desc,
                     &default_value,
                     &typ,
                     &user_editable,
+                    &internal,
                 ],
             )
             .await?;
@@ -128,7 +136,8 @@ VALUES ($1, $2, $3, $4, $5)"#,
             desc: new_attr.desc.clone(),
             default_value: default_value.clone(),
             typ: new_attr.typ.clone(),
-            user_editable: new_attr.user_editable.unwrap_or(false),
+            user_editable,
+            internal,
         };
         attrs.push(slf.clone());
         DB::hql()
@@ -287,6 +296,16 @@ VALUES ($1, $2, $3, $4, $5)"#,
             .collect::<Vec<_>>())
     }
 
+    #[inline]
+    pub async fn find_all_internal_names() -> Result<HashSet<String>, ErrorResponse> {
+        Ok(Self::find_all()
+            .await?
+            .into_iter()
+            .filter(|c| c.internal)
+            .map(|c| c.name)
+            .collect())
+    }
+
     #[inline]
     pub async fn find_with_default_value() -> Result<Vec<Self>, ErrorResponse> {
         // Even though with naively fetch all of them first and filter later, the `Self::find_all()`
@@ -308,6 +327,7 @@ VALUES ($1, $2, $3, $4, $5)"#,
         slf.name.clone_from(&req_data.name);
         slf.desc.clone_from(&req_data.desc);
         slf.user_editable = req_data.user_editable.unwrap_or(false);
+        slf.internal = req_data.internal.unwrap_or(false);
         slf.default_value = if let Some(v) = &req_data.default_value {
             Some(serde_json::to_vec(v)?)
         } else {
@@ -392,14 +412,15 @@ VALUES ($1, $2, $3, $4, $5)"#,
             txn.push((
                 r#"
 UPDATE user_attr_config
-SET name  = $1, desc = $2, default_value = $3, typ = $4, user_editable = $5
-WHERE name = $6"#,
+SET name  = $1, desc = $2, default_value = $3, typ = $4, user_editable = $5, internal = $6
+WHERE name = $7"#,
                 params!(
                     &slf.name,
                     &slf.desc,
                     &slf.default_value,
                     typ,
                     slf.user_editable,
+                    slf.internal,
                     name
                 ),
             ));
@@ -417,14 +438,15 @@ WHERE name = $6"#,
                 &txn,
                 r#"
 UPDATE user_attr_config
-SET name  = $1, "desc" = $2, default_value = $3, typ = $4, user_editable = $5
-WHERE name = $6"#,
+SET name  = $1, "desc" = $2, default_value = $3, typ = $4, user_editable = $5, internal = $6
+WHERE name = $7"#,
                 &[
                     &slf.name,
                     &slf.desc,
                     &slf.default_value,
                     typ,
                     &slf.user_editable,
+                    &slf.internal,
                     &name,
                 ],
             )
@@ -475,6 +497,7 @@ impl From<UserAttrConfigEntity> for UserAttrConfigValueResponse {
                 .map(|v| serde_json::from_slice(&v).unwrap_or_default()),
             typ: value.typ,
             user_editable: value.user_editable,
+            internal: value.internal,
         }
     }
 }
@@ -622,8 +645,13 @@ impl UserAttrValueEntity {
             })
             .collect::<Vec<_>>();
 
+        let internal = UserAttrConfigEntity::find_all_internal_names().await?;
+
         let mut res = HashMap::with_capacity(values.len());
         for value in values {
+            if internal.contains(&value.key) {
+                continue;
+            }
             for scope in &scopes {
                 if scope.contains(&value.key) {
                     if let Ok(json) = serde_json::from_slice(value.value.as_slice()) {
@@ -0,0 +1,187 @@
+use crate::database::DB;
+use chrono::Utc;
+use hiqlite_macros::params;
+use rauthy_common::is_hiqlite;
+use rauthy_error::{ErrorResponse, ErrorResponseType};
+use serde::{Deserialize, Serialize};
+
+pub(crate) static SQL_INSERT: &str = r#"
+INSERT INTO user_emails (email, user_id, verified, created_at)
+VALUES ($1, $2, $3, $4)"#;
+pub(crate) static SQL_DELETE: &str = "DELETE FROM user_emails WHERE user_id = $1 AND email = $2";
+
+/// Additional E-Mail addresses for a user. The primary address always lives in `users.email`
+/// and is never part of this table.
+///
+/// Only verified addresses can be used to log in and they must be unique across all users,
+/// including their primary address. Pending addresses never block other users.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserEmail {
+    pub email: String,
+    pub user_id: String,
+    pub verified: bool,
+    pub created_at: i64,
+}
+
+impl From<hiqlite::Row<'_>> for UserEmail {
+    fn from(mut row: hiqlite::Row<'_>) -> Self {
+        Self {
+            email: row.get("email"),
+            user_id: row.get("user_id"),
+            verified: row.get("verified"),
+            created_at: row.get("created_at"),
+        }
+    }
+}
+
+impl From<tokio_postgres::Row> for UserEmail {
+    fn from(row: tokio_postgres::Row) -> Self {
+        Self {
+            email: row.get("email"),
+            user_id: row.get("user_id"),
+            verified: row.get("verified"),
+            created_at: row.get("created_at"),
+        }
+    }
+}
+
+impl UserEmail {
+    #[inline(always)]
+    pub(crate) fn map_unique_violation(err: ErrorResponse) -> ErrorResponse {
+        if err.message.to_lowercase().contains("unique") {
+            ErrorResponse::new(
+                ErrorResponseType::NotAccepted,
+                "UNIQUE constraint on: 'email'",
+            )
+        } else {
+            err
+        }
+    }
+
+    /// Inserts a new, unverified address for the given user. If the user has added this address
+    /// already, the existing entry will be returned.
+    pub async fn create(user_id: String, email: String) -> Result<Self, ErrorResponse> {
+        let email = email.to_lowercase();
+        if let Some(slf) = Self::find(&user_id, &email).await? {
+            return Ok(slf);
+        }
+
+        let slf = Self {
+            email,
+            user_id,
+            verified: false,
+            created_at: Utc::now().timestamp(),
+        };
+        slf.insert().await?;
+
+        Ok(slf)
+    }
+
+    pub async fn insert(&self) -> Result<(), ErrorResponse> {
+        if is_hiqlite() {
+            DB::hql()
+                .execute(
+                    SQL_INSERT,
+                    params!(&self.email, &self.user_id, self.verified, self.created_at),
+                )
+                .await
+                .map_err(|err| Self::map_unique_violation(ErrorResponse::from(err)))?;
+        } else {
+            DB::pg_execute(
+                SQL_INSERT,
+                &[&self.email, &self.user_id, &self.verified, &self.created_at],
+            )
+            .await
+            .map_err(Self::map_unique_violation)?;
+        }
+        Ok(())
+    }
+
+    pub async fn find(user_id: &str, email: &str) -> Result<Option<Self>, ErrorResponse> {
+        let sql = "SELECT * FROM user_emails WHERE user_id = $1 AND email = $2";
+        let slf = if is_hiqlite() {
+            DB::hql()
+                .query_map_optional(sql, params!(user_id, email))
+                .await?
+        } else {
+            DB::pg_query_opt(sql, &[&user_id, &email]).await?
+        };
+        Ok(slf)
+    }
+
+    pub async fn find_for_user(user_id: &str) -> Result<Vec<Self>, ErrorResponse> {
+        let sql = "SELECT * FROM user_emails WHERE user_id = $1 ORDER BY created_at ASC";
+        let res = if is_hiqlite() {
+            DB::hql().query_map(sql, params!(user_id)).await?
+        } else {
+            DB::pg_query(sql, &[&user_id], 4).await?
+        };
+        Ok(res)
+    }
+
+    /// Returns the `user_id` this address belongs to, if it has been verified.
+    pub async fn find_verified_user_id(email: &str) -> Result<Option<String>, ErrorResponse> {
+        let sql = "SELECT user_id FROM user_emails WHERE email = $1 AND verified = TRUE";
+        let user_id = if is_hiqlite() {
+            DB::hql()
+                .query_raw(sql, params!(email))
+                .await?
+                .pop()
+                .map(|mut row| row.get("user_id"))
+        } else {
+            DB::pg_query_rows(sql, &[&email], 1)
+                .await?
+                .pop()
+                .map(|row| row.get("user_id"))
+        };
+        Ok(user_id)
+    }
+
+    /// Marks this address as verified. Pending entries for the same address from other users
+    /// will be removed, because they can never be verified anymore.
+    pub async fn set_verified(&mut self) -> Result<(), ErrorResponse> {
+        let sql_1 = "UPDATE user_emails SET verified = TRUE WHERE user_id = $1 AND email = $2";
+        let sql_2 =
+            "DELETE FROM user_emails WHERE email = $1 AND user_id != $2 AND verified = FALSE";
+        if is_hiqlite() {
+            DB::hql()
+                .execute(sql_1, params!(&self.user_id, &self.email))
+                .await
+                .map_err(|err| Self::map_unique_violation(ErrorResponse::from(err)))?;
+            DB::hql()
+                .execute(sql_2, params!(&self.email, &self.user_id))
+                .await?;
+        } else {
+            DB::pg_execute(sql_1, &[&self.user_id, &self.email])
+                .await
+                .map_err(Self::map_unique_violation)?;
+            DB::pg_execute(sql_2, &[&self.email, &self.user_id]).await?;
+        }
+        self.verified = true;
+
+        Ok(())
+    }
+
+    pub async fn delete(&self) -> Result<(), ErrorResponse> {
+        if is_hiqlite() {
+            DB::hql()
+                .execute(SQL_DELETE, params!(&self.user_id, &self.email))
+                .await?;
+        } else {
+            DB::pg_execute(SQL_DELETE, &[&self.user_id, &self.email]).await?;
+        }
+        Ok(())
+    }
+
+    /// Returns an error if the address is a verified additional address of any user.
+    pub async fn validate_free(email: &str) -> Result<(), ErrorResponse> {
+        if Self::find_verified_user_id(email).await?.is_some() {
+            Err(ErrorResponse::new(
+                ErrorResponseType::NotAccepted,
+                "UNIQUE constraint on: 'email'",
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -1,8 +1,11 @@
 use crate::database::DB;
+use crate::entity::auth_providers::AuthProviderUidNormalization;
+use crate::entity::continuation_token::Cursor;
 use hiqlite_macros::params;
 use rauthy_common::is_hiqlite;
 use rauthy_error::{ErrorResponse, ErrorResponseType};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserFederation {
@@ -34,15 +37,17 @@ impl UserFederation {
         }
     }
 
+    /// The `federation_uid` is stored with the normalization of the provider applied.
     pub async fn create(
         user_id: String,
         provider_id: String,
-        federation_uid: String,
+        federation_uid: &str,
+        normalization: AuthProviderUidNormalization,
     ) -> Result<Self, ErrorResponse> {
         let new_federation = Self {
             user_id,
             provider_id,
-            federation_uid,
+            federation_uid: normalization.apply(federation_uid).into_owned(),
         };
 
         let sql = "INSERT INTO user_federations (user_id, provider_id, federation_uid) VALUES ($1, $2, $3)";
@@ -85,6 +90,47 @@ impl UserFederation {
         }
     }
 
+    /// Keyset pagination ordered by `(provider_id, user_id)`. Returns a `Cursor` for the next
+    /// page, if this page has been completely filled.
+    pub async fn find_after(
+        cursor: Option<Cursor<(String, String)>>,
+        limit: i64,
+    ) -> Result<(Vec<Self>, Option<Cursor<(String, String)>>), ErrorResponse> {
+        let res: Vec<Self> = if let Some(Cursor((provider_id, user_id))) = cursor {
+            let sql = r#"
+SELECT * FROM user_federations
+WHERE provider_id > $1 OR (provider_id = $1 AND user_id > $2)
+ORDER BY provider_id ASC, user_id ASC
+LIMIT $3"#;
+            if is_hiqlite() {
+                DB::hql()
+                    .query_as(sql, params!(provider_id, user_id, limit))
+                    .await?
+            } else {
+                DB::pg_query(sql, &[&provider_id, &user_id, &limit], limit as usize).await?
+            }
+        } else {
+            let sql = r#"
+SELECT * FROM user_federations
+ORDER BY provider_id ASC, user_id ASC
+LIMIT $1"#;
+            if is_hiqlite() {
+                DB::hql().query_as(sql, params!(limit)).await?
+            } else {
+                DB::pg_query(sql, &[&limit], limit as usize).await?
+            }
+        };
+
+        let next = if res.len() as i64 == limit {
+            res.last()
+                .map(|f| Cursor((f.provider_id.clone(), f.user_id.clone())))
+        } else {
+            None
+        };
+
+        Ok((res, next))
+    }
+
     pub async fn count_for_user(user_id: &str) -> Result<i64, ErrorResponse> {
         let sql = "SELECT COUNT(*) AS count FROM user_federations WHERE user_id = $1";
         let count = if is_hiqlite() {
@@ -108,6 +154,19 @@ impl UserFederation {
         Ok(has_link)
     }
 
+    pub async fn has_for_provider(provider_id: &str) -> Result<bool, ErrorResponse> {
+        let sql = "SELECT 1 FROM user_federations WHERE provider_id = $1 LIMIT 1";
+        let has_link = if is_hiqlite() {
+            !DB::hql()
+                .query_raw(sql, params!(provider_id))
+                .await?
+                .is_empty()
+        } else {
+            DB::pg_query_rows(sql, &[&provider_id], 1).await?.len() == 1
+        };
+        Ok(has_link)
+    }
+
     pub async fn exists_for_user_provider(
         user_id: &str,
         provider_id: &str,
@@ -127,10 +186,14 @@ impl UserFederation {
         Ok(exists)
     }
 
+    /// The stored links are always normalized, so the lookup can use an exact match.
     pub async fn find_by_federation_id(
         provider_id: &str,
         federation_uid: &str,
+        normalization: AuthProviderUidNormalization,
     ) -> Result<Self, ErrorResponse> {
+        let federation_uid = normalization.apply(federation_uid);
+        let federation_uid = federation_uid.as_ref();
         let sql = "SELECT * FROM user_federations WHERE provider_id = $1 AND federation_uid = $2";
         let res = if is_hiqlite() {
             DB::hql()
@@ -142,6 +205,112 @@ impl UserFederation {
         Ok(res)
     }
 
+    /// Applies the `normalization` to all stored links of the provider. Nothing is changed, if
+    /// the links of different users would collapse into the same upstream id.
+    pub async fn normalize_for_provider(
+        provider_id: &str,
+        normalization: AuthProviderUidNormalization,
+    ) -> Result<(), ErrorResponse> {
+        let sql = "SELECT * FROM user_federations WHERE provider_id = $1";
+        let links: Vec<Self> = if is_hiqlite() {
+            DB::hql().query_as(sql, params!(provider_id)).await?
+        } else {
+            DB::pg_query(sql, &[&provider_id], 0).await?
+        };
+
+        let mut user_ids = HashMap::with_capacity(links.len());
+        let mut updates = Vec::new();
+        for link in links {
+            let uid = normalization.apply(&link.federation_uid).into_owned();
+            if let Some(other) = user_ids.insert(uid.clone(), link.user_id.clone()) {
+                return Err(ErrorResponse::new(
+                    ErrorResponseType::BadRequest,
+                    format!(
+                        "The upstream ids of the linked users {other} and {} would both be \
+                        normalized to '{uid}'",
+                        link.user_id
+                    ),
+                ));
+            }
+            if uid != link.federation_uid {
+                updates.push((link.user_id, uid));
+            }
+        }
+        if updates.is_empty() {
+            return Ok(());
+        }
+
+        let sql = r#"
+UPDATE user_federations
+SET federation_uid = $1
+WHERE user_id = $2 AND provider_id = $3"#;
+        if is_hiqlite() {
+            let txn = updates
+                .into_iter()
+                .map(|(user_id, uid)| (sql, params!(uid, user_id, provider_id.to_string())))
+                .collect::<Vec<_>>();
+            DB::hql().txn(txn).await?;
+        } else {
+            let mut cl = DB::pg().await?;
+            let txn = cl.transaction().await?;
+            for (user_id, uid) in &updates {
+                DB::pg_txn_append(&txn, sql, &[uid, user_id, &provider_id]).await?;
+            }
+            txn.commit().await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn find_by_user_provider(
+        user_id: &str,
+        provider_id: &str,
+    ) -> Result<Self, ErrorResponse> {
+        let sql = "SELECT * FROM user_federations WHERE user_id = $1 AND provider_id = $2";
+        let res = if is_hiqlite() {
+            DB::hql()
+                .query_as_one(sql, params!(user_id, provider_id))
+                .await?
+        } else {
+            DB::pg_query_one(sql, &[&user_id, &provider_id]).await?
+        };
+        Ok(res)
+    }
+
+    /// Re-maps an existing link to a new upstream user id, for instance after the upstream
+    /// provider has migrated its subject identifiers.
+    pub async fn update_uid(
+        user_id: &str,
+        provider_id: &str,
+        new_uid: &str,
+        normalization: AuthProviderUidNormalization,
+    ) -> Result<(), ErrorResponse> {
+        let new_uid = normalization.apply(new_uid);
+        let new_uid = new_uid.as_ref();
+        let sql = r#"
+UPDATE user_federations
+SET federation_uid = $1
+WHERE user_id = $2 AND provider_id = $3"#;
+        let rows = if is_hiqlite() {
+            DB::hql()
+                .execute(sql, params!(new_uid, user_id, provider_id))
+                .await
+                .map_err(|err| Self::map_unique_violation(ErrorResponse::from(err)))?
+        } else {
+            DB::pg_execute(sql, &[&new_uid, &user_id, &provider_id])
+                .await
+                .map_err(Self::map_unique_violation)?
+        };
+
+        if rows == 0 {
+            return Err(ErrorResponse::new(
+                ErrorResponseType::NotFound,
+                "provider is not linked to this account",
+            ));
+        }
+        Ok(())
+    }
+
     pub async fn delete(&self) -> Result<(), ErrorResponse> {
         let sql = "DELETE FROM user_federations WHERE user_id = $1 AND provider_id = $2";
         if is_hiqlite() {
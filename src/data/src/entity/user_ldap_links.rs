@@ -0,0 +1,117 @@
+use crate::database::DB;
+use hiqlite_macros::params;
+use rauthy_common::is_hiqlite;
+use rauthy_error::{ErrorResponse, ErrorResponseType};
+use serde::{Deserialize, Serialize};
+
+/// The link between a local user and its entry in an LDAP directory. This works like a
+/// `UserFederation`, but the `provider_id` is the configured `[ldap].provider_id` instead of an
+/// `auth_providers` entry. The `federation_uid` is the immutable directory GUID of the user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserLdapLink {
+    pub user_id: String,
+    pub provider_id: String,
+    pub federation_uid: String,
+}
+
+impl From<hiqlite::Row<'_>> for UserLdapLink {
+    fn from(mut row: hiqlite::Row<'_>) -> Self {
+        Self {
+            user_id: row.get("user_id"),
+            provider_id: row.get("provider_id"),
+            federation_uid: row.get("federation_uid"),
+        }
+    }
+}
+
+impl From<tokio_postgres::Row> for UserLdapLink {
+    fn from(row: tokio_postgres::Row) -> Self {
+        Self {
+            user_id: row.get("user_id"),
+            provider_id: row.get("provider_id"),
+            federation_uid: row.get("federation_uid"),
+        }
+    }
+}
+
+impl UserLdapLink {
+    #[inline(always)]
+    fn map_unique_violation(err: ErrorResponse) -> ErrorResponse {
+        if err.message.to_lowercase().contains("unique") {
+            ErrorResponse::new(
+                ErrorResponseType::NotAccepted,
+                "Directory user is already linked to another account",
+            )
+        } else {
+            err
+        }
+    }
+
+    pub async fn create(
+        user_id: String,
+        provider_id: String,
+        federation_uid: String,
+    ) -> Result<Self, ErrorResponse> {
+        let slf = Self {
+            user_id,
+            provider_id,
+            federation_uid,
+        };
+
+        let sql = r#"
+INSERT INTO user_ldap_links (user_id, provider_id, federation_uid)
+VALUES ($1, $2, $3)"#;
+        if is_hiqlite() {
+            DB::hql()
+                .execute(
+                    sql,
+                    params!(&slf.user_id, &slf.provider_id, &slf.federation_uid),
+                )
+                .await
+                .map_err(|err| Self::map_unique_violation(ErrorResponse::from(err)))?;
+        } else {
+            DB::pg_execute(sql, &[&slf.user_id, &slf.provider_id, &slf.federation_uid])
+                .await
+                .map_err(Self::map_unique_violation)?;
+        }
+
+        Ok(slf)
+    }
+
+    pub async fn find(user_id: &str, provider_id: &str) -> Result<Option<Self>, ErrorResponse> {
+        let sql = "SELECT * FROM user_ldap_links WHERE user_id = $1 AND provider_id = $2";
+        let slf = if is_hiqlite() {
+            DB::hql()
+                .query_map_optional(sql, params!(user_id, provider_id))
+                .await?
+        } else {
+            DB::pg_query_opt(sql, &[&user_id, &provider_id]).await?
+        };
+        Ok(slf)
+    }
+
+    pub async fn find_by_uid(
+        provider_id: &str,
+        federation_uid: &str,
+    ) -> Result<Option<Self>, ErrorResponse> {
+        let sql = "SELECT * FROM user_ldap_links WHERE provider_id = $1 AND federation_uid = $2";
+        let slf = if is_hiqlite() {
+            DB::hql()
+                .query_map_optional(sql, params!(provider_id, federation_uid))
+                .await?
+        } else {
+            DB::pg_query_opt(sql, &[&provider_id, &federation_uid]).await?
+        };
+        Ok(slf)
+    }
+
+    pub async fn find_for_user(user_id: &str) -> Result<Vec<Self>, ErrorResponse> {
+        let sql = "SELECT * FROM user_ldap_links WHERE user_id = $1";
+        let res = if is_hiqlite() {
+            DB::hql().query_map(sql, params!(user_id)).await?
+        } else {
+            DB::pg_query(sql, &[&user_id], 1).await?
+        };
+        Ok(res)
+    }
+}
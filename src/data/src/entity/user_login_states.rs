@@ -12,6 +12,9 @@ pub struct UserLoginState {
     pub user_id: String,
     pub client_id: String,
     pub session_id: Option<String>,
+    /// The `iss` the tokens for this login have been issued under. Only `None` for states that
+    /// have been created before the issuer was tracked, which always used the default issuer.
+    pub issuer: Option<String>,
 }
 
 impl From<tokio_postgres::Row> for UserLoginState {
@@ -21,6 +24,7 @@ impl From<tokio_postgres::Row> for UserLoginState {
             user_id: row.get("user_id"),
             client_id: row.get("client_id"),
             session_id: row.get("session_id"),
+            issuer: row.get("issuer"),
         }
     }
 }
@@ -31,31 +35,33 @@ impl UserLoginState {
         user_id: String,
         client_id: String,
         session_id: Option<String>,
+        issuer: &str,
     ) -> Result<(), ErrorResponse> {
         let now = Utc::now().timestamp_millis();
 
         if is_hiqlite() {
+            let issuer = issuer.to_string();
             let mut txn = Vec::with_capacity(session_id.as_ref().map(|_| 2).unwrap_or(1));
             txn.push((
                 r#"
-INSERT INTO user_login_states(timestamp, user_id, client_id)
-SELECT $1, $2, $3
+INSERT INTO user_login_states(timestamp, user_id, client_id, issuer)
+SELECT $1, $2, $3, $4
 WHERE NOT EXISTS (
     SELECT 1 FROM user_login_states
-    WHERE user_id = $2 AND client_id = $3 AND session_id IS NULL
+    WHERE user_id = $2 AND client_id = $3 AND session_id IS NULL AND issuer = $4
 )"#,
-                params!(now - 1, &user_id, &client_id),
+                params!(now - 1, &user_id, &client_id, &issuer),
             ));
             if session_id.is_some() {
                 txn.push((
                     r#"
-INSERT INTO user_login_states(timestamp, user_id, client_id, session_id)
-SELECT $1, $2, $3, $4
+INSERT INTO user_login_states(timestamp, user_id, client_id, session_id, issuer)
+SELECT $1, $2, $3, $4, $5
 WHERE NOT EXISTS (
     SELECT 1 FROM user_login_states
     WHERE user_id = $2 AND client_id = $3 AND session_id = $4
 )"#,
-                    params!(now, user_id, client_id, session_id),
+                    params!(now, user_id, client_id, session_id, issuer),
                 ));
             }
 
@@ -67,25 +73,26 @@ WHERE NOT EXISTS (
             DB::pg_txn_append(
                 &txn,
                 r#"
-INSERT INTO user_login_states(timestamp, user_id, client_id)
-SELECT $1, $2, $3
+INSERT INTO user_login_states(timestamp, user_id, client_id, issuer)
+SELECT $1, $2, $3, $4
 WHERE NOT EXISTS (
     SELECT 1 FROM user_login_states
     WHERE user_id = $2::VARCHAR AND client_id = $3::VARCHAR AND session_id IS NULL
+        AND issuer = $4::VARCHAR
 )"#,
-                &[&(now - 1), &user_id, &client_id],
+                &[&(now - 1), &user_id, &client_id, &issuer],
             )
             .await?;
             DB::pg_txn_append(
                 &txn,
                 r#"
-INSERT INTO user_login_states(timestamp, user_id, client_id, session_id)
-SELECT $1, $2, $3, $4
+INSERT INTO user_login_states(timestamp, user_id, client_id, session_id, issuer)
+SELECT $1, $2, $3, $4, $5
 WHERE NOT EXISTS (
     SELECT 1 FROM user_login_states
     WHERE user_id = $2::VARCHAR AND client_id = $3::VARCHAR AND session_id = $4::VARCHAR
 )"#,
-                &[&(now), &user_id, &client_id, &session_id],
+                &[&(now), &user_id, &client_id, &session_id, &issuer],
             )
             .await?;
 
@@ -1,9 +1,15 @@
 use crate::database::{Cache, DB};
 use crate::email::email_change_confirm::send_email_confirm_change;
-use crate::email::email_change_info::send_email_change_info_new;
+use crate::email::email_change_info::{
+    send_email_add_confirm, send_email_change_info_new, send_email_change_info_old,
+};
 use crate::email::password_reset::send_pwd_reset;
+use crate::email::registration::{send_registration_approved, send_registration_rejected};
+use crate::email::welcome::send_welcome;
 use crate::entity::continuation_token::ContinuationToken;
+use crate::entity::devices::DeviceEntity;
 use crate::entity::groups::Group;
+use crate::entity::login_locations::LoginLocation;
 use crate::entity::magic_links::{MagicLink, MagicLinkUsage};
 use crate::entity::password::PasswordPolicy;
 use crate::entity::password::RecentPasswordsEntity;
@@ -14,11 +20,15 @@ use crate::entity::sessions::Session;
 use crate::entity::theme::ThemeCssFull;
 use crate::entity::tos::ToS;
 use crate::entity::tos_user_accept::ToSUserAccept;
+use crate::entity::user_attr::UserAttrValueEntity;
+use crate::entity::user_emails::{self, UserEmail};
 use crate::entity::user_federation::UserFederation;
+use crate::entity::user_ldap_links::UserLdapLink;
+use crate::entity::user_login_states::UserLoginState;
 use crate::entity::users_values::UserValues;
 use crate::entity::webauthn::{PasskeyEntity, WebauthnServiceReq};
 use crate::events::event::Event;
-use crate::html::templates::{HtmlTemplate, UserEmailChangeConfirmHtml};
+use crate::html::templates::{HtmlTemplate, UserEmailChangeAbortHtml, UserEmailChangeConfirmHtml};
 use crate::language::Language;
 use crate::rauthy_config::RauthyConfig;
 use actix_web::HttpRequest;
@@ -29,9 +39,12 @@ use hiqlite::Params;
 use hiqlite_macros::params;
 use rauthy_api_types::PatchOp;
 use rauthy_api_types::generic::SearchParamsIdx;
+use rauthy_api_types::tos::ToSUserAcceptResponse;
 use rauthy_api_types::users::{
-    NewUserRegistrationRequest, NewUserRequest, UpdateUserRequest, UpdateUserSelfRequest,
-    UserAccountTypeResponse, UserResponse, UserResponseSimple, UserValuesRequest,
+    DeviceResponse, NewUserRegistrationRequest, NewUserRequest, PasskeyResponse, UpdateUserRequest,
+    UpdateUserSelfRequest, UserAccountTypeResponse, UserAttrValueResponse, UserDataExport,
+    UserDataExportClientLogin, UserDataExportFederation, UserDataExportLoginLocation,
+    UserDataExportSession, UserEmailResponse, UserResponse, UserResponseSimple, UserValuesRequest,
     UserValuesResponse,
 };
 use rauthy_common::constants::{
@@ -43,10 +56,12 @@ use rauthy_common::utils::{new_store_id, real_ip_from_req};
 use rauthy_error::{ErrorResponse, ErrorResponseType};
 use serde::{Deserialize, Serialize};
 use std::cmp::max;
+use std::collections::BTreeMap;
 use std::default::Default;
 use std::fmt::{Debug, Formatter};
 use std::ops::Add;
 use time::OffsetDateTime;
+use tokio::task;
 use tracing::{debug, error, trace};
 
 static SQL_SAVE: &str = r#"
@@ -54,8 +69,8 @@ UPDATE USERS SET
 email = $1, given_name = $2, family_name = $3, password = $4, roles = $5, groups = $6, enabled = $7,
 email_verified = $8, password_expires = $9, last_login = $10, last_failed_login = $11,
 failed_login_attempts = $12, language = $13, webauthn_user_id = $14, user_expires = $15,
-picture_id = $16
-WHERE id = $17"#;
+picture_id = $16, disabled_at = $17, pending_since = $18
+WHERE id = $19"#;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum AccountType {
@@ -109,6 +124,13 @@ pub struct User {
     #[serde(default)]
     pub federation_count: i64,
     pub picture_id: Option<String>,
+    /// Set when the user has been soft-deleted. It will be hard-deleted after the
+    /// `user_delete.disabled_retention_days`.
+    #[serde(default)]
+    pub disabled_at: Option<i64>,
+    /// Set while a registration awaits the approval of an admin.
+    #[serde(default)]
+    pub pending_since: Option<i64>,
 }
 
 impl Debug for User {
@@ -118,7 +140,7 @@ impl Debug for User {
             "User {{ id: {}, email: {}, given_name: {}, family_name: {:?}, password: <hidden>, \
         roles: {}, groups: {:?}, enabled: {}, email_verified: {}, password_expires: {:?}, \
         created_at: {}, last_login: {:?}, last_failed_login: {:?}, failed_login_attempts: {:?}, \
-        language: {}, webauthn_user_id: {:?}, user_expires: {:?}, auth_provider_id: {:?}, federation_uid: {:?}, federation_count: {}, picture_id: {:?} }}",
+        language: {}, webauthn_user_id: {:?}, user_expires: {:?}, auth_provider_id: {:?}, federation_uid: {:?}, federation_count: {}, picture_id: {:?}, disabled_at: {:?}, pending_since: {:?} }}",
             self.id,
             self.email,
             self.given_name,
@@ -139,6 +161,8 @@ impl Debug for User {
             self.federation_uid,
             self.federation_count,
             self.picture_id,
+            self.disabled_at,
+            self.pending_since,
         )
     }
 }
@@ -167,6 +191,8 @@ impl From<tokio_postgres::Row> for User {
             federation_uid: None,
             federation_count: row.get("federation_count"),
             picture_id: row.get("picture_id"),
+            disabled_at: row.get("disabled_at"),
+            pending_since: row.get("pending_since"),
         }
     }
 }
@@ -257,7 +283,19 @@ impl User {
     pub async fn create_from_new(new_user_req: NewUserRequest) -> Result<User, ErrorResponse> {
         let tz = new_user_req.tz.clone();
         let new_user = User::from_new_user_req(new_user_req).await?;
-        let user = User::create(new_user, None, tz.as_deref()).await?;
+        let user = Self::insert(new_user).await?;
+
+        let magic_link = MagicLink::create(
+            user.id.clone(),
+            RauthyConfig::get().vars.lifetimes.magic_link_pwd_first as i64,
+            MagicLinkUsage::NewUser(None),
+        )
+        .await?;
+        if RauthyConfig::get().vars.email.welcome_new_users {
+            task::spawn(send_welcome(magic_link, user.clone(), tz.clone()));
+        } else {
+            send_pwd_reset(&magic_link, &user, tz.as_deref()).await;
+        }
 
         if tz.is_some() && tz.as_deref() != Some("UTC") && tz.as_deref() != Some("Etc/UTC") {
             UserValues::insert(
@@ -280,6 +318,9 @@ impl User {
     }
 
     /// Inserts a user from the open registration endpoint into the database.
+    ///
+    /// With `user_registration.require_approval`, the user will be pending and the E-Mail to set
+    /// a password will only be sent after an admin has approved the registration.
     pub async fn create_from_reg(
         req_data: NewUserRegistrationRequest,
         lang: Language,
@@ -297,15 +338,20 @@ impl User {
             ..Default::default()
         };
         new_user.language = lang;
-        let new_user = User::create(
-            new_user,
-            req_data.redirect_uri,
-            req_data
-                .user_values
-                .as_ref()
-                .and_then(|uv| uv.tz.as_deref()),
-        )
-        .await?;
+        let new_user = if RauthyConfig::get().vars.user_registration.require_approval {
+            new_user.pending_since = Some(Utc::now().timestamp());
+            Self::insert(new_user).await?
+        } else {
+            User::create(
+                new_user,
+                req_data.redirect_uri,
+                req_data
+                    .user_values
+                    .as_ref()
+                    .and_then(|uv| uv.tz.as_deref()),
+            )
+            .await?
+        };
 
         if let Some(uv) = req_data.user_values {
             UserValues::insert(new_user.id.clone(), uv, req_data.preferred_username).await?;
@@ -334,6 +380,66 @@ impl User {
         Ok(())
     }
 
+    /// Soft-deletes the user. The row and all federation links will stay until the
+    /// `disabled_retention_days` have passed, while the user cannot authenticate anymore.
+    /// Existing sessions and refresh tokens will be invalidated with the `save()`.
+    pub async fn disable(&mut self) -> Result<(), ErrorResponse> {
+        self.enabled = false;
+        self.disabled_at = Some(Utc::now().timestamp());
+        self.save(None).await
+    }
+
+    /// Reverts a previous `disable()`.
+    pub async fn enable(&mut self) -> Result<(), ErrorResponse> {
+        self.enabled = true;
+        self.disabled_at = None;
+        self.save(None).await
+    }
+
+    /// Approves a pending registration. Local accounts will receive the welcome E-Mail to set a
+    /// password, while federated ones are only notified, that they can log in now.
+    pub async fn approve_registration(&mut self) -> Result<(), ErrorResponse> {
+        if self.pending_since.is_none() {
+            return Err(ErrorResponse::new(
+                ErrorResponseType::BadRequest,
+                "The user has no pending registration",
+            ));
+        }
+
+        self.pending_since = None;
+        self.save(None).await?;
+
+        if self.federation_count > 0 {
+            task::spawn(send_registration_approved(self.clone()));
+        } else {
+            let magic_link = MagicLink::create(
+                self.id.clone(),
+                RauthyConfig::get().vars.lifetimes.magic_link_pwd_first as i64,
+                MagicLinkUsage::NewUser(None),
+            )
+            .await?;
+            let tz = UserValues::find(&self.id).await?.and_then(|uv| uv.tz);
+            task::spawn(send_welcome(magic_link, self.clone(), tz));
+        }
+
+        Ok(())
+    }
+
+    /// Rejects a pending registration, which deletes the user and notifies the applicant.
+    pub async fn reject_registration(self) -> Result<(), ErrorResponse> {
+        if self.pending_since.is_none() {
+            return Err(ErrorResponse::new(
+                ErrorResponseType::BadRequest,
+                "The user has no pending registration",
+            ));
+        }
+
+        self.delete().await?;
+        task::spawn(send_registration_rejected(self));
+
+        Ok(())
+    }
+
     pub async fn exists(id: String) -> Result<(), ErrorResponse> {
         let idx = format!("{IDX_USERS}_{id}");
 
@@ -389,10 +495,24 @@ impl User {
         }
 
         let sql = "SELECT * FROM users WHERE email = $1";
-        let slf = if is_hiqlite() {
-            client.query_as_one(sql, params!(email)).await?
+        let res = if is_hiqlite() {
+            client
+                .query_as_one(sql, params!(email.clone()))
+                .await
+                .map_err(ErrorResponse::from)
         } else {
-            DB::pg_query_one(sql, &[&email]).await?
+            DB::pg_query_one(sql, &[&email]).await
+        };
+        let slf = match res {
+            Ok(slf) => slf,
+            Err(err) => {
+                // The address may be a verified additional one. These are not cached by email,
+                // because they would go stale when the primary address is swapped.
+                if let Ok(Some(user_id)) = UserEmail::find_verified_user_id(&email).await {
+                    return Self::find(user_id).await;
+                }
+                return Err(err);
+            }
         };
 
         client.put(Cache::User, idx, &slf, CACHE_TTL_USER).await?;
@@ -519,6 +639,32 @@ LIMIT $3"#;
         Ok(res)
     }
 
+    /// Returns all soft-deleted users, which have been disabled before the given timestamp.
+    pub async fn find_disabled_before(ts: i64) -> Result<Vec<Self>, ErrorResponse> {
+        let sql = "SELECT * FROM users WHERE disabled_at < $1";
+
+        let res = if is_hiqlite() {
+            DB::hql().query_as(sql, params!(ts)).await?
+        } else {
+            DB::pg_query(sql, &[&ts], 0).await?
+        };
+
+        Ok(res)
+    }
+
+    /// Returns all users with a registration, that awaits the approval of an admin.
+    pub async fn find_pending() -> Result<Vec<Self>, ErrorResponse> {
+        let sql = "SELECT * FROM users WHERE pending_since IS NOT NULL ORDER BY pending_since";
+
+        let res = if is_hiqlite() {
+            DB::hql().query_as(sql, params!()).await?
+        } else {
+            DB::pg_query(sql, &[], 0).await?
+        };
+
+        Ok(res)
+    }
+
     pub async fn find_for_fed_cm_validated(user_id: String) -> Result<Self, ErrorResponse> {
         // We will stick to the WWW-Authenticate header for now and use duplicated code from
         // some OAuth2 api for now until the spec has settled on an error behavior.
@@ -630,12 +776,15 @@ OFFSET $2"#;
     }
 
     pub async fn insert(new_user: User) -> Result<Self, ErrorResponse> {
+        // the `users.email` unique constraint does not cover additional addresses
+        UserEmail::validate_free(&new_user.email).await?;
+
         let lang = new_user.language.as_str();
         let sql = r#"
 INSERT INTO users
 (id, email, given_name, family_name, roles, groups, enabled, email_verified, created_at,
-last_login, language, user_expires, picture_id)
-VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)"#;
+last_login, language, user_expires, picture_id, pending_since)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)"#;
 
         if is_hiqlite() {
             DB::hql()
@@ -654,7 +803,8 @@ VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)"#;
                         new_user.last_login,
                         lang,
                         new_user.user_expires,
-                        &new_user.picture_id
+                        &new_user.picture_id,
+                        new_user.pending_since
                     ),
                 )
                 .await
@@ -686,6 +836,7 @@ VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)"#;
                     &lang,
                     &new_user.user_expires,
                     &new_user.picture_id,
+                    &new_user.pending_since,
                 ],
             )
             .await
@@ -750,6 +901,8 @@ LIMIT $2"#;
                     federation_uid: None,
                     federation_count: 0,
                     picture_id: row.get("picture_id"),
+                    disabled_at: None,
+                    pending_since: None,
                 };
                 let values = UserValues {
                     id: user.id.clone(),
@@ -790,6 +943,8 @@ LIMIT $2"#;
                     federation_uid: None,
                     federation_count: 0,
                     picture_id: row.get("picture_id"),
+                    disabled_at: None,
+                    pending_since: None,
                 };
                 let values = UserValues {
                     id: user.id.clone(),
@@ -871,6 +1026,8 @@ LIMIT $2"#;
                 self.webauthn_user_id,
                 self.user_expires,
                 self.picture_id,
+                self.disabled_at,
+                self.pending_since,
                 self.id
             ),
         ));
@@ -908,6 +1065,8 @@ LIMIT $2"#;
                 &self.webauthn_user_id,
                 &self.user_expires,
                 &self.picture_id,
+                &self.disabled_at,
+                &self.pending_since,
                 &self.id,
             ],
         )
@@ -945,6 +1104,8 @@ LIMIT $2"#;
                         &self.webauthn_user_id,
                         self.user_expires,
                         &self.picture_id,
+                        self.disabled_at,
+                        self.pending_since,
                         &self.id
                     ),
                 )
@@ -969,6 +1130,8 @@ LIMIT $2"#;
                     &self.webauthn_user_id,
                     &self.user_expires,
                     &self.picture_id,
+                    &self.disabled_at,
+                    &self.pending_since,
                     &self.id,
                 ],
             )
@@ -1249,6 +1412,8 @@ LIMIT $2"#;
         };
         upd_user.email = upd_user.email.to_lowercase();
         let old_email = if user.email != upd_user.email {
+            // the `users.email` unique constraint does not cover additional addresses
+            UserEmail::validate_free(&upd_user.email).await?;
             Some(user.email.clone())
         } else {
             None
@@ -1271,6 +1436,10 @@ LIMIT $2"#;
         user.groups = Group::sanitize(upd_user.groups).await?;
 
         user.enabled = upd_user.enabled;
+        if user.enabled {
+            // re-enabling a soft-deleted user must stop the scheduled deletion
+            user.disabled_at = None;
+        }
         user.email_verified = upd_user.email_verified;
         user.user_expires = upd_user.user_expires;
 
@@ -1371,9 +1540,24 @@ LIMIT $2"#;
             // if the email should be updated, we do not do it directly -> send out confirmation
             // email to old AND new address
             if email != user.email {
+                // never even send out a link for an address that is taken already
+                Self::validate_email_free(email.clone()).await?;
+
                 // invalidate possibly other existing MagicLinks of the same type
                 MagicLink::invalidate_all_email_change(&user.id).await?;
 
+                let user_tz = upd_user.user_values.as_ref().and_then(|v| v.tz.as_deref());
+
+                if RauthyConfig::get().vars.email.change_notify_old {
+                    let ml_abort = MagicLink::create(
+                        user.id.clone(),
+                        60,
+                        MagicLinkUsage::EmailChangeAbort(email.clone()),
+                    )
+                    .await?;
+                    send_email_change_info_old(&ml_abort, &user, user_tz, &email).await;
+                }
+
                 let ml = MagicLink::create(
                     user.id.clone(),
                     60,
@@ -1381,13 +1565,14 @@ LIMIT $2"#;
                 )
                 .await?;
 
-                send_email_change_info_new(
-                    &ml,
-                    &user,
-                    upd_user.user_values.as_ref().and_then(|v| v.tz.as_deref()),
-                    email,
-                )
-                .await;
+                let event_text = format!("{} -> {}", user.email, email);
+                send_email_change_info_new(&ml, &user, user_tz, email).await;
+
+                RauthyConfig::get()
+                    .tx_events
+                    .send_async(Event::user_email_change_request(event_text, None))
+                    .await
+                    .unwrap();
 
                 true
             } else {
@@ -1461,6 +1646,8 @@ LIMIT $2"#;
     }
 
     pub async fn validate_email_free(email: String) -> Result<(), ErrorResponse> {
+        UserEmail::validate_free(&email).await?;
+
         let sql = "SELECT 1 FROM users WHERE email = $1";
 
         let is_free = if is_hiqlite() {
@@ -1627,6 +1814,20 @@ impl User {
 
     #[inline]
     pub fn check_enabled(&self) -> Result<(), ErrorResponse> {
+        if self.pending_since.is_some() {
+            trace!("The user registration is pending");
+            return Err(ErrorResponse::new(
+                ErrorResponseType::Disabled,
+                "The registration is awaiting the approval of an admin",
+            ));
+        }
+        if self.disabled_at.is_some() {
+            trace!("The user has been soft-deleted");
+            return Err(ErrorResponse::new(
+                ErrorResponseType::Disabled,
+                "User has been deactivated and is scheduled for deletion",
+            ));
+        }
         if !self.enabled {
             trace!("The user is not enabled");
             return Err(ErrorResponse::new(
@@ -1661,7 +1862,10 @@ impl User {
 
         let usage = MagicLinkUsage::try_from(&ml.usage)?;
         let new_email = match usage {
-            MagicLinkUsage::NewUser(_) | MagicLinkUsage::PasswordReset(_) => {
+            MagicLinkUsage::NewUser(_)
+            | MagicLinkUsage::PasswordReset(_)
+            | MagicLinkUsage::EmailAdd(_)
+            | MagicLinkUsage::EmailChangeAbort(_) => {
                 return Err(ErrorResponse::new(
                     ErrorResponseType::BadRequest,
                     "The Magic Link is not meant to be used to confirm an E-Mail address",
@@ -1670,6 +1874,9 @@ impl User {
             MagicLinkUsage::EmailChange(email) => email,
         };
 
+        // the address might have been taken in the meantime
+        Self::validate_email_free(new_email.clone()).await?;
+
         let mut user = Self::find(user_id).await?;
 
         // save data
@@ -1678,6 +1885,8 @@ impl User {
         user.email_verified = true;
         user.save(Some(old_email.clone())).await?;
         ml.invalidate().await?;
+        // the abort link for the old address is not needed anymore
+        MagicLink::invalidate_all_email_change(&user.id).await?;
 
         // finally, invalidate all existing sessions with the old email
         Session::invalidate_for_user(&user.id).await?;
@@ -1708,6 +1917,225 @@ impl User {
         Ok(html)
     }
 
+    /// Aborts a pending E-Mail change via the link that has been sent to the old address.
+    pub async fn abort_email_change(
+        req: HttpRequest,
+        user_id: String,
+        abort_id: String,
+    ) -> Result<String, ErrorResponse> {
+        let ml = MagicLink::find(&abort_id).await?;
+        ml.validate(&user_id, &req, false)?;
+
+        let new_email = match MagicLinkUsage::try_from(&ml.usage)? {
+            MagicLinkUsage::EmailChangeAbort(email) => email,
+            _ => {
+                return Err(ErrorResponse::new(
+                    ErrorResponseType::BadRequest,
+                    "The Magic Link is not meant to be used to abort an E-Mail change",
+                ));
+            }
+        };
+
+        let user = Self::find(user_id).await?;
+        MagicLink::invalidate_all_email_change(&user.id).await?;
+
+        let event_text = format!("aborted: {} -> {new_email}", user.email);
+        let ip = real_ip_from_req(&req).ok();
+        RauthyConfig::get()
+            .tx_events
+            .send_async(Event::user_email_change_request(event_text, ip))
+            .await
+            .unwrap();
+
+        let lang = Language::try_from(&req).unwrap_or_default();
+        let html =
+            UserEmailChangeAbortHtml::build(&lang, ThemeCssFull::find_theme_ts_rauthy().await?);
+
+        Ok(html)
+    }
+
+    /// Adds an additional address for this user and sends out a confirmation link to it.
+    /// Calling this again for a pending address will send a new link.
+    pub async fn add_email(&self, email: String) -> Result<UserEmail, ErrorResponse> {
+        let email = email.to_lowercase();
+        if email == self.email {
+            return Err(ErrorResponse::new(
+                ErrorResponseType::BadRequest,
+                "This is the primary E-Mail address already",
+            ));
+        }
+        Self::validate_email_free(email.clone()).await?;
+
+        let user_email = UserEmail::create(self.id.clone(), email).await?;
+        if user_email.verified {
+            return Ok(user_email);
+        }
+
+        let ml = MagicLink::create(
+            self.id.clone(),
+            60,
+            MagicLinkUsage::EmailAdd(user_email.email.clone()),
+        )
+        .await?;
+        let values = UserValues::find(&self.id).await?;
+        let tz = values.as_ref().and_then(|uv| uv.tz.as_deref());
+        send_email_add_confirm(&ml, self, tz, &user_email.email).await;
+
+        Ok(user_email)
+    }
+
+    /// Confirms an additional address via the link that has been sent to it.
+    pub async fn confirm_email_add(
+        req: &HttpRequest,
+        user_id: String,
+        confirm_id: String,
+    ) -> Result<(), ErrorResponse> {
+        let mut ml = MagicLink::find(&confirm_id).await?;
+        ml.validate(&user_id, req, false)?;
+
+        let email = match MagicLinkUsage::try_from(&ml.usage)? {
+            MagicLinkUsage::EmailAdd(email) => email,
+            _ => {
+                return Err(ErrorResponse::new(
+                    ErrorResponseType::BadRequest,
+                    "The Magic Link is not meant to be used to confirm an additional address",
+                ));
+            }
+        };
+
+        // the address might have been taken in the meantime
+        Self::validate_email_free(email.clone()).await?;
+
+        let mut user_email = UserEmail::find(&user_id, &email).await?.ok_or_else(|| {
+            ErrorResponse::new(
+                ErrorResponseType::NotFound,
+                "The E-Mail address has been removed in the meantime",
+            )
+        })?;
+        user_email.set_verified().await?;
+        ml.invalidate().await?;
+
+        Ok(())
+    }
+
+    /// Removes an additional address. The primary address can never be removed.
+    pub async fn remove_email(&self, email: String) -> Result<(), ErrorResponse> {
+        let email = email.to_lowercase();
+        if email == self.email {
+            return Err(ErrorResponse::new(
+                ErrorResponseType::BadRequest,
+                "The primary E-Mail address cannot be removed",
+            ));
+        }
+
+        let user_email = UserEmail::find(&self.id, &email).await?.ok_or_else(|| {
+            ErrorResponse::new(ErrorResponseType::NotFound, "E-Mail address not found")
+        })?;
+        user_email.delete().await?;
+
+        Ok(())
+    }
+
+    /// Swaps the primary address with the given, verified additional one. The old primary
+    /// address will be kept as an additional one.
+    pub async fn set_primary_email(
+        &mut self,
+        req: &HttpRequest,
+        email: String,
+    ) -> Result<(), ErrorResponse> {
+        let email = email.to_lowercase();
+        if email == self.email {
+            return Ok(());
+        }
+
+        let user_email = UserEmail::find(&self.id, &email).await?.ok_or_else(|| {
+            ErrorResponse::new(ErrorResponseType::NotFound, "E-Mail address not found")
+        })?;
+        if !user_email.verified {
+            return Err(ErrorResponse::new(
+                ErrorResponseType::BadRequest,
+                "Only verified E-Mail addresses can be set as primary",
+            ));
+        }
+
+        // `find_by_email()` resolves verified additional addresses as well, which means it will
+        // return this user for the new address, unless it is the primary one of someone else.
+        if let Ok(user) = User::find_by_email(email.clone()).await
+            && user.id != self.id
+        {
+            return Err(ErrorResponse::new(
+                ErrorResponseType::BadRequest,
+                "E-Mail is already in use",
+            ));
+        }
+
+        let old_email = self.email.clone();
+        let old_address = UserEmail {
+            email: old_email.clone(),
+            user_id: self.id.clone(),
+            verified: self.email_verified,
+            created_at: Utc::now().timestamp(),
+        };
+        self.email = email;
+        self.email_verified = true;
+
+        // The additional address must be removed before the primary one is updated and the old
+        // one re-inserted. Without a txn, a failure in between would lose one of the addresses.
+        if is_hiqlite() {
+            let mut txn: Vec<(&str, Params)> = Vec::with_capacity(3);
+            txn.push((
+                user_emails::SQL_DELETE,
+                params!(self.id.clone(), self.email.clone()),
+            ));
+            self.clone().save_txn_append(&mut txn);
+            txn.push((
+                user_emails::SQL_INSERT,
+                params!(
+                    old_address.email,
+                    old_address.user_id,
+                    old_address.verified,
+                    old_address.created_at
+                ),
+            ));
+
+            for res in DB::hql().txn(txn).await? {
+                res.map_err(|err| UserEmail::map_unique_violation(ErrorResponse::from(err)))?;
+            }
+        } else {
+            let mut cl = DB::pg().await?;
+            let txn = cl.transaction().await?;
+
+            DB::pg_txn_append(&txn, user_emails::SQL_DELETE, &[&self.id, &self.email]).await?;
+            self.save_txn(&txn).await?;
+            DB::pg_txn_append(
+                &txn,
+                user_emails::SQL_INSERT,
+                &[
+                    &old_address.email,
+                    &old_address.user_id,
+                    &old_address.verified,
+                    &old_address.created_at,
+                ],
+            )
+            .await
+            .map_err(UserEmail::map_unique_violation)?;
+
+            txn.commit().await?;
+        }
+
+        Self::invalidate_cache(&self.id, &old_email).await?;
+
+        let event_text = format!("{old_email} -> {}", self.email);
+        let ip = real_ip_from_req(req).ok();
+        RauthyConfig::get()
+            .tx_events
+            .send_async(Event::user_email_change(event_text, ip))
+            .await
+            .unwrap();
+
+        Ok(())
+    }
+
     pub fn delete_group(&mut self, group: &str) {
         if self.groups.is_none() {
             return;
@@ -1876,6 +2304,101 @@ impl User {
             auth_provider_id: self.auth_provider_id,
             federation_uid: self.federation_uid,
             picture_id: self.picture_id,
+            disabled_at: self.disabled_at,
+            pending_since: self.pending_since,
+        })
+    }
+
+    /// Collects all personal data for this user, apart from events, for a self-service export.
+    /// Secrets like password hashes, session IDs or passkey credentials are never included.
+    pub async fn into_data_export(self) -> Result<UserDataExport, ErrorResponse> {
+        let emails = UserEmail::find_for_user(&self.id).await?;
+        let attributes = UserAttrValueEntity::find_for_user(&self.id).await?;
+        let federations = UserFederation::find_for_user(&self.id).await?;
+        let ldap_links = UserLdapLink::find_for_user(&self.id).await?;
+        let sessions = Session::find_for_user(&self.id).await?;
+        let passkeys = PasskeyEntity::find_for_user(&self.id).await?;
+        let devices = DeviceEntity::find_for_user(&self.id).await?;
+        let login_locations = LoginLocation::find_for_user(&self.id).await?;
+        let login_states = UserLoginState::find_by_user(self.id.clone()).await?;
+        let tos_accepts = ToSUserAccept::find_all(self.id.clone()).await?;
+
+        let mut client_logins: BTreeMap<String, i64> = BTreeMap::new();
+        for state in login_states {
+            // login states use ms precision
+            let ts = state.timestamp / 1000;
+            client_logins
+                .entry(state.client_id)
+                .and_modify(|last| *last = (*last).max(ts))
+                .or_insert(ts);
+        }
+
+        let mut email_responses = Vec::with_capacity(emails.len() + 1);
+        email_responses.push(UserEmailResponse {
+            email: self.email.clone(),
+            verified: self.email_verified,
+            primary: true,
+            created_at: None,
+        });
+        email_responses.extend(emails.into_iter().map(|email| UserEmailResponse {
+            email: email.email,
+            verified: email.verified,
+            primary: false,
+            created_at: Some(email.created_at),
+        }));
+
+        let values = UserValues::find(&self.id).await?;
+
+        Ok(UserDataExport {
+            exported_at: Utc::now().timestamp(),
+            user: self.into_response(values).await?,
+            emails: email_responses,
+            attributes: attributes
+                .into_iter()
+                .map(UserAttrValueResponse::from)
+                .collect(),
+            federations: federations
+                .into_iter()
+                .map(|f| UserDataExportFederation {
+                    provider_id: f.provider_id,
+                    federation_uid: f.federation_uid,
+                })
+                .chain(ldap_links.into_iter().map(|l| UserDataExportFederation {
+                    provider_id: l.provider_id,
+                    federation_uid: l.federation_uid,
+                }))
+                .collect(),
+            sessions: sessions
+                .into_iter()
+                .map(|s| UserDataExportSession {
+                    is_mfa: s.is_mfa,
+                    exp: s.exp,
+                    last_seen: s.last_seen,
+                    remote_ip: s.remote_ip,
+                })
+                .collect(),
+            passkeys: passkeys.into_iter().map(PasskeyResponse::from).collect(),
+            devices: devices.into_iter().map(DeviceResponse::from).collect(),
+            login_locations: login_locations
+                .into_iter()
+                .map(|l| UserDataExportLoginLocation {
+                    ip: l.ip,
+                    last_seen: l.last_seen,
+                    user_agent: l.user_agent,
+                    location: l.location,
+                })
+                .collect(),
+            client_logins: client_logins
+                .into_iter()
+                .map(|(client_id, last_login)| UserDataExportClientLogin {
+                    client_id,
+                    last_login,
+                })
+                .collect(),
+            tos_accepts: tos_accepts
+                .into_iter()
+                .map(ToSUserAcceptResponse::from)
+                .collect(),
         })
     }
 
@@ -1986,6 +2509,10 @@ impl User {
         if self.account_type() == AccountType::Passkey {
             return Ok(());
         }
+        // pending users receive their link with the approval
+        if self.pending_since.is_some() {
+            return Ok(());
+        }
 
         // if any active magic links already exist - delete them and only ever have 1 active.
         MagicLink::delete_all_pwd_reset_for_user(self.id.clone()).await?;
@@ -2089,6 +2616,8 @@ impl Default for User {
             federation_uid: None,
             federation_count: 0,
             picture_id: None,
+            disabled_at: None,
+            pending_since: None,
         }
     }
 }
@@ -2146,6 +2675,8 @@ mod tests {
             federation_uid: None,
             federation_count: 0,
             picture_id: None,
+            disabled_at: None,
+            pending_since: None,
         };
         let session = Session::try_new(&user, 1, None);
         assert!(session.is_err());
@@ -2206,12 +2737,20 @@ mod tests {
             federation_uid: None,
             federation_count: 0,
             picture_id: None,
+            disabled_at: None,
+            pending_since: None,
         };
 
         // enabled
         assert!(user.check_enabled().is_err());
         user.enabled = true;
         assert!(user.check_enabled().is_ok());
+        user.disabled_at = Some(OffsetDateTime::now_utc().unix_timestamp());
+        assert!(user.check_enabled().is_err());
+        user.disabled_at = None;
+        user.pending_since = Some(OffsetDateTime::now_utc().unix_timestamp());
+        assert!(user.check_enabled().is_err());
+        user.pending_since = None;
 
         // password expiry
         assert!(check_password_expired(&user).is_ok());
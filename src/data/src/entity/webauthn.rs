@@ -2,10 +2,13 @@ use crate::api_cookie::ApiCookie;
 use crate::database::{Cache, DB};
 use crate::entity::auth_codes::AuthCodeToSAwait;
 use crate::entity::browser_id::BrowserId;
+use crate::entity::failed_login_counter::FailedLoginCounter;
+use crate::entity::ip_blacklist::IpBlacklist;
 use crate::entity::login_locations::LoginLocation;
 use crate::entity::password::PasswordPolicy;
 use crate::entity::sessions::Session;
 use crate::entity::users::{AccountType, User};
+use crate::events::event::{Event, LoginFailureReason};
 use crate::rauthy_config::RauthyConfig;
 use actix_web::cookie::Cookie;
 use actix_web::http::header::{
@@ -25,9 +28,12 @@ use rauthy_api_types::users::{
 };
 use rauthy_common::constants::{COOKIE_MFA, IDX_WEBAUTHN};
 use rauthy_common::is_hiqlite;
-use rauthy_common::utils::{base64_decode, base64_encode, deserialize, get_rand, serialize};
+use rauthy_common::utils::{
+    base64_decode, base64_encode, deserialize, get_rand, real_ip_from_req, serialize,
+};
 use rauthy_error::{ErrorResponse, ErrorResponseType};
 use serde::{Deserialize, Serialize};
+use std::cmp::min;
 use std::fmt::{Debug, Formatter};
 use std::ops::Add;
 use std::str::FromStr;
@@ -854,6 +860,7 @@ pub async fn auth_finish(
                     user.id,
                     "Webauthn Authentication Ceremony without User Verification",
                 );
+                mfa_failed(req, &auth_data.data).await?;
                 return Err(ErrorResponse::new(
                     ErrorResponseType::Forbidden,
                     "User Presence only is not allowed - Verification is needed",
@@ -917,6 +924,7 @@ pub async fn auth_finish(
         }
         Err(err) => {
             error!(?err, "Webauthn Auth Finish");
+            mfa_failed(req, &auth_data.data).await?;
             Err(ErrorResponse::new(
                 ErrorResponseType::Unauthorized,
                 err.to_string(),
@@ -925,6 +933,37 @@ pub async fn auth_finish(
     }
 }
 
+/// Counts a failed MFA step of a login like any other failed login for this IP and sends the
+/// `InvalidLogins` event. As soon as a threshold has been reached, the IP will be blacklisted,
+/// so the MFA step cannot be used to bypass the login throttling.
+async fn mfa_failed(req: &HttpRequest, data: &WebauthnAdditionalData) -> Result<(), ErrorResponse> {
+    if !matches!(data, WebauthnAdditionalData::Login(_)) {
+        return Ok(());
+    }
+
+    let ip = real_ip_from_req(req)?.to_string();
+    let failed_logins = FailedLoginCounter::increase(ip.clone()).await?;
+    let failed_logins = min(failed_logins, u32::MAX as i64) as u32;
+    warn!(reason = %LoginFailureReason::MfaFailed, "Failed Logins from {ip}: {failed_logins}");
+
+    Event::invalid_login(failed_logins, ip.clone(), LoginFailureReason::MfaFailed)
+        .send()
+        .await?;
+
+    if let Some(secs) = FailedLoginCounter::blacklist_secs(failed_logins) {
+        let not_before = Utc::now().add(chrono::Duration::seconds(secs as i64));
+        Event::ip_blacklisted(not_before, ip.clone()).send().await?;
+        IpBlacklist::put(ip, secs as i64).await?;
+
+        return Err(ErrorResponse::new(
+            ErrorResponseType::TooManyRequests(not_before.timestamp()),
+            "Too many failed logins",
+        ));
+    }
+
+    Ok(())
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct WebauthnReg {
     pub user_id: String,
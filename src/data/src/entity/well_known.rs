@@ -28,7 +28,7 @@ pub struct WellKnown {
     pub jwks_uri: String,
     pub grant_types_supported: [&'static str; 5],
     pub response_types_supported: [&'static str; 1],
-    pub subject_types_supported: [&'static str; 1],
+    pub subject_types_supported: [&'static str; 2],
     pub id_token_signing_alg_values_supported: [&'static str; 4],
     pub token_endpoint_auth_methods_supported: [&'static str; 2],
     pub token_endpoint_auth_signing_alg_values_supported: [&'static str; 4],
@@ -45,9 +45,10 @@ pub struct WellKnown {
 static IDX: &str = ".well-known";
 
 impl WellKnown {
-    pub async fn json() -> Result<String, ErrorResponse> {
+    pub async fn json(issuer: &str) -> Result<String, ErrorResponse> {
         let client = DB::hql();
-        if let Some(slf) = client.get(Cache::App, IDX).await? {
+        let idx = Self::cache_idx(issuer);
+        if let Some(slf) = client.get(Cache::App, idx.clone()).await? {
             return Ok(slf);
         }
 
@@ -56,35 +57,46 @@ impl WellKnown {
             .into_iter()
             .map(|s| s.name)
             .collect::<Vec<String>>();
-        let slf = Self::new(scopes);
+        let slf = Self::new(issuer, scopes);
         let json = serde_json::to_string(&slf)?;
 
-        client.put(Cache::App, IDX, &json, CACHE_TTL_APP).await?;
+        client.put(Cache::App, idx, &json, CACHE_TTL_APP).await?;
 
         Ok(json)
     }
 
-    /// Rebuilds the WellKnown, serializes it as json and updates it inside the cache.
-    /// Should be called after any update on the Scopes.
+    /// Rebuilds the WellKnown for each issuer, serializes it as json and updates it inside the
+    /// cache. Should be called after any update on the Scopes.
     pub async fn rebuild() -> Result<(), ErrorResponse> {
         let scopes = Scope::find_all()
             .await?
             .into_iter()
             .map(|s| s.name)
             .collect::<Vec<String>>();
-        let slf = Self::new(scopes);
-        let json = serde_json::to_string(&slf)?;
 
-        DB::hql().put(Cache::App, IDX, &json, CACHE_TTL_APP).await?;
+        for issuer in &RauthyConfig::get().issuers {
+            let slf = Self::new(issuer, scopes.clone());
+            let json = serde_json::to_string(&slf)?;
+            DB::hql()
+                .put(Cache::App, Self::cache_idx(issuer), &json, CACHE_TTL_APP)
+                .await?;
+        }
 
         Ok(())
     }
+
+    #[inline]
+    fn cache_idx(issuer: &str) -> String {
+        if issuer == RauthyConfig::get().issuer {
+            IDX.to_string()
+        } else {
+            format!("{IDX}_{issuer}")
+        }
+    }
 }
 
 impl WellKnown {
-    pub fn new(scopes_supported: Vec<String>) -> Self {
-        let issuer = &RauthyConfig::get().issuer;
-
+    pub fn new(issuer: &str, scopes_supported: Vec<String>) -> Self {
         let authorization_endpoint = format!("{issuer}oidc/authorize");
         let device_authorization_endpoint = format!("{issuer}oidc/device");
         let token_endpoint = format!("{issuer}oidc/token");
@@ -120,7 +132,7 @@ impl WellKnown {
                 GRANT_TYPE_DEVICE_CODE,
             ],
             response_types_supported: ["code"],
-            subject_types_supported: ["public"],
+            subject_types_supported: ["public", "pairwise"],
             id_token_signing_alg_values_supported: ["RS256", "RS384", "RS512", "EdDSA"],
             token_endpoint_auth_methods_supported: ["client_secret_post", "client_secret_basic"],
             token_endpoint_auth_signing_alg_values_supported: ["RS256", "RS384", "RS512", "EdDSA"],
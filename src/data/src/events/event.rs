@@ -1,4 +1,5 @@
 use crate::database::DB;
+use crate::entity::clients::Client;
 use crate::entity::failed_scim_tasks::ScimAction;
 use crate::entity::login_locations::LoginLocation;
 use crate::entity::users::User;
@@ -163,6 +164,22 @@ pub enum EventType {
     SuspiciousApiScan,
     LoginNewLocation,
     TokenIssued,
+    UserEmailChangeRequest,
+    IntrospectionDenied,
+    RegistrationRateLimit,
+    TokensRevoked,
+    TokensInvalidated,
+    UserDataExported,
+    UserFederationRemapped,
+    UserOnboarded,
+    CacheFlushed,
+    RegistrationPending,
+    RegistrationApproved,
+    RegistrationRejected,
+    AccountRecovered,
+    RecordsSwept,
+    UserDisabled,
+    UserEnabled,
 }
 
 impl Display for EventType {
@@ -190,6 +207,22 @@ impl Display for EventType {
             Self::SuspiciousApiScan => write!(f, "Suspicious API scan"),
             Self::LoginNewLocation => write!(f, "Login from new location"),
             Self::TokenIssued => write!(f, "JWT Token issued"),
+            Self::UserEmailChangeRequest => write!(f, "User requested an E-Mail change"),
+            Self::IntrospectionDenied => write!(f, "Unauthorized token introspection"),
+            Self::RegistrationRateLimit => write!(f, "Registration rate limit"),
+            Self::TokensRevoked => write!(f, "Tokens revoked"),
+            Self::TokensInvalidated => write!(f, "All tokens invalidated"),
+            Self::UserDataExported => write!(f, "User data exported"),
+            Self::UserFederationRemapped => write!(f, "User federation remapped"),
+            Self::UserOnboarded => write!(f, "New user onboarded"),
+            Self::CacheFlushed => write!(f, "Cache flushed"),
+            Self::RegistrationPending => write!(f, "Registration pending"),
+            Self::RegistrationApproved => write!(f, "Registration approved"),
+            Self::RegistrationRejected => write!(f, "Registration rejected"),
+            Self::AccountRecovered => write!(f, "Account recovered"),
+            Self::RecordsSwept => write!(f, "Expired records swept"),
+            Self::UserDisabled => write!(f, "User has been disabled"),
+            Self::UserEnabled => write!(f, "User has been re-enabled"),
         }
     }
 }
@@ -221,6 +254,28 @@ impl From<rauthy_api_types::events::EventType> for EventType {
             rauthy_api_types::events::EventType::SuspiciousApiScan => Self::SuspiciousApiScan,
             rauthy_api_types::events::EventType::LoginNewLocation => Self::LoginNewLocation,
             rauthy_api_types::events::EventType::TokenIssued => Self::TokenIssued,
+            rauthy_api_types::events::EventType::UserEmailChangeRequest => {
+                Self::UserEmailChangeRequest
+            }
+            rauthy_api_types::events::EventType::IntrospectionDenied => Self::IntrospectionDenied,
+            rauthy_api_types::events::EventType::RegistrationRateLimit => {
+                Self::RegistrationRateLimit
+            }
+            rauthy_api_types::events::EventType::TokensRevoked => Self::TokensRevoked,
+            rauthy_api_types::events::EventType::TokensInvalidated => Self::TokensInvalidated,
+            rauthy_api_types::events::EventType::UserDataExported => Self::UserDataExported,
+            rauthy_api_types::events::EventType::UserFederationRemapped => {
+                Self::UserFederationRemapped
+            }
+            rauthy_api_types::events::EventType::UserOnboarded => Self::UserOnboarded,
+            rauthy_api_types::events::EventType::CacheFlushed => Self::CacheFlushed,
+            rauthy_api_types::events::EventType::RegistrationPending => Self::RegistrationPending,
+            rauthy_api_types::events::EventType::RegistrationApproved => Self::RegistrationApproved,
+            rauthy_api_types::events::EventType::RegistrationRejected => Self::RegistrationRejected,
+            rauthy_api_types::events::EventType::AccountRecovered => Self::AccountRecovered,
+            rauthy_api_types::events::EventType::RecordsSwept => Self::RecordsSwept,
+            rauthy_api_types::events::EventType::UserDisabled => Self::UserDisabled,
+            rauthy_api_types::events::EventType::UserEnabled => Self::UserEnabled,
         }
     }
 }
@@ -250,6 +305,22 @@ impl From<EventType> for rauthy_api_types::events::EventType {
             EventType::SuspiciousApiScan => Self::SuspiciousApiScan,
             EventType::LoginNewLocation => Self::LoginNewLocation,
             EventType::TokenIssued => Self::TokenIssued,
+            EventType::UserEmailChangeRequest => Self::UserEmailChangeRequest,
+            EventType::IntrospectionDenied => Self::IntrospectionDenied,
+            EventType::RegistrationRateLimit => Self::RegistrationRateLimit,
+            EventType::TokensRevoked => Self::TokensRevoked,
+            EventType::TokensInvalidated => Self::TokensInvalidated,
+            EventType::UserDataExported => Self::UserDataExported,
+            EventType::UserFederationRemapped => Self::UserFederationRemapped,
+            EventType::UserOnboarded => Self::UserOnboarded,
+            EventType::CacheFlushed => Self::CacheFlushed,
+            EventType::RegistrationPending => Self::RegistrationPending,
+            EventType::RegistrationApproved => Self::RegistrationApproved,
+            EventType::RegistrationRejected => Self::RegistrationRejected,
+            EventType::AccountRecovered => Self::AccountRecovered,
+            EventType::RecordsSwept => Self::RecordsSwept,
+            EventType::UserDisabled => Self::UserDisabled,
+            EventType::UserEnabled => Self::UserEnabled,
         }
     }
 }
@@ -279,6 +350,22 @@ impl EventType {
             Self::SuspiciousApiScan => "SuspiciousApiScan",
             Self::LoginNewLocation => "LoginNewLocation",
             Self::TokenIssued => "TokenIssued",
+            Self::UserEmailChangeRequest => "UserEmailChangeRequest",
+            Self::IntrospectionDenied => "IntrospectionDenied",
+            Self::RegistrationRateLimit => "RegistrationRateLimit",
+            Self::TokensRevoked => "TokensRevoked",
+            Self::TokensInvalidated => "TokensInvalidated",
+            Self::UserDataExported => "UserDataExported",
+            Self::UserFederationRemapped => "UserFederationRemapped",
+            Self::UserOnboarded => "UserOnboarded",
+            Self::CacheFlushed => "CacheFlushed",
+            Self::RegistrationPending => "RegistrationPending",
+            Self::RegistrationApproved => "RegistrationApproved",
+            Self::RegistrationRejected => "RegistrationRejected",
+            Self::AccountRecovered => "AccountRecovered",
+            Self::RecordsSwept => "RecordsSwept",
+            Self::UserDisabled => "UserDisabled",
+            Self::UserEnabled => "UserEnabled",
         }
     }
 
@@ -309,6 +396,22 @@ impl EventType {
             EventType::SuspiciousApiScan => 19,
             EventType::LoginNewLocation => 20,
             EventType::TokenIssued => 21,
+            EventType::UserEmailChangeRequest => 22,
+            EventType::IntrospectionDenied => 23,
+            EventType::RegistrationRateLimit => 24,
+            EventType::TokensRevoked => 25,
+            EventType::TokensInvalidated => 26,
+            EventType::UserDataExported => 27,
+            EventType::UserFederationRemapped => 28,
+            EventType::UserOnboarded => 29,
+            EventType::CacheFlushed => 30,
+            EventType::RegistrationPending => 31,
+            EventType::RegistrationApproved => 32,
+            EventType::RegistrationRejected => 33,
+            EventType::AccountRecovered => 34,
+            EventType::RecordsSwept => 35,
+            EventType::UserDisabled => 36,
+            EventType::UserEnabled => 37,
         }
     }
 }
@@ -338,6 +441,22 @@ impl From<String> for EventType {
             "SuspiciousApiScan" => Self::SuspiciousApiScan,
             "LoginNewLocation" => Self::LoginNewLocation,
             "TokenIssued" => Self::TokenIssued,
+            "UserEmailChangeRequest" => Self::UserEmailChangeRequest,
+            "IntrospectionDenied" => Self::IntrospectionDenied,
+            "RegistrationRateLimit" => Self::RegistrationRateLimit,
+            "TokensRevoked" => Self::TokensRevoked,
+            "TokensInvalidated" => Self::TokensInvalidated,
+            "UserDataExported" => Self::UserDataExported,
+            "UserFederationRemapped" => Self::UserFederationRemapped,
+            "UserOnboarded" => Self::UserOnboarded,
+            "CacheFlushed" => Self::CacheFlushed,
+            "RegistrationPending" => Self::RegistrationPending,
+            "RegistrationApproved" => Self::RegistrationApproved,
+            "RegistrationRejected" => Self::RegistrationRejected,
+            "AccountRecovered" => Self::AccountRecovered,
+            "RecordsSwept" => Self::RecordsSwept,
+            "UserDisabled" => Self::UserDisabled,
+            "UserEnabled" => Self::UserEnabled,
             // just return test to never panic
             s => {
                 error!("EventType::from() for invalid String: {s}");
@@ -378,11 +497,94 @@ impl From<i64> for EventType {
             19 => EventType::SuspiciousApiScan,
             20 => EventType::LoginNewLocation,
             21 => EventType::TokenIssued,
+            22 => EventType::UserEmailChangeRequest,
+            23 => EventType::IntrospectionDenied,
+            24 => EventType::RegistrationRateLimit,
+            25 => EventType::TokensRevoked,
+            26 => EventType::TokensInvalidated,
+            27 => EventType::UserDataExported,
+            28 => EventType::UserFederationRemapped,
+            29 => EventType::UserOnboarded,
+            30 => EventType::CacheFlushed,
+            31 => EventType::RegistrationPending,
+            32 => EventType::RegistrationApproved,
+            33 => EventType::RegistrationRejected,
+            34 => EventType::AccountRecovered,
+            35 => EventType::RecordsSwept,
+            36 => EventType::UserDisabled,
+            37 => EventType::UserEnabled,
             _ => EventType::Test,
         }
     }
 }
 
+/// The reason for a failed login, which will be attached to `InvalidLogins` events.
+/// It must never be returned to the client, to prevent user enumeration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoginFailureReason {
+    WrongPassword,
+    PasswordExpired,
+    UnknownUser,
+    AccountLocked,
+    AccountDisabled,
+    MfaFailed,
+    FederationRejected,
+    Other,
+}
+
+impl Display for LoginFailureReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl LoginFailureReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::WrongPassword => "wrong_password",
+            Self::PasswordExpired => "password_expired",
+            Self::UnknownUser => "unknown_user",
+            Self::AccountLocked => "account_locked",
+            Self::AccountDisabled => "account_disabled",
+            Self::MfaFailed => "mfa_failed",
+            Self::FederationRejected => "federation_rejected",
+            Self::Other => "other",
+        }
+    }
+
+    /// Maps the internal error of a failed password login to its reason.
+    pub fn from_login_err(err: &ErrorResponse) -> Self {
+        match err.error {
+            ErrorResponseType::NotFound => Self::UnknownUser,
+            ErrorResponseType::Unauthorized => Self::WrongPassword,
+            ErrorResponseType::PasswordExpired | ErrorResponseType::PasswordRefresh => {
+                Self::PasswordExpired
+            }
+            ErrorResponseType::Disabled => Self::AccountDisabled,
+            ErrorResponseType::Blocked | ErrorResponseType::TooManyRequests(_) => {
+                Self::AccountLocked
+            }
+            ErrorResponseType::MfaRequired => Self::MfaFailed,
+            _ => Self::Other,
+        }
+    }
+
+    /// Maps the error of a failed upstream auth provider login to its reason. An already
+    /// linked upstream id or a policy denial is a rejected federation, while connection or
+    /// internal errors are no login failure of the user.
+    pub fn from_federation_err(err: &ErrorResponse) -> Self {
+        match err.error {
+            ErrorResponseType::Disabled => Self::AccountDisabled,
+            ErrorResponseType::BadRequest
+            | ErrorResponseType::Forbidden
+            | ErrorResponseType::NotAccepted
+            | ErrorResponseType::NotFound
+            | ErrorResponseType::Unauthorized => Self::FederationRejected,
+            _ => Self::Other,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Event {
     pub id: String,
@@ -438,9 +640,10 @@ impl From<&Event> for Notification {
 
         let row_2 = match value.typ {
             EventType::InvalidLogins => Some(format!(
-                "{} invalid logins from IP: `{}`",
-                value.data.unwrap_or_default(),
-                value.ip.as_deref().unwrap_or_default()
+                "{} invalid logins from IP: `{}`, reason: {}",
+                value.data.unwrap_or(1),
+                value.ip.as_deref().unwrap_or_default(),
+                value.text.as_deref().unwrap_or("unknown"),
             )),
             EventType::IpBlacklisted => {
                 let d =
@@ -496,6 +699,26 @@ impl From<&Event> for Notification {
             EventType::SuspiciousApiScan => value.text.clone(),
             EventType::LoginNewLocation => value.text.clone(),
             EventType::TokenIssued => value.text.clone(),
+            EventType::UserEmailChangeRequest => value.text.clone(),
+            EventType::IntrospectionDenied => value.text.clone(),
+            EventType::RegistrationRateLimit => value.text.clone(),
+            EventType::TokensRevoked => Some(format!(
+                "{} tokens revoked for {}",
+                value.data.unwrap_or_default(),
+                value.text.as_deref().unwrap_or_default()
+            )),
+            EventType::TokensInvalidated => value.text.clone(),
+            EventType::UserDataExported => value.text.clone(),
+            EventType::UserFederationRemapped => value.text.clone(),
+            EventType::UserOnboarded => value.text.clone(),
+            EventType::CacheFlushed => value.text.clone(),
+            EventType::RegistrationPending => value.text.clone(),
+            EventType::RegistrationApproved => value.text.clone(),
+            EventType::RegistrationRejected => value.text.clone(),
+            EventType::AccountRecovered => value.text.clone(),
+            EventType::RecordsSwept => value.text.clone(),
+            EventType::UserDisabled => value.text.clone(),
+            EventType::UserEnabled => value.text.clone(),
         };
 
         Self {
@@ -625,6 +848,80 @@ ORDER BY timestamp DESC"#;
         Ok(res)
     }
 
+    /// Returns the next batch of events before the `(timestamp, id)` cursor, which reference the
+    /// given user either by its ID or E-Mail. Events do not have a direct link to users, which
+    /// makes this a costly `LIKE` query that should only be used for the user data export.
+    ///
+    /// The returned cursor is the one to use for the next batch, if another one may exist.
+    pub async fn find_for_user(
+        user_id: &str,
+        email: &str,
+        before: (i64, &str),
+        limit: i64,
+    ) -> Result<(Vec<Self>, Option<(i64, String)>), ErrorResponse> {
+        let q_id = format!("%{}%", Self::escape_like(user_id));
+        let q_email = format!("%{}%", Self::escape_like(email));
+        let (ts, id) = before;
+
+        let sql = r#"
+SELECT * FROM events
+WHERE (timestamp < $1 OR (timestamp = $1 AND id < $2))
+    AND (text LIKE $3 ESCAPE '\' OR text LIKE $4 ESCAPE '\')
+ORDER BY timestamp DESC, id DESC
+LIMIT $5"#;
+        let res: Vec<Self> = if is_hiqlite() {
+            DB::hql()
+                .query_map(sql, params!(ts, id, q_id, q_email, limit))
+                .await?
+        } else {
+            let size_hint = max(limit, 1) as usize;
+            DB::pg_query(sql, &[&ts, &id, &q_id, &q_email, &limit], size_hint).await?
+        };
+
+        let next = if res.len() as i64 == limit {
+            res.last().map(|e| (e.timestamp, e.id.clone()))
+        } else {
+            None
+        };
+
+        // `LIKE` matches any substring -> `bob@example.com` must not match `alice.bob@example.com`
+        let res = res
+            .into_iter()
+            .filter(|e| {
+                e.text.as_deref().is_some_and(|t| {
+                    Self::contains_identifier(t, user_id) || Self::contains_identifier(t, email)
+                })
+            })
+            .collect();
+
+        Ok((res, next))
+    }
+
+    /// Escapes all wildcards for a `LIKE` query with `ESCAPE '\'`.
+    fn escape_like(s: &str) -> String {
+        let mut res = String::with_capacity(s.len() + 2);
+        for c in s.chars() {
+            if matches!(c, '\\' | '%' | '_') {
+                res.push('\\');
+            }
+            res.push(c);
+        }
+        res
+    }
+
+    /// Returns `true` if the `text` contains the exact `ident`, and not only as part of a
+    /// longer E-Mail or ID.
+    fn contains_identifier(text: &str, ident: &str) -> bool {
+        let is_ident_char =
+            |c: char| c.is_alphanumeric() || matches!(c, '.' | '-' | '_' | '+' | '@');
+
+        text.match_indices(ident).any(|(i, _)| {
+            let before = text[..i].chars().next_back();
+            let after = text[i + ident.len()..].chars().next();
+            !before.is_some_and(is_ident_char) && !after.is_some_and(is_ident_char)
+        })
+    }
+
     pub fn as_json(&self) -> String {
         serde_json::to_string(self).unwrap()
     }
@@ -653,7 +950,7 @@ impl Event {
     }
 
     /// The EventLevel will change depending on the amount of invalid logins
-    pub fn invalid_login(failed_logins: u32, ip: String) -> Self {
+    pub fn invalid_login(failed_logins: u32, ip: String, reason: LoginFailureReason) -> Self {
         let events = &RauthyConfig::get().vars.events;
         let level = match failed_logins {
             l if l >= 25 => events.level_failed_logins_25.clone(),
@@ -668,7 +965,19 @@ impl Event {
             EventType::InvalidLogins,
             Some(ip),
             Some(failed_logins as i64),
+            Some(reason.as_str().to_string()),
+        )
+    }
+
+    /// A failed login, which is not counted for the login delay, like a failed MFA step or
+    /// a rejected upstream auth provider login.
+    pub fn login_failed(ip: String, reason: LoginFailureReason) -> Self {
+        Self::new(
+            RauthyConfig::get().vars.events.level_failed_login.clone(),
+            EventType::InvalidLogins,
+            Some(ip),
             None,
+            Some(reason.as_str().to_string()),
         )
     }
 
@@ -706,6 +1015,22 @@ impl Event {
         )
     }
 
+    pub fn introspection_denied(client_id: &str, ip: IpAddr) -> Self {
+        Self::new(
+            RauthyConfig::get()
+                .vars
+                .events
+                .level_introspection_denied
+                .clone(),
+            EventType::IntrospectionDenied,
+            Some(ip.to_string()),
+            None,
+            Some(format!(
+                "Client '{client_id}' is not allowed to use token introspection"
+            )),
+        )
+    }
+
     pub fn ip_blacklisted(exp: DateTime<Utc>, ip: String) -> Self {
         Self::new(
             RauthyConfig::get().vars.events.level_ip_blacklisted.clone(),
@@ -861,6 +1186,213 @@ impl Event {
         )
     }
 
+    pub fn registration_rate_limit(ip: IpAddr, retry_at: i64) -> Self {
+        Self::new(
+            RauthyConfig::get()
+                .vars
+                .events
+                .level_registration_rate_limit
+                .clone(),
+            EventType::RegistrationRateLimit,
+            Some(ip.to_string()),
+            Some(retry_at),
+            Some("Open user registration rate limit exceeded".to_string()),
+        )
+    }
+
+    /// `target` should be either `client <id>` or `user <email>`
+    pub fn tokens_revoked(target: String, count: usize) -> Self {
+        Self::new(
+            RauthyConfig::get().vars.events.level_tokens_revoked.clone(),
+            EventType::TokensRevoked,
+            None,
+            Some(count as i64),
+            Some(target),
+        )
+    }
+
+    /// `nbf` is the new global "not valid before" timestamp for all tokens
+    pub fn tokens_invalidated(ip: IpAddr, nbf: i64) -> Self {
+        Self::new(
+            RauthyConfig::get()
+                .vars
+                .events
+                .level_tokens_invalidated
+                .clone(),
+            EventType::TokensInvalidated,
+            Some(ip.to_string()),
+            Some(nbf),
+            Some("All tokens have been invalidated".to_string()),
+        )
+    }
+
+    pub fn user_data_exported(user_email: &str, ip: IpAddr) -> Self {
+        Self::new(
+            RauthyConfig::get()
+                .vars
+                .events
+                .level_user_data_exported
+                .clone(),
+            EventType::UserDataExported,
+            Some(ip.to_string()),
+            None,
+            Some(format!("User `{user_email}` exported all personal data")),
+        )
+    }
+
+    pub fn user_federation_remapped(
+        user_email: &str,
+        provider_id: &str,
+        old_uid: &str,
+        new_uid: &str,
+        ip: IpAddr,
+    ) -> Self {
+        Self::new(
+            RauthyConfig::get()
+                .vars
+                .events
+                .level_user_federation_remapped
+                .clone(),
+            EventType::UserFederationRemapped,
+            Some(ip.to_string()),
+            None,
+            Some(format!(
+                "Federation for user `{user_email}` on provider `{provider_id}` remapped from \
+                `{old_uid}` to `{new_uid}`"
+            )),
+        )
+    }
+
+    /// `via` is the credential the user has set up with the onboarding link.
+    pub fn user_onboarded(user_email: &str, via: &str, ip: Option<IpAddr>) -> Self {
+        Self::new(
+            RauthyConfig::get().vars.events.level_user_onboarded.clone(),
+            EventType::UserOnboarded,
+            ip.map(|ip| ip.to_string()),
+            None,
+            Some(format!(
+                "User `{user_email}` completed the onboarding via {via}"
+            )),
+        )
+    }
+
+    /// `actor` is the admin or API Key, which has flushed the `namespace`.
+    pub fn cache_flushed(namespace: &str, actor: &str, ip: IpAddr) -> Self {
+        Self::new(
+            RauthyConfig::get().vars.events.level_cache_flushed.clone(),
+            EventType::CacheFlushed,
+            Some(ip.to_string()),
+            None,
+            Some(format!("Cache `{namespace}` flushed by {actor}")),
+        )
+    }
+
+    /// `via` is either `registration` or the name of the upstream auth provider.
+    pub fn registration_pending(user_email: &str, via: &str, ip: Option<IpAddr>) -> Self {
+        Self::new(
+            RauthyConfig::get()
+                .vars
+                .events
+                .level_registration_pending
+                .clone(),
+            EventType::RegistrationPending,
+            ip.map(|ip| ip.to_string()),
+            None,
+            Some(format!(
+                "Registration for `{user_email}` via {via} awaits approval"
+            )),
+        )
+    }
+
+    /// `actor` is the admin or API Key, which has approved the registration.
+    pub fn registration_approved(user_email: &str, actor: &str, ip: IpAddr) -> Self {
+        Self::new(
+            RauthyConfig::get()
+                .vars
+                .events
+                .level_registration_approved
+                .clone(),
+            EventType::RegistrationApproved,
+            Some(ip.to_string()),
+            None,
+            Some(format!(
+                "Registration for `{user_email}` approved by {actor}"
+            )),
+        )
+    }
+
+    /// `actor` is the admin or API Key, which has rejected the registration.
+    pub fn registration_rejected(user_email: &str, actor: &str, ip: IpAddr) -> Self {
+        Self::new(
+            RauthyConfig::get()
+                .vars
+                .events
+                .level_registration_rejected
+                .clone(),
+            EventType::RegistrationRejected,
+            Some(ip.to_string()),
+            None,
+            Some(format!(
+                "Registration for `{user_email}` rejected by {actor}"
+            )),
+        )
+    }
+
+    /// `provider` is the name of the upstream auth provider used for the recovery.
+    pub fn account_recovered(user_email: &str, provider: &str, ip: IpAddr) -> Self {
+        Self::new(
+            RauthyConfig::get()
+                .vars
+                .events
+                .level_account_recovered
+                .clone(),
+            EventType::AccountRecovered,
+            Some(ip.to_string()),
+            None,
+            Some(format!(
+                "Account `{user_email}` recovered via provider {provider}"
+            )),
+        )
+    }
+
+    /// `counts` contains the amount of deleted rows for each swept table.
+    pub fn records_swept(counts: &[(&str, u64)]) -> Self {
+        let text = counts
+            .iter()
+            .map(|(table, count)| format!("{table}: {count}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Self::new(
+            RauthyConfig::get().vars.events.level_records_swept.clone(),
+            EventType::RecordsSwept,
+            None,
+            None,
+            Some(format!("Expired records swept - {text}")),
+        )
+    }
+
+    /// `actor` is the admin or API Key, which has disabled the user.
+    pub fn user_disabled(user_email: &str, actor: &str, ip: IpAddr) -> Self {
+        Self::new(
+            RauthyConfig::get().vars.events.level_user_disabled.clone(),
+            EventType::UserDisabled,
+            Some(ip.to_string()),
+            None,
+            Some(format!("User `{user_email}` disabled by {actor}")),
+        )
+    }
+
+    /// `actor` is the admin or API Key, which has re-enabled the user.
+    pub fn user_enabled(user_email: &str, actor: &str, ip: IpAddr) -> Self {
+        Self::new(
+            RauthyConfig::get().vars.events.level_user_enabled.clone(),
+            EventType::UserEnabled,
+            Some(ip.to_string()),
+            None,
+            Some(format!("User `{user_email}` re-enabled by {actor}")),
+        )
+    }
+
     pub fn scim_task_failed(client_id: &str, action: &ScimAction, retries: i64) -> Self {
         Self::new(
             RauthyConfig::get()
@@ -916,16 +1448,22 @@ impl Event {
         )
     }
 
-    pub fn token_issued(flow: &str, client_id: &str, email: Option<&str>) -> Self {
+    pub fn token_issued(flow: &str, client: &Client, email: Option<&str>) -> Self {
+        let trust = if client.first_party {
+            "first-party"
+        } else {
+            "third-party"
+        };
         Self::new(
             RauthyConfig::get().vars.events.level_token_issued.clone(),
             EventType::TokenIssued,
             None,
             None,
             Some(format!(
-                "{} ({}) {}",
-                client_id,
+                "{} ({}, {}) {}",
+                client.id,
                 flow,
+                trust,
                 email.unwrap_or_default()
             )),
         )
@@ -945,6 +1483,20 @@ impl Event {
         )
     }
 
+    pub fn user_email_change_request(text: String, ip: Option<IpAddr>) -> Self {
+        Self::new(
+            RauthyConfig::get()
+                .vars
+                .events
+                .level_user_email_change_request
+                .clone(),
+            EventType::UserEmailChangeRequest,
+            ip.map(|ip| ip.to_string()),
+            None,
+            Some(text),
+        )
+    }
+
     pub fn user_password_reset(text: String, ip: Option<String>) -> Self {
         Self::new(
             RauthyConfig::get()
@@ -961,7 +1513,11 @@ impl Event {
 
     pub fn fmt_data(&self) -> String {
         match self.typ {
-            EventType::InvalidLogins => format!("Counter: {}", self.data.unwrap_or_default()),
+            EventType::InvalidLogins => match (self.data, self.text.as_deref()) {
+                (Some(count), Some(reason)) => format!("Counter: {count}, Reason: {reason}"),
+                (None, Some(reason)) => format!("Reason: {reason}"),
+                (count, None) => format!("Counter: {}", count.unwrap_or_default()),
+            },
             EventType::IpBlacklisted => {
                 let d =
                     DateTime::from_timestamp(self.data.unwrap_or_default(), 0).unwrap_or_default();
@@ -1023,6 +1579,28 @@ impl Event {
             EventType::SuspiciousApiScan => self.text.clone().unwrap_or_default(),
             EventType::LoginNewLocation => self.text.clone().unwrap_or_default(),
             EventType::TokenIssued => self.text.clone().unwrap_or_default(),
+            EventType::UserEmailChangeRequest => self.text.clone().unwrap_or_default(),
+            EventType::IntrospectionDenied => self.text.clone().unwrap_or_default(),
+            EventType::RegistrationRateLimit => self.text.clone().unwrap_or_default(),
+            EventType::TokensRevoked => {
+                format!(
+                    "{} tokens revoked for {}",
+                    self.data.unwrap_or_default(),
+                    self.text.as_deref().unwrap_or_default()
+                )
+            }
+            EventType::TokensInvalidated => self.text.clone().unwrap_or_default(),
+            EventType::UserDataExported => self.text.clone().unwrap_or_default(),
+            EventType::UserFederationRemapped => self.text.clone().unwrap_or_default(),
+            EventType::UserOnboarded => self.text.clone().unwrap_or_default(),
+            EventType::CacheFlushed => self.text.clone().unwrap_or_default(),
+            EventType::RegistrationPending => self.text.clone().unwrap_or_default(),
+            EventType::RegistrationApproved => self.text.clone().unwrap_or_default(),
+            EventType::RegistrationRejected => self.text.clone().unwrap_or_default(),
+            EventType::AccountRecovered => self.text.clone().unwrap_or_default(),
+            EventType::RecordsSwept => self.text.clone().unwrap_or_default(),
+            EventType::UserDisabled => self.text.clone().unwrap_or_default(),
+            EventType::UserEnabled => self.text.clone().unwrap_or_default(),
         }
     }
 
@@ -1054,3 +1632,81 @@ impl From<Event> for EventResponse {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_login_failure_reason() {
+        let err = |typ| ErrorResponse::new(typ, "");
+
+        assert_eq!(
+            LoginFailureReason::from_login_err(&err(ErrorResponseType::NotFound)),
+            LoginFailureReason::UnknownUser
+        );
+        assert_eq!(
+            LoginFailureReason::from_login_err(&err(ErrorResponseType::Unauthorized)),
+            LoginFailureReason::WrongPassword
+        );
+        assert_eq!(
+            LoginFailureReason::from_login_err(&err(ErrorResponseType::PasswordExpired)),
+            LoginFailureReason::PasswordExpired
+        );
+        assert_eq!(
+            LoginFailureReason::from_login_err(&err(ErrorResponseType::PasswordRefresh)),
+            LoginFailureReason::PasswordExpired
+        );
+        assert_eq!(
+            LoginFailureReason::from_login_err(&err(ErrorResponseType::Disabled)),
+            LoginFailureReason::AccountDisabled
+        );
+        assert_eq!(
+            LoginFailureReason::from_login_err(&err(ErrorResponseType::TooManyRequests(1))),
+            LoginFailureReason::AccountLocked
+        );
+
+        assert_eq!(
+            LoginFailureReason::from_federation_err(&err(ErrorResponseType::NotAccepted)),
+            LoginFailureReason::FederationRejected
+        );
+        assert_eq!(
+            LoginFailureReason::from_federation_err(&err(ErrorResponseType::Forbidden)),
+            LoginFailureReason::FederationRejected
+        );
+        assert_eq!(
+            LoginFailureReason::from_federation_err(&err(ErrorResponseType::Disabled)),
+            LoginFailureReason::AccountDisabled
+        );
+        assert_eq!(
+            LoginFailureReason::from_federation_err(&err(ErrorResponseType::Connection)),
+            LoginFailureReason::Other
+        );
+        assert_eq!(
+            LoginFailureReason::FederationRejected.to_string(),
+            "federation_rejected"
+        );
+    }
+
+    #[test]
+    fn test_find_for_user_matching() {
+        assert_eq!(Event::escape_like("a_b%c\\d"), "a\\_b\\%c\\\\d");
+
+        let email = "bob@example.com";
+        assert!(Event::contains_identifier(email, email));
+        assert!(Event::contains_identifier(
+            "User `bob@example.com` exported all personal data",
+            email
+        ));
+        assert!(Event::contains_identifier(
+            "alice@example.com -> bob@example.com",
+            email
+        ));
+        assert!(!Event::contains_identifier("alice.bob@example.com", email));
+        assert!(!Event::contains_identifier("bob@example.com.evil", email));
+        assert!(!Event::contains_identifier("xbob@example.com", email));
+
+        assert!(Event::contains_identifier("client / abc123", "abc123"));
+        assert!(!Event::contains_identifier("client / abc1234", "abc123"));
+    }
+}
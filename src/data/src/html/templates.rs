@@ -26,12 +26,32 @@ impl Display for FrontendAction {
     }
 }
 
+/// The public config for the CAPTCHA widget. Only rendered if a provider is configured.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct TplCaptcha {
+    pub provider: &'static str,
+    pub site_key: String,
+    /// `true` if logins may require a CAPTCHA, either after failed logins or as IP reputation
+    /// step-up
+    pub login: bool,
+    pub registration: bool,
+}
+
 #[derive(Debug, Serialize)]
 pub struct TplClientData {
     pub name: String,
     pub url: String,
 }
 
+/// A validated `login_hint` from the authorize request
+#[derive(Debug, Serialize)]
+pub struct TplLoginHint {
+    pub email: String,
+    /// The provider with a matching `email_domains` pattern
+    pub provider_id: Option<String>,
+    pub auto_redirect: bool,
+}
+
 #[derive(Debug, Serialize)]
 pub struct TplPasswordReset {
     pub csrf_token: String,
@@ -50,10 +70,13 @@ pub enum HtmlTemplate {
     AtprotoId(String),
     /// Auth providers as pre-built, cached JSON value
     AuthProviders(String),
+    Captcha(TplCaptcha),
     ClientName(String),
     ClientUrl(String),
     ClientLogoUpdated(Option<i64>),
     CsrfToken(String),
+    /// A sanitized, non-default OIDC `display` value
+    Display(&'static str),
     EmailOld(String),
     EmailNew(String),
     ErrorDetails(Cow<'static, str>),
@@ -61,7 +84,10 @@ pub enum HtmlTemplate {
     DeviceUserCodeLength(u8),
     IsRegOpen(bool),
     LoginAction(FrontendAction),
+    LoginHint(TplLoginHint),
     PasswordReset(TplPasswordReset),
+    /// `mfa.provider_recovery` is enabled
+    ProviderRecovery(bool),
     RestrictedEmailDomain(String),
     StatusCode(StatusCode),
     UserValues,
@@ -74,10 +100,12 @@ impl HtmlTemplate {
             Self::AdminButtonHide(_) => "tpl_admin_btn_hide",
             Self::AtprotoId(_) => "tpl_atproto_id",
             Self::AuthProviders(_) => "tpl_auth_providers",
+            Self::Captcha(_) => "tpl_captcha",
             Self::ClientName(_) => "tpl_client_name",
             Self::ClientUrl(_) => "tpl_client_url",
             Self::ClientLogoUpdated(_) => "tpl_client_logo_updated",
             Self::CsrfToken(_) => "tpl_csrf_token",
+            Self::Display(_) => "tpl_display",
             Self::EmailOld(_) => "tpl_email_old",
             Self::EmailNew(_) => "tpl_email_new",
             Self::ErrorDetails(_) => "tpl_error_details",
@@ -85,7 +113,9 @@ impl HtmlTemplate {
             Self::DeviceUserCodeLength(_) => "tpl_device_user_code_length",
             Self::IsRegOpen(_) => "tpl_is_reg_open",
             Self::LoginAction(_) => "tpl_login_action",
+            Self::LoginHint(_) => "tpl_login_hint",
             Self::PasswordReset(_) => "tpl_password_reset",
+            Self::ProviderRecovery(_) => "tpl_provider_recovery",
             Self::RestrictedEmailDomain(_) => "tpl_restricted_email_domain",
             Self::StatusCode(_) => "tpl_status_code",
             Self::UserValues => "tpl_user_values_config",
@@ -99,10 +129,12 @@ impl HtmlTemplate {
             Self::AdminButtonHide(i) => i.to_string(),
             Self::AtprotoId(i) => i.to_string(),
             Self::AuthProviders(i) => i.to_string(),
+            Self::Captcha(i) => serde_json::to_string(i).unwrap(),
             Self::ClientName(i) => i.to_string(),
             Self::ClientUrl(i) => i.to_string(),
             Self::ClientLogoUpdated(i) => i.map(|i| i.to_string()).unwrap_or_default(),
             Self::CsrfToken(i) => i.to_string(),
+            Self::Display(i) => i.to_string(),
             Self::EmailOld(i) => i.to_string(),
             Self::EmailNew(i) => i.to_string(),
             Self::ErrorDetails(i) => i.to_string(),
@@ -110,7 +142,9 @@ impl HtmlTemplate {
             Self::DeviceUserCodeLength(i) => i.to_string(),
             Self::IsRegOpen(i) => i.to_string(),
             Self::LoginAction(i) => i.to_string(),
+            Self::LoginHint(i) => serde_json::to_string(i).unwrap(),
             Self::PasswordReset(i) => serde_json::to_string(i).unwrap(),
+            Self::ProviderRecovery(i) => i.to_string(),
             Self::StatusCode(i) => i.to_string(),
             Self::RestrictedEmailDomain(i) => i.to_string(),
             Self::UserValues => {
@@ -936,6 +970,28 @@ impl UserEmailChangeConfirmHtml<'_> {
     }
 }
 
+#[derive(Default, Template)]
+#[template(path = "html/users/{id}/email_change_abort/email_change_abort.html")]
+pub struct UserEmailChangeAbortHtml<'a> {
+    lang: &'a str,
+    client_id: &'a str,
+    theme_ts: i64,
+    templates: &'a [HtmlTemplate],
+}
+
+impl UserEmailChangeAbortHtml<'_> {
+    pub fn build(lang: &Language, theme_ts: i64) -> String {
+        UserEmailChangeAbortHtml {
+            lang: lang.as_str(),
+            client_id: "rauthy",
+            theme_ts,
+            templates: &[],
+        }
+        .render()
+        .expect("rendering email_change_abort.html")
+    }
+}
+
 #[derive(Default, Template)]
 #[template(path = "html/users/register.html")]
 pub struct UserRegisterHtml<'a> {
@@ -947,22 +1003,25 @@ pub struct UserRegisterHtml<'a> {
 
 impl UserRegisterHtml<'_> {
     pub fn build(lang: &Language, theme_ts: i64, auth_providers: HtmlTemplate) -> String {
+        let mut templates = vec![
+            HtmlTemplate::RestrictedEmailDomain(
+                RauthyConfig::get()
+                    .vars
+                    .user_registration
+                    .allowed_domains_display(),
+            ),
+            HtmlTemplate::UserValues,
+            auth_providers,
+        ];
+        if let Some(tpl) = TplCaptcha::from_config() {
+            templates.push(HtmlTemplate::Captcha(tpl));
+        }
+
         UserRegisterHtml {
             lang: lang.as_str(),
             client_id: "rauthy",
             theme_ts,
-            templates: &[
-                HtmlTemplate::RestrictedEmailDomain(
-                    RauthyConfig::get()
-                        .vars
-                        .user_registration
-                        .domain_restriction
-                        .clone()
-                        .unwrap_or_default(),
-                ),
-                HtmlTemplate::UserValues,
-                auth_providers,
-            ],
+            templates: &templates,
         }
         .render()
         .expect("rendering register.html")
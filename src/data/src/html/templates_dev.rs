@@ -4,7 +4,7 @@ use crate::entity::magic_links::{MagicLink, MagicLinkUsage};
 use crate::entity::password::PasswordPolicy;
 use crate::entity::sessions::Session;
 use crate::entity::users::User;
-use crate::html::templates::{FrontendAction, HtmlTemplate, TplPasswordReset};
+use crate::html::templates::{FrontendAction, HtmlTemplate, TplCaptcha, TplPasswordReset};
 use crate::rauthy_config::RauthyConfig;
 use actix_web::cookie::Cookie;
 use chrono::Utc;
@@ -42,6 +42,13 @@ impl HtmlTemplate {
                 let json = AuthProviderTemplate::get_all_json_template().await?;
                 Ok((Self::AuthProviders(json), None))
             }
+            "tpl_captcha" => match TplCaptcha::from_config() {
+                Some(tpl) => Ok((Self::Captcha(tpl), None)),
+                None => Err(ErrorResponse::new(
+                    ErrorResponseType::NotFound,
+                    "no CAPTCHA provider configured",
+                )),
+            },
             "tpl_client_logo_updated" => Ok((
                 Self::ClientLogoUpdated(Some(Utc::now().timestamp_millis())),
                 None,
@@ -69,9 +76,15 @@ impl HtmlTemplate {
                 Self::IsRegOpen(RauthyConfig::get().vars.user_registration.enable),
                 None,
             )),
+            "tpl_provider_recovery" => Ok((
+                Self::ProviderRecovery(RauthyConfig::get().vars.mfa.provider_recovery),
+                None,
+            )),
             // the LoginAction requires a complex logic + validation.
             // Simply always return None during local dev.
             "tpl_login_action" => Ok((Self::LoginAction(FrontendAction::None), None)),
+            // "tpl_display" => depends on the query params of the authorize request
+            // "tpl_login_hint" => depends on the query params of the authorize request
             // "tpl_client_name" => todo!("extract info from referrer?"),
             // "tpl_client_url" => todo!("extract info from referrer?"),
             "tpl_restricted_email_domain" => Ok((
@@ -79,9 +92,7 @@ impl HtmlTemplate {
                     RauthyConfig::get()
                         .vars
                         .user_registration
-                        .domain_restriction
-                        .clone()
-                        .unwrap_or_default(),
+                        .allowed_domains_display(),
                 ),
                 None,
             )),
@@ -0,0 +1,217 @@
+//! The TLS policy for the global outbound HTTP client, which is used for upstream auth
+//! providers, remote JWKS, webhooks / Slack notifications and so on.
+
+use crate::database::NoTlsVerifier;
+use crate::rauthy_config::VarsHttpClient;
+use rauthy_error::{ErrorResponse, ErrorResponseType};
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::CertificateDer;
+use rustls::pki_types::pem::PemObject;
+use rustls::{SupportedCipherSuite, SupportedProtocolVersion};
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+static TLS13_ONLY: &[&SupportedProtocolVersion] = &[&rustls::version::TLS13];
+
+#[inline]
+fn err(msg: String) -> ErrorResponse {
+    ErrorResponse::new(ErrorResponseType::Internal, msg)
+}
+
+/// Builds the `rustls::ClientConfig` for the global HTTP client from the `[http_client]`
+/// config. It must be passed to `reqwest::ClientBuilder::use_preconfigured_tls()`, which
+/// means the `min_tls_version()` and `danger_accept_invalid_certs()` of the builder will
+/// be ignored and must be handled here.
+pub fn tls_config(
+    config: &VarsHttpClient,
+    danger_insecure: bool,
+) -> Result<rustls::ClientConfig, ErrorResponse> {
+    let versions = protocol_versions(&config.min_tls)?;
+
+    let mut provider = rustls::crypto::ring::default_provider();
+    if !config.tls_cipher_suites.is_empty() {
+        provider.cipher_suites = select_cipher_suites(&provider, &config.tls_cipher_suites)?;
+    }
+    if !config.tls_kx_groups.is_empty() {
+        provider.kx_groups = select_kx_groups(&provider, &config.tls_kx_groups)?;
+    }
+
+    let builder = rustls::ClientConfig::builder_with_provider(Arc::new(provider))
+        .with_protocol_versions(versions)
+        .map_err(|e| {
+            err(format!(
+                "The `[http_client]` TLS policy does not allow any connections: {e}"
+            ))
+        })?;
+
+    let tls_config = if danger_insecure {
+        builder
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(NoTlsVerifier {}))
+            .with_no_client_auth()
+    } else {
+        let mut root_store = rustls::RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        };
+        if let Some(bundle) = &config.root_ca_bundle {
+            let mut count = 0;
+            for cert in CertificateDer::pem_slice_iter(bundle.trim().as_bytes()) {
+                let cert =
+                    cert.map_err(|e| err(format!("Invalid `[http_client].root_ca_bundle`: {e}")))?;
+                root_store
+                    .add(cert)
+                    .map_err(|e| err(format!("Invalid `[http_client].root_ca_bundle`: {e}")))?;
+                count += 1;
+            }
+            debug!("Adding {count} custom Root CA certificates to HTTP Client");
+        }
+
+        builder
+            .with_root_certificates(root_store)
+            .with_no_client_auth()
+    };
+
+    Ok(tls_config)
+}
+
+fn protocol_versions(
+    min_tls: &str,
+) -> Result<&'static [&'static SupportedProtocolVersion], ErrorResponse> {
+    match min_tls {
+        "1.3" => Ok(TLS13_ONLY),
+        "1.2" => Ok(rustls::ALL_VERSIONS),
+        "1.1" | "1.0" => {
+            // rustls never supports anything below TLS 1.2, so we would never actually
+            // downgrade to these versions.
+            warn!(
+                "`[http_client].min_tls = '{min_tls}'` is not supported anymore, TLS 1.2 will \
+                be used as the minimum version"
+            );
+            Ok(rustls::ALL_VERSIONS)
+        }
+        _ => Err(err(
+            "Invalid value for HTTP_MIN_TLS, allowed: '1.3', '1.2'".to_string()
+        )),
+    }
+}
+
+/// Keeps only the given suites, in the given order of preference.
+fn select_cipher_suites(
+    provider: &CryptoProvider,
+    names: &[String],
+) -> Result<Vec<SupportedCipherSuite>, ErrorResponse> {
+    names
+        .iter()
+        .map(|name| {
+            provider
+                .cipher_suites
+                .iter()
+                .find(|s| format!("{:?}", s.suite()).eq_ignore_ascii_case(name))
+                .copied()
+                .ok_or_else(|| {
+                    err(format!(
+                        "Unsupported `[http_client].tls_cipher_suites` value: {name}"
+                    ))
+                })
+        })
+        .collect()
+}
+
+/// Keeps only the given key exchange groups, in the given order of preference.
+fn select_kx_groups(
+    provider: &CryptoProvider,
+    names: &[String],
+) -> Result<Vec<&'static dyn rustls::crypto::SupportedKxGroup>, ErrorResponse> {
+    names
+        .iter()
+        .map(|name| {
+            provider
+                .kx_groups
+                .iter()
+                .find(|g| format!("{:?}", g.name()).eq_ignore_ascii_case(name))
+                .copied()
+                .ok_or_else(|| {
+                    err(format!(
+                        "Unsupported `[http_client].tls_kx_groups` value: {name}"
+                    ))
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> VarsHttpClient {
+        VarsHttpClient {
+            connect_timeout: 10,
+            request_timeout: 10,
+            min_tls: "1.2".into(),
+            idle_timeout: 900,
+            danger_unencrypted: false,
+            danger_insecure: false,
+            root_ca_bundle: None,
+            tls_cipher_suites: Vec::default(),
+            tls_kx_groups: Vec::default(),
+        }
+    }
+
+    #[test]
+    fn test_tls_config_policy() {
+        let cfg = tls_config(&config(), false).unwrap();
+        assert_eq!(
+            cfg.crypto_provider().cipher_suites.len(),
+            rustls::crypto::ring::default_provider().cipher_suites.len()
+        );
+
+        let mut c = config();
+        c.tls_cipher_suites = vec![
+            "TLS13_AES_256_GCM_SHA384".to_string(),
+            "tls_ecdhe_ecdsa_with_aes_256_gcm_sha384".to_string(),
+        ];
+        c.tls_kx_groups = vec!["X25519".to_string()];
+        let cfg = tls_config(&c, false).unwrap();
+        let suites = cfg
+            .crypto_provider()
+            .cipher_suites
+            .iter()
+            .map(|s| format!("{:?}", s.suite()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            suites,
+            [
+                "TLS13_AES_256_GCM_SHA384",
+                "TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384"
+            ]
+        );
+        assert_eq!(cfg.crypto_provider().kx_groups.len(), 1);
+
+        c.tls_cipher_suites = vec!["TLS_RSA_WITH_RC4_128_SHA".to_string()];
+        assert!(tls_config(&c, false).is_err());
+        c.tls_cipher_suites = Vec::default();
+        c.tls_kx_groups = vec!["ffdhe1024".to_string()];
+        assert!(tls_config(&c, false).is_err());
+    }
+
+    #[test]
+    fn test_tls_config_min_version() {
+        let mut c = config();
+        c.min_tls = "1.0".into();
+        assert!(tls_config(&c, false).is_ok());
+        c.min_tls = "1.4".into();
+        assert!(tls_config(&c, false).is_err());
+
+        // only TLS 1.2 suites cannot work with TLS 1.3 only
+        c.min_tls = "1.3".into();
+        c.tls_cipher_suites = vec!["TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256".to_string()];
+        assert!(tls_config(&c, false).is_err());
+    }
+
+    #[test]
+    fn test_tls_config_root_ca_bundle() {
+        let mut c = config();
+        c.root_ca_bundle = Some("-----BEGIN CERTIFICATE-----\ninvalid".to_string());
+        assert!(tls_config(&c, false).is_err());
+    }
+}
@@ -0,0 +1,281 @@
+use crate::database::{Cache, DB};
+use crate::rauthy_config::RauthyConfig;
+use chrono::Utc;
+use cidr::IpCidr;
+use rauthy_common::http_client;
+use rauthy_error::{ErrorResponse, ErrorResponseType};
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::LazyLock;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::{debug, error, info, warn};
+
+/// The score for entries inside the reputation file without an explicit one.
+const DEFAULT_FILE_SCORE: u8 = 100;
+
+static FILE_RANGES: LazyLock<RwLock<FileRanges>> =
+    LazyLock::new(|| RwLock::new(FileRanges::default()));
+
+#[derive(Default)]
+struct FileRanges {
+    /// Unix timestamp in seconds, `0` if it has never been loaded
+    loaded_at: i64,
+    ranges: Vec<(IpCidr, u8)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpReputationDecision {
+    Allow,
+    StepUp,
+    Block,
+}
+
+impl IpReputationDecision {
+    fn from_score(score: u8, threshold_step_up: u8, threshold_block: u8) -> Self {
+        if threshold_block > 0 && score >= threshold_block {
+            Self::Block
+        } else if threshold_step_up > 0 && score >= threshold_step_up {
+            Self::StepUp
+        } else {
+            Self::Allow
+        }
+    }
+}
+
+/// Advisory, score-based IP reputation checks for logins. Sources are a local file with CIDRs,
+/// that is updated out-of-band, and / or an HTTP lookup.
+pub struct IpReputation;
+
+impl IpReputation {
+    /// Returns the decision for a login from the given IP. Always returns `Allow` if no source
+    /// is configured, or if sources are unavailable and `fail_open` is set.
+    pub async fn check(ip: IpAddr) -> Result<IpReputationDecision, ErrorResponse> {
+        let cfg = &RauthyConfig::get().vars.ip_reputation;
+        if cfg.file.is_none() && cfg.url.is_none() {
+            return Ok(IpReputationDecision::Allow);
+        }
+
+        let score = Self::score(ip).await?;
+        let decision =
+            IpReputationDecision::from_score(score, cfg.threshold_step_up, cfg.threshold_block);
+        match decision {
+            IpReputationDecision::Allow => {}
+            IpReputationDecision::StepUp => {
+                info!(%ip, score, "IP reputation step-up required for login");
+            }
+            IpReputationDecision::Block => {
+                warn!(%ip, score, "Blocking login because of IP reputation");
+            }
+        }
+
+        Ok(decision)
+    }
+
+    async fn score(ip: IpAddr) -> Result<u8, ErrorResponse> {
+        let idx = format!("ip_rep_{ip}");
+        let cached: Option<u8> = DB::hql().get(Cache::IpRateLimit, idx.as_str()).await?;
+        if let Some(score) = cached {
+            return Ok(score);
+        }
+
+        let cfg = &RauthyConfig::get().vars.ip_reputation;
+
+        let mut score = 0;
+        if cfg.file.is_some() {
+            match Self::score_file(ip).await {
+                Ok(s) => score = s,
+                Err(err) => return Self::source_unavailable(&err.message),
+            }
+        }
+        if score < 100 && cfg.url.is_some() {
+            match Self::score_http(ip).await {
+                Ok(s) => score = score.max(s),
+                Err(err) => return Self::source_unavailable(&err.message),
+            }
+        }
+        debug!(%ip, score, "IP reputation lookup");
+
+        DB::hql()
+            .put(Cache::IpRateLimit, idx, &score, Some(cfg.cache_ttl as i64))
+            .await?;
+
+        Ok(score)
+    }
+
+    async fn score_file(ip: IpAddr) -> Result<u8, ErrorResponse> {
+        let cfg = &RauthyConfig::get().vars.ip_reputation;
+        let Some(path) = &cfg.file else {
+            return Ok(0);
+        };
+
+        let now = Utc::now().timestamp();
+        {
+            let lock = FILE_RANGES.read().await;
+            if lock.loaded_at + cfg.file_reload_secs as i64 > now {
+                return Ok(Self::score_ranges(&lock.ranges, ip));
+            }
+        }
+
+        let mut lock = FILE_RANGES.write().await;
+        // another task may have reloaded in the meantime
+        if lock.loaded_at + cfg.file_reload_secs as i64 <= now {
+            let content = tokio::fs::read_to_string(path).await.map_err(|err| {
+                ErrorResponse::new(
+                    ErrorResponseType::Internal,
+                    format!("Cannot read IP reputation file {path}: {err}"),
+                )
+            })?;
+            lock.ranges = Self::parse_file(&content);
+            lock.loaded_at = now;
+            debug!("Loaded {} IP reputation ranges", lock.ranges.len());
+        }
+
+        Ok(Self::score_ranges(&lock.ranges, ip))
+    }
+
+    async fn score_http(ip: IpAddr) -> Result<u8, ErrorResponse> {
+        let cfg = &RauthyConfig::get().vars.ip_reputation;
+        let Some(url) = &cfg.url else {
+            return Ok(0);
+        };
+
+        let mut req = http_client()
+            .get(url.replace("{ip}", &ip.to_string()))
+            .timeout(Duration::from_millis(cfg.timeout_ms as u64));
+        for (name, value) in &cfg.headers {
+            req = req.header(name, value);
+        }
+
+        let res = req.send().await?;
+        if !res.status().is_success() {
+            return Err(ErrorResponse::new(
+                ErrorResponseType::Connection,
+                format!("IP reputation lookup returned status {}", res.status()),
+            ));
+        }
+        let json = res.json::<serde_json::Value>().await?;
+
+        Self::score_from_json(&json, &cfg.score_pointer)
+    }
+
+    fn score_from_json(json: &serde_json::Value, pointer: &str) -> Result<u8, ErrorResponse> {
+        json.pointer(pointer)
+            .and_then(|v| v.as_f64())
+            .map(|s| s.clamp(0.0, 100.0) as u8)
+            .ok_or_else(|| {
+                ErrorResponse::new(
+                    ErrorResponseType::Connection,
+                    format!("No numeric score at '{pointer}' in IP reputation response"),
+                )
+            })
+    }
+
+    /// Parses one CIDR per line, optionally followed by a score. Invalid lines will be skipped.
+    fn parse_file(content: &str) -> Vec<(IpCidr, u8)> {
+        content
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut split = line.split_whitespace();
+                let range = split.next()?;
+
+                let cidr = match IpCidr::from_str(range) {
+                    Ok(cidr) => cidr,
+                    Err(err) => {
+                        warn!("Skipping invalid IP reputation entry '{line}': {err}");
+                        return None;
+                    }
+                };
+                let score = match split.next() {
+                    None => DEFAULT_FILE_SCORE,
+                    Some(s) => match s.parse::<u8>() {
+                        Ok(score) => score.min(100),
+                        Err(_) => {
+                            warn!("Skipping IP reputation entry with invalid score '{line}'");
+                            return None;
+                        }
+                    },
+                };
+
+                Some((cidr, score))
+            })
+            .collect()
+    }
+
+    #[inline]
+    fn score_ranges(ranges: &[(IpCidr, u8)], ip: IpAddr) -> u8 {
+        ranges
+            .iter()
+            .filter(|(cidr, _)| cidr.contains(&ip))
+            .map(|(_, score)| *score)
+            .max()
+            .unwrap_or(0)
+    }
+
+    #[inline]
+    fn source_unavailable(msg: &str) -> Result<u8, ErrorResponse> {
+        error!("{msg}");
+
+        if RauthyConfig::get().vars.ip_reputation.fail_open {
+            warn!("IP reputation source unavailable and `fail_open` is set - skipping check");
+            Ok(0)
+        } else {
+            Err(ErrorResponse::new(
+                ErrorResponseType::Connection,
+                "IP reputation source unavailable",
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_file() {
+        let content = r#"
+# comment
+203.0.113.0/24 80
+198.51.100.17
+2001:db8::/32 20
+not-an-ip 50
+192.0.2.1 many
+"#;
+        let ranges = IpReputation::parse_file(content);
+        assert_eq!(ranges.len(), 3);
+
+        let score = |ip: &str| IpReputation::score_ranges(&ranges, ip.parse().unwrap());
+        assert_eq!(score("203.0.113.7"), 80);
+        assert_eq!(score("198.51.100.17"), 100);
+        assert_eq!(score("198.51.100.18"), 0);
+        assert_eq!(score("2001:db8::1"), 20);
+        assert_eq!(score("192.0.2.1"), 0);
+    }
+
+    #[test]
+    fn test_score_from_json() {
+        let json = serde_json::json!({ "data": { "abuseConfidenceScore": 77 } });
+        let score = IpReputation::score_from_json(&json, "/data/abuseConfidenceScore").unwrap();
+        assert_eq!(score, 77);
+
+        let json = serde_json::json!({ "score": 250.5 });
+        assert_eq!(IpReputation::score_from_json(&json, "/score").unwrap(), 100);
+
+        assert!(IpReputation::score_from_json(&json, "/missing").is_err());
+    }
+
+    #[test]
+    fn test_decision() {
+        use IpReputationDecision::*;
+
+        assert_eq!(IpReputationDecision::from_score(0, 50, 90), Allow);
+        assert_eq!(IpReputationDecision::from_score(49, 50, 90), Allow);
+        assert_eq!(IpReputationDecision::from_score(50, 50, 90), StepUp);
+        assert_eq!(IpReputationDecision::from_score(90, 50, 90), Block);
+        assert_eq!(IpReputationDecision::from_score(100, 0, 0), Allow);
+        assert_eq!(IpReputationDecision::from_score(95, 50, 0), StepUp);
+    }
+}
@@ -38,6 +38,35 @@ impl Language {
             Language::ZhHans => "zhhans",
         }
     }
+
+    /// Resolves the language for a page which accepts an OIDC `ui_locales` param.
+    ///
+    /// An explicit selection via the locale cookie always wins, then the first supported
+    /// `ui_locales` entry, then the `accept-language` header, then the default.
+    pub fn from_req_ui_locales(req: &HttpRequest, ui_locales: Option<&str>) -> Self {
+        if req.cookie(COOKIE_LOCALE).is_none()
+            && let Some(lang) = ui_locales.and_then(Self::from_ui_locales)
+        {
+            return lang;
+        }
+        Self::try_from(req).unwrap_or_default()
+    }
+
+    /// Parses a space-separated list of BCP47 tags from the OIDC `ui_locales` param, ordered by
+    /// preference. Each tag is checked as-is first and then by its primary subtag, so `de-AT`
+    /// falls back to `de`. Unsupported tags are simply skipped.
+    pub fn from_ui_locales(ui_locales: &str) -> Option<Self> {
+        let available = Self::all_available();
+        ui_locales.split_whitespace().find_map(|tag| {
+            let primary = tag.split('-').next().unwrap_or_default();
+            [tag, primary].into_iter().find_map(|candidate| {
+                available
+                    .iter()
+                    .find(|lang| lang.eq_ignore_ascii_case(candidate))
+                    .map(|lang| Self::from(*lang))
+            })
+        })
+    }
 }
 
 impl Display for Language {
@@ -125,3 +154,29 @@ impl From<Language> for rauthy_api_types::generic::Language {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_ui_locales() {
+        assert_eq!(Language::from_ui_locales("de"), Some(Language::De));
+        assert_eq!(
+            Language::from_ui_locales("fr-CA fr de-AT en"),
+            Some(Language::De)
+        );
+        assert_eq!(
+            Language::from_ui_locales("zh-Hans-CN"),
+            Some(Language::ZhHans)
+        );
+        assert_eq!(Language::from_ui_locales("NB-no"), Some(Language::Nb));
+        assert_eq!(
+            Language::from_ui_locales("  uk-UA  ko "),
+            Some(Language::Uk)
+        );
+        assert_eq!(Language::from_ui_locales("fr es-ES"), None);
+        assert_eq!(Language::from_ui_locales(""), None);
+        assert_eq!(Language::from_ui_locales("-"), None);
+    }
+}
@@ -0,0 +1,237 @@
+use crate::database::NoTlsVerifier;
+use crate::rauthy_config::VarsLdap;
+use deadpool::managed::{self, Metrics, RecycleError, RecycleResult};
+use ldap3::{LdapConnAsync, LdapConnSettings, LdapError, LdapResult, Scope, SearchOptions};
+use rauthy_error::{ErrorResponse, ErrorResponseType};
+use rustls::pki_types::CertificateDer;
+use rustls::pki_types::pem::PemObject;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+pub const RESULT_SUCCESS: u32 = 0;
+pub const RESULT_SIZE_LIMIT_EXCEEDED: u32 = 4;
+pub const RESULT_INVALID_CREDENTIALS: u32 = 49;
+
+#[derive(Debug)]
+pub struct SearchEntry {
+    pub dn: String,
+    /// Text and binary attributes combined
+    pub attrs: Vec<(String, Vec<Vec<u8>>)>,
+}
+
+impl From<ldap3::SearchEntry> for SearchEntry {
+    fn from(entry: ldap3::SearchEntry) -> Self {
+        let attrs = entry
+            .attrs
+            .into_iter()
+            .map(|(name, values)| (name, values.into_iter().map(String::into_bytes).collect()))
+            .chain(entry.bin_attrs)
+            .collect();
+        Self {
+            dn: entry.dn,
+            attrs,
+        }
+    }
+}
+
+impl SearchEntry {
+    pub fn values(&self, attr: &str) -> Option<&Vec<Vec<u8>>> {
+        self.attrs
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(attr))
+            .map(|(_, values)| values)
+    }
+
+    pub fn first_str(&self, attr: &str) -> Option<String> {
+        self.values(attr)
+            .and_then(|v| v.first())
+            .map(|v| String::from_utf8_lossy(v).to_string())
+    }
+}
+
+pub struct LdapConn {
+    ldap: ldap3::Ldap,
+    timeout: Duration,
+    /// Set as soon as any operation failed in a way that leaves the connection in an unknown
+    /// state. Broken connections will not be recycled into the pool.
+    broken: bool,
+}
+
+impl LdapConn {
+    async fn connect(config: &VarsLdap, settings: LdapConnSettings) -> Result<Self, ErrorResponse> {
+        let url = config.url.as_deref().unwrap_or_default();
+        let (conn, ldap) = LdapConnAsync::with_settings(settings, url)
+            .await
+            .map_err(|err| map_err("connect", err))?;
+
+        tokio::spawn(async move {
+            if let Err(err) = conn.drive().await {
+                warn!("LDAP connection error: {err}");
+            }
+        });
+
+        Ok(Self {
+            ldap,
+            timeout: Duration::from_millis(config.timeout_ms as u64),
+            broken: false,
+        })
+    }
+
+    /// Performs a simple bind. An `invalidCredentials` result is returned as `Ok(_)`, because
+    /// it does not leave the connection in a broken state.
+    pub async fn bind(&mut self, dn: &str, password: &str) -> Result<LdapResult, ErrorResponse> {
+        let res = self
+            .ldap
+            .with_timeout(self.timeout)
+            .simple_bind(dn, password)
+            .await
+            .map_err(|err| map_err("bind", err));
+
+        if res.is_err() {
+            self.broken = true;
+        }
+        res
+    }
+
+    pub async fn search(
+        &mut self,
+        base_dn: &str,
+        filter: &str,
+        attrs: &[&str],
+    ) -> Result<Vec<SearchEntry>, ErrorResponse> {
+        // We only ever look for a single user. A size limit of 2 is enough to detect
+        // ambiguous filters without transferring a whole directory.
+        let res = self
+            .ldap
+            .with_timeout(self.timeout)
+            .with_search_options(SearchOptions::new().sizelimit(2))
+            .search(base_dn, Scope::Subtree, filter, attrs)
+            .await;
+
+        let (entries, res) = match res {
+            Ok(ldap3::SearchResult(entries, res)) => (entries, res),
+            Err(err) => {
+                self.broken = true;
+                return Err(map_err("search", err));
+            }
+        };
+        // sizeLimitExceeded is handled via the entries length
+        if res.rc != RESULT_SUCCESS && res.rc != RESULT_SIZE_LIMIT_EXCEEDED {
+            return Err(ErrorResponse::new(
+                ErrorResponseType::Connection,
+                format!("LDAP search failed: {} {}", res.rc, res.text),
+            ));
+        }
+        if !res.refs.is_empty() {
+            debug!("Ignoring LDAP search result references");
+        }
+
+        Ok(entries
+            .into_iter()
+            .map(|e| SearchEntry::from(ldap3::SearchEntry::construct(e)))
+            .collect())
+    }
+
+    pub fn mark_broken(&mut self) {
+        self.broken = true;
+    }
+}
+
+#[inline]
+fn map_err(op: &str, err: LdapError) -> ErrorResponse {
+    let typ = if matches!(err, LdapError::Timeout { .. }) {
+        ErrorResponseType::Timeout
+    } else {
+        ErrorResponseType::Connection
+    };
+    ErrorResponse::new(typ, format!("LDAP {op} failed: {err}"))
+}
+
+pub fn build_tls_config(config: &VarsLdap) -> Result<Arc<rustls::ClientConfig>, ErrorResponse> {
+    let tls_config = if config.danger_insecure {
+        rustls::ClientConfig::builder()
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(NoTlsVerifier {}))
+            .with_no_client_auth()
+    } else {
+        let mut root_store = rustls::RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        };
+        if let Some(pem) = &config.root_ca {
+            for cert in CertificateDer::pem_slice_iter(pem.as_bytes()) {
+                let cert = cert.map_err(|err| {
+                    ErrorResponse::new(
+                        ErrorResponseType::Internal,
+                        format!("Invalid `[ldap].root_ca`: {err}"),
+                    )
+                })?;
+                root_store.add(cert).map_err(|err| {
+                    ErrorResponse::new(
+                        ErrorResponseType::Internal,
+                        format!("Invalid `[ldap].root_ca`: {err}"),
+                    )
+                })?;
+            }
+        }
+        rustls::ClientConfig::builder()
+            .with_root_certificates(root_store)
+            .with_no_client_auth()
+    };
+
+    Ok(Arc::new(tls_config))
+}
+
+/// Creates connections which are already bound with the configured service account.
+pub struct LdapManager {
+    pub config: &'static VarsLdap,
+    pub tls: Arc<rustls::ClientConfig>,
+}
+
+impl LdapManager {
+    /// Binds the service account, or falls back to an anonymous bind without a `bind_dn`.
+    /// This must be called after each user bind, because the connection would stay bound as
+    /// that user otherwise, when it goes back into the pool.
+    pub async fn bind_service(&self, conn: &mut LdapConn) -> Result<(), ErrorResponse> {
+        let res = match &self.config.bind_dn {
+            Some(dn) => {
+                conn.bind(dn, self.config.bind_password.as_deref().unwrap_or_default())
+                    .await?
+            }
+            // an empty DN and password resets the connection to anonymous
+            None => conn.bind("", "").await?,
+        };
+        if res.rc != RESULT_SUCCESS {
+            conn.mark_broken();
+            return Err(ErrorResponse::new(
+                ErrorResponseType::Connection,
+                format!("LDAP service account bind failed: {} {}", res.rc, res.text),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl managed::Manager for LdapManager {
+    type Type = LdapConn;
+    type Error = ErrorResponse;
+
+    async fn create(&self) -> Result<LdapConn, ErrorResponse> {
+        let settings = LdapConnSettings::new()
+            .set_conn_timeout(Duration::from_millis(self.config.timeout_ms as u64))
+            .set_starttls(self.config.starttls)
+            .set_config(self.tls.clone());
+
+        let mut conn = LdapConn::connect(self.config, settings).await?;
+        self.bind_service(&mut conn).await?;
+        Ok(conn)
+    }
+
+    async fn recycle(&self, conn: &mut LdapConn, _: &Metrics) -> RecycleResult<ErrorResponse> {
+        if conn.broken || conn.ldap.is_closed() {
+            Err(RecycleError::message("broken LDAP connection"))
+        } else {
+            Ok(())
+        }
+    }
+}
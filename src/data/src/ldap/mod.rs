@@ -0,0 +1,464 @@
+use crate::entity::groups::Group;
+use crate::entity::roles::Role;
+use crate::entity::user_ldap_links::UserLdapLink;
+use crate::entity::users::User;
+use crate::ldap::conn::{
+    LdapConn, LdapManager, RESULT_INVALID_CREDENTIALS, RESULT_SUCCESS, SearchEntry,
+    build_tls_config,
+};
+use crate::rauthy_config::{RauthyConfig, VarsLdap};
+use chrono::Utc;
+use deadpool::managed::{Object, Pool, PoolError};
+use rauthy_error::{ErrorResponse, ErrorResponseType};
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+mod conn;
+
+static POOL: OnceLock<Pool<LdapManager>> = OnceLock::new();
+
+/// Authentication against an upstream LDAP / AD directory via a simple bind with the users'
+/// credentials. Users are looked up with the configured service account, created locally on
+/// their first login and linked via their immutable directory GUID.
+pub struct Ldap;
+
+impl Ldap {
+    #[inline]
+    pub fn is_enabled() -> bool {
+        RauthyConfig::get().vars.ldap.url.is_some()
+    }
+
+    #[inline]
+    fn config() -> &'static VarsLdap {
+        &RauthyConfig::get().vars.ldap
+    }
+
+    /// Returns `true` if the given user is linked to the directory and must therefore always
+    /// authenticate against it.
+    pub async fn is_linked(user_id: &str) -> Result<bool, ErrorResponse> {
+        if !Self::is_enabled() {
+            return Ok(false);
+        }
+        let link = UserLdapLink::find(user_id, &Self::config().provider_id).await?;
+        Ok(link.is_some())
+    }
+
+    fn pool() -> Result<&'static Pool<LdapManager>, ErrorResponse> {
+        if let Some(pool) = POOL.get() {
+            return Ok(pool);
+        }
+
+        let config = Self::config();
+        let mgr = LdapManager {
+            config,
+            tls: build_tls_config(config)?,
+        };
+        let timeout = Duration::from_millis(config.timeout_ms as u64);
+        let pool = Pool::builder(mgr)
+            .max_size(config.pool_size as usize)
+            .runtime(deadpool::Runtime::Tokio1)
+            .create_timeout(Some(timeout))
+            .wait_timeout(Some(timeout))
+            .recycle_timeout(Some(timeout))
+            .build()?;
+
+        // if another task was faster, its pool wins and ours is simply dropped
+        let _ = POOL.set(pool);
+        Ok(POOL.get().unwrap())
+    }
+
+    async fn conn() -> Result<Object<LdapManager>, ErrorResponse> {
+        Self::pool()?.get().await.map_err(|err| match err {
+            PoolError::Backend(err) => err,
+            err => ErrorResponse::new(
+                ErrorResponseType::Connection,
+                format!("Cannot get an LDAP connection: {err}"),
+            ),
+        })
+    }
+
+    /// Authenticates the user against the directory and returns the synced local user.
+    ///
+    /// If `user` is given, it must already be linked to the directory entry. Otherwise, a
+    /// possibly existing link for the directory GUID is used, or a new user will be created.
+    pub async fn login(
+        email: &str,
+        password: &str,
+        user: Option<User>,
+    ) -> Result<User, ErrorResponse> {
+        // An empty password would result in an unauthenticated bind, which succeeds on most
+        // servers without checking anything.
+        if password.is_empty() {
+            return Err(Self::invalid_credentials());
+        }
+
+        let config = Self::config();
+        let mut conn = Self::conn().await?;
+
+        let entry = Self::search_user(&mut conn, config, email).await?;
+
+        let res = conn.bind(&entry.dn, password).await?;
+        if res.rc != RESULT_SUCCESS {
+            // rebind the service account, so the connection can be re-used
+            Self::pool()?.manager().bind_service(&mut conn).await?;
+            drop(conn);
+
+            if res.rc == RESULT_INVALID_CREDENTIALS {
+                Self::record_failed_login(config, &entry, user).await?;
+                return Err(Self::invalid_credentials());
+            }
+            error!(
+                "Unexpected LDAP bind result for '{}': {} {}",
+                entry.dn, res.rc, res.text
+            );
+            return Err(ErrorResponse::new(
+                ErrorResponseType::Connection,
+                "Error during LDAP authentication",
+            ));
+        }
+
+        if let Err(err) = Self::pool()?.manager().bind_service(&mut conn).await {
+            // the user itself has been authenticated successfully at this point
+            warn!("Cannot re-bind the LDAP service account: {}", err.message);
+        }
+        drop(conn);
+
+        Self::sync_user(config, email, entry, user).await
+    }
+
+    async fn search_user(
+        conn: &mut LdapConn,
+        config: &VarsLdap,
+        email: &str,
+    ) -> Result<SearchEntry, ErrorResponse> {
+        let filter = config
+            .user_filter
+            .replace("{email}", &ldap3::ldap_escape(email));
+
+        let attrs = [
+            config.attr_uid.as_ref(),
+            config.attr_email.as_ref(),
+            config.attr_given_name.as_ref(),
+            config.attr_family_name.as_ref(),
+            config.attr_groups.as_ref(),
+        ];
+        let mut entries = conn
+            .search(
+                config.base_dn.as_deref().unwrap_or_default(),
+                &filter,
+                &attrs,
+            )
+            .await?;
+
+        match entries.len() {
+            0 => {
+                debug!("No LDAP entry found for '{email}'");
+                Err(Self::invalid_credentials())
+            }
+            1 => Ok(entries.swap_remove(0)),
+            _ => {
+                warn!("LDAP user filter matches multiple entries for '{email}'");
+                Err(Self::invalid_credentials())
+            }
+        }
+    }
+
+    async fn sync_user(
+        config: &VarsLdap,
+        email: &str,
+        entry: SearchEntry,
+        user: Option<User>,
+    ) -> Result<User, ErrorResponse> {
+        let Some(uid) = entry
+            .values(&config.attr_uid)
+            .and_then(|v| v.first())
+            .map(|v| format_uid(v))
+        else {
+            error!(
+                "LDAP entry '{}' has no `{}` attribute",
+                entry.dn, config.attr_uid
+            );
+            return Err(ErrorResponse::new(
+                ErrorResponseType::Internal,
+                "LDAP entry is missing its unique ID",
+            ));
+        };
+
+        let dir_email = entry
+            .first_str(&config.attr_email)
+            .unwrap_or_else(|| email.to_string())
+            .to_lowercase();
+        let given_name = entry.first_str(&config.attr_given_name);
+        let family_name = entry.first_str(&config.attr_family_name);
+        let ldap_groups = entry
+            .values(&config.attr_groups)
+            .map(|values| {
+                values
+                    .iter()
+                    .map(|v| String::from_utf8_lossy(v).to_string())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        let now = Utc::now().timestamp();
+        let link = UserLdapLink::find_by_uid(&config.provider_id, &uid).await?;
+
+        let mut user = match (user, link) {
+            (Some(user), Some(link)) => {
+                if user.id != link.user_id {
+                    warn!(
+                        "LDAP entry '{}' is linked to another user than '{}'",
+                        entry.dn, user.id
+                    );
+                    return Err(Self::invalid_credentials());
+                }
+                user
+            }
+            (Some(user), None) => {
+                // The user is linked, but the GUID does not match -> the directory entry has
+                // been replaced, which must never silently take over the local account.
+                warn!(
+                    "LDAP entry '{}' does not match the linked directory ID of user '{}'",
+                    entry.dn, user.id
+                );
+                return Err(Self::invalid_credentials());
+            }
+            (None, Some(link)) => {
+                // the email has changed inside the directory
+                User::find(link.user_id).await?
+            }
+            (None, None) => {
+                let new_user = User {
+                    email: dir_email.clone(),
+                    given_name: given_name.clone().unwrap_or_default(),
+                    family_name: family_name.clone(),
+                    enabled: true,
+                    email_verified: true,
+                    last_login: Some(now),
+                    ..Default::default()
+                };
+                let user = User::create_federated(new_user).await?;
+                UserLdapLink::create(user.id.clone(), config.provider_id.to_string(), uid).await?;
+                info!("New user '{}' created from LDAP", user.email);
+                user
+            }
+        };
+
+        let mut old_email = None;
+        if user.email != dir_email {
+            old_email = Some(std::mem::replace(&mut user.email, dir_email));
+        }
+        if let Some(given_name) = given_name {
+            user.given_name = given_name;
+        }
+        if family_name.is_some() {
+            user.family_name = family_name;
+        }
+
+        if !config.role_mappings.is_empty() {
+            let existing = Role::find_all()
+                .await?
+                .into_iter()
+                .map(|r| r.name)
+                .collect::<HashSet<_>>();
+            let roles = apply_mappings(
+                &user.get_roles(),
+                &ldap_groups,
+                &config.role_mappings,
+                &existing,
+            );
+            user.roles = roles.join(",");
+        }
+        if !config.group_mappings.is_empty() {
+            let existing = Group::find_all()
+                .await?
+                .into_iter()
+                .map(|g| g.name)
+                .collect::<HashSet<_>>();
+            let groups = apply_mappings(
+                &user.get_groups(),
+                &ldap_groups,
+                &config.group_mappings,
+                &existing,
+            );
+            user.groups = if groups.is_empty() {
+                None
+            } else {
+                Some(groups.join(","))
+            };
+        }
+
+        user.last_login = Some(now);
+        user.last_failed_login = None;
+        user.failed_login_attempts = None;
+        user.save(old_email).await?;
+
+        Ok(user)
+    }
+
+    /// Counts a failed bind for the local user, just like a wrong password for local accounts.
+    async fn record_failed_login(
+        config: &VarsLdap,
+        entry: &SearchEntry,
+        user: Option<User>,
+    ) -> Result<(), ErrorResponse> {
+        let user = match user {
+            Some(user) => Some(user),
+            None => match entry.values(&config.attr_uid).and_then(|v| v.first()) {
+                Some(uid) => {
+                    match UserLdapLink::find_by_uid(&config.provider_id, &format_uid(uid)).await? {
+                        Some(link) => Some(User::find(link.user_id).await?),
+                        None => None,
+                    }
+                }
+                None => None,
+            },
+        };
+
+        if let Some(mut user) = user {
+            user.last_failed_login = Some(Utc::now().timestamp());
+            user.failed_login_attempts = Some(user.failed_login_attempts.unwrap_or(0) + 1);
+            user.save(None).await?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn invalid_credentials() -> ErrorResponse {
+        ErrorResponse::new(ErrorResponseType::Unauthorized, "Invalid user credentials")
+    }
+}
+
+/// Formats the directory ID. An AD `objectGUID` is a 16 byte binary value in mixed endian
+/// format, which we convert into its usual UUID string representation.
+fn format_uid(value: &[u8]) -> String {
+    if value.len() == 16 && std::str::from_utf8(value).is_err() {
+        let mut res = String::with_capacity(36);
+        for (i, idx) in [3, 2, 1, 0, 5, 4, 7, 6, 8, 9, 10, 11, 12, 13, 14, 15]
+            .into_iter()
+            .enumerate()
+        {
+            if i == 4 || i == 6 || i == 8 || i == 10 {
+                res.push('-');
+            }
+            let _ = write!(res, "{:02x}", value[idx]);
+        }
+        res
+    } else if let Ok(s) = std::str::from_utf8(value) {
+        s.to_string()
+    } else {
+        value
+            .iter()
+            .fold(String::with_capacity(value.len() * 2), |mut acc, b| {
+                let _ = write!(acc, "{b:02x}");
+                acc
+            })
+    }
+}
+
+/// Returns the new list of values after applying the mapping rules. Only targets that appear in
+/// any rule are managed by the directory. They are added when the user is a member of a mapped
+/// LDAP group and removed otherwise. All other values stay untouched.
+fn apply_mappings(
+    current: &[String],
+    ldap_groups: &[String],
+    mappings: &[(String, String)],
+    existing: &HashSet<String>,
+) -> Vec<String> {
+    let ldap_groups = ldap_groups
+        .iter()
+        .flat_map(|g| {
+            let g = g.to_lowercase();
+            // match the full DN as well as only the first CN
+            let cn = g
+                .strip_prefix("cn=")
+                .and_then(|rest| rest.split(',').next())
+                .map(String::from);
+            [Some(g), cn]
+        })
+        .flatten()
+        .collect::<HashSet<_>>();
+
+    let managed = mappings
+        .iter()
+        .map(|(_, target)| target.as_str())
+        .collect::<HashSet<_>>();
+    let mut res = current
+        .iter()
+        .filter(|v| !v.is_empty() && !managed.contains(v.as_str()))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    for (source, target) in mappings {
+        if !ldap_groups.contains(source) || res.contains(target) {
+            continue;
+        }
+        if !existing.contains(target) {
+            warn!("LDAP mapping target '{target}' does not exist - skipping");
+            continue;
+        }
+        res.push(target.clone());
+    }
+
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_uid() {
+        let guid = [
+            0x67, 0x45, 0x23, 0x01, 0xab, 0x89, 0xef, 0xcd, 0x01, 0x23, 0x45, 0x67, 0x89, 0xab,
+            0xcd, 0xef,
+        ];
+        assert_eq!(format_uid(&guid), "01234567-89ab-cdef-0123-456789abcdef");
+        assert_eq!(format_uid(b"8f0c1a2e"), "8f0c1a2e");
+        assert_eq!(format_uid(&[0xff, 0x00]), "ff00");
+    }
+
+    #[test]
+    fn test_apply_mappings() {
+        let mappings = vec![
+            (
+                "cn=admins,ou=groups,dc=example,dc=com".to_string(),
+                "admin".to_string(),
+            ),
+            ("developers".to_string(), "dev".to_string()),
+            ("ops".to_string(), "missing".to_string()),
+        ];
+        let existing = ["admin", "dev", "user"]
+            .into_iter()
+            .map(String::from)
+            .collect::<HashSet<_>>();
+
+        // unmanaged values stay, managed ones get added by DN and by CN
+        let res = apply_mappings(
+            &["user".to_string()],
+            &[
+                "CN=Admins,OU=Groups,DC=example,DC=com".to_string(),
+                "cn=Developers,ou=groups,dc=example,dc=com".to_string(),
+                "cn=ops,ou=groups,dc=example,dc=com".to_string(),
+            ],
+            &mappings,
+            &existing,
+        );
+        assert_eq!(res, vec!["user", "admin", "dev"]);
+
+        // managed values are removed when the group membership is gone
+        let res = apply_mappings(
+            &["admin".to_string(), "user".to_string(), "dev".to_string()],
+            &["cn=developers,dc=example,dc=com".to_string()],
+            &mappings,
+            &existing,
+        );
+        assert_eq!(res, vec!["user", "dev"]);
+
+        let res = apply_mappings(&[String::default()], &[], &mappings, &existing);
+        assert!(res.is_empty());
+    }
+}
@@ -5,13 +5,18 @@ use actix_web::http::header::{HeaderName, HeaderValue};
 use std::fmt::{Display, Formatter};
 
 pub mod api_cookie;
+pub mod captcha;
+pub mod claims_provider;
 pub mod database;
 pub mod email;
 pub mod entity;
 pub mod events;
 pub mod html;
+pub mod http_client;
+pub mod ip_reputation;
 pub mod ipgeo;
 pub mod language;
+pub mod ldap;
 pub mod migration;
 pub mod rauthy_config;
 pub mod temp_migrations;
@@ -22,6 +27,8 @@ pub enum AuthStep {
     AwaitToSAccept(AwaitToSAccept),
     AwaitWebauthn(AuthStepAwaitWebauthn),
     ProviderLink,
+    /// Contains the `Location` of the password reset page after an account recovery
+    ProviderRecovery(HeaderValue),
 }
 
 pub struct AuthStepLoggedIn {
@@ -2,6 +2,7 @@ use crate::database::DB;
 use crate::entity::clients::Client;
 use crate::rauthy_config::RauthyConfig;
 use deadpool_postgres::GenericClient;
+use rauthy_api_types::clients::{SubjectType, UriMatchMode};
 use rauthy_common::is_hiqlite;
 use rauthy_error::ErrorResponse;
 use tracing::debug;
@@ -44,6 +45,7 @@ pub async fn anti_lockout() -> Result<(), ErrorResponse> {
         confidential: false,
         secret: None,
         secret_kid: None,
+        secret_hashed: false,
         redirect_uris,
         post_logout_redirect_uris: None,
         allowed_origins,
@@ -60,6 +62,15 @@ pub async fn anti_lockout() -> Result<(), ErrorResponse> {
         contacts: vars.email.rauthy_admin_email.clone(),
         backchannel_logout_uri: None,
         restrict_group_prefix: None,
+        introspect: false,
+        claim_mapping: None,
+        claim_namespace: None,
+        refresh_token_max_lifetime: None,
+        require_nonce: false,
+        first_party: true,
+        subject_type: SubjectType::Public,
+        sector_identifier_uri: None,
+        uri_match_mode: UriMatchMode::Strict,
     };
     debug!(client = ?rauthy, "Rauthy client anti-lockout");
 
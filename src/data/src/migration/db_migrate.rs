@@ -31,6 +31,9 @@ use crate::entity::theme::{ThemeCss, ThemeCssFull};
 use crate::entity::tos::ToS;
 use crate::entity::tos_user_accept::ToSUserAccept;
 use crate::entity::user_attr::{UserAttrConfigEntity, UserAttrValueEntity};
+use crate::entity::user_emails::UserEmail;
+use crate::entity::user_federation::UserFederation;
+use crate::entity::user_ldap_links::UserLdapLink;
 use crate::entity::user_login_states::UserLoginState;
 use crate::entity::user_revoke::UserRevoke;
 use crate::entity::users::User;
@@ -43,13 +46,67 @@ use crate::rauthy_config::RauthyConfig;
 use hiqlite_macros::params;
 use itertools::Itertools;
 use rauthy_common::constants::RAUTHY_VERSION;
+use rauthy_common::is_hiqlite;
 use rauthy_common::utils::deserialize;
-use rauthy_error::ErrorResponse;
+use rauthy_error::{ErrorResponse, ErrorResponseType};
+use ring::digest;
 use semver::Version;
 use serde::Deserialize;
 use std::fmt::Debug;
 use std::str::FromStr;
-use tracing::{debug, info};
+use tracing::{debug, error, info};
+
+/// All tables checked during `MIGRATE_VERIFY`. The checksum is built over the full content of
+/// each row, which makes it possible to detect modified values and not only missing rows.
+static VERIFY_TABLES: &[&str] = &[
+    "config",
+    "api_keys",
+    "auth_providers",
+    "auth_provider_logos",
+    "pictures",
+    "users",
+    "user_federations",
+    "user_emails",
+    "user_ldap_links",
+    "passkeys",
+    "clients",
+    "clients_dyn",
+    "client_logos",
+    "clients_scim",
+    "groups",
+    "jwks",
+    "magic_links",
+    "refresh_tokens",
+    "roles",
+    "scopes",
+    "events",
+    "user_attr_config",
+    "user_attr_values",
+    "users_values",
+    "user_revoke",
+    "devices",
+    "refresh_tokens_devices",
+    "sessions",
+    "user_login_states",
+    "login_locations",
+    "failed_backchannel_logouts",
+    "failed_scim_tasks",
+    "recent_passwords",
+    "themes",
+    "webids",
+    "pam_groups",
+    "pam_hosts",
+    "pam_hosts_aliases",
+    "pam_hosts_ips",
+    "pam_users",
+    "pam_rel_groups_users",
+    "tos",
+    "tos_user_accept",
+    "email_jobs",
+    "ssh_auth_keys",
+    "ssh_auth_keys_used",
+    "issued_tokens",
+];
 
 async fn query_sqlite<T>(conn: &rusqlite::Connection, query: &str) -> Result<Vec<T>, ErrorResponse>
 where
@@ -91,18 +148,24 @@ pub async fn migrate_from_sqlite(db_from: &str) -> Result<(), ErrorResponse> {
     // CONFIG
     debug!("Migrating table: config");
     let before = query_sqlite::<ConfigEntity>(&conn, "SELECT * FROM config").await?;
+    let rows = before.len();
     inserts::config(before).await?;
+    log_progress("config", rows);
 
     // API KEYS
     debug!("Migrating table: api_keys");
     let before = query_sqlite::<ApiKeyEntity>(&conn, "SELECT * FROM api_keys").await?;
+    let rows = before.len();
     inserts::api_keys(before).await?;
+    log_progress("api_keys", rows);
 
     // The users table has a FK to auth_providers - the order is important here!
     // AUTH PROVIDERS
     debug!("Migrating table: auth_providers");
     let before = query_sqlite::<AuthProvider>(&conn, "SELECT * FROM auth_providers").await?;
+    let rows = before.len();
     inserts::auth_providers(before).await?;
+    log_progress("auth_providers", rows);
 
     // AUTH PROVIDER LOGOS
     debug!("Migrating table: auth_provider_logos");
@@ -111,34 +174,67 @@ pub async fn migrate_from_sqlite(db_from: &str) -> Result<(), ErrorResponse> {
         "SELECT auth_provider_id AS id, res, content_type, data, updated FROM auth_provider_logos",
     )
     .await?;
+    let rows = before.len();
     inserts::auth_provider_logos(before).await?;
+    log_progress("auth_provider_logos", rows);
 
     // users has an FK to pictures
     // PICTURES
     debug!("Migrating table: pictures");
     let before = query_sqlite::<UserPicture>(&conn, "SELECT * FROM pictures").await?;
+    let rows = before.len();
     inserts::pictures(before).await?;
+    log_progress("pictures", rows);
 
     // USERS
     debug!("Migrating table: users");
     let before = query_sqlite::<User>(&conn, "SELECT * FROM users").await?;
+    let rows = before.len();
     inserts::users(before).await?;
+    log_progress("users", rows);
+
+    // USER FEDERATIONS
+    debug!("Migrating table: user_federations");
+    let before = query_sqlite::<UserFederation>(&conn, "SELECT * FROM user_federations").await?;
+    let rows = before.len();
+    inserts::user_federations(before).await?;
+    log_progress("user_federations", rows);
+
+    // USER EMAILS
+    debug!("Migrating table: user_emails");
+    let before = query_sqlite::<UserEmail>(&conn, "SELECT * FROM user_emails").await?;
+    let rows = before.len();
+    inserts::user_emails(before).await?;
+    log_progress("user_emails", rows);
+
+    // USER LDAP LINKS
+    debug!("Migrating table: user_ldap_links");
+    let before = query_sqlite::<UserLdapLink>(&conn, "SELECT * FROM user_ldap_links").await?;
+    let rows = before.len();
+    inserts::user_ldap_links(before).await?;
+    log_progress("user_ldap_links", rows);
 
     // PASSKEYS
     debug!("Migrating table: passkeys");
     let before = query_sqlite::<PasskeyEntity>(&conn, "SELECT * FROM passkeys").await?;
+    let rows = before.len();
     inserts::passkeys(before).await?;
+    log_progress("passkeys", rows);
 
     // Do not change the order - tables below have FKs to clients
     // CLIENTS
     debug!("Migrating table: clients");
     let before = query_sqlite::<Client>(&conn, "SELECT * FROM clients").await?;
+    let rows = before.len();
     inserts::clients(before).await?;
+    log_progress("clients", rows);
 
     // CLIENTS DYN
     debug!("Migrating table: clients_dyn");
     let before = query_sqlite::<ClientDyn>(&conn, "SELECT * FROM clients_dyn").await?;
+    let rows = before.len();
     inserts::clients_dyn(before).await?;
+    log_progress("clients_dyn", rows);
 
     // CLIENT LOGOS
     debug!("Migrating table: client_logos");
@@ -147,7 +243,9 @@ pub async fn migrate_from_sqlite(db_from: &str) -> Result<(), ErrorResponse> {
         "SELECT client_id AS id, res, content_type, data, updated FROM client_logos",
     )
     .await?;
+    let rows = before.len();
     inserts::client_logos(before).await?;
+    log_progress("client_logos", rows);
 
     // CLIENTS SCIM
     debug!("Migrating table: clients_scim");
@@ -166,37 +264,51 @@ pub async fn migrate_from_sqlite(db_from: &str) -> Result<(), ErrorResponse> {
         })?
         .map(|r| r.unwrap())
         .collect_vec();
+    let rows = before.len();
     inserts::clients_scim(before).await?;
+    log_progress("clients_scim", rows);
 
     // GROUPS
     debug!("Migrating table: groups");
     let before = query_sqlite::<Group>(&conn, "SELECT * FROM groups").await?;
+    let rows = before.len();
     inserts::groups(before).await?;
+    log_progress("groups", rows);
 
     // JWKS
     debug!("Migrating table: jwks");
     let before = query_sqlite::<Jwk>(&conn, "SELECT * FROM jwks").await?;
+    let rows = before.len();
     inserts::jwks(before).await?;
+    log_progress("jwks", rows);
 
     // MAGIC LINKS
     debug!("Migrating table: magic_links");
     let before = query_sqlite::<MagicLink>(&conn, "SELECT * FROM magic_links").await?;
+    let rows = before.len();
     inserts::magic_links(before).await?;
+    log_progress("magic_links", rows);
 
     // REFRESH TOKENS
     debug!("Migrating table: refresh_tokens");
     let before = query_sqlite::<RefreshToken>(&conn, "SELECT * FROM refresh_tokens").await?;
+    let rows = before.len();
     inserts::refresh_tokens(before).await?;
+    log_progress("refresh_tokens", rows);
 
     // ROLES
     debug!("Migrating table: roles");
     let before = query_sqlite::<Role>(&conn, "SELECT * FROM roles").await?;
+    let rows = before.len();
     inserts::roles(before).await?;
+    log_progress("roles", rows);
 
     // SCOPES
     debug!("Migrating table: scopes");
     let before = query_sqlite::<Scope>(&conn, "SELECT * FROM scopes").await?;
+    let rows = before.len();
     inserts::scopes(before).await?;
+    log_progress("scopes", rows);
 
     // EVENTS
     debug!("Migrating table: events");
@@ -215,24 +327,32 @@ pub async fn migrate_from_sqlite(db_from: &str) -> Result<(), ErrorResponse> {
         })?
         .map(|r| r.unwrap())
         .collect_vec();
+    let rows = before.len();
     inserts::events(before).await?;
+    log_progress("events", rows);
 
     // USER ATTR CONFIG
     debug!("Migrating table: user_attr_config");
     let before =
         query_sqlite::<UserAttrConfigEntity>(&conn, "SELECT * FROM user_attr_config").await?;
+    let rows = before.len();
     inserts::user_attr_config(before).await?;
+    log_progress("user_attr_config", rows);
 
     // USER ATTR VALUES
     debug!("Migrating table: user_attr_values");
     let before =
         query_sqlite::<UserAttrValueEntity>(&conn, "SELECT * FROM user_attr_values").await?;
+    let rows = before.len();
     inserts::user_attr_values(before).await?;
+    log_progress("user_attr_values", rows);
 
     // USERS VALUES
     debug!("Migrating table: users_values");
     let before = query_sqlite::<UserValues>(&conn, "SELECT * FROM users_values").await?;
+    let rows = before.len();
     inserts::users_values(before).await?;
+    log_progress("users_values", rows);
 
     // USER REVOKE
     debug!("Migrating table: user_revoke");
@@ -246,18 +366,24 @@ pub async fn migrate_from_sqlite(db_from: &str) -> Result<(), ErrorResponse> {
         })?
         .map(|r| r.unwrap())
         .collect_vec();
+    let rows = before.len();
     inserts::user_revoke(before).await?;
+    log_progress("user_revoke", rows);
 
     // DEVICES
     debug!("Migrating table: devices");
     let before = query_sqlite::<DeviceEntity>(&conn, "SELECT * FROM devices").await?;
+    let rows = before.len();
     inserts::devices(before).await?;
+    log_progress("devices", rows);
 
     // REFRESH TOKENS DEVICES
     debug!("Migrating table: refresh_tokens_devices");
     let before =
         query_sqlite::<RefreshTokenDevice>(&conn, "SELECT * FROM refresh_tokens_devices").await?;
+    let rows = before.len();
     inserts::refresh_tokens_devices(before).await?;
+    log_progress("refresh_tokens_devices", rows);
 
     // SESSIONS
     debug!("Migrating table: sessions");
@@ -281,12 +407,16 @@ pub async fn migrate_from_sqlite(db_from: &str) -> Result<(), ErrorResponse> {
         })?
         .map(|r| r.unwrap())
         .collect_vec();
+    let rows = before.len();
     inserts::sessions(before).await?;
+    log_progress("sessions", rows);
 
     // USER LOGIN STATES
     debug!("Migrating table: user_login_states");
     let before = query_sqlite::<UserLoginState>(&conn, "SELECT * FROM user_login_states").await?;
+    let rows = before.len();
     inserts::user_login_states(before).await?;
+    log_progress("user_login_states", rows);
 
     // LOGIN LOCATIONS
     debug!("Migrating table: login_locations");
@@ -304,14 +434,18 @@ pub async fn migrate_from_sqlite(db_from: &str) -> Result<(), ErrorResponse> {
         })?
         .map(|r| r.unwrap())
         .collect_vec();
+    let rows = before.len();
     inserts::login_locations(before).await?;
+    log_progress("login_locations", rows);
 
     // FAILED BACKCHANNEL LOGOUTS
     debug!("Migrating table: failed_backchannel_logouts");
     let before =
         query_sqlite::<FailedBackchannelLogout>(&conn, "SELECT * FROM failed_backchannel_logouts")
             .await?;
+    let rows = before.len();
     inserts::failed_backchannel_logouts(before).await?;
+    log_progress("failed_backchannel_logouts", rows);
 
     // FAILED SCIM TASKS
     debug!("Migrating table: failed_scim_tasks");
@@ -326,13 +460,17 @@ pub async fn migrate_from_sqlite(db_from: &str) -> Result<(), ErrorResponse> {
         })?
         .map(|r| r.unwrap())
         .collect_vec();
+    let rows = before.len();
     inserts::failed_scim_tasks(before).await?;
+    log_progress("failed_scim_tasks", rows);
 
     // RECENT PASSWORDS
     debug!("Migrating table: recent_passwords");
     let before =
         query_sqlite::<RecentPasswordsEntity>(&conn, "SELECT * FROM recent_passwords").await?;
+    let rows = before.len();
     inserts::recent_passwords(before).await?;
+    log_progress("recent_passwords", rows);
 
     // THEMES
     debug!("Migrating table: themes");
@@ -359,12 +497,16 @@ pub async fn migrate_from_sqlite(db_from: &str) -> Result<(), ErrorResponse> {
         })?
         .map(|r| r.unwrap())
         .collect_vec();
+    let rows = before.len();
     inserts::themes(before).await?;
+    log_progress("themes", rows);
 
     // WEBIDS
     debug!("Migrating table: webids");
     let before = query_sqlite::<WebId>(&conn, "SELECT * FROM webids").await?;
+    let rows = before.len();
     inserts::webids(before).await?;
+    log_progress("webids", rows);
 
     // PAM GROUPS
     debug!("Migrating table: pam_groups");
@@ -379,7 +521,9 @@ pub async fn migrate_from_sqlite(db_from: &str) -> Result<(), ErrorResponse> {
         })?
         .map(|r| r.unwrap())
         .collect_vec();
+    let rows = before.len();
     inserts::pam_groups(before).await?;
+    log_progress("pam_groups", rows);
 
     // PAM HOSTS
     debug!("Migrating table: pam_hosts");
@@ -400,7 +544,9 @@ pub async fn migrate_from_sqlite(db_from: &str) -> Result<(), ErrorResponse> {
         })?
         .map(|r| r.unwrap())
         .collect_vec();
+    let rows = before.len();
     inserts::pam_hosts(before).await?;
+    log_progress("pam_hosts", rows);
 
     // PAM HOSTS ALIASES
     debug!("Migrating table: pam_hosts_aliases");
@@ -413,7 +559,9 @@ pub async fn migrate_from_sqlite(db_from: &str) -> Result<(), ErrorResponse> {
         })?
         .map(|r| r.unwrap())
         .collect_vec();
+    let rows = before.len();
     inserts::pam_hosts_aliases(before).await?;
+    log_progress("pam_hosts_aliases", rows);
 
     // PAM HOSTS IPS
     debug!("Migrating table: pam_hosts_ips");
@@ -426,7 +574,9 @@ pub async fn migrate_from_sqlite(db_from: &str) -> Result<(), ErrorResponse> {
         })?
         .map(|r| r.unwrap())
         .collect_vec();
+    let rows = before.len();
     inserts::pam_hosts_ips(before).await?;
+    log_progress("pam_hosts_ips", rows);
 
     // PAM USERS
     debug!("Migrating table: pam_users");
@@ -443,7 +593,9 @@ pub async fn migrate_from_sqlite(db_from: &str) -> Result<(), ErrorResponse> {
         })?
         .map(|r| r.unwrap())
         .collect_vec();
+    let rows = before.len();
     inserts::pam_users(before).await?;
+    log_progress("pam_users", rows);
 
     // PAM REL GROUPS USERS
     debug!("Migrating table: pam_rel_groups_users");
@@ -457,17 +609,23 @@ pub async fn migrate_from_sqlite(db_from: &str) -> Result<(), ErrorResponse> {
         })?
         .map(|r| r.unwrap())
         .collect_vec();
+    let rows = before.len();
     inserts::pam_rel_groups_users(before).await?;
+    log_progress("pam_rel_groups_users", rows);
 
     // TOS
     debug!("Migrating table: tos");
     let before = query_sqlite::<ToS>(&conn, "SELECT * FROM tos").await?;
+    let rows = before.len();
     inserts::tos(before).await?;
+    log_progress("tos", rows);
 
     // TOS USER ACCEPT
     debug!("Migrating table: tos_user_accept");
     let before = query_sqlite::<ToSUserAccept>(&conn, "SELECT * FROM tos_user_accept").await?;
+    let rows = before.len();
     inserts::tos_user_accept(before).await?;
+    log_progress("tos_user_accept", rows);
 
     // EMAIL JOBS
     debug!("Migrating table: email_jobs");
@@ -493,7 +651,9 @@ pub async fn migrate_from_sqlite(db_from: &str) -> Result<(), ErrorResponse> {
         })?
         .map(|r| r.unwrap())
         .collect_vec();
+    let rows = before.len();
     inserts::email_jobs(before).await?;
+    log_progress("email_jobs", rows);
 
     // SSH AUTH KEYS
     debug!("Migrating table: ssh_auth_keys");
@@ -512,7 +672,9 @@ pub async fn migrate_from_sqlite(db_from: &str) -> Result<(), ErrorResponse> {
         })?
         .map(|r| r.unwrap())
         .collect_vec();
+    let rows = before.len();
     inserts::ssh_auth_keys(before).await?;
+    log_progress("ssh_auth_keys", rows);
 
     // SSH AUTH KEYS USED
     debug!("Migrating table: ssh_auth_keys_used");
@@ -525,7 +687,9 @@ pub async fn migrate_from_sqlite(db_from: &str) -> Result<(), ErrorResponse> {
         })?
         .map(|r| r.unwrap())
         .collect_vec();
+    let rows = before.len();
     inserts::ssh_auth_keys_used(before).await?;
+    log_progress("ssh_auth_keys_used", rows);
 
     // ISSUED TOKENS
     debug!("Migrating table: issued_tokens");
@@ -534,6 +698,7 @@ pub async fn migrate_from_sqlite(db_from: &str) -> Result<(), ErrorResponse> {
         .query_map([], |row| {
             Ok(IssuedToken {
                 jti: row.get("jti")?,
+                client_id: row.get("client_id")?,
                 user_id: row.get("user_id")?,
                 did: row.get("did")?,
                 sid: row.get("sid")?,
@@ -543,7 +708,36 @@ pub async fn migrate_from_sqlite(db_from: &str) -> Result<(), ErrorResponse> {
         })?
         .map(|r| r.unwrap())
         .collect_vec();
+    let rows = before.len();
     inserts::issued_tokens(before).await?;
+    log_progress("issued_tokens", rows);
+
+    info!("All tables migrated successfully");
+
+    if RauthyConfig::get().vars.database.migrate_verify {
+        let mut sources = Vec::with_capacity(VERIFY_TABLES.len());
+        for table in VERIFY_TABLES {
+            let mut stmt = conn.prepare(&VerifyColumn::query_sqlite(table))?;
+            let cols_source = stmt
+                .query_map([], |row| {
+                    Ok(VerifyColumn::from_sqlite(
+                        row.get("name")?,
+                        row.get("type")?,
+                    ))
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            let cols =
+                VerifyColumn::intersect(cols_source, VerifyColumn::find_target(table).await?);
+
+            let mut stmt = conn.prepare(&verify_query(table, &cols, true))?;
+            let rows = stmt
+                .query_map([], |row| row.get::<_, String>("k"))?
+                .map(|r| r.map(|row| row_digest(&row)))
+                .collect::<Result<Vec<_>, _>>()?;
+            sources.push((*table, cols, rows));
+        }
+        verify(sources).await?;
+    }
 
     Ok(())
 }
@@ -581,18 +775,24 @@ pub async fn migrate_from_postgres() -> Result<(), ErrorResponse> {
     // CONFIG
     debug!("Migrating table: config");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM config", &[], 1).await?;
+    let rows = before.len();
     inserts::config(before).await?;
+    log_progress("config", rows);
 
     // API KEYS
     debug!("Migrating table: api_keys");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM api_keys", &[], 0).await?;
+    let rows = before.len();
     inserts::api_keys(before).await?;
+    log_progress("api_keys", rows);
 
     // The users table has a FK to auth_providers - the order is important here!
     // AUTH PROVIDERS
     debug!("Migrating table: auth_providers");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM auth_providers", &[], 0).await?;
+    let rows = before.len();
     inserts::auth_providers(before).await?;
+    log_progress("auth_providers", rows);
 
     // AUTH PROVIDER LOGOS
     debug!("Migrating table: auth_provider_logos");
@@ -603,33 +803,66 @@ pub async fn migrate_from_postgres() -> Result<(), ErrorResponse> {
         0,
     )
     .await?;
+    let rows = before.len();
     inserts::auth_provider_logos(before).await?;
+    log_progress("auth_provider_logos", rows);
 
     // PICTURES
     debug!("Migrating table: pictures");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM pictures", &[], 0).await?;
+    let rows = before.len();
     inserts::pictures(before).await?;
+    log_progress("pictures", rows);
 
     // USERS
     debug!("Migrating table: users");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM users", &[], 2).await?;
+    let rows = before.len();
     inserts::users(before).await?;
+    log_progress("users", rows);
+
+    // USER FEDERATIONS
+    debug!("Migrating table: user_federations");
+    let before = DB::pg_query_map_with(&cl, "SELECT * FROM user_federations", &[], 0).await?;
+    let rows = before.len();
+    inserts::user_federations(before).await?;
+    log_progress("user_federations", rows);
+
+    // USER EMAILS
+    debug!("Migrating table: user_emails");
+    let before = DB::pg_query_map_with(&cl, "SELECT * FROM user_emails", &[], 0).await?;
+    let rows = before.len();
+    inserts::user_emails(before).await?;
+    log_progress("user_emails", rows);
+
+    // USER LDAP LINKS
+    debug!("Migrating table: user_ldap_links");
+    let before = DB::pg_query_map_with(&cl, "SELECT * FROM user_ldap_links", &[], 0).await?;
+    let rows = before.len();
+    inserts::user_ldap_links(before).await?;
+    log_progress("user_ldap_links", rows);
 
     // PASSKEYS
     debug!("Migrating table: passkeys");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM passkeys", &[], 0).await?;
+    let rows = before.len();
     inserts::passkeys(before).await?;
+    log_progress("passkeys", rows);
 
     // Do not change the order - tables below have FKs to clients
     // CLIENTS
     debug!("Migrating table: clients");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM clients", &[], 2).await?;
+    let rows = before.len();
     inserts::clients(before).await?;
+    log_progress("clients", rows);
 
     // CLIENTS DYN
     debug!("Migrating table: clients_dyn");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM clients_dyn", &[], 0).await?;
+    let rows = before.len();
     inserts::clients_dyn(before).await?;
+    log_progress("clients_dyn", rows);
 
     // CLIENT LOGOS
     debug!("Migrating table: client_logos");
@@ -640,7 +873,9 @@ pub async fn migrate_from_postgres() -> Result<(), ErrorResponse> {
         0,
     )
     .await?;
+    let rows = before.len();
     inserts::client_logos(before).await?;
+    log_progress("client_logos", rows);
 
     // CLIENTS SCIM
     debug!("Migrating table: clients_scim");
@@ -660,93 +895,129 @@ pub async fn migrate_from_postgres() -> Result<(), ErrorResponse> {
             }
         })
         .collect::<Vec<_>>();
+    let rows = before.len();
     inserts::clients_scim(before).await?;
+    log_progress("clients_scim", rows);
 
     // GROUPS
     debug!("Migrating table: groups");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM groups", &[], 4).await?;
+    let rows = before.len();
     inserts::groups(before).await?;
+    log_progress("groups", rows);
 
     // JWKS
     debug!("Migrating table: jwks");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM jwks", &[], 8).await?;
+    let rows = before.len();
     inserts::jwks(before).await?;
+    log_progress("jwks", rows);
 
     // MAGIC LINKS
     debug!("Migrating table: magic_links");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM magic_links", &[], 0).await?;
+    let rows = before.len();
     inserts::magic_links(before).await?;
+    log_progress("magic_links", rows);
 
     // REFRESH TOKENS
     debug!("Migrating table: refresh_tokens");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM refresh_tokens", &[], 0).await?;
+    let rows = before.len();
     inserts::refresh_tokens(before).await?;
+    log_progress("refresh_tokens", rows);
 
     // ROLES
     debug!("Migrating table: roles");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM roles", &[], 6).await?;
+    let rows = before.len();
     inserts::roles(before).await?;
+    log_progress("roles", rows);
 
     // SCOPES
     debug!("Migrating table: scopes");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM scopes", &[], 0).await?;
+    let rows = before.len();
     inserts::scopes(before).await?;
+    log_progress("scopes", rows);
 
     // EVENTS
     debug!("Migrating table: events");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM events", &[], 32).await?;
+    let rows = before.len();
     inserts::events(before).await?;
+    log_progress("events", rows);
 
     // USER ATTR CONFIG
     debug!("Migrating table: user_attr_config");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM user_attr_config", &[], 0).await?;
+    let rows = before.len();
     inserts::user_attr_config(before).await?;
+    log_progress("user_attr_config", rows);
 
     // USER ATTR VALUES
     debug!("Migrating table: user_attr_values");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM user_attr_values", &[], 0).await?;
+    let rows = before.len();
     inserts::user_attr_values(before).await?;
+    log_progress("user_attr_values", rows);
 
     // USERS VALUES
     debug!("Migrating table: users_values");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM users_values", &[], 0).await?;
+    let rows = before.len();
     inserts::users_values(before).await?;
+    log_progress("users_values", rows);
 
     // USER REVOKE
     debug!("Migrating table: user_revoke");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM user_revoke", &[], 0).await?;
+    let rows = before.len();
     inserts::user_revoke(before).await?;
+    log_progress("user_revoke", rows);
 
     // DEVICES
     debug!("Migrating table: devices");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM devices", &[], 0).await?;
+    let rows = before.len();
     inserts::devices(before).await?;
+    log_progress("devices", rows);
 
     // REFRESH TOKENS DEVICES
     debug!("Migrating table: refresh_tokens_devices");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM refresh_tokens_devices", &[], 0).await?;
+    let rows = before.len();
     inserts::refresh_tokens_devices(before).await?;
+    log_progress("refresh_tokens_devices", rows);
 
     // SESSIONS
     debug!("Migrating table: sessions");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM sessions", &[], 16).await?;
+    let rows = before.len();
     inserts::sessions(before).await?;
+    log_progress("sessions", rows);
 
     // USER LOGIN STATES
     debug!("Migrating table: user_login_states");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM user_login_states", &[], 0).await?;
+    let rows = before.len();
     inserts::user_login_states(before).await?;
+    log_progress("user_login_states", rows);
 
     // LOGIN LOCATIONS
     debug!("Migrating table: login_locations");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM login_locations", &[], 0).await?;
+    let rows = before.len();
     inserts::login_locations(before).await?;
+    log_progress("login_locations", rows);
 
     // FAILED BACKCHANNEL LOGOUTS
     debug!("Migrating table: failed_backchannel_logouts");
     let before =
         DB::pg_query_map_with(&cl, "SELECT * FROM failed_backchannel_logouts", &[], 0).await?;
+    let rows = before.len();
     inserts::failed_backchannel_logouts(before).await?;
+    log_progress("failed_backchannel_logouts", rows);
 
     // FAILED SCIM TASKS
     debug!("Migrating table: failed_scim_tasks");
@@ -759,32 +1030,44 @@ pub async fn migrate_from_postgres() -> Result<(), ErrorResponse> {
             retry_count: row.get::<_, i32>("retry_count") as i64,
         })
         .collect::<Vec<_>>();
+    let rows = before.len();
     inserts::failed_scim_tasks(before).await?;
+    log_progress("failed_scim_tasks", rows);
 
     // RECENT PASSWORDS
     debug!("Migrating table: recent_passwords");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM recent_passwords", &[], 0).await?;
+    let rows = before.len();
     inserts::recent_passwords(before).await?;
+    log_progress("recent_passwords", rows);
 
     // THEMES
     debug!("Migrating table: themes");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM themes", &[], 0).await?;
+    let rows = before.len();
     inserts::themes(before).await?;
+    log_progress("themes", rows);
 
     // WEBIDS
     debug!("Migrating table: webids");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM webids", &[], 0).await?;
+    let rows = before.len();
     inserts::webids(before).await?;
+    log_progress("webids", rows);
 
     // PAM GROUPS
     debug!("Migrating table: pam_groups");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM pam_groups", &[], 0).await?;
+    let rows = before.len();
     inserts::pam_groups(before).await?;
+    log_progress("pam_groups", rows);
 
     // PAM HOSTS
     debug!("Migrating table: pam_hosts");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM pam_hosts", &[], 0).await?;
+    let rows = before.len();
     inserts::pam_hosts(before).await?;
+    log_progress("pam_hosts", rows);
 
     // PAM HOSTS ALIASES
     debug!("Migrating table: pam_hosts_aliases");
@@ -797,7 +1080,9 @@ pub async fn migrate_from_postgres() -> Result<(), ErrorResponse> {
             (id, alias)
         })
         .collect::<Vec<_>>();
+    let rows = before.len();
     inserts::pam_hosts_aliases(before).await?;
+    log_progress("pam_hosts_aliases", rows);
 
     // PAM HOSTS IPS
     debug!("Migrating table: pam_hosts_ips");
@@ -810,12 +1095,16 @@ pub async fn migrate_from_postgres() -> Result<(), ErrorResponse> {
             (id, ip)
         })
         .collect::<Vec<_>>();
+    let rows = before.len();
     inserts::pam_hosts_ips(before).await?;
+    log_progress("pam_hosts_ips", rows);
 
     // PAM USERS
     debug!("Migrating table: pam_users");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM pam_users", &[], 0).await?;
+    let rows = before.len();
     inserts::pam_users(before).await?;
+    log_progress("pam_users", rows);
 
     // PAM REL GROUPS USERS
     debug!("Migrating table: pam_rel_groups_users");
@@ -829,27 +1118,37 @@ pub async fn migrate_from_postgres() -> Result<(), ErrorResponse> {
             (gid, uid, wheel)
         })
         .collect::<Vec<_>>();
+    let rows = before.len();
     inserts::pam_rel_groups_users(before).await?;
+    log_progress("pam_rel_groups_users", rows);
 
     // TOS
     debug!("Migrating table: tos");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM tos", &[], 0).await?;
+    let rows = before.len();
     inserts::tos(before).await?;
+    log_progress("tos", rows);
 
     // TOS USER ACCEPT
     debug!("Migrating table: tos_user_accept");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM tos_user_accept", &[], 0).await?;
+    let rows = before.len();
     inserts::tos_user_accept(before).await?;
+    log_progress("tos_user_accept", rows);
 
     // EMAIL JOBS
     debug!("Migrating table: email_jobs");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM email_jobs", &[], 0).await?;
+    let rows = before.len();
     inserts::email_jobs(before).await?;
+    log_progress("email_jobs", rows);
 
     // SSH AUTH KEYS
     debug!("Migrating table: ssh_auth_keys");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM ssh_auth_keys", &[], 0).await?;
+    let rows = before.len();
     inserts::ssh_auth_keys(before).await?;
+    log_progress("ssh_auth_keys", rows);
 
     // SSH AUTH KEYS USED
     debug!("Migrating table: ssh_auth_keys_used");
@@ -862,16 +1161,229 @@ pub async fn migrate_from_postgres() -> Result<(), ErrorResponse> {
             (used_key_hash, ts_added)
         })
         .collect::<Vec<_>>();
+    let rows = before.len();
     inserts::ssh_auth_keys_used(before).await?;
+    log_progress("ssh_auth_keys_used", rows);
 
     // ISSUED TOKENS
     debug!("Migrating table: issued_tokens");
     let before = DB::pg_query_map_with(&cl, "SELECT * FROM issued_tokens", &[], 0).await?;
+    let rows = before.len();
     inserts::issued_tokens(before).await?;
+    log_progress("issued_tokens", rows);
+
+    info!("All tables migrated successfully");
+
+    if RauthyConfig::get().vars.database.migrate_verify {
+        let mut sources = Vec::with_capacity(VERIFY_TABLES.len());
+        for table in VERIFY_TABLES {
+            let cols_source =
+                DB::pg_query_rows_with(&cl, VerifyColumn::QUERY_PG, &[&table.to_string()], 0)
+                    .await?
+                    .into_iter()
+                    .map(|row| VerifyColumn::from_pg(row.get("name"), row.get("type")))
+                    .collect::<Vec<_>>();
+            let cols =
+                VerifyColumn::intersect(cols_source, VerifyColumn::find_target(table).await?);
+
+            let rows = DB::pg_query_rows_with(&cl, &verify_query(table, &cols, false), &[], 0)
+                .await?
+                .into_iter()
+                .map(|row| row_digest(row.get("k")))
+                .collect::<Vec<_>>();
+            sources.push((*table, cols, rows));
+        }
+        verify(sources).await?;
+    }
 
     Ok(())
 }
 
+#[inline]
+fn log_progress(table: &str, rows: usize) {
+    info!("Migrated table {table}: {rows} rows");
+}
+
+/// The table with its columns and the row digests from the source database.
+type VerifySource<'a> = (&'a str, Vec<VerifyColumn>, Vec<Vec<u8>>);
+
+/// A column, which is part of the checksum during `MIGRATE_VERIFY`.
+#[derive(Debug, Clone, PartialEq)]
+struct VerifyColumn {
+    name: String,
+    kind: VerifyColumnKind,
+}
+
+/// Only the types, which have a different text representation in SQLite and Postgres.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum VerifyColumnKind {
+    Blob,
+    Bool,
+    Other,
+}
+
+impl VerifyColumn {
+    const QUERY_PG: &'static str = r#"
+SELECT column_name::TEXT AS name, data_type::TEXT AS type
+FROM information_schema.columns
+WHERE table_schema = current_schema() AND table_name = $1"#;
+
+    fn query_sqlite(table: &str) -> String {
+        format!("SELECT name, type FROM pragma_table_info('{table}')")
+    }
+
+    fn from_sqlite(name: String, typ: String) -> Self {
+        let kind = if typ.eq_ignore_ascii_case("blob") {
+            VerifyColumnKind::Blob
+        } else {
+            VerifyColumnKind::Other
+        };
+        Self { name, kind }
+    }
+
+    fn from_pg(name: String, typ: String) -> Self {
+        let kind = match typ.as_str() {
+            "bytea" => VerifyColumnKind::Blob,
+            "boolean" => VerifyColumnKind::Bool,
+            _ => VerifyColumnKind::Other,
+        };
+        Self { name, kind }
+    }
+
+    async fn find_target(table: &str) -> Result<Vec<Self>, ErrorResponse> {
+        let cols = if is_hiqlite() {
+            DB::hql()
+                .query_raw(Self::query_sqlite(table), params!())
+                .await?
+                .into_iter()
+                .map(|mut row| Self::from_sqlite(row.get("name"), row.get("type")))
+                .collect()
+        } else {
+            DB::pg_query_rows(Self::QUERY_PG, &[&table], 0)
+                .await?
+                .into_iter()
+                .map(|row| Self::from_pg(row.get("name"), row.get("type")))
+                .collect()
+        };
+        Ok(cols)
+    }
+
+    /// Returns the columns existing on both sides, sorted by name. A Postgres `BOOLEAN` is an
+    /// `INTEGER` with SQLite, which is why the more specific kind wins.
+    fn intersect(source: Vec<Self>, target: Vec<Self>) -> Vec<Self> {
+        let mut cols = source
+            .into_iter()
+            .filter_map(|mut col| {
+                let other = target.iter().find(|c| c.name == col.name)?;
+                if col.kind == VerifyColumnKind::Other {
+                    col.kind = other.kind;
+                }
+                Some(col)
+            })
+            .collect::<Vec<_>>();
+        cols.sort_by(|a, b| a.name.cmp(&b.name));
+        cols
+    }
+
+    /// The expression returning the value as `TEXT`, which is the same for both databases.
+    /// `NULL`s are mapped to `n`, while all other values get a `v` prefix to keep them apart.
+    fn expr(&self, sqlite: bool) -> String {
+        let name = &self.name;
+        let value = match (self.kind, sqlite) {
+            (VerifyColumnKind::Blob, true) => format!(r#"lower(hex("{name}"))"#),
+            (VerifyColumnKind::Blob, false) => format!(r#"encode("{name}", 'hex')"#),
+            (VerifyColumnKind::Bool, false) => {
+                format!(r#"CAST(CAST("{name}" AS INTEGER) AS TEXT)"#)
+            }
+            (_, _) => format!(r#"CAST("{name}" AS TEXT)"#),
+        };
+        format!("COALESCE('v' || {value}, 'n')")
+    }
+}
+
+/// Builds the query returning the full content of each row as a single `k` column.
+fn verify_query(table: &str, cols: &[VerifyColumn], sqlite: bool) -> String {
+    let sep = if sqlite {
+        " || char(31) || "
+    } else {
+        " || chr(31) || "
+    };
+    let row = cols.iter().map(|c| c.expr(sqlite)).join(sep);
+    format!("SELECT {row} AS k FROM {table}")
+}
+
+#[inline]
+fn row_digest(row: &str) -> Vec<u8> {
+    digest::digest(&digest::SHA256, row.as_bytes())
+        .as_ref()
+        .to_vec()
+}
+
+/// Calculates a checksum over all (sorted) row digests to be independent of the database
+/// specific result order.
+fn checksum(rows: &mut [Vec<u8>]) -> String {
+    rows.sort_unstable();
+
+    let mut ctx = digest::Context::new(&digest::SHA256);
+    for row in rows.iter() {
+        ctx.update(row);
+    }
+    hex::encode(ctx.finish())
+}
+
+/// Compares the row count and checksum for each table from the `MIGRATE_DB_FROM` source
+/// with the current database and returns an error, if any of them do not match.
+async fn verify(sources: Vec<VerifySource<'_>>) -> Result<(), ErrorResponse> {
+    info!("Verifying migrated data");
+
+    let mut mismatches = Vec::new();
+    for (table, cols, mut rows_source) in sources {
+        let sql = verify_query(table, &cols, is_hiqlite());
+        let mut rows_target = if is_hiqlite() {
+            DB::hql()
+                .query_raw(sql, params!())
+                .await?
+                .into_iter()
+                .map(|mut row| row_digest(&row.get::<String>("k")))
+                .collect::<Vec<_>>()
+        } else {
+            DB::pg_query_rows(&sql, &[], 0)
+                .await?
+                .into_iter()
+                .map(|row| row_digest(row.get("k")))
+                .collect::<Vec<_>>()
+        };
+
+        let count_source = rows_source.len();
+        let count_target = rows_target.len();
+        let checksum_source = checksum(&mut rows_source);
+        let checksum_target = checksum(&mut rows_target);
+
+        if count_source == count_target && checksum_source == checksum_target {
+            info!("Verified table {table}: {count_target} rows, checksum {checksum_target}");
+        } else {
+            error!(
+                "Verification failed for table {table}: source {count_source} rows / \
+                {checksum_source}, target {count_target} rows / {checksum_target}"
+            );
+            mismatches.push(table);
+        }
+    }
+
+    if mismatches.is_empty() {
+        info!("Verification of all migrated tables successful");
+        Ok(())
+    } else {
+        Err(ErrorResponse::new(
+            ErrorResponseType::Database,
+            format!(
+                "Migration verification failed for tables: {}",
+                mismatches.join(", ")
+            ),
+        ))
+    }
+}
+
 /// Makes sure that the given version matches the current app version in major and feature level.
 /// Will panic if they don't match.
 fn check_feature_version_migrate(version: semver::Version) {
@@ -884,3 +1396,47 @@ fn check_feature_version_migrate(version: semver::Version) {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_query() {
+        let source = vec![
+            VerifyColumn::from_sqlite("id".to_string(), "TEXT".to_string()),
+            VerifyColumn::from_sqlite("enabled".to_string(), "INTEGER".to_string()),
+            VerifyColumn::from_sqlite("data".to_string(), "BLOB".to_string()),
+            VerifyColumn::from_sqlite("only_sqlite".to_string(), "TEXT".to_string()),
+        ];
+        let target = vec![
+            VerifyColumn::from_pg("id".to_string(), "character varying".to_string()),
+            VerifyColumn::from_pg("enabled".to_string(), "boolean".to_string()),
+            VerifyColumn::from_pg("data".to_string(), "bytea".to_string()),
+        ];
+        let cols = VerifyColumn::intersect(source, target);
+        assert_eq!(
+            cols.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(),
+            vec!["data", "enabled", "id"]
+        );
+
+        assert_eq!(
+            verify_query("t", &cols, true),
+            r#"SELECT COALESCE('v' || lower(hex("data")), 'n') || char(31) || COALESCE('v' || CAST("enabled" AS TEXT), 'n') || char(31) || COALESCE('v' || CAST("id" AS TEXT), 'n') AS k FROM t"#
+        );
+        assert_eq!(
+            verify_query("t", &cols, false),
+            r#"SELECT COALESCE('v' || encode("data", 'hex'), 'n') || chr(31) || COALESCE('v' || CAST(CAST("enabled" AS INTEGER) AS TEXT), 'n') || chr(31) || COALESCE('v' || CAST("id" AS TEXT), 'n') AS k FROM t"#
+        );
+    }
+
+    #[test]
+    fn test_checksum_order_independent() {
+        let mut a = vec![row_digest("a"), row_digest("b")];
+        let mut b = vec![row_digest("b"), row_digest("a")];
+        assert_eq!(checksum(&mut a), checksum(&mut b));
+
+        let mut c = vec![row_digest("a"), row_digest("c")];
+        assert_ne!(checksum(&mut a), checksum(&mut c));
+    }
+}
@@ -219,9 +219,123 @@ ON CONFLICT DO NOTHING"#;
         .await?;
     }
 
+    // a pending additional E-Mail address for `admin@localhost` with its confirm link, and a
+    // password reset link for the same user to check resets via additional addresses
+    let email_add = "admin.secondary@localhost";
+    let links = [
+        MagicLink {
+            id: "EmAdd8jxH0UfJ7gtIvZq3Nc2ZdPmgXsQ1bLR4kTnWyVoEa6Bp9CrDuS5FlYhKzMx".to_string(),
+            user_id: "za9UxpH7XVxqrtpEbThoqvn2".to_string(),
+            csrf_token: "J4bRqQm3Lw6nXy8UvTz1Ks5PoHd7GcFa9EjNi2ZBeRt0MWlC".to_string(),
+            cookie: None,
+            exp: Utc::now().add(chrono::Duration::days(1)).timestamp(),
+            used: false,
+            usage: MagicLinkUsage::EmailAdd(email_add.to_string()).to_string(),
+        },
+        MagicLink {
+            id: "PwdRst5tGkWn2QxLb7HcVu0ZyJ3mDe8FaRo1NsKi6TpXqMjB4lEvCh9SdUgYwPzA".to_string(),
+            user_id: "za9UxpH7XVxqrtpEbThoqvn2".to_string(),
+            csrf_token: "Xc7VnB2mQ9wE4rT1yU6iO3pA8sD5fG0hJkLzPqWeRtYuIoMn".to_string(),
+            cookie: None,
+            exp: Utc::now().add(chrono::Duration::days(1)).timestamp(),
+            used: false,
+            usage: MagicLinkUsage::PasswordReset(None).to_string(),
+        },
+    ];
+    let sql_email = r#"
+INSERT INTO user_emails (email, user_id, verified, created_at)
+VALUES ($1, $2, $3, $4)
+ON CONFLICT DO NOTHING"#;
+    let now = Utc::now().timestamp();
+
+    if is_hiqlite() {
+        DB::hql()
+            .execute(
+                sql_email,
+                params!(email_add, "za9UxpH7XVxqrtpEbThoqvn2", false, now),
+            )
+            .await?;
+        for ml in links {
+            DB::hql()
+                .execute(
+                    sql_2,
+                    params!(ml.id, ml.user_id, ml.csrf_token, ml.exp, false, ml.usage),
+                )
+                .await?;
+        }
+    } else {
+        DB::pg_execute(
+            sql_email,
+            &[&email_add, &"za9UxpH7XVxqrtpEbThoqvn2", &false, &now],
+        )
+        .await?;
+        for ml in links {
+            DB::pg_execute(
+                sql_2,
+                &[
+                    &ml.id,
+                    &ml.user_id,
+                    &ml.csrf_token,
+                    &ml.exp,
+                    &false,
+                    &ml.usage,
+                ],
+            )
+            .await?;
+        }
+    }
+
+    // A user, which has been soft-deleted longer ago than `user_delete.disabled_retention_days`.
+    // The very first run of the cleanup scheduler at startup must delete it.
+    let sql_user = r#"
+INSERT INTO users
+(id, email, given_name, roles, enabled, email_verified, created_at, language, disabled_at)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+ON CONFLICT DO NOTHING"#;
+    let id = "DsbldRtntnExprdUsr000001";
+    let email = "disabled.expired@localhost";
+    let disabled_at = now - 365 * 86400;
+    if is_hiqlite() {
+        DB::hql()
+            .execute(
+                sql_user,
+                params!(
+                    id,
+                    email,
+                    "Expired",
+                    "",
+                    false,
+                    true,
+                    now,
+                    "en",
+                    disabled_at
+                ),
+            )
+            .await?;
+    } else {
+        DB::pg_execute(
+            sql_user,
+            &[
+                &id,
+                &email,
+                &"Expired",
+                &"",
+                &false,
+                &true,
+                &now,
+                &"en",
+                &disabled_at,
+            ],
+        )
+        .await?;
+    }
+
     // make sure `init_client` has `profile` as default scope to make user picture integration
-    // tests succeed
-    let sql = "UPDATE clients SET default_scopes = 'email,openid,profile' WHERE id = 'init_client'";
+    // tests succeed, and allow it to be used as a resource server for token introspection
+    let sql = r#"
+UPDATE clients
+SET default_scopes = 'email,openid,profile', introspect = true
+WHERE id = 'init_client'"#;
     if is_hiqlite() {
         DB::hql().execute(sql, params!()).await?;
     } else {
@@ -0,0 +1,227 @@
+use crate::database::DB;
+use hiqlite_macros::params;
+use rauthy_common::is_hiqlite;
+use rauthy_error::ErrorResponse;
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::{info, warn};
+
+/// A single referential consistency check. The `child` table references the `parent`
+/// table with its `fk` column. Nullable references are only checked when they are set.
+struct Check {
+    child: &'static str,
+    fk: &'static str,
+    parent: &'static str,
+    parent_pk: &'static str,
+    nullable: bool,
+}
+
+static CHECKS: &[Check] = &[
+    Check {
+        child: "user_federations",
+        fk: "user_id",
+        parent: "users",
+        parent_pk: "id",
+        nullable: false,
+    },
+    Check {
+        child: "user_federations",
+        fk: "provider_id",
+        parent: "auth_providers",
+        parent_pk: "id",
+        nullable: false,
+    },
+    Check {
+        child: "user_emails",
+        fk: "user_id",
+        parent: "users",
+        parent_pk: "id",
+        nullable: false,
+    },
+    Check {
+        child: "user_ldap_links",
+        fk: "user_id",
+        parent: "users",
+        parent_pk: "id",
+        nullable: false,
+    },
+    Check {
+        child: "sessions",
+        fk: "user_id",
+        parent: "users",
+        parent_pk: "id",
+        nullable: true,
+    },
+    Check {
+        child: "refresh_tokens",
+        fk: "user_id",
+        parent: "users",
+        parent_pk: "id",
+        nullable: false,
+    },
+    Check {
+        child: "issued_tokens",
+        fk: "user_id",
+        parent: "users",
+        parent_pk: "id",
+        nullable: true,
+    },
+    Check {
+        child: "login_locations",
+        fk: "user_id",
+        parent: "users",
+        parent_pk: "id",
+        nullable: false,
+    },
+    Check {
+        child: "user_login_states",
+        fk: "user_id",
+        parent: "users",
+        parent_pk: "id",
+        nullable: false,
+    },
+    Check {
+        child: "user_login_states",
+        fk: "client_id",
+        parent: "clients",
+        parent_pk: "id",
+        nullable: false,
+    },
+    Check {
+        child: "devices",
+        fk: "client_id",
+        parent: "clients",
+        parent_pk: "id",
+        nullable: false,
+    },
+    Check {
+        child: "devices",
+        fk: "user_id",
+        parent: "users",
+        parent_pk: "id",
+        nullable: true,
+    },
+    Check {
+        child: "refresh_tokens_devices",
+        fk: "device_id",
+        parent: "devices",
+        parent_pk: "id",
+        nullable: false,
+    },
+    Check {
+        child: "failed_backchannel_logouts",
+        fk: "client_id",
+        parent: "clients",
+        parent_pk: "id",
+        nullable: false,
+    },
+];
+
+impl Check {
+    fn name(&self) -> String {
+        format!("{}.{} -> {}", self.child, self.fk, self.parent)
+    }
+
+    fn query_count(&self) -> String {
+        let Self {
+            child,
+            fk,
+            parent,
+            parent_pk,
+            nullable,
+        } = self;
+        let not_null = if *nullable {
+            format!(" AND c.{fk} IS NOT NULL")
+        } else {
+            String::default()
+        };
+
+        format!(
+            "SELECT COUNT(*) AS count FROM {child} c LEFT JOIN {parent} p ON p.{parent_pk} = c.{fk}
+WHERE p.{parent_pk} IS NULL{not_null}"
+        )
+    }
+
+    fn query_delete(&self) -> String {
+        let Self {
+            child,
+            fk,
+            parent,
+            parent_pk,
+            nullable: _,
+        } = self;
+
+        // `NOT IN` never matches `NULL` values, which makes this work for nullable
+        // references as well.
+        format!("DELETE FROM {child} WHERE {fk} NOT IN (SELECT {parent_pk} FROM {parent})")
+    }
+
+    async fn count(&self) -> Result<i64, ErrorResponse> {
+        let sql = self.query_count();
+        let count = if is_hiqlite() {
+            DB::hql().query_raw_one(sql, params!()).await?.get("count")
+        } else {
+            DB::pg_query_one_row(&sql, &[]).await?.get("count")
+        };
+        Ok(count)
+    }
+
+    async fn delete(&self) -> Result<usize, ErrorResponse> {
+        let sql = self.query_delete();
+        if is_hiqlite() {
+            Ok(DB::hql().execute(sql, params!()).await?)
+        } else {
+            DB::pg_execute(&sql, &[]).await
+        }
+    }
+}
+
+/// Scans the database for rows with dangling references and reports them. When `fix` is
+/// `true`, the orphaned rows will be deleted after the report has been logged.
+///
+/// Returns the total amount of orphaned rows found.
+pub async fn fsck(fix: bool) -> Result<i64, ErrorResponse> {
+    info!("Checking database for orphaned rows");
+
+    let mut findings = Vec::with_capacity(CHECKS.len());
+    let mut total = 0;
+    for check in CHECKS {
+        let count = check.count().await?;
+        if count > 0 {
+            warn!("{}: {count} orphaned rows", check.name());
+            findings.push(check);
+        } else {
+            info!("{}: ok", check.name());
+        }
+        total += count;
+    }
+
+    if total == 0 {
+        info!("No orphaned rows found");
+        return Ok(0);
+    }
+    if !fix {
+        warn!("Found {total} orphaned rows in total - run with '--fix' to delete them");
+        return Ok(total);
+    }
+
+    warn!(
+        r#"
+
+    Deleting {total} orphaned rows in total.
+    Make sure you have a backup, this cannot be undone!
+
+    Proceeding in 10 seconds...
+
+    "#
+    );
+    sleep(Duration::from_secs(10)).await;
+
+    for check in findings {
+        let deleted = check.delete().await?;
+        info!("{}: deleted {deleted} rows", check.name());
+    }
+    info!("Database cleanup finished");
+
+    Ok(total)
+}
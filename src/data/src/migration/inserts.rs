@@ -30,6 +30,9 @@ use crate::entity::theme::ThemeCssFull;
 use crate::entity::tos::ToS;
 use crate::entity::tos_user_accept::ToSUserAccept;
 use crate::entity::user_attr::{UserAttrConfigEntity, UserAttrValueEntity};
+use crate::entity::user_emails::UserEmail;
+use crate::entity::user_federation::UserFederation;
+use crate::entity::user_ldap_links::UserLdapLink;
 use crate::entity::user_login_states::UserLoginState;
 use crate::entity::user_revoke::UserRevoke;
 use crate::entity::users::User;
@@ -43,6 +46,9 @@ use rauthy_common::is_hiqlite;
 use rauthy_error::ErrorResponse;
 use std::cmp::max;
 
+/// The amount of rows written inside a single transaction for batched inserts.
+const BATCH_SIZE: usize = 500;
+
 pub async fn api_keys(data_before: Vec<ApiKeyEntity>) -> Result<(), ErrorResponse> {
     let sql_1 = "DELETE FROM api_keys";
     let sql_2 = r#"
@@ -125,9 +131,10 @@ INSERT INTO
 auth_providers (id, enabled, name, typ, issuer, authorization_endpoint, token_endpoint,
 userinfo_endpoint, client_id, secret, scope, admin_claim_path, admin_claim_value, mfa_claim_path,
 mfa_claim_value, use_pkce, client_secret_basic, client_secret_post, jwks_endpoint, auto_onboarding,
-auto_link)
+auto_link, auto_redirect, email_domains, claim_precedence, uid_normalization)
 VALUES (
-    $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21
+    $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21,
+    $22, $23, $24, $25
 )"#;
 
     if is_hiqlite() {
@@ -157,7 +164,11 @@ VALUES (
                         b.client_secret_post,
                         b.jwks_endpoint,
                         b.auto_onboarding,
-                        b.auto_link
+                        b.auto_link,
+                        b.auto_redirect,
+                        b.email_domains,
+                        b.claim_precedence.as_str(),
+                        b.uid_normalization.as_str()
                     ),
                 )
                 .await?;
@@ -189,6 +200,10 @@ VALUES (
                     &b.jwks_endpoint,
                     &b.auto_onboarding,
                     &b.auto_link,
+                    &b.auto_redirect,
+                    &b.email_domains,
+                    &b.claim_precedence.as_str(),
+                    &b.uid_normalization.as_str(),
                 ],
             )
             .await?;
@@ -233,9 +248,12 @@ INSERT INTO clients
 (id, name, enabled, confidential, secret, secret_kid, redirect_uris, post_logout_redirect_uris,
 allowed_origins, flows_enabled, access_token_alg, id_token_alg, auth_code_lifetime,
 access_token_lifetime, scopes, default_scopes, challenge, force_mfa, client_uri, contacts,
-backchannel_logout_uri, restrict_group_prefix)
+backchannel_logout_uri, restrict_group_prefix, introspect, claim_mapping, claim_namespace,
+refresh_token_max_lifetime, require_nonce, first_party, subject_type, sector_identifier_uri,
+secret_hashed, uri_match_mode)
 VALUES
-($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22)"#;
+($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22,
+$23, $24, $25, $26, $27, $28, $29, $30, $31, $32)"#;
 
     if is_hiqlite() {
         DB::hql().execute(sql_1, params!()).await?;
@@ -266,7 +284,17 @@ VALUES
                         b.client_uri,
                         b.contacts,
                         b.backchannel_logout_uri,
-                        b.restrict_group_prefix
+                        b.restrict_group_prefix,
+                        b.introspect,
+                        b.claim_mapping,
+                        b.claim_namespace,
+                        b.refresh_token_max_lifetime,
+                        b.require_nonce,
+                        b.first_party,
+                        b.subject_type.as_str(),
+                        b.sector_identifier_uri,
+                        b.secret_hashed,
+                        b.uri_match_mode.as_str()
                     ),
                 )
                 .await?;
@@ -299,6 +327,16 @@ VALUES
                     &b.contacts,
                     &b.backchannel_logout_uri,
                     &b.restrict_group_prefix,
+                    &b.introspect,
+                    &b.claim_mapping,
+                    &b.claim_namespace,
+                    &b.refresh_token_max_lifetime,
+                    &b.require_nonce,
+                    &b.first_party,
+                    &b.subject_type.as_str(),
+                    &b.sector_identifier_uri,
+                    &b.secret_hashed,
+                    &b.uri_match_mode.as_str(),
                 ],
             )
             .await?;
@@ -555,21 +593,28 @@ pub async fn failed_backchannel_logouts(
 ) -> Result<(), ErrorResponse> {
     let sql_1 = "DELETE FROM failed_backchannel_logouts";
     let sql_2 = r#"
-INSERT INTO failed_backchannel_logouts (client_id, sub, sid, retry_count)
-VALUES ($1, $2, $3, $4)"#;
+INSERT INTO failed_backchannel_logouts (client_id, sub, sid, retry_count, issuer)
+VALUES ($1, $2, $3, $4, $5)"#;
 
     if is_hiqlite() {
         DB::hql().execute(sql_1, params!()).await?;
 
         for b in data_before {
             DB::hql()
-                .execute(sql_2, params!(b.client_id, b.sub, b.sid, b.retry_count))
+                .execute(
+                    sql_2,
+                    params!(b.client_id, b.sub, b.sid, b.retry_count, b.issuer),
+                )
                 .await?;
         }
     } else {
         DB::pg_execute(sql_1, &[]).await?;
         for b in data_before {
-            DB::pg_execute(sql_2, &[&b.client_id, &b.sub, &b.sid, &b.retry_count]).await?;
+            DB::pg_execute(
+                sql_2,
+                &[&b.client_id, &b.sub, &b.sid, &b.retry_count, &b.issuer],
+            )
+            .await?;
         }
     }
     Ok(())
@@ -626,8 +671,8 @@ pub async fn groups(data_before: Vec<Group>) -> Result<(), ErrorResponse> {
 pub async fn issued_tokens(data_before: Vec<IssuedToken>) -> Result<(), ErrorResponse> {
     let sql_1 = "DELETE FROM issued_tokens";
     let sql_2 = r#"
-INSERT INTO issued_tokens (jti, user_id, did, sid, exp, revoked)
-VALUES ($1, $2, $3, $4, $5, $6)"#;
+INSERT INTO issued_tokens (jti, client_id, user_id, did, sid, exp, revoked)
+VALUES ($1, $2, $3, $4, $5, $6, $7)"#;
 
     if is_hiqlite() {
         DB::hql().execute(sql_1, params!()).await?;
@@ -635,7 +680,15 @@ VALUES ($1, $2, $3, $4, $5, $6)"#;
             DB::hql()
                 .execute(
                     sql_2,
-                    params!(b.jti, b.user_id, b.did, b.sid, b.exp, b.revoked),
+                    params!(
+                        b.jti,
+                        b.client_id,
+                        b.user_id,
+                        b.did,
+                        b.sid,
+                        b.exp,
+                        b.revoked
+                    ),
                 )
                 .await?;
         }
@@ -644,7 +697,15 @@ VALUES ($1, $2, $3, $4, $5, $6)"#;
         for b in data_before {
             DB::pg_execute(
                 sql_2,
-                &[&b.jti, &b.user_id, &b.did, &b.sid, &b.exp, &b.revoked],
+                &[
+                    &b.jti,
+                    &b.client_id,
+                    &b.user_id,
+                    &b.did,
+                    &b.sid,
+                    &b.exp,
+                    &b.revoked,
+                ],
             )
             .await?;
         }
@@ -1099,8 +1160,9 @@ pub async fn recent_passwords(
 pub async fn refresh_tokens(data_before: Vec<RefreshToken>) -> Result<(), ErrorResponse> {
     let sql_1 = "DELETE FROM refresh_tokens";
     let sql_2 = r#"
-INSERT INTO refresh_tokens (id, user_id, nbf, exp, scope, is_mfa, session_id, access_token_jti)
-VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"#;
+INSERT INTO refresh_tokens
+(id, user_id, nbf, exp, scope, is_mfa, session_id, access_token_jti, family_id)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"#;
 
     if is_hiqlite() {
         DB::hql().execute(sql_1, params!()).await?;
@@ -1116,7 +1178,8 @@ VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"#;
                         b.scope,
                         b.is_mfa,
                         b.session_id,
-                        b.access_token_jti
+                        b.access_token_jti,
+                        b.family_id
                     ),
                 )
                 .await?;
@@ -1135,6 +1198,7 @@ VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"#;
                     &b.is_mfa,
                     &b.session_id,
                     &b.access_token_jti,
+                    &b.family_id,
                 ],
             )
             .await?;
@@ -1212,8 +1276,8 @@ pub async fn roles(data_before: Vec<Role>) -> Result<(), ErrorResponse> {
 pub async fn scopes(data_before: Vec<Scope>) -> Result<(), ErrorResponse> {
     let sql_1 = "DELETE FROM scopes";
     let sql_2 = r#"
-INSERT INTO scopes (id, name, attr_include_access, attr_include_id)
-VALUES ($1, $2, $3, $4)"#;
+INSERT INTO scopes (id, name, attr_include_access, attr_include_id, required_roles)
+VALUES ($1, $2, $3, $4, $5)"#;
 
     if is_hiqlite() {
         DB::hql().execute(sql_1, params!()).await?;
@@ -1221,7 +1285,13 @@ VALUES ($1, $2, $3, $4)"#;
             DB::hql()
                 .execute(
                     sql_2,
-                    params!(b.id, b.name, b.attr_include_access, b.attr_include_id),
+                    params!(
+                        b.id,
+                        b.name,
+                        b.attr_include_access,
+                        b.attr_include_id,
+                        b.required_roles
+                    ),
                 )
                 .await?;
         }
@@ -1230,7 +1300,13 @@ VALUES ($1, $2, $3, $4)"#;
         for b in data_before {
             DB::pg_execute(
                 sql_2,
-                &[&b.id, &b.name, &b.attr_include_access, &b.attr_include_id],
+                &[
+                    &b.id,
+                    &b.name,
+                    &b.attr_include_access,
+                    &b.attr_include_id,
+                    &b.required_roles,
+                ],
             )
             .await?;
         }
@@ -1442,8 +1518,8 @@ VALUES ($1, $2, $3, $4)"#;
 pub async fn user_attr_config(data_before: Vec<UserAttrConfigEntity>) -> Result<(), ErrorResponse> {
     let sql_1 = "DELETE FROM user_attr_config";
     let sql_2 = r#"
-INSERT INTO user_attr_config (name, "desc", default_value, typ, user_editable)
-VALUES ($1, $2, $3, $4, $5)"#;
+INSERT INTO user_attr_config (name, "desc", default_value, typ, user_editable, internal)
+VALUES ($1, $2, $3, $4, $5, $6)"#;
 
     if is_hiqlite() {
         DB::hql().execute(sql_1, params!()).await?;
@@ -1452,7 +1528,14 @@ VALUES ($1, $2, $3, $4, $5)"#;
             DB::hql()
                 .execute(
                     sql_2,
-                    params!(b.name, b.desc, b.default_value, typ, b.user_editable),
+                    params!(
+                        b.name,
+                        b.desc,
+                        b.default_value,
+                        typ,
+                        b.user_editable,
+                        b.internal
+                    ),
                 )
                 .await?;
         }
@@ -1462,7 +1545,14 @@ VALUES ($1, $2, $3, $4, $5)"#;
             let typ = b.typ.as_ref().map(|t| t.as_str());
             DB::pg_execute(
                 sql_2,
-                &[&b.name, &b.desc, &b.default_value, &typ, &b.user_editable],
+                &[
+                    &b.name,
+                    &b.desc,
+                    &b.default_value,
+                    &typ,
+                    &b.user_editable,
+                    &b.internal,
+                ],
             )
             .await?;
         }
@@ -1490,11 +1580,121 @@ pub async fn user_attr_values(data_before: Vec<UserAttrValueEntity>) -> Result<(
     Ok(())
 }
 
+/// Writes the rows in batches with one transaction each, because this table grows with the
+/// amount of users and each insert fires the `federation_count` trigger on `users`.
+pub async fn user_federations(data_before: Vec<UserFederation>) -> Result<(), ErrorResponse> {
+    let sql_1 = "DELETE FROM user_federations";
+    let sql_2 = r#"
+INSERT INTO user_federations (user_id, provider_id, federation_uid)
+VALUES ($1, $2, $3)"#;
+
+    if is_hiqlite() {
+        DB::hql().execute(sql_1, params!()).await?;
+        for batch in data_before.chunks(BATCH_SIZE) {
+            let txn = batch
+                .iter()
+                .map(|b| {
+                    (
+                        sql_2,
+                        params!(
+                            b.user_id.clone(),
+                            b.provider_id.clone(),
+                            b.federation_uid.clone()
+                        ),
+                    )
+                })
+                .collect::<Vec<_>>();
+            for res in DB::hql().txn(txn).await? {
+                res?;
+            }
+        }
+    } else {
+        DB::pg_execute(sql_1, &[]).await?;
+        let mut cl = DB::pg().await?;
+        for batch in data_before.chunks(BATCH_SIZE) {
+            let txn = cl.transaction().await?;
+            for b in batch {
+                DB::pg_txn_append(
+                    &txn,
+                    sql_2,
+                    &[&b.user_id, &b.provider_id, &b.federation_uid],
+                )
+                .await?;
+            }
+            txn.commit().await?;
+        }
+    }
+    Ok(())
+}
+
+pub async fn user_emails(data_before: Vec<UserEmail>) -> Result<(), ErrorResponse> {
+    let sql_1 = "DELETE FROM user_emails";
+    let sql_2 = r#"
+INSERT INTO user_emails (email, user_id, verified, created_at)
+VALUES ($1, $2, $3, $4)"#;
+
+    if is_hiqlite() {
+        DB::hql().execute(sql_1, params!()).await?;
+        for batch in data_before.chunks(BATCH_SIZE) {
+            let txn = batch
+                .iter()
+                .map(|b| {
+                    (
+                        sql_2,
+                        params!(b.email.clone(), b.user_id.clone(), b.verified, b.created_at),
+                    )
+                })
+                .collect::<Vec<_>>();
+            for res in DB::hql().txn(txn).await? {
+                res?;
+            }
+        }
+    } else {
+        DB::pg_execute(sql_1, &[]).await?;
+        let mut cl = DB::pg().await?;
+        for batch in data_before.chunks(BATCH_SIZE) {
+            let txn = cl.transaction().await?;
+            for b in batch {
+                DB::pg_txn_append(
+                    &txn,
+                    sql_2,
+                    &[&b.email, &b.user_id, &b.verified, &b.created_at],
+                )
+                .await?;
+            }
+            txn.commit().await?;
+        }
+    }
+    Ok(())
+}
+
+pub async fn user_ldap_links(data_before: Vec<UserLdapLink>) -> Result<(), ErrorResponse> {
+    let sql_1 = "DELETE FROM user_ldap_links";
+    let sql_2 = r#"
+INSERT INTO user_ldap_links (user_id, provider_id, federation_uid)
+VALUES ($1, $2, $3)"#;
+
+    if is_hiqlite() {
+        DB::hql().execute(sql_1, params!()).await?;
+        for b in data_before {
+            DB::hql()
+                .execute(sql_2, params!(b.user_id, b.provider_id, b.federation_uid))
+                .await?;
+        }
+    } else {
+        DB::pg_execute(sql_1, &[]).await?;
+        for b in data_before {
+            DB::pg_execute(sql_2, &[&b.user_id, &b.provider_id, &b.federation_uid]).await?;
+        }
+    }
+    Ok(())
+}
+
 pub async fn user_login_states(data_before: Vec<UserLoginState>) -> Result<(), ErrorResponse> {
     let sql_1 = "DELETE FROM user_login_states";
     let sql_2 = r#"
-INSERT INTO user_login_states(timestamp, user_id, client_id, session_id)
-VALUES ($1, $2, $3, $4)
+INSERT INTO user_login_states(timestamp, user_id, client_id, session_id, issuer)
+VALUES ($1, $2, $3, $4, $5)
 ON CONFLICT (user_id, client_id, session_id)
 DO NOTHING"#;
 
@@ -1505,7 +1705,7 @@ DO NOTHING"#;
             DB::hql()
                 .execute(
                     sql_2,
-                    params!(b.timestamp, b.user_id, b.client_id, b.session_id),
+                    params!(b.timestamp, b.user_id, b.client_id, b.session_id, b.issuer),
                 )
                 .await?;
         }
@@ -1514,7 +1714,13 @@ DO NOTHING"#;
         for b in data_before {
             DB::pg_execute(
                 sql_2,
-                &[&b.timestamp, &b.user_id, &b.client_id, &b.session_id],
+                &[
+                    &b.timestamp,
+                    &b.user_id,
+                    &b.client_id,
+                    &b.session_id,
+                    &b.issuer,
+                ],
             )
             .await?;
         }
@@ -1531,8 +1737,10 @@ pub async fn users(data_before: Vec<User>) -> Result<(), ErrorResponse> {
 INSERT INTO users
 (id, email, given_name, family_name, password, roles, groups, enabled, email_verified,
 password_expires, created_at, last_login, last_failed_login, failed_login_attempts, language,
-webauthn_user_id, user_expires, picture_id)
-VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)"#;
+webauthn_user_id, user_expires, auth_provider_id, federation_uid, picture_id, disabled_at,
+pending_since)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19,
+$20, $21, $22)"#;
 
     if is_hiqlite() {
         DB::hql().execute(sql_1, params!()).await?;
@@ -1559,7 +1767,11 @@ VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $
                         b.language.as_str(),
                         b.webauthn_user_id,
                         b.user_expires,
-                        b.picture_id
+                        b.auth_provider_id,
+                        b.federation_uid,
+                        b.picture_id,
+                        b.disabled_at,
+                        b.pending_since
                     ),
                 )
                 .await?;
@@ -1588,7 +1800,11 @@ VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $
                     &b.language.as_str(),
                     &b.webauthn_user_id,
                     &b.user_expires,
+                    &b.auth_provider_id,
+                    &b.federation_uid,
                     &b.picture_id,
+                    &b.disabled_at,
+                    &b.pending_since,
                 ],
             )
             .await?;
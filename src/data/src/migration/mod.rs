@@ -1,5 +1,6 @@
 pub mod anti_lockout;
 pub mod db_migrate;
 pub mod db_migrate_dev;
+pub mod fsck;
 pub mod init_prod;
 mod inserts;
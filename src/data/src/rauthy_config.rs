@@ -1,19 +1,26 @@
 use crate::ListenScheme;
+use crate::captcha::CaptchaProvider;
 use crate::email::mailer::{EMail, SmtpConnMode};
 use crate::events::event::{Event, EventLevel};
 use crate::events::listener::EventRouterMsg;
 use crate::vault_config::VaultConfig;
+use actix_web::HttpRequest;
+use actix_web::cookie::SameSite;
 use cryptr::EncKeys;
 use hiqlite::NodeConfig;
-use rauthy_common::constants::CookieMode;
+use rauthy_common::constants::{CookieMode, RESERVED_CLAIMS};
 use rauthy_common::logging::LogLevelAccess;
-use rauthy_common::regex::{RE_LINUX_USERNAME, RE_PREFERRED_USERNAME};
+use rauthy_common::redact::Redact;
+use rauthy_common::regex::{RE_EMAIL_DOMAIN_PATTERN, RE_LINUX_USERNAME, RE_PREFERRED_USERNAME};
+use rauthy_common::utils::{email_domain_blocked, email_domain_matches};
+use rauthy_error::{ErrorResponse, ErrorResponseType};
 use regex::Regex;
 use serde::Serialize;
 use spow::pow::Pow;
 use std::borrow::Cow;
 use std::env;
 use std::error::Error;
+use std::fmt::{Debug, Formatter};
 use std::str::FromStr;
 use std::sync::OnceLock;
 use tokio::fs;
@@ -29,6 +36,9 @@ static CONFIG: OnceLock<RauthyConfig> = OnceLock::new();
 pub struct RauthyConfig {
     pub argon2_params: argon2::Params,
     pub issuer: String,
+    /// All valid issuers, the default `issuer` always being the first one, followed by one for
+    /// each `server.issuer_hosts`.
+    pub issuers: Vec<String>,
     pub is_primary_node: bool,
     pub is_ha_cluster: bool,
     pub listen_scheme: ListenScheme,
@@ -112,6 +122,11 @@ impl RauthyConfig {
         ) || vars.server.proxy_mode;
         let issuer_scheme = if is_https { "https" } else { "http" };
         let issuer = format!("{issuer_scheme}://{}/auth/v1/", vars.server.pub_url);
+        let issuers = build_issuers(
+            issuer_scheme,
+            &vars.server.pub_url,
+            &vars.server.issuer_hosts,
+        );
 
         let Ok(log_level_access) = LogLevelAccess::from_str(&vars.logging.level_access) else {
             panic!(
@@ -171,17 +186,12 @@ impl RauthyConfig {
             format!("{scheme}://{pub_url}")
         };
 
-        let rp_origin = webauthn_rs::prelude::Url::parse(&vars.webauthn.rp_origin)
-            .expect("Cannot parse `webauthn.rp_origin` to URL");
-        let builder = webauthn_rs::WebauthnBuilder::new(&vars.webauthn.rp_id, &rp_origin)
-            .expect("Invalid `webauthn.rp_id`")
-            // Set a "nice" relying party name. Has no security properties - may be changed in the future.
-            .rp_name(&vars.webauthn.rp_name);
-        let webauthn = builder.build().expect("Invalid `webauthn.rp_name`");
+        let webauthn = build_webauthn(&vars.webauthn);
 
         let slf = Self {
             argon2_params,
             issuer,
+            issuers,
             is_primary_node: node_config.node_id == 1 || node_config.nodes.len() == 1,
             is_ha_cluster: node_config.nodes.len() > 1,
             listen_scheme,
@@ -207,6 +217,9 @@ impl RauthyConfig {
         debug!("Proxy Mode: {}", slf.vars.server.proxy_mode);
         debug!("Trusted Proxies: {:?}", slf.vars.server.trusted_proxies);
         info!("Issuer: {}", slf.issuer);
+        if slf.issuers.len() > 1 {
+            info!("Additional Issuers: {:?}", &slf.issuers[1..]);
+        }
         debug!("HA Deployment: {}", slf.is_ha_cluster);
         debug!(
             "Argon2id Params: m_code: {}, t_cost: {}, p_cost: {}",
@@ -241,6 +254,49 @@ impl RauthyConfig {
     pub fn get() -> &'static Self {
         CONFIG.get().unwrap()
     }
+
+    /// Returns the issuer for the given request host. Only hosts from `server.issuer_hosts` are
+    /// respected. Any other host, which may very well be a spoofed `Host` header, will always
+    /// resolve to the default `issuer`.
+    pub fn issuer_for_host(&self, host: Option<&str>) -> &str {
+        find_issuer(&self.issuers, &self.vars.server.issuer_hosts, host)
+    }
+
+    /// Returns the issuer for the given request. Same as `issuer_for_host()`.
+    #[inline]
+    pub fn issuer_from_req(&self, req: &HttpRequest) -> &str {
+        if self.issuers.len() == 1 {
+            return &self.issuer;
+        }
+        self.issuer_for_host(Some(req.connection_info().host()))
+    }
+
+    #[inline]
+    pub fn is_valid_issuer(&self, iss: &str) -> bool {
+        self.issuers.iter().any(|i| i == iss)
+    }
+}
+
+fn build_issuers(scheme: &str, pub_url: &str, issuer_hosts: &[String]) -> Vec<String> {
+    let mut issuers = Vec::with_capacity(issuer_hosts.len() + 1);
+    issuers.push(format!("{scheme}://{pub_url}/auth/v1/"));
+    for host in issuer_hosts {
+        issuers.push(format!("{scheme}://{host}/auth/v1/"));
+    }
+    issuers
+}
+
+#[inline]
+fn find_issuer<'a>(issuers: &'a [String], issuer_hosts: &[String], host: Option<&str>) -> &'a str {
+    if let Some(host) = host {
+        // `issuers` has the same order as `issuer_hosts`, with the default one in front
+        for (i, allowed) in issuer_hosts.iter().enumerate() {
+            if allowed.eq_ignore_ascii_case(host) {
+                return &issuers[i + 1];
+            }
+        }
+    }
+    &issuers[0]
 }
 
 #[derive(Debug)]
@@ -251,6 +307,8 @@ pub struct Vars {
     pub atproto: VarsAtproto,
     pub backchannel_logout: VarsBackchannelLogout,
     pub bootstrap: VarsBootstrap,
+    pub captcha: VarsCaptcha,
+    pub claims_provider: VarsClaimsProvider,
     pub database: VarsDatabase,
     pub device_grant: VarsDeviceGrant,
     pub dpop: VarsDpop,
@@ -264,6 +322,8 @@ pub struct Vars {
     pub hashing: VarsHashing,
     pub http_client: VarsHttpClient,
     pub i18n: VarsI18n,
+    pub ip_reputation: VarsIpReputation,
+    pub ldap: VarsLdap,
     pub lifetimes: VarsLifetimes,
     pub logging: VarsLogging,
     pub matrix: VarsMatrix,
@@ -291,10 +351,13 @@ impl Default for Vars {
                 dpop_http: false,
                 insecure_cookie: false,
                 provider_callback_url: None,
+                token_preview: false,
+                danger_unsafe_test_mode: false,
             },
             access: VarsAccess {
                 userinfo_strict: true,
                 danger_disable_introspect_auth: false,
+                introspect_restrict_aud: false,
                 disable_refresh_token_nbf: false,
                 sec_header_block: true,
                 session_validate_ip: true,
@@ -302,12 +365,29 @@ impl Default for Vars {
                 peer_ip_header_name: None,
                 cookie_mode: CookieMode::Host,
                 cookie_set_path: true,
+                session_cookie_domain: None,
+                session_cookie_path: None,
+                session_cookie_same_site: SameSite::Lax,
                 client_credentials_map_sub: false,
                 token_len_limit: 4096,
                 token_revoke_on_logout: false,
                 token_revoke_device_tokens: false,
+                token_bind_session: false,
                 whoami_headers: false,
                 admin_button_hide: false,
+                strict_scopes: false,
+                tokens_not_before: 0,
+                pkce_allow_plain: true,
+                auth_state_required: false,
+                auth_state_binding: true,
+                nonce_replay_protection: false,
+                redirect_uri_validation_rate_limit: 10,
+                client_secret_min_length: 32,
+                client_secret_min_entropy: 96,
+                token_rate_limit_window: 60,
+                token_rate_limit_client: 0,
+                token_rate_limit_ip: 0,
+                token_max_body_bytes: 16384,
             },
             auth_headers: VarsAuthHeaders {
                 enable: false,
@@ -335,6 +415,22 @@ impl Default for Vars {
                 api_key: None,
                 api_key_secret: None,
             },
+            captcha: VarsCaptcha {
+                provider: None,
+                site_key: String::default(),
+                secret: String::default(),
+                login: true,
+                login_threshold: 3,
+                registration: true,
+                fail_open: false,
+            },
+            claims_provider: VarsClaimsProvider {
+                url: None,
+                secret: String::default(),
+                timeout_ms: 1000,
+                fail_open: false,
+                allowed_claims: Vec::default(),
+            },
             database: VarsDatabase {
                 hiqlite: true,
                 health_check_delay_secs: 30,
@@ -350,8 +446,12 @@ impl Default for Vars {
                 migrate_pg_user: None,
                 migrate_pg_password: None,
                 migrate_pg_db_name: "rauthy".into(),
+                migrate_verify: true,
+                migrate_exit: false,
                 sched_user_exp_mins: 60,
                 sched_user_exp_delete_mins: None,
+                sched_sweeper_mins: 60,
+                sched_sweeper_batch_size: 500,
             },
             device_grant: VarsDeviceGrant {
                 code_lifetime: 300,
@@ -405,6 +505,10 @@ impl Default for Vars {
                 root_ca: None,
                 starttls_only: false,
                 danger_insecure: false,
+                change_notify_old: true,
+                lockout_notify: true,
+                lockout_notify_interval: 3600,
+                welcome_new_users: true,
                 tz_fmt: VarsEmailTzFmt {
                     de: "%d.%m.%Y %T (%Z)".into(),
                     en: "%m/%d/%Y %T (%Z)".into(),
@@ -452,6 +556,7 @@ impl Default for Vars {
                 generate_token_issued: true,
                 level_new_user: EventLevel::Info,
                 level_user_email_change: EventLevel::Notice,
+                level_user_email_change_request: EventLevel::Info,
                 level_user_password_reset: EventLevel::Notice,
                 level_rauthy_admin: EventLevel::Notice,
                 level_rauthy_version: EventLevel::Notice,
@@ -466,6 +571,21 @@ impl Default for Vars {
                 level_user_login_revoke: EventLevel::Warning,
                 level_scim_task_failed: EventLevel::Critical,
                 level_suspicious_request: EventLevel::Notice,
+                level_introspection_denied: EventLevel::Notice,
+                level_registration_rate_limit: EventLevel::Warning,
+                level_tokens_revoked: EventLevel::Warning,
+                level_tokens_invalidated: EventLevel::Critical,
+                level_user_data_exported: EventLevel::Notice,
+                level_user_federation_remapped: EventLevel::Notice,
+                level_user_onboarded: EventLevel::Info,
+                level_cache_flushed: EventLevel::Notice,
+                level_registration_pending: EventLevel::Notice,
+                level_registration_approved: EventLevel::Info,
+                level_registration_rejected: EventLevel::Notice,
+                level_account_recovered: EventLevel::Notice,
+                level_records_swept: EventLevel::Info,
+                level_user_disabled: EventLevel::Notice,
+                level_user_enabled: EventLevel::Notice,
                 level_new_login_location: EventLevel::Notice,
                 level_token_issued: EventLevel::Info,
                 level_failed_logins_25: EventLevel::Critical,
@@ -507,6 +627,8 @@ impl Default for Vars {
                 danger_unencrypted: false,
                 danger_insecure: false,
                 root_ca_bundle: None,
+                tls_cipher_suites: Vec::default(),
+                tls_kx_groups: Vec::default(),
             },
             i18n: VarsI18n {
                 filter_lang_common: vec![
@@ -525,27 +647,66 @@ impl Default for Vars {
                     "uk".into(),
                 ],
             },
+            ip_reputation: VarsIpReputation {
+                file: None,
+                file_reload_secs: 300,
+                url: None,
+                headers: Vec::default(),
+                score_pointer: "/score".into(),
+                timeout_ms: 1000,
+                cache_ttl: 3600,
+                threshold_step_up: 50,
+                threshold_block: 90,
+                fail_open: true,
+            },
+            ldap: VarsLdap {
+                url: None,
+                starttls: false,
+                root_ca: None,
+                danger_insecure: false,
+                danger_unencrypted: false,
+                provider_id: "ldap".into(),
+                bind_dn: None,
+                bind_password: None,
+                base_dn: None,
+                user_filter: "(&(objectClass=person)(mail={email}))".into(),
+                attr_uid: "objectGUID".into(),
+                attr_email: "mail".into(),
+                attr_given_name: "givenName".into(),
+                attr_family_name: "sn".into(),
+                attr_groups: "memberOf".into(),
+                role_mappings: Vec::default(),
+                group_mappings: Vec::default(),
+                pool_size: 8,
+                timeout_ms: 5000,
+            },
             lifetimes: VarsLifetimes {
                 refresh_token_grace_time: 5,
                 refresh_token_lifetime: 48,
+                refresh_token_reuse_detection: true,
                 session_lifetime: 14400,
                 session_renew_mfa: false,
                 session_timeout: 5400,
                 magic_link_pwd_reset: 30,
                 magic_link_pwd_first: 4320,
                 jwk_autorotate_cron: "0 30 3 1 * * *".into(),
+                jwks_max_age: 300,
             },
             logging: VarsLogging {
                 level: "info".into(),
                 level_database: "info".into(),
                 level_access: "modifying".into(),
                 log_fmt: "text".into(),
+                redact_fields: Vec::default(),
+                redact_level: "error".into(),
             },
             matrix: VarsMatrix {
                 msc3861_enable: false,
             },
             mfa: VarsMfa {
                 admin_force_mfa: true,
+                provider_recovery: false,
+                provider_recovery_notify: true,
             },
             pam: VarsPam {
                 remote_password_len: 24,
@@ -574,6 +735,7 @@ impl Default for Vars {
                 port_https: 8443,
                 scheme: "http_https".into(),
                 pub_url: String::default(),
+                issuer_hosts: Vec::default(),
                 http_workers: 0,
                 proxy_mode: false,
                 trusted_proxies: Vec::default(),
@@ -751,6 +913,72 @@ impl Default for Vars {
                     footer: Some("If this link has expired, you can request a new one.".into()),
                     button_text_request_new: Some("Request New Link".into()),
                 },
+                welcome_de: VarsTemplate {
+                    subject: "Willkommen".into(),
+                    header: "Willkommen bei".into(),
+                    text: Some("Dein neuer Account wurde angelegt.".into()),
+                    click_link: "Klicke auf den Link, um dein Passwort zu setzen oder einen Passkey zu registrieren.".into(),
+                    validity: "Aus Sicherheitsgründen ist der Link nur für eine kurze Zeit gültig.".into(),
+                    expires: "Gültig bis:".into(),
+                    button: "Account einrichten".into(),
+                    footer: None,
+                    button_text_request_new: None,
+                },
+                welcome_en: VarsTemplate {
+                    subject: "Welcome".into(),
+                    header: "Welcome to".into(),
+                    text: Some("Your new account has been created.".into()),
+                    click_link: "Click the link to set your password or register a passkey.".into(),
+                    validity: "For security reasons, this link is only valid for a short period of time.".into(),
+                    expires: "Expires:".into(),
+                    button: "Set up Account".into(),
+                    footer: None,
+                    button_text_request_new: None,
+                },
+                welcome_ko: VarsTemplate {
+                    subject: "Welcome".into(),
+                    header: "Welcome to".into(),
+                    text: Some("Your new account has been created.".into()),
+                    click_link: "Click the link to set your password or register a passkey.".into(),
+                    validity: "For security reasons, this link is only valid for a short period of time.".into(),
+                    expires: "Expires:".into(),
+                    button: "Set up Account".into(),
+                    footer: None,
+                    button_text_request_new: None,
+                },
+                welcome_nb: VarsTemplate {
+                    subject: "Velkommen".into(),
+                    header: "Velkommen til".into(),
+                    text: Some("Den nye kontoen din er opprettet.".into()),
+                    click_link: "Klikk på lenken for å angi passordet ditt eller registrere en passkey.".into(),
+                    validity: "Av sikkerhetsgrunner er denne lenken bare gyldig i en kort periode.".into(),
+                    expires: "Utløper:".into(),
+                    button: "Sett opp konto".into(),
+                    footer: None,
+                    button_text_request_new: None,
+                },
+                welcome_uk: VarsTemplate {
+                    subject: "Ласкаво просимо".into(),
+                    header: "Ласкаво просимо до".into(),
+                    text: Some("Ваш новий акаунт створено.".into()),
+                    click_link: "Натисніть на посилання, щоб встановити пароль або зареєструвати ключ доступу.".into(),
+                    validity: "З міркувань безпеки це посилання дійсне лише короткий час.".into(),
+                    expires: "Дійсне до:".into(),
+                    button: "Налаштувати акаунт".into(),
+                    footer: None,
+                    button_text_request_new: None,
+                },
+                welcome_zhhans: VarsTemplate {
+                    subject: "Welcome".into(),
+                    header: "Welcome to".into(),
+                    text: Some("Your new account has been created.".into()),
+                    click_link: "Click the link to set your password or register a passkey.".into(),
+                    validity: "For security reasons, this link is only valid for a short period of time.".into(),
+                    expires: "Expires:".into(),
+                    button: "Set up Account".into(),
+                    footer: None,
+                    button_text_request_new: None,
+                },
             },
             tls: VarsTls {
                 cert_path: None,
@@ -762,6 +990,7 @@ impl Default for Vars {
             },
             user_delete: VarsUserDelete {
                 enable_self_delete: false,
+                disabled_retention_days: 30,
             },
             user_pictures: VarsUserPictures {
                 storage_type: "db".into(),
@@ -777,9 +1006,15 @@ impl Default for Vars {
             },
             user_registration: VarsUserRegistration {
                 enable: false,
-                domain_restriction: None,
+                domain_allowlist: Vec::default(),
                 domain_blacklist: Vec::default(),
+                domain_restriction_admin_bypass: true,
+                rate_limit_max: 5,
+                rate_limit_window: 3600,
+                rate_limit_min_interval: 10,
                 allow_open_redirect: false,
+                require_approval: false,
+                require_approval_federated: false,
             },
             user_values: VarsUserValuesConfig {
                 given_name: UserValueConfigValue::Required,
@@ -803,6 +1038,7 @@ impl Default for Vars {
             webauthn: VarsWebauthn {
                 rp_id: String::default(),
                 rp_origin: String::default(),
+                additional_origins: Vec::default(),
                 rp_name: "Rauthy IAM".into(),
                 req_exp: 60,
                 data_exp: 90,
@@ -858,6 +1094,8 @@ impl Vars {
         slf.parse_auth_headers(&mut table);
         slf.parse_backchannel_logout(&mut table);
         slf.parse_bootstrap(&mut table);
+        slf.parse_captcha(&mut table);
+        slf.parse_claims_provider(&mut table);
         slf.parse_database(&mut table);
         slf.parse_device_grant(&mut table);
         slf.parse_dpop(&mut table);
@@ -871,6 +1109,8 @@ impl Vars {
         slf.parse_hashing(&mut table);
         slf.parse_http_client(&mut table);
         slf.parse_i18n(&mut table);
+        slf.parse_ip_reputation(&mut table);
+        slf.parse_ldap(&mut table);
         slf.parse_lifetimes(&mut table);
         slf.parse_logging(&mut table);
         slf.parse_matrix(&mut table);
@@ -914,6 +1154,19 @@ impl Vars {
         ) {
             self.dev.provider_callback_url = Some(v);
         }
+        if let Some(v) = t_bool(&mut table, "dev", "token_preview", "DEV_TOKEN_PREVIEW") {
+            self.dev.token_preview = v;
+        }
+        if let Some(v) = t_bool(
+            &mut table,
+            "dev",
+            "danger_unsafe_test_mode",
+            "DANGER_UNSAFE_TEST_MODE",
+        ) {
+            self.dev.danger_unsafe_test_mode = v;
+        }
+
+        self.dev.validate_test_mode();
     }
 
     fn parse_atproto(&mut self, table: &mut toml::Table) {
@@ -938,6 +1191,14 @@ impl Vars {
         ) {
             self.access.danger_disable_introspect_auth = v;
         }
+        if let Some(v) = t_bool(
+            &mut table,
+            "access",
+            "introspect_restrict_aud",
+            "INTROSPECT_RESTRICT_AUD",
+        ) {
+            self.access.introspect_restrict_aud = v;
+        }
         if let Some(v) = t_bool(
             &mut table,
             "access",
@@ -979,6 +1240,38 @@ impl Vars {
         if let Some(v) = t_bool(&mut table, "access", "cookie_set_path", "COOKIE_SET_PATH") {
             self.access.cookie_set_path = v;
         }
+        if let Some(v) = t_str(
+            &mut table,
+            "access",
+            "session_cookie_domain",
+            "SESSION_COOKIE_DOMAIN",
+        ) {
+            self.access.session_cookie_domain = Some(v);
+        }
+        if let Some(v) = t_str(
+            &mut table,
+            "access",
+            "session_cookie_path",
+            "SESSION_COOKIE_PATH",
+        ) {
+            self.access.session_cookie_path = Some(v);
+        }
+        if let Some(v) = t_str(
+            &mut table,
+            "access",
+            "session_cookie_same_site",
+            "SESSION_COOKIE_SAME_SITE",
+        ) {
+            self.access.session_cookie_same_site = match v.as_str() {
+                "strict" => SameSite::Strict,
+                "lax" => SameSite::Lax,
+                "none" => SameSite::None,
+                v => panic!(
+                    "Cannot parse '{v}' for `access.session_cookie_same_site`, expected one of: strict, lax, none"
+                ),
+            };
+        }
+        self.access.validate_session_cookie();
         if let Some(v) = t_bool(
             &mut table,
             "access",
@@ -1006,6 +1299,14 @@ impl Vars {
         ) {
             self.access.token_revoke_device_tokens = v;
         }
+        if let Some(v) = t_bool(
+            &mut table,
+            "access",
+            "token_bind_session",
+            "TOKEN_BIND_SESSION",
+        ) {
+            self.access.token_bind_session = v;
+        }
         if let Some(v) = t_bool(&mut table, "access", "whoami_headers", "WHOAMI_HEADERS") {
             self.access.whoami_headers = v;
         }
@@ -1017,6 +1318,100 @@ impl Vars {
         ) {
             self.access.admin_button_hide = v;
         }
+        if let Some(v) = t_bool(&mut table, "access", "strict_scopes", "STRICT_SCOPES") {
+            self.access.strict_scopes = v;
+        }
+        if let Some(v) = t_i64(
+            &mut table,
+            "access",
+            "tokens_not_before",
+            "TOKENS_NOT_BEFORE",
+        ) {
+            self.access.tokens_not_before = v;
+        }
+        if let Some(v) = t_bool(&mut table, "access", "pkce_allow_plain", "PKCE_ALLOW_PLAIN") {
+            self.access.pkce_allow_plain = v;
+        }
+        if let Some(v) = t_bool(
+            &mut table,
+            "access",
+            "auth_state_required",
+            "AUTH_STATE_REQUIRED",
+        ) {
+            self.access.auth_state_required = v;
+        }
+        if let Some(v) = t_bool(
+            &mut table,
+            "access",
+            "auth_state_binding",
+            "AUTH_STATE_BINDING",
+        ) {
+            self.access.auth_state_binding = v;
+        }
+        if let Some(v) = t_bool(
+            &mut table,
+            "access",
+            "nonce_replay_protection",
+            "NONCE_REPLAY_PROTECTION",
+        ) {
+            self.access.nonce_replay_protection = v;
+        }
+        if let Some(v) = t_u16(
+            &mut table,
+            "access",
+            "redirect_uri_validation_rate_limit",
+            "REDIRECT_URI_VALIDATION_RATE_LIMIT",
+        ) {
+            self.access.redirect_uri_validation_rate_limit = v;
+        }
+        if let Some(v) = t_u16(
+            &mut table,
+            "access",
+            "client_secret_min_length",
+            "CLIENT_SECRET_MIN_LENGTH",
+        ) {
+            self.access.client_secret_min_length = v;
+        }
+        if let Some(v) = t_u16(
+            &mut table,
+            "access",
+            "client_secret_min_entropy",
+            "CLIENT_SECRET_MIN_ENTROPY",
+        ) {
+            self.access.client_secret_min_entropy = v;
+        }
+        if let Some(v) = t_u16(
+            &mut table,
+            "access",
+            "token_rate_limit_window",
+            "TOKEN_RATE_LIMIT_WINDOW",
+        ) {
+            self.access.token_rate_limit_window = v;
+        }
+        if let Some(v) = t_u16(
+            &mut table,
+            "access",
+            "token_rate_limit_client",
+            "TOKEN_RATE_LIMIT_CLIENT",
+        ) {
+            self.access.token_rate_limit_client = v;
+        }
+        if let Some(v) = t_u16(
+            &mut table,
+            "access",
+            "token_rate_limit_ip",
+            "TOKEN_RATE_LIMIT_IP",
+        ) {
+            self.access.token_rate_limit_ip = v;
+        }
+        if let Some(v) = t_u32(
+            &mut table,
+            "access",
+            "token_max_body_bytes",
+            "TOKEN_MAX_BODY_BYTES",
+        ) {
+            self.access.token_max_body_bytes = v;
+        }
     }
 
     fn parse_auth_headers(&mut self, table: &mut toml::Table) {
@@ -1160,6 +1555,106 @@ impl Vars {
         }
     }
 
+    fn parse_captcha(&mut self, table: &mut toml::Table) {
+        let mut table = t_table(table, "captcha");
+
+        if let Some(v) = t_str(&mut table, "captcha", "provider", "CAPTCHA_PROVIDER") {
+            self.captcha.provider = Some(CaptchaProvider::from(v.as_str()));
+        }
+        if let Some(v) = t_str(&mut table, "captcha", "site_key", "CAPTCHA_SITE_KEY") {
+            self.captcha.site_key = v;
+        }
+        if let Some(v) = t_str(&mut table, "captcha", "secret", "CAPTCHA_SECRET") {
+            self.captcha.secret = v;
+        }
+        if let Some(v) = t_bool(&mut table, "captcha", "login", "CAPTCHA_LOGIN") {
+            self.captcha.login = v;
+        }
+        if let Some(v) = t_u16(
+            &mut table,
+            "captcha",
+            "login_threshold",
+            "CAPTCHA_LOGIN_THRESHOLD",
+        ) {
+            self.captcha.login_threshold = v;
+        }
+        if let Some(v) = t_bool(
+            &mut table,
+            "captcha",
+            "registration",
+            "CAPTCHA_REGISTRATION",
+        ) {
+            self.captcha.registration = v;
+        }
+        if let Some(v) = t_bool(&mut table, "captcha", "fail_open", "CAPTCHA_FAIL_OPEN") {
+            self.captcha.fail_open = v;
+        }
+
+        if self.captcha.provider.is_some() && self.captcha.secret.is_empty() {
+            panic!("`[captcha].secret` must be set when a `provider` is configured");
+        }
+        if self.captcha.provider.is_some() && self.captcha.site_key.is_empty() {
+            panic!("`[captcha].site_key` must be set when a `provider` is configured");
+        }
+    }
+
+    fn parse_claims_provider(&mut self, table: &mut toml::Table) {
+        let mut table = t_table(table, "claims_provider");
+
+        if let Some(v) = t_str(&mut table, "claims_provider", "url", "CLAIMS_PROVIDER_URL") {
+            self.claims_provider.url = Some(v);
+        }
+        if let Some(v) = t_str(
+            &mut table,
+            "claims_provider",
+            "secret",
+            "CLAIMS_PROVIDER_SECRET",
+        ) {
+            self.claims_provider.secret = v;
+        }
+        if let Some(v) = t_u32(
+            &mut table,
+            "claims_provider",
+            "timeout_ms",
+            "CLAIMS_PROVIDER_TIMEOUT_MS",
+        ) {
+            self.claims_provider.timeout_ms = v;
+        }
+        if let Some(v) = t_bool(
+            &mut table,
+            "claims_provider",
+            "fail_open",
+            "CLAIMS_PROVIDER_FAIL_OPEN",
+        ) {
+            self.claims_provider.fail_open = v;
+        }
+        if let Some(v) = t_str_vec(
+            &mut table,
+            "claims_provider",
+            "allowed_claims",
+            "CLAIMS_PROVIDER_ALLOWED_CLAIMS",
+        ) {
+            self.claims_provider.allowed_claims = v;
+        }
+
+        if self.claims_provider.url.is_some() {
+            if self.claims_provider.secret.len() < 32 {
+                panic!("`[claims_provider].secret` must be at least 32 characters long");
+            }
+            if self.claims_provider.allowed_claims.is_empty() {
+                panic!("`[claims_provider].allowed_claims` must not be empty when a `url` is set");
+            }
+            if let Some(c) = self
+                .claims_provider
+                .allowed_claims
+                .iter()
+                .find(|c| RESERVED_CLAIMS.contains(&c.as_str()))
+            {
+                panic!("`[claims_provider].allowed_claims` must not contain reserved claim '{c}'");
+            }
+        }
+    }
+
     fn parse_database(&mut self, table: &mut toml::Table) {
         let mut table = t_table(table, "database");
 
@@ -1227,6 +1722,12 @@ impl Vars {
         ) {
             self.database.migrate_pg_db_name = v.into();
         }
+        if let Some(v) = t_bool(&mut table, "database", "migrate_verify", "MIGRATE_VERIFY") {
+            self.database.migrate_verify = v;
+        }
+        if let Some(v) = t_bool(&mut table, "database", "migrate_exit", "MIGRATE_EXIT") {
+            self.database.migrate_exit = v;
+        }
 
         if let Some(v) = t_u32(
             &mut table,
@@ -1244,6 +1745,22 @@ impl Vars {
         ) {
             self.database.sched_user_exp_delete_mins = Some(v);
         }
+        if let Some(v) = t_u32(
+            &mut table,
+            "database",
+            "sched_sweeper_mins",
+            "SCHED_SWEEPER_MINS",
+        ) {
+            self.database.sched_sweeper_mins = v;
+        }
+        if let Some(v) = t_u32(
+            &mut table,
+            "database",
+            "sched_sweeper_batch_size",
+            "SCHED_SWEEPER_BATCH_SIZE",
+        ) {
+            self.database.sched_sweeper_batch_size = v;
+        }
     }
 
     fn parse_device_grant(&mut self, table: &mut toml::Table) {
@@ -1452,6 +1969,38 @@ impl Vars {
         ) {
             self.email.danger_insecure = v;
         }
+        if let Some(v) = t_bool(
+            &mut table,
+            "email",
+            "change_notify_old",
+            "EMAIL_CHANGE_NOTIFY_OLD",
+        ) {
+            self.email.change_notify_old = v;
+        }
+        if let Some(v) = t_bool(
+            &mut table,
+            "email",
+            "lockout_notify",
+            "EMAIL_LOCKOUT_NOTIFY",
+        ) {
+            self.email.lockout_notify = v;
+        }
+        if let Some(v) = t_u32(
+            &mut table,
+            "email",
+            "lockout_notify_interval",
+            "EMAIL_LOCKOUT_NOTIFY_INTERVAL",
+        ) {
+            self.email.lockout_notify_interval = v;
+        }
+        if let Some(v) = t_bool(
+            &mut table,
+            "email",
+            "welcome_new_users",
+            "EMAIL_WELCOME_NEW_USERS",
+        ) {
+            self.email.welcome_new_users = v;
+        }
 
         // [email.jobs]
         let mut jobs = t_table(&mut table, "jobs");
@@ -1729,6 +2278,15 @@ impl Vars {
             self.events.level_user_email_change = EventLevel::from_str(&v)
                 .expect("Cannot parse EventLevel for level_user_email_change");
         }
+        if let Some(v) = t_str(
+            &mut table,
+            "events",
+            "level_user_email_change_request",
+            "EVENT_LEVEL_USER_EMAIL_CHANGE_REQUEST",
+        ) {
+            self.events.level_user_email_change_request = EventLevel::from_str(&v)
+                .expect("Cannot parse EventLevel for level_user_email_change_request");
+        }
         if let Some(v) = t_str(
             &mut table,
             "events",
@@ -1867,84 +2425,219 @@ impl Vars {
         if let Some(v) = t_str(
             &mut table,
             "events",
-            "level_token_issued",
-            "EVENT_LEVEL_TOKEN_ISSUED",
+            "level_introspection_denied",
+            "EVENT_LEVEL_INTROSPECTION_DENIED",
         ) {
-            self.events.level_token_issued =
-                EventLevel::from_str(&v).expect("Cannot parse EventLevel for level_token_issued");
+            self.events.level_introspection_denied = EventLevel::from_str(&v)
+                .expect("Cannot parse EventLevel for level_introspection_denied");
         }
-
         if let Some(v) = t_str(
             &mut table,
             "events",
-            "level_failed_logins_25",
-            "EVENT_LEVEL_FAILED_LOGINS_25",
+            "level_registration_rate_limit",
+            "EVENT_LEVEL_REGISTRATION_RATE_LIMIT",
         ) {
-            self.events.level_failed_logins_25 = EventLevel::from_str(&v)
-                .expect("Cannot parse EventLevel for level_failed_logins_25");
+            self.events.level_registration_rate_limit = EventLevel::from_str(&v)
+                .expect("Cannot parse EventLevel for level_registration_rate_limit");
         }
         if let Some(v) = t_str(
             &mut table,
             "events",
-            "level_failed_logins_20",
-            "EVENT_LEVEL_FAILED_LOGINS_20",
+            "level_tokens_revoked",
+            "EVENT_LEVEL_TOKENS_REVOKED",
         ) {
-            self.events.level_failed_logins_20 = EventLevel::from_str(&v)
-                .expect("Cannot parse EventLevel for level_failed_logins_20");
+            self.events.level_tokens_revoked =
+                EventLevel::from_str(&v).expect("Cannot parse EventLevel for level_tokens_revoked");
         }
         if let Some(v) = t_str(
             &mut table,
             "events",
-            "level_failed_logins_15",
-            "EVENT_LEVEL_FAILED_LOGINS_15",
+            "level_tokens_invalidated",
+            "EVENT_LEVEL_TOKENS_INVALIDATED",
         ) {
-            self.events.level_failed_logins_15 = EventLevel::from_str(&v)
-                .expect("Cannot parse EventLevel for level_failed_logins_15");
+            self.events.level_tokens_invalidated = EventLevel::from_str(&v)
+                .expect("Cannot parse EventLevel for level_tokens_invalidated");
         }
         if let Some(v) = t_str(
             &mut table,
             "events",
-            "level_failed_logins_10",
-            "EVENT_LEVEL_FAILED_LOGINS_10",
+            "level_user_data_exported",
+            "EVENT_LEVEL_USER_DATA_EXPORTED",
         ) {
-            self.events.level_failed_logins_10 = EventLevel::from_str(&v)
-                .expect("Cannot parse EventLevel for level_failed_logins_10");
+            self.events.level_user_data_exported = EventLevel::from_str(&v)
+                .expect("Cannot parse EventLevel for level_user_data_exported");
         }
         if let Some(v) = t_str(
             &mut table,
             "events",
-            "level_failed_logins_7",
-            "EVENT_LEVEL_FAILED_LOGINS_7",
+            "level_user_federation_remapped",
+            "EVENT_LEVEL_USER_FEDERATION_REMAPPED",
         ) {
-            self.events.level_failed_logins_7 = EventLevel::from_str(&v)
-                .expect("Cannot parse EventLevel for level_failed_logins_7");
+            self.events.level_user_federation_remapped = EventLevel::from_str(&v)
+                .expect("Cannot parse EventLevel for level_user_federation_remapped");
         }
         if let Some(v) = t_str(
             &mut table,
             "events",
-            "level_failed_login",
-            "EVENT_LEVEL_FAILED_LOGIN",
+            "level_user_onboarded",
+            "EVENT_LEVEL_USER_ONBOARDED",
         ) {
-            self.events.level_failed_login =
-                EventLevel::from_str(&v).expect("Cannot parse EventLevel for level_failed_login");
+            self.events.level_user_onboarded =
+                EventLevel::from_str(&v).expect("Cannot parse EventLevel for level_user_onboarded");
         }
-
-        if let Some(v) = t_bool(
+        if let Some(v) = t_str(
             &mut table,
             "events",
-            "disable_app_version_check",
-            "DISABLE_APP_VERSION_CHECK",
+            "level_cache_flushed",
+            "EVENT_LEVEL_CACHE_FLUSHED",
         ) {
-            self.events.disable_app_version_check = v;
+            self.events.level_cache_flushed =
+                EventLevel::from_str(&v).expect("Cannot parse EventLevel for level_cache_flushed");
         }
-    }
-
-    fn parse_fedcm(&mut self, table: &mut toml::Table) {
-        let mut table = t_table(table, "fedcm");
-
-        if let Some(v) = t_bool(
+        if let Some(v) = t_str(
             &mut table,
-            "fedcm",
+            "events",
+            "level_registration_pending",
+            "EVENT_LEVEL_REGISTRATION_PENDING",
+        ) {
+            self.events.level_registration_pending = EventLevel::from_str(&v)
+                .expect("Cannot parse EventLevel for level_registration_pending");
+        }
+        if let Some(v) = t_str(
+            &mut table,
+            "events",
+            "level_registration_approved",
+            "EVENT_LEVEL_REGISTRATION_APPROVED",
+        ) {
+            self.events.level_registration_approved = EventLevel::from_str(&v)
+                .expect("Cannot parse EventLevel for level_registration_approved");
+        }
+        if let Some(v) = t_str(
+            &mut table,
+            "events",
+            "level_registration_rejected",
+            "EVENT_LEVEL_REGISTRATION_REJECTED",
+        ) {
+            self.events.level_registration_rejected = EventLevel::from_str(&v)
+                .expect("Cannot parse EventLevel for level_registration_rejected");
+        }
+        if let Some(v) = t_str(
+            &mut table,
+            "events",
+            "level_account_recovered",
+            "EVENT_LEVEL_ACCOUNT_RECOVERED",
+        ) {
+            self.events.level_account_recovered = EventLevel::from_str(&v)
+                .expect("Cannot parse EventLevel for level_account_recovered");
+        }
+        if let Some(v) = t_str(
+            &mut table,
+            "events",
+            "level_records_swept",
+            "EVENT_LEVEL_RECORDS_SWEPT",
+        ) {
+            self.events.level_records_swept =
+                EventLevel::from_str(&v).expect("Cannot parse EventLevel for level_records_swept");
+        }
+        if let Some(v) = t_str(
+            &mut table,
+            "events",
+            "level_user_disabled",
+            "EVENT_LEVEL_USER_DISABLED",
+        ) {
+            self.events.level_user_disabled =
+                EventLevel::from_str(&v).expect("Cannot parse EventLevel for level_user_disabled");
+        }
+        if let Some(v) = t_str(
+            &mut table,
+            "events",
+            "level_user_enabled",
+            "EVENT_LEVEL_USER_ENABLED",
+        ) {
+            self.events.level_user_enabled =
+                EventLevel::from_str(&v).expect("Cannot parse EventLevel for level_user_enabled");
+        }
+        if let Some(v) = t_str(
+            &mut table,
+            "events",
+            "level_token_issued",
+            "EVENT_LEVEL_TOKEN_ISSUED",
+        ) {
+            self.events.level_token_issued =
+                EventLevel::from_str(&v).expect("Cannot parse EventLevel for level_token_issued");
+        }
+
+        if let Some(v) = t_str(
+            &mut table,
+            "events",
+            "level_failed_logins_25",
+            "EVENT_LEVEL_FAILED_LOGINS_25",
+        ) {
+            self.events.level_failed_logins_25 = EventLevel::from_str(&v)
+                .expect("Cannot parse EventLevel for level_failed_logins_25");
+        }
+        if let Some(v) = t_str(
+            &mut table,
+            "events",
+            "level_failed_logins_20",
+            "EVENT_LEVEL_FAILED_LOGINS_20",
+        ) {
+            self.events.level_failed_logins_20 = EventLevel::from_str(&v)
+                .expect("Cannot parse EventLevel for level_failed_logins_20");
+        }
+        if let Some(v) = t_str(
+            &mut table,
+            "events",
+            "level_failed_logins_15",
+            "EVENT_LEVEL_FAILED_LOGINS_15",
+        ) {
+            self.events.level_failed_logins_15 = EventLevel::from_str(&v)
+                .expect("Cannot parse EventLevel for level_failed_logins_15");
+        }
+        if let Some(v) = t_str(
+            &mut table,
+            "events",
+            "level_failed_logins_10",
+            "EVENT_LEVEL_FAILED_LOGINS_10",
+        ) {
+            self.events.level_failed_logins_10 = EventLevel::from_str(&v)
+                .expect("Cannot parse EventLevel for level_failed_logins_10");
+        }
+        if let Some(v) = t_str(
+            &mut table,
+            "events",
+            "level_failed_logins_7",
+            "EVENT_LEVEL_FAILED_LOGINS_7",
+        ) {
+            self.events.level_failed_logins_7 = EventLevel::from_str(&v)
+                .expect("Cannot parse EventLevel for level_failed_logins_7");
+        }
+        if let Some(v) = t_str(
+            &mut table,
+            "events",
+            "level_failed_login",
+            "EVENT_LEVEL_FAILED_LOGIN",
+        ) {
+            self.events.level_failed_login =
+                EventLevel::from_str(&v).expect("Cannot parse EventLevel for level_failed_login");
+        }
+
+        if let Some(v) = t_bool(
+            &mut table,
+            "events",
+            "disable_app_version_check",
+            "DISABLE_APP_VERSION_CHECK",
+        ) {
+            self.events.disable_app_version_check = v;
+        }
+    }
+
+    fn parse_fedcm(&mut self, table: &mut toml::Table) {
+        let mut table = t_table(table, "fedcm");
+
+        if let Some(v) = t_bool(
+            &mut table,
+            "fedcm",
             "experimental_enable",
             "EXPERIMENTAL_FED_CM_ENABLE",
         ) {
@@ -2144,6 +2837,22 @@ impl Vars {
         ) {
             self.http_client.root_ca_bundle = Some(v);
         }
+        if let Some(v) = t_str_vec(
+            &mut table,
+            "http_client",
+            "tls_cipher_suites",
+            "HTTP_TLS_CIPHER_SUITES",
+        ) {
+            self.http_client.tls_cipher_suites = v;
+        }
+        if let Some(v) = t_str_vec(
+            &mut table,
+            "http_client",
+            "tls_kx_groups",
+            "HTTP_TLS_KX_GROUPS",
+        ) {
+            self.http_client.tls_kx_groups = v;
+        }
     }
 
     fn parse_i18n(&mut self, table: &mut toml::Table) {
@@ -2162,6 +2871,221 @@ impl Vars {
         }
     }
 
+    fn parse_ip_reputation(&mut self, table: &mut toml::Table) {
+        let mut table = t_table(table, "ip_reputation");
+
+        if let Some(v) = t_str(&mut table, "ip_reputation", "file", "IP_REPUTATION_FILE") {
+            self.ip_reputation.file = Some(v);
+        }
+        if let Some(v) = t_u32(
+            &mut table,
+            "ip_reputation",
+            "file_reload_secs",
+            "IP_REPUTATION_FILE_RELOAD_SECS",
+        ) {
+            self.ip_reputation.file_reload_secs = v;
+        }
+        if let Some(v) = t_str(&mut table, "ip_reputation", "url", "IP_REPUTATION_URL") {
+            self.ip_reputation.url = Some(v);
+        }
+        if let Some(v) = t_str_vec(
+            &mut table,
+            "ip_reputation",
+            "headers",
+            "IP_REPUTATION_HEADERS",
+        ) {
+            self.ip_reputation.headers = v
+                .into_iter()
+                .map(|h| {
+                    let (name, value) = h.split_once(':').unwrap_or_else(|| {
+                        panic!("Invalid `[ip_reputation].headers` value, expected `Name: value`")
+                    });
+                    (name.trim().to_string(), value.trim().to_string())
+                })
+                .collect();
+        }
+        if let Some(v) = t_str(
+            &mut table,
+            "ip_reputation",
+            "score_pointer",
+            "IP_REPUTATION_SCORE_POINTER",
+        ) {
+            self.ip_reputation.score_pointer = v.into();
+        }
+        if let Some(v) = t_u32(
+            &mut table,
+            "ip_reputation",
+            "timeout_ms",
+            "IP_REPUTATION_TIMEOUT_MS",
+        ) {
+            self.ip_reputation.timeout_ms = v;
+        }
+        if let Some(v) = t_u32(
+            &mut table,
+            "ip_reputation",
+            "cache_ttl",
+            "IP_REPUTATION_CACHE_TTL",
+        ) {
+            self.ip_reputation.cache_ttl = v;
+        }
+        if let Some(v) = t_u8(
+            &mut table,
+            "ip_reputation",
+            "threshold_step_up",
+            "IP_REPUTATION_THRESHOLD_STEP_UP",
+        ) {
+            self.ip_reputation.threshold_step_up = v;
+        }
+        if let Some(v) = t_u8(
+            &mut table,
+            "ip_reputation",
+            "threshold_block",
+            "IP_REPUTATION_THRESHOLD_BLOCK",
+        ) {
+            self.ip_reputation.threshold_block = v;
+        }
+        if let Some(v) = t_bool(
+            &mut table,
+            "ip_reputation",
+            "fail_open",
+            "IP_REPUTATION_FAIL_OPEN",
+        ) {
+            self.ip_reputation.fail_open = v;
+        }
+
+        if self.ip_reputation.threshold_step_up > 100 || self.ip_reputation.threshold_block > 100 {
+            panic!("`[ip_reputation]` thresholds must be between 0 and 100");
+        }
+        if (self.ip_reputation.file.is_some() || self.ip_reputation.url.is_some())
+            && self.ip_reputation.threshold_step_up > 0
+            && self.captcha.provider.is_none()
+        {
+            panic!(
+                "`[ip_reputation].threshold_step_up` needs a `[captcha].provider`. Configure one, \
+                or set `threshold_step_up = 0` to disable the step-up."
+            );
+        }
+    }
+
+    fn parse_ldap(&mut self, table: &mut toml::Table) {
+        let mut table = t_table(table, "ldap");
+
+        if let Some(v) = t_str(&mut table, "ldap", "url", "LDAP_URL") {
+            if !v.starts_with("ldap://") && !v.starts_with("ldaps://") {
+                panic!("`[ldap].url` must start with `ldap://` or `ldaps://`");
+            }
+            self.ldap.url = Some(v);
+        }
+        if let Some(v) = t_bool(&mut table, "ldap", "starttls", "LDAP_STARTTLS") {
+            self.ldap.starttls = v;
+        }
+        if let Some(v) = t_str(&mut table, "ldap", "root_ca", "LDAP_ROOT_CA") {
+            self.ldap.root_ca = Some(v);
+        }
+        if let Some(v) = t_bool(
+            &mut table,
+            "ldap",
+            "danger_insecure",
+            "LDAP_DANGER_INSECURE",
+        ) {
+            self.ldap.danger_insecure = v;
+        }
+        if let Some(v) = t_bool(
+            &mut table,
+            "ldap",
+            "danger_unencrypted",
+            "LDAP_DANGER_UNENCRYPTED",
+        ) {
+            self.ldap.danger_unencrypted = v;
+        }
+        if let Some(v) = t_str(&mut table, "ldap", "provider_id", "LDAP_PROVIDER_ID") {
+            self.ldap.provider_id = v.into();
+        }
+        if let Some(v) = t_str(&mut table, "ldap", "bind_dn", "LDAP_BIND_DN") {
+            self.ldap.bind_dn = Some(v);
+        }
+        if let Some(v) = t_str(&mut table, "ldap", "bind_password", "LDAP_BIND_PASSWORD") {
+            self.ldap.bind_password = Some(v);
+        }
+        if let Some(v) = t_str(&mut table, "ldap", "base_dn", "LDAP_BASE_DN") {
+            self.ldap.base_dn = Some(v);
+        }
+        if let Some(v) = t_str(&mut table, "ldap", "user_filter", "LDAP_USER_FILTER") {
+            self.ldap.user_filter = v.into();
+        }
+        if let Some(v) = t_str(&mut table, "ldap", "attr_uid", "LDAP_ATTR_UID") {
+            self.ldap.attr_uid = v.into();
+        }
+        if let Some(v) = t_str(&mut table, "ldap", "attr_email", "LDAP_ATTR_EMAIL") {
+            self.ldap.attr_email = v.into();
+        }
+        if let Some(v) = t_str(
+            &mut table,
+            "ldap",
+            "attr_given_name",
+            "LDAP_ATTR_GIVEN_NAME",
+        ) {
+            self.ldap.attr_given_name = v.into();
+        }
+        if let Some(v) = t_str(
+            &mut table,
+            "ldap",
+            "attr_family_name",
+            "LDAP_ATTR_FAMILY_NAME",
+        ) {
+            self.ldap.attr_family_name = v.into();
+        }
+        if let Some(v) = t_str(&mut table, "ldap", "attr_groups", "LDAP_ATTR_GROUPS") {
+            self.ldap.attr_groups = v.into();
+        }
+        if let Some(v) = t_str_vec(&mut table, "ldap", "role_mappings", "LDAP_ROLE_MAPPINGS") {
+            self.ldap.role_mappings = Self::parse_ldap_mappings(v, "role_mappings");
+        }
+        if let Some(v) = t_str_vec(&mut table, "ldap", "group_mappings", "LDAP_GROUP_MAPPINGS") {
+            self.ldap.group_mappings = Self::parse_ldap_mappings(v, "group_mappings");
+        }
+        if let Some(v) = t_u16(&mut table, "ldap", "pool_size", "LDAP_POOL_SIZE") {
+            self.ldap.pool_size = v;
+        }
+        if let Some(v) = t_u32(&mut table, "ldap", "timeout_ms", "LDAP_TIMEOUT_MS") {
+            self.ldap.timeout_ms = v;
+        }
+
+        if self.ldap.url.is_some() {
+            if self.ldap.base_dn.is_none() {
+                panic!("`[ldap].url` is set, but `[ldap].base_dn` is missing");
+            }
+            if !self.ldap.user_filter.contains("{email}") {
+                panic!("`[ldap].user_filter` must contain the `{{email}}` placeholder");
+            }
+            if self.ldap.starttls && self.ldap.url.as_deref().unwrap().starts_with("ldaps://") {
+                panic!("`[ldap].starttls` cannot be used with an `ldaps://` URL");
+            }
+            if !self.ldap.starttls
+                && self.ldap.url.as_deref().unwrap().starts_with("ldap://")
+                && !self.ldap.danger_unencrypted
+            {
+                panic!(
+                    "`[ldap].url` uses `ldap://` without `starttls`, which would send passwords \
+                    in plain text. Set `starttls = true`, use `ldaps://`, or set \
+                    `danger_unencrypted = true` for testing."
+                );
+            }
+        }
+    }
+
+    fn parse_ldap_mappings(values: Vec<String>, key: &str) -> Vec<(String, String)> {
+        values
+            .into_iter()
+            .map(|m| {
+                let (source, target) = m.split_once("=>").unwrap_or_else(|| {
+                    panic!("Invalid `[ldap].{key}` value, expected `<ldap group> => <target>`")
+                });
+                (source.trim().to_lowercase(), target.trim().to_string())
+            })
+            .collect()
+    }
+
     fn parse_lifetimes(&mut self, table: &mut toml::Table) {
         let mut table = t_table(table, "lifetimes");
 
@@ -2181,6 +3105,14 @@ impl Vars {
         ) {
             self.lifetimes.refresh_token_lifetime = v;
         }
+        if let Some(v) = t_bool(
+            &mut table,
+            "lifetimes",
+            "refresh_token_reuse_detection",
+            "REFRESH_TOKEN_REUSE_DETECTION",
+        ) {
+            self.lifetimes.refresh_token_reuse_detection = v;
+        }
         if let Some(v) = t_u32(
             &mut table,
             "lifetimes",
@@ -2229,6 +3161,9 @@ impl Vars {
         ) {
             self.lifetimes.jwk_autorotate_cron = v.into();
         }
+        if let Some(v) = t_u32(&mut table, "lifetimes", "jwks_max_age", "JWKS_MAX_AGE") {
+            self.lifetimes.jwks_max_age = v;
+        }
     }
 
     fn parse_logging(&mut self, table: &mut toml::Table) {
@@ -2251,6 +3186,15 @@ impl Vars {
         if let Some(v) = t_str(&mut table, "logging", "log_fmt", "LOG_FMT") {
             self.logging.log_fmt = v.into();
         }
+        if let Some(v) = t_str_vec(&mut table, "logging", "redact_fields", "LOG_REDACT_FIELDS") {
+            if let Err(err) = Redact::from_fields(&v) {
+                panic!("Invalid `[logging].redact_fields`: {}", err.message);
+            }
+            self.logging.redact_fields = v;
+        }
+        if let Some(v) = t_str(&mut table, "logging", "redact_level", "LOG_REDACT_LEVEL") {
+            self.logging.redact_level = v.into();
+        }
     }
 
     fn parse_matrix(&mut self, table: &mut toml::Table) {
@@ -2272,6 +3216,22 @@ impl Vars {
         if let Some(v) = t_bool(&mut table, "mfa", "admin_force_mfa", "ADMIN_FORCE_MFA") {
             self.mfa.admin_force_mfa = v;
         }
+        if let Some(v) = t_bool(
+            &mut table,
+            "mfa",
+            "provider_recovery",
+            "MFA_PROVIDER_RECOVERY",
+        ) {
+            self.mfa.provider_recovery = v;
+        }
+        if let Some(v) = t_bool(
+            &mut table,
+            "mfa",
+            "provider_recovery_notify",
+            "MFA_PROVIDER_RECOVERY_NOTIFY",
+        ) {
+            self.mfa.provider_recovery_notify = v;
+        }
     }
 
     fn parse_pam(&mut self, table: &mut toml::Table) {
@@ -2400,6 +3360,19 @@ impl Vars {
         if let Some(v) = t_str(&mut table, "server", "pub_url", "PUB_URL") {
             self.server.pub_url = v;
         }
+        if let Some(v) = t_str_vec(&mut table, "server", "issuer_hosts", "ISSUER_HOSTS") {
+            self.server.issuer_hosts = v
+                .into_iter()
+                .map(|host| {
+                    if host.contains('/') || host.contains(char::is_whitespace) {
+                        panic!(
+                            "Invalid `[server].issuer_hosts` value '{host}', expected `host[:port]`"
+                        );
+                    }
+                    host.to_lowercase()
+                })
+                .collect();
+        }
         if let Some(v) = t_u16(&mut table, "server", "http_workers", "HTTP_WORKERS") {
             self.server.http_workers = v;
         }
@@ -2490,60 +3463,36 @@ impl Vars {
             let typ = t_str(&mut table, "[templates]", "typ", "")
                 .expect("`typ` is mandatory for `[[templates]]`");
 
-            let is_password_new = match typ.as_str() {
-                "password_new" => true,
-                "password_reset" => false,
-                _ => {
+            let tpls = &mut self.templates;
+            let tpl = match (typ.as_str(), lang.as_str()) {
+                ("password_new", "de") => &mut tpls.password_new_de,
+                ("password_new", "en") => &mut tpls.password_new_en,
+                ("password_new", "ko") => &mut tpls.password_new_ko,
+                ("password_new", "nb") => &mut tpls.password_new_nb,
+                ("password_new", "uk") => &mut tpls.password_new_uk,
+                ("password_new", "zh_hans") => &mut tpls.password_new_zhhans,
+                ("password_reset", "de") => &mut tpls.password_reset_de,
+                ("password_reset", "en") => &mut tpls.password_reset_en,
+                ("password_reset", "ko") => &mut tpls.password_reset_ko,
+                ("password_reset", "nb") => &mut tpls.password_reset_nb,
+                ("password_reset", "uk") => &mut tpls.password_reset_uk,
+                ("password_reset", "zh_hans") => &mut tpls.password_reset_zhhans,
+                ("welcome", "de") => &mut tpls.welcome_de,
+                ("welcome", "en") => &mut tpls.welcome_en,
+                ("welcome", "ko") => &mut tpls.welcome_ko,
+                ("welcome", "nb") => &mut tpls.welcome_nb,
+                ("welcome", "uk") => &mut tpls.welcome_uk,
+                ("welcome", "zh_hans") => &mut tpls.welcome_zhhans,
+                ("password_new" | "password_reset" | "welcome", _) => {
                     panic!(
-                        "Invalid value for `templates.typ`, allowed are: password_new password_reset"
+                        "Invalid value for `templates.lang`, allowed are: en de ko nb uk zh_hans"
                     )
                 }
-            };
-            let mut tpl = match lang.as_str() {
-                "en" => {
-                    if is_password_new {
-                        self.templates.password_new_en.clone()
-                    } else {
-                        self.templates.password_reset_en.clone()
-                    }
-                }
-                "de" => {
-                    if is_password_new {
-                        self.templates.password_new_de.clone()
-                    } else {
-                        self.templates.password_reset_de.clone()
-                    }
-                }
-                "ko" => {
-                    if is_password_new {
-                        self.templates.password_new_ko.clone()
-                    } else {
-                        self.templates.password_reset_ko.clone()
-                    }
-                }
-                "nb" => {
-                    if is_password_new {
-                        self.templates.password_new_nb.clone()
-                    } else {
-                        self.templates.password_reset_nb.clone()
-                    }
-                }
-                "uk" => {
-                    if is_password_new {
-                        self.templates.password_new_uk.clone()
-                    } else {
-                        self.templates.password_reset_uk.clone()
-                    }
-                }
-                "zh_hans" => {
-                    if is_password_new {
-                        self.templates.password_new_zhhans.clone()
-                    } else {
-                        self.templates.password_reset_zhhans.clone()
-                    }
-                }
                 _ => {
-                    panic!("Invalid value for `templates.lang`, allowed are: en de ko zh_hans")
+                    panic!(
+                        "Invalid value for `templates.typ`, allowed are: password_new \
+                        password_reset welcome"
+                    )
                 }
             };
 
@@ -2568,52 +3517,12 @@ impl Vars {
             if let Some(v) = t_str(&mut table, "templates", "footer", "") {
                 tpl.footer = Some(v);
             }
+            if let Some(v) = t_str(&mut table, "templates", "button", "") {
+                tpl.button = v.into();
+            }
             if let Some(v) = t_str(&mut table, "templates", "button_text_request_new", "") {
                 tpl.button_text_request_new = Some(v.into());
             }
-
-            match lang.as_str() {
-                "en" => {
-                    if is_password_new {
-                        self.templates.password_new_en = tpl;
-                    } else {
-                        self.templates.password_reset_en = tpl;
-                    }
-                }
-                "de" => {
-                    if is_password_new {
-                        self.templates.password_new_de = tpl;
-                    } else {
-                        self.templates.password_reset_de = tpl;
-                    }
-                }
-                "ko" => {
-                    if is_password_new {
-                        self.templates.password_new_ko = tpl;
-                    } else {
-                        self.templates.password_reset_ko = tpl;
-                    }
-                }
-                "uk" => {
-                    if is_password_new {
-                        self.templates.password_new_uk = tpl;
-                    } else {
-                        self.templates.password_reset_uk = tpl;
-                    }
-                }
-                "zh_hans" => {
-                    if is_password_new {
-                        self.templates.password_new_zhhans = tpl;
-                    } else {
-                        self.templates.password_reset_zhhans = tpl;
-                    }
-                }
-                _ => {
-                    panic!(
-                        "Invalid value for `templates.lang`, allowed are: en de ko nb uk zh_hans"
-                    )
-                }
-            }
         }
     }
 
@@ -2656,6 +3565,14 @@ impl Vars {
         ) {
             self.user_delete.enable_self_delete = v;
         }
+        if let Some(v) = t_u32(
+            &mut table,
+            "user_delete",
+            "disabled_retention_days",
+            "USER_DISABLED_RETENTION_DAYS",
+        ) {
+            self.user_delete.disabled_retention_days = v;
+        }
     }
 
     fn parse_user_pictures(&mut self, table: &mut toml::Table) {
@@ -2719,13 +3636,31 @@ impl Vars {
         if let Some(v) = t_bool(&mut table, "user_registration", "enable", "OPEN_USER_REG") {
             self.user_registration.enable = v;
         }
+        if let Some(v) = t_str_vec(
+            &mut table,
+            "user_registration",
+            "domain_allowlist",
+            "USER_REG_DOMAIN_ALLOWLIST",
+        ) {
+            self.user_registration.domain_allowlist = v
+                .iter()
+                .map(|d| VarsUserRegistration::migrate_domain_pattern(d, "domain_allowlist"))
+                .collect();
+        }
+        // Legacy single-domain value, kept for backwards compatibility. It has always matched
+        // subdomains as well, which is why the wildcard is added too.
         if let Some(v) = t_str(
             &mut table,
             "user_registration",
             "domain_restriction",
             "USER_REG_DOMAIN_RESTRICTION",
         ) {
-            self.user_registration.domain_restriction = Some(v);
+            let v = VarsUserRegistration::migrate_domain_pattern(&v, "domain_restriction");
+            for pattern in [format!("*.{v}"), v] {
+                if !self.user_registration.domain_allowlist.contains(&pattern) {
+                    self.user_registration.domain_allowlist.push(pattern);
+                }
+            }
         }
         if let Some(v) = t_str_vec(
             &mut table,
@@ -2733,7 +3668,58 @@ impl Vars {
             "domain_blacklist",
             "USER_REG_DOMAIN_BLACKLIST",
         ) {
-            self.user_registration.domain_blacklist = v;
+            self.user_registration.domain_blacklist = v
+                .iter()
+                .map(|d| VarsUserRegistration::migrate_domain_pattern(d, "domain_blacklist"))
+                .collect();
+        }
+        if let Some(v) = t_bool(
+            &mut table,
+            "user_registration",
+            "domain_restriction_admin_bypass",
+            "USER_REG_DOMAIN_RESTRICTION_ADMIN_BYPASS",
+        ) {
+            self.user_registration.domain_restriction_admin_bypass = v;
+        }
+        if let Some(v) = t_u16(
+            &mut table,
+            "user_registration",
+            "rate_limit_max",
+            "USER_REG_RATE_LIMIT_MAX",
+        ) {
+            self.user_registration.rate_limit_max = v;
+        }
+        if let Some(v) = t_u32(
+            &mut table,
+            "user_registration",
+            "rate_limit_window",
+            "USER_REG_RATE_LIMIT_WINDOW",
+        ) {
+            self.user_registration.rate_limit_window = v;
+        }
+        if let Some(v) = t_u16(
+            &mut table,
+            "user_registration",
+            "rate_limit_min_interval",
+            "USER_REG_RATE_LIMIT_MIN_INTERVAL",
+        ) {
+            self.user_registration.rate_limit_min_interval = v;
+        }
+        if let Some(v) = t_bool(
+            &mut table,
+            "user_registration",
+            "require_approval",
+            "USER_REG_REQUIRE_APPROVAL",
+        ) {
+            self.user_registration.require_approval = v;
+        }
+        if let Some(v) = t_bool(
+            &mut table,
+            "user_registration",
+            "require_approval_federated",
+            "USER_REG_REQUIRE_APPROVAL_FEDERATED",
+        ) {
+            self.user_registration.require_approval_federated = v;
         }
     }
 
@@ -2848,6 +3834,14 @@ impl Vars {
         if let Some(v) = t_str(&mut table, "webauthn", "rp_origin", "RP_ORIGIN") {
             self.webauthn.rp_origin = v;
         }
+        if let Some(v) = t_str_vec(
+            &mut table,
+            "webauthn",
+            "additional_origins",
+            "WEBAUTHN_ADDITIONAL_ORIGINS",
+        ) {
+            self.webauthn.additional_origins = v;
+        }
         if let Some(v) = t_str(&mut table, "webauthn", "rp_name", "RP_NAME") {
             self.webauthn.rp_name = v.into();
         }
@@ -2940,19 +3934,49 @@ impl Vars {
             panic!("`server.proxy_mode` is set but `server.trusted_proxies` is empty");
         }
 
+        for pattern in self
+            .user_registration
+            .domain_allowlist
+            .iter()
+            .chain(self.user_registration.domain_blacklist.iter())
+        {
+            if !RE_EMAIL_DOMAIN_PATTERN.is_match(pattern) {
+                panic!(
+                    "Invalid domain pattern '{pattern}' in `user_registration.domain_allowlist` / \
+                    `user_registration.domain_blacklist` - expected e.g. `example.com` or \
+                    `*.example.com`"
+                );
+            }
+        }
+
         if self.webauthn.rp_id.is_empty() {
             panic!("`webauthn.rp_id` is missing");
         }
         if self.webauthn.rp_origin.is_empty() {
             panic!("`webauthn.rp_origin` is missing");
         }
-        let (_, webauthn_port) = self
-            .webauthn
-            .rp_origin
-            .rsplit_once(':')
-            .expect("Invalid format for `webauthn.rp_origin` - missing port");
-        if webauthn_port.parse::<u16>().is_err() {
-            panic!("Invalid value for `webauthn.rp_origin` port");
+        for origin in
+            std::iter::once(&self.webauthn.rp_origin).chain(self.webauthn.additional_origins.iter())
+        {
+            let (_, webauthn_port) = origin.rsplit_once(':').unwrap_or_else(|| {
+                panic!("Invalid format for WebAuthn origin '{origin}' - missing port")
+            });
+            if webauthn_port.parse::<u16>().is_err() {
+                panic!("Invalid port for WebAuthn origin '{origin}'");
+            }
+
+            let url = webauthn_rs::prelude::Url::parse(origin)
+                .unwrap_or_else(|_| panic!("Cannot parse WebAuthn origin '{origin}' to URL"));
+            if url.path() != "/" || url.query().is_some() {
+                panic!("WebAuthn origin '{origin}' must not contain a path or query");
+            }
+            if !rp_id_matches_origin(&self.webauthn.rp_id, &url) {
+                panic!(
+                    "`webauthn.rp_id` '{}' is not a registrable suffix of the WebAuthn origin \
+                    '{origin}'",
+                    self.webauthn.rp_id
+                );
+            }
         }
     }
 }
@@ -2963,12 +3987,31 @@ pub struct VarsDev {
     pub dpop_http: bool,
     pub insecure_cookie: bool,
     pub provider_callback_url: Option<String>,
+    pub token_preview: bool,
+    pub danger_unsafe_test_mode: bool,
+}
+
+impl VarsDev {
+    /// The token preview must never be enabled by accident in production, so it requires the
+    /// additional `danger_unsafe_test_mode` to be set.
+    fn validate_test_mode(&self) {
+        if self.token_preview && !self.danger_unsafe_test_mode {
+            panic!(
+                "`dev.token_preview` must only be used for testing and requires \
+                `dev.danger_unsafe_test_mode = true` as well"
+            );
+        }
+        if self.danger_unsafe_test_mode {
+            warn!("`dev.danger_unsafe_test_mode` is enabled - DO NOT USE IN PRODUCTION!");
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct VarsAccess {
     pub userinfo_strict: bool,
     pub danger_disable_introspect_auth: bool,
+    pub introspect_restrict_aud: bool,
     pub disable_refresh_token_nbf: bool,
     pub sec_header_block: bool,
     pub session_validate_ip: bool,
@@ -2976,12 +4019,66 @@ pub struct VarsAccess {
     pub peer_ip_header_name: Option<String>,
     pub cookie_mode: CookieMode,
     pub cookie_set_path: bool,
+    pub session_cookie_domain: Option<String>,
+    pub session_cookie_path: Option<String>,
+    pub session_cookie_same_site: SameSite,
     pub client_credentials_map_sub: bool,
     pub token_len_limit: u32,
     pub token_revoke_on_logout: bool,
     pub token_revoke_device_tokens: bool,
+    pub token_bind_session: bool,
     pub whoami_headers: bool,
     pub admin_button_hide: bool,
+    pub strict_scopes: bool,
+    pub tokens_not_before: i64,
+    pub pkce_allow_plain: bool,
+    pub auth_state_required: bool,
+    pub auth_state_binding: bool,
+    pub nonce_replay_protection: bool,
+    pub redirect_uri_validation_rate_limit: u16,
+    pub client_secret_min_length: u16,
+    pub client_secret_min_entropy: u16,
+    pub token_rate_limit_window: u16,
+    pub token_rate_limit_client: u16,
+    pub token_rate_limit_ip: u16,
+    pub token_max_body_bytes: u32,
+}
+
+impl VarsAccess {
+    fn validate_session_cookie(&self) {
+        if self.session_cookie_same_site == SameSite::None
+            && self.cookie_mode == CookieMode::DangerInsecure
+        {
+            panic!(
+                "`access.session_cookie_same_site = 'none'` requires a secure cookie, it cannot be \
+                used with `cookie_mode = 'danger-insecure'`"
+            );
+        }
+
+        if self.cookie_mode == CookieMode::Host
+            && (self.session_cookie_domain.is_some()
+                || self.session_cookie_path.as_ref().is_some_and(|p| p != "/"))
+        {
+            panic!(
+                "`__Host-` cookies must not have a `Domain` and their `Path` must be '/' - \
+                use `cookie_mode = 'secure'` with `access.session_cookie_domain` / \
+                `access.session_cookie_path`"
+            );
+        }
+
+        if let Some(domain) = &self.session_cookie_domain
+            && (domain.is_empty() || domain.contains(['/', ':', ' ']))
+        {
+            panic!("Invalid `access.session_cookie_domain`: '{domain}'");
+        }
+
+        // the session cookie must be sent with each request to the API
+        if let Some(path) = &self.session_cookie_path
+            && !matches!(path.as_str(), "/" | "/auth" | "/auth/v1")
+        {
+            panic!("`access.session_cookie_path` must be one of: '/', '/auth', '/auth/v1'");
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -3016,6 +4113,50 @@ pub struct VarsBootstrap {
     pub api_key_secret: Option<String>,
 }
 
+pub struct VarsCaptcha {
+    pub provider: Option<CaptchaProvider>,
+    pub site_key: String,
+    pub secret: String,
+    pub login: bool,
+    pub login_threshold: u16,
+    pub registration: bool,
+    pub fail_open: bool,
+}
+
+impl Debug for VarsCaptcha {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // the secret must never end up in any logs
+        f.debug_struct("VarsCaptcha")
+            .field("provider", &self.provider)
+            .field("site_key", &self.site_key)
+            .field("login", &self.login)
+            .field("login_threshold", &self.login_threshold)
+            .field("registration", &self.registration)
+            .field("fail_open", &self.fail_open)
+            .finish()
+    }
+}
+
+pub struct VarsClaimsProvider {
+    pub url: Option<String>,
+    pub secret: String,
+    pub timeout_ms: u32,
+    pub fail_open: bool,
+    pub allowed_claims: Vec<String>,
+}
+
+impl Debug for VarsClaimsProvider {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // the secret must never end up in any logs
+        f.debug_struct("VarsClaimsProvider")
+            .field("url", &self.url)
+            .field("timeout_ms", &self.timeout_ms)
+            .field("fail_open", &self.fail_open)
+            .field("allowed_claims", &self.allowed_claims)
+            .finish()
+    }
+}
+
 #[derive(Debug)]
 pub struct VarsDatabase {
     pub hiqlite: bool,
@@ -3034,9 +4175,13 @@ pub struct VarsDatabase {
     pub migrate_pg_user: Option<String>,
     pub migrate_pg_password: Option<String>,
     pub migrate_pg_db_name: Cow<'static, str>,
+    pub migrate_verify: bool,
+    pub migrate_exit: bool,
 
     pub sched_user_exp_mins: u32,
     pub sched_user_exp_delete_mins: Option<u32>,
+    pub sched_sweeper_mins: u32,
+    pub sched_sweeper_batch_size: u32,
 }
 
 #[derive(Debug)]
@@ -3088,6 +4233,10 @@ pub struct VarsEmail {
     pub root_ca: Option<String>,
     pub starttls_only: bool,
     pub danger_insecure: bool,
+    pub change_notify_old: bool,
+    pub lockout_notify: bool,
+    pub lockout_notify_interval: u32,
+    pub welcome_new_users: bool,
     pub tz_fmt: VarsEmailTzFmt,
 }
 
@@ -3151,6 +4300,7 @@ pub struct VarsEvents {
 
     pub level_new_user: EventLevel,
     pub level_user_email_change: EventLevel,
+    pub level_user_email_change_request: EventLevel,
     pub level_user_password_reset: EventLevel,
     pub level_rauthy_admin: EventLevel,
     pub level_rauthy_version: EventLevel,
@@ -3165,6 +4315,21 @@ pub struct VarsEvents {
     pub level_user_login_revoke: EventLevel,
     pub level_scim_task_failed: EventLevel,
     pub level_suspicious_request: EventLevel,
+    pub level_introspection_denied: EventLevel,
+    pub level_registration_rate_limit: EventLevel,
+    pub level_tokens_revoked: EventLevel,
+    pub level_tokens_invalidated: EventLevel,
+    pub level_user_data_exported: EventLevel,
+    pub level_user_federation_remapped: EventLevel,
+    pub level_user_onboarded: EventLevel,
+    pub level_cache_flushed: EventLevel,
+    pub level_registration_pending: EventLevel,
+    pub level_registration_approved: EventLevel,
+    pub level_registration_rejected: EventLevel,
+    pub level_account_recovered: EventLevel,
+    pub level_records_swept: EventLevel,
+    pub level_user_disabled: EventLevel,
+    pub level_user_enabled: EventLevel,
     pub level_new_login_location: EventLevel,
     pub level_token_issued: EventLevel,
 
@@ -3216,6 +4381,8 @@ pub struct VarsHttpClient {
     pub danger_unencrypted: bool,
     pub danger_insecure: bool,
     pub root_ca_bundle: Option<String>,
+    pub tls_cipher_suites: Vec<String>,
+    pub tls_kx_groups: Vec<String>,
 }
 
 #[derive(Debug)]
@@ -3224,16 +4391,98 @@ pub struct VarsI18n {
     pub filter_lang_admin: Vec<Cow<'static, str>>,
 }
 
+pub struct VarsIpReputation {
+    pub file: Option<String>,
+    pub file_reload_secs: u32,
+    pub url: Option<String>,
+    pub headers: Vec<(String, String)>,
+    pub score_pointer: Cow<'static, str>,
+    pub timeout_ms: u32,
+    pub cache_ttl: u32,
+    pub threshold_step_up: u8,
+    pub threshold_block: u8,
+    pub fail_open: bool,
+}
+
+impl Debug for VarsIpReputation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // headers may contain API keys, which must never end up in any logs
+        f.debug_struct("VarsIpReputation")
+            .field("file", &self.file)
+            .field("file_reload_secs", &self.file_reload_secs)
+            .field("url", &self.url)
+            .field("score_pointer", &self.score_pointer)
+            .field("timeout_ms", &self.timeout_ms)
+            .field("cache_ttl", &self.cache_ttl)
+            .field("threshold_step_up", &self.threshold_step_up)
+            .field("threshold_block", &self.threshold_block)
+            .field("fail_open", &self.fail_open)
+            .finish()
+    }
+}
+
+pub struct VarsLdap {
+    pub url: Option<String>,
+    pub starttls: bool,
+    pub root_ca: Option<String>,
+    pub danger_insecure: bool,
+    pub danger_unencrypted: bool,
+    pub provider_id: Cow<'static, str>,
+    pub bind_dn: Option<String>,
+    pub bind_password: Option<String>,
+    pub base_dn: Option<String>,
+    pub user_filter: Cow<'static, str>,
+    pub attr_uid: Cow<'static, str>,
+    pub attr_email: Cow<'static, str>,
+    pub attr_given_name: Cow<'static, str>,
+    pub attr_family_name: Cow<'static, str>,
+    pub attr_groups: Cow<'static, str>,
+    /// `(lowercase LDAP group, role name)`
+    pub role_mappings: Vec<(String, String)>,
+    /// `(lowercase LDAP group, group name)`
+    pub group_mappings: Vec<(String, String)>,
+    pub pool_size: u16,
+    pub timeout_ms: u32,
+}
+
+impl Debug for VarsLdap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VarsLdap")
+            .field("url", &self.url)
+            .field("starttls", &self.starttls)
+            .field("root_ca", &self.root_ca)
+            .field("danger_insecure", &self.danger_insecure)
+            .field("danger_unencrypted", &self.danger_unencrypted)
+            .field("provider_id", &self.provider_id)
+            .field("bind_dn", &self.bind_dn)
+            .field("bind_password", &"<hidden>")
+            .field("base_dn", &self.base_dn)
+            .field("user_filter", &self.user_filter)
+            .field("attr_uid", &self.attr_uid)
+            .field("attr_email", &self.attr_email)
+            .field("attr_given_name", &self.attr_given_name)
+            .field("attr_family_name", &self.attr_family_name)
+            .field("attr_groups", &self.attr_groups)
+            .field("role_mappings", &self.role_mappings)
+            .field("group_mappings", &self.group_mappings)
+            .field("pool_size", &self.pool_size)
+            .field("timeout_ms", &self.timeout_ms)
+            .finish()
+    }
+}
+
 #[derive(Debug)]
 pub struct VarsLifetimes {
     pub refresh_token_grace_time: u16,
     pub refresh_token_lifetime: u16,
+    pub refresh_token_reuse_detection: bool,
     pub session_lifetime: u32,
     pub session_renew_mfa: bool,
     pub session_timeout: u32,
     pub magic_link_pwd_reset: u32,
     pub magic_link_pwd_first: u32,
     pub jwk_autorotate_cron: Cow<'static, str>,
+    pub jwks_max_age: u32,
 }
 
 #[derive(Debug)]
@@ -3242,6 +4491,8 @@ pub struct VarsLogging {
     pub level_database: Cow<'static, str>,
     pub level_access: Cow<'static, str>,
     pub log_fmt: Cow<'static, str>,
+    pub redact_fields: Vec<String>,
+    pub redact_level: Cow<'static, str>,
 }
 
 #[derive(Debug)]
@@ -3252,6 +4503,8 @@ pub struct VarsMatrix {
 #[derive(Debug)]
 pub struct VarsMfa {
     pub admin_force_mfa: bool,
+    pub provider_recovery: bool,
+    pub provider_recovery_notify: bool,
 }
 
 #[derive(Debug)]
@@ -3291,6 +4544,7 @@ pub struct VarsServer {
     pub port_https: u16,
     pub scheme: Cow<'static, str>,
     pub pub_url: String,
+    pub issuer_hosts: Vec<String>,
     pub http_workers: u16,
     pub proxy_mode: bool,
     pub trusted_proxies: Vec<String>,
@@ -3325,6 +4579,13 @@ pub struct VarsTemplates {
     pub password_reset_nb: VarsTemplate,
     pub password_reset_uk: VarsTemplate,
     pub password_reset_zhhans: VarsTemplate,
+
+    pub welcome_de: VarsTemplate,
+    pub welcome_en: VarsTemplate,
+    pub welcome_ko: VarsTemplate,
+    pub welcome_nb: VarsTemplate,
+    pub welcome_uk: VarsTemplate,
+    pub welcome_zhhans: VarsTemplate,
 }
 
 #[derive(Debug, Clone)]
@@ -3355,6 +4616,7 @@ pub struct VarsToS {
 #[derive(Debug)]
 pub struct VarsUserDelete {
     pub enable_self_delete: bool,
+    pub disabled_retention_days: u32,
 }
 
 #[derive(Debug)]
@@ -3374,9 +4636,71 @@ pub struct VarsUserPictures {
 #[derive(Debug)]
 pub struct VarsUserRegistration {
     pub enable: bool,
-    pub domain_restriction: Option<String>,
+    pub domain_allowlist: Vec<String>,
     pub domain_blacklist: Vec<String>,
+    pub domain_restriction_admin_bypass: bool,
+    pub rate_limit_max: u16,
+    pub rate_limit_window: u32,
+    pub rate_limit_min_interval: u16,
     pub allow_open_redirect: bool,
+    pub require_approval: bool,
+    pub require_approval_federated: bool,
+}
+
+impl VarsUserRegistration {
+    /// Validates the domain of the given E-Mail against the `domain_allowlist` and
+    /// `domain_blacklist`. Matching is case-insensitive.
+    ///
+    /// Returns an `EmailDomainNotAllowed` error, which the UI can match on.
+    pub fn validate_email_domain(&self, email: &str) -> Result<(), ErrorResponse> {
+        let domain = email
+            .rsplit_once('@')
+            .map(|(_, d)| d)
+            .unwrap_or_default()
+            .to_lowercase();
+
+        let is_allowed = (self.domain_allowlist.is_empty()
+            || self
+                .domain_allowlist
+                .iter()
+                .any(|p| email_domain_matches(p, &domain)))
+            && !self
+                .domain_blacklist
+                .iter()
+                .any(|p| email_domain_blocked(p, &domain));
+
+        if is_allowed {
+            Ok(())
+        } else {
+            Err(ErrorResponse::new(
+                ErrorResponseType::EmailDomainNotAllowed,
+                "E-Mail domain not allowed",
+            ))
+        }
+    }
+
+    /// Older versions simply checked with `ends_with()`, which made values like `@evil.net`
+    /// valid. These are migrated to the new format with a warning instead of a panic.
+    fn migrate_domain_pattern(raw: &str, key: &str) -> String {
+        let pattern = raw.trim().to_lowercase();
+        let migrated = pattern.trim_start_matches(['@', '.']);
+        if migrated != pattern {
+            warn!(
+                "Legacy value '{raw}' in `user_registration.{key}` - it has been migrated to \
+                '{migrated}', please update your config"
+            );
+        }
+        migrated.to_string()
+    }
+
+    /// Returns the allowed domains in a human-readable format for the registration page.
+    pub fn allowed_domains_display(&self) -> String {
+        self.domain_allowlist
+            .iter()
+            .map(|d| format!("@{d}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -3430,6 +4754,7 @@ impl From<&str> for UserValueConfigValue {
 pub struct VarsWebauthn {
     pub rp_id: String,
     pub rp_origin: String,
+    pub additional_origins: Vec<String>,
     pub rp_name: Cow<'static, str>,
     pub req_exp: u16,
     pub data_exp: u16,
@@ -3577,3 +4902,317 @@ pub fn err_t(key: &str, parent: &str, typ: &str) -> String {
     let sep = if parent.is_empty() { "" } else { "." };
     format!("Expected type `{typ}` for {parent}{sep}{key}")
 }
+
+/// Builds the `Webauthn` instance, which accepts ceremonies from the `rp_origin` and all
+/// `additional_origins`. Any other origin will be rejected.
+fn build_webauthn(vars: &VarsWebauthn) -> Webauthn {
+    let rp_origin = webauthn_rs::prelude::Url::parse(&vars.rp_origin)
+        .expect("Cannot parse `webauthn.rp_origin` to URL");
+    let mut builder = webauthn_rs::WebauthnBuilder::new(&vars.rp_id, &rp_origin)
+        .expect("Invalid `webauthn.rp_id`")
+        // Set a "nice" relying party name. Has no security properties - may be changed in the future.
+        .rp_name(&vars.rp_name);
+    for origin in &vars.additional_origins {
+        let url = webauthn_rs::prelude::Url::parse(origin)
+            .expect("Cannot parse `webauthn.additional_origins` to URL");
+        builder = builder.append_allowed_origin(&url);
+    }
+    builder.build().expect("Invalid `webauthn.rp_name`")
+}
+
+/// Checks if the `rp_id` is the effective domain of the origin or a registrable suffix of it.
+fn rp_id_matches_origin(rp_id: &str, origin: &webauthn_rs::prelude::Url) -> bool {
+    if let Some(host) = origin.domain() {
+        !rp_id.is_empty()
+            && !is_public_suffix(rp_id)
+            && (host == rp_id
+                || host
+                    .strip_suffix(rp_id)
+                    .is_some_and(|sub| sub.ends_with('.')))
+    } else {
+        // IP addresses must always match exactly
+        origin.host_str() == Some(rp_id)
+    }
+}
+
+/// A public suffix like `com` or `co.uk` can never be used as an `rp_id`, because it would
+/// allow credentials to be shared across unrelated sites. Unknown TLDs like `localhost` are fine.
+fn is_public_suffix(rp_id: &str) -> bool {
+    let rp_id = rp_id.trim_end_matches('.').to_ascii_lowercase();
+    psl::suffix(rp_id.as_bytes()).is_some_and(|s| s.is_known() && s.as_bytes() == rp_id.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issuer_for_host() {
+        let hosts = vec![
+            "auth.example.org".to_string(),
+            "iam.example.net:8443".to_string(),
+        ];
+        let issuers = build_issuers("https", "iam.example.com", &hosts);
+        assert_eq!(
+            issuers,
+            vec![
+                "https://iam.example.com/auth/v1/",
+                "https://auth.example.org/auth/v1/",
+                "https://iam.example.net:8443/auth/v1/",
+            ]
+        );
+
+        assert_eq!(
+            find_issuer(&issuers, &hosts, Some("auth.example.org")),
+            "https://auth.example.org/auth/v1/"
+        );
+        assert_eq!(
+            find_issuer(&issuers, &hosts, Some("Auth.Example.ORG")),
+            "https://auth.example.org/auth/v1/"
+        );
+        assert_eq!(
+            find_issuer(&issuers, &hosts, Some("iam.example.net:8443")),
+            "https://iam.example.net:8443/auth/v1/"
+        );
+
+        // anything not explicitly allowed must resolve to the default issuer
+        for host in [
+            None,
+            Some("iam.example.com"),
+            Some("evil.example.org"),
+            Some("auth.example.org.evil.com"),
+            Some("iam.example.net"),
+            Some(""),
+        ] {
+            assert_eq!(
+                find_issuer(&issuers, &hosts, host),
+                "https://iam.example.com/auth/v1/"
+            );
+        }
+
+        // without any `issuer_hosts`, the default issuer is the only one
+        let issuers = build_issuers("https", "iam.example.com", &[]);
+        assert_eq!(
+            find_issuer(&issuers, &[], Some("auth.example.org")),
+            "https://iam.example.com/auth/v1/"
+        );
+    }
+
+    #[test]
+    fn test_parse_templates() {
+        let mut vars = Vars::default();
+        let mut table: toml::Table = toml::from_str(
+            r#"
+[[templates]]
+lang = 'de'
+typ = 'welcome'
+subject = 'Hallo'
+text = 'Willkommen im Team.'
+button = 'Los geht es'
+
+[[templates]]
+lang = 'nb'
+typ = 'password_reset'
+header = 'Tilbakestill'
+"#,
+        )
+        .unwrap();
+        vars.parse_templates(&mut table);
+
+        assert_eq!(vars.templates.welcome_de.subject, "Hallo");
+        assert_eq!(
+            vars.templates.welcome_de.text.as_deref(),
+            Some("Willkommen im Team.")
+        );
+        assert_eq!(vars.templates.welcome_de.button, "Los geht es");
+        // values that are not given keep their defaults
+        assert_eq!(vars.templates.welcome_de.header, "Willkommen bei");
+        assert_eq!(vars.templates.welcome_en.subject, "Welcome");
+        assert_eq!(vars.templates.password_new_de.subject, "Neues Passwort");
+
+        assert_eq!(vars.templates.password_reset_nb.header, "Tilbakestill");
+        assert_eq!(vars.templates.password_new_nb.header, "Ny passord for");
+    }
+
+    #[test]
+    fn test_token_preview_startup_guard() {
+        let mut dev = VarsDev {
+            dev_mode: false,
+            dpop_http: false,
+            insecure_cookie: false,
+            provider_callback_url: None,
+            token_preview: false,
+            danger_unsafe_test_mode: false,
+        };
+        dev.validate_test_mode();
+
+        dev.danger_unsafe_test_mode = true;
+        dev.validate_test_mode();
+
+        dev.token_preview = true;
+        dev.validate_test_mode();
+
+        dev.danger_unsafe_test_mode = false;
+        let res = std::panic::catch_unwind(|| dev.validate_test_mode());
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_legacy_domain_patterns() {
+        let migrate = |v| VarsUserRegistration::migrate_domain_pattern(v, "domain_blacklist");
+        assert_eq!(migrate("@Evil.net"), "evil.net");
+        assert_eq!(migrate(" .evil.net"), "evil.net");
+        assert_eq!(migrate("*.evil.net"), "*.evil.net");
+        assert!(RE_EMAIL_DOMAIN_PATTERN.is_match(&migrate("@evil.net")));
+
+        let reg = VarsUserRegistration {
+            enable: true,
+            domain_allowlist: Vec::default(),
+            domain_blacklist: vec![migrate("@evil.net")],
+            domain_restriction_admin_bypass: true,
+            allow_open_redirect: false,
+            rate_limit_max: 0,
+            rate_limit_window: 0,
+            rate_limit_min_interval: 0,
+            require_approval: false,
+            require_approval_federated: false,
+        };
+        assert!(reg.validate_email_domain("a@evil.net").is_err());
+        let err = reg.validate_email_domain("a@sub.evil.net").unwrap_err();
+        assert_eq!(err.error, ErrorResponseType::EmailDomainNotAllowed);
+        assert!(reg.validate_email_domain("a@notevil.net").is_ok());
+    }
+
+    #[test]
+    fn test_rp_id_matches_origin() {
+        let matches = |rp_id: &str, origin: &str| {
+            rp_id_matches_origin(rp_id, &webauthn_rs::prelude::Url::parse(origin).unwrap())
+        };
+
+        assert!(matches("example.com", "https://example.com:443"));
+        assert!(matches("example.com", "https://auth.example.com:443"));
+        assert!(matches("example.com", "https://login.example.com:8443"));
+        assert!(matches("localhost", "http://localhost:8080"));
+        assert!(matches("127.0.0.1", "http://127.0.0.1:8080"));
+
+        assert!(!matches("example.com", "https://evilexample.com:443"));
+        assert!(!matches("example.com", "https://example.com.evil.net:443"));
+        assert!(!matches(
+            "auth.example.com",
+            "https://login.example.com:443"
+        ));
+        assert!(!matches("", "https://example.com:443"));
+        assert!(!matches("0.0.1", "http://127.0.0.1:8080"));
+
+        // public suffixes
+        assert!(!matches("com", "https://example.com:443"));
+        assert!(!matches("COM", "https://example.com:443"));
+        assert!(!matches("co.uk", "https://example.co.uk:443"));
+        assert!(!matches("github.io", "https://example.github.io:443"));
+        assert!(matches("example.co.uk", "https://auth.example.co.uk:443"));
+    }
+
+    #[test]
+    fn test_webauthn_multiple_origins() {
+        use openssl::bn::{BigNum, BigNumContext};
+        use openssl::ec::{EcGroup, EcKey};
+        use openssl::ecdsa::EcdsaSig;
+        use openssl::nid::Nid;
+        use openssl::sha::sha256;
+        use rauthy_common::utils::base64_url_no_pad_encode;
+        use webauthn_rs::prelude::*;
+        use webauthn_rs_proto::{
+            AuthenticatorAssertionResponseRaw, RegisteredExtensions, UserVerificationPolicy,
+        };
+
+        let vars = VarsWebauthn {
+            rp_id: "example.com".to_string(),
+            rp_origin: "https://auth.example.com:443".to_string(),
+            additional_origins: vec!["https://login.example.com:443".to_string()],
+            rp_name: "Rauthy IAM".into(),
+            req_exp: 60,
+            data_exp: 90,
+            renew_exp: 2160,
+            force_uv: false,
+            no_password_exp: true,
+        };
+        let webauthn = build_webauthn(&vars);
+
+        // a software authenticator with a single registered P-256 key
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let key = EcKey::generate(&group).unwrap();
+        let mut x = BigNum::new().unwrap();
+        let mut y = BigNum::new().unwrap();
+        key.public_key()
+            .affine_coordinates(&group, &mut x, &mut y, &mut BigNumContext::new().unwrap())
+            .unwrap();
+        let cred_id = vec![7u8; 16];
+        let passkey = Passkey::from(Credential {
+            cred_id: cred_id.clone().into(),
+            cred: COSEKey {
+                type_: COSEAlgorithm::ES256,
+                key: COSEKeyType::EC_EC2(COSEEC2Key {
+                    curve: ECDSACurve::SECP256R1,
+                    x: x.to_vec_padded(32).unwrap().into(),
+                    y: y.to_vec_padded(32).unwrap().into(),
+                }),
+            },
+            counter: 0,
+            transports: None,
+            user_verified: true,
+            backup_eligible: false,
+            backup_state: false,
+            registration_policy: UserVerificationPolicy::Required,
+            extensions: RegisteredExtensions::none(),
+            attestation: ParsedAttestation::default(),
+            attestation_format: AttestationFormat::None,
+        });
+
+        let assert_from = |origin: &str, counter: u32| {
+            let (rcr, auth_state) = webauthn
+                .start_passkey_authentication(std::slice::from_ref(&passkey))
+                .unwrap();
+
+            let client_data_json = serde_json::json!({
+                "type": "webauthn.get",
+                "challenge": base64_url_no_pad_encode(rcr.public_key.challenge.as_ref()),
+                "origin": origin,
+            })
+            .to_string()
+            .into_bytes();
+
+            // rpIdHash | flags (UP + UV) | signCount
+            let mut authenticator_data = sha256(vars.rp_id.as_bytes()).to_vec();
+            authenticator_data.push(0b0000_0101);
+            authenticator_data.extend_from_slice(&counter.to_be_bytes());
+
+            let mut signed = authenticator_data.clone();
+            signed.extend_from_slice(&sha256(&client_data_json));
+            let signature = EcdsaSig::sign(&sha256(&signed), &key)
+                .unwrap()
+                .to_der()
+                .unwrap();
+
+            let cred = PublicKeyCredential {
+                id: base64_url_no_pad_encode(&cred_id),
+                raw_id: cred_id.clone().into(),
+                response: AuthenticatorAssertionResponseRaw {
+                    authenticator_data: authenticator_data.into(),
+                    client_data_json: client_data_json.into(),
+                    signature: signature.into(),
+                    user_handle: None,
+                },
+                extensions: Default::default(),
+                type_: "public-key".to_string(),
+            };
+            webauthn.finish_passkey_authentication(&cred, &auth_state)
+        };
+
+        assert!(assert_from("https://auth.example.com", 1).is_ok());
+        assert!(assert_from("https://login.example.com", 2).is_ok());
+
+        assert!(assert_from("https://evil.example.com", 3).is_err());
+        assert!(assert_from("https://login.example.com:8443", 4).is_err());
+        assert!(assert_from("https://example.com.evil.net", 5).is_err());
+    }
+}
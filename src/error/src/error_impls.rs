@@ -53,9 +53,12 @@ impl ErrorResponse {
 impl ResponseError for ErrorResponse {
     fn status_code(&self) -> StatusCode {
         match self.error {
-            ErrorResponseType::BadRequest | ErrorResponseType::UseDpopNonce(_) => {
-                StatusCode::BAD_REQUEST
-            }
+            ErrorResponseType::BadRequest
+            | ErrorResponseType::EmailDomainNotAllowed
+            | ErrorResponseType::InvalidGrant
+            | ErrorResponseType::InvalidScope
+            | ErrorResponseType::UnauthorizedClient
+            | ErrorResponseType::UseDpopNonce(_) => StatusCode::BAD_REQUEST,
             ErrorResponseType::Blocked
             | ErrorResponseType::Forbidden
             | ErrorResponseType::PasswordRefresh => StatusCode::FORBIDDEN,
@@ -63,6 +66,7 @@ impl ResponseError for ErrorResponse {
                 StatusCode::NOT_ACCEPTABLE
             }
             ErrorResponseType::NotFound => StatusCode::NOT_FOUND,
+            ErrorResponseType::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
             ErrorResponseType::Disabled
             | ErrorResponseType::CSRFTokenError
             | ErrorResponseType::DPoP(_)
@@ -87,8 +91,11 @@ impl ResponseError for ErrorResponse {
                 .body(self.message.clone()),
 
             ErrorResponseType::TooManyRequests(not_before_timestamp) => {
+                let retry_after =
+                    (*not_before_timestamp - OffsetDateTime::now_utc().unix_timestamp()).max(0);
                 HttpResponseBuilder::new(status)
                     .insert_header((HEADER_RETRY_NOT_BEFORE, *not_before_timestamp))
+                    .insert_header((header::RETRY_AFTER, retry_after))
                     .insert_header(HEADER_HTML)
                     .body(self.message.clone())
             }
@@ -400,6 +407,9 @@ impl From<reqwest::header::ToStrError> for ErrorResponse {
 impl From<reqwest::Error> for ErrorResponse {
     fn from(value: reqwest::Error) -> Self {
         debug!("reqwest::Error: {value:?}");
+        if let Some(err) = find_tls_error(&value) {
+            return ErrorResponse::new(ErrorResponseType::Connection, tls_error_msg(err));
+        }
         ErrorResponse::new(
             ErrorResponseType::Connection,
             format!("Cannot send out HTTP request: {value:?}"),
@@ -407,6 +417,45 @@ impl From<reqwest::Error> for ErrorResponse {
     }
 }
 
+/// Finds the `rustls::Error` inside the source chain, if the request failed during the TLS
+/// handshake.
+fn find_tls_error<'a>(err: &'a (dyn std::error::Error + 'static)) -> Option<&'a rustls::Error> {
+    let mut source = Some(err);
+    while let Some(err) = source {
+        if let Some(err) = err.downcast_ref::<rustls::Error>() {
+            return Some(err);
+        }
+        // `io::Error::source()` skips the wrapped error itself
+        source = match err
+            .downcast_ref::<std::io::Error>()
+            .and_then(|err| err.get_ref())
+        {
+            Some(inner) => Some(inner),
+            None => err.source(),
+        };
+    }
+    None
+}
+
+fn tls_error_msg(err: &rustls::Error) -> String {
+    match err {
+        rustls::Error::InvalidCertificate(_) => format!(
+            "Outbound TLS connection refused, untrusted server certificate: {err}. You may need \
+            to add its Root CA to `[http_client].root_ca_bundle`"
+        ),
+        rustls::Error::PeerIncompatible(_)
+        | rustls::Error::AlertReceived(
+            rustls::AlertDescription::HandshakeFailure
+            | rustls::AlertDescription::ProtocolVersion
+            | rustls::AlertDescription::InsufficientSecurity,
+        ) => format!(
+            "Outbound TLS connection refused by the `[http_client]` TLS policy, the server does \
+            not support the `min_tls` version, `tls_cipher_suites` or `tls_kx_groups`: {err}"
+        ),
+        err => format!("Outbound TLS connection failed: {err}"),
+    }
+}
+
 impl From<TurtleError> for ErrorResponse {
     fn from(value: TurtleError) -> Self {
         debug!("TurtleError: {value:?}");
@@ -597,3 +646,23 @@ impl From<ring::error::Unspecified> for ErrorResponse {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_tls_error() {
+        let tls_err = rustls::Error::PeerIncompatible(
+            rustls::PeerIncompatible::ServerDoesNotSupportTls12Or13,
+        );
+        // this is how `hyper` / `reqwest` wrap TLS handshake errors
+        let err = std::io::Error::other(tls_err);
+        let found = find_tls_error(&err).unwrap();
+        assert!(matches!(found, rustls::Error::PeerIncompatible(_)));
+        assert!(tls_error_msg(found).contains("TLS policy"));
+
+        let err = std::io::Error::other("connection reset");
+        assert!(find_tls_error(&err).is_none());
+    }
+}
@@ -16,6 +16,7 @@ pub enum ErrorResponseType {
     Database,
     DatabaseIo,
     Disabled,
+    EmailDomainNotAllowed,
     // These String could be optimized in the future with borrowing
     // -> just not going down that rabbit hole for now
     DPoP(Option<String>),
@@ -23,6 +24,8 @@ pub enum ErrorResponseType {
     UseDpopNonce((Option<String>, String)),
     Forbidden,
     Internal,
+    InvalidGrant,
+    InvalidScope,
     JwtToken,
     JoseError,
     MfaRequired,
@@ -30,12 +33,14 @@ pub enum ErrorResponseType {
     NotFound,
     PasswordExpired,
     PasswordRefresh,
+    PayloadTooLarge,
     Scim,
     SessionExpired,
     SessionTimeout,
     Timeout,
     TooManyRequests(i64),
     Unauthorized,
+    UnauthorizedClient,
     NotAccepted,
     WWWAuthenticate(String),
 }
@@ -197,6 +197,8 @@ pub struct JwtRefreshClaims<'a> {
     pub uid: &'a str,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub auth_time: Option<i64>,
+    #[serde(borrow, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<&'a str>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
@@ -113,7 +113,7 @@ impl JwtToken {
         buf.clear();
         base64_url_no_pad_decode_buf(claims, buf)?;
         serde_json::from_slice::<ValidationClaims>(buf)?.validate(
-            &RauthyConfig::get().issuer,
+            &RauthyConfig::get().issuers,
             expected_type,
             allowed_clock_skew_seconds,
         )?;
@@ -138,7 +138,7 @@ impl ValidationClaims<'_> {
     #[inline]
     fn validate(
         &self,
-        issuer: &str,
+        issuers: &[String],
         expected_type: Option<JwtTokenType>,
         allowed_clock_skew_seconds: u16,
     ) -> Result<(), ErrorResponse> {
@@ -171,7 +171,7 @@ impl ValidationClaims<'_> {
                 "Invalid `typ`",
             ));
         }
-        if self.iss != issuer {
+        if !issuers.iter().any(|iss| iss == self.iss) {
             return Err(ErrorResponse::new(
                 ErrorResponseType::JwtToken,
                 "Invalid `iss`",
@@ -193,6 +193,10 @@ mod tests {
     fn test_validation_claims() -> Result<(), ErrorResponse> {
         let now = Utc::now().timestamp();
         let iss = "http://localhost:8080/auth/v1";
+        let issuers = [
+            iss.to_string(),
+            "http://auth.localhost:8080/auth/v1".to_string(),
+        ];
 
         ValidationClaims {
             iat: now,
@@ -201,7 +205,7 @@ mod tests {
             iss,
             typ: JwtTokenType::Bearer,
         }
-        .validate(iss, Some(JwtTokenType::Bearer), 0)?;
+        .validate(&issuers, Some(JwtTokenType::Bearer), 0)?;
 
         ValidationClaims {
             iat: now + 2,
@@ -210,7 +214,7 @@ mod tests {
             iss,
             typ: JwtTokenType::Bearer,
         }
-        .validate(iss, Some(JwtTokenType::Bearer), 2)?;
+        .validate(&issuers, Some(JwtTokenType::Bearer), 2)?;
 
         let res = ValidationClaims {
             iat: now,
@@ -219,7 +223,7 @@ mod tests {
             iss,
             typ: JwtTokenType::Bearer,
         }
-        .validate(iss, Some(JwtTokenType::Bearer), 0);
+        .validate(&issuers, Some(JwtTokenType::Bearer), 0);
         assert_eq!(
             res,
             Err(ErrorResponse::new(
@@ -235,7 +239,7 @@ mod tests {
             iss,
             typ: JwtTokenType::Bearer,
         }
-        .validate(iss, Some(JwtTokenType::Bearer), 0);
+        .validate(&issuers, Some(JwtTokenType::Bearer), 0);
         assert_eq!(
             res,
             Err(ErrorResponse::new(
@@ -251,7 +255,7 @@ mod tests {
             iss,
             typ: JwtTokenType::Bearer,
         }
-        .validate(iss, Some(JwtTokenType::Bearer), 0);
+        .validate(&issuers, Some(JwtTokenType::Bearer), 0);
         assert_eq!(
             res,
             Err(ErrorResponse::new(
@@ -267,7 +271,7 @@ mod tests {
             iss,
             typ: JwtTokenType::Bearer,
         }
-        .validate(iss, Some(JwtTokenType::Bearer), 5);
+        .validate(&issuers, Some(JwtTokenType::Bearer), 5);
         assert_eq!(res, Ok(()));
 
         let res = ValidationClaims {
@@ -277,7 +281,7 @@ mod tests {
             iss,
             typ: JwtTokenType::Bearer,
         }
-        .validate(iss, Some(JwtTokenType::Id), 0);
+        .validate(&issuers, Some(JwtTokenType::Id), 0);
         assert_eq!(
             res,
             Err(ErrorResponse::new(
@@ -293,7 +297,7 @@ mod tests {
             iss: "http://localhost:9090/something/else",
             typ: JwtTokenType::Bearer,
         }
-        .validate(iss, Some(JwtTokenType::Bearer), 0);
+        .validate(&issuers, Some(JwtTokenType::Bearer), 0);
         assert_eq!(
             res,
             Err(ErrorResponse::new(
@@ -302,6 +306,16 @@ mod tests {
             ))
         );
 
+        // any of the additional `issuer_hosts` issuers must be accepted as well
+        ValidationClaims {
+            iat: now,
+            exp: now + 10,
+            nbf: now,
+            iss: "http://auth.localhost:8080/auth/v1",
+            typ: JwtTokenType::Bearer,
+        }
+        .validate(&issuers, Some(JwtTokenType::Bearer), 0)?;
+
         Ok(())
     }
 }
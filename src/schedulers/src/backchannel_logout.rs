@@ -101,6 +101,7 @@ async fn execute_logout_retries(
             client.backchannel_logout_uri.unwrap_or_default(),
             sub,
             sid,
+            failure.issuer.clone(),
             kp.unwrap(),
             &mut tasks,
         )
@@ -17,6 +17,7 @@ mod magic_links;
 mod passwords;
 mod scim_tasks;
 mod sessions;
+mod sweeper;
 mod tokens;
 mod user_login_states;
 mod users;
@@ -37,11 +38,13 @@ pub fn spawn() {
     tokio::spawn(tokens::refresh_tokens_cleanup());
     tokio::spawn(user_login_states::user_login_states_cleanup());
     tokio::spawn(sessions::sessions_cleanup());
+    tokio::spawn(sweeper::expired_records_sweeper());
     tokio::spawn(jwks::jwks_auto_rotate());
     tokio::spawn(jwks::jwks_cleanup());
     tokio::spawn(passwords::password_expiry_checker());
     tokio::spawn(issued_tokens::cleanup_issued_tokens());
     tokio::spawn(users::user_expiry_checker());
+    tokio::spawn(users::user_disabled_cleanup());
     tokio::spawn(app_version::app_version_check());
 }
 
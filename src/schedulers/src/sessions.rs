@@ -2,6 +2,8 @@ use chrono::Utc;
 use hiqlite_macros::params;
 use rauthy_common::is_hiqlite;
 use rauthy_data::database::DB;
+use rauthy_data::entity::issued_tokens::IssuedToken;
+use rauthy_data::entity::refresh_tokens::RefreshToken;
 use rauthy_data::rauthy_config::RauthyConfig;
 use std::ops::Sub;
 use std::time::Duration;
@@ -49,8 +51,27 @@ pub async fn sessions_cleanup() {
             error!(?err, "Session Cleanup")
         }
 
+        if RauthyConfig::get().vars.access.token_bind_session {
+            revoke_bound_tokens().await;
+        }
+
         // For some reason, the interval could `.tick()` multiple times,
         // if it finishes too quickly.
         time::sleep(Duration::from_secs(3)).await;
     }
 }
+
+/// Cascades the session cleanup to all tokens which are bound to a session via
+/// `token_bind_session`.
+async fn revoke_bound_tokens() {
+    match RefreshToken::delete_for_inactive_sessions().await {
+        Ok(count) if count > 0 => debug!("Deleted {count} refresh tokens for inactive sessions"),
+        Ok(_) => {}
+        Err(err) => error!(?err, "Refresh tokens cleanup for inactive sessions"),
+    }
+    match IssuedToken::revoke_for_inactive_sessions().await {
+        Ok(count) if count > 0 => debug!("Revoked {count} tokens for inactive sessions"),
+        Ok(_) => {}
+        Err(err) => error!(?err, "Tokens revocation for inactive sessions"),
+    }
+}
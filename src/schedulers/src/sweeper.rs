@@ -0,0 +1,162 @@
+use chrono::Utc;
+use hiqlite_macros::params;
+use rauthy_common::is_hiqlite;
+use rauthy_data::database::DB;
+use rauthy_data::events::event::Event;
+use rauthy_data::rauthy_config::RauthyConfig;
+use rauthy_error::ErrorResponse;
+use std::time::Duration;
+use tokio::time;
+use tracing::{debug, error, info};
+
+/// `(table, primary key, condition)` for all ephemeral records the sweeper takes care of.
+/// Each condition receives the current timestamp as `$1`.
+///
+/// Magic links are left to the `magic_link_cleanup`, which needs the expired, unused ones to find
+/// and delete users that never set their initial password.
+///
+/// Authorization and device codes live inside the cache with a TTL and never need a sweep.
+static SWEEP_TABLES: [(&str, &str, &str); 1] = [("refresh_tokens_devices", "id", "exp < $1")];
+
+/// Deletes used or expired ephemeral records, which are not cleaned up by any other scheduler.
+/// All conditions are idempotent, which makes it safe to run concurrently with normal traffic.
+pub async fn expired_records_sweeper() {
+    let mins = RauthyConfig::get().vars.database.sched_sweeper_mins as u64;
+    if mins == 0 {
+        info!("Sweeper for expired records disabled");
+        return;
+    }
+    let mut interval = tokio::time::interval(Duration::from_secs(mins * 60));
+
+    loop {
+        interval.tick().await;
+
+        if !DB::hql().is_leader_cache().await {
+            debug!(
+                "Running HA mode without being the leader - skipping expired_records_sweeper scheduler"
+            );
+            continue;
+        }
+
+        debug!("Running expired_records_sweeper scheduler");
+
+        if let Err(err) = sweep().await {
+            error!(?err, "expired_records_sweeper");
+        }
+
+        // For some reason, the interval could `.tick()` multiple times,
+        // if it finished too quickly.
+        time::sleep(Duration::from_secs(3)).await;
+    }
+}
+
+async fn sweep() -> Result<(), ErrorResponse> {
+    let batch_size = RauthyConfig::get()
+        .vars
+        .database
+        .sched_sweeper_batch_size
+        .max(1) as i64;
+    let now = Utc::now().timestamp();
+
+    let mut counts = Vec::with_capacity(SWEEP_TABLES.len());
+    for (table, pk, condition) in SWEEP_TABLES {
+        let count = sweep_table(table, pk, condition, now, batch_size).await?;
+        debug!("Swept {count} rows from {table}");
+        counts.push((table, count));
+    }
+
+    if counts.iter().any(|(_, count)| *count > 0) {
+        Event::records_swept(&counts).send().await?;
+    }
+
+    Ok(())
+}
+
+/// Deletes in batches of `batch_size` until nothing is left, so a single statement never holds
+/// a lock for too long.
+async fn sweep_table(
+    table: &str,
+    pk: &str,
+    condition: &str,
+    now: i64,
+    batch_size: i64,
+) -> Result<u64, ErrorResponse> {
+    let sql = format!(
+        "DELETE FROM {table} WHERE {pk} IN (SELECT {pk} FROM {table} WHERE {condition} LIMIT $2)"
+    );
+
+    delete_batched(batch_size, || async {
+        if is_hiqlite() {
+            Ok(DB::hql()
+                .execute(sql.clone(), params!(now, batch_size))
+                .await? as u64)
+        } else {
+            Ok(DB::pg_execute(&sql, &[&now, &batch_size]).await? as u64)
+        }
+    })
+    .await
+}
+
+/// Runs `delete_batch` until it deletes less than `batch_size` rows and returns the total count.
+async fn delete_batched<F, Fut>(batch_size: i64, mut delete_batch: F) -> Result<u64, ErrorResponse>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<u64, ErrorResponse>>,
+{
+    let mut count = 0;
+    loop {
+        let rows_affected = delete_batch().await?;
+        count += rows_affected;
+
+        if (rows_affected as i64) < batch_size {
+            return Ok(count);
+        }
+        // give normal traffic the chance to acquire the lock in between
+        time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rauthy_error::ErrorResponseType;
+
+    #[tokio::test]
+    async fn test_delete_batched() {
+        // (rows in the table, expected number of batches)
+        for (rows, batches) in [(0, 1), (3, 1), (5, 2), (12, 3), (15, 4)] {
+            let mut left = rows;
+            let mut calls = 0;
+            let count = delete_batched(5, || {
+                let deleted = left.min(5);
+                left -= deleted;
+                calls += 1;
+                async move { Ok(deleted) }
+            })
+            .await
+            .unwrap();
+
+            assert_eq!(count, rows);
+            assert_eq!(left, 0);
+            assert_eq!(calls, batches, "rows: {rows}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delete_batched_err() {
+        let mut calls = 0;
+        let res = delete_batched(5, || {
+            calls += 1;
+            let res = if calls < 2 {
+                Ok(5)
+            } else {
+                Err(ErrorResponse::new(ErrorResponseType::Database, "locked"))
+            };
+            async move { res }
+        })
+        .await;
+
+        assert!(res.is_err());
+        assert_eq!(calls, 2);
+    }
+}
@@ -2,6 +2,7 @@ use chrono::Utc;
 use hiqlite_macros::params;
 use rauthy_common::is_hiqlite;
 use rauthy_data::database::DB;
+use rauthy_data::rauthy_config::RauthyConfig;
 use std::time::Duration;
 use tokio::time;
 use tracing::{debug, error};
@@ -21,14 +22,21 @@ pub async fn refresh_tokens_cleanup() {
 
         debug!("Running refresh_tokens_cleanup scheduler");
 
-        let now = Utc::now().timestamp();
+        // Rotated tokens are kept around for a while, so a replay can still be detected.
+        let lifetimes = &RauthyConfig::get().vars.lifetimes;
+        let retention = if lifetimes.refresh_token_reuse_detection {
+            lifetimes.refresh_token_lifetime as i64 * 3600
+        } else {
+            0
+        };
+        let threshold = Utc::now().timestamp() - retention;
         let sql = "DELETE FROM refresh_tokens WHERE exp < $1";
 
         if is_hiqlite() {
-            if let Err(err) = DB::hql().execute(sql, params!(now)).await {
+            if let Err(err) = DB::hql().execute(sql, params!(threshold)).await {
                 error!(?err, "Refresh Token Cleanup")
             }
-        } else if let Err(err) = DB::pg_execute(sql, &[&now]).await {
+        } else if let Err(err) = DB::pg_execute(sql, &[&threshold]).await {
             error!(?err, "Refresh Token Cleanup")
         }
 
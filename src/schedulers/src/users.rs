@@ -96,3 +96,67 @@ async fn execute(cleanup_after_secs: Option<u64>) -> Result<(), ErrorResponse> {
 
     Ok(())
 }
+
+/// Hard-deletes soft-deleted users after the configured `disabled_retention_days`.
+/// Runs every hour.
+pub async fn user_disabled_cleanup() {
+    let retention_days = RauthyConfig::get().vars.user_delete.disabled_retention_days;
+    if retention_days == 0 {
+        info!("Auto cleanup for disabled users disabled");
+        return;
+    }
+
+    let mut interval = tokio::time::interval(Duration::from_secs(3600));
+
+    loop {
+        interval.tick().await;
+
+        if !DB::hql().is_leader_cache().await {
+            debug!(
+                "Running HA mode without being the leader - skipping user_disabled_cleanup scheduler"
+            );
+            continue;
+        }
+
+        debug!("Running user_disabled_cleanup scheduler");
+        let threshold = Utc::now().timestamp() - retention_days as i64 * 86400;
+        if let Err(err) = cleanup_disabled(threshold).await {
+            error!("Error during user_disabled_cleanup: {}", err.message);
+        }
+
+        // For some reason, the interval could `.tick()` multiple times,
+        // if it finished too quickly.
+        time::sleep(Duration::from_secs(3)).await;
+    }
+}
+
+async fn cleanup_disabled(threshold: i64) -> Result<(), ErrorResponse> {
+    let users = User::find_disabled_before(threshold).await?;
+    if users.is_empty() {
+        return Ok(());
+    }
+
+    let clients_scim = ClientScim::find_all().await?;
+    for user in users {
+        info!(
+            user.id,
+            "Auto cleanup for user after the retention period for disabled users"
+        );
+        // sessions, federation links and other tables have a cascading FK to the users table
+        if let Err(err) = user.delete().await {
+            error!(user.id, ?err, "auto cleanup - deleting disabled user");
+            continue;
+        }
+
+        for client_scim in &clients_scim {
+            if let Err(err) = client_scim.delete_user(&user).await {
+                error!(
+                    "Error during SCIM Client user delete for {}: {:?}",
+                    user.email, err
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,7 +1,21 @@
-use rauthy_api_types::clients::{ClientSecretResponse, UpdateClientRequest};
+use crate::token_set::{TokenIssuer, TokenScopes, TokenSet};
+use chrono::Utc;
+use cryptr::EncKeys;
+use rauthy_api_types::clients::{
+    ClientSecretResponse, TokenPreviewRequest, TokenPreviewResponse, UpdateClientRequest,
+};
+use rauthy_api_types::generic::TokensRevokedResponse;
+use rauthy_common::utils::base64_url_no_pad_decode;
 use rauthy_data::entity::clients::Client;
 use rauthy_data::entity::clients_scim::ClientScim;
+use rauthy_data::entity::issued_tokens::IssuedToken;
+use rauthy_data::entity::refresh_tokens::RefreshToken;
+use rauthy_data::entity::refresh_tokens_devices::RefreshTokenDevice;
+use rauthy_data::entity::token_nbf::ClientTokenNbf;
+use rauthy_data::entity::users::User;
+use rauthy_data::events::event::Event;
 use rauthy_error::{ErrorResponse, ErrorResponseType};
+use tracing::warn;
 
 /// Returns `true` inside `Option<(ClientScim, bool)>` if `ClientScim`
 /// has been updated and therefore needs a full sync.
@@ -23,12 +37,15 @@ pub async fn update_client(
         if !client.confidential {
             let (_, enc) = Client::generate_new_secret()?;
             client.secret = Some(enc);
+            client.secret_hashed = false;
         }
     } else {
         client.secret = None;
+        client.secret_hashed = false;
     }
     client.confidential = client_req.confidential;
 
+    let redirect_uris_before = client.redirect_uris;
     client.redirect_uris = client_req.redirect_uris.join(",");
     client.post_logout_redirect_uris = client_req.post_logout_redirect_uris.map(|u| u.join(","));
     client.allowed_origins = client_req.allowed_origins.map(|o| o.join(","));
@@ -56,7 +73,39 @@ pub async fn update_client(
     client.client_uri = client_req.client_uri;
     client.backchannel_logout_uri = client_req.backchannel_logout_uri;
     client.restrict_group_prefix = client_req.restrict_group_prefix;
+    client.introspect = client_req.introspect;
+    client.claim_mapping = client_req.claim_mapping.filter(|m| !m.is_empty()).map(|m| {
+        let mut mapping = m
+            .into_iter()
+            .map(|(from, to)| format!("{from}={to}"))
+            .collect::<Vec<_>>();
+        // sort for a stable value in the DB
+        mapping.sort();
+        mapping.join(",")
+    });
+    client.claim_namespace = client_req.claim_namespace.filter(|ns| !ns.is_empty());
+    client.refresh_token_max_lifetime = client_req.refresh_token_max_lifetime;
+    client.require_nonce = client_req.require_nonce;
+    client.first_party = client_req.first_party;
+    if let Some(subject_type) = client_req.subject_type {
+        client.subject_type = subject_type;
+    }
+    let sector_identifier_uri_before = client.sector_identifier_uri;
+    client.sector_identifier_uri = client_req
+        .sector_identifier_uri
+        .filter(|uri| !uri.is_empty());
+    if let Some(mode) = client_req.uri_match_mode {
+        client.uri_match_mode = mode;
+    }
 
+    client.validate_flows_combination()?;
+    client.validate_uri_rules()?;
+    // only fetch the `sector_identifier_uri` again if anything it depends on has changed
+    if client.sector_identifier_uri != sector_identifier_uri_before
+        || client.redirect_uris != redirect_uris_before
+    {
+        client.validate_sector_identifier_uri().await?;
+    }
     client.save().await?;
 
     let scim = if let Some(scim_req) = client_req.scim {
@@ -116,7 +165,8 @@ pub async fn update_client(
     Ok((client, scim))
 }
 
-/// Returns the clients secret in cleartext.
+/// Returns the clients secret in cleartext. Hashed secrets cannot be recovered and will always
+/// return `secret: None`.
 pub async fn get_client_secret(id: String) -> Result<ClientSecretResponse, ErrorResponse> {
     let client = Client::find(id).await?;
 
@@ -135,22 +185,114 @@ pub async fn get_client_secret(id: String) -> Result<ClientSecretResponse, Error
     })
 }
 
+/// Generates a new random secret, which is only saved hashed and therefore returned this one
+/// time only.
 pub async fn generate_new_secret(
     id: String,
     cache_current_hours: Option<u8>,
 ) -> Result<ClientSecretResponse, ErrorResponse> {
-    let mut client = Client::find(id).await?;
+    let client = Client::find(id).await?;
 
-    client.cache_current_secret(cache_current_hours).await?;
+    let (secret, hash) = Client::generate_new_secret_hashed()?;
+    let client = replace_secret(client, hash, cache_current_hours).await?;
 
-    let (clear, enc) = Client::generate_new_secret()?;
-    client.confidential = true;
-    client.secret = Some(enc);
-    client.save().await?;
+    Ok(ClientSecretResponse {
+        id: client.id,
+        confidential: true,
+        secret: Some(secret),
+    })
+}
+
+/// Sets the given custom secret, as long as it matches the `access.client_secret_min_*` policy.
+/// It will be saved hashed and is never returned.
+pub async fn set_custom_secret(
+    id: String,
+    cache_current_hours: Option<u8>,
+    secret: String,
+) -> Result<ClientSecretResponse, ErrorResponse> {
+    let client = Client::find(id).await?;
+
+    // validate the custom secret before touching the current one
+    let hash = Client::hash_custom_secret(&secret)?;
+    let client = replace_secret(client, hash, cache_current_hours).await?;
 
     Ok(ClientSecretResponse {
         id: client.id,
         confidential: true,
-        secret: Some(clear),
+        secret: None,
+    })
+}
+
+async fn replace_secret(
+    mut client: Client,
+    hash: Vec<u8>,
+    cache_current_hours: Option<u8>,
+) -> Result<Client, ErrorResponse> {
+    client.cache_current_secret(cache_current_hours).await?;
+
+    client.confidential = true;
+    client.secret = Some(hash);
+    client.secret_kid = Some(EncKeys::get_static().enc_key_active.clone());
+    client.secret_hashed = true;
+    client.save().await?;
+
+    Ok(client)
+}
+
+/// Revokes all access and refresh tokens that have been issued for the given client so far.
+pub async fn revoke_tokens(id: String) -> Result<TokensRevokedResponse, ErrorResponse> {
+    let client = Client::find(id).await?;
+
+    // The `nbf` must be set first. As soon as it exists, all tokens for this client will be
+    // rejected, even if any of the following steps fails.
+    ClientTokenNbf::upsert(client.id.clone(), Utc::now().timestamp()).await?;
+
+    let access_tokens = IssuedToken::revoke_for_client(&client.id).await?;
+    let refresh_tokens = RefreshToken::invalidate_for_client(&client.id).await?
+        + RefreshTokenDevice::invalidate_all_for_client(&client.id).await?;
+
+    warn!(
+        "All tokens for client '{}' have been revoked: {access_tokens} access, \
+        {refresh_tokens} refresh tokens",
+        client.id
+    );
+    Event::tokens_revoked(
+        format!("client {}", client.id),
+        access_tokens + refresh_tokens,
+    )
+    .send()
+    .await?;
+
+    Ok(TokensRevokedResponse {
+        access_tokens,
+        refresh_tokens,
+    })
+}
+
+/// Builds the tokens a user would receive for the given client and returns their decoded claims.
+/// Nothing will be persisted and the signed tokens themselves are never returned.
+pub async fn token_preview(
+    client_id: String,
+    payload: TokenPreviewRequest,
+    issuer: TokenIssuer,
+) -> Result<TokenPreviewResponse, ErrorResponse> {
+    let client = Client::find(client_id).await?;
+    client.validate_enabled()?;
+
+    let user = User::find_by_email(payload.email).await?;
+    user.check_enabled()?;
+    user.check_expired()?;
+    client.validate_user_groups(&user)?;
+
+    let scopes = client.sanitize_login_scopes(&payload.scopes)?.join(" ");
+    let ts = TokenSet::preview_for_user(&user, &client, Some(TokenScopes(scopes)), issuer).await?;
+
+    let decode = |token: &str| -> Result<serde_json::Value, ErrorResponse> {
+        let claims = token.split('.').nth(1).unwrap_or_default();
+        Ok(serde_json::from_slice(&base64_url_no_pad_decode(claims)?)?)
+    };
+    Ok(TokenPreviewResponse {
+        access_token: decode(&ts.access_token)?,
+        id_token: decode(ts.id_token.as_deref().unwrap_or_default())?,
     })
 }
@@ -4,6 +4,7 @@ use rauthy_data::entity::auth_providers::AuthProvider;
 use rauthy_data::entity::clients::Client;
 use rauthy_data::entity::clients_scim::ClientScim;
 use rauthy_data::entity::jwk::JWKS;
+use rauthy_data::entity::pairwise_secret::PairwiseSecret;
 use rauthy_error::ErrorResponse;
 use tracing::{error, info};
 
@@ -121,6 +122,9 @@ pub async fn migrate_encryption_alg(new_kid: &str) -> Result<(), ErrorResponse>
         new_kid
     );
 
+    PairwiseSecret::migrate_enc_key(new_kid).await?;
+    info!("Finished pairwise secret migration to key id: {new_kid}");
+
     info!(
         "Finished secrets migration to key id: {new_kid} after {} ms. Modified {modified} \
         encryption's",
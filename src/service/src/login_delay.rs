@@ -1,18 +1,23 @@
-use actix_web::HttpResponse;
+use actix_web::{HttpRequest, HttpResponse};
 use chrono::Utc;
-use rauthy_common::constants::IDX_LOGIN_TIME;
+use rauthy_common::constants::{IDX_LOCKOUT_NOTIFY, IDX_LOGIN_TIME};
 use rauthy_data::database::{Cache, DB};
+use rauthy_data::email::lockout::send_lockout;
 use rauthy_data::entity::failed_login_counter::FailedLoginCounter;
 use rauthy_data::entity::ip_blacklist::IpBlacklist;
-use rauthy_data::events::event::Event;
+use rauthy_data::entity::users::User;
+use rauthy_data::entity::users_values::UserValues;
+use rauthy_data::events::event::{Event, LoginFailureReason};
 use rauthy_data::html::templates::TooManyRequestsHtml;
+use rauthy_data::ipgeo::get_location;
 use rauthy_data::rauthy_config::RauthyConfig;
 use rauthy_error::{ErrorResponse, ErrorResponseType};
 use std::cmp::min;
 use std::net::IpAddr;
 use std::ops::{Add, Sub};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use tracing::{debug, warn};
+use tokio::task;
+use tracing::{debug, error, info, warn};
 
 /**
 Handles the login delay.
@@ -20,12 +25,14 @@ Handles the login delay.
 With every successful login, a new average login time is calculated for how
 long it took for a successful login. If a login failed though, the answer will be delayed by the
 current average for a successful login, to prevent things like username enumeration.
+The `failure_reason` is only used for the event and logging and never returned to the client.
  */
 pub async fn handle_login_delay(
     peer_ip: IpAddr,
     start: Duration,
     res: Result<HttpResponse, ErrorResponse>,
     has_password_been_hashed: bool,
+    failure_reason: LoginFailureReason,
 ) -> Result<HttpResponse, ErrorResponse> {
     let client = DB::hql();
     let success_time: i64 = client
@@ -53,11 +60,18 @@ pub async fn handle_login_delay(
         Err(err) => {
             let failed_logins = FailedLoginCounter::increase(peer_ip.to_string()).await?;
             let failed_logins = min(failed_logins, u32::MAX as i64) as u32;
-            warn!("Failed Logins from {peer_ip}: {failed_logins}");
+            warn!(
+                reason = %failure_reason,
+                "Failed Logins from {peer_ip}: {failed_logins}"
+            );
 
             RauthyConfig::get()
                 .tx_events
-                .send_async(Event::invalid_login(failed_logins, peer_ip.to_string()))
+                .send_async(Event::invalid_login(
+                    failed_logins,
+                    peer_ip.to_string(),
+                    failure_reason,
+                ))
                 .await
                 .unwrap();
 
@@ -71,27 +85,16 @@ pub async fn handle_login_delay(
                 sleep_time_median
             };
 
+            // n-th blacklist -> blocks for 24h with each invalid request
+            if let Some(secs) = FailedLoginCounter::blacklist_secs(failed_logins) {
+                return build_send_event(&peer_ip, secs).await;
+            }
+
             let sleep_time = match failed_logins as u64 {
-                // n-th blacklist -> blocks for 24h with each invalid request
-                t if t >= 25 => {
-                    return build_send_event(&peer_ip, 86400).await;
-                }
                 t if t > 20 => sleep_time_median + t * 20_000,
-                20 => {
-                    return build_send_event(&peer_ip, 3600).await;
-                }
                 t if t > 15 => sleep_time_median + t * 15_000,
-                15 => {
-                    return build_send_event(&peer_ip, 900).await;
-                }
                 t if t > 10 => sleep_time_median + t * 10_000,
-                10 => {
-                    return build_send_event(&peer_ip, 600).await;
-                }
                 t if t > 7 => sleep_time_median + t * 5_000,
-                7 => {
-                    return build_send_event(&peer_ip, 60).await;
-                }
                 t if t >= 5 => sleep_time_median + t * 3_000,
                 t if t >= 3 => sleep_time_median + t * 2_000,
                 _ => sleep_time_median,
@@ -125,3 +128,73 @@ async fn build_send_event(
         html,
     ))
 }
+
+/// Notifies the owner of the account for the given `email`, if the result of
+/// `handle_login_delay()` shows that the IP has just been blocked because of too many failed
+/// logins.
+///
+/// Notifications are rate-limited per user via `email.lockout_notify_interval`, so that
+/// lockouts triggered on purpose cannot be used to spam the victim.
+pub fn notify_lockout(
+    req: &HttpRequest,
+    peer_ip: IpAddr,
+    email: String,
+    res: &Result<HttpResponse, ErrorResponse>,
+) {
+    let Err(err) = res else {
+        return;
+    };
+    if !matches!(err.error, ErrorResponseType::TooManyRequests(_))
+        || !RauthyConfig::get().vars.email.lockout_notify
+    {
+        return;
+    }
+
+    let location = get_location(req, peer_ip).ok().flatten();
+    task::spawn(async move {
+        if let Err(err) = send_lockout_notification(email, peer_ip, location).await {
+            error!(?err, "Error sending lockout notification");
+        }
+    });
+}
+
+async fn send_lockout_notification(
+    email: String,
+    peer_ip: IpAddr,
+    location: Option<String>,
+) -> Result<(), ErrorResponse> {
+    // The email may belong to no user at all, which is totally fine here.
+    let Ok(user) = User::find_by_email(email).await else {
+        return Ok(());
+    };
+    if !user.email_verified {
+        debug!(
+            "Skipping lockout notification for unverified address {}",
+            user.email
+        );
+        return Ok(());
+    }
+
+    let client = DB::hql();
+    let idx = format!("{IDX_LOCKOUT_NOTIFY}{}", user.id);
+    let already_notified: Option<i64> = client.get(Cache::App, &idx).await?;
+    if already_notified.is_some() {
+        debug!(
+            "Lockout notification for {} has been sent already",
+            user.email
+        );
+        return Ok(());
+    }
+
+    let now = Utc::now().timestamp();
+    let interval = RauthyConfig::get().vars.email.lockout_notify_interval as i64;
+    client.put(Cache::App, idx, &now, Some(interval)).await?;
+
+    let values = UserValues::find(&user.id).await?;
+    let tz = values.as_ref().and_then(|uv| uv.tz.as_deref());
+
+    info!("Sending lockout notification to {}", user.email);
+    send_lockout(&user, peer_ip.to_string(), location, now, tz).await;
+
+    Ok(())
+}
@@ -2,20 +2,28 @@ use crate::oidc;
 use crate::oidc::authorize::AuthorizeData;
 use actix_web::HttpRequest;
 use actix_web::cookie::Cookie;
+use actix_web::http::header::HeaderValue;
 use rauthy_api_types::auth_providers::ProviderCallbackRequest;
 use rauthy_common::constants::{COOKIE_UPSTREAM_CALLBACK, PROVIDER_ATPROTO, PROVIDER_LINK_COOKIE};
 use rauthy_common::sha256;
-use rauthy_common::utils::base64_url_encode;
+use rauthy_common::utils::{base64_url_encode, real_ip_from_req};
 use rauthy_data::AuthStep;
 use rauthy_data::api_cookie::ApiCookie;
+use rauthy_data::email::account_recovery::send_account_recovered;
 use rauthy_data::entity::auth_providers::{
     AuthProvider, AuthProviderCallback, AuthProviderLinkCookie, NewFederatedUserCreated,
     ProviderMfaLogin,
 };
 use rauthy_data::entity::clients::Client;
+use rauthy_data::entity::magic_links::{MagicLink, MagicLinkUsage};
+use rauthy_data::entity::refresh_tokens::RefreshToken;
 use rauthy_data::entity::sessions::Session;
+use rauthy_data::entity::users::User;
+use rauthy_data::entity::webauthn::PasskeyEntity;
+use rauthy_data::events::event::{Event, LoginFailureReason};
+use rauthy_data::rauthy_config::RauthyConfig;
 use rauthy_error::{ErrorResponse, ErrorResponseType};
-use tracing::error;
+use tracing::{error, warn};
 
 /// In case of any error, the callback code will be fully deleted for security reasons.
 pub async fn login_finish<'a>(
@@ -75,15 +83,41 @@ pub async fn login_finish<'a>(
         .and_then(|value| AuthProviderLinkCookie::try_from(value.as_str()).ok());
 
     // deserialize payload and validate the information
-    let (user, provider_mfa_login, is_new_user) = if provider.issuer == PROVIDER_ATPROTO {
+    let res = if provider.issuer == PROVIDER_ATPROTO {
         slf.extract_user_at_proto(&provider, &link_cookie, payload)
-            .await?
+            .await
     } else {
-        slf.extract_user(&provider, &link_cookie, payload).await?
+        slf.extract_user(&provider, &link_cookie, payload).await
+    };
+    let (user, provider_mfa_login, is_new_user) = match res {
+        Ok(res) => res,
+        Err(err) => {
+            login_failed_event(req, LoginFailureReason::from_federation_err(&err)).await;
+            return Err(err);
+        }
     };
 
-    user.check_enabled()?;
-    user.check_expired()?;
+    if user.pending_since.is_some() {
+        // not a failed login - the user simply has to wait for the approval
+        return Err(ErrorResponse::new(
+            ErrorResponseType::Forbidden,
+            "The registration is awaiting the approval of an admin",
+        ));
+    }
+    if let Err(err) = user.check_enabled().and_then(|_| user.check_expired()) {
+        login_failed_event(req, LoginFailureReason::AccountDisabled).await;
+        return Err(err);
+    }
+
+    if slf.recovery {
+        AuthProviderCallback::delete(slf.callback_id).await?;
+        let location = recover_account(req, user, &provider).await?;
+        return Ok((
+            AuthStep::ProviderRecovery(location),
+            ApiCookie::build(COOKIE_UPSTREAM_CALLBACK, "", 0),
+            is_new_user,
+        ));
+    }
 
     if link_cookie.is_some() {
         // If this is the case, we don't need to validate any further client values.
@@ -130,3 +164,78 @@ pub async fn login_finish<'a>(
 
     Ok((auth_step, cookie, is_new_user))
 }
+
+/// Resets the MFA and all sessions for the user, which has just been validated via an already
+/// linked provider, and returns the `Location` of the password reset page.
+async fn recover_account(
+    req: &HttpRequest,
+    user: User,
+    provider: &AuthProvider,
+) -> Result<HeaderValue, ErrorResponse> {
+    if !RauthyConfig::get().vars.mfa.provider_recovery {
+        return Err(ErrorResponse::new(
+            ErrorResponseType::Forbidden,
+            "Account recovery via upstream providers is disabled",
+        ));
+    }
+    if user.is_admin() {
+        return Err(ErrorResponse::new(
+            ErrorResponseType::Forbidden,
+            "Admin accounts must be recovered by another admin",
+        ));
+    }
+    let ip = real_ip_from_req(req)?;
+
+    for pk in PasskeyEntity::find_for_user(&user.id).await? {
+        PasskeyEntity::delete(user.id.clone(), pk.name).await?;
+    }
+    Session::invalidate_for_user(&user.id).await?;
+    RefreshToken::invalidate_for_user(&user.id).await?;
+
+    // the user may not have had a password before, if it was a passkey only account
+    let user = User::find(user.id).await?;
+    MagicLink::delete_all_pwd_reset_for_user(user.id.clone()).await?;
+    let usage = if user.password.is_none() {
+        MagicLinkUsage::NewUser(None)
+    } else {
+        MagicLinkUsage::PasswordReset(None)
+    };
+    let ml = MagicLink::create(
+        user.id.clone(),
+        RauthyConfig::get().vars.lifetimes.magic_link_pwd_reset as i64,
+        usage,
+    )
+    .await?;
+
+    Event::account_recovered(&user.email, &provider.name, ip)
+        .send()
+        .await?;
+    if RauthyConfig::get().vars.mfa.provider_recovery_notify {
+        send_account_recovered(&user, &provider.name).await;
+    }
+
+    let location = format!(
+        "{}/auth/v1/users/{}/reset/{}?type={}",
+        RauthyConfig::get().pub_url_with_scheme,
+        ml.user_id,
+        ml.id,
+        ml.usage,
+    );
+    Ok(HeaderValue::from_str(&location)?)
+}
+
+async fn login_failed_event(req: &HttpRequest, reason: LoginFailureReason) {
+    if reason == LoginFailureReason::Other {
+        return;
+    }
+    warn!(%reason, "Upstream auth provider login failed");
+
+    match real_ip_from_req(req) {
+        Ok(ip) => {
+            if let Err(err) = Event::login_failed(ip.to_string(), reason).send().await {
+                error!(?err, "Error sending login failed event");
+            }
+        }
+        Err(err) => error!(?err, "Cannot extract the IP for the login failed event"),
+    }
+}
@@ -16,8 +16,12 @@ use std::fmt::Write;
 use tracing::error;
 
 /// returns (encrypted cookie, xsrf token, location header, optional allowed origins)
+///
+/// With `recovery` set, the callback will only accept users with an already existing link to
+/// this provider and starts an account recovery instead of a login.
 pub async fn login_start<'a>(
     payload: ProviderLoginRequest,
+    recovery: bool,
 ) -> Result<(Cookie<'a>, String, HeaderValue), ErrorResponse> {
     let provider = AuthProvider::find(&payload.provider_id).await?;
 
@@ -46,6 +50,8 @@ pub async fn login_start<'a>(
         provider_id: provider.id,
 
         pkce_challenge: payload.pkce_challenge,
+
+        recovery,
     };
 
     let mut location = format!(
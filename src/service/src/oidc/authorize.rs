@@ -12,9 +12,11 @@ use rauthy_data::entity::auth_providers::ProviderMfaLogin;
 use rauthy_data::entity::browser_id::BrowserId;
 use rauthy_data::entity::clients::Client;
 use rauthy_data::entity::login_locations::LoginLocation;
+use rauthy_data::entity::scopes::Scope;
 use rauthy_data::entity::sessions::Session;
 use rauthy_data::entity::users::{AccountType, User};
 use rauthy_data::entity::webauthn::{WebauthnCookie, WebauthnLoginReq, WebauthnToSAwaitData};
+use rauthy_data::ldap::Ldap;
 use rauthy_data::rauthy_config::RauthyConfig;
 use rauthy_data::{AuthStep, AuthStepAwaitWebauthn, AuthStepLoggedIn, AwaitToSAccept};
 use rauthy_error::{ErrorResponse, ErrorResponseType};
@@ -31,16 +33,96 @@ pub async fn post_authorize(
 ) -> Result<AuthStep, ErrorResponse> {
     *add_login_delay = true;
 
-    let mut user = User::find_by_email(req_data.email).await.inspect_err(|_| {
-        // The UI does not show the password input form when there is no user yet.
-        // To prevent username enumeration, we should not add a login delay if a user does not
-        // even exist, when the UI is in that phase where the user does not provide any
-        // password.
-        if req_data.password.is_none() {
-            *add_login_delay = false;
+    let user = match User::find_by_email(req_data.email.clone()).await {
+        Ok(user) if !Ldap::is_linked(&user.id).await? => {
+            post_authorize_local(
+                req,
+                user,
+                req_data.password,
+                has_password_been_hashed,
+                add_login_delay,
+                browser_id,
+            )
+            .await?
         }
-    })?;
+        // Users linked to the directory always authenticate via LDAP. Unknown users will be
+        // looked up in the directory, if it is enabled.
+        Ok(user) => {
+            post_authorize_ldap(
+                req,
+                &req_data.email,
+                req_data.password,
+                Some(user),
+                has_password_been_hashed,
+                add_login_delay,
+                browser_id,
+            )
+            .await?
+        }
+        Err(err) if err.error == ErrorResponseType::NotFound && Ldap::is_enabled() => {
+            post_authorize_ldap(
+                req,
+                &req_data.email,
+                req_data.password,
+                None,
+                has_password_been_hashed,
+                add_login_delay,
+                browser_id,
+            )
+            .await?
+        }
+        Err(err) => {
+            // The UI does not show the password input form when there is no user yet.
+            // To prevent username enumeration, we should not add a login delay if a user does not
+            // even exist, when the UI is in that phase where the user does not provide any
+            // password.
+            if req_data.password.is_none() {
+                *add_login_delay = false;
+            }
+            return Err(err);
+        }
+    };
+    // If the password was correct, we don't want a login delay anymore.
+    // It should only prevent username enumeration and brute force, not degrade the UX.
+    *add_login_delay = false;
+
+    // client validations
+    let client = Client::find_maybe_ephemeral(req_data.client_id).await?;
+    let header_origin = client.get_validated_origin_header(req)?;
+
+    let require_webauthn = user.has_webauthn_enabled();
+    if require_webauthn {
+        session.set_mfa(true).await?;
+    }
+
+    finish_authorize(
+        user,
+        client,
+        &mut session,
+        AuthorizeData {
+            redirect_uri: req_data.redirect_uri,
+            scopes: req_data.scopes,
+            state: req_data.state,
+            nonce: req_data.nonce,
+            code_challenge: req_data.code_challenge,
+            code_challenge_method: req_data.code_challenge_method,
+            header_origin,
+            require_webauthn,
+        },
+        Some(user_needs_mfa),
+        None,
+    )
+    .await
+}
 
+async fn post_authorize_local(
+    req: &HttpRequest,
+    mut user: User,
+    password: Option<String>,
+    has_password_been_hashed: &mut bool,
+    add_login_delay: &mut bool,
+    browser_id: BrowserId,
+) -> Result<User, ErrorResponse> {
     let mfa_cookie =
         if let Ok(c) = WebauthnCookie::parse_validate(&ApiCookie::from_req(req, COOKIE_MFA)) {
             if c.email == user.email && user.has_webauthn_enabled() {
@@ -58,7 +140,7 @@ pub async fn post_authorize(
 
     // Only allow an empty password, if the user has a passkey only account or a valid MFA cookie.
     let user_must_provide_password =
-        req_data.password.is_none() && account_type != AccountType::Passkey && mfa_cookie.is_none();
+        password.is_none() && account_type != AccountType::Passkey && mfa_cookie.is_none();
     if user_must_provide_password {
         // if we get here, the UI did the first step from the login form
         // -> username only without password
@@ -84,7 +166,7 @@ pub async fn post_authorize(
     user.check_enabled()?;
     user.check_expired()?;
 
-    if let Some(pwd) = req_data.password {
+    if let Some(pwd) = password {
         *has_password_been_hashed = true;
         user.validate_password(pwd).await?;
 
@@ -101,37 +183,44 @@ pub async fn post_authorize(
         user.failed_login_attempts = None;
         user.save(None).await?;
     }
-    // If the password was correct, we don't want a login delay anymore.
-    // It should only prevent username enumeration and brute force, not degrade the UX.
-    *add_login_delay = false;
 
-    // client validations
-    let client = Client::find_maybe_ephemeral(req_data.client_id).await?;
-    let header_origin = client.get_validated_origin_header(req)?;
+    Ok(user)
+}
 
-    let require_webauthn = user.has_webauthn_enabled();
-    if require_webauthn {
-        session.set_mfa(true).await?;
+async fn post_authorize_ldap(
+    req: &HttpRequest,
+    email: &str,
+    password: Option<String>,
+    user: Option<User>,
+    has_password_been_hashed: &mut bool,
+    add_login_delay: &mut bool,
+    browser_id: BrowserId,
+) -> Result<User, ErrorResponse> {
+    let Some(password) = password else {
+        // Same as for local accounts, the UI needs to ask for the password first. We must not
+        // reveal whether the user exists in the directory at this point.
+        *add_login_delay = false;
+
+        trace!("No user password has been provided");
+        return Err(ErrorResponse::new(
+            ErrorResponseType::Unauthorized,
+            "User needs to provide a password",
+        ));
+    };
+
+    if let Some(user) = &user {
+        user.check_enabled()?;
+        user.check_expired()?;
     }
 
-    finish_authorize(
-        user,
-        client,
-        &mut session,
-        AuthorizeData {
-            redirect_uri: req_data.redirect_uri,
-            scopes: req_data.scopes,
-            state: req_data.state,
-            nonce: req_data.nonce,
-            code_challenge: req_data.code_challenge,
-            code_challenge_method: req_data.code_challenge_method,
-            header_origin,
-            require_webauthn,
-        },
-        Some(user_needs_mfa),
-        None,
-    )
-    .await
+    *has_password_been_hashed = true;
+    let user = Ldap::login(email, &password, user).await?;
+    user.check_enabled()?;
+    user.check_expired()?;
+
+    LoginLocation::spawn_background_check(user.clone(), req, browser_id)?;
+
+    Ok(user)
 }
 
 pub async fn post_authorize_refresh(
@@ -173,6 +262,28 @@ pub async fn post_authorize_refresh(
     .await
 }
 
+/// Builds the `Location` for an RFC 6749 error response to the client's `redirect_uri`.
+/// The `redirect_uri` must have been validated for the client before.
+pub fn build_error_location(redirect_uri: &str, state: Option<&str>, error: &str) -> String {
+    let append_char = if redirect_uri.contains('?') { '&' } else { '?' };
+    match state {
+        Some(state) => format!("{redirect_uri}{append_char}error={error}&state={state}"),
+        None => format!("{redirect_uri}{append_char}error={error}"),
+    }
+}
+
+/// Rejects the `plain` PKCE method, if it has been disabled globally.
+#[inline]
+pub(crate) fn validate_pkce_method(method: Option<&str>) -> Result<(), ErrorResponse> {
+    if method == Some("plain") && !RauthyConfig::get().vars.access.pkce_allow_plain {
+        return Err(ErrorResponse::new(
+            ErrorResponseType::BadRequest,
+            "code_challenge_method 'plain' is not allowed",
+        ));
+    }
+    Ok(())
+}
+
 pub(crate) struct AuthorizeData {
     pub redirect_uri: String,
     pub scopes: Option<Vec<String>>,
@@ -208,8 +319,24 @@ pub(crate) async fn finish_authorize(
     client.validate_user_groups(&user)?;
     client.validate_redirect_uri(&data.redirect_uri)?;
     client.validate_code_challenge(&data.code_challenge, &data.code_challenge_method)?;
+    client.validate_nonce(data.nonce.as_deref())?;
+    validate_pkce_method(data.code_challenge_method.as_deref())?;
+    session.validate_auth_state(data.state.as_deref()).await?;
 
     let scopes = client.sanitize_login_scopes(&data.scopes)?;
+    let (scopes, denied) = Scope::filter_user_entitled(scopes, &user).await?;
+    // Trusted first-party clients only ever get the entitled scopes silently, like without
+    // `strict_scopes`.
+    if !denied.is_empty() && RauthyConfig::get().vars.access.strict_scopes && !client.first_party {
+        // default scopes are set by the client config and should never lead to an error
+        let requested = data.scopes.as_deref().unwrap_or_default();
+        if let Some(s) = denied.iter().find(|d| requested.contains(d)) {
+            return Err(ErrorResponse::new(
+                ErrorResponseType::InvalidScope,
+                format!("the user is not entitled to '{s}'"),
+            ));
+        }
+    }
 
     let config = RauthyConfig::get();
     let mut code_lifetime = client.auth_code_lifetime;
@@ -222,6 +349,18 @@ pub(crate) async fn finish_authorize(
     }
     let needs_user_update = UserValuesValidator::does_user_need_update(&user, &client.id).await?;
 
+    if config.vars.access.nonce_replay_protection
+        && let Some(nonce) = &data.nonce
+    {
+        // the `nonce` is meaningful until the `id_token` issued for this code has expired
+        AuthCode::register_nonce(
+            &client.id,
+            nonce,
+            code_lifetime + client.access_token_lifetime,
+        )
+        .await?;
+    }
+
     let code = AuthCode::new(
         user.id.clone(),
         client.id,
@@ -308,3 +447,20 @@ pub(crate) async fn finish_authorize(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_error_location() {
+        assert_eq!(
+            build_error_location("https://app.example.com/cb", Some("abc"), "invalid_scope"),
+            "https://app.example.com/cb?error=invalid_scope&state=abc"
+        );
+        assert_eq!(
+            build_error_location("https://app.example.com/cb?x=1", None, "invalid_scope"),
+            "https://app.example.com/cb?x=1&error=invalid_scope"
+        );
+    }
+}
@@ -102,10 +102,13 @@ impl LogoutToken<'_> {
 
     /// Parse and validate the token as specified in
     /// https://openid.net/specs/openid-connect-backchannel-1_0.html#Validation
+    ///
+    /// Returns the upstream provider that issued the token as well, because the `sub` is the
+    /// upstream one and can only be resolved via the federation link.
     pub async fn from_str_validated<'a>(
         logout_token: &'a str,
         buf: &'a mut Vec<u8>,
-    ) -> Result<LogoutToken<'a>, ErrorResponse> {
+    ) -> Result<(LogoutToken<'a>, AuthProvider), ErrorResponse> {
         let (header, slf) = LogoutToken::build_from_str(logout_token, buf)?;
         let (kid, alg) = slf.validate_claims(header)?;
 
@@ -151,7 +154,7 @@ impl LogoutToken<'_> {
         let mut buf = Vec::with_capacity(256);
         jwk.validate_token_signature(logout_token, &mut buf)?;
 
-        Ok(slf)
+        Ok((slf, provider))
     }
 
     /// CAUTION: DO NOT use this function directly outside of tests. It DOES NOT VALIDATE the input.
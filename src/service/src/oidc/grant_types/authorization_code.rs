@@ -1,6 +1,7 @@
+use crate::oidc::authorize::validate_pkce_method;
 use crate::token_set::{
-    AuthCodeFlow, AuthTime, DeviceCodeFlow, DpopFingerprint, SessionId, TokenNonce, TokenScopes,
-    TokenSet,
+    AuthCodeFlow, AuthTime, DeviceCodeFlow, DpopFingerprint, SessionId, TokenIssuer, TokenNonce,
+    TokenScopes, TokenSet,
 };
 use actix_web::HttpRequest;
 use actix_web::http::header::{
@@ -123,7 +124,16 @@ pub async fn grant_type_authorization_code(
             "The Authorization Code has expired",
         ));
     }
+    if code.challenge.is_none() && !client.confidential {
+        // should never happen, since it is checked during the authorize already
+        warn!("PKCE is required for public clients");
+        return Err(ErrorResponse::new(
+            ErrorResponseType::BadRequest,
+            "'code_challenge' is required for public clients",
+        ));
+    }
     if code.challenge.is_some() {
+        validate_pkce_method(code.challenge_method.as_deref())?;
         if req_data.code_verifier.is_none() {
             warn!("'code_verifier' is missing");
             return Err(ErrorResponse::new(
@@ -155,6 +165,7 @@ pub async fn grant_type_authorization_code(
     }
 
     let user = User::find(code.user_id.clone()).await?;
+    let issuer = TokenIssuer::from_req(&req);
     let token_set = TokenSet::from_user(
         &user,
         &client,
@@ -165,6 +176,8 @@ pub async fn grant_type_authorization_code(
         code.session_id.clone().map(SessionId),
         AuthCodeFlow::Yes,
         DeviceCodeFlow::No,
+        issuer,
+        None,
     )
     .await?;
 
@@ -181,14 +194,14 @@ pub async fn grant_type_authorization_code(
     }
 
     if RauthyConfig::get().vars.events.generate_token_issued {
-        Event::token_issued("authorization_code", &client.id, Some(&user.email))
+        Event::token_issued("authorization_code", &client, Some(&user.email))
             .send()
             .await?;
     }
 
     // backchannel logout and login state tracking is not supported for ephemeral clients
     if !client.is_ephemeral() {
-        UserLoginState::insert(user.id.clone(), client.id, code.session_id).await?;
+        UserLoginState::insert(user.id.clone(), client.id, code.session_id, issuer.0).await?;
     }
 
     // No location check here, this is done in `POST /authorize` already
@@ -1,4 +1,4 @@
-use crate::token_set::{DpopFingerprint, TokenSet};
+use crate::token_set::{DpopFingerprint, TokenIssuer, TokenSet};
 use actix_web::HttpRequest;
 use actix_web::http::header::{HeaderName, HeaderValue};
 use rauthy_api_types::oidc::TokenRequest;
@@ -60,10 +60,12 @@ pub async fn grant_type_credentials(
         ClientDyn::update_used(&client.id).await?;
     }
 
-    let ts = TokenSet::for_client_credentials(&client, dpop_fingerprint).await?;
+    let ts =
+        TokenSet::for_client_credentials(&client, dpop_fingerprint, TokenIssuer::from_req(&req))
+            .await?;
 
     if RauthyConfig::get().vars.events.generate_token_issued {
-        Event::token_issued("client_credentials", &client.id, None)
+        Event::token_issued("client_credentials", &client, None)
             .send()
             .await?;
     }
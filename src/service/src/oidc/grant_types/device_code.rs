@@ -1,4 +1,6 @@
-use crate::token_set::{AuthCodeFlow, AuthTime, DeviceCodeFlow, TokenScopes, TokenSet};
+use crate::token_set::{
+    AuthCodeFlow, AuthTime, DeviceCodeFlow, TokenIssuer, TokenScopes, TokenSet,
+};
 use actix_web::HttpResponse;
 use chrono::Utc;
 use rauthy_api_types::oidc::{OAuth2ErrorResponse, OAuth2ErrorTypeResponse, TokenRequest};
@@ -15,7 +17,11 @@ use tracing::{debug, error, warn};
 
 /// Return a [TokenSet](crate::models::response::TokenSet) for the `device_code` flow
 #[tracing::instrument(skip_all, fields(client_id = payload.client_id))]
-pub async fn grant_type_device_code(peer_ip: IpAddr, payload: TokenRequest) -> HttpResponse {
+pub async fn grant_type_device_code(
+    peer_ip: IpAddr,
+    payload: TokenRequest,
+    issuer: TokenIssuer,
+) -> HttpResponse {
     let device_code = match &payload.device_code {
         None => {
             return HttpResponse::BadRequest().json(OAuth2ErrorResponse {
@@ -165,6 +171,8 @@ pub async fn grant_type_device_code(peer_ip: IpAddr, payload: TokenRequest) -> H
             None,
             AuthCodeFlow::No,
             DeviceCodeFlow::Yes(id),
+            issuer,
+            None,
         )
         .await
         {
@@ -179,7 +187,7 @@ pub async fn grant_type_device_code(peer_ip: IpAddr, payload: TokenRequest) -> H
         };
 
         if RauthyConfig::get().vars.events.generate_token_issued
-            && let Err(err) = Event::token_issued("device_code", &client.id, Some(&user.email))
+            && let Err(err) = Event::token_issued("device_code", &client, Some(&user.email))
                 .send()
                 .await
         {
@@ -1,4 +1,6 @@
-use crate::token_set::{AuthCodeFlow, AuthTime, DeviceCodeFlow, DpopFingerprint, TokenSet};
+use crate::token_set::{
+    AuthCodeFlow, AuthTime, DeviceCodeFlow, DpopFingerprint, TokenIssuer, TokenSet,
+};
 use actix_web::HttpRequest;
 use actix_web::http::header::{
     ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_METHODS, HeaderName, HeaderValue,
@@ -108,6 +110,7 @@ pub async fn grant_type_password(
                 ClientDyn::update_used(&client.id).await?;
             }
 
+            let issuer = TokenIssuer::from_req(&req);
             let ts = TokenSet::from_user(
                 &user,
                 &client,
@@ -118,16 +121,18 @@ pub async fn grant_type_password(
                 None,
                 AuthCodeFlow::No,
                 DeviceCodeFlow::No,
+                issuer,
+                None,
             )
             .await?;
 
             if RauthyConfig::get().vars.events.generate_token_issued {
-                Event::token_issued("password", &client.id, Some(&user.email))
+                Event::token_issued("password", &client, Some(&user.email))
                     .send()
                     .await?;
             }
 
-            UserLoginState::insert(user.id.clone(), client.id, None).await?;
+            UserLoginState::insert(user.id.clone(), client.id, None, issuer.0).await?;
 
             LoginLocation::spawn_background_check(user, &req, browser_id)?;
 
@@ -14,6 +14,7 @@ use rauthy_data::entity::jwk::{JwkKeyPair, JwkKeyPairAlg};
 use rauthy_data::entity::refresh_tokens::RefreshToken;
 use rauthy_data::entity::sessions::Session;
 use rauthy_data::entity::theme::ThemeCssFull;
+use rauthy_data::entity::user_federation::UserFederation;
 use rauthy_data::entity::user_login_states::UserLoginState;
 use rauthy_data::entity::users::User;
 use rauthy_data::html::HtmlCached;
@@ -59,32 +60,8 @@ pub async fn get_logout_html(
 
     // from here on, the token_hint contains a valid ID token -> skip the logout confirmation
     if let Some(target) = logout_request.post_logout_redirect_uri {
-        // unwrap is safe since the token is valid already
         let client = Client::find(claims.common.azp.to_string()).await?;
-        if client.post_logout_redirect_uris.is_none() {
-            return Err(ErrorResponse::new(
-                ErrorResponseType::BadRequest,
-                "Given 'post_logout_redirect_uri' is not allowed",
-            ));
-        }
-
-        let uri_vec = client.get_post_logout_uris();
-
-        let valid_redirect = uri_vec.as_ref().unwrap().iter().any(|uri| {
-            if uri.ends_with('*') && target.starts_with(uri.split_once('*').unwrap().0) {
-                return true;
-            }
-            if target.eq(uri) {
-                return true;
-            }
-            false
-        });
-        if valid_redirect {
-            return Err(ErrorResponse::new(
-                ErrorResponseType::BadRequest,
-                "Given 'post_logout_redirect_uri' is not allowed",
-            ));
-        }
+        client.validate_post_logout_redirect_uri(&target)?;
     }
 
     HtmlCached::Logout(session.csrf_token)
@@ -103,54 +80,68 @@ pub async fn post_logout_handle(
         || req.headers().get("sec-fetch-site").is_none();
 
     let mut buf = Vec::with_capacity(512);
-    let (session, user, cors_header, post_logout_redirect_uri) =
-        if let Some(id_token_hint) = &params.id_token_hint {
-            JwtToken::validate_claims_into(
-                id_token_hint,
-                Some(JwtTokenType::Id),
-                LOGOUT_TOKEN_CLOCK_SKEW,
-                &mut buf,
-            )
-            .await?;
-            let claims = serde_json::from_slice::<JwtIdClaims>(&buf)?;
-            let client = Client::find(claims.common.azp.to_string()).await?;
-            let cors_header = client.get_validated_origin_header(&req)?;
-
-            let post_logout_redirect_uri = if let Some(uri) = params.post_logout_redirect_uri {
-                client.validate_post_logout_redirect_uri(&uri)?;
-                Some(uri)
-            } else {
-                None
-            };
+    let (session, user, cors_header, post_logout_redirect_uri) = if let Some(id_token_hint) =
+        &params.id_token_hint
+    {
+        JwtToken::validate_claims_into(
+            id_token_hint,
+            Some(JwtTokenType::Id),
+            LOGOUT_TOKEN_CLOCK_SKEW,
+            &mut buf,
+        )
+        .await?;
+        let claims = serde_json::from_slice::<JwtIdClaims>(&buf)?;
+        let client = Client::find(claims.common.azp.to_string()).await?;
+        let cors_header = client.get_validated_origin_header(&req)?;
 
-            let (session, user) = find_session_with_user_fallback(
-                claims.sid.map(String::from),
-                claims.common.sub.map(String::from),
-            )
-            .await?;
-            (session, user, cors_header, post_logout_redirect_uri)
-        } else if let Some(s) = session {
-            let (session, user) = find_session_with_user_fallback(Some(s.id), s.user_id).await?;
-            (session, user, None, None)
-        } else if let Some(token) = params.logout_token {
-            let lt = LogoutToken::from_str_validated(&token, &mut buf).await?;
-            let (session, user) =
-                find_session_with_user_fallback(lt.sid.map(String::from), lt.sub.map(String::from))
-                    .await?;
-            (session, user, None, None)
+        let post_logout_redirect_uri = if let Some(uri) = params.post_logout_redirect_uri {
+            client.validate_post_logout_redirect_uri(&uri)?;
+            Some(uri)
         } else {
-            return Err(ErrorResponse::new(
-                ErrorResponseType::BadRequest,
-                "need at least one of `id_token_hint`, `logout_token`, or a valid session",
-            ));
+            None
+        };
+
+        // a pairwise `sub` cannot be used as a fallback to find the user
+        let sub = claims
+            .common
+            .sub
+            .filter(|_| !client.is_pairwise())
+            .map(String::from);
+        let (session, user) =
+            find_session_with_user_fallback(claims.sid.map(String::from), sub).await?;
+        (session, user, cors_header, post_logout_redirect_uri)
+    } else if let Some(s) = session {
+        let (session, user) = find_session_with_user_fallback(Some(s.id), s.user_id).await?;
+        (session, user, None, None)
+    } else if let Some(token) = params.logout_token {
+        let (lt, provider) = LogoutToken::from_str_validated(&token, &mut buf).await?;
+        // the `sub` is the one from the upstream provider and never a local user id
+        let uid = match lt.sub {
+            Some(sub) => {
+                UserFederation::find_by_federation_id(&provider.id, sub, provider.uid_normalization)
+                    .await
+                    .ok()
+                    .map(|link| link.user_id)
+            }
+            None => None,
         };
+        let (session, user) =
+            find_session_with_user_fallback(lt.sid.map(String::from), uid).await?;
+        (session, user, None, None)
+    } else {
+        return Err(ErrorResponse::new(
+            ErrorResponseType::BadRequest,
+            "need at least one of `id_token_hint`, `logout_token`, or a valid session",
+        ));
+    };
 
     let token_revoke = RauthyConfig::get().vars.access.token_revoke_on_logout;
+    let token_bind = RauthyConfig::get().vars.access.token_bind_session;
 
     let sid = session.as_ref().map(|s| s.id.clone());
     if let Some(session) = session {
         let uid = session.user_id.clone();
-        if token_revoke {
+        if token_revoke || token_bind {
             RefreshToken::delete_by_sid(session.id.clone()).await?;
             IssuedToken::revoke_for_session(
                 &session.id,
@@ -171,28 +162,36 @@ pub async fn post_logout_handle(
             )
             .await?;
         }
-        Session::invalidate_for_user(&user.id).await?;
+        let sids = Session::invalidate_for_user(&user.id).await?;
+        if token_bind && !token_revoke {
+            for sid in sids {
+                RefreshToken::delete_by_sid(sid.clone()).await?;
+                IssuedToken::revoke_for_session(&sid, false).await?;
+            }
+        }
         execute_backchannel_logout(None, Some(user.id)).await?;
     }
 
     if is_backchannel {
         Ok(HttpResponse::build(StatusCode::OK).finish())
     } else {
-        let uri = post_logout_redirect_uri
-            .as_ref()
-            .unwrap_or(&RauthyConfig::get().issuer);
-        let state = params
-            .state
-            .map(|st| format!("?state={st}"))
-            .unwrap_or_default();
-        let loc = format!("{uri}{state}");
+        // without a valid `post_logout_redirect_uri`, we show our own logged-out page
+        let loc = if let Some(uri) = post_logout_redirect_uri {
+            let state = params
+                .state
+                .map(|st| format!("?state={st}"))
+                .unwrap_or_default();
+            format!("{uri}{state}")
+        } else {
+            format!("{}oidc/logout?logged_out=true", RauthyConfig::get().issuer)
+        };
 
         let mut resp = HttpResponse::build(StatusCode::from_u16(302).unwrap())
             .append_header((header::LOCATION, loc))
             .finish();
 
         if let Some(sid) = sid {
-            let cookie_session = ApiCookie::build(COOKIE_SESSION, &sid, 0);
+            let cookie_session = ApiCookie::build_session(COOKIE_SESSION, &sid, 0);
             resp.add_cookie(&cookie_session)?;
 
             if RauthyConfig::get().vars.fedcm.experimental_enable {
@@ -307,11 +306,10 @@ pub async fn execute_backchannel_logout(
 
         for client in clients {
             let sub = if sid.is_none() {
-                Some(uid.clone())
+                Some(client.subject(&uid).into_owned())
             } else {
                 None
             };
-            let sid = sid.clone();
             debug!(sub, sid);
 
             let mut kp = kps.iter().find(|kp| kp.typ.as_str() == client.id_token_alg);
@@ -322,17 +320,30 @@ pub async fn execute_backchannel_logout(
             }
             debug_assert!(kp.is_some());
 
-            if let Err(err) = send_backchannel_logout(
-                client.id.clone(),
-                client.backchannel_logout_uri.unwrap_or_default(),
-                sub,
-                sid,
-                kp.as_ref().unwrap(),
-                &mut tasks,
-            )
-            .await
-            {
-                error!("Error executing Backchannel Logout: {}", err);
+            // With `issuer_hosts`, the same user may be logged in to this client under
+            // different issuers. Each of them needs its own logout token.
+            let mut issuers: Vec<Option<&str>> = Vec::with_capacity(1);
+            for state in states.iter().filter(|st| st.client_id == client.id) {
+                if !issuers.contains(&state.issuer.as_deref()) {
+                    issuers.push(state.issuer.as_deref());
+                }
+            }
+
+            let uri = client.backchannel_logout_uri.unwrap_or_default();
+            for issuer in issuers {
+                if let Err(err) = send_backchannel_logout(
+                    client.id.clone(),
+                    uri.clone(),
+                    sub.clone(),
+                    sid.clone(),
+                    issuer.map(String::from),
+                    kp.as_ref().unwrap(),
+                    &mut tasks,
+                )
+                .await
+                {
+                    error!("Error executing Backchannel Logout: {}", err);
+                }
             }
         }
 
@@ -398,8 +409,9 @@ pub async fn execute_backchannel_logout_by_client(client: &Client) -> Result<(),
         if let Err(err) = send_backchannel_logout(
             client.id.clone(),
             uri.to_string(),
-            Some(state.user_id),
+            Some(client.subject(&state.user_id).into_owned()),
             None,
+            state.issuer,
             &kp,
             &mut tasks,
         )
@@ -427,13 +439,15 @@ pub async fn send_backchannel_logout(
     backchannel_logout_uri: String,
     sub: Option<String>,
     sid: Option<String>,
+    issuer: Option<String>,
     kp: &JwkKeyPair,
     tasks: &mut JoinSet<Result<(), ErrorResponse>>,
 ) -> Result<(), ErrorResponse> {
     debug_assert!(sub.is_some() || sid.is_some());
 
+    // The token must be issued under the same `iss` as the tokens of the login itself.
     let logout_token = LogoutToken::new(
-        &RauthyConfig::get().issuer,
+        issuer.as_deref().unwrap_or(&RauthyConfig::get().issuer),
         &client_id,
         sub.as_deref(),
         sid.as_deref(),
@@ -466,7 +480,7 @@ pub async fn send_backchannel_logout(
             }
         };
 
-        FailedBackchannelLogout::upsert(client_id, sub, sid).await?;
+        FailedBackchannelLogout::upsert(client_id, sub, sid, issuer).await?;
 
         Err(ErrorResponse::new(ErrorResponseType::BadRequest, err))
     });
@@ -1,14 +1,16 @@
 use actix_web::HttpRequest;
 use actix_web::http::header::{AUTHORIZATION, HeaderName, HeaderValue};
 use rauthy_api_types::oidc::TokenInfo;
-use rauthy_common::utils::base64_decode_buf;
+use rauthy_common::utils::{base64_decode_buf, real_ip_from_req};
 use rauthy_data::entity::clients::Client;
 use rauthy_data::entity::issued_tokens::IssuedToken;
+use rauthy_data::entity::token_nbf::TokenNbf;
+use rauthy_data::events::event::Event;
 use rauthy_data::rauthy_config::RauthyConfig;
 use rauthy_error::{ErrorResponse, ErrorResponseType};
 use rauthy_jwt::claims::{JwtAccessClaims, JwtCommonClaims, JwtTokenType};
 use rauthy_jwt::token::JwtToken;
-use tracing::error;
+use tracing::{error, warn};
 
 pub async fn get_token_info(
     req: &HttpRequest,
@@ -29,8 +31,19 @@ pub async fn get_token_info(
     }
     let claims = serde_json::from_slice::<JwtCommonClaims>(&buf)?;
 
-    if let Some(jti) = claims.jti {
-        IssuedToken::validate_not_revoked(jti).await?;
+    let is_revoked = if let Some(jti) = claims.jti {
+        IssuedToken::validate_not_revoked(jti).await.is_err()
+    } else {
+        false
+    };
+    if is_revoked || TokenNbf::validate(claims.azp, claims.iat).await.is_err() {
+        return Ok((
+            serde_json::to_string(&TokenInfo {
+                active: false,
+                ..Default::default()
+            })?,
+            None,
+        ));
     }
 
     if claims.aud.is_empty() {
@@ -44,11 +57,45 @@ pub async fn get_token_info(
         ));
     }
     let client_id = claims.azp.to_string();
+    let aud = claims.aud.to_string();
+
+    let mut buf_client = Vec::with_capacity(512);
+    let client = check_client_auth(req, client_id.clone(), &mut buf_client).await?;
+    let cors_header = client.get_validated_origin_header(req)?;
+
+    // A resource server should only be able to introspect tokens that were issued for itself.
+    if RauthyConfig::get().vars.access.introspect_restrict_aud
+        && client.id != aud
+        && client.id != client_id
+    {
+        return Ok((
+            serde_json::to_string(&TokenInfo {
+                active: false,
+                ..Default::default()
+            })?,
+            cors_header,
+        ));
+    }
+
+    // The `sub` may be a pairwise one for the client the token has been issued to. Any other
+    // client must only ever see the `sub` from its own sector.
+    let sub = match claims.sub {
+        Some(sub) if client.id != client_id => {
+            let user_id = if !Client::find(client_id).await?.is_pairwise() {
+                Some(sub.to_string())
+            } else if let Some(jti) = claims.jti {
+                IssuedToken::find(jti).await.ok().and_then(|t| t.user_id)
+            } else {
+                None
+            };
+            user_id.map(|id| client.subject(&id).into_owned())
+        }
+        sub => sub.map(String::from),
+    };
 
-    // serialize token already before checking client to be able to re-use `buf`
     let info = serde_json::to_string(&TokenInfo {
         active: true,
-        sub: claims.sub,
+        sub: sub.as_deref(),
         scope: claims.scope,
         client_id: Some(claims.azp),
         aud: Some(claims.aud.as_ref()),
@@ -58,10 +105,6 @@ pub async fn get_token_info(
         cnf: claims.cnf,
     })?;
 
-    buf.clear();
-    let client = check_client_auth(req, client_id, &mut buf).await?;
-    let cors_header = client.get_validated_origin_header(req)?;
-
     Ok((info, cors_header))
 }
 
@@ -96,13 +139,13 @@ async fn check_client_auth(
         let claims = serde_json::from_slice::<JwtAccessClaims>(buf)?;
 
         // If a different client was used for authentication, make sure it exists and is enabled.
-        if claims.common.azp != claims_client.id {
-            let client = find_enabled_client(claims.common.azp.to_string()).await?;
+        let client = if claims.common.azp != claims_client.id {
             // no need to validate the secret - the valid token was the authentication
-            Ok(client)
+            find_enabled_client(claims.common.azp.to_string()).await?
         } else {
-            Ok(claims_client)
-        }
+            claims_client
+        };
+        validate_introspect_allowed(req, client).await
     } else if let Some(basic) = header.strip_prefix("Basic ") {
         base64_decode_buf(basic, buf)?;
         let decoded = String::from_utf8_lossy(buf);
@@ -120,7 +163,7 @@ async fn check_client_auth(
         };
         client.validate_secret(secret, req).await?;
 
-        Ok(client)
+        validate_introspect_allowed(req, client).await
     } else {
         Err(ErrorResponse::new(
             ErrorResponseType::WWWAuthenticate("invalid-authorization-header".to_string()),
@@ -129,6 +172,28 @@ async fn check_client_auth(
     }
 }
 
+/// Only registered resource servers with the `introspect` flag are allowed to introspect tokens.
+async fn validate_introspect_allowed(
+    req: &HttpRequest,
+    client: Client,
+) -> Result<Client, ErrorResponse> {
+    if client.introspect {
+        return Ok(client);
+    }
+
+    let ip = real_ip_from_req(req)?;
+    warn!(
+        "Client '{}' from {ip} is not allowed to use token introspection",
+        client.id
+    );
+    Event::introspection_denied(&client.id, ip).send().await?;
+
+    Err(ErrorResponse::new(
+        ErrorResponseType::Forbidden,
+        "access_denied: client is not allowed to use token introspection",
+    ))
+}
+
 #[inline]
 async fn find_enabled_client(id: String) -> Result<Client, ErrorResponse> {
     let client = Client::find(id).await.map_err(|_| {
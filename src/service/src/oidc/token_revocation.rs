@@ -98,9 +98,11 @@ pub async fn handle_token_revocation(
             return Ok(cors_header);
         };
 
+        // the `sub` may be a pairwise one -> the issued token knows the real user id
+        let user_id = IssuedToken::find(jti).await.ok().and_then(|t| t.user_id);
         IssuedToken::revoke(jti.to_string()).await?;
 
-        if let Some(user_id) = claims.sub {
+        if let Some(user_id) = user_id.as_deref() {
             if claims.did.is_some() {
                 if let Some(rt) = RefreshTokenDevice::find_by_user_id_jti(user_id, jti).await? {
                     rt.delete().await?;
@@ -5,6 +5,7 @@ use rauthy_api_types::users::Userinfo;
 use rauthy_data::entity::clients::Client;
 use rauthy_data::entity::devices::DeviceEntity;
 use rauthy_data::entity::issued_tokens::IssuedToken;
+use rauthy_data::entity::token_nbf::TokenNbf;
 use rauthy_data::entity::users::User;
 use rauthy_data::entity::users_values::UserValues;
 use rauthy_data::entity::webids::WebId;
@@ -35,17 +36,35 @@ pub async fn get_userinfo(
         ));
     };
 
-    if let Some(jti) = claims.jti
-        && IssuedToken::validate_not_revoked(jti).await.is_err()
-    {
+    let is_revoked = if let Some(jti) = claims.jti {
+        IssuedToken::validate_not_revoked(jti).await.is_err()
+    } else {
+        false
+    };
+    if is_revoked || TokenNbf::validate(claims.azp, claims.iat).await.is_err() {
         return Err(ErrorResponse::new(
             ErrorResponseType::WWWAuthenticate("token-revoked".to_string()),
             "The token has been revoked",
         ));
     }
 
+    // a `pairwise` subject cannot be mapped back to the user -> the issued token knows it
+    let is_ephemeral = claims.azp.starts_with("http://") || claims.azp.starts_with("https://");
+    let pairwise_client = if is_ephemeral {
+        None
+    } else {
+        Client::find(claims.azp.to_string())
+            .await
+            .ok()
+            .filter(|c| c.is_pairwise())
+    };
+    let user_id = match (&pairwise_client, claims.jti) {
+        (Some(_), Some(jti)) => IssuedToken::find(jti).await?.user_id.unwrap_or_default(),
+        _ => sub.to_string(),
+    };
+
     let scope = claims.scope.unwrap_or_else(|| Cow::from("openid"));
-    let user = User::find(sub.to_string()).await.map_err(|_| {
+    let user = User::find(user_id).await.map_err(|_| {
         ErrorResponse::new(
             ErrorResponseType::WWWAuthenticate("user-not-found".to_string()),
             "The user has not been found",
@@ -101,9 +120,13 @@ pub async fn get_userinfo(
         && scope.contains("webid"))
     .then(|| WebId::resolve_webid_uri(&user.id));
 
+    let sub = match &pairwise_client {
+        Some(client) => client.subject(&user.id).into_owned(),
+        None => user.id.clone(),
+    };
     let mut userinfo = Userinfo {
-        id: user.id.clone(),
-        sub: user.id.clone(),
+        id: sub.clone(),
+        sub,
         name: user.email_recipient_name(),
         roles,
         mfa_enabled: user.has_webauthn_enabled(),
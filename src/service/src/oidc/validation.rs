@@ -1,26 +1,32 @@
+use crate::oidc::authorize::validate_pkce_method;
 use crate::token_set::{
-    AuthCodeFlow, AuthTime, DeviceCodeFlow, DpopFingerprint, TokenScopes, TokenSet,
+    AuthCodeFlow, AuthTime, DeviceCodeFlow, DpopFingerprint, RefreshTokenFamily, SessionId,
+    TokenIssuer, TokenScopes, TokenSet,
 };
 use actix_web::HttpRequest;
 use actix_web::http::header::{HeaderName, HeaderValue};
 use chrono::Utc;
 use rauthy_data::entity::clients::Client;
 use rauthy_data::entity::dpop_proof::DPoPProof;
+use rauthy_data::entity::issued_tokens::IssuedToken;
 use rauthy_data::entity::refresh_tokens::RefreshToken;
 use rauthy_data::entity::refresh_tokens_devices::RefreshTokenDevice;
+use rauthy_data::entity::sessions::Session;
+use rauthy_data::entity::token_nbf::TokenNbf;
 use rauthy_data::entity::users::User;
 use rauthy_data::events::event::Event;
 use rauthy_data::rauthy_config::RauthyConfig;
 use rauthy_error::{ErrorResponse, ErrorResponseType};
 use rauthy_jwt::claims::{JwtRefreshClaims, JwtTokenType};
 use rauthy_jwt::token::JwtToken;
-use tracing::debug;
+use tracing::{debug, warn};
 
 /// Validates request parameters for the authorization and refresh endpoints
 pub async fn validate_auth_req_param(
     req: &HttpRequest,
     client_id: &str,
     redirect_uri: &str,
+    response_type: &str,
     code_challenge: &Option<String>,
     code_challenge_method: &Option<String>,
 ) -> Result<(Client, Option<(HeaderName, HeaderValue)>), ErrorResponse> {
@@ -36,9 +42,10 @@ pub async fn validate_auth_req_param(
     let header = client.get_validated_origin_header(req)?;
 
     client.validate_enabled()?;
+    client.validate_response_type(response_type)?;
     client.validate_redirect_uri(redirect_uri)?;
 
-    if client.challenge.is_some() {
+    if client.challenges_allowed().is_some() {
         if code_challenge.is_none() {
             return Err(ErrorResponse::new(
                 ErrorResponseType::BadRequest,
@@ -53,6 +60,7 @@ pub async fn validate_auth_req_param(
                 String::from("plain")
             };
             client.validate_challenge_method(&method)?;
+            validate_pkce_method(Some(&method))?;
         }
     }
 
@@ -69,6 +77,7 @@ pub async fn validate_and_refresh_token(
     let mut buf = Vec::with_capacity(256);
     JwtToken::validate_claims_into(refresh_token, Some(JwtTokenType::Refresh), 0, &mut buf).await?;
     let claims: JwtRefreshClaims = serde_json::from_slice(&buf)?;
+    TokenNbf::validate(claims.common.azp, claims.common.iat).await?;
 
     let client = if let Some(c) = client_opt {
         c
@@ -109,8 +118,28 @@ pub async fn validate_and_refresh_token(
     let (_, validation_str) = refresh_token.split_at(refresh_token.len() - 49);
     let now = Utc::now().timestamp();
     let exp_at_secs = now + RauthyConfig::get().vars.lifetimes.refresh_token_grace_time as i64;
-    let rt_scope = if let Some(device_id) = &claims.common.did {
-        let mut rt = RefreshTokenDevice::find(validation_str).await?;
+    let reuse_detection = RauthyConfig::get()
+        .vars
+        .lifetimes
+        .refresh_token_reuse_detection;
+    let (rt_scope, rt_sid, rt_family) = if let Some(device_id) = &claims.common.did {
+        let mut rt = match RefreshTokenDevice::find(validation_str).await {
+            Ok(rt) => rt,
+            Err(err) => {
+                // The JWT itself is still valid at this point. If the token exists in the DB,
+                // it can only have been expired early by a rotation.
+                if reuse_detection
+                    && RefreshTokenDevice::find_opt(validation_str)
+                        .await?
+                        .is_some_and(|rt| rt.user_id == user.id)
+                {
+                    // each device has its own chain of refresh tokens
+                    let count = RefreshTokenDevice::invalidate_all_for_device(device_id).await?;
+                    replayed_refresh_token(&user, count).await?;
+                }
+                return Err(err);
+            }
+        };
 
         if &rt.device_id != device_id {
             return Err(ErrorResponse::new(
@@ -129,14 +158,40 @@ pub async fn validate_and_refresh_token(
             rt.exp = exp_at_secs;
             rt.save().await?;
         }
-        rt.scope
+        (rt.scope, None, None)
     } else {
-        let mut rt = RefreshToken::find(validation_str).await?;
+        // The session is checked before the lookup, because the refresh tokens may have been
+        // deleted already during a logout, while the client should still get an `invalid_grant`.
+        if let Some(sid) = claims.sid {
+            check_bound_session_active(sid).await?;
+        }
+
+        let mut rt = match RefreshToken::find(validation_str).await {
+            Ok(rt) => rt,
+            Err(err) => {
+                if reuse_detection
+                    && let Some(rt) = RefreshToken::find_opt(validation_str).await?
+                    && rt.user_id == user.id
+                {
+                    let count = RefreshToken::invalidate_family(&rt.family_id).await?;
+                    replayed_refresh_token(&user, count).await?;
+                }
+                return Err(err);
+            }
+        };
+
+        // older refresh tokens do not contain the `sid` claim
+        if claims.sid.is_none()
+            && let Some(sid) = &rt.session_id
+        {
+            check_bound_session_active(sid).await?;
+        }
+
         if rt.exp > exp_at_secs + 1 {
             rt.exp = exp_at_secs;
             rt.save().await?;
         }
-        rt.scope
+        (rt.scope, rt.session_id, Some(rt.family_id))
     };
 
     // at this point, everything has been validated -> we can issue a new TokenSet safely
@@ -163,17 +218,53 @@ pub async fn validate_and_refresh_token(
         dpop_fingerprint,
         None,
         rt_scope.map(TokenScopes),
-        None,
+        // keep the binding to the original session
+        rt_sid.map(SessionId),
         AuthCodeFlow::No,
         DeviceCodeFlow::No,
+        TokenIssuer::from_req(req),
+        rt_family.map(RefreshTokenFamily),
     )
     .await?;
 
     if RauthyConfig::get().vars.events.generate_token_issued {
-        Event::token_issued("refresh", &client.id, Some(&user.email))
+        Event::token_issued("refresh", &client, Some(&user.email))
             .send()
             .await?;
     }
 
     Ok((ts, dpop_nonce))
 }
+
+/// Returns an `invalid_grant` error if `access.token_bind_session` is set and the session
+/// a refresh token is bound to is not active anymore.
+async fn check_bound_session_active(sid: &str) -> Result<(), ErrorResponse> {
+    if !RauthyConfig::get().vars.access.token_bind_session
+        || Session::is_active(sid.to_string()).await?
+    {
+        return Ok(());
+    }
+
+    // the session may have expired without a logout -> clean up all bound tokens
+    RefreshToken::delete_by_sid(sid.to_string()).await?;
+    IssuedToken::revoke_for_session(sid, false).await?;
+    Err(ErrorResponse::new(
+        ErrorResponseType::InvalidGrant,
+        "The session this refresh token is bound to is not active anymore",
+    ))
+}
+
+/// Called after the token family of an already rotated refresh token, which has been used
+/// again after the `refresh_token_grace_time`, has been revoked. Such a replay most likely means
+/// the token has been stolen. We cannot know which side is the legitimate one, so the whole
+/// family is affected, while other logins of the same user stay valid.
+async fn replayed_refresh_token(user: &User, count: usize) -> Result<(), ErrorResponse> {
+    warn!(
+        user_id = user.id,
+        count, "Refresh token replay detected - revoked the refresh token family"
+    );
+
+    Event::tokens_revoked(format!("user {}", user.email), count)
+        .send()
+        .await
+}
@@ -30,6 +30,7 @@ pub async fn handle_get_pwd_reset<'a>(
 ) -> Result<(String, cookie::Cookie<'a>), ErrorResponse> {
     let mut ml = MagicLink::find(&reset_id).await?;
     ml.validate(&user_id, &req, false)?;
+    validate_pwd_reset_usage(&ml)?;
 
     let user = User::find(ml.user_id.clone()).await?;
     let lang = Language::try_from(&req).unwrap_or_default();
@@ -127,7 +128,14 @@ pub async fn handle_put_user_passkey_finish(
     debug!("invalidating magic link pwd");
     // all good
     ml.invalidate().await?;
-    User::set_email_verified(user_id, true).await?;
+    User::set_email_verified(user_id.clone(), true).await?;
+
+    if let MagicLinkUsage::NewUser(_) = MagicLinkUsage::try_from(&ml.usage)? {
+        let user = User::find(user_id).await?;
+        Event::user_onboarded(&user.email, "passkey", real_ip_from_req(&req).ok())
+            .send()
+            .await?;
+    }
 
     // delete the cookie
     let cookie = ApiCookie::build(PWD_RESET_COOKIE, "", 0);
@@ -169,6 +177,7 @@ pub async fn handle_put_user_password_reset<'a>(
 
     let mut ml = MagicLink::find(&req_data.magic_link_id).await?;
     ml.validate(&user.id, &req, true)?;
+    validate_pwd_reset_usage(&ml)?;
 
     user.apply_password_rules(&req_data.password).await?;
 
@@ -198,7 +207,12 @@ pub async fn handle_put_user_password_reset<'a>(
 
     // check if we got a custom `redirect_uri` during registration
     let redirect_uri = match MagicLinkUsage::try_from(&ml.usage)? {
-        MagicLinkUsage::NewUser(redirect_uri) => redirect_uri,
+        MagicLinkUsage::NewUser(redirect_uri) => {
+            Event::user_onboarded(&user.email, "password", real_ip_from_req(&req).ok())
+                .send()
+                .await?;
+            redirect_uri
+        }
         MagicLinkUsage::PasswordReset(redirect_uri) => redirect_uri,
         _ => None,
     };
@@ -207,3 +221,18 @@ pub async fn handle_put_user_password_reset<'a>(
     let cookie = ApiCookie::build(PWD_RESET_COOKIE, "", 0);
     Ok((cookie, redirect_uri))
 }
+
+/// E-Mail change links must never be usable for a password reset, because otherwise anyone with
+/// access to the (possibly mistyped) new address could take over the account.
+#[inline]
+fn validate_pwd_reset_usage(ml: &MagicLink) -> Result<(), ErrorResponse> {
+    match MagicLinkUsage::try_from(&ml.usage)? {
+        MagicLinkUsage::NewUser(_) | MagicLinkUsage::PasswordReset(_) => Ok(()),
+        MagicLinkUsage::EmailAdd(_)
+        | MagicLinkUsage::EmailChange(_)
+        | MagicLinkUsage::EmailChangeAbort(_) => Err(ErrorResponse::new(
+            ErrorResponseType::BadRequest,
+            "The Magic Link is not meant to be used for a password reset",
+        )),
+    }
+}
@@ -1,14 +1,16 @@
+use actix_web::HttpRequest;
 use chrono::Utc;
 use cryptr::utils::secure_random_alnum;
 use rauthy_api_types::oidc::JktClaim;
 use rauthy_common::utils::base64_url_no_pad_encode;
+use rauthy_data::claims_provider::{ClaimsProvider, ClaimsTokenType};
 use rauthy_data::entity::clients::Client;
 use rauthy_data::entity::issued_tokens::IssuedToken;
 use rauthy_data::entity::jwk::{JwkKeyPair, JwkKeyPairAlg};
 use rauthy_data::entity::refresh_tokens::RefreshToken;
 use rauthy_data::entity::refresh_tokens_devices::RefreshTokenDevice;
 use rauthy_data::entity::scopes::Scope;
-use rauthy_data::entity::user_attr::UserAttrValueEntity;
+use rauthy_data::entity::user_attr::{UserAttrConfigEntity, UserAttrValueEntity};
 use rauthy_data::entity::users::User;
 use rauthy_data::entity::users_values::UserValues;
 use rauthy_data::entity::webids::WebId;
@@ -106,9 +108,30 @@ pub struct DpopFingerprint(pub String);
 pub struct SessionId(pub String);
 pub struct TokenNonce(pub String);
 
+/// The `iss` for new tokens, which depends on the request host with `server.issuer_hosts`.
+#[derive(Debug, Clone, Copy)]
+pub struct TokenIssuer(pub &'static str);
+
+impl TokenIssuer {
+    #[inline]
+    pub fn from_req(req: &HttpRequest) -> Self {
+        Self(RauthyConfig::get().issuer_from_req(req))
+    }
+}
+
+/// The `family_id` of the refresh token which is being rotated.
+pub struct RefreshTokenFamily(pub String);
+
 /// Contains the scopes as a single String separated by `\s`
 pub struct TokenScopes(pub String);
 
+/// Tokens built with `Preview` are never persisted and must only be used to inspect their claims.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TokenBuildMode {
+    Issue,
+    Preview,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TokenSet {
     pub access_token: String,
@@ -132,6 +155,8 @@ impl TokenSet {
         scope_customs: Option<(Vec<&Scope>, &Option<HashMap<String, Vec<u8>>>)>,
         sid: Option<SessionId>,
         device_code_flow: DeviceCodeFlow,
+        issuer: TokenIssuer,
+        mode: TokenBuildMode,
     ) -> Result<(AccessTokenJti, String), ErrorResponse> {
         let did = match device_code_flow {
             DeviceCodeFlow::Yes(did) => Some(did),
@@ -157,11 +182,24 @@ impl TokenSet {
         let now = Utc::now().timestamp();
         let exp = now + lifetime;
 
-        let issued_token =
-            IssuedToken::create(user_id, did.as_deref(), sid.map(|sid| sid.0), exp).await?;
+        let jti = match mode {
+            TokenBuildMode::Issue => {
+                IssuedToken::create(
+                    &client.id,
+                    user_id,
+                    did.as_deref(),
+                    sid.map(|sid| sid.0),
+                    exp,
+                )
+                .await?
+                .jti
+            }
+            TokenBuildMode::Preview => secure_random_alnum(12),
+        };
 
-        let sub = if let Some(user) = user {
-            Some(user.id.as_str())
+        let user_sub = user.map(|user| client.subject(&user.id));
+        let sub = if let Some(user_sub) = &user_sub {
+            Some(user_sub.as_ref())
         } else if RauthyConfig::get().vars.access.client_credentials_map_sub {
             Some(client.id.as_str())
         } else {
@@ -173,8 +211,8 @@ impl TokenSet {
                 iat: now,
                 nbf: now,
                 exp,
-                iss: &RauthyConfig::get().issuer,
-                jti: Some(&issued_token.jti),
+                iss: issuer.0,
+                jti: Some(&jti),
                 aud: Cow::Borrowed(client.id.as_str()),
                 sub,
                 typ: JwtTokenType::Bearer,
@@ -212,11 +250,22 @@ impl TokenSet {
             }
         }
 
+        let provided = match user {
+            Some(user) => {
+                let scope = claims_new_impl.common.scope.as_deref().unwrap_or_default();
+                ClaimsProvider::fetch(&user.id, &client.id, scope, ClaimsTokenType::Access).await?
+            }
+            None => None,
+        };
+
         let key_pair_alg = JwkKeyPairAlg::from_str(&client.access_token_alg)?;
         let kp = JwkKeyPair::find_latest(key_pair_alg).await?;
-        let token = JwtToken::build(&kp, &claims_new_impl)?;
+        let token = match Self::finalize_claims(client, &claims_new_impl, provided)? {
+            Some(mapped) => JwtToken::build(&kp, &mapped)?,
+            None => JwtToken::build(&kp, &claims_new_impl)?,
+        };
 
-        Ok((AccessTokenJti(issued_token.jti), token))
+        Ok((AccessTokenJti(jti), token))
     }
 
     /// Builds the id token for a user after all validation has been successful
@@ -233,6 +282,7 @@ impl TokenSet {
         scope_customs: Option<(Vec<&Scope>, &Option<HashMap<String, Vec<u8>>>)>,
         sid: Option<SessionId>,
         auth_code_flow: AuthCodeFlow,
+        issuer: TokenIssuer,
     ) -> Result<String, ErrorResponse> {
         let config = RauthyConfig::get();
 
@@ -252,16 +302,17 @@ impl TokenSet {
         let webid = (config.vars.ephemeral_clients.enable_web_id && scope.contains("webid"))
             .then(|| Cow::from(WebId::resolve_webid_uri(&user.id)));
 
+        let sub = client.subject(&user.id);
         let now = Utc::now().timestamp();
         let mut claims = JwtIdClaims {
             common: JwtCommonClaims {
                 iat: now,
                 nbf: now,
                 exp: now + lifetime,
-                iss: &config.issuer,
+                iss: issuer.0,
                 jti: None,
                 aud,
-                sub: Some(user.id.as_str()),
+                sub: Some(sub.as_ref()),
                 typ: JwtTokenType::Id,
                 azp: &client.id,
                 scope: Some(Cow::Borrowed(scope)),
@@ -358,9 +409,78 @@ impl TokenSet {
             }
         }
 
+        let provided =
+            ClaimsProvider::fetch(&user.id, &client.id, scope, ClaimsTokenType::Id).await?;
+
         let key_pair_alg = JwkKeyPairAlg::from_str(&client.id_token_alg)?;
         let kp = JwkKeyPair::find_latest(key_pair_alg).await?;
-        JwtToken::build(&kp, &claims)
+        match Self::finalize_claims(client, &claims, provided)? {
+            Some(mapped) => JwtToken::build(&kp, &mapped),
+            None => JwtToken::build(&kp, &claims),
+        }
+    }
+
+    /// Merges the claims from the `ClaimsProvider` into the token claims and applies the client's
+    /// claim mapping afterward. Returns `None` if neither of them changed anything.
+    fn finalize_claims<C: Serialize>(
+        client: &Client,
+        claims: &C,
+        provided: Option<serde_json::Map<String, serde_json::Value>>,
+    ) -> Result<Option<serde_json::Value>, ErrorResponse> {
+        let Some(provided) = provided.filter(|p| !p.is_empty()) else {
+            return Self::map_claims(client, claims);
+        };
+
+        let mut value = serde_json::to_value(claims)?;
+        let Some(obj) = value.as_object_mut() else {
+            return Err(ErrorResponse::new(
+                ErrorResponseType::Internal,
+                "JWT claims must serialize into an object",
+            ));
+        };
+        // reserved claims have been filtered by the provider already
+        obj.extend(provided);
+
+        match Self::map_claims(client, &value)? {
+            Some(mapped) => Ok(Some(mapped)),
+            None => Ok(Some(value)),
+        }
+    }
+
+    /// Renames the claims from the client's `claim_mapping` and optionally nests them inside
+    /// the `claim_namespace` object. Returns `None` if the client has no mapping, so that the
+    /// claims can be serialized directly without the intermediate `serde_json::Value`.
+    fn map_claims<C: Serialize>(
+        client: &Client,
+        claims: &C,
+    ) -> Result<Option<serde_json::Value>, ErrorResponse> {
+        let Some(mapping) = client.get_claim_mapping() else {
+            return Ok(None);
+        };
+
+        let mut value = serde_json::to_value(claims)?;
+        let Some(obj) = value.as_object_mut() else {
+            return Err(ErrorResponse::new(
+                ErrorResponseType::Internal,
+                "JWT claims must serialize into an object",
+            ));
+        };
+
+        // remove all values first, so that mappings cannot overwrite each other
+        let mapped = mapping
+            .into_iter()
+            .filter_map(|(from, to)| obj.remove(from).map(|v| (to.to_string(), v)))
+            .collect::<serde_json::Map<_, _>>();
+
+        if let Some(ns) = &client.claim_namespace {
+            if !mapped.is_empty() {
+                obj.insert(ns.clone(), serde_json::Value::Object(mapped));
+            }
+        } else {
+            obj.extend(mapped);
+        }
+
+        Ok(Some(value))
     }
 
     /// Builds the refresh token for a user after all validation has been successful
@@ -376,6 +496,8 @@ impl TokenSet {
         device_code_flow: DeviceCodeFlow,
         sid: Option<SessionId>,
         jti: AccessTokenJti,
+        issuer: TokenIssuer,
+        family: Option<RefreshTokenFamily>,
     ) -> Result<String, ErrorResponse> {
         let did = if let DeviceCodeFlow::Yes(device_id) = device_code_flow {
             Some(device_id)
@@ -390,11 +512,16 @@ impl TokenSet {
             // allow 60 second early usage
             now + access_token_lifetime - 60
         };
-        let exp = if did.is_some() {
+        let mut exp = if did.is_some() {
             nbf + 3600 * RauthyConfig::get().vars.device_grant.refresh_token_lifetime as i64
         } else {
             nbf + 3600 * RauthyConfig::get().vars.lifetimes.refresh_token_lifetime as i64
         };
+        // Each rotation extends the lifetime again, but never beyond the absolute max counted
+        // from the original authentication, which is carried over in the `auth_time`.
+        if let Some(max) = client.refresh_token_max_lifetime {
+            exp = exp.min(auth_time.get() + max as i64);
+        }
 
         let token = {
             let jti = secure_random_alnum(8);
@@ -404,7 +531,7 @@ impl TokenSet {
                     iat: now,
                     nbf,
                     exp,
-                    iss: &RauthyConfig::get().issuer,
+                    iss: issuer.0,
                     // jti is not really used for any validation, it just exists
                     // to bring a bit more randomness into the claims
                     jti: Some(&jti),
@@ -422,6 +549,7 @@ impl TokenSet {
                 // Only Optional for backwards compatibility with older Rauthy versions and tokens.
                 // Could be changed with v1.0.0 maybe.
                 auth_time: Some(auth_time.get()),
+                sid: sid.as_ref().map(|sid| sid.0.as_str()),
             };
 
             let kp = JwkKeyPair::find_latest(JwkKeyPairAlg::default()).await?;
@@ -452,6 +580,8 @@ impl TokenSet {
                 is_mfa,
                 sid.map(|s| s.0),
                 Some(jti.0),
+                family.map(|f| f.0),
+                client.id.clone(),
             )
             .await?;
         }
@@ -462,6 +592,7 @@ impl TokenSet {
     pub async fn for_client_credentials(
         client: &Client,
         dpop_fingerprint: Option<DpopFingerprint>,
+        issuer: TokenIssuer,
     ) -> Result<Self, ErrorResponse> {
         let token_type = if dpop_fingerprint.is_some() {
             JwtTokenType::DPoP
@@ -477,6 +608,8 @@ impl TokenSet {
             None,
             None,
             DeviceCodeFlow::No,
+            issuer,
+            TokenBuildMode::Issue,
         )
         .await?;
 
@@ -501,6 +634,65 @@ impl TokenSet {
         sid: Option<SessionId>,
         auth_code_flow: AuthCodeFlow,
         device_code_flow: DeviceCodeFlow,
+        issuer: TokenIssuer,
+        family: Option<RefreshTokenFamily>,
+    ) -> Result<Self, ErrorResponse> {
+        Self::build_for_user(
+            user,
+            client,
+            auth_time,
+            dpop_fingerprint,
+            nonce,
+            scopes,
+            sid,
+            auth_code_flow,
+            device_code_flow,
+            issuer,
+            family,
+            TokenBuildMode::Issue,
+        )
+        .await
+    }
+
+    /// Builds the access and ID token for a user, just like they would be issued after an
+    /// `authorization_code` flow, but without persisting anything and without a refresh token.
+    pub async fn preview_for_user(
+        user: &User,
+        client: &Client,
+        scopes: Option<TokenScopes>,
+        issuer: TokenIssuer,
+    ) -> Result<Self, ErrorResponse> {
+        Self::build_for_user(
+            user,
+            client,
+            AuthTime::now(),
+            None,
+            None,
+            scopes,
+            None,
+            AuthCodeFlow::Yes,
+            DeviceCodeFlow::No,
+            issuer,
+            None,
+            TokenBuildMode::Preview,
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn build_for_user(
+        user: &User,
+        client: &Client,
+        auth_time: AuthTime,
+        dpop_fingerprint: Option<DpopFingerprint>,
+        nonce: Option<TokenNonce>,
+        scopes: Option<TokenScopes>,
+        sid: Option<SessionId>,
+        auth_code_flow: AuthCodeFlow,
+        device_code_flow: DeviceCodeFlow,
+        issuer: TokenIssuer,
+        family: Option<RefreshTokenFamily>,
+        mode: TokenBuildMode,
     ) -> Result<Self, ErrorResponse> {
         let scopes = scopes.map(|s| s.0);
         let scope = if let Some(s) = &scopes {
@@ -508,6 +700,19 @@ impl TokenSet {
         } else {
             client.default_scopes.clone().replace(',', " ")
         };
+        // The user's roles may have changed since the original authorization (e.g. on refresh),
+        // so we always check for entitlement before issuing new tokens.
+        let (scope, scopes) = {
+            let (allowed, denied) =
+                Scope::filter_user_entitled(scope.split(' ').collect(), user).await?;
+            if denied.is_empty() {
+                (scope, scopes)
+            } else {
+                let allowed = allowed.join(" ");
+                let scopes = scopes.map(|_| allowed.clone());
+                (allowed, scopes)
+            }
+        };
 
         // check for any non-custom scopes and prepare data
         let cust = Scope::extract_custom(&scope);
@@ -534,10 +739,15 @@ impl TokenSet {
             // if there was any custom mapping, we need the additional user attributes
             attrs = if !customs_access.is_empty() || !customs_id.is_empty() {
                 let attrs = UserAttrValueEntity::find_for_user_with_defaults(&user.id).await?;
+                // internal attributes must never end up in any token, even if mapped
+                let internal = UserAttrConfigEntity::find_all_internal_names().await?;
                 let mut res = HashMap::with_capacity(attrs.len());
-                attrs.iter().for_each(|a| {
-                    res.insert(a.key.clone(), a.value.clone());
-                });
+                attrs
+                    .iter()
+                    .filter(|a| !internal.contains(&a.key))
+                    .for_each(|a| {
+                        res.insert(a.key.clone(), a.value.clone());
+                    });
                 Some(res)
             } else {
                 None
@@ -591,6 +801,8 @@ impl TokenSet {
             customs_access,
             sid.clone(),
             device_code_flow.clone(),
+            issuer,
+            mode,
         )
         .await?;
 
@@ -610,9 +822,10 @@ impl TokenSet {
             customs_id,
             sid.clone(),
             auth_code_flow,
+            issuer,
         )
         .await?;
-        let refresh_token = if client.allow_refresh_token() {
+        let refresh_token = if client.allow_refresh_token() && mode == TokenBuildMode::Issue {
             Some(
                 Self::build_refresh_token(
                     user,
@@ -625,6 +838,8 @@ impl TokenSet {
                     device_code_flow,
                     sid,
                     jti,
+                    issuer,
+                    family,
                 )
                 .await?,
             )
@@ -660,4 +875,35 @@ mod tests {
         let sha512 = AtHash::build(ref_token, AtHashAlg::Sha512);
         assert_eq!(&sha512.0, "p2LHG4H-8pYDc0hyVOo3iIHvZJUqe9tbj3jESOuXbkY");
     }
+
+    #[test]
+    fn test_map_claims() {
+        let claims = serde_json::json!({
+            "iss": "http://localhost:8080/auth/v1",
+            "sub": "user_id",
+            "email": "admin@localhost",
+            "roles": ["admin"],
+        });
+
+        let mut client = Client::default();
+        assert!(TokenSet::map_claims(&client, &claims).unwrap().is_none());
+
+        client.claim_mapping = Some("roles=https://example.com/roles".to_string());
+        let mapped = TokenSet::map_claims(&client, &claims).unwrap().unwrap();
+        assert!(mapped.get("roles").is_none());
+        assert_eq!(
+            mapped.get("https://example.com/roles").unwrap(),
+            &serde_json::json!(["admin"])
+        );
+        assert_eq!(mapped.get("sub").unwrap(), "user_id");
+
+        client.claim_mapping = Some("email=mail,roles=groups".to_string());
+        client.claim_namespace = Some("https://example.com".to_string());
+        let mapped = TokenSet::map_claims(&client, &claims).unwrap().unwrap();
+        assert!(mapped.get("email").is_none());
+        assert!(mapped.get("roles").is_none());
+        let ns = mapped.get("https://example.com").unwrap();
+        assert_eq!(ns.get("mail").unwrap(), "admin@localhost");
+        assert_eq!(ns.get("groups").unwrap(), &serde_json::json!(["admin"]));
+    }
 }